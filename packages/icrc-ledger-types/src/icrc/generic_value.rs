@@ -133,6 +133,64 @@ impl std::fmt::Display for Value {
     }
 }
 
+/// A value as defined by the ICRC-3 standard.
+///
+/// Unlike [Value], it has no `Nat64` variant: all natural numbers are
+/// represented as `Nat`. Both types have the same hash for the same value.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ICRC3Value {
+    Blob(ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<ICRC3Value>),
+    Map(BTreeMap<String, ICRC3Value>),
+}
+
+impl ICRC3Value {
+    /// Computes the representation-independent hash of a value.
+    pub fn hash(self) -> Hash {
+        Value::from(self).hash()
+    }
+}
+
+impl From<Value> for ICRC3Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Blob(bytes) => Self::Blob(bytes),
+            Value::Text(text) => Self::Text(text),
+            Value::Nat(nat) => Self::Nat(nat),
+            Value::Nat64(nat64) => Self::Nat(Nat::from(nat64)),
+            Value::Int(int) => Self::Int(int),
+            Value::Array(array) => Self::Array(array.into_iter().map(Self::from).collect()),
+            Value::Map(map) => {
+                Self::Map(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl From<ICRC3Value> for Value {
+    fn from(value: ICRC3Value) -> Self {
+        match value {
+            ICRC3Value::Blob(bytes) => Self::Blob(bytes),
+            ICRC3Value::Text(text) => Self::Text(text),
+            ICRC3Value::Nat(nat) => Self::Nat(nat),
+            ICRC3Value::Int(int) => Self::Int(int),
+            ICRC3Value::Array(array) => Self::Array(array.into_iter().map(Self::from).collect()),
+            ICRC3Value::Map(map) => {
+                Self::Map(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ICRC3Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Value::from(self.clone()))
+    }
+}
+
 /// Encodes a 128-bit integer using unsigned LEB-128 encoding.
 /// Returns the index of the last valid byte in the buffer.
 fn leb128(buf: &mut [u8; INT128_BUF_SIZE], v: u128) -> usize {
@@ -283,3 +341,18 @@ fn test_test_vectors() {
         );
     }
 }
+
+#[test]
+fn test_icrc3_value_hash_agrees_with_value_hash() {
+    let value = Value::map(vec![
+        ("amt", Value::Nat64(42)),
+        ("ts", Value::Nat(1699218263_u32.into())),
+        (
+            "tx",
+            Value::Array(vec![Value::Nat64(u64::MAX), Value::text("memo")]),
+        ),
+    ]);
+    let icrc3_value = ICRC3Value::from(value.clone());
+    assert!(!format!("{:?}", icrc3_value).contains("Nat64"));
+    assert_eq!(icrc3_value.hash(), value.hash());
+}
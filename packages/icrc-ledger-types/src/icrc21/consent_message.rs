@@ -0,0 +1,605 @@
+//! Consent messages for the ICRC-1 and ICRC-2 endpoints of a ledger as
+//! specified by [ICRC-21](https://github.com/dfinity/wg-identity-authentication/blob/main/topics/ICRC-21/icrc_21_consent_msg.md).
+
+use super::errors::{ErrorInfo, Icrc21Error};
+use super::requests::{ConsentMessageMetadata, ConsentMessageRequest, DisplayMessageType};
+use super::responses::{ConsentInfo, ConsentMessage, LineDisplayPage};
+use crate::icrc1::account::{Account, Subaccount};
+use crate::icrc1::transfer::{Memo, TransferArg};
+use crate::icrc2::approve::ApproveArgs;
+use crate::icrc2::transfer_from::TransferFromArgs;
+use candid::{Decode, Nat, Principal};
+
+/// The maximum size of the argument of a call the consent message is
+/// requested for.
+pub const MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES: usize = 500;
+
+/// The languages consent messages are available in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    /// Returns the language matching the primary subtag of the BCP-47
+    /// `language` tag, falling back to English.
+    pub fn from_language_tag(language: &str) -> Self {
+        let primary_subtag = language.split(['-', '_']).next().unwrap_or_default();
+        match primary_subtag.to_ascii_lowercase().as_str() {
+            "de" => Language::German,
+            _ => Language::English,
+        }
+    }
+
+    pub fn language_tag(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+}
+
+/// The texts of the consent messages in a given language.
+struct Texts {
+    transfer_title: &'static str,
+    approve_title: &'static str,
+    transfer_from_title: &'static str,
+    amount: &'static str,
+    from: &'static str,
+    to: &'static str,
+    fee: &'static str,
+    memo: &'static str,
+    spender: &'static str,
+    your_account: &'static str,
+    requested_allowance: &'static str,
+    existing_allowance: &'static str,
+    expiration: &'static str,
+    no_expiration: &'static str,
+    approval_fee: &'static str,
+    withdrawal_account: &'static str,
+    requesting_account: &'static str,
+    amount_to_withdraw: &'static str,
+    withdrawal_fee: &'static str,
+    anonymous_owner: &'static str,
+}
+
+const ENGLISH: Texts = Texts {
+    transfer_title: "Approve the transfer of funds",
+    approve_title: "Authorize another address to withdraw from your account",
+    transfer_from_title: "Transfer from a withdrawal account",
+    amount: "Amount",
+    from: "From",
+    to: "To",
+    fee: "Fee",
+    memo: "Memo",
+    spender: "The following address is allowed to withdraw from your account",
+    your_account: "Your account",
+    requested_allowance: "Requested withdrawal allowance",
+    existing_allowance: "Current withdrawal allowance",
+    expiration: "Approval expiration",
+    no_expiration: "This approval does not have an expiration.",
+    approval_fee: "Approval fee",
+    withdrawal_account: "Withdrawal account",
+    requesting_account: "Account sending the transfer request",
+    amount_to_withdraw: "Amount to withdraw",
+    withdrawal_fee: "Fee paid by withdrawal account",
+    anonymous_owner: "the signing principal",
+};
+
+const GERMAN: Texts = Texts {
+    transfer_title: "Überweisung von Guthaben bestätigen",
+    approve_title: "Einer anderen Adresse Abhebungen von Ihrem Konto erlauben",
+    transfer_from_title: "Überweisung von einem Abhebungskonto",
+    amount: "Betrag",
+    from: "Von",
+    to: "An",
+    fee: "Gebühr",
+    memo: "Verwendungszweck",
+    spender: "Die folgende Adresse darf von Ihrem Konto abheben",
+    your_account: "Ihr Konto",
+    requested_allowance: "Angefragtes Abhebungslimit",
+    existing_allowance: "Aktuelles Abhebungslimit",
+    expiration: "Ablauf der Genehmigung",
+    no_expiration: "Diese Genehmigung läuft nicht ab.",
+    approval_fee: "Genehmigungsgebühr",
+    withdrawal_account: "Abhebungskonto",
+    requesting_account: "Konto, das die Überweisung anfragt",
+    amount_to_withdraw: "Abzuhebender Betrag",
+    withdrawal_fee: "Vom Abhebungskonto bezahlte Gebühr",
+    anonymous_owner: "der signierende Principal",
+};
+
+impl Language {
+    fn texts(&self) -> &'static Texts {
+        match self {
+            Language::English => &ENGLISH,
+            Language::German => &GERMAN,
+        }
+    }
+}
+
+/// The token parameters used to render amounts.
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+    /// The fee used if the call doesn't specify one.
+    pub fee: Nat,
+}
+
+/// Builds the consent message for a call to `icrc1_transfer`,
+/// `icrc2_approve` or `icrc2_transfer_from` issued by `caller`.
+pub fn build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+    consent_msg_request: ConsentMessageRequest,
+    caller: Principal,
+    token_info: TokenInfo,
+) -> Result<ConsentInfo, Icrc21Error> {
+    if consent_msg_request.arg.len() > MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES {
+        return Err(Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+            description: format!(
+                "The argument size is too large. The maximum allowed size is {} bytes.",
+                MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES
+            ),
+        }));
+    }
+
+    let preferences = consent_msg_request.user_preferences;
+    let language = Language::from_language_tag(&preferences.metadata.language);
+    let utc_offset_minutes = preferences.metadata.utc_offset_minutes;
+    let renderer = Renderer {
+        texts: language.texts(),
+        caller,
+        token_info,
+        utc_offset_minutes,
+    };
+
+    let arg = consent_msg_request.arg.as_slice();
+    let sections = match consent_msg_request.method.as_str() {
+        "icrc1_transfer" => renderer.transfer(decode_arg::<TransferArg>(arg, "TransferArg")?),
+        "icrc2_approve" => renderer.approve(decode_arg::<ApproveArgs>(arg, "ApproveArgs")?),
+        "icrc2_transfer_from" => {
+            renderer.transfer_from(decode_arg::<TransferFromArgs>(arg, "TransferFromArgs")?)
+        }
+        method => {
+            return Err(Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+                description: format!("The call to {} is not supported.", method),
+            }))
+        }
+    };
+
+    let consent_message = match preferences.device_spec {
+        None | Some(DisplayMessageType::GenericDisplay) => {
+            ConsentMessage::GenericDisplayMessage(sections.to_markdown())
+        }
+        Some(DisplayMessageType::LineDisplay {
+            characters_per_line,
+            lines_per_page,
+        }) => {
+            if characters_per_line == 0 || lines_per_page == 0 {
+                return Err(Icrc21Error::ConsentMessageUnavailable(ErrorInfo {
+                    description:
+                        "The line display must have at least one line with at least one character."
+                            .to_string(),
+                }));
+            }
+            ConsentMessage::LineDisplayMessage {
+                pages: sections.to_pages(characters_per_line as usize, lines_per_page as usize),
+            }
+        }
+    };
+
+    Ok(ConsentInfo {
+        consent_message,
+        metadata: ConsentMessageMetadata {
+            language: language.language_tag().to_string(),
+            utc_offset_minutes,
+        },
+    })
+}
+
+fn decode_arg<T: candid::CandidType + for<'a> candid::Deserialize<'a>>(
+    arg: &[u8],
+    type_name: &str,
+) -> Result<T, Icrc21Error> {
+    Decode!(arg, T).map_err(|err| {
+        Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+            description: format!("Failed to decode {}: {}", type_name, err),
+        })
+    })
+}
+
+/// A consent message made of a title and a list of labeled values.
+struct Sections {
+    title: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Sections {
+    fn to_markdown(&self) -> String {
+        let mut message = format!("# {}", self.title);
+        for (label, value) in &self.fields {
+            message.push_str(&format!("\n\n**{}:**\n{}", label, value));
+        }
+        message
+    }
+
+    /// Splits the message in pages of at most `lines_per_page` lines, each
+    /// line containing at most `characters_per_line` characters.
+    fn to_pages(&self, characters_per_line: usize, lines_per_page: usize) -> Vec<LineDisplayPage> {
+        let mut lines = wrap(self.title, characters_per_line);
+        for (label, value) in &self.fields {
+            lines.extend(wrap(&format!("{}:", label), characters_per_line));
+            lines.extend(wrap(value, characters_per_line));
+        }
+        lines
+            .chunks(lines_per_page)
+            .map(|lines| LineDisplayPage {
+                lines: lines.to_vec(),
+            })
+            .collect()
+    }
+}
+
+/// Breaks `text` into lines of at most `width` characters, preferring to
+/// break at whitespace.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        let line_len = line.chars().count();
+        if line_len > 0 && line_len + 1 + word.len() <= width {
+            line.push(' ');
+            line.extend(word.iter());
+            continue;
+        }
+        if line_len > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line = word.into_iter().collect();
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct Renderer {
+    texts: &'static Texts,
+    caller: Principal,
+    token_info: TokenInfo,
+    utc_offset_minutes: Option<i16>,
+}
+
+impl Renderer {
+    fn transfer(&self, arg: TransferArg) -> Sections {
+        let mut fields = vec![
+            (self.texts.amount, self.tokens(&arg.amount)),
+            (self.texts.from, self.caller_account(arg.from_subaccount)),
+            (self.texts.to, arg.to.to_string()),
+            (self.texts.fee, self.fee(arg.fee)),
+        ];
+        if let Some(memo) = arg.memo {
+            fields.push((self.texts.memo, format_memo(&memo)));
+        }
+        Sections {
+            title: self.texts.transfer_title,
+            fields,
+        }
+    }
+
+    fn approve(&self, arg: ApproveArgs) -> Sections {
+        let mut fields = vec![
+            (self.texts.spender, arg.spender.to_string()),
+            (
+                self.texts.your_account,
+                self.caller_account(arg.from_subaccount),
+            ),
+            (self.texts.requested_allowance, self.tokens(&arg.amount)),
+        ];
+        if let Some(expected_allowance) = arg.expected_allowance {
+            fields.push((
+                self.texts.existing_allowance,
+                self.tokens(&expected_allowance),
+            ));
+        }
+        let expiration = match arg.expires_at {
+            Some(expires_at) => format_timestamp(expires_at, self.utc_offset_minutes),
+            None => self.texts.no_expiration.to_string(),
+        };
+        fields.push((self.texts.expiration, expiration));
+        fields.push((self.texts.approval_fee, self.fee(arg.fee)));
+        if let Some(memo) = arg.memo {
+            fields.push((self.texts.memo, format_memo(&memo)));
+        }
+        Sections {
+            title: self.texts.approve_title,
+            fields,
+        }
+    }
+
+    fn transfer_from(&self, arg: TransferFromArgs) -> Sections {
+        let mut fields = vec![
+            (self.texts.withdrawal_account, arg.from.to_string()),
+            (
+                self.texts.requesting_account,
+                self.caller_account(arg.spender_subaccount),
+            ),
+            (self.texts.amount_to_withdraw, self.tokens(&arg.amount)),
+            (self.texts.to, arg.to.to_string()),
+            (self.texts.withdrawal_fee, self.fee(arg.fee)),
+        ];
+        if let Some(memo) = arg.memo {
+            fields.push((self.texts.memo, format_memo(&memo)));
+        }
+        Sections {
+            title: self.texts.transfer_from_title,
+            fields,
+        }
+    }
+
+    fn tokens(&self, amount: &Nat) -> String {
+        format!(
+            "{} {}",
+            format_tokens(amount, self.token_info.decimals),
+            self.token_info.symbol
+        )
+    }
+
+    fn fee(&self, fee: Option<Nat>) -> String {
+        self.tokens(fee.as_ref().unwrap_or(&self.token_info.fee))
+    }
+
+    /// The consent message might be requested anonymously, in which case the
+    /// owner of the account is not known.
+    fn caller_account(&self, subaccount: Option<Subaccount>) -> String {
+        if self.caller == Principal::anonymous() {
+            let subaccount = subaccount.unwrap_or_default();
+            if subaccount == [0; 32] {
+                return self.texts.anonymous_owner.to_string();
+            }
+            return format!(
+                "{} ({})",
+                self.texts.anonymous_owner,
+                hex::encode(subaccount)
+            );
+        }
+        Account {
+            owner: self.caller,
+            subaccount,
+        }
+        .to_string()
+    }
+}
+
+/// Renders an amount of the smallest token unit as a decimal number of tokens,
+/// e.g., 150_000_000 with 8 decimals is rendered as "1.5".
+pub fn format_tokens(amount: &Nat, decimals: u8) -> String {
+    let digits = amount.0.to_str_radix(10);
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+fn format_memo(memo: &Memo) -> String {
+    match std::str::from_utf8(memo.0.as_slice()) {
+        Ok(text) if !text.chars().any(char::is_control) => text.to_string(),
+        _ => format!("0x{}", hex::encode(memo.0.as_slice())),
+    }
+}
+
+/// Renders nanoseconds since the Unix epoch as a date in the time zone with
+/// the given offset from UTC, e.g., "2024-05-16 10:30:00 UTC+02:00".
+fn format_timestamp(timestamp_nanos: u64, utc_offset_minutes: Option<i16>) -> String {
+    let offset_minutes = utc_offset_minutes.unwrap_or(0) as i64;
+    let seconds = (timestamp_nanos / 1_000_000_000) as i64 + offset_minutes * 60;
+    let days = seconds.div_euclid(86_400);
+    let seconds_of_day = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    let zone = if offset_minutes == 0 {
+        "UTC".to_string()
+    } else {
+        format!(
+            "UTC{}{:02}:{:02}",
+            if offset_minutes < 0 { '-' } else { '+' },
+            offset_minutes.abs() / 60,
+            offset_minutes.abs() % 60
+        )
+    };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+        zone
+    )
+}
+
+/// Converts days since the Unix epoch into a (year, month, day) date of the
+/// proleptic Gregorian calendar.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icrc21::requests::ConsentMessageSpec;
+    use candid::Encode;
+    use serde_bytes::ByteBuf;
+
+    fn request(
+        method: &str,
+        arg: Vec<u8>,
+        device_spec: Option<DisplayMessageType>,
+    ) -> ConsentMessageRequest {
+        ConsentMessageRequest {
+            method: method.to_string(),
+            arg: ByteBuf::from(arg),
+            user_preferences: ConsentMessageSpec {
+                metadata: ConsentMessageMetadata {
+                    language: "en".to_string(),
+                    utc_offset_minutes: None,
+                },
+                device_spec,
+            },
+        }
+    }
+
+    fn token_info() -> TokenInfo {
+        TokenInfo {
+            symbol: "XTST".to_string(),
+            decimals: 8,
+            fee: Nat::from(10_000_u64),
+        }
+    }
+
+    #[test]
+    fn test_format_tokens() {
+        assert_eq!(format_tokens(&Nat::from(0_u8), 8), "0");
+        assert_eq!(format_tokens(&Nat::from(1_u8), 8), "0.00000001");
+        assert_eq!(format_tokens(&Nat::from(150_000_000_u64), 8), "1.5");
+        assert_eq!(format_tokens(&Nat::from(1_000_000_000_u64), 8), "10");
+        assert_eq!(format_tokens(&Nat::from(123_u8), 0), "123");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0, None), "1970-01-01 00:00:00 UTC");
+        assert_eq!(
+            format_timestamp(1_715_855_400_000_000_000, Some(120)),
+            "2024-05-16 12:30:00 UTC+02:00"
+        );
+        assert_eq!(
+            format_timestamp(0, Some(-90)),
+            "1969-12-31 22:30:00 UTC-01:30"
+        );
+    }
+
+    #[test]
+    fn test_transfer_consent_message() {
+        let caller = Principal::from_slice(&[1]);
+        let to = Account::from(Principal::from_slice(&[2]));
+        let arg = TransferArg {
+            from_subaccount: None,
+            to,
+            fee: None,
+            created_at_time: None,
+            memo: Some(Memo::from(b"coffee".to_vec())),
+            amount: Nat::from(150_000_000_u64),
+        };
+        let consent_info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_transfer", Encode!(&arg).unwrap(), None),
+            caller,
+            token_info(),
+        )
+        .unwrap();
+        assert_eq!(consent_info.metadata.language, "en");
+        assert_eq!(
+            consent_info.consent_message,
+            ConsentMessage::GenericDisplayMessage(format!(
+                "# Approve the transfer of funds\n\n**Amount:**\n1.5 XTST\n\n**From:**\n{}\n\n**To:**\n{}\n\n**Fee:**\n0.0001 XTST\n\n**Memo:**\ncoffee",
+                caller, to
+            ))
+        );
+    }
+
+    #[test]
+    fn test_line_display_consent_message() {
+        let arg = ApproveArgs {
+            from_subaccount: None,
+            spender: Account::from(Principal::from_slice(&[2])),
+            amount: Nat::from(100_000_000_u64),
+            expected_allowance: None,
+            expires_at: None,
+            fee: None,
+            memo: None,
+            created_at_time: None,
+        };
+        let consent_info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request(
+                "icrc2_approve",
+                Encode!(&arg).unwrap(),
+                Some(DisplayMessageType::LineDisplay {
+                    characters_per_line: 20,
+                    lines_per_page: 4,
+                }),
+            ),
+            Principal::anonymous(),
+            token_info(),
+        )
+        .unwrap();
+        let pages = match consent_info.consent_message {
+            ConsentMessage::LineDisplayMessage { pages } => pages,
+            other => panic!("unexpected consent message {:?}", other),
+        };
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(page.lines.len() <= 4);
+            assert!(page.lines.iter().all(|line| line.chars().count() <= 20));
+        }
+        assert_eq!(
+            pages[0].lines,
+            vec![
+                "Authorize another",
+                "address to withdraw",
+                "from your account",
+                "The following"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_consent_message_errors() {
+        let unsupported = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_balance_of", Encode!(&()).unwrap(), None),
+            Principal::anonymous(),
+            token_info(),
+        );
+        assert!(matches!(
+            unsupported,
+            Err(Icrc21Error::UnsupportedCanisterCall(_))
+        ));
+
+        let undecodable = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_transfer", Encode!(&"garbage").unwrap(), None),
+            Principal::anonymous(),
+            token_info(),
+        );
+        assert!(matches!(
+            undecodable,
+            Err(Icrc21Error::UnsupportedCanisterCall(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_language_falls_back_to_english() {
+        assert_eq!(Language::from_language_tag("de-CH"), Language::German);
+        assert_eq!(Language::from_language_tag("fr"), Language::English);
+    }
+}
@@ -0,0 +1,21 @@
+use candid::{CandidType, Deserialize, Nat};
+use serde::Serialize;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub description: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Icrc21Error {
+    /// The canister doesn't provide consent messages for the requested call.
+    UnsupportedCanisterCall(ErrorInfo),
+    /// The canister cannot produce a consent message for the requested call,
+    /// e.g., because of the requested display format.
+    ConsentMessageUnavailable(ErrorInfo),
+    InsufficientPayment(ErrorInfo),
+    GenericError {
+        error_code: Nat,
+        description: String,
+    },
+}
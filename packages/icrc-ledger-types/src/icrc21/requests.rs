@@ -0,0 +1,35 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+use serde_bytes::ByteBuf;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsentMessageMetadata {
+    /// The language of the consent message as a BCP-47 language tag.
+    pub language: String,
+    /// The offset of the user's time zone from UTC, used to render timestamps.
+    pub utc_offset_minutes: Option<i16>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DisplayMessageType {
+    GenericDisplay,
+    LineDisplay {
+        characters_per_line: u16,
+        lines_per_page: u16,
+    },
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsentMessageSpec {
+    pub metadata: ConsentMessageMetadata,
+    pub device_spec: Option<DisplayMessageType>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsentMessageRequest {
+    /// The name of the method the user is asked to consent to.
+    pub method: String,
+    /// The candid-encoded argument of the call.
+    pub arg: ByteBuf,
+    pub user_preferences: ConsentMessageSpec,
+}
@@ -0,0 +1,25 @@
+use super::requests::ConsentMessageMetadata;
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LineDisplayPage {
+    pub lines: Vec<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConsentMessage {
+    /// A markdown-formatted message for devices with a generic display.
+    GenericDisplayMessage(String),
+    /// A message split into pages of fixed-width lines for devices that can
+    /// only display a few lines of text at once.
+    LineDisplayMessage { pages: Vec<LineDisplayPage> },
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsentInfo {
+    pub consent_message: ConsentMessage,
+    /// The metadata the message was rendered with. The language might differ
+    /// from the requested one if the requested language is not supported.
+    pub metadata: ConsentMessageMetadata,
+}
@@ -1,7 +1,7 @@
 use crate::icrc1::transfer::BlockIndex;
 
 use super::{
-    blocks::{BlockRange, GetBlocksRequest},
+    blocks::{BlockRange, GetBlocksArgs, GetBlocksRequest, GetBlocksResult},
     transactions::{GetTransactionsRequest, TransactionRange},
 };
 use candid::{CandidType, Deserialize, Nat, Principal};
@@ -92,3 +92,22 @@ pub struct ArchiveInfo {
 }
 pub type QueryBlockArchiveFn = QueryArchiveFn<GetBlocksRequest, BlockRange>;
 pub type QueryTxArchiveFn = QueryArchiveFn<GetTransactionsRequest, TransactionRange>;
+pub type ICRC3ArchiveFn = QueryArchiveFn<GetBlocksArgs, GetBlocksResult>;
+
+/// The arguments of the `icrc3_get_archives` endpoint.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetArchivesArgs {
+    /// The last archive seen by the client, the result contains only
+    /// archives with a greater canister id.
+    pub from: Option<Principal>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ICRC3ArchiveInfo {
+    pub canister_id: Principal,
+    pub start: Nat,
+    pub end: Nat,
+}
+
+/// The result of the `icrc3_get_archives` endpoint.
+pub type GetArchivesResult = Vec<ICRC3ArchiveInfo>;
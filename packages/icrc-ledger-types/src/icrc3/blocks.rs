@@ -1,12 +1,17 @@
 use crate::icrc3::archive::ArchivedRange;
-use crate::icrc3::archive::QueryBlockArchiveFn;
-use crate::{icrc::generic_value::Value, icrc1::transfer::BlockIndex};
+use crate::icrc3::archive::{ICRC3ArchiveFn, QueryBlockArchiveFn};
+use crate::{
+    icrc::generic_value::{ICRC3Value, Value},
+    icrc1::transfer::BlockIndex,
+};
 use candid::{CandidType, Deserialize, Nat};
 use serde::Serialize;
 use serde_bytes::ByteBuf;
 
 pub type GenericBlock = Value;
 
+pub type ICRC3GenericBlock = ICRC3Value;
+
 #[derive(Debug, CandidType, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct GetBlocksResponse {
     pub first_index: BlockIndex,
@@ -53,3 +58,42 @@ pub struct DataCertificate {
     pub certificate: Option<serde_bytes::ByteBuf>,
     pub hash_tree: serde_bytes::ByteBuf,
 }
+
+/// The arguments of the `icrc3_get_blocks` endpoint.
+pub type GetBlocksArgs = Vec<GetBlocksRequest>;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockWithId {
+    pub id: Nat,
+    pub block: ICRC3GenericBlock,
+}
+
+/// Block ranges that have to be fetched from an archive using the `callback`.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArchivedBlocks {
+    pub args: GetBlocksArgs,
+    pub callback: ICRC3ArchiveFn,
+}
+
+/// The result of the `icrc3_get_blocks` endpoint.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetBlocksResult {
+    pub log_length: Nat,
+    pub blocks: Vec<BlockWithId>,
+    pub archived_blocks: Vec<ArchivedBlocks>,
+}
+
+/// The certificate of the tip of the chain returned by
+/// `icrc3_get_tip_certificate`. The `hash_tree` contains the labels
+/// `last_block_index` (LEB128-encoded) and `last_block_hash`.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ICRC3DataCertificate {
+    pub certificate: serde_bytes::ByteBuf,
+    pub hash_tree: serde_bytes::ByteBuf,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SupportedBlockType {
+    pub block_type: String,
+    pub url: String,
+}
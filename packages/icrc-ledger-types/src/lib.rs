@@ -2,3 +2,4 @@ pub mod icrc;
 pub mod icrc1;
 pub mod icrc2;
 pub mod icrc3;
+pub mod icrc21;
@@ -30,6 +30,13 @@ pub struct RawTime {
     pub nanos_since_epoch: u64,
 }
 
+/// A directory on the server's file system into which a checkpoint of a whole
+/// instance is written, or from which an instance is restored.
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub struct RawCheckpoint {
+    pub checkpoint_dir: PathBuf,
+}
+
 /// Relevant for calls to the management canister. If a subnet ID is
 /// provided, the call will be sent to the management canister of that subnet.
 /// If a canister ID is provided, the call will be sent to the management
@@ -71,6 +78,48 @@ pub enum RawCanisterResult {
     Err(UserError),
 }
 
+/// Identifies an update call submitted via `submit_ingress_message`. The effective principal
+/// is the subnet to which the call has been submitted.
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq, Hash)]
+pub struct RawMessageId {
+    pub effective_principal: RawEffectivePrincipal,
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub message_id: Vec<u8>,
+}
+
+/// Identifies an update call submitted via [`crate::PocketIc::submit_call`] or
+/// [`crate::PocketIc::submit_call_with_effective_principal`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MessageId {
+    pub effective_principal: RawEffectivePrincipal,
+    pub message_id: Vec<u8>,
+}
+
+impl From<RawMessageId> for MessageId {
+    fn from(raw: RawMessageId) -> Self {
+        Self {
+            effective_principal: raw.effective_principal,
+            message_id: raw.message_id,
+        }
+    }
+}
+
+impl From<MessageId> for RawMessageId {
+    fn from(message_id: MessageId) -> Self {
+        Self {
+            effective_principal: message_id.effective_principal,
+            message_id: message_id.message_id,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub enum RawSubmitIngressResult {
+    Ok(RawMessageId),
+    Err(UserError),
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub enum RawWasmResult {
     /// Raw response, returned in a "happy" case
@@ -99,6 +148,19 @@ pub struct RawStableMemory {
     pub blob: Vec<u8>,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub struct RawMineBitcoinBlocks {
+    /// One block is mined per address, paying the block subsidy to that address.
+    pub addresses: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq)]
+pub struct RawBitcoinTransaction {
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub transaction: Vec<u8>,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct ApiError {
     message: String,
@@ -234,6 +296,125 @@ pub struct RawVerifyCanisterSigArg {
     pub root_pubkey: Vec<u8>,
 }
 
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+pub enum CanisterHttpMethod {
+    GET,
+    POST,
+    HEAD,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+pub struct CanisterHttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// A pending HTTPS outcall made by a canister via the management canister's `http_request`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+pub struct RawCanisterHttpRequest {
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub subnet_id: Vec<u8>,
+    pub request_id: u64,
+    pub http_method: CanisterHttpMethod,
+    pub url: String,
+    pub headers: Vec<CanisterHttpHeader>,
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub body: Vec<u8>,
+    pub max_response_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanisterHttpRequest {
+    pub subnet_id: Principal,
+    pub request_id: u64,
+    pub http_method: CanisterHttpMethod,
+    pub url: String,
+    pub headers: Vec<CanisterHttpHeader>,
+    pub body: Vec<u8>,
+    pub max_response_bytes: Option<u64>,
+}
+
+impl From<RawCanisterHttpRequest> for CanisterHttpRequest {
+    fn from(raw: RawCanisterHttpRequest) -> Self {
+        Self {
+            subnet_id: Principal::from_slice(&raw.subnet_id),
+            request_id: raw.request_id,
+            http_method: raw.http_method,
+            url: raw.url,
+            headers: raw.headers,
+            body: raw.body,
+            max_response_bytes: raw.max_response_bytes,
+        }
+    }
+}
+
+impl From<CanisterHttpRequest> for RawCanisterHttpRequest {
+    fn from(request: CanisterHttpRequest) -> Self {
+        Self {
+            subnet_id: request.subnet_id.as_slice().to_vec(),
+            request_id: request.request_id,
+            http_method: request.http_method,
+            url: request.url,
+            headers: request.headers,
+            body: request.body,
+            max_response_bytes: request.max_response_bytes,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CanisterHttpReply {
+    pub status: u16,
+    pub headers: Vec<CanisterHttpHeader>,
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub body: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CanisterHttpReject {
+    pub reject_code: u64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum CanisterHttpResponse {
+    CanisterHttpReply(CanisterHttpReply),
+    CanisterHttpReject(CanisterHttpReject),
+}
+
+/// A canned response to a pending HTTPS outcall, identified by the subnet
+/// on which the outcall is pending and its request ID.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RawMockCanisterHttpResponse {
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub subnet_id: Vec<u8>,
+    pub request_id: u64,
+    pub response: CanisterHttpResponse,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockCanisterHttpResponse {
+    pub subnet_id: Principal,
+    pub request_id: u64,
+    pub response: CanisterHttpResponse,
+}
+
+impl From<MockCanisterHttpResponse> for RawMockCanisterHttpResponse {
+    fn from(mock: MockCanisterHttpResponse) -> Self {
+        Self {
+            subnet_id: mock.subnet_id.as_slice().to_vec(),
+            request_id: mock.request_id,
+            response: mock.response,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct BlobId(
     #[serde(deserialize_with = "base64::deserialize")]
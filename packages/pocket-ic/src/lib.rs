@@ -33,10 +33,13 @@
 //! For more information, see the [README](https://crates.io/crates/pocket-ic).
 //!
 use crate::common::rest::{
-    ApiResponse, BlobCompression, BlobId, CreateInstanceResponse, ExtendedSubnetConfigSet,
-    InstanceId, RawAddCycles, RawCanisterCall, RawCanisterId, RawCanisterResult, RawCycles,
-    RawEffectivePrincipal, RawSetStableMemory, RawStableMemory, RawSubnetId, RawTime,
-    RawVerifyCanisterSigArg, RawWasmResult, SubnetId, SubnetSpec, Topology,
+    ApiResponse, BlobCompression, BlobId, CanisterHttpRequest, CreateInstanceResponse,
+    ExtendedSubnetConfigSet, InstanceId, MessageId, MockCanisterHttpResponse, RawAddCycles,
+    RawBitcoinTransaction, RawCanisterCall, RawCanisterHttpRequest, RawCanisterId,
+    RawCanisterResult, RawCheckpoint, RawCycles, RawEffectivePrincipal, RawMessageId,
+    RawMineBitcoinBlocks, RawMockCanisterHttpResponse, RawSetStableMemory, RawStableMemory,
+    RawSubmitIngressResult, RawSubnetId, RawTime, RawVerifyCanisterSigArg, RawWasmResult, SubnetId,
+    SubnetSpec, Topology,
 };
 use candid::{
     decode_args, encode_args,
@@ -64,6 +67,7 @@ pub mod common;
 
 const PROCESSING_TIME_HEADER: &str = "processing-timeout-ms";
 const PROCESSING_TIME_VALUE_MS: u64 = 300_000;
+const BUSY_RETRY_INTERVAL_MS: u64 = 10;
 const LOCALHOST: &str = "127.0.0.1";
 
 const LOG_DIR_PATH_ENV_NAME: &str = "POCKET_IC_LOG_DIR";
@@ -213,15 +217,28 @@ impl PocketIc {
     pub fn from_config(config: impl Into<ExtendedSubnetConfigSet>) -> Self {
         let config = config.into();
         config.validate().unwrap();
+        Self::create_instance("instances", &config)
+    }
+
+    /// Creates a new PocketIC instance from a checkpoint directory on the server's file system
+    /// that has previously been written by [`PocketIc::checkpoint`]. The new instance has the
+    /// same topology, state, and time as the checkpointed instance.
+    pub fn from_checkpoint(checkpoint_dir: PathBuf) -> Self {
+        Self::create_instance(
+            "instances/from_checkpoint",
+            &RawCheckpoint { checkpoint_dir },
+        )
+    }
 
+    fn create_instance(endpoint: &str, body: &impl Serialize) -> Self {
         let parent_pid = std::os::unix::process::parent_id();
         let log_guard = setup_tracing(parent_pid);
 
         let server_url = crate::start_or_reuse_server();
         let reqwest_client = reqwest::blocking::Client::new();
         let (instance_id, topology) = match reqwest_client
-            .post(server_url.join("instances").unwrap())
-            .json(&config)
+            .post(server_url.join(endpoint).unwrap())
+            .json(body)
             .send()
             .expect("Failed to get result")
             .json::<CreateInstanceResponse>()
@@ -346,6 +363,32 @@ impl PocketIc {
         self.post::<(), _>(endpoint, "");
     }
 
+    /// Write a checkpoint of this IC instance, i.e., of all its subnets, its topology,
+    /// and its current time, into the given directory on the server's file system.
+    /// The checkpoint can be used to create a new instance via [`PocketIc::from_checkpoint`].
+    #[instrument(skip(self), fields(instance_id=self.instance_id, checkpoint_dir = %checkpoint_dir.display()))]
+    pub fn checkpoint(&self, checkpoint_dir: PathBuf) {
+        let endpoint = "update/checkpoint";
+        self.post::<(), _>(endpoint, RawCheckpoint { checkpoint_dir });
+    }
+
+    /// Put this IC instance into live mode: The server executes rounds periodically and the time
+    /// of the instance follows the wall-clock time. Returns the URL under which the instance serves
+    /// the IC HTTP interface, e.g., to be used as the URL of an agent.
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn auto_progress(&self) -> Url {
+        let endpoint = "auto_progress";
+        self.post::<(), _>(endpoint, "");
+        self.instance_url()
+    }
+
+    /// Stop live mode of this IC instance.
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn stop_progress(&self) {
+        let endpoint = "stop_progress";
+        self.post::<(), _>(endpoint, "");
+    }
+
     /// Get the root key of this IC instance. Returns `None` if the IC has no NNS subnet.
     #[instrument(skip(self), fields(instance_id=self.instance_id))]
     pub fn root_key(&self) -> Option<Vec<u8>> {
@@ -433,6 +476,80 @@ impl PocketIc {
         )
     }
 
+    /// Submit an update call (without executing it immediately).
+    /// The returned message ID can be used to await the call via [`PocketIc::await_call`]
+    /// or to fetch its status via [`PocketIc::ingress_status`]. This way, several calls can be
+    /// in flight at the same time.
+    #[instrument(skip(self, payload), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), sender = %sender.to_string(), method = %method, payload_len = %payload.len()))]
+    pub fn submit_call(
+        &self,
+        canister_id: CanisterId,
+        sender: Principal,
+        method: &str,
+        payload: Vec<u8>,
+    ) -> Result<MessageId, UserError> {
+        self.submit_call_with_effective_principal(
+            canister_id,
+            RawEffectivePrincipal::None,
+            sender,
+            method,
+            payload,
+        )
+    }
+
+    /// Submit an update call with a provided effective principal (without executing it immediately).
+    #[instrument(skip(self, payload), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), effective_principal = ?effective_principal, sender = %sender.to_string(), method = %method, payload_len = %payload.len()))]
+    pub fn submit_call_with_effective_principal(
+        &self,
+        canister_id: CanisterId,
+        effective_principal: RawEffectivePrincipal,
+        sender: Principal,
+        method: &str,
+        payload: Vec<u8>,
+    ) -> Result<MessageId, UserError> {
+        let endpoint = "update/submit_ingress_message";
+        let raw_canister_call = RawCanisterCall {
+            sender: sender.as_slice().to_vec(),
+            canister_id: canister_id.as_slice().to_vec(),
+            method: method.to_string(),
+            payload,
+            effective_principal,
+        };
+        let result: RawSubmitIngressResult = self.post(endpoint, raw_canister_call);
+        match result {
+            RawSubmitIngressResult::Ok(raw_message_id) => Ok(raw_message_id.into()),
+            RawSubmitIngressResult::Err(user_error) => Err(user_error),
+        }
+    }
+
+    /// Await an update call submitted previously by [`PocketIc::submit_call`] or
+    /// [`PocketIc::submit_call_with_effective_principal`]. Rounds are executed until the call
+    /// completes.
+    #[instrument(skip(self), fields(instance_id=self.instance_id, message_id = %hex::encode(&message_id.message_id)))]
+    pub fn await_call(&self, message_id: MessageId) -> Result<WasmResult, UserError> {
+        let endpoint = "update/await_ingress_message";
+        let result: RawCanisterResult = self.post(endpoint, RawMessageId::from(message_id));
+        match result {
+            RawCanisterResult::Ok(RawWasmResult::Reply(data)) => Ok(WasmResult::Reply(data)),
+            RawCanisterResult::Ok(RawWasmResult::Reject(text)) => Ok(WasmResult::Reject(text)),
+            RawCanisterResult::Err(user_error) => Err(user_error),
+        }
+    }
+
+    /// Fetch the status of an update call submitted previously by [`PocketIc::submit_call`] or
+    /// [`PocketIc::submit_call_with_effective_principal`] without executing any rounds.
+    /// Returns `None` if the call has not completed yet.
+    #[instrument(skip(self), fields(instance_id=self.instance_id, message_id = %hex::encode(&message_id.message_id)))]
+    pub fn ingress_status(&self, message_id: MessageId) -> Option<Result<WasmResult, UserError>> {
+        let endpoint = "read/ingress_status";
+        let result: Option<RawCanisterResult> = self.post(endpoint, RawMessageId::from(message_id));
+        result.map(|result| match result {
+            RawCanisterResult::Ok(RawWasmResult::Reply(data)) => Ok(WasmResult::Reply(data)),
+            RawCanisterResult::Ok(RawWasmResult::Reject(text)) => Ok(WasmResult::Reject(text)),
+            RawCanisterResult::Err(user_error) => Err(user_error),
+        })
+    }
+
     /// Execute a query call on a canister.
     #[instrument(skip(self, payload), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), sender = %sender.to_string(), method = %method, payload_len = %payload.len()))]
     pub fn query_call(
@@ -471,6 +588,25 @@ impl PocketIc {
         .map(|responses| responses.0)
     }
 
+    /// Request the breakdown of a canister's memory usage as reported by `canister_status`.
+    /// Returns `None` if the server does not report the breakdown.
+    #[instrument(skip(self), fields(instance_id=self.instance_id, sender = %sender.unwrap_or(Principal::anonymous()).to_string()))]
+    pub fn canister_memory_metrics(
+        &self,
+        canister_id: CanisterId,
+        sender: Option<Principal>,
+    ) -> Result<Option<MemoryMetrics>, CallError> {
+        call_candid_as::<(CanisterIdRecord,), (CanisterStatusMemoryMetrics,)>(
+            self,
+            Principal::management_canister(),
+            RawEffectivePrincipal::CanisterId(canister_id.as_slice().to_vec()),
+            sender.unwrap_or(Principal::anonymous()),
+            "canister_status",
+            (CanisterIdRecord { canister_id },),
+        )
+        .map(|responses| responses.0.memory_metrics)
+    }
+
     /// Create a canister with default settings as the anonymous principal.
     #[instrument(ret(Display), skip(self), fields(instance_id=self.instance_id))]
     pub fn create_canister(&self) -> CanisterId {
@@ -732,6 +868,52 @@ impl PocketIc {
         self.get_subnet(canister_id).is_some()
     }
 
+    /// Get the pending canister HTTP outcalls.
+    /// Note that an outcall is only pending after the canister has made the call
+    /// to the management canister and a round has been executed (e.g., via `tick`).
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn get_canister_http(&self) -> Vec<CanisterHttpRequest> {
+        let endpoint = "read/get_canister_http";
+        let res: Vec<RawCanisterHttpRequest> = self.post(endpoint, "");
+        res.into_iter().map(|r| r.into()).collect()
+    }
+
+    /// Mock a response to a pending canister HTTP outcall. The response is delivered to the
+    /// calling canister in a new round. If the outcall specified a transform function,
+    /// the transform is applied to the mocked reply before it is delivered.
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn mock_canister_http_response(
+        &self,
+        mock_canister_http_response: MockCanisterHttpResponse,
+    ) {
+        let endpoint = "update/mock_canister_http";
+        let raw: RawMockCanisterHttpResponse = mock_canister_http_response.into();
+        self.post::<(), _>(endpoint, raw);
+    }
+
+    /// Mine one block per given address on the local bitcoin (regtest) blockchain
+    /// served to the bitcoin canister on the bitcoin subnet. The coinbase transaction
+    /// of each block pays the block subsidy to the corresponding address and the first
+    /// block contains all transactions sent by the bitcoin canister that have not been
+    /// mined yet. The bitcoin canister fetches the new blocks in subsequent rounds
+    /// (e.g., executed via `tick`).
+    /// Panics if the instance has no bitcoin subnet or an address is invalid.
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn mine_bitcoin_blocks(&self, addresses: Vec<String>) {
+        let endpoint = "update/mine_bitcoin_blocks";
+        self.post::<(), _>(endpoint, RawMineBitcoinBlocks { addresses });
+    }
+
+    /// Returns all transactions (in their raw encoding) that the bitcoin canister on the
+    /// bitcoin subnet has sent to the (local) bitcoin network so far.
+    /// Panics if the instance has no bitcoin subnet.
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn get_bitcoin_transactions(&self) -> Vec<Vec<u8>> {
+        let endpoint = "read/get_bitcoin_transactions";
+        let res: Vec<RawBitcoinTransaction> = self.get(endpoint);
+        res.into_iter().map(|r| r.transaction).collect()
+    }
+
     /// Returns the subnet ID of the canister if the canister exists.
     #[instrument(ret, skip(self), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string()))]
     pub fn get_subnet(&self, canister_id: CanisterId) -> Option<SubnetId> {
@@ -754,26 +936,38 @@ impl PocketIc {
     }
 
     fn get<T: DeserializeOwned>(&self, endpoint: &str) -> T {
-        let result = self
-            .reqwest_client
-            .get(self.instance_url().join(endpoint).unwrap())
-            .header(PROCESSING_TIME_HEADER, PROCESSING_TIME_VALUE_MS)
-            .send()
-            .expect("HTTP failure");
+        let result = Self::send_retrying_while_busy(|| {
+            self.reqwest_client
+                .get(self.instance_url().join(endpoint).unwrap())
+                .header(PROCESSING_TIME_HEADER, PROCESSING_TIME_VALUE_MS)
+        });
         Self::check_response(result)
     }
 
     fn post<T: DeserializeOwned, B: Serialize>(&self, endpoint: &str, body: B) -> T {
-        let result = self
-            .reqwest_client
-            .post(self.instance_url().join(endpoint).unwrap())
-            .header(PROCESSING_TIME_HEADER, PROCESSING_TIME_VALUE_MS)
-            .json(&body)
-            .send()
-            .expect("HTTP failure");
+        let result = Self::send_retrying_while_busy(|| {
+            self.reqwest_client
+                .post(self.instance_url().join(endpoint).unwrap())
+                .header(PROCESSING_TIME_HEADER, PROCESSING_TIME_VALUE_MS)
+                .json(&body)
+        });
         Self::check_response(result)
     }
 
+    // In live mode, the instance is busy with executing rounds from time to time,
+    // so we retry requests which are rejected because the instance is busy.
+    fn send_retrying_while_busy(
+        request: impl Fn() -> reqwest::blocking::RequestBuilder,
+    ) -> reqwest::blocking::Response {
+        loop {
+            let result = request().send().expect("HTTP failure");
+            if result.status() != reqwest::StatusCode::CONFLICT {
+                return result;
+            }
+            std::thread::sleep(Duration::from_millis(BUSY_RETRY_INTERVAL_MS));
+        }
+    }
+
     fn check_response<T: DeserializeOwned>(result: reqwest::blocking::Response) -> T {
         match result.into() {
             ApiResponse::Success(t) => t,
@@ -1023,6 +1217,26 @@ struct ProvisionalCreateCanisterArgument {
     pub amount: Option<Nat>,
 }
 
+/// Breakdown of a canister's memory usage in bytes, as reported by `canister_status`.
+#[derive(CandidType, Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct MemoryMetrics {
+    pub wasm_memory_size: Nat,
+    pub stable_memory_size: Nat,
+    pub global_memory_size: Nat,
+    pub wasm_binary_size: Nat,
+    pub custom_sections_size: Nat,
+    pub canister_history_size: Nat,
+    pub wasm_chunk_store_size: Nat,
+    pub snapshots_size: Nat,
+    pub queued_messages_size: Nat,
+}
+
+/// The part of the `canister_status` response that holds the memory breakdown.
+#[derive(CandidType, Deserialize)]
+struct CanisterStatusMemoryMetrics {
+    memory_metrics: Option<MemoryMetrics>,
+}
+
 /// Error type for [`TryFrom<u64>`].
 #[derive(Clone, Copy, Debug)]
 pub enum TryFromError {
@@ -1,16 +1,33 @@
-use candid::{decode_one, encode_one, Principal};
+use candid::{decode_one, encode_one, CandidType, Nat, Principal};
 use ic_base_types::PrincipalId;
+use ic_cdk::api::management_canister::http_request::{
+    CanisterHttpRequestArgument, HttpMethod, HttpResponse,
+};
 use ic_cdk::api::management_canister::provisional::CanisterId;
+use ic_crypto_tree_hash::{Label, LookupStatus, MixedHashTree, Path};
+use ic_types::messages::{
+    Blob, Certificate, HttpCallContent, HttpCanisterUpdate, HttpQueryContent, HttpQueryResponse,
+    HttpReadState, HttpReadStateContent, HttpReadStateResponse, HttpRequestEnvelope,
+    HttpSignedQueryResponse, HttpUserQuery, MessageId,
+};
+use ic_types::Cycles;
 use ic_universal_canister::{wasm, CallArgs, UNIVERSAL_CANISTER_WASM};
 use icp_ledger::{
     AccountIdentifier, BinaryAccountBalanceArgs, BlockIndex, LedgerCanisterInitPayload, Memo, Name,
     Symbol, Tokens, TransferArgs, TransferError,
 };
 use pocket_ic::{
-    common::rest::{BlobCompression, SubnetConfigSet, SubnetKind},
+    common::rest::{
+        BlobCompression, CanisterHttpMethod, CanisterHttpReply, CanisterHttpResponse,
+        MockCanisterHttpResponse, SubnetConfigSet, SubnetKind,
+    },
     PocketIc, PocketIcBuilder, WasmResult,
 };
-use std::{collections::HashMap, io::Read, time::SystemTime};
+use std::{
+    collections::HashMap,
+    io::Read,
+    time::{Duration, SystemTime},
+};
 
 // 2T cycles
 const INIT_CYCLES: u128 = 2_000_000_000_000;
@@ -38,6 +55,69 @@ fn test_counter_canister() {
     assert_eq!(reply, WasmResult::Reply(vec![2, 0, 0, 0]));
 }
 
+#[test]
+fn test_canister_memory_metrics() {
+    let pic = PocketIc::new();
+
+    let can_id = pic.create_canister();
+    pic.add_cycles(can_id, INIT_CYCLES);
+    pic.install_canister(can_id, counter_wasm(), vec![], None);
+
+    let memory_metrics = pic.canister_memory_metrics(can_id, None).unwrap().unwrap();
+    assert!(memory_metrics.wasm_binary_size > Nat::from(0_u64));
+    assert!(memory_metrics.canister_history_size > Nat::from(0_u64));
+    assert_eq!(memory_metrics.snapshots_size, Nat::from(0_u64));
+
+    let status = pic.canister_status(can_id, None).unwrap();
+    let total = memory_metrics.wasm_memory_size
+        + memory_metrics.stable_memory_size
+        + memory_metrics.global_memory_size
+        + memory_metrics.wasm_binary_size
+        + memory_metrics.custom_sections_size
+        + memory_metrics.canister_history_size
+        + memory_metrics.wasm_chunk_store_size;
+    assert_eq!(total, status.memory_size);
+}
+
+#[test]
+fn test_submit_and_await_call() {
+    let pic = PocketIc::new();
+
+    let can_id = pic.create_canister();
+    pic.add_cycles(can_id, INIT_CYCLES);
+    pic.install_canister(can_id, counter_wasm(), vec![], None);
+
+    // Submit two calls which are both in flight before any of them executes.
+    let first = pic
+        .submit_call(
+            can_id,
+            Principal::anonymous(),
+            "write",
+            encode_one(()).unwrap(),
+        )
+        .unwrap();
+    let second = pic
+        .submit_call(
+            can_id,
+            Principal::anonymous(),
+            "write",
+            encode_one(()).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(pic.ingress_status(first.clone()), None);
+    assert_eq!(pic.ingress_status(second.clone()), None);
+
+    // Awaiting the second call also completes the first one.
+    let reply = pic.await_call(second.clone()).unwrap();
+    assert_eq!(reply, WasmResult::Reply(vec![2, 0, 0, 0]));
+    assert_eq!(
+        pic.ingress_status(first.clone()),
+        Some(Ok(WasmResult::Reply(vec![1, 0, 0, 0])))
+    );
+    let reply = pic.await_call(first).unwrap();
+    assert_eq!(reply, WasmResult::Reply(vec![1, 0, 0, 0]));
+}
+
 fn counter_wasm() -> Vec<u8> {
     let wasm_path = std::env::var_os("COUNTER_WASM").expect("Missing counter wasm file");
     std::fs::read(wasm_path).unwrap()
@@ -635,6 +715,430 @@ fn test_get_subnet_of_canister() {
     assert!(subnet_id.is_none());
 }
 
+#[test]
+fn test_checkpoint_and_restore() {
+    let pic = PocketIcBuilder::new()
+        .with_nns_subnet()
+        .with_application_subnet()
+        .build();
+    let app_subnet = pic.topology().get_app_subnets()[0];
+    let canister_id = pic.create_canister_on_subnet(None, None, app_subnet);
+    pic.add_cycles(canister_id, INIT_CYCLES);
+    pic.install_canister(canister_id, counter_wasm(), vec![], None);
+    call_counter_can(&pic, canister_id, "write");
+    let time = pic.get_time();
+
+    let checkpoint_dir = tempfile::TempDir::new().unwrap();
+    pic.checkpoint(checkpoint_dir.path().to_path_buf());
+    drop(pic);
+
+    let pic = PocketIc::from_checkpoint(checkpoint_dir.path().to_path_buf());
+    assert_eq!(pic.topology().get_app_subnets(), vec![app_subnet]);
+    assert_eq!(pic.get_subnet(canister_id), Some(app_subnet));
+    assert!(pic.get_time() >= time);
+    let reply = call_counter_can(&pic, canister_id, "read");
+    assert_eq!(reply, WasmResult::Reply(vec![1, 0, 0, 0]));
+}
+
+#[test]
+fn test_auto_progress() {
+    let pic = PocketIc::new();
+    let url = pic.auto_progress();
+
+    // The time of the instance catches up with the wall-clock time.
+    let now = SystemTime::now();
+    let mut caught_up = false;
+    for _ in 0..100 {
+        if pic.get_time() >= now {
+            caught_up = true;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert!(caught_up);
+
+    // The IC HTTP interface is served.
+    let response = reqwest::blocking::get(url.join("api/v2/status").unwrap()).unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_TYPE],
+        "application/cbor"
+    );
+
+    pic.stop_progress();
+}
+
+fn ingress_expiry(pic: &PocketIc) -> u64 {
+    (pic.get_time() + Duration::from_secs(120))
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
+}
+
+fn post_cbor<C: serde::Serialize>(
+    url: &reqwest::Url,
+    endpoint: &str,
+    content: C,
+) -> reqwest::blocking::Response {
+    let envelope = HttpRequestEnvelope {
+        content,
+        sender_pubkey: None,
+        sender_sig: None,
+        sender_delegation: None,
+    };
+    reqwest::blocking::Client::new()
+        .post(url.join(endpoint).unwrap())
+        .header(reqwest::header::CONTENT_TYPE, "application/cbor")
+        .body(serde_cbor::to_vec(&envelope).unwrap())
+        .send()
+        .unwrap()
+}
+
+fn read_state(
+    pic: &PocketIc,
+    url: &reqwest::Url,
+    canister_id: CanisterId,
+    sender: PrincipalId,
+    paths: Vec<Path>,
+) -> reqwest::blocking::Response {
+    let content = HttpReadStateContent::ReadState {
+        read_state: HttpReadState {
+            sender: Blob(sender.to_vec()),
+            paths,
+            nonce: None,
+            ingress_expiry: ingress_expiry(pic),
+        },
+    };
+    post_cbor(
+        url,
+        &format!("api/v2/canister/{}/read_state", canister_id),
+        content,
+    )
+}
+
+fn lookup_leaf(tree: &MixedHashTree, path: &[&[u8]]) -> Option<Vec<u8>> {
+    match tree.lookup(path) {
+        LookupStatus::Found(MixedHashTree::Leaf(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn request_status_path(message_id: &MessageId, label: &str) -> Path {
+    Path::new(vec![
+        Label::from("request_status"),
+        Label::from(message_id.as_bytes()),
+        Label::from(label),
+    ])
+}
+
+#[test]
+fn test_http_interface_call_query_and_read_state() {
+    let pic = PocketIc::new();
+    let canister_id = pic.create_canister();
+    pic.add_cycles(canister_id, INIT_CYCLES);
+    pic.install_canister(canister_id, UNIVERSAL_CANISTER_WASM.to_vec(), vec![], None);
+    let url = pic.auto_progress();
+    let sender = PrincipalId::new_anonymous();
+
+    // Submit an update call that stores some data in the canister.
+    let update = HttpCanisterUpdate {
+        canister_id: Blob(canister_id.as_slice().to_vec()),
+        method_name: "update".to_string(),
+        arg: Blob(
+            wasm()
+                .set_global_data(b"hello")
+                .reply_data(b"stored")
+                .build(),
+        ),
+        sender: Blob(sender.to_vec()),
+        ingress_expiry: ingress_expiry(&pic),
+        nonce: None,
+    };
+    let message_id = update.id();
+    let response = post_cbor(
+        &url,
+        &format!("api/v2/canister/{}/call", canister_id),
+        HttpCallContent::Call { update },
+    );
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    // Poll the status of the update call via `read_state` until it is replied.
+    let mut reply = None;
+    for _ in 0..100 {
+        let response = read_state(
+            &pic,
+            &url,
+            canister_id,
+            sender,
+            vec![
+                request_status_path(&message_id, "status"),
+                request_status_path(&message_id, "reply"),
+            ],
+        );
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response: HttpReadStateResponse =
+            serde_cbor::from_slice(&response.bytes().unwrap()).unwrap();
+        let certificate: Certificate = serde_cbor::from_slice(&response.certificate).unwrap();
+        let status_path: [&[u8]; 3] = [b"request_status", message_id.as_bytes(), b"status"];
+        if lookup_leaf(&certificate.tree, &status_path) == Some(b"replied".to_vec()) {
+            let reply_path: [&[u8]; 3] = [b"request_status", message_id.as_bytes(), b"reply"];
+            reply = lookup_leaf(&certificate.tree, &reply_path);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert_eq!(reply, Some(b"stored".to_vec()));
+
+    // A query observes the data stored by the update call.
+    let query = HttpUserQuery {
+        canister_id: Blob(canister_id.as_slice().to_vec()),
+        method_name: "query".to_string(),
+        arg: Blob(wasm().get_global_data().append_and_reply().build()),
+        sender: Blob(sender.to_vec()),
+        ingress_expiry: ingress_expiry(&pic),
+        nonce: None,
+    };
+    let response = post_cbor(
+        &url,
+        &format!("api/v2/canister/{}/query", canister_id),
+        HttpQueryContent::Query { query },
+    );
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response: HttpSignedQueryResponse =
+        serde_cbor::from_slice(&response.bytes().unwrap()).unwrap();
+    match response.response {
+        HttpQueryResponse::Replied { reply } => assert_eq!(reply.arg.0, b"hello".to_vec()),
+        HttpQueryResponse::Rejected { reject_message, .. } => {
+            panic!("Unexpected reject: {}", reject_message)
+        }
+    }
+
+    pic.stop_progress();
+}
+
+#[test]
+fn test_http_interface_read_state_authorization() {
+    let pic = PocketIc::new();
+    let canister_id = pic.create_canister();
+    pic.add_cycles(canister_id, INIT_CYCLES);
+    pic.install_canister(canister_id, UNIVERSAL_CANISTER_WASM.to_vec(), vec![], None);
+    let url = pic.auto_progress();
+    let sender = PrincipalId::new_user_test_id(1);
+    let other_sender = PrincipalId::new_user_test_id(2);
+
+    let update = HttpCanisterUpdate {
+        canister_id: Blob(canister_id.as_slice().to_vec()),
+        method_name: "update".to_string(),
+        arg: Blob(wasm().reply().build()),
+        sender: Blob(sender.to_vec()),
+        ingress_expiry: ingress_expiry(&pic),
+        nonce: None,
+    };
+    let message_id = update.id();
+    let response = post_cbor(
+        &url,
+        &format!("api/v2/canister/{}/call", canister_id),
+        HttpCallContent::Call { update },
+    );
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    // Wait until the update call is known to the subnet.
+    let mut known = false;
+    for _ in 0..100 {
+        let response = read_state(
+            &pic,
+            &url,
+            canister_id,
+            sender,
+            vec![request_status_path(&message_id, "status")],
+        );
+        let response: HttpReadStateResponse =
+            serde_cbor::from_slice(&response.bytes().unwrap()).unwrap();
+        let certificate: Certificate = serde_cbor::from_slice(&response.certificate).unwrap();
+        let status_path: [&[u8]; 3] = [b"request_status", message_id.as_bytes(), b"status"];
+        if lookup_leaf(&certificate.tree, &status_path).is_some() {
+            known = true;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert!(known);
+
+    // The status of a request can only be read by its sender.
+    let response = read_state(
+        &pic,
+        &url,
+        canister_id,
+        other_sender,
+        vec![request_status_path(&message_id, "status")],
+    );
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert!(response
+        .text()
+        .unwrap()
+        .contains("Request IDs must be for requests signed by the caller."));
+
+    // The canister paths must match the effective canister ID.
+    let other_canister_id = pic.create_canister();
+    let response = read_state(
+        &pic,
+        &url,
+        canister_id,
+        sender,
+        vec![Path::new(vec![
+            Label::from("canister"),
+            Label::from(other_canister_id.as_slice()),
+            Label::from("module_hash"),
+        ])],
+    );
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // Paths that are not part of the interface are rejected.
+    let response = read_state(
+        &pic,
+        &url,
+        canister_id,
+        sender,
+        vec![Path::new(vec![Label::from("canister")])],
+    );
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert!(response.text().unwrap().contains("Invalid path requested."));
+
+    pic.stop_progress();
+}
+
+fn btc_canister_wasm() -> Vec<u8> {
+    let wasm_path = std::env::var_os("BTC_CANISTER_WASM").expect("Missing bitcoin canister wasm");
+    std::fs::read(wasm_path).unwrap()
+}
+
+// The ID of the bitcoin testnet canister, which has privileged access to the bitcoin adapter.
+const BTC_TESTNET_CANISTER_ID: &str = "g4xu7-jiaaa-aaaan-aaaaq-cai";
+
+#[derive(CandidType)]
+enum BitcoinNetwork {
+    #[serde(rename = "regtest")]
+    Regtest,
+}
+
+#[derive(CandidType)]
+enum BitcoinFlag {
+    #[serde(rename = "enabled")]
+    Enabled,
+    #[serde(rename = "disabled")]
+    Disabled,
+}
+
+#[derive(CandidType, Default)]
+struct BitcoinFees {
+    get_utxos_base: u128,
+    get_utxos_cycles_per_ten_instructions: u128,
+    get_utxos_maximum: u128,
+    get_balance: u128,
+    get_balance_maximum: u128,
+    get_current_fee_percentiles: u128,
+    get_current_fee_percentiles_maximum: u128,
+    send_transaction_base: u128,
+    send_transaction_per_byte: u128,
+}
+
+/// The init argument of the bitcoin canister.
+#[derive(CandidType)]
+struct BitcoinCanisterConfig {
+    stability_threshold: u128,
+    network: BitcoinNetwork,
+    blocks_source: Principal,
+    syncing: BitcoinFlag,
+    fees: BitcoinFees,
+    api_access: BitcoinFlag,
+    disable_api_if_not_fully_synced: BitcoinFlag,
+}
+
+#[derive(CandidType)]
+struct GetBalanceRequest {
+    address: String,
+    network: BitcoinNetwork,
+    min_confirmations: Option<u32>,
+}
+
+#[test]
+fn test_mine_bitcoin_blocks() {
+    let pic = PocketIcBuilder::new()
+        .with_application_subnet()
+        .with_bitcoin_subnet()
+        .build();
+
+    // Deploy the bitcoin canister on the bitcoin subnet.
+    let btc_canister_id = Principal::from_text(BTC_TESTNET_CANISTER_ID).unwrap();
+    pic.create_canister_with_id(None, None, btc_canister_id)
+        .unwrap();
+    let config = BitcoinCanisterConfig {
+        stability_threshold: 0,
+        network: BitcoinNetwork::Regtest,
+        blocks_source: Principal::management_canister(),
+        syncing: BitcoinFlag::Enabled,
+        fees: BitcoinFees::default(),
+        api_access: BitcoinFlag::Enabled,
+        disable_api_if_not_fully_synced: BitcoinFlag::Disabled,
+    };
+    pic.install_canister(
+        btc_canister_id,
+        btc_canister_wasm(),
+        encode_one(config).unwrap(),
+        None,
+    );
+
+    // A P2WSH address on regtest (see BIP-173).
+    let address = "bcrt1quwcvgs5clswpfxhm7nyfjmaeysn6us0yvjdexn9yjkv3k7zjhp2snwgpgy".to_string();
+    pic.mine_bitcoin_blocks(vec![address.clone(), address.clone()]);
+
+    // The bitcoin canister fetches the mined blocks and the address
+    // receives the block subsidy of both blocks.
+    let expected_balance: u64 = 2 * 50 * 100_000_000;
+    let mut balance = 0;
+    for _ in 0..100 {
+        pic.tick();
+        let request = GetBalanceRequest {
+            address: address.clone(),
+            network: BitcoinNetwork::Regtest,
+            min_confirmations: None,
+        };
+        let reply = pic
+            .update_call(
+                btc_canister_id,
+                Principal::anonymous(),
+                "bitcoin_get_balance",
+                encode_one(request).unwrap(),
+            )
+            .unwrap();
+        balance = match reply {
+            WasmResult::Reply(bytes) => decode_one(&bytes).unwrap(),
+            WasmResult::Reject(msg) => panic!("Unexpected reject: {}", msg),
+        };
+        if balance == expected_balance {
+            break;
+        }
+    }
+    assert_eq!(balance, expected_balance);
+
+    // The bitcoin canister did not send any transactions.
+    assert!(pic.get_bitcoin_transactions().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "InvalidBitcoinAddress")]
+fn test_mine_bitcoin_blocks_invalid_address() {
+    let pic = PocketIcBuilder::new().with_bitcoin_subnet().build();
+    pic.mine_bitcoin_blocks(vec!["not a bitcoin address".to_string()]);
+}
+
+#[test]
+#[should_panic(expected = "BitcoinSubnetNotFound")]
+fn test_mine_bitcoin_blocks_without_bitcoin_subnet() {
+    let pic = PocketIc::new();
+    pic.mine_bitcoin_blocks(vec![]);
+}
+
 #[test]
 fn test_set_and_get_stable_memory_not_compressed() {
     let pic = PocketIc::new();
@@ -669,3 +1173,80 @@ fn test_set_and_get_stable_memory_compressed() {
     let read_data = pic.get_stable_memory(canister_id);
     assert_eq!(data, read_data[..8]);
 }
+
+#[test]
+fn test_canister_http() {
+    let pic = PocketIc::new();
+    let canister_id = pic.create_canister();
+    pic.add_cycles(canister_id, INIT_CYCLES);
+    pic.install_canister(canister_id, UNIVERSAL_CANISTER_WASM.to_vec(), vec![], None);
+
+    // The outcall is made from the canister's global timer so that the update call
+    // setting up the timer completes without waiting for the outcall's response.
+    let arg = CanisterHttpRequestArgument {
+        url: "https://example.com".to_string(),
+        max_response_bytes: Some(1_000),
+        method: HttpMethod::GET,
+        headers: vec![],
+        body: None,
+        transform: None,
+    };
+    let http_request = wasm().call_with_cycles(
+        Principal::management_canister(),
+        "http_request",
+        CallArgs::default()
+            .other_side(encode_one(arg).unwrap())
+            .on_reply(wasm().message_payload().set_global_data_from_stack())
+            .on_reject(wasm().reject_message().set_global_data_from_stack()),
+        Cycles::new(100_000_000_000),
+    );
+    pic.update_call(
+        canister_id,
+        Principal::anonymous(),
+        "update",
+        wasm()
+            .set_global_timer_method(http_request)
+            .api_global_timer_set(1)
+            .reply()
+            .build(),
+    )
+    .unwrap();
+
+    // One round to fire the timer and one round to execute the management canister call.
+    pic.tick();
+    pic.tick();
+
+    let canister_http_requests = pic.get_canister_http();
+    assert_eq!(canister_http_requests.len(), 1);
+    let canister_http_request = &canister_http_requests[0];
+    assert_eq!(canister_http_request.url, "https://example.com");
+    assert_eq!(canister_http_request.http_method, CanisterHttpMethod::GET);
+    assert_eq!(canister_http_request.max_response_bytes, Some(1_000));
+
+    let body = b"hello".to_vec();
+    pic.mock_canister_http_response(MockCanisterHttpResponse {
+        subnet_id: canister_http_request.subnet_id,
+        request_id: canister_http_request.request_id,
+        response: CanisterHttpResponse::CanisterHttpReply(CanisterHttpReply {
+            status: 200,
+            headers: vec![],
+            body: body.clone(),
+        }),
+    });
+    assert!(pic.get_canister_http().is_empty());
+
+    let reply = pic
+        .query_call(
+            canister_id,
+            Principal::anonymous(),
+            "query",
+            wasm().get_global_data().append_and_reply().build(),
+        )
+        .unwrap();
+    let WasmResult::Reply(bytes) = reply else {
+        panic!("Unexpected reject: {:?}", reply);
+    };
+    let http_response: HttpResponse = decode_one(&bytes).unwrap();
+    assert_eq!(http_response.status, candid::Nat::from(200_u16));
+    assert_eq!(http_response.body, body);
+}
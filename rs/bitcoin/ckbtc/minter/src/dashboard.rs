@@ -281,6 +281,10 @@ pub fn build_metadata() -> String {
                         <th>Total BTC managed</th>
                         <td>{}</td>
                     </tr>
+                    <tr>
+                        <th>UTXO consolidation fees</th>
+                        <td>{}</td>
+                    </tr>
                 </tbody>
             </table>",
             s.btc_network,
@@ -297,7 +301,8 @@ pub fn build_metadata() -> String {
                 .unwrap_or_else(|| "N/A".to_string()),
             DisplayAmount(s.kyt_fee),
             DisplayAmount(s.retrieve_btc_min_amount),
-            DisplayAmount(get_total_btc_managed())
+            DisplayAmount(get_total_btc_managed()),
+            DisplayAmount(s.utxo_consolidation_fees)
         )
     })
 }
@@ -3,7 +3,7 @@ use crate::logs::{P0, P1};
 use crate::memo::Status;
 use crate::queries::WithdrawalFee;
 use crate::state::ReimbursementReason;
-use crate::tasks::schedule_after;
+use crate::tasks::{schedule_after, schedule_now};
 use candid::{CandidType, Deserialize};
 use ic_btc_interface::{MillisatoshiPerByte, Network, OutPoint, Satoshi, Txid, Utxo};
 use ic_canister_log::log;
@@ -68,6 +68,23 @@ pub const CKBTC_LEDGER_MEMO_SIZE: u16 = 80;
 /// when building transactions.
 pub const UTXOS_COUNT_THRESHOLD: usize = 1_000;
 
+/// The maximum number of UTXOs that the minter spends in a single
+/// consolidation transaction. This keeps consolidation transactions
+/// well below the standard transaction weight limit.
+pub const MAX_CONSOLIDATION_INPUTS: usize = 500;
+
+/// Having a sequence number lower than (0xffffffff - 1) signals the use of replacement by fee.
+/// It allows us to increase the fee of a transaction already sent to the mempool.
+/// The rbf option is used in `resubmit_retrieve_btc`.
+/// https://github.com/bitcoin/bips/blob/master/bip-0125.mediawiki
+const SEQUENCE_RBF_ENABLED: u32 = 0xfffffffd;
+
+/// The default dustRelayFee is 3 sat/vB,
+/// which translates to a dust threshold of 546 satoshi for P2PKH outputs.
+/// The threshold for other types is lower,
+/// so we simply use 546 satoshi as the minimum amount per output.
+const MIN_OUTPUT_AMOUNT: u64 = 546;
+
 #[derive(Clone, serde::Serialize, Deserialize, Debug)]
 pub enum Priority {
     P0,
@@ -609,8 +626,6 @@ async fn finalize_requests() {
     let key_name = state::read_state(|s| s.ecdsa_key_name.clone());
 
     for (old_txid, submitted_tx) in maybe_finalized_transactions {
-        let mut utxos: BTreeSet<_> = submitted_tx.used_utxos.iter().cloned().collect();
-
         let tx_fee_per_vbyte = match submitted_tx.fee_per_vbyte {
             Some(prev_fee) => {
                 // Ensure that the fee is at least min relay fee higher than the previous
@@ -620,15 +635,8 @@ async fn finalize_requests() {
             None => fee_per_vbyte,
         };
 
-        let outputs = submitted_tx
-            .requests
-            .iter()
-            .map(|req| (req.address.clone(), req.amount))
-            .collect();
-
-        let (unsigned_tx, change_output, used_utxos) = match build_unsigned_transaction(
-            &mut utxos,
-            outputs,
+        let (unsigned_tx, change_output, used_utxos) = match build_replacement_transaction(
+            &submitted_tx,
             main_address.clone(),
             tx_fee_per_vbyte,
         ) {
@@ -648,10 +656,6 @@ async fn finalize_requests() {
 
         let outpoint_account = state::read_state(|s| filter_output_accounts(s, &unsigned_tx));
 
-        assert!(
-            utxos.is_empty(),
-            "build_unsigned_transaction didn't use all inputs"
-        );
         assert_eq!(used_utxos.len(), submitted_tx.used_utxos.len());
 
         let new_txid = unsigned_tx.txid();
@@ -721,6 +725,40 @@ async fn finalize_requests() {
     }
 }
 
+/// Rebuilds a stuck transaction with the specified fee, spending the same UTXOs.
+///
+/// A consolidation transaction is rebuilt into a single output to the minter's
+/// main address, other transactions pay the same retrieve_btc requests.
+fn build_replacement_transaction(
+    submitted_tx: &state::SubmittedBtcTransaction,
+    main_address: BitcoinAddress,
+    fee_per_vbyte: u64,
+) -> Result<(tx::UnsignedTransaction, state::ChangeOutput, Vec<Utxo>), BuildTxError> {
+    if submitted_tx.is_consolidation() {
+        return build_consolidation_transaction(
+            &submitted_tx.used_utxos,
+            main_address,
+            fee_per_vbyte,
+        )
+        .map(|(unsigned_tx, change_output)| {
+            (unsigned_tx, change_output, submitted_tx.used_utxos.clone())
+        });
+    }
+    let mut utxos: BTreeSet<_> = submitted_tx.used_utxos.iter().cloned().collect();
+    let outputs = submitted_tx
+        .requests
+        .iter()
+        .map(|req| (req.address.clone(), req.amount))
+        .collect();
+    build_unsigned_transaction(&mut utxos, outputs, main_address, fee_per_vbyte).map(|tx| {
+        assert!(
+            utxos.is_empty(),
+            "build_unsigned_transaction didn't use all inputs"
+        );
+        tx
+    })
+}
+
 /// Builds the minimal OutPoint -> Account map required to sign a transaction.
 fn filter_output_accounts(
     state: &state::CkBtcMinterState,
@@ -954,12 +992,6 @@ pub fn build_unsigned_transaction(
 ) -> Result<(tx::UnsignedTransaction, state::ChangeOutput, Vec<Utxo>), BuildTxError> {
     assert!(!outputs.is_empty());
 
-    /// Having a sequence number lower than (0xffffffff - 1) signals the use of replacement by fee.
-    /// It allows us to increase the fee of a transaction already sent to the mempool.
-    /// The rbf option is used in `resubmit_retrieve_btc`.
-    /// https://github.com/bitcoin/bips/blob/master/bip-0125.mediawiki
-    const SEQUENCE_RBF_ENABLED: u32 = 0xfffffffd;
-
     let amount = outputs.iter().map(|(_, amount)| amount).sum::<u64>();
 
     let input_utxos = utxos_selection(amount, minter_utxos, outputs.len());
@@ -1028,11 +1060,6 @@ pub fn build_unsigned_transaction(
     }
 
     let fee_shares = distribute(fee + minter_fee, outputs.len() as u64);
-    // The default dustRelayFee is 3 sat/vB,
-    // which translates to a dust threshold of 546 satoshi for P2PKH outputs.
-    // The threshold for other types is lower,
-    // so we simply use 546 satoshi as the minimum amount per output.
-    const MIN_OUTPUT_AMOUNT: u64 = 546;
 
     for (output, fee_share) in unsigned_tx.outputs.iter_mut().zip(fee_shares.iter()) {
         if output.address != main_address {
@@ -1058,6 +1085,214 @@ pub fn build_unsigned_transaction(
     ))
 }
 
+/// Selects up to [MAX_CONSOLIDATION_INPUTS] of the smallest available UTXOs
+/// worth spending at the specified fee.
+///
+/// UTXOs whose value does not cover the fee for spending them are never
+/// selected. Returns an empty vector if there are less than two UTXOs to
+/// consolidate.
+fn select_utxos_to_consolidate(available_utxos: &BTreeSet<Utxo>, fee_per_vbyte: u64) -> Vec<Utxo> {
+    let input_fee = (tx_vsize_estimate(1, 0) - tx_vsize_estimate(0, 0)) * fee_per_vbyte / 1000;
+
+    let mut candidates: Vec<Utxo> = available_utxos
+        .iter()
+        .filter(|u| u.value > input_fee)
+        .cloned()
+        .collect();
+    candidates.sort_by_key(|u| u.value);
+    candidates.truncate(MAX_CONSOLIDATION_INPUTS);
+
+    if candidates.len() < 2 {
+        return vec![];
+    }
+    candidates
+}
+
+/// Builds a transaction that spends the specified minter UTXOs into a single
+/// output to the minter's main address. The minter pays the fee.
+///
+/// The only output of the transaction is the minter's change output.
+///
+/// # Panics
+///
+/// This function panics if the `input_utxos` slice is empty as it indicates a
+/// bug in the caller's code.
+pub fn build_consolidation_transaction(
+    input_utxos: &[Utxo],
+    main_address: BitcoinAddress,
+    fee_per_vbyte: u64,
+) -> Result<(tx::UnsignedTransaction, state::ChangeOutput), BuildTxError> {
+    assert!(!input_utxos.is_empty());
+
+    let inputs_value = input_utxos.iter().map(|u| u.value).sum::<u64>();
+
+    let mut unsigned_tx = tx::UnsignedTransaction {
+        inputs: input_utxos
+            .iter()
+            .map(|utxo| tx::UnsignedInput {
+                previous_output: utxo.outpoint.clone(),
+                value: utxo.value,
+                sequence: SEQUENCE_RBF_ENABLED,
+            })
+            .collect(),
+        outputs: vec![tx::TxOut {
+            address: main_address,
+            value: inputs_value,
+        }],
+        lock_time: 0,
+    };
+
+    let tx_vsize = fake_sign(&unsigned_tx).vsize();
+    let fee = (tx_vsize as u64 * fee_per_vbyte) / 1000;
+
+    if fee + MIN_OUTPUT_AMOUNT >= inputs_value {
+        return Err(BuildTxError::AmountTooLow);
+    }
+
+    let change_output = state::ChangeOutput {
+        vout: 0,
+        value: inputs_value - fee,
+    };
+    unsigned_tx.outputs[0].value = change_output.value;
+
+    debug_assert_eq!(
+        inputs_value,
+        fee + unsigned_tx.outputs.iter().map(|u| u.value).sum::<u64>()
+    );
+
+    Ok((unsigned_tx, change_output))
+}
+
+/// Spends many small UTXOs into a single UTXO owned by the minter's main account
+/// when the Bitcoin fees are low enough.
+async fn consolidate_utxos() {
+    let fee_millisatoshi_per_vbyte = match estimate_fee_per_vbyte().await {
+        Some(fee) => fee,
+        None => return,
+    };
+
+    if !state::read_state(|s| s.should_consolidate_utxos(fee_millisatoshi_per_vbyte)) {
+        return;
+    }
+
+    let main_account = Account {
+        owner: ic_cdk::id(),
+        subaccount: None,
+    };
+
+    let ecdsa_public_key = updates::get_btc_address::init_ecdsa_public_key().await;
+    let main_address = address::account_to_bitcoin_address(&ecdsa_public_key, &main_account);
+
+    let maybe_sign_request = state::mutate_state(|s| {
+        if !s.should_consolidate_utxos(fee_millisatoshi_per_vbyte) {
+            return None;
+        }
+
+        let utxos = select_utxos_to_consolidate(&s.available_utxos, fee_millisatoshi_per_vbyte);
+        if utxos.is_empty() {
+            return None;
+        }
+
+        match build_consolidation_transaction(&utxos, main_address, fee_millisatoshi_per_vbyte) {
+            Ok((unsigned_tx, change_output)) => {
+                for utxo in utxos.iter() {
+                    assert!(s.available_utxos.remove(utxo));
+                }
+
+                Some(SignTxRequest {
+                    key_name: s.ecdsa_key_name.clone(),
+                    ecdsa_public_key,
+                    change_output,
+                    outpoint_account: filter_output_accounts(s, &unsigned_tx),
+                    network: s.btc_network,
+                    unsigned_tx,
+                    requests: vec![],
+                    utxos,
+                })
+            }
+            Err(err) => {
+                log!(
+                    P1,
+                    "[consolidate_utxos]: failed to build a consolidation transaction for {} UTXOs: {:?}",
+                    utxos.len(),
+                    err
+                );
+                None
+            }
+        }
+    });
+
+    let req = match maybe_sign_request {
+        Some(req) => req,
+        None => return,
+    };
+
+    log!(
+        P1,
+        "[consolidate_utxos]: signing a new consolidation transaction: {}",
+        hex::encode(tx::encode_into(&req.unsigned_tx, Vec::new()))
+    );
+
+    // This guard ensures that we return the UTXOs back to the state if the
+    // signing or sending a transaction fails or panics.
+    let utxos_guard = guard((req.requests, req.utxos), |(reqs, utxos)| {
+        undo_sign_request(reqs, utxos);
+    });
+
+    let txid = req.unsigned_tx.txid();
+
+    match sign_transaction(
+        req.key_name,
+        &req.ecdsa_public_key,
+        &req.outpoint_account,
+        req.unsigned_tx,
+    )
+    .await
+    {
+        Ok(signed_tx) => match management::send_transaction(&signed_tx, req.network).await {
+            Ok(()) => {
+                let (_, used_utxos) = ScopeGuard::into_inner(utxos_guard);
+
+                log!(
+                    P0,
+                    "[consolidate_utxos]: sent transaction {} consolidating {} UTXOs into {}",
+                    &txid,
+                    used_utxos.len(),
+                    tx::DisplayAmount(req.change_output.value),
+                );
+
+                state::mutate_state(|s| {
+                    state::audit::sent_consolidation_transaction(
+                        s,
+                        state::SubmittedBtcTransaction {
+                            requests: vec![],
+                            txid,
+                            used_utxos,
+                            change_output: Some(req.change_output),
+                            submitted_at: ic_cdk::api::time(),
+                            fee_per_vbyte: Some(fee_millisatoshi_per_vbyte),
+                        },
+                    );
+                });
+            }
+            Err(err) => {
+                log!(
+                    P0,
+                    "[consolidate_utxos]: failed to send a consolidation transaction: {}",
+                    err
+                );
+            }
+        },
+        Err(err) => {
+            log!(
+                P0,
+                "[consolidate_utxos]: failed to sign a consolidation transaction: {}",
+                err
+            );
+        }
+    }
+}
+
 /// Distributes an amount across the specified number of shares as fairly as
 /// possible.
 ///
@@ -1187,10 +1422,29 @@ pub fn timer() {
         TaskType::RefreshFeePercentiles => {
             ic_cdk::spawn(async {
                 const FEE_ESTIMATE_DELAY: Duration = Duration::from_secs(60 * 60);
-                let _ = estimate_fee_per_vbyte().await;
+                if let Some(fee) = estimate_fee_per_vbyte().await {
+                    if state::read_state(|s| s.should_consolidate_utxos(fee)) {
+                        schedule_now(TaskType::ConsolidateUtxos);
+                    }
+                }
                 schedule_after(FEE_ESTIMATE_DELAY, TaskType::RefreshFeePercentiles);
             });
         }
+        TaskType::ConsolidateUtxos => {
+            ic_cdk::spawn(async {
+                // Consolidation competes with withdrawals for the available UTXOs,
+                // so it runs under the same guard as the processing logic.
+                let _guard = match crate::guard::TimerLogicGuard::new() {
+                    Some(guard) => guard,
+                    None => {
+                        schedule_after(INTERVAL_PROCESSING, TaskType::ConsolidateUtxos);
+                        return;
+                    }
+                };
+
+                consolidate_utxos().await;
+            });
+        }
         TaskType::DistributeKytFee => {
             ic_cdk::spawn(async {
                 let _guard = match crate::guard::DistributeKytFeeGuard::new() {
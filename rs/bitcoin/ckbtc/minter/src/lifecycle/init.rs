@@ -84,6 +84,12 @@ pub struct InitArgs {
     /// NOTE: this field is optional for backward compatibility.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kyt_principal: Option<CanisterId>,
+
+    /// The median fee per vbyte (in millisatoshi) at or below which the minter
+    /// consolidates small UTXOs into larger ones.
+    /// The minter does not consolidate UTXOs if this field is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utxo_consolidation_fee_threshold: Option<u64>,
 }
 
 pub fn init(args: InitArgs) {
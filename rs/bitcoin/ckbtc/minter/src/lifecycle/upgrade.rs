@@ -32,6 +32,11 @@ pub struct UpgradeArgs {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kyt_principal: Option<CanisterId>,
+
+    /// The median fee per vbyte (in millisatoshi) at or below which the minter
+    /// consolidates small UTXOs into larger ones. Zero disables the consolidation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utxo_consolidation_fee_threshold: Option<u64>,
 }
 
 pub fn post_upgrade(upgrade_args: Option<UpgradeArgs>) {
@@ -86,6 +86,14 @@ pub struct SubmittedBtcTransaction {
     pub fee_per_vbyte: Option<u64>,
 }
 
+impl SubmittedBtcTransaction {
+    /// Returns true if the minter sent this transaction to consolidate its own
+    /// UTXOs rather than to serve retrieve_btc requests.
+    pub fn is_consolidation(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
 /// Pairs a retrieve_btc request with its outcome.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FinalizedBtcRetrieval {
@@ -329,6 +337,10 @@ pub struct CkBtcMinterState {
     /// The total amount of ckBTC burned.
     pub tokens_burned: u64,
 
+    /// The total amount of BTC (in satoshi) that the minter paid from its own
+    /// UTXOs in fees of confirmed UTXO consolidation transactions.
+    pub utxo_consolidation_fees: u64,
+
     /// The CanisterId of the ckBTC Ledger.
     pub ledger_id: CanisterId,
 
@@ -388,6 +400,10 @@ pub struct CkBtcMinterState {
 
     /// Map from burn block index to the the reimbursed request.
     pub reimbursed_transactions: BTreeMap<u64, ReimbursedDeposit>,
+
+    /// The median fee per vbyte (in millisatoshi) at or below which the minter
+    /// consolidates its UTXOs. The minter does not consolidate UTXOs if not set.
+    pub utxo_consolidation_fee_threshold: Option<u64>,
 }
 
 #[derive(CandidType, Clone, Debug, PartialEq, Eq, serde::Deserialize, Serialize)]
@@ -427,6 +443,7 @@ impl CkBtcMinterState {
             mode,
             kyt_fee,
             kyt_principal,
+            utxo_consolidation_fee_threshold,
         }: InitArgs,
     ) {
         self.btc_network = btc_network.into();
@@ -442,6 +459,7 @@ impl CkBtcMinterState {
         if let Some(min_confirmations) = min_confirmations {
             self.min_confirmations = min_confirmations;
         }
+        self.utxo_consolidation_fee_threshold = utxo_consolidation_fee_threshold;
     }
 
     pub fn upgrade(
@@ -453,6 +471,7 @@ impl CkBtcMinterState {
             mode,
             kyt_principal,
             kyt_fee,
+            utxo_consolidation_fee_threshold,
         }: UpgradeArgs,
     ) {
         if let Some(retrieve_btc_min_amount) = retrieve_btc_min_amount {
@@ -482,6 +501,10 @@ impl CkBtcMinterState {
         if let Some(kyt_fee) = kyt_fee {
             self.kyt_fee = kyt_fee;
         }
+        if let Some(threshold) = utxo_consolidation_fee_threshold {
+            // A zero threshold disables the UTXO consolidation.
+            self.utxo_consolidation_fee_threshold = Some(threshold).filter(|t| *t > 0);
+        }
     }
 
     pub fn validate_config(&self) {
@@ -726,6 +749,26 @@ impl CkBtcMinterState {
         batch
     }
 
+    /// Returns true if the minter should consolidate its UTXOs given the
+    /// current median fee per vbyte (in millisatoshi).
+    ///
+    /// The minter consolidates UTXOs only if the fees are at or below the
+    /// configured threshold, if it manages more than [crate::UTXOS_COUNT_THRESHOLD]
+    /// available UTXOs, and if there is no other consolidation transaction in flight.
+    pub fn should_consolidate_utxos(&self, median_fee_per_vbyte: u64) -> bool {
+        match self.utxo_consolidation_fee_threshold {
+            Some(threshold) => {
+                median_fee_per_vbyte <= threshold
+                    && self.available_utxos.len() > crate::UTXOS_COUNT_THRESHOLD
+                    && !self
+                        .submitted_transactions
+                        .iter()
+                        .any(|tx| tx.is_consolidation())
+            }
+            None => false,
+        }
+    }
+
     /// Returns the total number of all retrieve_btc requests that we haven't
     /// finalized yet.
     pub fn count_incomplete_retrieve_btc_requests(&self) -> usize {
@@ -792,6 +835,15 @@ impl CkBtcMinterState {
             self.forget_utxo(utxo);
         }
         self.finalized_requests_count += finalized_tx.requests.len() as u64;
+        if finalized_tx.is_consolidation() {
+            let inputs_value = finalized_tx.used_utxos.iter().map(|u| u.value).sum::<u64>();
+            let change_value = finalized_tx
+                .change_output
+                .as_ref()
+                .map(|out| out.value)
+                .unwrap_or_default();
+            self.utxo_consolidation_fees += inputs_value - change_value;
+        }
         for request in finalized_tx.requests {
             self.push_finalized_request(FinalizedBtcRetrieval {
                 request,
@@ -1160,6 +1212,12 @@ impl CkBtcMinterState {
 
         ensure_eq!(self.kyt_fee, other.kyt_fee, "kyt_fee does not match");
 
+        ensure_eq!(
+            self.utxo_consolidation_fee_threshold,
+            other.utxo_consolidation_fee_threshold,
+            "utxo_consolidation_fee_threshold does not match"
+        );
+
         ensure_eq!(
             self.owed_kyt_amount,
             other.owed_kyt_amount,
@@ -1248,6 +1306,7 @@ impl From<InitArgs> for CkBtcMinterState {
             finalized_requests_count: 0,
             tokens_minted: 0,
             tokens_burned: 0,
+            utxo_consolidation_fees: 0,
             ledger_id: args.ledger_id,
             kyt_principal: args.kyt_principal,
             available_utxos: Default::default(),
@@ -1267,6 +1326,7 @@ impl From<InitArgs> for CkBtcMinterState {
             quarantined_utxos: Default::default(),
             pending_reimbursements: Default::default(),
             reimbursed_transactions: Default::default(),
+            utxo_consolidation_fee_threshold: args.utxo_consolidation_fee_threshold,
         }
     }
 }
@@ -65,6 +65,24 @@ pub fn sent_transaction(state: &mut CkBtcMinterState, tx: SubmittedBtcTransactio
     state.push_submitted_transaction(tx);
 }
 
+pub fn sent_consolidation_transaction(state: &mut CkBtcMinterState, tx: SubmittedBtcTransaction) {
+    debug_assert!(tx.is_consolidation());
+    record_event(&Event::SentConsolidationTransaction {
+        txid: tx.txid,
+        utxos: tx.used_utxos.clone(),
+        change_output: tx
+            .change_output
+            .clone()
+            .expect("bug: all consolidation transactions must have the change output"),
+        submitted_at: tx.submitted_at,
+        fee_per_vbyte: tx
+            .fee_per_vbyte
+            .expect("bug: all consolidation transactions must have the fee"),
+    });
+
+    state.push_submitted_transaction(tx);
+}
+
 pub fn confirm_transaction(state: &mut CkBtcMinterState, txid: &Txid) {
     record_event(&Event::ConfirmedBtcTransaction { txid: *txid });
     state.finalize_transaction(txid);
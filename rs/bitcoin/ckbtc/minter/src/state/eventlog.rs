@@ -83,6 +83,27 @@ pub enum Event {
         fee_per_vbyte: Option<u64>,
     },
 
+    /// Indicates that the minter sent out a new transaction to consolidate
+    /// its UTXOs into a single output to the minter's main address.
+    #[serde(rename = "sent_consolidation_transaction")]
+    SentConsolidationTransaction {
+        /// The Txid of the Bitcoin transaction.
+        #[serde(rename = "txid")]
+        txid: Txid,
+        /// UTXOs consolidated by the transaction.
+        #[serde(rename = "utxos")]
+        utxos: Vec<Utxo>,
+        /// The output holding the consolidated amount.
+        #[serde(rename = "change_output")]
+        change_output: ChangeOutput,
+        /// The IC time at which the minter submitted the transaction.
+        #[serde(rename = "submitted_at")]
+        submitted_at: u64,
+        /// The fee per vbyte (in millisatoshi) that we used for the transaction.
+        #[serde(rename = "fee")]
+        fee_per_vbyte: u64,
+    },
+
     /// Indicates that the minter sent out a new transaction to replace an older transaction
     /// because the old transaction did not appear on the Bitcoin blockchain.
     #[serde(rename = "replaced_transaction")]
@@ -262,6 +283,25 @@ pub fn replay(mut events: impl Iterator<Item = Event>) -> Result<CkBtcMinterStat
                     submitted_at,
                 });
             }
+            Event::SentConsolidationTransaction {
+                txid,
+                utxos,
+                change_output,
+                submitted_at,
+                fee_per_vbyte,
+            } => {
+                for utxo in utxos.iter() {
+                    state.available_utxos.remove(utxo);
+                }
+                state.push_submitted_transaction(SubmittedBtcTransaction {
+                    requests: vec![],
+                    txid,
+                    used_utxos: utxos,
+                    fee_per_vbyte: Some(fee_per_vbyte),
+                    change_output: Some(change_output),
+                    submitted_at,
+                });
+            }
             Event::ReplacedBtcTransaction {
                 old_txid,
                 new_txid,
@@ -1,6 +1,7 @@
 use crate::MINTER_FEE_CONSTANT;
 use crate::{
-    address::BitcoinAddress, build_unsigned_transaction, estimate_fee, fake_sign, greedy,
+    address::BitcoinAddress, build_consolidation_transaction, build_replacement_transaction,
+    build_unsigned_transaction, estimate_fee, fake_sign, greedy, select_utxos_to_consolidate,
     signature::EncodedSignature, tx, BuildTxError,
 };
 use crate::{
@@ -790,6 +791,60 @@ proptest! {
         prop_assert_eq!(change_output, ChangeOutput { vout: 1, value: inputs_value - target + minter_fee });
     }
 
+    #[test]
+    fn build_consolidation_tx_spends_all_inputs(
+        utxos in btree_set(arb_utxo(10_000u64..1_000_000_000), 1..50),
+        main_pkhash in uniform20(any::<u8>()),
+        fee_per_vbyte in 1000..2000u64,
+    ) {
+        let utxos: Vec<_> = utxos.into_iter().collect();
+        let inputs_value = utxos.iter().map(|u| u.value).sum::<u64>();
+
+        let (unsigned_tx, change_output) = build_consolidation_transaction(
+            &utxos,
+            BitcoinAddress::P2wpkhV0(main_pkhash),
+            fee_per_vbyte,
+        )
+        .expect("failed to build transaction");
+
+        let fee = fake_sign(&unsigned_tx).vsize() as u64 * fee_per_vbyte / 1000;
+
+        prop_assert_eq!(
+            unsigned_tx.inputs.iter().map(|input| input.previous_output.clone()).collect::<Vec<_>>(),
+            utxos.iter().map(|u| u.outpoint.clone()).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            &unsigned_tx.outputs,
+            &vec![tx::TxOut {
+                value: inputs_value - fee,
+                address: BitcoinAddress::P2wpkhV0(main_pkhash),
+            }]
+        );
+        prop_assert_eq!(change_output, ChangeOutput { vout: 0, value: inputs_value - fee });
+    }
+
+    #[test]
+    fn should_select_smallest_utxos_worth_consolidating(
+        utxos in btree_set(arb_utxo(1u64..1_000_000), 0..1_000),
+        fee_per_vbyte in 1000..100_000u64,
+    ) {
+        let input_fee = 68 * fee_per_vbyte / 1000;
+        let selected = select_utxos_to_consolidate(&utxos, fee_per_vbyte);
+
+        prop_assert!(selected.len() <= crate::MAX_CONSOLIDATION_INPUTS);
+        prop_assert!(selected.len() != 1);
+        prop_assert!(selected.iter().all(|u| u.value > input_fee && utxos.contains(u)));
+
+        if let Some(max_selected) = selected.iter().map(|u| u.value).max() {
+            let not_selected_candidates = utxos
+                .iter()
+                .filter(|u| u.value > input_fee && !selected.contains(u));
+            for u in not_selected_candidates {
+                prop_assert!(u.value >= max_selected);
+            }
+        }
+    }
+
     #[test]
     fn build_tx_does_not_modify_utxos_on_error(
         mut utxos in btree_set(arb_utxo(5_000u64..1_000_000_000), 1..20),
@@ -838,7 +893,8 @@ proptest! {
             min_confirmations: None,
             mode: Mode::GeneralAvailability,
             kyt_fee: None,
-            kyt_principal: None
+            kyt_principal: None,
+            utxo_consolidation_fee_threshold: None,
         });
         for (utxo, acc_idx) in utxos_acc_idx {
             state.add_utxos(accounts[acc_idx], vec![utxo]);
@@ -862,7 +918,8 @@ proptest! {
             min_confirmations: None,
             mode: Mode::GeneralAvailability,
             kyt_fee: None,
-            kyt_principal: None
+            kyt_principal: None,
+            utxo_consolidation_fee_threshold: None,
         });
 
         let mut available_amount = 0;
@@ -905,7 +962,8 @@ proptest! {
             min_confirmations: None,
             mode: Mode::GeneralAvailability,
             kyt_fee: None,
-            kyt_principal: None
+            kyt_principal: None,
+            utxo_consolidation_fee_threshold: None,
         });
 
         for (utxo, acc_idx) in utxos_acc_idx {
@@ -1121,6 +1179,184 @@ proptest! {
     }
 }
 
+#[test]
+fn should_fail_to_consolidate_dust() {
+    let utxos: Vec<_> = (1..=10).map(|i| dummy_utxo_from_value(i * 100)).collect();
+
+    assert_eq!(
+        build_consolidation_transaction(&utxos, BitcoinAddress::P2wpkhV0([0; 20]), 10_000),
+        Err(BuildTxError::AmountTooLow)
+    );
+}
+
+#[test]
+fn should_consolidate_utxos_conditions() {
+    let mut state = CkBtcMinterState::from(InitArgs {
+        btc_network: Network::Regtest.into(),
+        ecdsa_key_name: "".to_string(),
+        retrieve_btc_min_amount: 0,
+        ledger_id: CanisterId::from_u64(42),
+        max_time_in_queue_nanos: 1000,
+        min_confirmations: None,
+        mode: Mode::GeneralAvailability,
+        kyt_fee: None,
+        kyt_principal: None,
+        utxo_consolidation_fee_threshold: None,
+    });
+    let account = Account {
+        owner: Principal::management_canister(),
+        subaccount: None,
+    };
+    state.add_utxos(
+        account,
+        (1..=crate::UTXOS_COUNT_THRESHOLD as u64 + 1)
+            .map(|i| dummy_utxo_from_value(i * 1_000))
+            .collect(),
+    );
+
+    // No threshold configured, fail.
+    assert!(!state.should_consolidate_utxos(1_000));
+
+    state.utxo_consolidation_fee_threshold = Some(2_000);
+    // Fee below the threshold, pass.
+    assert!(state.should_consolidate_utxos(1_000));
+    // Fee above the threshold, fail.
+    assert!(!state.should_consolidate_utxos(3_000));
+
+    let utxos = select_utxos_to_consolidate(&state.available_utxos, 1_000);
+    let (unsigned_tx, change_output) =
+        build_consolidation_transaction(&utxos, BitcoinAddress::P2wpkhV0([0; 20]), 1_000)
+            .expect("failed to build transaction");
+    for utxo in utxos.iter() {
+        state.available_utxos.remove(utxo);
+    }
+    state.push_submitted_transaction(SubmittedBtcTransaction {
+        requests: vec![],
+        txid: unsigned_tx.txid(),
+        used_utxos: utxos,
+        submitted_at: 0,
+        change_output: Some(change_output),
+        fee_per_vbyte: Some(1_000),
+    });
+    assert!(state.submitted_transactions[0].is_consolidation());
+    // A consolidation transaction is already in flight, fail.
+    assert!(!state.should_consolidate_utxos(1_000));
+
+    state.finalize_transaction(&unsigned_tx.txid());
+    // Not enough UTXOs left after the consolidation, fail.
+    assert!(!state.should_consolidate_utxos(1_000));
+}
+
+#[test]
+fn should_replay_consolidation_transaction() {
+    use crate::state::eventlog::{replay, Event};
+
+    let init_args = InitArgs {
+        btc_network: Network::Regtest.into(),
+        ecdsa_key_name: "".to_string(),
+        retrieve_btc_min_amount: 0,
+        ledger_id: CanisterId::from_u64(42),
+        max_time_in_queue_nanos: 1000,
+        min_confirmations: None,
+        mode: Mode::GeneralAvailability,
+        kyt_fee: None,
+        kyt_principal: None,
+        utxo_consolidation_fee_threshold: Some(2_000),
+    };
+    let account = Account {
+        owner: Principal::management_canister(),
+        subaccount: None,
+    };
+    let utxos: Vec<_> = (1..=10)
+        .map(|i| dummy_utxo_from_value(i * 10_000))
+        .collect();
+    let (unsigned_tx, change_output) =
+        build_consolidation_transaction(&utxos[..5], BitcoinAddress::P2wpkhV0([0; 20]), 1_000)
+            .expect("failed to build transaction");
+    let txid = unsigned_tx.txid();
+    let fee = utxos[..5].iter().map(|u| u.value).sum::<u64>() - change_output.value;
+
+    let events = vec![
+        Event::Init(init_args),
+        Event::ReceivedUtxos {
+            mint_txid: None,
+            to_account: account,
+            utxos: utxos.clone(),
+        },
+        Event::SentConsolidationTransaction {
+            txid,
+            utxos: utxos[..5].to_vec(),
+            change_output: change_output.clone(),
+            submitted_at: 0,
+            fee_per_vbyte: 1_000,
+        },
+    ];
+
+    let state = replay(events.clone().into_iter()).expect("failed to replay events");
+    assert_eq!(
+        state.available_utxos,
+        utxos[5..].iter().cloned().collect::<BTreeSet<_>>()
+    );
+    assert_eq!(state.submitted_transactions.len(), 1);
+    let submitted_tx = &state.submitted_transactions[0];
+    assert!(submitted_tx.is_consolidation());
+    assert_eq!(submitted_tx.txid, txid);
+    assert_eq!(submitted_tx.used_utxos, utxos[..5].to_vec());
+    assert_eq!(submitted_tx.change_output, Some(change_output));
+    assert_eq!(submitted_tx.fee_per_vbyte, Some(1_000));
+    assert_eq!(state.utxo_consolidation_fees, 0);
+
+    let state = replay(
+        events
+            .into_iter()
+            .chain(std::iter::once(Event::ConfirmedBtcTransaction { txid })),
+    )
+    .expect("failed to replay events");
+    assert!(state.submitted_transactions.is_empty());
+    assert_eq!(state.utxo_consolidation_fees, fee);
+}
+
+#[test]
+fn should_rebuild_stuck_consolidation_transaction() {
+    let main_address = BitcoinAddress::P2wpkhV0([0; 20]);
+    let utxos: Vec<_> = (1..=5).map(|i| dummy_utxo_from_value(i * 10_000)).collect();
+    let (unsigned_tx, change_output) =
+        build_consolidation_transaction(&utxos, main_address.clone(), 1_000)
+            .expect("failed to build transaction");
+    let submitted_tx = SubmittedBtcTransaction {
+        requests: vec![],
+        txid: unsigned_tx.txid(),
+        used_utxos: utxos.clone(),
+        submitted_at: 0,
+        change_output: Some(change_output.clone()),
+        fee_per_vbyte: Some(1_000),
+    };
+    assert!(submitted_tx.is_consolidation());
+
+    let (replacement_tx, replacement_change_output, used_utxos) =
+        build_replacement_transaction(&submitted_tx, main_address.clone(), 2_000)
+            .expect("failed to rebuild transaction");
+
+    assert_eq!(used_utxos, utxos);
+    assert_eq!(
+        replacement_tx
+            .inputs
+            .iter()
+            .map(|input| input.previous_output.clone())
+            .collect::<Vec<_>>(),
+        utxos.iter().map(|u| u.outpoint.clone()).collect::<Vec<_>>()
+    );
+    assert_eq!(replacement_tx.outputs.len(), 1);
+    assert_eq!(replacement_tx.outputs[0].address, main_address);
+    assert_eq!(
+        replacement_tx.outputs[0].value,
+        replacement_change_output.value
+    );
+    assert_eq!(replacement_change_output.vout, 0);
+    assert!(replacement_change_output.value < change_output.value);
+    assert_ne!(replacement_tx.txid(), unsigned_tx.txid());
+}
+
 #[test]
 fn can_form_a_batch_conditions() {
     let mut state = CkBtcMinterState::from(InitArgs {
@@ -1133,6 +1369,7 @@ fn can_form_a_batch_conditions() {
         mode: Mode::GeneralAvailability,
         kyt_fee: None,
         kyt_principal: None,
+        utxo_consolidation_fee_threshold: None,
     });
     // no request, can't form a batch, fail.
     assert!(!state.can_form_a_batch(1, 0));
@@ -111,6 +111,7 @@ fn install_minter(env: &StateMachine, ledger_id: CanisterId) -> CanisterId {
         mode: Mode::GeneralAvailability,
         kyt_fee: None,
         kyt_principal: Some(CanisterId::from(0)),
+        utxo_consolidation_fee_threshold: None,
     };
     let minter_arg = MinterArg::Init(args);
     env.install_canister(minter_wasm(), Encode!(&minter_arg).unwrap(), None)
@@ -176,6 +177,7 @@ fn test_wrong_upgrade_parameter() {
         mode: Mode::GeneralAvailability,
         kyt_fee: Some(1001),
         kyt_principal: None,
+        utxo_consolidation_fee_threshold: None,
     });
     let args = Encode!(&args).unwrap();
     if env.install_canister(minter_wasm(), args, None).is_ok() {
@@ -191,6 +193,7 @@ fn test_wrong_upgrade_parameter() {
         mode: Mode::GeneralAvailability,
         kyt_fee: Some(1001),
         kyt_principal: None,
+        utxo_consolidation_fee_threshold: None,
     });
     let args = Encode!(&args).unwrap();
     if env.install_canister(minter_wasm(), args, None).is_ok() {
@@ -210,6 +213,7 @@ fn test_wrong_upgrade_parameter() {
         mode: Some(Mode::ReadOnly),
         kyt_principal: None,
         kyt_fee: None,
+        utxo_consolidation_fee_threshold: None,
     };
     let minter_arg = MinterArg::Upgrade(Some(upgrade_args));
     if env
@@ -238,6 +242,7 @@ fn test_upgrade_read_only() {
         mode: Some(Mode::ReadOnly),
         kyt_principal: Some(CanisterId::from(0)),
         kyt_fee: None,
+        utxo_consolidation_fee_threshold: None,
     };
     let minter_arg = MinterArg::Upgrade(Some(upgrade_args));
     env.upgrade_canister(minter_id, minter_wasm(), Encode!(&minter_arg).unwrap())
@@ -308,6 +313,7 @@ fn test_upgrade_restricted() {
         mode: Some(Mode::RestrictedTo(vec![authorized_principal])),
         kyt_fee: None,
         kyt_principal: Some(CanisterId::from(0)),
+        utxo_consolidation_fee_threshold: None,
     };
     let minter_arg = MinterArg::Upgrade(Some(upgrade_args));
     env.upgrade_canister(minter_id, minter_wasm(), Encode!(&minter_arg).unwrap())
@@ -363,6 +369,7 @@ fn test_upgrade_restricted() {
         mode: Some(Mode::DepositsRestrictedTo(vec![authorized_principal])),
         kyt_principal: Some(CanisterId::from(0)),
         kyt_fee: None,
+        utxo_consolidation_fee_threshold: None,
     };
     env.upgrade_canister(minter_id, minter_wasm(), Encode!(&upgrade_args).unwrap())
         .expect("Failed to upgrade the minter canister");
@@ -448,6 +455,7 @@ fn update_balance_should_return_correct_confirmations() {
         mode: None,
         kyt_principal: None,
         kyt_fee: None,
+        utxo_consolidation_fee_threshold: None,
     };
     let minter_arg = MinterArg::Upgrade(Some(upgrade_args));
     ckbtc
@@ -566,6 +574,7 @@ fn test_minter() {
         mode: Mode::GeneralAvailability,
         kyt_fee: Some(1001),
         kyt_principal: Some(CanisterId::from(0)),
+        utxo_consolidation_fee_threshold: None,
     });
     let args = Encode!(&args).unwrap();
     let minter_id = env.install_canister(minter_wasm(), args, None).unwrap();
@@ -660,6 +669,7 @@ impl CkBtcSetup {
                 mode: Mode::GeneralAvailability,
                 kyt_fee: Some(KYT_FEE),
                 kyt_principal: kyt_id.into(),
+                utxo_consolidation_fee_threshold: None,
             }))
             .unwrap(),
         )
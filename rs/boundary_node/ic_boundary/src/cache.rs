@@ -1,23 +1,41 @@
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use anyhow::{anyhow, Error};
+use anyhow::{anyhow, Context, Error};
 use axum::{
     body::Body,
-    extract::State,
+    extract::{ConnectInfo, Query, State},
     http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Extension,
 };
-use http::header::{HeaderMap, CACHE_CONTROL, CONTENT_LENGTH};
+use candid::Principal;
+use http::header::{HeaderMap, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH};
 use http::{response, Version};
-use moka::future::{Cache as MokaCache, CacheBuilder as MokaCacheBuilder};
+use ic_types::CanisterId;
+use moka::{
+    future::{Cache as MokaCache, CacheBuilder as MokaCacheBuilder},
+    Expiry,
+};
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use serde::Deserialize;
+use tracing::info;
 
 use crate::{
     http::{read_streaming_body, AxumResponse},
-    routes::{ApiError, ErrorCause, RequestContext},
+    routes::{ApiError, ErrorCause, RequestContext, RequestType},
 };
 
+// Label of the canisters without a cache policy in the metrics, keeps the cardinality bounded
+const METRICS_LABEL_OTHER: &str = "other";
+
 // A list of possible Cache-Control directives that ask us not to cache the response
 const SKIP_CACHE_DIRECTIVES: &[&str] = &["no-store", "no-cache", "max-age=0"];
 
@@ -30,6 +48,8 @@ pub enum CacheBypassReason {
     SizeUnknown,
     TooBig,
     HTTPError,
+    Policy,
+    RequestStatus,
 }
 
 impl fmt::Display for CacheBypassReason {
@@ -41,6 +61,8 @@ impl fmt::Display for CacheBypassReason {
             Self::SizeUnknown => write!(f, "size_unknown"),
             Self::TooBig => write!(f, "too_big"),
             Self::HTTPError => write!(f, "http_error"),
+            Self::Policy => write!(f, "policy"),
+            Self::RequestStatus => write!(f, "request_status"),
         }
     }
 }
@@ -73,30 +95,84 @@ impl fmt::Display for CacheStatus {
     }
 }
 
+// Caching policy for a specific canister in the form of <canister_id>:<ttl_seconds>
+// TTL of zero disables the caching for the canister
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachePolicy {
+    pub canister_id: Principal,
+    pub ttl: Duration,
+}
+
+impl FromStr for CachePolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (canister_id, ttl) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("cache policy should be in <canister_id>:<ttl_seconds> form"))?;
+
+        Ok(Self {
+            canister_id: Principal::from_text(canister_id)
+                .context("unable to parse canister id")?,
+            ttl: Duration::from_secs(ttl.parse::<u64>().context("unable to parse ttl")?),
+        })
+    }
+}
+
+// Cached responses are keyed by the target canister and the request contents
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    canister_id: Principal,
+    ctx: Arc<RequestContext>,
+}
+
 #[derive(Clone)]
 struct CacheItem {
     status: StatusCode,
     version: Version,
     headers: HeaderMap,
     body: Vec<u8>,
+    ttl: Duration,
+}
+
+// Expires each entry according to the TTL it was stored with
+struct CacheExpiry;
+
+impl Expiry<CacheKey, CacheItem> for CacheExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &CacheKey,
+        value: &CacheItem,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(value.ttl)
+    }
 }
 
 #[derive(Clone)]
 pub struct Cache {
-    cache: MokaCache<Arc<RequestContext>, CacheItem>,
+    cache: MokaCache<CacheKey, CacheItem>,
     max_item_size: u64,
+    ttl: Duration,
     cache_non_anonymous: bool,
+    policies: HashMap<Principal, Duration>,
+    counter: IntCounterVec,
 }
 
 // Estimate rough amount of bytes that cache entry takes in memory
-fn weigh_entry(k: &Arc<RequestContext>, v: &CacheItem) -> u32 {
+fn weigh_entry(k: &CacheKey, v: &CacheItem) -> u32 {
+    let k = &k.ctx;
+
     let mut cost = v.body.capacity()
         + std::mem::size_of::<CacheItem>()
-        + std::mem::size_of::<Arc<RequestContext>>()
+        + std::mem::size_of::<CacheKey>()
         + k.method_name.as_ref().map(|x| x.len()).unwrap_or(0)
         + k.arg.as_ref().map(|x| x.len()).unwrap_or(0)
         + k.nonce.as_ref().map(|x| x.len()).unwrap_or(0)
-        + 58; // 2 x Principal
+        + k.paths
+            .as_ref()
+            .map_or(0, |x| x.iter().flatten().map(|x| x.len()).sum())
+        + 87; // 3 x Principal
 
     for (k, v) in v.headers.iter() {
         cost += k.as_str().as_bytes().len();
@@ -115,6 +191,8 @@ impl Cache {
         max_item_size: u64,
         ttl: Duration,
         cache_non_anonymous: bool,
+        policies: Vec<CachePolicy>,
+        registry: &Registry,
     ) -> Result<Self, Error> {
         if max_item_size >= cache_size {
             return Err(anyhow!(
@@ -123,20 +201,48 @@ impl Cache {
         }
 
         let cache = MokaCacheBuilder::new(cache_size)
-            .time_to_live(ttl)
+            .expire_after(CacheExpiry)
             .weigher(weigh_entry)
+            .support_invalidation_closures()
             .build();
 
+        let counter = register_int_counter_vec_with_registry!(
+            "cache_requests_total",
+            "Counts cache lookups per canister with a cache policy (others are counted as 'other') by their result",
+            &["canister_id", "cache_status"],
+            registry
+        )?;
+
         Ok(Self {
             cache,
             max_item_size,
+            ttl,
             cache_non_anonymous,
+            policies: policies
+                .into_iter()
+                .map(|x| (x.canister_id, x.ttl))
+                .collect(),
+            counter,
         })
     }
 
+    // Returns the TTL for the given canister's responses
+    fn ttl(&self, canister_id: &Principal) -> Duration {
+        self.policies.get(canister_id).copied().unwrap_or(self.ttl)
+    }
+
+    // Only the canisters with a configured policy get their own metrics label
+    fn metrics_label(&self, canister_id: &Principal) -> String {
+        if self.policies.contains_key(canister_id) {
+            canister_id.to_string()
+        } else {
+            METRICS_LABEL_OTHER.to_string()
+        }
+    }
+
     // Stores the response components in the cache
     // Response itself cannot be stored since it's not cloneable, so we have to rebuild it
-    async fn store(&self, ctx: Arc<RequestContext>, parts: &response::Parts, body: &[u8]) {
+    async fn store(&self, key: CacheKey, parts: &response::Parts, body: &[u8]) {
         // Make sure that the vector has the smallest possible memory footprint
         let mut body = body.to_vec();
         body.shrink_to_fit();
@@ -146,15 +252,16 @@ impl Cache {
             version: parts.version,
             headers: parts.headers.clone(),
             body,
+            ttl: self.ttl(&key.canister_id),
         };
 
         // Insert the response into the cache & wait for it to persist there
-        self.cache.insert(ctx, item).await;
+        self.cache.insert(key, item).await;
     }
 
     // Looks up the request in the cache
-    async fn lookup(&self, ctx: &RequestContext) -> Option<AxumResponse> {
-        let item = match self.cache.get(ctx).await {
+    async fn lookup(&self, key: &CacheKey) -> Option<AxumResponse> {
+        let item = match self.cache.get(key).await {
             Some(v) => v,
             None => return None,
         };
@@ -185,6 +292,22 @@ impl Cache {
         self.cache.run_pending_tasks().await;
     }
 
+    // Removes the entries of the given canister, or all of them
+    pub async fn invalidate(&self, canister_id: Option<Principal>) -> Result<(), Error> {
+        match canister_id {
+            Some(id) => {
+                self.cache
+                    .invalidate_entries_if(move |k, _| k.canister_id == id)
+                    .map_err(|e| anyhow!("unable to invalidate entries: {e}"))?;
+            }
+
+            None => self.cache.invalidate_all(),
+        }
+
+        self.housekeep().await;
+        Ok(())
+    }
+
     // For now stuff below is used only in tests, but belongs here
     #[allow(dead_code)]
     async fn clear(&self) {
@@ -207,9 +330,31 @@ fn extract_content_length(resp: &Response) -> Result<Option<u64>, Error> {
 pub async fn cache_middleware(
     State(cache): State<Arc<Cache>>,
     Extension(ctx): Extension<Arc<RequestContext>>,
+    Extension(canister_id): Extension<CanisterId>,
     request: Request<Body>,
     next: Next<Body>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let canister_id = canister_id.get().0;
+    let (status, response) = cache_request(&cache, ctx, canister_id, request, next).await?;
+
+    cache
+        .counter
+        .with_label_values(&[
+            cache.metrics_label(&canister_id).as_str(),
+            status.to_string().as_str(),
+        ])
+        .inc();
+
+    Ok(status.with_response(response))
+}
+
+async fn cache_request(
+    cache: &Cache,
+    ctx: Arc<RequestContext>,
+    canister_id: Principal,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<(CacheStatus, AxumResponse), ApiError> {
     let bypass_reason = (|| {
         // Skip cache if there's a nonce
         if ctx.nonce.is_some() {
@@ -221,6 +366,16 @@ pub async fn cache_middleware(
             return Some(CacheBypassReason::NonAnonymous);
         }
 
+        // Skip canisters that have caching disabled
+        if cache.ttl(&canister_id).is_zero() {
+            return Some(CacheBypassReason::Policy);
+        }
+
+        // Request statuses change quickly, caching them would delay the clients
+        if ctx.request_type == RequestType::ReadState && ctx.is_request_status() {
+            return Some(CacheBypassReason::RequestStatus);
+        }
+
         // Check if we have a Cache-Control header and if it asks us not to use the cache
         if let Some(v) = request.headers().get(CACHE_CONTROL) {
             if let Ok(hdr) = v.to_str() {
@@ -234,12 +389,14 @@ pub async fn cache_middleware(
     })();
 
     if let Some(v) = bypass_reason {
-        return Ok(CacheStatus::Bypass(v).with_response(next.run(request).await));
+        return Ok((CacheStatus::Bypass(v), next.run(request).await));
     }
 
+    let key = CacheKey { canister_id, ctx };
+
     // Try to look up the request in the cache
-    if let Some(v) = cache.lookup(&ctx).await {
-        return Ok(CacheStatus::Hit.with_response(v));
+    if let Some(v) = cache.lookup(&key).await {
+        return Ok((CacheStatus::Hit, v));
     }
 
     // If not found - pass the request down the stack
@@ -247,7 +404,7 @@ pub async fn cache_middleware(
 
     // Do not cache non-2xx responses
     if !response.status().is_success() {
-        return Ok(CacheStatus::Bypass(CacheBypassReason::HTTPError).with_response(response));
+        return Ok((CacheStatus::Bypass(CacheBypassReason::HTTPError), response));
     }
 
     let content_length = extract_content_length(&response).map_err(|_| {
@@ -258,13 +415,16 @@ pub async fn cache_middleware(
     let body_size = match content_length {
         Some(v) => v,
         None => {
-            return Ok(CacheStatus::Bypass(CacheBypassReason::SizeUnknown).with_response(response))
+            return Ok((
+                CacheStatus::Bypass(CacheBypassReason::SizeUnknown),
+                response,
+            ))
         }
     };
 
     // Do not cache items larger than configured
     if body_size > cache.max_item_size {
-        return Ok(CacheStatus::Bypass(CacheBypassReason::TooBig).with_response(response));
+        return Ok((CacheStatus::Bypass(CacheBypassReason::TooBig), response));
     }
 
     // Buffer entire response body to be able to cache it
@@ -272,12 +432,63 @@ pub async fn cache_middleware(
     let body = read_streaming_body(body, body_size as usize).await?;
 
     // Insert the response into the cache
-    cache.store(ctx, &parts, &body).await;
+    cache.store(key, &parts, &body).await;
 
     // Reconstruct the response from components
     let response = Response::from_parts(parts, axum::body::boxed(Body::from(body)));
 
-    Ok(CacheStatus::Miss.with_response(response))
+    Ok((CacheStatus::Miss, response))
+}
+
+#[derive(Deserialize)]
+pub struct InvalidateParams {
+    canister_id: Option<Principal>,
+}
+
+#[derive(Clone)]
+pub struct InvalidateHandlerArgs {
+    pub cache: Arc<Cache>,
+    // If set, must be passed as a bearer token. Otherwise only loopback clients are allowed.
+    pub token: Option<String>,
+}
+
+impl InvalidateHandlerArgs {
+    fn is_authorized(&self, addr: &SocketAddr, headers: &HeaderMap) -> bool {
+        match &self.token {
+            Some(token) => {
+                headers
+                    .get(AUTHORIZATION)
+                    .and_then(|x| x.to_str().ok())
+                    .and_then(|x| x.strip_prefix("Bearer "))
+                    == Some(token.as_str())
+            }
+
+            None => addr.ip().to_canonical().is_loopback(),
+        }
+    }
+}
+
+// Axum handler that drops the cached responses of a given canister, or all of them if none is given
+pub async fn invalidate_handler(
+    State(args): State<InvalidateHandlerArgs>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(params): Query<InvalidateParams>,
+) -> impl IntoResponse {
+    if !args.is_authorized(&addr, &headers) {
+        return (StatusCode::FORBIDDEN, "Forbidden\n".into());
+    }
+
+    if let Err(e) = args.cache.invalidate(params.canister_id).await {
+        return (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}\n"));
+    }
+
+    info!(
+        action = "cache_invalidate",
+        canister_id = params.canister_id.map(|x| x.to_string()),
+    );
+
+    (StatusCode::OK, "OK\n".into())
 }
 
 #[cfg(test)]
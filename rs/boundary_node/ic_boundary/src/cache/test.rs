@@ -1,13 +1,14 @@
 use super::*;
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
-    body::Body, http::Request, middleware, response::IntoResponse, routing::method_routing::post,
-    Extension, Router,
+    body::Body, extract::ConnectInfo, http::Request, middleware, response::IntoResponse,
+    routing::method_routing::post, Extension, Router,
 };
 use candid::Principal;
 use http::header::HeaderValue;
+use prometheus::Registry;
 use tower::Service;
 
 use crate::routes::ANONYMOUS_PRINCIPAL;
@@ -48,12 +49,82 @@ fn gen_request_with_params(
     let ctx = Arc::new(ctx);
 
     req.extensions_mut().insert(ctx);
+    req.extensions_mut()
+        .insert(CanisterId::from_str(canister_id).unwrap());
     req.extensions_mut().insert(size);
     req.extensions_mut().insert(status_code);
 
     req
 }
 
+fn gen_read_state_request(canister_id: &str, paths: Vec<Vec<&[u8]>>) -> Request<Body> {
+    let mut req = Request::post("/").body(Body::from("foobar")).unwrap();
+
+    let ctx = RequestContext {
+        request_type: RequestType::ReadState,
+        sender: Some(ANONYMOUS_PRINCIPAL),
+        ingress_expiry: Some(0),
+        paths: Some(
+            paths
+                .into_iter()
+                .map(|x| x.into_iter().map(|x| x.to_vec()).collect())
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    req.extensions_mut().insert(Arc::new(ctx));
+    req.extensions_mut()
+        .insert(CanisterId::from_str(canister_id).unwrap());
+    req.extensions_mut().insert(DEFAULT_SIZE);
+    req.extensions_mut().insert(StatusCode::OK);
+
+    req
+}
+
+fn new_cache(ttl: Duration, policies: Vec<CachePolicy>) -> Result<Arc<Cache>, Error> {
+    Ok(Arc::new(Cache::new(
+        MAX_MEM_SIZE,
+        MAX_RESP_SIZE,
+        ttl,
+        false,
+        policies,
+        &Registry::new(),
+    )?))
+}
+
+fn app(cache: Arc<Cache>) -> Router {
+    Router::new()
+        .route("/", post(handler))
+        .layer(middleware::from_fn_with_state(cache, cache_middleware))
+}
+
+async fn cache_status(app: &mut Router, req: Request<Body>) -> CacheStatus {
+    let res = app.call(req).await.unwrap();
+    res.extensions().get::<CacheStatus>().cloned().unwrap()
+}
+
+fn gen_invalidate_request(uri: &str, addr: [u8; 4], token: Option<&str>) -> Request<Body> {
+    let mut req = Request::post(uri);
+    if let Some(v) = token {
+        req = req.header(AUTHORIZATION, format!("Bearer {v}"));
+    }
+
+    let mut req = req.body(Body::empty()).unwrap();
+    req.extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from((addr, 1337))));
+    req
+}
+
+fn invalidate_app(cache: Arc<Cache>, token: Option<&str>) -> Router {
+    Router::new()
+        .route("/cache/invalidate", post(invalidate_handler))
+        .with_state(InvalidateHandlerArgs {
+            cache,
+            token: token.map(|x| x.to_string()),
+        })
+}
+
 fn gen_request(canister_id: &str, nonce: bool) -> Request<Body> {
     gen_request_with_params(canister_id, nonce, DEFAULT_SIZE, 0, true, StatusCode::OK)
 }
@@ -69,22 +140,18 @@ async fn handler(
 #[tokio::test]
 async fn test_cache() -> Result<(), Error> {
     // Check that we fail if item size >= max size
-    assert!(Cache::new(1024, 1024, Duration::from_secs(60), false).is_err());
-
-    let cache = Cache::new(
-        MAX_MEM_SIZE,
-        MAX_RESP_SIZE,
-        Duration::from_secs(3600),
+    assert!(Cache::new(
+        1024,
+        1024,
+        Duration::from_secs(60),
         false,
-    )?;
-    let cache = Arc::new(cache);
+        vec![],
+        &Registry::new()
+    )
+    .is_err());
 
-    let mut app = Router::new()
-        .route("/", post(handler))
-        .layer(middleware::from_fn_with_state(
-            Arc::clone(&cache),
-            cache_middleware,
-        ));
+    let cache = new_cache(Duration::from_secs(3600), vec![])?;
+    let mut app = app(Arc::clone(&cache));
 
     // Check non-anonymous
     let req = gen_request_with_params(CANISTER_1, false, DEFAULT_SIZE, 0, false, StatusCode::OK);
@@ -202,3 +269,261 @@ async fn test_cache() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_cache_policy_parse() {
+    assert_eq!(
+        CachePolicy::from_str(&format!("{CANISTER_1}:10")).unwrap(),
+        CachePolicy {
+            canister_id: Principal::from_text(CANISTER_1).unwrap(),
+            ttl: Duration::from_secs(10),
+        }
+    );
+
+    assert_eq!(
+        CachePolicy::from_str(&format!("{CANISTER_1}:0"))
+            .unwrap()
+            .ttl,
+        Duration::ZERO
+    );
+
+    assert!(CachePolicy::from_str(CANISTER_1).is_err());
+    assert!(CachePolicy::from_str("foo:10").is_err());
+    assert!(CachePolicy::from_str(&format!("{CANISTER_1}:-1")).is_err());
+    assert!(CachePolicy::from_str(&format!("{CANISTER_1}:1s")).is_err());
+}
+
+#[tokio::test]
+async fn test_cache_policies() -> Result<(), Error> {
+    let cache = new_cache(
+        Duration::from_secs(3600),
+        vec![
+            CachePolicy {
+                canister_id: Principal::from_text(CANISTER_1).unwrap(),
+                ttl: Duration::ZERO,
+            },
+            CachePolicy {
+                canister_id: Principal::from_text(CANISTER_2).unwrap(),
+                ttl: Duration::from_millis(100),
+            },
+        ],
+    )?;
+    let mut app = app(Arc::clone(&cache));
+
+    // Caching is disabled for the 1st canister
+    for _ in 0..2 {
+        assert_eq!(
+            cache_status(&mut app, gen_request(CANISTER_1, false)).await,
+            CacheStatus::Bypass(CacheBypassReason::Policy)
+        );
+    }
+
+    // 2nd canister has a short TTL
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_2, false)).await,
+        CacheStatus::Miss
+    );
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_2, false)).await,
+        CacheStatus::Hit
+    );
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_2, false)).await,
+        CacheStatus::Miss
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_read_state() -> Result<(), Error> {
+    let cache = new_cache(Duration::from_secs(3600), vec![])?;
+    let mut app = app(Arc::clone(&cache));
+
+    let path_1: Vec<&[u8]> = vec![b"canister", b"foo", b"module_hash"];
+    let path_2: Vec<&[u8]> = vec![b"time"];
+
+    // Responses are keyed on the paths
+    assert_eq!(
+        cache_status(
+            &mut app,
+            gen_read_state_request(CANISTER_1, vec![path_1.clone()])
+        )
+        .await,
+        CacheStatus::Miss
+    );
+    assert_eq!(
+        cache_status(
+            &mut app,
+            gen_read_state_request(CANISTER_1, vec![path_1.clone()])
+        )
+        .await,
+        CacheStatus::Hit
+    );
+    assert_eq!(
+        cache_status(
+            &mut app,
+            gen_read_state_request(CANISTER_1, vec![path_1.clone(), path_2.clone()])
+        )
+        .await,
+        CacheStatus::Miss
+    );
+
+    // And on the canister
+    assert_eq!(
+        cache_status(
+            &mut app,
+            gen_read_state_request(CANISTER_2, vec![path_1.clone()])
+        )
+        .await,
+        CacheStatus::Miss
+    );
+
+    // Request status is never cached
+    let path_status: Vec<&[u8]> = vec![b"request_status", b"foo", b"status"];
+    for _ in 0..2 {
+        assert_eq!(
+            cache_status(
+                &mut app,
+                gen_read_state_request(CANISTER_1, vec![path_2.clone(), path_status.clone()])
+            )
+            .await,
+            CacheStatus::Bypass(CacheBypassReason::RequestStatus)
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_invalidate() -> Result<(), Error> {
+    let cache = new_cache(Duration::from_secs(3600), vec![])?;
+    let mut app = app(Arc::clone(&cache));
+
+    for c in [CANISTER_1, CANISTER_2] {
+        assert_eq!(
+            cache_status(&mut app, gen_request(c, false)).await,
+            CacheStatus::Miss
+        );
+    }
+
+    // Invalidate only the 1st canister
+    let mut admin = invalidate_app(Arc::clone(&cache), None);
+
+    let req = gen_invalidate_request(
+        &format!("/cache/invalidate?canister_id={CANISTER_1}"),
+        [127, 0, 0, 1],
+        None,
+    );
+    let res = admin.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_1, false)).await,
+        CacheStatus::Miss
+    );
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_2, false)).await,
+        CacheStatus::Hit
+    );
+
+    // Invalidate everything
+    let req = gen_invalidate_request("/cache/invalidate", [127, 0, 0, 1], None);
+    let res = admin.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(cache.len(), 0);
+
+    for c in [CANISTER_1, CANISTER_2] {
+        assert_eq!(
+            cache_status(&mut app, gen_request(c, false)).await,
+            CacheStatus::Miss
+        );
+    }
+
+    // Bad canister id
+    let req = gen_invalidate_request("/cache/invalidate?canister_id=foo", [127, 0, 0, 1], None);
+    let res = admin.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_invalidate_authorization() -> Result<(), Error> {
+    let cache = new_cache(Duration::from_secs(3600), vec![])?;
+    let mut app = app(Arc::clone(&cache));
+
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_1, false)).await,
+        CacheStatus::Miss
+    );
+
+    // Without a token only loopback clients are allowed
+    let mut admin = invalidate_app(Arc::clone(&cache), None);
+    let req = gen_invalidate_request("/cache/invalidate", [10, 0, 0, 1], None);
+    let res = admin.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_1, false)).await,
+        CacheStatus::Hit
+    );
+
+    // With a token it has to be passed, regardless of the client address
+    let mut admin = invalidate_app(Arc::clone(&cache), Some("secret"));
+    for (addr, token) in [
+        ([127, 0, 0, 1], None),
+        ([10, 0, 0, 1], None),
+        ([10, 0, 0, 1], Some("foobar")),
+    ] {
+        let req = gen_invalidate_request("/cache/invalidate", addr, token);
+        let res = admin.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_1, false)).await,
+        CacheStatus::Hit
+    );
+
+    let req = gen_invalidate_request("/cache/invalidate", [10, 0, 0, 1], Some("secret"));
+    let res = admin.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        cache_status(&mut app, gen_request(CANISTER_1, false)).await,
+        CacheStatus::Miss
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_metrics() -> Result<(), Error> {
+    let cache = new_cache(
+        Duration::from_secs(3600),
+        vec![CachePolicy::from_str(&format!("{CANISTER_1}:3600"))?],
+    )?;
+    let mut app = app(Arc::clone(&cache));
+
+    for _ in 0..3 {
+        cache_status(&mut app, gen_request(CANISTER_1, false)).await;
+    }
+    cache_status(&mut app, gen_request(CANISTER_2, true)).await;
+
+    let count = |canister_id: &str, status: &str| {
+        cache
+            .counter
+            .with_label_values(&[canister_id, status])
+            .get()
+    };
+
+    assert_eq!(count(CANISTER_1, "MISS"), 1);
+    assert_eq!(count(CANISTER_1, "HIT"), 2);
+    // Canisters without a policy are not labeled individually
+    assert_eq!(count("other", "BYPASS"), 1);
+    assert_eq!(count("other", "HIT"), 0);
+    assert_eq!(count(CANISTER_2, "BYPASS"), 0);
+
+    Ok(())
+}
@@ -23,15 +23,43 @@ use crate::{
     core::{Run, WithRetryLimited},
     http::HttpClient,
     metrics::{MetricParamsCheck, WithMetricsCheck},
-    persist::Persist,
+    persist::{NodeWeights, Persist},
     snapshot::RegistrySnapshot,
     snapshot::{Node, Subnet},
 };
 
+// Smoothing factor for the per-node moving averages.
+// Higher values make the averages react faster to the recent checks.
+const EWMA_ALPHA: f64 = 0.2;
+
+// Latency floor used when calculating node weights.
+// Prevents nodes with a near-zero latency from getting all the traffic.
+const MIN_WEIGHT_LATENCY: f64 = 0.005;
+
+// Minimal share of the weight that a node keeps regardless of its error rate
+const MIN_WEIGHT_SUCCESS_RATE: f64 = 0.05;
+
+fn ewma(avg: f64, sample: f64) -> f64 {
+    EWMA_ALPHA * sample + (1.0 - EWMA_ALPHA) * avg
+}
+
 struct NodeState {
     ok_count: u8,
     last_check_id: Wrapping<u64>,
     replica_version: String,
+    // Exponentially weighted moving average of the check latency, in seconds
+    avg_latency_secs: f64,
+    // Exponentially weighted moving average of the check failures, in 0..1 range
+    avg_error_rate: f64,
+}
+
+impl NodeState {
+    // Routing weight of the node: inversely proportional to its latency
+    // and proportional to its success rate
+    fn weight(&self) -> f64 {
+        let success_rate = (1.0 - self.avg_error_rate).max(MIN_WEIGHT_SUCCESS_RATE);
+        success_rate / self.avg_latency_secs.max(MIN_WEIGHT_LATENCY)
+    }
 }
 
 struct NodeCheckResult {
@@ -121,6 +149,7 @@ impl<P: Persist, C: Check> Runner<P, C> {
                 if let Some(mut x) = node_state {
                     x.ok_count = 0;
                     x.last_check_id = self.last_check_id;
+                    x.avg_error_rate = ewma(x.avg_error_rate, 1.0);
                 }
 
                 return Err(e);
@@ -146,6 +175,7 @@ impl<P: Persist, C: Check> Runner<P, C> {
         };
 
         let height = check_result.height;
+        let latency = check_result.latency.as_secs_f64();
 
         // Insert or update the entry
         match node_state {
@@ -156,6 +186,8 @@ impl<P: Persist, C: Check> Runner<P, C> {
                         ok_count,
                         last_check_id: self.last_check_id,
                         replica_version: check_result.replica_version,
+                        avg_latency_secs: latency,
+                        avg_error_rate: 0.0,
                     },
                 );
             }
@@ -164,6 +196,8 @@ impl<P: Persist, C: Check> Runner<P, C> {
                 e.ok_count = ok_count;
                 e.last_check_id = self.last_check_id;
                 e.replica_version = check_result.replica_version;
+                e.avg_latency_secs = ewma(e.avg_latency_secs, latency);
+                e.avg_error_rate = ewma(e.avg_error_rate, 0.0);
             }
         };
 
@@ -247,8 +281,15 @@ impl<P: Persist, C: Check> Run for Runner<P, C> {
         self.node_states
             .retain(|_, x| x.last_check_id == self.last_check_id);
 
+        // Calculate the routing weights of the nodes based on their health statistics
+        let weights = self
+            .node_states
+            .iter()
+            .map(|x| (*x.key(), x.weight()))
+            .collect::<NodeWeights>();
+
         // Persist the routing table
-        self.persist.persist(subnets);
+        self.persist.persist(subnets, &weights);
 
         Ok(())
     }
@@ -32,6 +32,19 @@ impl Routes {
 
         false
     }
+
+    // Get the routing weight of a given node, if it exists in the lookup table
+    pub fn node_weight(&self, node_id: Principal) -> Option<f64> {
+        for s in self.subnets.iter() {
+            for (n, w) in s.nodes.iter().zip(s.weights.iter()) {
+                if n.id == node_id {
+                    return Some(*w);
+                }
+            }
+        }
+
+        None
+    }
 }
 
 pub fn generate_custom_registry_snapshot(
@@ -321,3 +334,98 @@ async fn test_check_node_upgrade() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_node_state_weight() {
+    let state = |avg_latency_secs, avg_error_rate| NodeState {
+        ok_count: 1,
+        last_check_id: Wrapping(0),
+        replica_version: "ver1".into(),
+        avg_latency_secs,
+        avg_error_rate,
+    };
+
+    // Faster nodes get higher weight
+    assert!(state(0.01, 0.0).weight() > state(0.1, 0.0).weight());
+    // Nodes with more errors get lower weight
+    assert!(state(0.1, 0.0).weight() > state(0.1, 0.5).weight());
+    // Latency is clamped so that a near-zero latency doesn't produce infinite weight
+    assert_eq!(
+        state(0.0, 0.0).weight(),
+        state(MIN_WEIGHT_LATENCY, 0.0).weight()
+    );
+    // Nodes that always fail still keep some weight
+    assert!(state(0.1, 1.0).weight() > 0.0);
+
+    // Moving average converges towards the samples
+    let mut avg = 1.0;
+    for _ in 0..100 {
+        avg = ewma(avg, 0.0);
+    }
+    assert!(avg < 0.001);
+}
+
+// Ensure that the routing weights reflect the latency and error rate of the nodes
+#[tokio::test(flavor = "multi_thread")]
+async fn test_check_weights() -> Result<(), Error> {
+    let routes = Arc::new(ArcSwapOption::empty());
+    let persist = Persister::new(Arc::clone(&routes));
+    let routing_table = Arc::new(ArcSwapOption::from_pointee(
+        generate_custom_registry_snapshot(1, 3, 0),
+    ));
+
+    let mut check = MockCheck::new();
+    let mut seq = Sequence::new();
+
+    check
+        .expect_check()
+        .withf(|x: &Node| x.id == node_id(0))
+        .times(3)
+        .returning(|_| Ok(check_result(1000, 10)));
+
+    check
+        .expect_check()
+        .withf(|x: &Node| x.id == node_id(1))
+        .times(3)
+        .returning(|_| Ok(check_result(1000, 200)));
+
+    // Node 2 is as fast as node 0, but fails in the 2nd round
+    check
+        .expect_check()
+        .withf(|x: &Node| x.id == node_id(2))
+        .times(1)
+        .returning(|_| Ok(check_result(1000, 10)))
+        .in_sequence(&mut seq);
+
+    check
+        .expect_check()
+        .withf(|x: &Node| x.id == node_id(2))
+        .times(1)
+        .returning(|_| Err(CheckError::Health))
+        .in_sequence(&mut seq);
+
+    check
+        .expect_check()
+        .withf(|x: &Node| x.id == node_id(2))
+        .times(1)
+        .returning(|_| Ok(check_result(1000, 10)))
+        .in_sequence(&mut seq);
+
+    let mut check_runner = Runner::new(Arc::clone(&routing_table), 1, 10, persist, check);
+
+    for _ in 0..3 {
+        check_runner.run().await.expect("run should succeed");
+    }
+
+    let rt = routes.load_full().unwrap();
+    let w0 = rt.node_weight(node_id(0)).unwrap();
+    let w1 = rt.node_weight(node_id(1)).unwrap();
+    let w2 = rt.node_weight(node_id(2)).unwrap();
+
+    // Slow node should get lower weight than the fast ones
+    assert!(w0 > w1);
+    // Recently failed node should get lower weight than the healthy one with the same latency
+    assert!(w0 > w2);
+
+    Ok(())
+}
@@ -3,7 +3,10 @@ use std::{net::SocketAddr, path::PathBuf};
 use clap::{Args, Parser};
 use url::Url;
 
-use crate::core::{AUTHOR_NAME, SERVICE_NAME};
+use crate::{
+    cache::CachePolicy,
+    core::{AUTHOR_NAME, SERVICE_NAME},
+};
 
 #[derive(Parser)]
 #[clap(name = SERVICE_NAME)]
@@ -207,6 +210,10 @@ pub struct RateLimitingConfig {
     /// Allowed number of ledger transfer calls per second
     #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
     pub rate_limit_ledger_transfer: Option<u32>,
+    /// Path to a YAML file with rate limiting rules matching on canister id, subnet id, caller principal, method name and request type.
+    /// The file is re-read periodically and the rules are applied without a restart.
+    #[clap(long)]
+    pub rate_limit_generic_file: Option<PathBuf>,
 }
 
 #[derive(Args)]
@@ -223,6 +230,17 @@ pub struct CacheConfig {
     /// Whether to cache non-anonymous requests
     #[clap(long, default_value = "false")]
     pub cache_non_anonymous: bool,
+    /// Whether to cache read_state responses. Requests for the status of other requests are never cached.
+    #[clap(long, default_value = "false")]
+    pub cache_read_state: bool,
+    /// Per-canister cache policies in the form of <canister_id>:<ttl_seconds>, comma separated.
+    /// Overrides the default TTL for the given canisters, TTL of 0 disables caching for the canister.
+    #[clap(long, value_delimiter = ',')]
+    pub cache_canister_policy: Vec<CachePolicy>,
+    /// Token that has to be passed as `Authorization: Bearer <token>` to invalidate the cache.
+    /// If not set, the cache can be invalidated only from the loopback interface.
+    #[clap(long)]
+    pub cache_invalidate_token: Option<String>,
 }
 
 #[derive(Args)]
@@ -36,7 +36,7 @@ use tower_http::{compression::CompressionLayer, request_id::MakeRequestUuid, Ser
 use tracing::{info, warn};
 
 use crate::{
-    cache::{cache_middleware, Cache},
+    cache::{cache_middleware, invalidate_handler, Cache, InvalidateHandlerArgs},
     check::{Checker, Runner as CheckRunner},
     cli::Cli,
     dns::DnsResolver,
@@ -49,7 +49,10 @@ use crate::{
         WithMetricsCheck, WithMetricsPersist, WithMetricsSnapshot,
     },
     persist::{Persister, Routes},
-    rate_limiting::RateLimit,
+    rate_limiting::{
+        generic::{self, GenericLimiter, RulesLoader},
+        RateLimit,
+    },
     retry::{retry_request, RetryParams},
     routes::{self, ErrorCause, Health, Lookup, Proxy, ProxyRouter, RootKey},
     snapshot::{RegistrySnapshot, SnapshotPersister, Snapshotter},
@@ -165,11 +168,20 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
                 cli.cache.cache_max_item_size_bytes,
                 Duration::from_secs(cli.cache.cache_ttl_seconds),
                 cli.cache.cache_non_anonymous,
+                cli.cache.cache_canister_policy.clone(),
+                &metrics_registry,
             )
             .expect("unable to initialize cache"),
         )
     });
 
+    // Generic rate limiting
+    let generic_limiter = cli
+        .rate_limiting
+        .rate_limit_generic_file
+        .as_ref()
+        .map(|_| Arc::new(GenericLimiter::default()));
+
     // Server / API
     let routers_https = setup_router(
         registry_snapshot.clone(),
@@ -178,6 +190,7 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
         &cli,
         &metrics_registry,
         cache.clone(),
+        generic_limiter.clone(),
     );
 
     #[cfg(feature = "tls")]
@@ -255,6 +268,20 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
             cache: metrics_cache.clone(),
         });
 
+    // Cache invalidation is served on the metrics listener since it's not publicly exposed.
+    // It additionally requires a token, or comes from the loopback interface if none is configured.
+    let metrics_router = match &cache {
+        Some(v) => metrics_router.merge(
+            Router::new()
+                .route("/cache/invalidate", post(invalidate_handler))
+                .with_state(InvalidateHandlerArgs {
+                    cache: v.clone(),
+                    token: cli.cache.cache_invalidate_token.clone(),
+                }),
+        ),
+        None => metrics_router,
+    };
+
     let metrics_runner = WithThrottle(
         WithMetrics(
             MetricsRunner::new(
@@ -323,7 +350,7 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
     );
 
     // Runners
-    let runners: Vec<Box<dyn Run>> = vec![
+    let mut runners: Vec<Box<dyn Run>> = vec![
         #[cfg(feature = "tls")]
         Box::new(configuration_runner),
         Box::new(snapshot_runner),
@@ -331,6 +358,21 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
         Box::new(metrics_runner),
     ];
 
+    // Rate limiting rules reloader
+    if let (Some(path), Some(limiter)) =
+        (&cli.rate_limiting.rate_limit_generic_file, generic_limiter)
+    {
+        let rules_loader = WithThrottle(
+            WithMetrics(
+                RulesLoader::new(path.clone(), limiter),
+                MetricParams::new(&metrics_registry, "run_rate_limit_rules"),
+            ),
+            ThrottleParams::new(10 * SECOND),
+        );
+
+        runners.push(Box::new(rules_loader));
+    }
+
     let (registry_replicator, nns_pub_key) = if !cli.registry.disable_registry_replicator {
         // Check if we require an NNS key
         let nns_pub_key = {
@@ -377,7 +419,7 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
     TokioScope::scope_and_block(|s| {
         s.spawn(
             axum::Server::bind(&cli.monitoring.metrics_addr)
-                .serve(metrics_router.into_make_service())
+                .serve(metrics_router.into_make_service_with_connect_info::<SocketAddr>())
                 .map_err(|err| anyhow!("server failed: {:?}", err)),
         );
 
@@ -432,6 +474,7 @@ pub fn setup_router(
     cli: &Cli,
     metrics_registry: &Registry,
     cache: Option<Arc<Cache>>,
+    generic_limiter: Option<Arc<GenericLimiter>>,
 ) -> Router {
     let proxy_router = ProxyRouter::new(
         http_client.clone(),
@@ -452,9 +495,11 @@ pub fn setup_router(
         .route(routes::PATH_QUERY, {
             post(routes::handle_call).with_state(proxy.clone())
         })
-        .layer(option_layer(cache.map(|x| {
-            middleware::from_fn_with_state(x.clone(), cache_middleware)
-        })));
+        .layer(option_layer(
+            cache
+                .clone()
+                .map(|x| middleware::from_fn_with_state(x, cache_middleware)),
+        ));
 
     let call_route = {
         let mut route = Router::new().route(routes::PATH_CALL, {
@@ -476,9 +521,15 @@ pub fn setup_router(
         route
     };
 
-    let read_state_route = Router::new().route(routes::PATH_READ_STATE, {
-        post(routes::handle_call).with_state(proxy.clone())
-    });
+    let read_state_route = Router::new()
+        .route(routes::PATH_READ_STATE, {
+            post(routes::handle_call).with_state(proxy.clone())
+        })
+        .layer(option_layer(
+            cache
+                .filter(|_| cli.cache.cache_read_state)
+                .map(|x| middleware::from_fn_with_state(x, cache_middleware)),
+        ));
 
     let status_route = Router::new()
         .route(routes::PATH_STATUS, {
@@ -550,6 +601,9 @@ pub fn setup_router(
                 lookup.clone(),
                 routes::lookup_subnet,
             ))
+            .layer(option_layer(generic_limiter.map(|x| {
+                middleware::from_fn_with_state(x, generic::middleware)
+            })))
             .layer(middleware::from_fn_with_state(
                 RetryParams {
                     retry_count: cli.retry.retry_count as usize,
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use arc_swap::ArcSwapOption;
 use async_trait::async_trait;
@@ -13,6 +13,13 @@ use crate::{
     snapshot::{Node, Subnet},
 };
 
+// Weight given to the nodes that have no health statistics
+const DEFAULT_NODE_WEIGHT: f64 = 1.0;
+
+// Routing weights of the nodes, derived from their health check statistics.
+// Nodes with a higher weight are picked proportionally more often.
+pub type NodeWeights = HashMap<Principal, f64>;
+
 #[derive(Copy, Clone)]
 pub struct PersistResults {
     pub ranges_old: u32,
@@ -46,21 +53,35 @@ fn principal_bytes_to_u256(p: &[u8]) -> u256 {
 // This is more efficient than lexographically sorted hexadecimal strings as done in JS router
 // Currently the largest canister_id range is somewhere around 2^40 - so probably using one u128 would work for a long time
 // But going u256 makes it future proof and according to spec
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct RouteSubnet {
     pub id: String,
     pub range_start: u256,
     pub range_end: u256,
     pub nodes: Vec<Arc<Node>>,
+    // Routing weights, one per node in `nodes`
+    pub weights: Vec<f64>,
 }
 
 impl RouteSubnet {
+    // Picks up to `n` distinct nodes, preferring the ones with a higher weight
     pub fn pick_random_nodes(&self, n: usize) -> Result<Vec<Arc<Node>>, ErrorCause> {
-        let nodes = self
-            .nodes
-            .choose_multiple(&mut rand::thread_rng(), n)
-            .cloned()
-            .collect::<Vec<_>>();
+        let mut rng = rand::thread_rng();
+
+        let nodes = match (0..self.nodes.len())
+            .collect::<Vec<_>>()
+            .choose_multiple_weighted(&mut rng, n, |&i| {
+                self.weights.get(i).copied().unwrap_or(DEFAULT_NODE_WEIGHT)
+            }) {
+            Ok(v) => v.map(|&i| self.nodes[i].clone()).collect::<Vec<_>>(),
+
+            // Fall back to the uniform selection if the weights are unusable
+            Err(_) => self
+                .nodes
+                .choose_multiple(&mut rng, n)
+                .cloned()
+                .collect::<Vec<_>>(),
+        };
 
         if nodes.is_empty() {
             return Err(ErrorCause::NoHealthyNodes);
@@ -70,7 +91,7 @@ impl RouteSubnet {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct Routes {
     pub node_count: u32,
     // subnets should be sorted by `range_start` field for the binary search to work
@@ -114,7 +135,7 @@ impl Routes {
 }
 
 pub trait Persist: Send + Sync {
-    fn persist(&self, subnets: Vec<Subnet>) -> PersistStatus;
+    fn persist(&self, subnets: Vec<Subnet>, weights: &NodeWeights) -> PersistStatus;
 }
 
 pub struct Persister {
@@ -130,7 +151,7 @@ impl Persister {
 #[async_trait]
 impl Persist for Persister {
     // Construct a lookup table based on the provided subnet list
-    fn persist(&self, subnets: Vec<Subnet>) -> PersistStatus {
+    fn persist(&self, subnets: Vec<Subnet>, weights: &NodeWeights) -> PersistStatus {
         if subnets.is_empty() {
             return PersistStatus::SkippedEmpty;
         }
@@ -144,6 +165,10 @@ impl Persist for Persister {
             .flat_map(|subnet| {
                 let id = subnet.id.to_string();
                 let nodes = subnet.nodes;
+                let weights = nodes
+                    .iter()
+                    .map(|x| weights.get(&x.id).copied().unwrap_or(DEFAULT_NODE_WEIGHT))
+                    .collect::<Vec<_>>();
 
                 subnet.ranges.into_iter().map(move |range| {
                     Arc::new(RouteSubnet {
@@ -151,6 +176,7 @@ impl Persist for Persister {
                         range_start: principal_bytes_to_u256(range.start.as_slice()),
                         range_end: principal_bytes_to_u256(range.end.as_slice()),
                         nodes: nodes.clone(),
+                        weights: weights.clone(),
                     })
                 })
             })
@@ -185,8 +211,8 @@ impl Persist for Persister {
 
 #[async_trait]
 impl<T: Persist> Persist for WithMetricsPersist<T> {
-    fn persist(&self, subnets: Vec<Subnet>) -> PersistStatus {
-        let out = self.0.persist(subnets);
+    fn persist(&self, subnets: Vec<Subnet>, weights: &NodeWeights) -> PersistStatus {
+        let out = self.0.persist(subnets, weights);
         let MetricParamsPersist { nodes, ranges } = &self.1;
 
         match out {
@@ -1,4 +1,6 @@
-use super::{principal_bytes_to_u256, Persist, PersistStatus, Persister, RouteSubnet, Routes};
+use super::{
+    principal_bytes_to_u256, NodeWeights, Persist, PersistStatus, Persister, RouteSubnet, Routes,
+};
 
 use std::{
     net::{IpAddr, Ipv4Addr},
@@ -151,6 +153,7 @@ fn generate_test_routes(offset: u64) -> Routes {
         range_start: principal_to_u256("f7crg-kabae").unwrap(),
         range_end: principal_to_u256("sxiki-5ygae-aq").unwrap(),
         nodes: vec![node(1 + offset, subnet_id_1)],
+        weights: vec![1.0],
     };
 
     let subnet2 = RouteSubnet {
@@ -158,6 +161,7 @@ fn generate_test_routes(offset: u64) -> Routes {
         range_start: principal_to_u256("sqjm4-qahae-aq").unwrap(),
         range_end: principal_to_u256("sqjm4-qahae-aq").unwrap(),
         nodes: vec![node(2 + offset, subnet_id_2)],
+        weights: vec![1.0],
     };
 
     let subnet3 = RouteSubnet {
@@ -165,6 +169,7 @@ fn generate_test_routes(offset: u64) -> Routes {
         range_start: principal_to_u256("t5his-7iiae-aq").unwrap(),
         range_end: principal_to_u256("jlzvg-byp77-7qcai").unwrap(),
         nodes: vec![node(1 + offset, subnet_id_1)],
+        weights: vec![1.0],
     };
 
     let subnet4 = RouteSubnet {
@@ -172,6 +177,7 @@ fn generate_test_routes(offset: u64) -> Routes {
         range_start: principal_to_u256("zdpgc-saqaa-aacai").unwrap(),
         range_end: principal_to_u256("fij4j-bi777-7qcai").unwrap(),
         nodes: vec![node(3 + offset, subnet_id_3)],
+        weights: vec![1.0],
     };
 
     let subnet5 = RouteSubnet {
@@ -179,6 +185,7 @@ fn generate_test_routes(offset: u64) -> Routes {
         range_start: principal_to_u256("6l3jn-7icca-aaaai-b").unwrap(),
         range_end: principal_to_u256("ca5tg-macd7-776ai-b").unwrap(),
         nodes: vec![node(2 + offset, subnet_id_2)],
+        weights: vec![1.0],
     };
 
     Routes {
@@ -202,21 +209,21 @@ fn test_persist() -> Result<(), Error> {
     let persister = Persister::new(Arc::clone(&rt_init));
 
     // Persist the routing table
-    let result = persister.persist(subnets.clone());
+    let result = persister.persist(subnets.clone(), &NodeWeights::new());
     // Check the result
     assert!(matches!(result, PersistStatus::Completed(_)));
     // Compare the persisted table state with expected
     assert_eq!(&routes, rt_init.load_full().unwrap().as_ref());
 
     // Check empty table
-    let result = persister.persist(vec![]);
+    let result = persister.persist(vec![], &NodeWeights::new());
     assert!(matches!(result, PersistStatus::SkippedEmpty));
     // Check if the table hasn't changed
     assert_eq!(&routes, rt_init.load_full().unwrap().as_ref());
 
     // Generate different table
     let subnets = generate_test_subnets(1);
-    let result = persister.persist(subnets);
+    let result = persister.persist(subnets, &NodeWeights::new());
     // Check if it was updated
     assert!(matches!(result, PersistStatus::Completed(_)));
     // Check if the routing table matches expected one
@@ -226,6 +233,86 @@ fn test_persist() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_persist_weights() -> Result<(), Error> {
+    let subnets = generate_test_subnets(0);
+
+    let rt_init = Arc::new(ArcSwapOption::empty());
+    let persister = Persister::new(Arc::clone(&rt_init));
+
+    // Provide the weight only for the node of the first subnet
+    let weights = NodeWeights::from([(subnets[0].nodes[0].id, 42.0)]);
+    let result = persister.persist(subnets.clone(), &weights);
+    assert!(matches!(result, PersistStatus::Completed(_)));
+
+    let rt = rt_init.load_full().unwrap();
+    for s in rt.subnets.iter() {
+        assert_eq!(s.nodes.len(), s.weights.len());
+
+        // Nodes without the statistics should get the default weight
+        let expected = if s.id == subnets[0].id.to_string() {
+            42.0
+        } else {
+            1.0
+        };
+        assert_eq!(s.weights, vec![expected]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_pick_random_nodes_weighted() -> Result<(), Error> {
+    let subnet_id = Principal::from_text("f7crg-kabae").unwrap();
+
+    let subnet = RouteSubnet {
+        id: subnet_id.to_string(),
+        range_start: principal_to_u256("f7crg-kabae").unwrap(),
+        range_end: principal_to_u256("f7crg-kabae").unwrap(),
+        nodes: vec![node(1, subnet_id), node(2, subnet_id), node(3, subnet_id)],
+        weights: vec![100.0, 1.0, 1.0],
+    };
+
+    // Make sure that the fast node is picked first most of the time
+    let mut fast_count = 0;
+    for _ in 0..1000 {
+        let nodes = subnet.pick_random_nodes(1)?;
+        assert_eq!(nodes.len(), 1);
+        if nodes[0].id == subnet.nodes[0].id {
+            fast_count += 1;
+        }
+    }
+    assert!(fast_count > 900);
+
+    // Make sure that nodes are distinct and we can still get all of them
+    let mut nodes = subnet
+        .pick_random_nodes(5)?
+        .into_iter()
+        .map(|x| x.id)
+        .collect::<Vec<_>>();
+    nodes.sort();
+    let mut expected = subnet.nodes.iter().map(|x| x.id).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(nodes, expected);
+
+    // Check that unusable weights fall back to uniform selection
+    let subnet = RouteSubnet {
+        weights: vec![f64::NAN, -1.0, 1.0],
+        ..subnet
+    };
+    assert_eq!(subnet.pick_random_nodes(3)?.len(), 3);
+
+    // Check that empty subnet yields an error
+    let subnet = RouteSubnet {
+        nodes: vec![],
+        weights: vec![],
+        ..subnet
+    };
+    assert!(subnet.pick_random_nodes(1).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_lookup() -> Result<(), Error> {
     let r = generate_test_routes(0);
@@ -13,6 +13,8 @@ use tower_governor::{
 
 use crate::{persist::RouteSubnet, routes::ApiError};
 
+pub mod generic;
+
 pub struct RateLimit {
     requests_per_second: u32, // requests per second allowed
 }
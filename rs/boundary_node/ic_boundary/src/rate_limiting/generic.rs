@@ -0,0 +1,378 @@
+use std::{
+    fs,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Error};
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    middleware::Next,
+    response::IntoResponse,
+    Extension,
+};
+use candid::Principal;
+use http::Request;
+use ic_types::CanisterId;
+use moka::sync::Cache;
+use ratelimit::Ratelimiter;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use tracing::{info, warn};
+
+use crate::{
+    core::Run,
+    persist::RouteSubnet,
+    routes::{ApiError, ErrorCause, RateLimitCause, RequestContext, RequestType},
+};
+
+// Upper bound on the number of per-IP buckets kept for a single rule
+const MAX_IP_BUCKETS: u64 = 1_000_000;
+
+// What to do with the request that matched the rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    // Let the request through without any limits
+    Pass,
+    // Reject the request
+    Block,
+    // Allow given number of requests per given interval
+    Limit(u32, Duration),
+}
+
+// Parses the interval in the form of "<number><unit>" where unit is one of ms, s, m, h
+fn parse_interval(s: &str) -> Result<Duration, Error> {
+    let idx = s
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("interval '{s}' has no unit"))?;
+
+    let (value, unit) = s.split_at(idx);
+    let value = value
+        .parse::<u64>()
+        .with_context(|| format!("unable to parse interval '{s}'"))?;
+
+    let interval = match unit {
+        "ms" => Duration::from_millis(value),
+        "s" => Duration::from_secs(value),
+        "m" => Duration::from_secs(value * 60),
+        "h" => Duration::from_secs(value * 3600),
+        _ => return Err(anyhow!("unknown interval unit '{unit}'")),
+    };
+
+    if interval.is_zero() {
+        return Err(anyhow!("interval cannot be zero"));
+    }
+
+    Ok(interval)
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    // Accepts "pass", "block" or "<count>/<interval>" e.g. "100/1s"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "pass" => Ok(Self::Pass),
+            "block" => Ok(Self::Block),
+            limit => {
+                let (count, interval) = limit.split_once('/').ok_or_else(|| {
+                    anyhow!("limit '{limit}' should be in <count>/<interval> form")
+                })?;
+
+                let count = count
+                    .trim()
+                    .parse::<u32>()
+                    .with_context(|| format!("unable to parse request count in '{limit}'"))?;
+
+                if count == 0 {
+                    return Err(anyhow!("request count cannot be 0, use 'block' instead"));
+                }
+
+                Ok(Self::Limit(count, parse_interval(interval.trim())?))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+// The regex is anchored so that it has to match the whole method name
+fn deserialize_regex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Regex>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|x| Regex::new(&format!("^(?:{x})$")))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+// Who shares the token bucket of the rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitScope {
+    // All requests matching the rule share a single bucket
+    #[default]
+    Global,
+    // Each client IP gets its own bucket.
+    // The sender principal can't be used here since it is not authenticated
+    // and could be rotated by the client to get a fresh bucket for each request.
+    PerIp,
+}
+
+// Single rate limiting rule.
+// All of the specified fields must match for the rule to apply, absent fields match anything.
+// The `principal_id` is matched against the sender from the request body, whose signature
+// is not verified here, so it must not be relied upon to grant anything.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitRule {
+    pub canister_id: Option<Principal>,
+    pub subnet_id: Option<Principal>,
+    pub principal_id: Option<Principal>,
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub methods_regex: Option<Regex>,
+    pub request_types: Option<Vec<RequestType>>,
+    pub limit: Action,
+    #[serde(default)]
+    pub scope: LimitScope,
+}
+
+impl RateLimitRule {
+    fn matches(&self, ctx: &RequestContext, canister_id: Principal, subnet_id: &str) -> bool {
+        if let Some(v) = self.canister_id {
+            if v != canister_id {
+                return false;
+            }
+        }
+
+        if let Some(v) = self.subnet_id {
+            if v.to_string() != subnet_id {
+                return false;
+            }
+        }
+
+        if let Some(v) = self.principal_id {
+            if ctx.sender != Some(v) {
+                return false;
+            }
+        }
+
+        if let Some(v) = &self.request_types {
+            if !v.contains(&ctx.request_type) {
+                return false;
+            }
+        }
+
+        if let Some(v) = &self.methods_regex {
+            match &ctx.method_name {
+                Some(method) if v.is_match(method) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+// Parses the rules from YAML
+pub fn parse_rules(data: &[u8]) -> Result<Vec<RateLimitRule>, Error> {
+    // Empty file means no rules
+    if data.iter().all(|x| x.is_ascii_whitespace()) {
+        return Ok(vec![]);
+    }
+
+    serde_yaml::from_slice(data).context("unable to parse rate limiting rules")
+}
+
+// Refills one token every refill_interval, allows bursts up to `count`
+fn new_ratelimiter(count: u32, refill_interval: Duration) -> Result<Ratelimiter, Error> {
+    Ratelimiter::builder(1, refill_interval)
+        .max_tokens(count as u64)
+        .initial_available(count as u64)
+        .build()
+        .context("unable to create rate limiter")
+}
+
+enum Limiter {
+    Global(Ratelimiter),
+    PerIp {
+        count: u32,
+        refill_interval: Duration,
+        // Buckets idle for the whole interval are full again, so they can be dropped
+        buckets: Cache<IpAddr, Arc<Ratelimiter>>,
+    },
+}
+
+impl Limiter {
+    fn try_wait(&self, client_ip: IpAddr) -> bool {
+        match self {
+            Self::Global(limiter) => limiter.try_wait().is_ok(),
+            Self::PerIp {
+                count,
+                refill_interval,
+                buckets,
+            } => buckets
+                .get_with(client_ip, || {
+                    // Parameters were already validated when the bucket was created
+                    Arc::new(new_ratelimiter(*count, *refill_interval).unwrap())
+                })
+                .try_wait()
+                .is_ok(),
+        }
+    }
+}
+
+struct Bucket {
+    rule: RateLimitRule,
+    limiter: Option<Limiter>,
+}
+
+impl Bucket {
+    fn new(rule: RateLimitRule) -> Result<Self, Error> {
+        let limiter = match rule.limit {
+            Action::Limit(count, interval) => {
+                let refill_interval = interval / count;
+                if refill_interval.is_zero() {
+                    return Err(anyhow!("rate {count}/{interval:?} is too high"));
+                }
+
+                let limiter = new_ratelimiter(count, refill_interval)?;
+
+                Some(match rule.scope {
+                    LimitScope::Global => Limiter::Global(limiter),
+                    LimitScope::PerIp => Limiter::PerIp {
+                        count,
+                        refill_interval,
+                        buckets: Cache::builder()
+                            .max_capacity(MAX_IP_BUCKETS)
+                            .time_to_idle(interval)
+                            .build(),
+                    },
+                })
+            }
+
+            _ => None,
+        };
+
+        Ok(Self { rule, limiter })
+    }
+}
+
+// Rule-based rate limiter.
+// Rules are evaluated in order and the first one that matches the request decides its fate.
+// Requests that match no rule are let through.
+#[derive(Default)]
+pub struct GenericLimiter {
+    buckets: ArcSwap<Vec<Bucket>>,
+}
+
+impl GenericLimiter {
+    // Replaces the active rules, token buckets are reset
+    pub fn apply_rules(&self, rules: Vec<RateLimitRule>) -> Result<(), Error> {
+        let buckets = rules
+            .into_iter()
+            .map(Bucket::new)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.buckets.store(Arc::new(buckets));
+        Ok(())
+    }
+
+    fn check(
+        &self,
+        ctx: &RequestContext,
+        canister_id: Principal,
+        subnet_id: &str,
+        client_ip: IpAddr,
+    ) -> Result<(), ErrorCause> {
+        let buckets = self.buckets.load();
+
+        let bucket = match buckets
+            .iter()
+            .find(|x| x.rule.matches(ctx, canister_id, subnet_id))
+        {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        match (&bucket.rule.limit, &bucket.limiter) {
+            (Action::Block, _) => Err(ErrorCause::Forbidden),
+            (Action::Limit(..), Some(limiter)) if !limiter.try_wait(client_ip) => {
+                Err(ErrorCause::RateLimited(RateLimitCause::Generic))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Reloads the rules from the file when it changes
+pub struct RulesLoader {
+    path: PathBuf,
+    limiter: Arc<GenericLimiter>,
+    last_data: Option<Vec<u8>>,
+}
+
+impl RulesLoader {
+    pub fn new(path: PathBuf, limiter: Arc<GenericLimiter>) -> Self {
+        Self {
+            path,
+            limiter,
+            last_data: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Run for RulesLoader {
+    async fn run(&mut self) -> Result<(), Error> {
+        let data = fs::read(&self.path)
+            .with_context(|| format!("unable to read rules file {}", self.path.display()))?;
+
+        // Skip if nothing has changed
+        if self.last_data.as_ref() == Some(&data) {
+            return Ok(());
+        }
+
+        // On any error the currently active rules are kept
+        let rules = parse_rules(&data).map_err(|e| {
+            warn!("Unable to load rate limiting rules: {e:#}");
+            e
+        })?;
+
+        let count = rules.len();
+        self.limiter.apply_rules(rules)?;
+        self.last_data = Some(data);
+
+        info!("Rate limiting rules loaded: {count} rules");
+
+        Ok(())
+    }
+}
+
+pub async fn middleware(
+    State(limiter): State<Arc<GenericLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(ctx): Extension<Arc<RequestContext>>,
+    Extension(canister_id): Extension<CanisterId>,
+    Extension(subnet): Extension<Arc<RouteSubnet>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<impl IntoResponse, ApiError> {
+    limiter.check(&ctx, canister_id.get().0, &subnet.id, addr.ip())?;
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+pub mod test;
@@ -0,0 +1,365 @@
+use super::*;
+
+use std::{io::Write, net::Ipv4Addr};
+
+use tempfile::NamedTempFile;
+
+const CANISTER_1: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+const CANISTER_2: &str = "qjdve-lqaaa-aaaaa-aaaeq-cai";
+const SUBNET_1: &str = "tdb26-jop6k-aogll-7ltgs-eruif-6kk7m-qpktf-gdiqx-mxtrf-vb5e6-eqe";
+const SUBNET_2: &str = "uzr34-akd3s-xrdag-3ql62-ocgoh-ld2ao-tamcv-54e7j-krwgb-2gm4z-oqe";
+const IP_1: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+const IP_2: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+fn ctx(request_type: RequestType, method: &str) -> RequestContext {
+    RequestContext {
+        request_type,
+        method_name: Some(method.into()),
+        ..Default::default()
+    }
+}
+
+fn ctx_with_sender(sender: Principal) -> RequestContext {
+    RequestContext {
+        sender: Some(sender),
+        ..ctx(RequestType::Call, "foo")
+    }
+}
+
+fn principal(s: &str) -> Principal {
+    Principal::from_text(s).unwrap()
+}
+
+#[test]
+fn test_parse_action() {
+    assert_eq!(Action::from_str("pass").unwrap(), Action::Pass);
+    assert_eq!(Action::from_str("block").unwrap(), Action::Block);
+    assert_eq!(
+        Action::from_str("100/1s").unwrap(),
+        Action::Limit(100, Duration::from_secs(1))
+    );
+    assert_eq!(
+        Action::from_str("5 / 10m").unwrap(),
+        Action::Limit(5, Duration::from_secs(600))
+    );
+    assert_eq!(
+        Action::from_str("1/500ms").unwrap(),
+        Action::Limit(1, Duration::from_millis(500))
+    );
+    assert_eq!(
+        Action::from_str("10/2h").unwrap(),
+        Action::Limit(10, Duration::from_secs(7200))
+    );
+
+    assert!(Action::from_str("foo").is_err());
+    assert!(Action::from_str("0/1s").is_err());
+    assert!(Action::from_str("10/0s").is_err());
+    assert!(Action::from_str("10/1d").is_err());
+    assert!(Action::from_str("10/s").is_err());
+    assert!(Action::from_str("10/10").is_err());
+    assert!(Action::from_str("-1/1s").is_err());
+}
+
+#[test]
+fn test_parse_rules() {
+    let rules = parse_rules(
+        format!(
+            r#"
+- canister_id: {CANISTER_1}
+  methods_regex: ^(foo|bar)$
+  request_types: [query, call]
+  limit: 10/1s
+- subnet_id: {SUBNET_1}
+  limit: block
+- principal_id: {CANISTER_2}
+  limit: 1/1s
+  scope: per_ip
+- limit: pass
+"#
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(rules.len(), 4);
+    assert_eq!(rules[0].canister_id, Some(principal(CANISTER_1)));
+    assert_eq!(
+        rules[0].methods_regex.as_ref().unwrap().as_str(),
+        "^(?:^(foo|bar)$)$"
+    );
+    assert_eq!(
+        rules[0].request_types,
+        Some(vec![RequestType::Query, RequestType::Call])
+    );
+    assert_eq!(rules[0].limit, Action::Limit(10, Duration::from_secs(1)));
+    assert_eq!(rules[1].subnet_id, Some(principal(SUBNET_1)));
+    assert_eq!(rules[1].limit, Action::Block);
+    assert_eq!(rules[1].scope, LimitScope::Global);
+    assert_eq!(rules[2].principal_id, Some(principal(CANISTER_2)));
+    assert_eq!(rules[2].scope, LimitScope::PerIp);
+    assert_eq!(rules[3].limit, Action::Pass);
+
+    // Empty file
+    assert!(parse_rules(b"").unwrap().is_empty());
+    assert!(parse_rules(b" \n").unwrap().is_empty());
+
+    // Bad rules
+    assert!(parse_rules(b"- limit: foo").is_err());
+    assert!(parse_rules(b"- canister_id: foo\n  limit: block").is_err());
+    assert!(parse_rules(b"- methods_regex: '('\n  limit: block").is_err());
+    assert!(parse_rules(b"- request_types: [foo]\n  limit: block").is_err());
+    assert!(parse_rules(b"- foo: bar\n  limit: block").is_err());
+    assert!(parse_rules(b"- canister_id: aaaaa-aa").is_err());
+    assert!(parse_rules(b"- principal_id: foo\n  limit: block").is_err());
+    assert!(parse_rules(b"- scope: foo\n  limit: 1/1s").is_err());
+}
+
+#[test]
+fn test_rule_matching() {
+    let rules = parse_rules(
+        format!(
+            r#"
+- canister_id: {CANISTER_1}
+  methods_regex: ^foo$
+  limit: block
+- subnet_id: {SUBNET_2}
+  request_types: [call]
+  limit: block
+"#
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let limiter = GenericLimiter::default();
+    limiter.apply_rules(rules).unwrap();
+
+    let c1 = principal(CANISTER_1);
+    let c2 = principal(CANISTER_2);
+
+    // Rule 1
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "foo"), c1, SUBNET_1, IP_1)
+        .is_err());
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "foobar"), c1, SUBNET_1, IP_1)
+        .is_ok());
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "foo"), c2, SUBNET_1, IP_1)
+        .is_ok());
+    assert!(limiter
+        .check(&RequestContext::default(), c1, SUBNET_1, IP_1)
+        .is_ok());
+
+    // Rule 2
+    assert!(limiter
+        .check(&ctx(RequestType::Call, "bar"), c2, SUBNET_2, IP_1)
+        .is_err());
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "bar"), c2, SUBNET_2, IP_1)
+        .is_ok());
+    assert!(limiter
+        .check(&ctx(RequestType::Call, "bar"), c2, SUBNET_1, IP_1)
+        .is_ok());
+}
+
+#[test]
+fn test_rule_matching_whole_method_name() {
+    let rules = parse_rules(
+        format!(
+            r#"
+- canister_id: {CANISTER_1}
+  methods_regex: foo|bar
+  limit: block
+"#
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let limiter = GenericLimiter::default();
+    limiter.apply_rules(rules).unwrap();
+
+    let c1 = principal(CANISTER_1);
+
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "foo"), c1, SUBNET_1, IP_1)
+        .is_err());
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "bar"), c1, SUBNET_1, IP_1)
+        .is_err());
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "foobar"), c1, SUBNET_1, IP_1)
+        .is_ok());
+    assert!(limiter
+        .check(&ctx(RequestType::Query, "xfoo"), c1, SUBNET_1, IP_1)
+        .is_ok());
+}
+
+#[test]
+fn test_rule_matching_principal() {
+    let rules = parse_rules(
+        format!(
+            r#"
+- principal_id: {CANISTER_2}
+  limit: block
+"#
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let limiter = GenericLimiter::default();
+    limiter.apply_rules(rules).unwrap();
+
+    let c1 = principal(CANISTER_1);
+
+    assert!(limiter
+        .check(&ctx_with_sender(principal(CANISTER_2)), c1, SUBNET_1, IP_1)
+        .is_err());
+    assert!(limiter
+        .check(&ctx_with_sender(principal(CANISTER_1)), c1, SUBNET_1, IP_1)
+        .is_ok());
+    // Requests without a sender don't match
+    assert!(limiter
+        .check(&ctx(RequestType::Call, "foo"), c1, SUBNET_1, IP_1)
+        .is_ok());
+}
+
+#[test]
+fn test_per_ip_buckets() {
+    let rules = parse_rules(
+        format!(
+            r#"
+- canister_id: {CANISTER_1}
+  limit: 2/1h
+  scope: per_ip
+"#
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let limiter = GenericLimiter::default();
+    limiter.apply_rules(rules).unwrap();
+
+    let c1 = principal(CANISTER_1);
+    let caller_1 = ctx_with_sender(principal(CANISTER_1));
+    let caller_2 = ctx_with_sender(principal(CANISTER_2));
+
+    for _ in 0..2 {
+        assert!(limiter.check(&caller_1, c1, SUBNET_1, IP_1).is_ok());
+    }
+    assert!(matches!(
+        limiter.check(&caller_1, c1, SUBNET_1, IP_1),
+        Err(ErrorCause::RateLimited(RateLimitCause::Generic))
+    ));
+
+    // Changing the sender principal doesn't give a fresh bucket
+    assert!(limiter.check(&caller_2, c1, SUBNET_1, IP_1).is_err());
+    assert!(limiter
+        .check(&ctx(RequestType::Call, "foo"), c1, SUBNET_1, IP_1)
+        .is_err());
+
+    // Other IPs have their own buckets
+    for _ in 0..2 {
+        assert!(limiter.check(&caller_1, c1, SUBNET_1, IP_2).is_ok());
+    }
+    assert!(limiter.check(&caller_1, c1, SUBNET_1, IP_2).is_err());
+}
+
+#[test]
+fn test_rule_actions() {
+    let rules = parse_rules(
+        format!(
+            r#"
+- canister_id: {CANISTER_1}
+  methods_regex: ^allowed$
+  limit: pass
+- canister_id: {CANISTER_1}
+  limit: 3/1h
+- canister_id: {CANISTER_2}
+  limit: block
+"#
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let limiter = GenericLimiter::default();
+    limiter.apply_rules(rules).unwrap();
+
+    let c1 = principal(CANISTER_1);
+    let c2 = principal(CANISTER_2);
+
+    // Token bucket should allow the burst and then reject
+    for _ in 0..3 {
+        assert!(limiter
+            .check(&ctx(RequestType::Call, "foo"), c1, SUBNET_1, IP_1)
+            .is_ok());
+    }
+
+    assert!(matches!(
+        limiter.check(&ctx(RequestType::Call, "foo"), c1, SUBNET_1, IP_1),
+        Err(ErrorCause::RateLimited(RateLimitCause::Generic))
+    ));
+
+    // Pass rule is matched first and isn't limited
+    for _ in 0..10 {
+        assert!(limiter
+            .check(&ctx(RequestType::Call, "allowed"), c1, SUBNET_1, IP_1)
+            .is_ok());
+    }
+
+    // Block rule
+    assert!(matches!(
+        limiter.check(&ctx(RequestType::Call, "foo"), c2, SUBNET_1, IP_1),
+        Err(ErrorCause::Forbidden)
+    ));
+
+    // Removing the rules should lift all the limits
+    limiter.apply_rules(vec![]).unwrap();
+    assert!(limiter
+        .check(&ctx(RequestType::Call, "foo"), c1, SUBNET_1, IP_1)
+        .is_ok());
+    assert!(limiter
+        .check(&ctx(RequestType::Call, "foo"), c2, SUBNET_1, IP_1)
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_rules_loader() -> Result<(), Error> {
+    let mut file = NamedTempFile::new()?;
+    let limiter = Arc::new(GenericLimiter::default());
+    let mut loader = RulesLoader::new(file.path().to_path_buf(), limiter.clone());
+
+    let c1 = principal(CANISTER_1);
+    let req = ctx(RequestType::Call, "foo");
+
+    // Empty file
+    loader.run().await?;
+    assert!(limiter.check(&req, c1, SUBNET_1, IP_1).is_ok());
+
+    // Block the canister
+    writeln!(file, "- canister_id: {CANISTER_1}\n  limit: block")?;
+    loader.run().await?;
+    assert!(limiter.check(&req, c1, SUBNET_1, IP_1).is_err());
+
+    // Broken file should keep the old rules
+    writeln!(file, "- foo")?;
+    assert!(loader.run().await.is_err());
+    assert!(limiter.check(&req, c1, SUBNET_1, IP_1).is_err());
+
+    // Replace the rules
+    fs::write(
+        file.path(),
+        format!("- canister_id: {CANISTER_2}\n  limit: block"),
+    )?;
+    loader.run().await?;
+    assert!(limiter.check(&req, c1, SUBNET_1, IP_1).is_ok());
+    assert!(limiter
+        .check(&req, principal(CANISTER_2), SUBNET_1, IP_1)
+        .is_err());
+
+    Ok(())
+}
@@ -23,7 +23,7 @@ use http::{
     Method,
 };
 use ic_types::{
-    messages::{Blob, HttpStatusResponse, ReplicaHealthStatus},
+    messages::{Blob, HttpStatusResponse, ReplicaHealthStatus, IC_API_VERSION},
     CanisterId,
 };
 
@@ -43,8 +43,6 @@ use crate::{
     snapshot::{Node, RegistrySnapshot},
 };
 
-// TODO which one to use?
-const IC_API_VERSION: &str = "0.18.0";
 pub const ANONYMOUS_PRINCIPAL: Principal = Principal::anonymous();
 const METHOD_HTTP: &str = "http_request";
 
@@ -95,8 +93,9 @@ lazy_static! {
 }
 
 // Type of IC request
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Display, IntoStaticStr)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Display, IntoStaticStr, Deserialize)]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum RequestType {
     #[default]
     Unknown,
@@ -111,6 +110,7 @@ pub enum RequestType {
 pub enum RateLimitCause {
     Normal,
     LedgerTransfer,
+    Generic,
 }
 
 // Categorized possible causes for request processing failures
@@ -134,6 +134,7 @@ pub enum ErrorCause {
     ReplicaTLSErrorCert(String),
     ReplicaErrorOther(String),
     RateLimited(RateLimitCause),
+    Forbidden,
     Other(String),
 }
 
@@ -158,6 +159,7 @@ impl ErrorCause {
             Self::ReplicaTLSErrorCert(_) => StatusCode::SERVICE_UNAVAILABLE,
             Self::ReplicaErrorOther(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::Forbidden => StatusCode::FORBIDDEN,
         }
     }
 
@@ -211,6 +213,7 @@ impl fmt::Display for ErrorCause {
             Self::ReplicaTLSErrorCert(_) => write!(f, "replica_tls_error_cert"),
             Self::ReplicaErrorOther(_) => write!(f, "replica_error_other"),
             Self::RateLimited(x) => write!(f, "rate_limited_{x}"),
+            Self::Forbidden => write!(f, "forbidden"),
         }
     }
 }
@@ -252,6 +255,7 @@ pub struct RequestContext {
     pub nonce: Option<Vec<u8>>,
     pub ingress_expiry: Option<u64>,
     pub arg: Option<Vec<u8>>,
+    pub paths: Option<Vec<Vec<Vec<u8>>>>,
 
     // Filled in when the request is HTTP
     pub http_request: Option<HttpRequest>,
@@ -261,6 +265,15 @@ impl RequestContext {
     pub fn is_anonymous(&self) -> Option<bool> {
         self.sender.map(|x| x == ANONYMOUS_PRINCIPAL)
     }
+
+    // Checks if the read_state request asks for the status of some request
+    pub fn is_request_status(&self) -> bool {
+        self.paths.as_ref().map_or(false, |paths| {
+            paths
+                .iter()
+                .any(|x| x.first().map(|x| x.as_slice()) == Some(b"request_status".as_slice()))
+        })
+    }
 }
 
 // Hash and Eq are implemented for request caching
@@ -272,6 +285,7 @@ impl Hash for RequestContext {
         self.sender.hash(state);
         self.method_name.hash(state);
         self.ingress_expiry.hash(state);
+        self.paths.hash(state);
 
         // Hash http_request if it's present, arg otherwise
         // They're mutually exclusive
@@ -288,7 +302,8 @@ impl PartialEq for RequestContext {
         let r = self.canister_id == other.canister_id
             && self.sender == other.sender
             && self.method_name == other.method_name
-            && self.ingress_expiry == other.ingress_expiry;
+            && self.ingress_expiry == other.ingress_expiry
+            && self.paths == other.paths;
 
         // Same as in hash()
         if self.http_request.is_some() {
@@ -309,6 +324,7 @@ struct ICRequestContent {
     nonce: Option<Blob>,
     ingress_expiry: Option<u64>,
     arg: Option<Blob>,
+    paths: Option<Vec<Vec<Blob>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -593,6 +609,11 @@ pub async fn preprocess_request(
         ingress_expiry: content.ingress_expiry,
         arg: arg.map(|x| x.0),
         nonce: content.nonce.map(|x| x.0),
+        paths: content.paths.map(|x| {
+            x.into_iter()
+                .map(|x| x.into_iter().map(|x| x.0).collect())
+                .collect()
+        }),
         http_request,
     };
 
@@ -42,6 +42,7 @@ pub fn test_route_subnet_with_id(id: String, n: usize) -> RouteSubnet {
         range_start: u256::from(zero),
         range_end: u256::from(zero),
         nodes,
+        weights: vec![1.0; n],
     }
 }
 
@@ -41,7 +41,7 @@ use crate::{
     cli::Cli,
     core::setup_router,
     http::HttpClient,
-    persist::{Persist, Persister, Routes},
+    persist::{NodeWeights, Persist, Persister, Routes},
     snapshot::{RegistrySnapshot, Snapshot, Snapshotter, Subnet},
 };
 
@@ -232,7 +232,7 @@ pub fn setup_test_router(
 
     snapshotter.snapshot().unwrap();
     let subnets = registry_snapshot.load_full().unwrap().subnets.clone();
-    persister.persist(subnets.clone());
+    persister.persist(subnets.clone(), &NodeWeights::new());
 
     let router = setup_router(
         registry_snapshot,
@@ -241,8 +241,17 @@ pub fn setup_test_router(
         &cli,
         &metrics_registry,
         enable_cache.then_some(Arc::new(
-            Cache::new(10485760, 262144, Duration::from_secs(1), false).unwrap(),
+            Cache::new(
+                10485760,
+                262144,
+                Duration::from_secs(1),
+                false,
+                vec![],
+                &metrics_registry,
+            )
+            .unwrap(),
         )),
+        None,
     );
 
     let router = router.layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 1337))));
@@ -1,8 +1,9 @@
 //! An agent to talk to the Internet Computer through the public endpoints.
 use crate::{
     cbor::{
-        parse_query_response, parse_read_state_response, prepare_query, prepare_read_state,
-        prepare_update, RequestStatus,
+        parse_canister_controllers_read_state_response, parse_query_response,
+        parse_read_state_response, prepare_query, prepare_read_state, prepare_update,
+        RequestStatus,
     },
     http_client::{HttpClient, HttpClientConfig},
 };
@@ -16,7 +17,7 @@ use ic_types::{
     crypto::threshold_sig::ThresholdSigPublicKey,
     messages::{Blob, HttpStatusResponse, MessageId, ReplicaHealthStatus},
     time::expiry_time_from_now,
-    CanisterId,
+    CanisterId, PrincipalId,
 };
 use prost::Message;
 use serde_cbor::value::Value as CBOR;
@@ -369,6 +370,39 @@ impl Agent {
         )
     }
 
+    /// Reads the controllers of the given canister from the certified state
+    /// tree using the `read_state` API.
+    pub async fn read_canister_controllers(
+        &self,
+        canister_id: &CanisterId,
+    ) -> Result<Vec<PrincipalId>, String> {
+        let path = Path::new(vec![
+            "canister".into(),
+            canister_id.get().into_vec().into(),
+            "controllers".into(),
+        ]);
+        let signed_request_bytes =
+            prepare_read_state(&self.sender, &[path], self.sender_field.clone())
+                .map_err(|e| format!("Failed to prepare read state: {:?}", e))?;
+
+        let bytes = self
+            .http_client
+            .post_with_response(
+                &self.url,
+                &read_state_path(*canister_id),
+                signed_request_bytes.into(),
+                tokio::time::Instant::now() + self.query_timeout,
+            )
+            .await?;
+        let cbor = bytes_to_cbor(bytes)?;
+
+        parse_canister_controllers_read_state_response(
+            canister_id,
+            self.nns_public_key.as_ref(),
+            cbor,
+        )
+    }
+
     async fn get_status(&self) -> Result<HttpStatusResponse, String> {
         let bytes = self
             .http_client
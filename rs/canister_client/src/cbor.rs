@@ -9,7 +9,7 @@ use ic_types::{
         SignedRequestBytes,
     },
     time::expiry_time_from_now,
-    CanisterId, SubnetId, Time,
+    CanisterId, PrincipalId, SubnetId, Time,
 };
 use serde::Deserialize;
 use serde_cbor::value::Value as CBOR;
@@ -127,6 +127,44 @@ pub fn parse_subnet_read_state_response(
     }
 }
 
+/// Given a CBOR response from a canister `read_state` of the `controllers` path
+/// and a `canister_id` extracts the controllers of the canister.
+pub fn parse_canister_controllers_read_state_response(
+    canister_id: &CanisterId,
+    root_pk: Option<&ThresholdSigPublicKey>,
+    message: CBOR,
+) -> Result<Vec<PrincipalId>, String> {
+    let response = serde_cbor::value::from_value::<HttpReadStateResponse>(message)
+        .map_err(|source| format!("decoding to HttpReadStateResponse failed: {}", source))?;
+
+    let certificate = match root_pk {
+        Some(pk) => ic_certification::verify_certificate(&response.certificate, canister_id, pk)
+            .map_err(|source| format!("verifying certificate failed: {}", source))?,
+        None => serde_cbor::from_slice(response.certificate.as_slice())
+            .map_err(|source| format!("decoding Certificate failed: {}", source))?,
+    };
+
+    let controllers_leaf = match certificate.tree.lookup(&[
+        &b"canister"[..],
+        canister_id.get().as_ref(),
+        &b"controllers"[..],
+    ]) {
+        LookupStatus::Found(controllers_leaf) => controllers_leaf.clone(),
+        LookupStatus::Absent | LookupStatus::Unknown => {
+            return Err(format!(
+                "The certificate does not contain the controllers of canister {}",
+                canister_id
+            ))
+        }
+    };
+
+    match controllers_leaf {
+        MixedHashTree::Leaf(bytes) => serde_cbor::from_slice::<Vec<PrincipalId>>(&bytes)
+            .map_err(|err| format!("deserializing controllers failed: {:?}", err)),
+        tree => Err(format!("Expected controllers leaf but found {:?}", tree)),
+    }
+}
+
 /// Given a CBOR response from a `query`, extract the response.
 pub fn parse_query_response(message: &CBOR) -> Result<RequestStatus, String> {
     let content = match message {
@@ -733,6 +771,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_canister_controllers_read_state_response() {
+        let canister_id = CanisterId::from(1);
+        let controllers = vec![PrincipalId::new_user_test_id(1), canister_id.get()];
+
+        let tree = MixedHashTree::Fork(Box::new((
+            MixedHashTree::Labeled(
+                "canister".into(),
+                Box::new(MixedHashTree::Labeled(
+                    canister_id.get().into_vec().into(),
+                    Box::new(MixedHashTree::Labeled(
+                        "controllers".into(),
+                        Box::new(MixedHashTree::Leaf(
+                            to_self_describing_cbor(&controllers).unwrap(),
+                        )),
+                    )),
+                )),
+            ),
+            MixedHashTree::Labeled("time".into(), Box::new(MixedHashTree::Leaf(vec![1]))),
+        )));
+        let labeled_tree = LabeledTree::try_from(tree).unwrap();
+        let data = CertificateData::CustomTree(labeled_tree);
+        let (certificate, root_pk, _) = CertificateBuilder::new(data).build();
+
+        let certificate_cbor: Vec<u8> = to_self_describing_cbor(&certificate).unwrap();
+
+        let response = HttpReadStateResponse {
+            certificate: Blob(certificate_cbor),
+        };
+
+        let response_cbor: Vec<u8> = to_self_describing_cbor(&response).unwrap();
+
+        let response: CBOR = serde_cbor::from_slice(response_cbor.as_slice()).unwrap();
+
+        assert_eq!(
+            parse_canister_controllers_read_state_response(
+                &canister_id,
+                Some(&root_pk),
+                response.clone()
+            ),
+            Ok(controllers),
+        );
+
+        // The controllers of another canister are not in the certificate.
+        assert!(parse_canister_controllers_read_state_response(
+            &CanisterId::from(2),
+            Some(&root_pk),
+            response
+        )
+        .is_err());
+    }
+
     fn request_validator() -> HttpRequestVerifierImpl {
         HttpRequestVerifierImpl::new(Arc::new(temp_crypto_component_with_fake_registry(
             node_test_id(VALIDATOR_NODE_ID),
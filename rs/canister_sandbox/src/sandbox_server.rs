@@ -161,7 +161,7 @@ mod tests {
     use ic_test_utilities::types::ids::{canister_test_id, subnet_test_id, user_test_id};
     use ic_types::{
         ingress::WasmResult,
-        messages::{CallContextId, RequestMetadata},
+        messages::{CallContextId, RequestMetadata, NO_DEADLINE},
         methods::{FuncRef, WasmMethod},
         time::Time,
         CanisterTimer, ComputeAllocation, Cycles, MemoryAllocation, NumBytes, NumInstructions,
@@ -202,6 +202,7 @@ mod tests {
             Cycles::new(1_000_000),
             Cycles::zero(),
             None,
+            None,
             BTreeMap::new(),
             CyclesAccountManager::new(
                 NumInstructions::from(1_000_000_000),
@@ -251,6 +252,7 @@ mod tests {
                 Cycles::zero(),
                 PrincipalId::try_from([0].as_ref()).unwrap(),
                 CallContextId::from(0),
+                NO_DEADLINE,
             ),
             globals,
             canister_current_memory_usage: NumBytes::from(0),
@@ -11,11 +11,193 @@ use std::{
 use crate::CertificationVersion;
 
 use super::types;
-use crate::encoding::types::{Bytes, Cycles, Funds, Response};
+use crate::encoding::types::{Bytes, Cycles, Funds, Payload, RequestMetadata};
 use ic_protobuf::proxy::ProxyDecodeError;
-use ic_types::xnet::StreamHeader;
+use ic_types::{messages::NO_DEADLINE, xnet::StreamHeader};
 use serde::{Deserialize, Serialize};
 
+// Copy of `types::RequestOrResponse` at canonical version 16 (before the
+// addition of `deadline` to `types::Request` and `types::Response`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestOrResponseV16 {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<RequestV16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<ResponseV16>,
+}
+
+// Copy of `types::Request` at canonical version 16 (before the addition of `deadline`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestV16 {
+    #[serde(with = "serde_bytes")]
+    pub receiver: Bytes,
+    #[serde(with = "serde_bytes")]
+    pub sender: Bytes,
+    pub sender_reply_callback: u64,
+    pub payment: Funds,
+    pub method_name: String,
+    #[serde(with = "serde_bytes")]
+    pub method_payload: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycles_payment: Option<Cycles>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RequestMetadata>,
+}
+
+// Copy of `types::Response` at canonical version 16 (before the addition of `deadline`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseV16 {
+    #[serde(with = "serde_bytes")]
+    pub originator: Bytes,
+    #[serde(with = "serde_bytes")]
+    pub respondent: Bytes,
+    pub originator_reply_callback: u64,
+    pub refund: Funds,
+    pub response_payload: Payload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycles_refund: Option<Cycles>,
+}
+
+impl From<(&ic_types::messages::RequestOrResponse, CertificationVersion)> for RequestOrResponseV16 {
+    fn from(
+        (message, certification_version): (
+            &ic_types::messages::RequestOrResponse,
+            CertificationVersion,
+        ),
+    ) -> Self {
+        use ic_types::messages::RequestOrResponse::*;
+        match message {
+            Request(request) => Self {
+                request: Some((request.as_ref(), certification_version).into()),
+                response: None,
+            },
+            Response(response) => Self {
+                request: None,
+                response: Some((response.as_ref(), certification_version).into()),
+            },
+        }
+    }
+}
+
+impl TryFrom<RequestOrResponseV16> for ic_types::messages::RequestOrResponse {
+    type Error = ProxyDecodeError;
+
+    fn try_from(message: RequestOrResponseV16) -> Result<Self, Self::Error> {
+        match message {
+            RequestOrResponseV16 {
+                request: Some(request),
+                response: None,
+            } => Ok(Self::Request(Arc::new(request.try_into()?))),
+            RequestOrResponseV16 {
+                request: None,
+                response: Some(response),
+            } => Ok(Self::Response(Arc::new(response.try_into()?))),
+            other => Err(ProxyDecodeError::Other(format!(
+                "RequestOrResponse: expected exactly one of `request` or `response` to be `Some(_)`, got `{:?}`",
+                other
+            )))
+        }
+    }
+}
+
+impl From<(&ic_types::messages::Request, CertificationVersion)> for RequestV16 {
+    fn from(
+        (request, certification_version): (&ic_types::messages::Request, CertificationVersion),
+    ) -> Self {
+        let funds = Funds {
+            cycles: (&request.payment, certification_version).into(),
+            icp: 0,
+        };
+
+        Self {
+            receiver: request.receiver.get().to_vec(),
+            sender: request.sender.get().to_vec(),
+            sender_reply_callback: request.sender_reply_callback.get(),
+            payment: funds,
+            method_name: request.method_name.clone(),
+            method_payload: request.method_payload.clone(),
+            cycles_payment: None,
+            metadata: request.metadata.as_ref().and_then(|metadata| {
+                (certification_version >= CertificationVersion::V14).then_some(metadata.into())
+            }),
+        }
+    }
+}
+
+impl TryFrom<RequestV16> for ic_types::messages::Request {
+    type Error = ProxyDecodeError;
+
+    fn try_from(request: RequestV16) -> Result<Self, Self::Error> {
+        let payment = match request.cycles_payment {
+            Some(cycles) => cycles,
+            None => request.payment.cycles,
+        }
+        .try_into()?;
+
+        Ok(Self {
+            receiver: ic_types::CanisterId::unchecked_from_principal(
+                request.receiver.as_slice().try_into()?,
+            ),
+            sender: ic_types::CanisterId::unchecked_from_principal(
+                request.sender.as_slice().try_into()?,
+            ),
+            sender_reply_callback: request.sender_reply_callback.into(),
+            payment,
+            method_name: request.method_name,
+            method_payload: request.method_payload,
+            metadata: request.metadata.map(From::from),
+            deadline: NO_DEADLINE,
+        })
+    }
+}
+
+impl From<(&ic_types::messages::Response, CertificationVersion)> for ResponseV16 {
+    fn from(
+        (response, certification_version): (&ic_types::messages::Response, CertificationVersion),
+    ) -> Self {
+        let funds = Funds {
+            cycles: (&response.refund, certification_version).into(),
+            icp: 0,
+        };
+        Self {
+            originator: response.originator.get().to_vec(),
+            respondent: response.respondent.get().to_vec(),
+            originator_reply_callback: response.originator_reply_callback.get(),
+            refund: funds,
+            response_payload: (&response.response_payload, certification_version).into(),
+            cycles_refund: None,
+        }
+    }
+}
+
+impl TryFrom<ResponseV16> for ic_types::messages::Response {
+    type Error = ProxyDecodeError;
+
+    fn try_from(response: ResponseV16) -> Result<Self, Self::Error> {
+        let refund = match response.cycles_refund {
+            Some(cycles) => cycles,
+            None => response.refund.cycles,
+        }
+        .try_into()?;
+
+        Ok(Self {
+            originator: ic_types::CanisterId::unchecked_from_principal(
+                response.originator.as_slice().try_into()?,
+            ),
+            respondent: ic_types::CanisterId::unchecked_from_principal(
+                response.respondent.as_slice().try_into()?,
+            ),
+            originator_reply_callback: response.originator_reply_callback.into(),
+            refund,
+            response_payload: response.response_payload.try_into()?,
+            deadline: NO_DEADLINE,
+        })
+    }
+}
+
 // Copy of `types::RequestOrResponse` at canonical version 13 (before the
 // addition of `metadata` to `types::Request`).
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,7 +206,7 @@ pub struct RequestOrResponseV13 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request: Option<RequestV13>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub response: Option<Response>,
+    pub response: Option<ResponseV16>,
 }
 
 // Copy of `types::Request` at canonical version 13 (before the addition of `metadata`).
@@ -128,6 +310,7 @@ impl TryFrom<RequestV13> for ic_types::messages::Request {
             method_name: request.method_name,
             method_payload: request.method_payload,
             metadata: None,
+            deadline: NO_DEADLINE,
         })
     }
 }
@@ -182,6 +365,7 @@ impl TryFrom<RequestV3> for ic_types::messages::Request {
             method_name: request.method_name,
             method_payload: request.method_payload,
             metadata: None,
+            deadline: NO_DEADLINE,
         })
     }
 }
@@ -231,6 +415,7 @@ impl TryFrom<ResponseV3> for ic_types::messages::Response {
             originator_reply_callback: response.originator_reply_callback.into(),
             refund: response.refund.cycles.try_into()?,
             response_payload: response.response_payload.try_into()?,
+            deadline: NO_DEADLINE,
         })
     }
 }
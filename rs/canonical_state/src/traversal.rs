@@ -651,6 +651,8 @@ mod tests {
                 subnet_type: SubnetType::Application,
                 subnet_features: SubnetFeatures::default(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: Default::default(),
+                vetkd_keys_held: Default::default(),
             },
             subnet_test_id(1) => SubnetTopology {
                 public_key: vec![5, 6, 7, 8],
@@ -658,6 +660,8 @@ mod tests {
                 subnet_type: SubnetType::Application,
                 subnet_features: SubnetFeatures::default(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: Default::default(),
+                vetkd_keys_held: Default::default(),
             }
         };
         fn id_range(from: u64, to: u64) -> CanisterIdRange {
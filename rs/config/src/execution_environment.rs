@@ -132,6 +132,9 @@ pub const MAX_COMPILATION_CACHE_SIZE: NumBytes = NumBytes::new(10 * GIB);
 /// Maximum number of controllers allowed in a request (specified in the interface spec).
 pub const MAX_ALLOWED_CONTROLLERS_COUNT: usize = 10;
 
+/// Maximum number of snapshots a single canister can have.
+pub const MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER: usize = 1;
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
 pub struct Config {
@@ -261,12 +264,27 @@ pub struct Config {
     /// Indicates whether canister backup and restore feature is enabled or not.
     pub canister_snapshots: FlagStatus,
 
+    /// Maximum number of snapshots a single canister can have.
+    pub max_number_of_snapshots_per_canister: usize,
+
     // TODO(IC-272): remove this flag once the feature is enabled by default.
     /// Indicates whether canister logging feature is enabled or not.
     pub canister_logging: FlagStatus,
 
     /// Indicates whether dirty page logging is enabled or not.
     pub dirty_page_logging: FlagStatus,
+
+    /// Indicates whether the threshold Schnorr API (`sign_with_schnorr` and
+    /// `schnorr_public_key`) is enabled or not. Consensus does not produce
+    /// Schnorr signatures yet, so this is only enabled in test environments
+    /// that answer the signing requests themselves.
+    pub threshold_schnorr: FlagStatus,
+
+    /// Indicates whether the vetKD API (`vetkd_derive_encrypted_key` and
+    /// `vetkd_public_key`) is enabled or not. Consensus does not deliver vetKD
+    /// keys or derive encrypted keys yet, so this is only enabled in test
+    /// environments that answer the requests themselves.
+    pub vetkd: FlagStatus,
 }
 
 impl Default for Config {
@@ -337,8 +355,11 @@ impl Default for Config {
             wasm_chunk_store: FlagStatus::Enabled,
             stop_canister_timeout_duration: STOP_CANISTER_TIMEOUT_DURATION,
             canister_snapshots: FlagStatus::Disabled,
+            max_number_of_snapshots_per_canister: MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER,
             canister_logging: FlagStatus::Disabled,
             dirty_page_logging: FlagStatus::Disabled,
+            threshold_schnorr: FlagStatus::Disabled,
+            vetkd: FlagStatus::Disabled,
         }
     }
 }
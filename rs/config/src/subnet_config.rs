@@ -120,6 +120,14 @@ const MAX_PAUSED_EXECUTIONS: usize = 4;
 /// cover the cost of the subnet.
 pub const ECDSA_SIGNATURE_FEE: Cycles = Cycles::new(10 * B as u128);
 
+/// The fee for a threshold Schnorr signature is the same as for an ECDSA
+/// signature since the signing protocols have comparable costs.
+pub const SCHNORR_SIGNATURE_FEE: Cycles = Cycles::new(10 * B as u128);
+
+/// The fee for a vetKD key derivation. The protocol involves pairing-based
+/// cryptography and is priced like a threshold signature.
+pub const VETKD_FEE: Cycles = Cycles::new(10 * B as u128);
+
 /// Default subnet size which is used to scale cycles cost according to a subnet replication factor.
 ///
 /// All initial costs were calculated with the assumption that a subnet had 13 replicas.
@@ -390,6 +398,12 @@ pub struct CyclesAccountManagerConfig {
     /// Amount to charge for an ECDSA signature.
     pub ecdsa_signature_fee: Cycles,
 
+    /// Amount to charge for a Schnorr signature.
+    pub schnorr_signature_fee: Cycles,
+
+    /// Amount to charge for a vetKD key derivation.
+    pub vetkd_fee: Cycles,
+
     /// A linear factor of the baseline cost to be charged for HTTP requests per node.
     /// The cost of an HTTP request is represented by a quadratic function due to the communication complexity of the subnet.
     pub http_request_linear_baseline_fee: Cycles,
@@ -439,6 +453,8 @@ impl CyclesAccountManagerConfig {
             gib_storage_per_second_fee: Cycles::new(127_000),
             duration_between_allocation_charges: Duration::from_secs(10),
             ecdsa_signature_fee: ECDSA_SIGNATURE_FEE,
+            schnorr_signature_fee: SCHNORR_SIGNATURE_FEE,
+            vetkd_fee: VETKD_FEE,
             http_request_linear_baseline_fee: Cycles::new(3_000_000),
             http_request_quadratic_baseline_fee: Cycles::new(60_000),
             http_request_per_byte_fee: Cycles::new(400),
@@ -473,6 +489,8 @@ impl CyclesAccountManagerConfig {
             // - zero cost if called from NNS subnet
             // - non-zero cost if called from any other subnet which is not NNS subnet
             ecdsa_signature_fee: ECDSA_SIGNATURE_FEE,
+            schnorr_signature_fee: SCHNORR_SIGNATURE_FEE,
+            vetkd_fee: VETKD_FEE,
             http_request_linear_baseline_fee: Cycles::new(0),
             http_request_quadratic_baseline_fee: Cycles::new(0),
             http_request_per_byte_fee: Cycles::new(0),
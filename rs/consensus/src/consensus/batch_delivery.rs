@@ -19,7 +19,7 @@ use ic_interfaces::{
 };
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{debug, error, info, trace, warn, ReplicaLogger};
-use ic_management_canister_types::SetupInitialDKGResponse;
+use ic_management_canister_types::{MasterPublicKeyId, SetupInitialDKGResponse};
 use ic_protobuf::{
     log::consensus_log_entry::v1::ConsensusLogEntry,
     registry::{crypto::v1::PublicKey as PublicKeyProto, subnet::v1::InitialNiDkgTranscriptRecord},
@@ -32,7 +32,7 @@ use ic_types::{
         Block,
     },
     crypto::threshold_sig::ni_dkg::{NiDkgId, NiDkgTag, NiDkgTranscript},
-    messages::{CallbackId, Payload, RejectContext, Response},
+    messages::{CallbackId, Payload, RejectContext, Response, NO_DEADLINE},
     CanisterId, Cycles, Height, PrincipalId, Randomness, ReplicaVersion, SubnetId,
 };
 use std::collections::BTreeMap;
@@ -186,7 +186,10 @@ pub fn deliver_batches(
                     requires_full_state_hash,
                     messages: batch_messages,
                     randomness,
-                    ecdsa_subnet_public_keys: ecdsa_subnet_public_key.into_iter().collect(),
+                    chain_key_subnet_public_keys: ecdsa_subnet_public_key
+                        .into_iter()
+                        .map(|(key_id, public_key)| (MasterPublicKeyId::Ecdsa(key_id), public_key))
+                        .collect(),
                     ecdsa_quadruple_ids: get_quadruple_ids_to_deliver(&block),
                     registry_version: block.context.registry_version,
                     time: block.context.time,
@@ -338,6 +341,7 @@ pub fn generate_responses_to_setup_initial_dkg_calls(
                 originator_reply_callback: callback_id,
                 refund: Cycles::zero(),
                 response_payload,
+                deadline: NO_DEADLINE,
             });
         }
     }
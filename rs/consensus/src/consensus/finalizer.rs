@@ -254,7 +254,7 @@ mod tests {
     use ic_types::messages::Payload;
     use ic_types::{
         crypto::threshold_sig::ni_dkg::{NiDkgId, NiDkgTag, NiDkgTargetId, NiDkgTargetSubnet},
-        messages::{CallbackId, Request},
+        messages::{CallbackId, Request, NO_DEADLINE},
     };
     use ic_types::{CanisterId, Cycles, PrincipalId, RegistryVersion, SubnetId};
     use std::{
@@ -482,6 +482,7 @@ mod tests {
                     method_name: "".to_string(),
                     method_payload: vec![],
                     metadata: None,
+                    deadline: NO_DEADLINE,
                 },
                 nodes_in_target_subnet: BTreeSet::new(),
                 target_id: TARGET_ID,
@@ -36,7 +36,7 @@ use ic_types::{
         canister_threshold_sig::idkg::{IDkgTranscript, InitialIDkgDealings},
         AlgorithmId,
     },
-    messages::{CallbackId, RejectContext},
+    messages::{CallbackId, RejectContext, NO_DEADLINE},
     Height, NodeId, RegistryVersion, SubnetId, Time,
 };
 use std::collections::{BTreeMap, BTreeSet};
@@ -859,6 +859,7 @@ pub(crate) fn get_signing_requests<'a>(
                         context.key_id
                     ),
                 )),
+                deadline: NO_DEADLINE,
             };
             ecdsa_payload.signature_agreements.insert(
                 context.pseudo_random_id,
@@ -915,6 +916,7 @@ pub(crate) fn get_signing_requests<'a>(
                         RejectCode::CanisterError,
                         "Signature request expired",
                     )),
+                    deadline: NO_DEADLINE,
                 };
                 ecdsa_payload.signature_agreements.insert(
                     context.pseudo_random_id,
@@ -7,7 +7,7 @@ use ic_types::{
     crypto::canister_threshold_sig::{
         error::InitialIDkgDealingsValidationError, idkg::InitialIDkgDealings,
     },
-    messages::CallbackId,
+    messages::{CallbackId, NO_DEADLINE},
 };
 
 use crate::ecdsa::pre_signer::EcdsaTranscriptBuilder;
@@ -78,6 +78,7 @@ pub(super) fn make_reshare_dealings_response(
                             }
                             .encode(),
                         ),
+                        deadline: NO_DEADLINE,
                     });
                 }
             }
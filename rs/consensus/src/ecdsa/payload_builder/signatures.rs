@@ -7,7 +7,7 @@ use ic_replicated_state::metadata_state::subnet_call_context_manager::SignWithEc
 use ic_types::{
     consensus::ecdsa,
     crypto::canister_threshold_sig::ExtendedDerivationPath,
-    messages::{CallbackId, RejectContext},
+    messages::{CallbackId, RejectContext, NO_DEADLINE},
     Time,
 };
 use phantom_newtype::Id;
@@ -71,6 +71,7 @@ pub(crate) fn update_signature_agreements(
                 }
                 .encode(),
             ),
+            deadline: NO_DEADLINE,
         };
 
         completed.insert(
@@ -172,6 +173,7 @@ pub(crate) fn update_signature_agreements_improved_latency(
                         context.key_id
                     ),
                 )),
+                deadline: NO_DEADLINE,
             };
             payload.signature_agreements.insert(
                 context.pseudo_random_id,
@@ -201,6 +203,7 @@ pub(crate) fn update_signature_agreements_improved_latency(
                     RejectCode::CanisterError,
                     "Signature request expired",
                 )),
+                deadline: NO_DEADLINE,
             };
             payload.signature_agreements.insert(
                 context.pseudo_random_id,
@@ -234,6 +237,7 @@ pub(crate) fn update_signature_agreements_improved_latency(
                 }
                 .encode(),
             ),
+            deadline: NO_DEADLINE,
         };
         payload.signature_agreements.insert(
             context.pseudo_random_id,
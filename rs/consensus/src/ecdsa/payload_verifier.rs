@@ -622,7 +622,7 @@ mod test {
     use ic_types::{
         consensus::ecdsa::{CompletedSignature, TranscriptAttributes},
         crypto::AlgorithmId,
-        messages::CallbackId,
+        messages::{CallbackId, NO_DEADLINE},
         Height,
     };
     use std::{collections::BTreeSet, str::FromStr};
@@ -1226,6 +1226,7 @@ mod test {
             response_payload: ic_types::messages::Payload::Data(
                 SignWithECDSAReply { signature: vec![] }.encode(),
             ),
+            deadline: NO_DEADLINE,
         });
 
         // Insert agreement for incomplete context
@@ -421,7 +421,7 @@ pub fn derive_public_key(
     derivation_path: &DerivationPath,
 ) -> ThresholdEcdsaResult<EcdsaPublicKey> {
     let raw_master_pk = match master_public_key.algorithm_id {
-        AlgorithmId::EcdsaSecp256k1 => {
+        AlgorithmId::EcdsaSecp256k1 | AlgorithmId::ThresholdSchnorrBip340 => {
             EccPoint::deserialize(EccCurveType::K256, &master_public_key.public_key)?
         }
         AlgorithmId::EcdsaP256 => {
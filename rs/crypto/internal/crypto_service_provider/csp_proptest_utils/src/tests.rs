@@ -4,7 +4,7 @@ use crate::common::MAX_ALGORITHM_ID_INDEX;
 #[test]
 fn should_be_maximal_algorithm_index_id_to_ensure_all_variants_covered_by_strategy() {
     assert_eq!(
-        AlgorithmId::ThresholdSchnorrBip340,
+        AlgorithmId::ThresholdEd25519,
         AlgorithmId::from(MAX_ALGORITHM_ID_INDEX)
     );
     assert_eq!(
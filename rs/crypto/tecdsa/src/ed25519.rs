@@ -0,0 +1,187 @@
+//! Key derivation for threshold Ed25519 master public keys.
+//!
+//! Ed25519 keys are derived additively, similar to SLIP-0010: for each
+//! derivation index an offset is computed with HKDF-SHA-512 from the current
+//! public key, the index and the current chain code, and the corresponding
+//! multiple of the base point is added to the public key. The master secret
+//! key can be derived in the same way by adding the accumulated offset.
+
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_TABLE,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+};
+use ic_crypto_internal_hmac::{Hmac, Sha512};
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+
+const PUBLIC_KEY_SIZE: usize = 32;
+const CHAIN_CODE_SIZE: usize = 32;
+const HKDF_INFO: &[u8] = b"Ed25519";
+
+/// The result of deriving an Ed25519 public key.
+pub(crate) struct DerivedKey {
+    pub public_key: [u8; PUBLIC_KEY_SIZE],
+    pub offset: Scalar,
+    pub chain_code: [u8; CHAIN_CODE_SIZE],
+}
+
+/// Derives the Ed25519 public key from the encoded `master_public_key` for
+/// the given `extended_derivation_path`, starting from an all-zero chain code.
+pub(crate) fn derive_public_key(
+    master_public_key: &[u8],
+    extended_derivation_path: &ExtendedDerivationPath,
+) -> Result<DerivedKey, String> {
+    if master_public_key.len() != PUBLIC_KEY_SIZE {
+        return Err(format!(
+            "Ed25519 public key must be {} bytes, got {}",
+            PUBLIC_KEY_SIZE,
+            master_public_key.len()
+        ));
+    }
+    let mut point = CompressedEdwardsY::from_slice(master_public_key)
+        .decompress()
+        .ok_or_else(|| "Invalid Ed25519 public key encoding".to_string())?;
+
+    let mut chain_code = [0u8; CHAIN_CODE_SIZE];
+    let mut offset = Scalar::zero();
+
+    let path = std::iter::once(extended_derivation_path.caller.to_vec())
+        .chain(extended_derivation_path.derivation_path.iter().cloned());
+    for index in path {
+        let (next_point, next_offset, next_chain_code) = derive_child(&point, &chain_code, &index);
+        point = next_point;
+        offset += next_offset;
+        chain_code = next_chain_code;
+    }
+
+    Ok(DerivedKey {
+        public_key: point.compress().to_bytes(),
+        offset,
+        chain_code,
+    })
+}
+
+fn derive_child(
+    point: &EdwardsPoint,
+    chain_code: &[u8; CHAIN_CODE_SIZE],
+    index: &[u8],
+) -> (EdwardsPoint, Scalar, [u8; CHAIN_CODE_SIZE]) {
+    let mut ikm = Vec::with_capacity(PUBLIC_KEY_SIZE + index.len());
+    ikm.extend_from_slice(point.compress().as_bytes());
+    ikm.extend_from_slice(index);
+
+    let okm = hkdf_sha512(chain_code, &ikm, HKDF_INFO);
+
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(&okm[..64]);
+    let offset = Scalar::from_bytes_mod_order_wide(&wide);
+
+    let mut next_chain_code = [0u8; CHAIN_CODE_SIZE];
+    next_chain_code.copy_from_slice(&okm[64..]);
+
+    (
+        point + &offset * &ED25519_BASEPOINT_TABLE,
+        offset,
+        next_chain_code,
+    )
+}
+
+/// HKDF (RFC 5869) with SHA-512, producing 96 bytes of output keying material.
+fn hkdf_sha512(salt: &[u8], ikm: &[u8], info: &[u8]) -> [u8; 96] {
+    let prk = Hmac::<Sha512>::hmac(salt, ikm);
+
+    let mut okm = [0u8; 96];
+    let mut previous: Vec<u8> = vec![];
+    for (i, block) in okm.chunks_mut(64).enumerate() {
+        let mut hmac = Hmac::<Sha512>::new(&prk);
+        hmac.write(&previous);
+        hmac.write(info);
+        hmac.write(&[(i + 1) as u8]);
+        previous = hmac.finish();
+        block.copy_from_slice(&previous[..block.len()]);
+    }
+    okm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_types::PrincipalId;
+
+    // A canonical little-endian Ed25519 scalar.
+    const MASTER_SECRET_SCALAR: &str =
+        "3b7a9c2e5d1f40a8b6c4e2d0f1a3b5c7d9e0f2a4b6c8d0e1f3a5b7c9d1e3f506";
+    const MASTER_PUBLIC_KEY: &str =
+        "a99bcbf730e395de7995197454e389f7f4b827a0c2a0864a1dae6b990e27c939";
+
+    fn master_secret_scalar() -> Scalar {
+        let bytes: [u8; 32] = hex::decode(MASTER_SECRET_SCALAR)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        Scalar::from_canonical_bytes(bytes).unwrap()
+    }
+
+    fn path(derivation_path: &[&[u8]]) -> ExtendedDerivationPath {
+        ExtendedDerivationPath {
+            caller: PrincipalId::new_anonymous(),
+            derivation_path: derivation_path.iter().map(|index| index.to_vec()).collect(),
+        }
+    }
+
+    #[test]
+    fn should_match_master_public_key() {
+        let public_key = (&master_secret_scalar() * &ED25519_BASEPOINT_TABLE)
+            .compress()
+            .to_bytes();
+        assert_eq!(hex::encode(public_key), MASTER_PUBLIC_KEY);
+    }
+
+    #[test]
+    fn should_derive_expected_keys() {
+        let master_public_key = hex::decode(MASTER_PUBLIC_KEY).unwrap();
+
+        for (derivation_path, public_key, chain_code) in [
+            (
+                vec![],
+                "7c9144c22cf25409454703fdc1e83ab93d0d272056d54a0e7ea7efcf8dce3abf",
+                "c37dbee85dfb8899bc5e5040a51ac0094b966ec56d3bd094ed341e71c4f24f2e",
+            ),
+            (
+                vec![&[1u8, 2, 3][..], &[4u8][..]],
+                "f0cb0db997d5f4bfac017a6734f0e37be1f59d2392f5bdb3a594c5099ac9e3c5",
+                "4385aa3255d5a681dadaf38eec677a50c481e05c8cf624cec426e594a2a04bab",
+            ),
+        ] {
+            let derived = derive_public_key(&master_public_key, &path(&derivation_path)).unwrap();
+            assert_eq!(hex::encode(derived.public_key), public_key);
+            assert_eq!(hex::encode(derived.chain_code), chain_code);
+        }
+    }
+
+    #[test]
+    fn should_derive_secret_key_matching_public_key() {
+        let master_secret = master_secret_scalar();
+        let master_public_key = hex::decode(MASTER_PUBLIC_KEY).unwrap();
+
+        let derived =
+            derive_public_key(&master_public_key, &path(&[&[1, 2, 3][..], &[4][..]])).unwrap();
+        let derived_secret = master_secret + derived.offset;
+
+        assert_eq!(
+            hex::encode(derived_secret.to_bytes()),
+            "366cb0a56a833254c1284a718c8339708eeb45eac2cc8fba8a13260edfbdf405"
+        );
+        assert_eq!(
+            (&derived_secret * &ED25519_BASEPOINT_TABLE)
+                .compress()
+                .to_bytes(),
+            derived.public_key
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_master_public_key() {
+        assert!(derive_public_key(&[0; 31], &path(&[])).is_err());
+    }
+}
@@ -1,8 +1,13 @@
 use ic_crypto_internal_threshold_sig_ecdsa::ThresholdEcdsaDerivePublicKeyError;
-use ic_types::crypto::canister_threshold_sig::error::ThresholdEcdsaGetPublicKeyError;
+use ic_types::crypto::canister_threshold_sig::error::{
+    ThresholdEcdsaGetPublicKeyError, ThresholdSchnorrGetPublicKeyError,
+};
 use ic_types::crypto::canister_threshold_sig::{
-    EcdsaPublicKey, ExtendedDerivationPath, MasterEcdsaPublicKey,
+    EcdsaPublicKey, ExtendedDerivationPath, MasterEcdsaPublicKey, MasterPublicKey, PublicKey,
 };
+use ic_types::crypto::AlgorithmId;
+
+mod ed25519;
 
 /// Derives the ECDSA public key from the specified `master_public_key` for
 /// the given `extended_derivation_path`.
@@ -23,3 +28,69 @@ pub fn derive_tecdsa_public_key(
         }
     })
 }
+
+/// Derives the Schnorr public key from the specified threshold Schnorr
+/// `master_public_key` for the given `extended_derivation_path`.
+///
+/// BIP340 keys use the same BIP32-style derivation as threshold ECDSA keys
+/// on secp256k1, Ed25519 keys use additive derivation based on HKDF-SHA-512.
+pub fn derive_threshold_schnorr_public_key(
+    master_public_key: &MasterPublicKey,
+    extended_derivation_path: &ExtendedDerivationPath,
+) -> Result<PublicKey, ThresholdSchnorrGetPublicKeyError> {
+    match master_public_key.algorithm_id {
+        AlgorithmId::ThresholdSchnorrBip340 => {
+            ic_crypto_internal_threshold_sig_ecdsa::derive_public_key(
+                master_public_key,
+                &extended_derivation_path.into(),
+            )
+            .map_err(|e| match e {
+                ThresholdEcdsaDerivePublicKeyError::InvalidArgument(s) => {
+                    ThresholdSchnorrGetPublicKeyError::InvalidArgument(s)
+                }
+                ThresholdEcdsaDerivePublicKeyError::InternalError(e) => {
+                    ThresholdSchnorrGetPublicKeyError::InternalError(format!("{:?}", e))
+                }
+            })
+        }
+        AlgorithmId::ThresholdEd25519 => {
+            let derived =
+                ed25519::derive_public_key(&master_public_key.public_key, extended_derivation_path)
+                    .map_err(ThresholdSchnorrGetPublicKeyError::InvalidArgument)?;
+            Ok(PublicKey {
+                algorithm_id: AlgorithmId::ThresholdEd25519,
+                public_key: derived.public_key.to_vec(),
+                chain_key: derived.chain_code.to_vec(),
+            })
+        }
+        unsupported => Err(ThresholdSchnorrGetPublicKeyError::UnsupportedAlgorithm(
+            unsupported,
+        )),
+    }
+}
+
+/// Derives the Ed25519 secret scalar corresponding to the public key
+/// returned by [`derive_threshold_schnorr_public_key`] for an Ed25519 master
+/// key.
+///
+/// The master secret key is given as a canonical little-endian scalar. This
+/// is only meaningful in test environments where the master secret key is
+/// known; on the IC the master secret key is never reconstructed.
+pub fn derive_ed25519_secret_scalar(
+    master_secret_scalar: &[u8; 32],
+    extended_derivation_path: &ExtendedDerivationPath,
+) -> Result<[u8; 32], ThresholdSchnorrGetPublicKeyError> {
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
+
+    let master_secret = Scalar::from_canonical_bytes(*master_secret_scalar).ok_or_else(|| {
+        ThresholdSchnorrGetPublicKeyError::InvalidArgument(
+            "Ed25519 secret scalar is not canonical".to_string(),
+        )
+    })?;
+    let master_public_key = (&master_secret * &ED25519_BASEPOINT_TABLE)
+        .compress()
+        .to_bytes();
+    let derived = ed25519::derive_public_key(&master_public_key, extended_derivation_path)
+        .map_err(ThresholdSchnorrGetPublicKeyError::InvalidArgument)?;
+    Ok((master_secret + derived.offset).to_bytes())
+}
@@ -1043,6 +1043,8 @@ impl EcdsaSubnetConfig {
                     idkg_key_rotation_period_ms: key_rotation_period
                         .map(|key_rotation_period| key_rotation_period.as_millis() as u64),
                 }),
+                schnorr_config: None,
+                vetkd_config: None,
             },
         }
     }
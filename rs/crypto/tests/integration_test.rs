@@ -744,7 +744,7 @@ fn should_fail_check_keys_with_registry_if_no_idkg_key_in_registry() {
 /// Ensure the structs are consistent and then update the test below.
 #[test]
 fn algorithm_id_should_match_algorithm_id_proto() {
-    let algorithm_id_variants = 19;
+    let algorithm_id_variants = 20;
     assert_eq!(AlgorithmId::iter().count(), algorithm_id_variants);
 
     for i in 0..algorithm_id_variants {
@@ -825,6 +825,10 @@ fn algorithm_id_should_match_algorithm_id_proto() {
         AlgorithmId::ThresholdSchnorrBip340 as i32,
         AlgorithmIdProto::ThresholdSchnorrBip340 as i32
     );
+    assert_eq!(
+        AlgorithmId::ThresholdEd25519 as i32,
+        AlgorithmIdProto::ThresholdEd25519 as i32
+    );
 }
 
 #[test]
@@ -0,0 +1,96 @@
+//! Key derivation for vetKD master public keys on BLS12-381.
+//!
+//! The derived public key for a canister and derivation path is obtained by
+//! adding a multiple of the G2 generator, computed from the canister id and
+//! the derivation path, to the master public key.
+
+use crate::VetKdDeriveKeyError;
+use ic_crypto_internal_bls12_381_vetkd::{
+    DerivationPath, DerivedPublicKey, EncryptedKey, EncryptedKeyShare, G2Affine, Scalar,
+    TransportPublicKey,
+};
+use ic_types::crypto::canister_threshold_sig::ExtendedDerivationPath;
+use rand::{CryptoRng, RngCore};
+
+fn derivation_path(extended_derivation_path: &ExtendedDerivationPath) -> DerivationPath {
+    DerivationPath::new(
+        extended_derivation_path.caller.as_slice(),
+        &extended_derivation_path.derivation_path,
+    )
+}
+
+fn deserialize_master_public_key(
+    master_public_key: &[u8],
+) -> Result<G2Affine, VetKdDeriveKeyError> {
+    G2Affine::deserialize(&master_public_key)
+        .map_err(|_| VetKdDeriveKeyError::InvalidMasterPublicKey)
+}
+
+fn deserialize_encryption_public_key(
+    encryption_public_key: &[u8],
+) -> Result<TransportPublicKey, VetKdDeriveKeyError> {
+    TransportPublicKey::deserialize(encryption_public_key)
+        .map_err(|_| VetKdDeriveKeyError::InvalidEncryptionPublicKey)
+}
+
+fn deserialize_master_secret_key(master_secret_key: &[u8]) -> Result<Scalar, VetKdDeriveKeyError> {
+    Scalar::deserialize(&master_secret_key).map_err(|_| VetKdDeriveKeyError::InvalidMasterSecretKey)
+}
+
+/// Returns the encoded master public key for the given master secret key.
+pub(crate) fn master_public_key(
+    master_secret_key: &[u8],
+) -> Result<[u8; G2Affine::BYTES], VetKdDeriveKeyError> {
+    let master_secret_key = deserialize_master_secret_key(master_secret_key)?;
+    Ok(G2Affine::from(G2Affine::generator() * &master_secret_key).serialize())
+}
+
+/// Checks that `encryption_public_key` is the encoding of a G1 point.
+pub(crate) fn validate_encryption_public_key(
+    encryption_public_key: &[u8],
+) -> Result<(), VetKdDeriveKeyError> {
+    deserialize_encryption_public_key(encryption_public_key).map(|_| ())
+}
+
+/// Derives the vetKD public key from the encoded `master_public_key` for the
+/// given `extended_derivation_path`.
+pub(crate) fn derive_public_key(
+    master_public_key: &[u8],
+    extended_derivation_path: &ExtendedDerivationPath,
+) -> Result<[u8; DerivedPublicKey::BYTES], VetKdDeriveKeyError> {
+    let master_public_key = deserialize_master_public_key(master_public_key)?;
+    Ok(DerivedPublicKey::compute_derived_key(
+        &master_public_key,
+        &derivation_path(extended_derivation_path),
+    )
+    .serialize())
+}
+
+/// Derives the vetKD key for the given `extended_derivation_path` and
+/// `derivation_id` from the master secret key and encrypts it under
+/// `encryption_public_key`.
+pub(crate) fn derive_encrypted_key<R: RngCore + CryptoRng>(
+    master_secret_key: &[u8],
+    extended_derivation_path: &ExtendedDerivationPath,
+    derivation_id: &[u8],
+    encryption_public_key: &[u8],
+    rng: &mut R,
+) -> Result<[u8; EncryptedKey::BYTES], VetKdDeriveKeyError> {
+    let master_secret_key = deserialize_master_secret_key(master_secret_key)?;
+    let master_public_key = G2Affine::from(G2Affine::generator() * &master_secret_key);
+    let transport_public_key = deserialize_encryption_public_key(encryption_public_key)?;
+    // A share created with the master secret key is the encrypted key itself,
+    // so it can be reinterpreted without any interpolation.
+    let share = EncryptedKeyShare::create(
+        rng,
+        &master_public_key,
+        &master_secret_key,
+        &transport_public_key,
+        &derivation_path(extended_derivation_path),
+        derivation_id,
+    );
+    let encrypted_key = EncryptedKey::deserialize(share.serialize()).map_err(|_| {
+        VetKdDeriveKeyError::InternalError("vetKD encrypted key is invalid".to_string())
+    })?;
+    Ok(encrypted_key.serialize())
+}
@@ -0,0 +1,100 @@
+//! vetKD key derivation for the management canister.
+//!
+//! Provides the derivation of vetKD public keys from a subnet's master public
+//! key and, for test environments where the master secret key is known, the
+//! derivation of encrypted vetKD keys.
+
+use ic_types::crypto::canister_threshold_sig::{ExtendedDerivationPath, MasterPublicKey};
+use ic_types::crypto::AlgorithmId;
+use rand::{CryptoRng, RngCore};
+
+mod derivation;
+
+/// Errors that can occur when deriving vetKD keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VetKdDeriveKeyError {
+    UnsupportedAlgorithm(AlgorithmId),
+    InvalidMasterPublicKey,
+    InvalidMasterSecretKey,
+    InvalidEncryptionPublicKey,
+    InternalError(String),
+}
+
+impl std::fmt::Display for VetKdDeriveKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedAlgorithm(algorithm_id) => {
+                write!(f, "vetKD does not support algorithm {:?}", algorithm_id)
+            }
+            Self::InvalidMasterPublicKey => {
+                write!(f, "vetKD master public key is not a valid G2 point")
+            }
+            Self::InvalidMasterSecretKey => {
+                write!(f, "vetKD master secret key is not a valid scalar")
+            }
+            Self::InvalidEncryptionPublicKey => {
+                write!(f, "vetKD encryption public key is not a valid G1 point")
+            }
+            Self::InternalError(err) => write!(f, "vetKD internal error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for VetKdDeriveKeyError {}
+
+/// Derives the vetKD public key from the specified vetKD `master_public_key`
+/// for the given `extended_derivation_path`.
+pub fn derive_vetkd_public_key(
+    master_public_key: &MasterPublicKey,
+    extended_derivation_path: &ExtendedDerivationPath,
+) -> Result<Vec<u8>, VetKdDeriveKeyError> {
+    match master_public_key.algorithm_id {
+        AlgorithmId::ThresBls12_381 => {
+            derivation::derive_public_key(&master_public_key.public_key, extended_derivation_path)
+                .map(|key| key.to_vec())
+        }
+        unsupported => Err(VetKdDeriveKeyError::UnsupportedAlgorithm(unsupported)),
+    }
+}
+
+/// Checks that `encryption_public_key` is a valid transport public key, i.e.,
+/// the encoding of a BLS12-381 G1 point that the encrypted vetKD key can be
+/// encrypted under.
+pub fn validate_encryption_public_key(
+    encryption_public_key: &[u8],
+) -> Result<(), VetKdDeriveKeyError> {
+    derivation::validate_encryption_public_key(encryption_public_key)
+}
+
+/// Returns the encoded vetKD master public key for the given master secret
+/// key, which is given as a big-endian BLS12-381 scalar.
+///
+/// This is only meaningful in test environments where the master secret key
+/// is known.
+pub fn vetkd_master_public_key(master_secret_key: &[u8]) -> Result<Vec<u8>, VetKdDeriveKeyError> {
+    derivation::master_public_key(master_secret_key).map(|key| key.to_vec())
+}
+
+/// Derives the vetKD key for the given `extended_derivation_path` and
+/// `derivation_id` and encrypts it under `encryption_public_key`.
+///
+/// The master secret key is given as a big-endian BLS12-381 scalar. This is
+/// only meaningful in test environments where the master secret key is
+/// known; on the IC the encrypted key is combined from the shares of the
+/// nodes.
+pub fn derive_vetkd_encrypted_key<R: RngCore + CryptoRng>(
+    master_secret_key: &[u8],
+    extended_derivation_path: &ExtendedDerivationPath,
+    derivation_id: &[u8],
+    encryption_public_key: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, VetKdDeriveKeyError> {
+    derivation::derive_encrypted_key(
+        master_secret_key,
+        extended_derivation_path,
+        derivation_id,
+        encryption_public_key,
+        rng,
+    )
+    .map(|key| key.to_vec())
+}
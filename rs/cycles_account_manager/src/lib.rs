@@ -634,6 +634,16 @@ impl CyclesAccountManager {
         self.scale_cost(self.config.ecdsa_signature_fee, subnet_size)
     }
 
+    /// Amount to charge for a Schnorr signature.
+    pub fn schnorr_signature_fee(&self, subnet_size: usize) -> Cycles {
+        self.scale_cost(self.config.schnorr_signature_fee, subnet_size)
+    }
+
+    /// Amount to charge for a vetKD key derivation.
+    pub fn vetkd_fee(&self, subnet_size: usize) -> Cycles {
+        self.scale_cost(self.config.vetkd_fee, subnet_size)
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     //
     // Storage
@@ -886,6 +896,8 @@ impl CyclesAccountManager {
             | CyclesUseCase::RequestAndResponseTransmission
             | CyclesUseCase::CanisterCreation
             | CyclesUseCase::ECDSAOutcalls
+            | CyclesUseCase::SchnorrOutcalls
+            | CyclesUseCase::VetKd
             | CyclesUseCase::HTTPOutcalls
             | CyclesUseCase::DeletedCanisters
             | CyclesUseCase::NonConsumed
@@ -1051,22 +1063,38 @@ impl CyclesAccountManager {
     }
 
     /// Charges a canister for its resource allocation and usage for the
-    /// duration specified. If fees were successfully charged, then returns
-    /// Ok() else returns Err(CanisterOutOfCyclesError).
+    /// duration specified, including the memory taken by its snapshots. If
+    /// fees were successfully charged, then returns Ok() else returns
+    /// Err(CanisterOutOfCyclesError).
     pub fn charge_canister_for_resource_allocation_and_usage(
         &self,
         log: &ReplicaLogger,
         canister: &mut CanisterState,
         duration_since_last_charge: Duration,
+        snapshots_memory_usage: NumBytes,
         subnet_size: usize,
     ) -> Result<(), CanisterOutOfCyclesError> {
-        for (use_case, rate) in self.idle_cycles_burned_rate_by_resource(
-            canister.memory_allocation(),
-            canister.memory_usage(),
-            canister.message_memory_usage(),
-            canister.compute_allocation(),
-            subnet_size,
-        ) {
+        // Snapshots are not covered by the memory allocation of the canister,
+        // so their memory is charged in addition to it.
+        let snapshots_rate = (
+            CyclesUseCase::Memory,
+            self.memory_cost(
+                snapshots_memory_usage,
+                Duration::from_secs(SECONDS_PER_DAY as u64),
+                subnet_size,
+            ),
+        );
+        for (use_case, rate) in self
+            .idle_cycles_burned_rate_by_resource(
+                canister.memory_allocation(),
+                canister.memory_usage(),
+                canister.message_memory_usage(),
+                canister.compute_allocation(),
+                subnet_size,
+            )
+            .into_iter()
+            .chain(std::iter::once(snapshots_rate))
+        {
             let cycles = rate * duration_since_last_charge.as_secs() / SECONDS_PER_DAY;
 
             // Charging for resources can charge all the way down to zero cycles.
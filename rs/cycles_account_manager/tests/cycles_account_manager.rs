@@ -73,6 +73,7 @@ fn test_can_charge_application_subnets() {
                             &log,
                             &mut canister,
                             duration,
+                            NumBytes::from(0),
                             subnet_size,
                         )
                         .unwrap();
@@ -82,6 +83,47 @@ fn test_can_charge_application_subnets() {
     })
 }
 
+#[test]
+fn test_charges_for_snapshots_memory() {
+    with_test_replica_logger(|log| {
+        let subnet_size = SMALL_APP_SUBNET_MAX_SIZE;
+        let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+        let initial_cycles = Cycles::new(1_000_000_000_000_000);
+        let mut canister = new_canister_state(
+            canister_test_id(1),
+            canister_test_id(2).get(),
+            initial_cycles,
+            NumSeconds::from(0),
+        );
+        // Snapshots are charged even if they exceed the memory allocation.
+        canister.system_state.memory_allocation =
+            MemoryAllocation::try_from(NumBytes::from(1 << 20)).unwrap();
+        let duration = Duration::from_secs(24 * 3600);
+        let snapshots_memory_usage = NumBytes::from(1 << 30);
+
+        cycles_account_manager
+            .charge_canister_for_resource_allocation_and_usage(
+                &log,
+                &mut canister,
+                duration,
+                snapshots_memory_usage,
+                subnet_size,
+            )
+            .unwrap();
+
+        assert_eq!(
+            canister.system_state.balance(),
+            initial_cycles
+                - cycles_account_manager.memory_cost(
+                    NumBytes::from(1 << 20),
+                    duration,
+                    subnet_size
+                )
+                - cycles_account_manager.memory_cost(snapshots_memory_usage, duration, subnet_size)
+        );
+    })
+}
+
 #[test]
 fn withdraw_cycles_with_not_enough_balance_returns_error() {
     let initial_cycles = Cycles::new(100_000);
@@ -333,6 +375,7 @@ fn charging_removes_canisters_with_insufficient_balance() {
                 &log,
                 &mut canister,
                 Duration::from_secs(1),
+                NumBytes::from(0),
                 subnet_size,
             )
             .unwrap();
@@ -351,6 +394,7 @@ fn charging_removes_canisters_with_insufficient_balance() {
                 &log,
                 &mut canister,
                 Duration::from_secs(1),
+                NumBytes::from(0),
                 subnet_size,
             )
             .unwrap_err();
@@ -369,6 +413,7 @@ fn charging_removes_canisters_with_insufficient_balance() {
                 &log,
                 &mut canister,
                 Duration::from_secs(1),
+                NumBytes::from(0),
                 subnet_size,
             )
             .unwrap_err();
@@ -1,6 +1,7 @@
 //! Standalone interface for testing application canisters.
 
 use crate::message::{msg_stream_from_file, Message};
+use crate::scenario::{Scenario, ScenarioFormat, ScenarioRunner};
 use hex::encode;
 use ic_config::{subnet_config::SubnetConfig, Config};
 use ic_cycles_account_manager::CyclesAccountManager;
@@ -38,6 +39,7 @@ use ic_types::{
     messages::{MessageId, SignedIngress},
     replica_config::ReplicaConfig,
     time, CanisterId, NodeId, NumInstructions, PrincipalId, Randomness, RegistryVersion, SubnetId,
+    Time,
 };
 use rand::distributions::{Distribution, Uniform};
 use slog::{Drain, Logger};
@@ -48,6 +50,7 @@ use std::sync::{Arc, Mutex};
 use std::{thread::sleep, time::Duration};
 
 mod message;
+mod scenario;
 
 // drun will panic if it takes more than this many batches
 // until a response for a message is received
@@ -64,19 +67,49 @@ pub struct DrunOptions {
     pub subnet_type: SubnetType,
 }
 
+/// The input of a run: either a line-based message file or a structured scenario.
+enum Input {
+    Messages(Box<dyn Iterator<Item = Result<Message, String>>>),
+    Scenario(Scenario),
+}
+
+/// The time of the batches delivered to message routing: the system time, moved forward by as
+/// much as a scenario has advanced it.
+#[derive(Default)]
+pub(crate) struct Clock {
+    offset: Duration,
+}
+
+impl Clock {
+    pub(crate) fn now(&self) -> Time {
+        time::current_time() + self.offset
+    }
+
+    pub(crate) fn advance(&mut self, duration: Duration) {
+        self.offset += duration;
+    }
+}
+
 /// Deliver a single message to the Message Routing layer
 fn deliver_message(
     msg: SignedIngress,
     message_routing: &dyn MessageRouting,
     ingress_hist_reader: &dyn IngressHistoryReader,
     extra_batches: u64,
+    clock: &Clock,
 ) {
     let message_id = msg.id();
 
-    let _ = execute_ingress_message(message_routing, msg, &message_id, ingress_hist_reader);
+    let _ = execute_ingress_message(
+        message_routing,
+        msg,
+        &message_id,
+        ingress_hist_reader,
+        clock,
+    );
     // print result after waiting, to not interleave the result
     // with debug.print messages from subsequent calls. revise after DFN-1269.
-    wait_extra_batches(message_routing, extra_batches);
+    wait_extra_batches(message_routing, extra_batches, clock);
     print_ingress_result(&message_id, ingress_hist_reader);
 }
 
@@ -182,7 +215,10 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
         subnet_id,
     };
 
-    let mut msg_stream = msg_stream_from_file(&msg_filename)?;
+    let input = match ScenarioFormat::from_path(&msg_filename) {
+        Some(format) => Input::Scenario(Scenario::from_file(&msg_filename, format)?),
+        None => Input::Messages(Box::new(msg_stream_from_file(&msg_filename)?)),
+    };
     let log = match log_file {
         Some(log_file) => setup_logger(log_file),
         None => slog::Logger::root(slog::Discard, slog::o!()),
@@ -249,6 +285,24 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
         MaliciousFlags::default(),
     );
 
+    let clock = Clock::default();
+    let mut msg_stream = match input {
+        Input::Messages(msg_stream) => msg_stream,
+        Input::Scenario(scenario) => {
+            return ScenarioRunner {
+                message_routing: &message_routing,
+                ingress_hist_reader: ingress_hist_reader.as_ref(),
+                query_handler: query_handler.as_ref(),
+                state_reader: state_manager.as_ref(),
+                extra_batches,
+                clock,
+                canisters: BTreeMap::new(),
+                nonce: 0,
+            }
+            .run(scenario)
+        }
+    };
+
     msg_stream.try_for_each(|parse_result| {
         parse_result.map(|msg| match msg {
             Message::Install(msg) => {
@@ -257,6 +311,7 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
                     &message_routing,
                     ingress_hist_reader.as_ref(),
                     extra_batches,
+                    &clock,
                 );
             }
 
@@ -277,6 +332,7 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
                     &message_routing,
                     ingress_hist_reader.as_ref(),
                     extra_batches,
+                    &clock,
                 );
             }
             Message::Create(msg) => {
@@ -285,6 +341,7 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
                     &message_routing,
                     ingress_hist_reader.as_ref(),
                     extra_batches,
+                    &clock,
                 );
             }
         })
@@ -334,7 +391,11 @@ fn get_random_seed() -> [u8; 32] {
     seed.try_into().unwrap()
 }
 
-fn build_batch(message_routing: &dyn MessageRouting, msgs: Vec<SignedIngress>) -> Batch {
+fn build_batch(
+    message_routing: &dyn MessageRouting,
+    msgs: Vec<SignedIngress>,
+    clock: &Clock,
+) -> Batch {
     Batch {
         batch_number: message_routing.expected_batch_height(),
         requires_full_state_hash: !msgs.is_empty(),
@@ -343,10 +404,10 @@ fn build_batch(message_routing: &dyn MessageRouting, msgs: Vec<SignedIngress>) -
             ..BatchMessages::default()
         },
         randomness: Randomness::from(get_random_seed()),
-        ecdsa_subnet_public_keys: BTreeMap::new(),
+        chain_key_subnet_public_keys: BTreeMap::new(),
         ecdsa_quadruple_ids: BTreeMap::new(),
         registry_version: RegistryVersion::from(1),
-        time: time::current_time(),
+        time: clock.now(),
         consensus_responses: vec![],
         blockmaker_metrics: BlockmakerMetrics::new_for_test(),
     }
@@ -360,8 +421,9 @@ fn execute_ingress_message(
     msg: SignedIngress,
     msg_id: &MessageId,
     ingress_history: &dyn IngressHistoryReader,
+    clock: &Clock,
 ) -> Result<WasmResult, UserError> {
-    let mut batch = build_batch(message_routing, vec![msg]);
+    let mut batch = build_batch(message_routing, vec![msg], clock);
     for _ in 0..MAX_BATCHES_UNTIL_RESPONSE {
         // In the first batch we try to send the ingress message itself. If it fails, we
         // repeat with the same batch.
@@ -372,7 +434,7 @@ fn execute_ingress_message(
         // potential inter-canister messages that the ingress message may have
         // triggered.
         if message_routing.deliver_batch(batch.clone()).is_ok() {
-            batch = build_batch(message_routing, vec![])
+            batch = build_batch(message_routing, vec![], clock)
         }
         sleep(WAIT_PER_BATCH);
 
@@ -418,10 +480,10 @@ fn execute_ingress_message(
 ///
 /// This is a temporary measure until DFN-1269 is resolved. In that ticket, we
 /// will actually try to wait until all messages have been executed.
-fn wait_extra_batches(message_routing: &dyn MessageRouting, extra_batches: u64) {
+fn wait_extra_batches(message_routing: &dyn MessageRouting, extra_batches: u64, clock: &Clock) {
     for _ in 0..extra_batches {
         loop {
-            let batch = build_batch(message_routing, vec![]);
+            let batch = build_batch(message_routing, vec![], clock);
             let ok = message_routing.deliver_batch(batch).is_ok();
             sleep(WAIT_PER_BATCH);
             if ok {
@@ -139,7 +139,10 @@ fn get_arg_matches() -> ArgMatches {
             Arg::new(ARG_MESSAGES)
                 .required(true)
                 .value_name("Query/Ingress Messages")
-                .help("Text file containing one message per line."),
+                .help(
+                    "Text file containing one message per line, or a .json/.yaml scenario file \
+                     whose expectations are checked.",
+                ),
         )
         .arg(
             Arg::new(ARG_LOG_FILE)
@@ -192,7 +192,7 @@ fn parse_message(s: &str, nonce: u64) -> Result<Message, String> {
     }
 }
 
-fn parse_canister_id(canister_id: &str) -> Result<CanisterId, String> {
+pub(crate) fn parse_canister_id(canister_id: &str) -> Result<CanisterId, String> {
     use std::str::FromStr;
     match PrincipalId::from_str(canister_id) {
         Ok(id) => Ok(CanisterId::unchecked_from_principal(id)),
@@ -277,7 +277,7 @@ fn validate_method_name(method_name: &str) -> Result<String, String> {
     }
 }
 
-fn parse_octet_string(input_str: &str) -> Result<Vec<u8>, String> {
+pub(crate) fn parse_octet_string(input_str: &str) -> Result<Vec<u8>, String> {
     if input_str.starts_with('"') {
         parse_quoted(input_str)
     } else {
@@ -0,0 +1,646 @@
+//! Structured scenarios: a JSON or YAML alternative to the line-based message file, whose steps
+//! use Candid arguments, refer to canisters by name, manipulate cycles and time, and check the
+//! replies they get.
+
+use crate::{
+    execute_ingress_message,
+    message::{parse_canister_id, parse_octet_string},
+    wait_extra_batches, Clock,
+};
+use candid::{Encode, IDLArgs, TypeEnv};
+use hex::encode;
+use ic_constants::{MAX_INGRESS_TTL, PERMITTED_DRIFT};
+use ic_error_types::{RejectCode, UserError};
+use ic_interfaces::{
+    execution_environment::{IngressHistoryReader, QueryHandler},
+    messaging::MessageRouting,
+};
+use ic_interfaces_state_manager::StateReader;
+use ic_management_canister_types::{
+    self as ic00, CanisterIdRecord, CanisterInstallMode, InstallCodeArgs, Payload,
+    ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs,
+};
+use ic_replicated_state::ReplicatedState;
+use ic_test_utilities::types::messages::SignedIngressBuilder;
+use ic_types::{
+    ingress::WasmResult,
+    messages::{SignedIngress, UserQuery},
+    CanisterId, PrincipalId, UserId,
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+/// The format of a scenario file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ScenarioFormat {
+    Json,
+    Yaml,
+}
+
+impl ScenarioFormat {
+    /// Returns the format of the scenario in the given file, based on its extension, or `None` if
+    /// the file is a line-based message file.
+    pub(crate) fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Some(Self::Json),
+            Some("yaml") | Some("yml") => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Scenario {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Step {
+    /// Creates a canister, optionally binding its ID to a name.
+    Create(Create),
+    /// Installs, reinstalls or upgrades the code of a canister.
+    Install(Install),
+    /// Calls a method of a canister as an ingress message.
+    Update(Call),
+    /// Calls a method of a canister as a query.
+    Query(Call),
+    /// Adds cycles to the balance of a canister.
+    TopUp(TopUp),
+    /// Moves the time of the subsequent batches forward.
+    AdvanceTime(AdvanceTime),
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct Create {
+    name: Option<String>,
+    cycles: Option<u128>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Install {
+    canister: String,
+    wasm: String,
+    #[serde(default)]
+    mode: CanisterInstallMode,
+    arg: Option<String>,
+    raw_arg: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Call {
+    canister: String,
+    method: String,
+    arg: Option<String>,
+    raw_arg: Option<String>,
+    expect: Option<Expectation>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct TopUp {
+    canister: String,
+    cycles: u128,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct AdvanceTime {
+    seconds: u64,
+}
+
+/// What the reply to a call is expected to be.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Expectation {
+    /// A reply with the given Candid values, in textual form.
+    Reply(String),
+    /// A reply with the given octet string, in the notation of the line-based message file.
+    RawReply(String),
+    /// A reject, optionally with the given reject code and with a message that contains the
+    /// given text.
+    Reject {
+        code: Option<u64>,
+        message: Option<String>,
+    },
+}
+
+impl Scenario {
+    pub(crate) fn from_file(path: &str, format: ScenarioFormat) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read scenario file: {} - Error: {}", path, e))?;
+        Self::parse(&contents, format)
+    }
+
+    fn parse(contents: &str, format: ScenarioFormat) -> Result<Self, String> {
+        match format {
+            ScenarioFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            ScenarioFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("Failed to parse scenario: {}", e))
+    }
+}
+
+/// Executes the steps of a scenario, one after the other, and counts the steps whose outcome
+/// was not the expected one.
+pub(crate) struct ScenarioRunner<'a> {
+    pub(crate) message_routing: &'a dyn MessageRouting,
+    pub(crate) ingress_hist_reader: &'a dyn IngressHistoryReader,
+    pub(crate) query_handler: &'a dyn QueryHandler<State = ReplicatedState>,
+    pub(crate) state_reader: &'a dyn StateReader<State = ReplicatedState>,
+    pub(crate) extra_batches: u64,
+    pub(crate) clock: Clock,
+    pub(crate) canisters: BTreeMap<String, CanisterId>,
+    pub(crate) nonce: u64,
+}
+
+impl ScenarioRunner<'_> {
+    /// Runs all steps of the scenario, and returns an error if any of them failed.
+    pub(crate) fn run(mut self, scenario: Scenario) -> Result<(), String> {
+        let total = scenario.steps.len();
+        let mut failures = 0;
+        for (i, step) in scenario.steps.into_iter().enumerate() {
+            if let Err(e) = self.run_step(step) {
+                println!("step {}: FAILED: {}", i + 1, e);
+                failures += 1;
+            }
+        }
+        if failures > 0 {
+            return Err(format!("{} of {} steps failed", failures, total));
+        }
+        Ok(())
+    }
+
+    fn run_step(&mut self, step: Step) -> Result<(), String> {
+        match step {
+            Step::Create(Create { name, cycles }) => {
+                let payload = ProvisionalCreateCanisterWithCyclesArgs::new(cycles, None).encode();
+                let reply = expect_reply(self.call_management_canister(
+                    ic00::Method::ProvisionalCreateCanisterWithCycles,
+                    payload,
+                ))?;
+                let canister_id = CanisterIdRecord::decode(&reply)
+                    .map_err(|e| format!("Failed to decode the created canister ID: {}", e))?
+                    .get_canister_id();
+                println!("create: {}", canister_id);
+                if let Some(name) = name {
+                    self.canisters.insert(name, canister_id);
+                }
+                Ok(())
+            }
+            Step::Install(Install {
+                canister,
+                wasm,
+                mode,
+                arg,
+                raw_arg,
+            }) => {
+                let canister_id = self.canister_id(&canister)?;
+                let wasm_module = fs::read(&wasm)
+                    .map_err(|e| format!("Could not read wasm file: {} - Error: {}", wasm, e))?;
+                let arg = self.encode_arg(arg.as_deref(), raw_arg.as_deref())?;
+                let payload = InstallCodeArgs::new(
+                    mode,
+                    canister_id,
+                    wasm_module,
+                    arg,
+                    None,
+                    Some(8 * 1024 * 1024 * 1024), // drun users dont care about memory limits
+                    None,
+                )
+                .encode();
+                expect_reply(self.call_management_canister(ic00::Method::InstallCode, payload))?;
+                println!("{} {}: Completed", String::from(mode), canister);
+                Ok(())
+            }
+            Step::Update(call) => {
+                let canister_id = self.canister_id(&call.canister)?;
+                let payload = self.encode_arg(call.arg.as_deref(), call.raw_arg.as_deref())?;
+                let msg = self.ingress(canister_id, &call.method, payload);
+                let result = self.execute_ingress(msg);
+                print_result("update", &call, &result);
+                self.check(call.expect, result)
+            }
+            Step::Query(call) => {
+                let canister_id = self.canister_id(&call.canister)?;
+                let query = UserQuery {
+                    source: UserId::from(PrincipalId::new_anonymous()),
+                    receiver: canister_id,
+                    method_name: call.method.clone(),
+                    method_payload: self
+                        .encode_arg(call.arg.as_deref(), call.raw_arg.as_deref())?,
+                    ingress_expiry: self.expiry_time().as_nanos_since_unix_epoch(),
+                    nonce: Some(self.next_nonce().to_le_bytes().to_vec()),
+                };
+                // NOTE: Data certificates aren't supported in drun yet.
+                let result = self.query_handler.query(
+                    query,
+                    self.state_reader.get_latest_state(),
+                    Vec::new(),
+                );
+                print_result("query", &call, &result);
+                self.check(call.expect, result)
+            }
+            Step::TopUp(TopUp { canister, cycles }) => {
+                let canister_id = self.canister_id(&canister)?;
+                let payload = ProvisionalTopUpCanisterArgs::new(canister_id, cycles).encode();
+                expect_reply(
+                    self.call_management_canister(ic00::Method::ProvisionalTopUpCanister, payload),
+                )?;
+                println!("top_up {}: {} cycles", canister, cycles);
+                Ok(())
+            }
+            Step::AdvanceTime(AdvanceTime { seconds }) => {
+                self.clock.advance(Duration::from_secs(seconds));
+                // Execute a batch at the new time, so that queries see it and timers and
+                // heartbeats that are due run.
+                wait_extra_batches(self.message_routing, 1, &self.clock);
+                println!("advance_time: {}s", seconds);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the ID of the canister with the given name, or the canister ID that is given in
+    /// textual form.
+    fn canister_id(&self, canister: &str) -> Result<CanisterId, String> {
+        match self.canisters.get(canister) {
+            Some(canister_id) => Ok(*canister_id),
+            None => parse_canister_id(canister),
+        }
+    }
+
+    /// Replaces the canister names in `${name}` placeholders with their canister IDs.
+    fn substitute_canister_ids(&self, text: &str) -> Result<String, String> {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| format!("Unterminated placeholder in {}", text))?;
+            let name = &rest[start + 2..start + end];
+            let canister_id = self
+                .canisters
+                .get(name)
+                .ok_or_else(|| format!("Unknown canister name {}", name))?;
+            result.push_str(&rest[..start]);
+            result.push_str(&canister_id.to_string());
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    /// Encodes the argument of a call: either Candid values in textual form, or an octet string
+    /// in the notation of the line-based message file. No argument means no Candid values.
+    fn encode_arg(&self, arg: Option<&str>, raw_arg: Option<&str>) -> Result<Vec<u8>, String> {
+        match (arg, raw_arg) {
+            (Some(_), Some(_)) => Err("Only one of arg and raw_arg can be given".to_string()),
+            (Some(arg), None) => {
+                let arg = self.substitute_canister_ids(arg)?;
+                candid_parser::parse_idl_args(&arg)
+                    .map_err(|e| format!("Failed to parse Candid argument {}: {}", arg, e))?
+                    .to_bytes()
+                    .map_err(|e| format!("Failed to encode Candid argument {}: {}", arg, e))
+            }
+            (None, Some(raw_arg)) => parse_octet_string(raw_arg),
+            (None, None) => Ok(Encode!().unwrap()),
+        }
+    }
+
+    fn check(
+        &self,
+        expectation: Option<Expectation>,
+        result: Result<WasmResult, UserError>,
+    ) -> Result<(), String> {
+        match expectation {
+            None => Ok(()),
+            Some(Expectation::Reply(expected)) => {
+                let reply = expect_reply(result)?;
+                let expected = self.substitute_canister_ids(&expected)?;
+                if candid_reply_matches(&expected, &reply)? {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected reply {}, got {}",
+                        expected,
+                        format_reply(&reply)
+                    ))
+                }
+            }
+            Some(Expectation::RawReply(expected)) => {
+                let reply = expect_reply(result)?;
+                if parse_octet_string(&expected)? == reply {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected reply {}, got 0x{}",
+                        expected,
+                        encode(reply)
+                    ))
+                }
+            }
+            Some(Expectation::Reject { code, message }) => {
+                let (reject_code, reject_message) = match result {
+                    Ok(WasmResult::Reply(reply)) => {
+                        return Err(format!(
+                            "expected a reject, got reply {}",
+                            format_reply(&reply)
+                        ))
+                    }
+                    Ok(WasmResult::Reject(message)) => (RejectCode::CanisterReject, message),
+                    Err(error) => (error.reject_code(), error.description().to_string()),
+                };
+                if let Some(code) = code {
+                    if reject_code as u64 != code {
+                        return Err(format!(
+                            "expected reject code {}, got {} ({:?})",
+                            code, reject_code as u64, reject_code
+                        ));
+                    }
+                }
+                if let Some(message) = message {
+                    if !reject_message.contains(&message) {
+                        return Err(format!(
+                            "expected a reject message containing {:?}, got {:?}",
+                            message, reject_message
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn call_management_canister(
+        &mut self,
+        method: ic00::Method,
+        payload: Vec<u8>,
+    ) -> Result<WasmResult, UserError> {
+        let msg = self.ingress(ic00::IC_00, &method.to_string(), payload);
+        self.execute_ingress(msg)
+    }
+
+    fn execute_ingress(&self, msg: SignedIngress) -> Result<WasmResult, UserError> {
+        let message_id = msg.id();
+        let result = execute_ingress_message(
+            self.message_routing,
+            msg,
+            &message_id,
+            self.ingress_hist_reader,
+            &self.clock,
+        );
+        wait_extra_batches(self.message_routing, self.extra_batches, &self.clock);
+        result
+    }
+
+    fn ingress(
+        &mut self,
+        canister_id: CanisterId,
+        method: &str,
+        payload: Vec<u8>,
+    ) -> SignedIngress {
+        SignedIngressBuilder::new()
+            .canister_id(canister_id)
+            .method_name(method)
+            .method_payload(payload)
+            .nonce(self.next_nonce())
+            .expiry_time(self.expiry_time())
+            .build()
+    }
+
+    /// The expiry time of messages, relative to the time of the batches rather than to the
+    /// system time, so that messages don't expire after the time was advanced.
+    fn expiry_time(&self) -> ic_types::Time {
+        self.clock.now() + MAX_INGRESS_TTL - PERMITTED_DRIFT
+    }
+
+    fn next_nonce(&mut self) -> u64 {
+        self.nonce += 1;
+        self.nonce
+    }
+}
+
+fn expect_reply(result: Result<WasmResult, UserError>) -> Result<Vec<u8>, String> {
+    match result {
+        Ok(WasmResult::Reply(reply)) => Ok(reply),
+        Ok(WasmResult::Reject(message)) => Err(format!("expected a reply, got reject {}", message)),
+        Err(error) => Err(format!("expected a reply, got error {}", error)),
+    }
+}
+
+/// Returns whether the reply consists of the expected Candid values. The expected values are
+/// interpreted with the types of the reply, e.g. so that `42` matches both a `nat` and an `int`.
+fn candid_reply_matches(expected: &str, reply: &[u8]) -> Result<bool, String> {
+    let reply = IDLArgs::from_bytes(reply)
+        .map_err(|e| format!("Failed to decode the reply as Candid: {}", e))?;
+    let expected = candid_parser::parse_idl_args(expected)
+        .map_err(|e| format!("Failed to parse expected reply {}: {}", expected, e))?;
+
+    let env = TypeEnv::new();
+    let types = reply.get_types();
+    let reply = reply
+        .to_bytes_with_types(&env, &types)
+        .map_err(|e| format!("Failed to encode the reply: {}", e))?;
+    Ok(expected
+        .annotate_types(true, &env, &types)
+        .and_then(|expected| expected.to_bytes_with_types(&env, &types))
+        .map_or(false, |expected| expected == reply))
+}
+
+/// Formats a reply as Candid values in textual form if it can be decoded as such, and as hex
+/// otherwise.
+fn format_reply(reply: &[u8]) -> String {
+    match IDLArgs::from_bytes(reply) {
+        Ok(args) => args.to_string(),
+        Err(_) => format!("0x{}", encode(reply)),
+    }
+}
+
+fn print_result(kind: &str, call: &Call, result: &Result<WasmResult, UserError>) {
+    print!("{} {} {}: ", kind, call.canister, call.method);
+    match result {
+        Ok(WasmResult::Reply(reply)) => println!("Reply: {}", format_reply(reply)),
+        Ok(WasmResult::Reject(message)) => println!("Reject: {}", message),
+        Err(error) => println!("Err: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_drun, DrunOptions};
+    use ic_config::{flag_status::FlagStatus, Config};
+    use ic_registry_subnet_type::SubnetType;
+
+    const YAML_SCENARIO: &str = r#"
+steps:
+  - create: { name: counter, cycles: 1000000000000 }
+  - install: { canister: counter, wasm: counter.wasm }
+  - update:
+      canister: counter
+      method: inc
+      arg: "(1 : nat)"
+      expect: { reply: "(1 : nat)" }
+  - query:
+      canister: counter
+      method: read
+      raw_arg: "0x00"
+      expect: { reject: { code: 5, message: trapped } }
+  - top_up: { canister: counter, cycles: 10 }
+  - advance_time: { seconds: 60 }
+"#;
+
+    #[test]
+    fn test_scenario_format_from_path() {
+        assert_eq!(
+            ScenarioFormat::from_path("test.json"),
+            Some(ScenarioFormat::Json)
+        );
+        assert_eq!(
+            ScenarioFormat::from_path("dir/test.yml"),
+            Some(ScenarioFormat::Yaml)
+        );
+        assert_eq!(ScenarioFormat::from_path("in.txt"), None);
+    }
+
+    #[test]
+    fn test_parse_yaml_scenario() {
+        let scenario = Scenario::parse(YAML_SCENARIO, ScenarioFormat::Yaml).unwrap();
+        assert_eq!(
+            scenario.steps,
+            vec![
+                Step::Create(Create {
+                    name: Some("counter".to_string()),
+                    cycles: Some(1_000_000_000_000),
+                }),
+                Step::Install(Install {
+                    canister: "counter".to_string(),
+                    wasm: "counter.wasm".to_string(),
+                    mode: CanisterInstallMode::Install,
+                    arg: None,
+                    raw_arg: None,
+                }),
+                Step::Update(Call {
+                    canister: "counter".to_string(),
+                    method: "inc".to_string(),
+                    arg: Some("(1 : nat)".to_string()),
+                    raw_arg: None,
+                    expect: Some(Expectation::Reply("(1 : nat)".to_string())),
+                }),
+                Step::Query(Call {
+                    canister: "counter".to_string(),
+                    method: "read".to_string(),
+                    arg: None,
+                    raw_arg: Some("0x00".to_string()),
+                    expect: Some(Expectation::Reject {
+                        code: Some(5),
+                        message: Some("trapped".to_string()),
+                    }),
+                }),
+                Step::TopUp(TopUp {
+                    canister: "counter".to_string(),
+                    cycles: 10,
+                }),
+                Step::AdvanceTime(AdvanceTime { seconds: 60 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_json_scenario() {
+        let json = r#"{"steps": [
+            {"create": {}},
+            {"install": {"canister": "rwlgt-iiaaa-aaaaa-aaaaa-cai", "wasm": "a.wasm", "mode": "upgrade"}}
+        ]}"#;
+        let scenario = Scenario::parse(json, ScenarioFormat::Json).unwrap();
+        assert_eq!(scenario.steps[0], Step::Create(Create::default()));
+        assert_eq!(
+            scenario.steps[1],
+            Step::Install(Install {
+                canister: "rwlgt-iiaaa-aaaaa-aaaaa-cai".to_string(),
+                wasm: "a.wasm".to_string(),
+                mode: CanisterInstallMode::Upgrade,
+                arg: None,
+                raw_arg: None,
+            })
+        );
+
+        let unknown_field = r#"{"steps": [{"advance_time": {"minutes": 1}}]}"#;
+        assert!(Scenario::parse(unknown_field, ScenarioFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_candid_reply_matches() {
+        let reply = Encode!(&candid::Nat::from(42_u64), &"hello").unwrap();
+        assert_eq!(candid_reply_matches("(42, \"hello\")", &reply), Ok(true));
+        assert_eq!(
+            candid_reply_matches("(42 : nat, \"hello\")", &reply),
+            Ok(true)
+        );
+        assert_eq!(candid_reply_matches("(43, \"hello\")", &reply), Ok(false));
+        assert_eq!(candid_reply_matches("(42)", &reply), Ok(false));
+        assert!(candid_reply_matches("(42", &reply).is_err());
+        assert!(candid_reply_matches("(42)", &[1, 2, 3]).is_err());
+    }
+
+    /// A canister whose `answer` update and `read` query both reply with `(42 : nat)`.
+    const ANSWER_WAT: &str = r#"
+(module
+  (import "ic0" "msg_reply_data_append" (func $msg_reply_data_append (param i32 i32)))
+  (import "ic0" "msg_reply" (func $msg_reply))
+  (func $answer
+    (call $msg_reply_data_append (i32.const 0) (i32.const 8))
+    (call $msg_reply))
+  (memory 1)
+  (data (i32.const 0) "DIDL\00\01\7d\2a")
+  (export "canister_update answer" (func $answer))
+  (export "canister_query read" (func $answer)))
+"#;
+
+    // Only one scenario is run per process, as drun registers its metrics in the global
+    // metrics registry.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_scenario() {
+        let dir = tempfile::tempdir().unwrap();
+        let wasm = dir.path().join("answer.wasm");
+        fs::write(&wasm, wat::parse_str(ANSWER_WAT).unwrap()).unwrap();
+        let scenario = dir.path().join("scenario.yaml");
+        fs::write(
+            &scenario,
+            format!(
+                r#"
+steps:
+  - create: {{ name: answer, cycles: 1000000000000 }}
+  - install: {{ canister: answer, wasm: "{}" }}
+  - update: {{ canister: answer, method: answer, expect: {{ reply: "(42 : nat)" }} }}
+  - query: {{ canister: answer, method: read, raw_arg: "0x4449444c0000", expect: {{ reply: "(42)" }} }}
+  - update: {{ canister: answer, method: missing, expect: {{ reject: {{ code: 3 }} }} }}
+  - query: {{ canister: answer, method: read, expect: {{ reply: "(43 : nat)" }} }}
+"#,
+                wasm.display()
+            ),
+        )
+        .unwrap();
+
+        let result = Config::run_with_temp_config(|mut cfg| {
+            // The test binary cannot be launched as a canister sandbox.
+            cfg.hypervisor.canister_sandboxing_flag = FlagStatus::Disabled;
+            run_drun(DrunOptions {
+                msg_filename: scenario.to_str().unwrap().to_string(),
+                cfg,
+                extra_batches: 0,
+                log_file: None,
+                instruction_limit: None,
+                subnet_type: SubnetType::System,
+            })
+        });
+
+        // Only the step with the mismatched reply fails.
+        assert_eq!(result, Err("1 of 6 steps failed".to_string()));
+    }
+}
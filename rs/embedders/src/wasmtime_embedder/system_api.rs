@@ -671,6 +671,20 @@ pub(crate) fn syscalls(
         })
         .unwrap();
 
+    linker
+        .func_wrap("ic0", "call_with_best_effort_response", {
+            move |mut caller: Caller<'_, StoreData>, timeout_seconds: u32| {
+                charge_for_cpu(
+                    &mut caller,
+                    overhead!(CALL_WITH_BEST_EFFORT_RESPONSE, metering_type),
+                )?;
+                with_system_api(&mut caller, |s| {
+                    s.ic0_call_with_best_effort_response(timeout_seconds)
+                })
+            }
+        })
+        .unwrap();
+
     linker
         .func_wrap("ic0", "call_cycles_add", {
             move |mut caller: Caller<'_, StoreData>, amount: u64| {
@@ -839,6 +853,15 @@ pub(crate) fn syscalls(
         })
         .unwrap();
 
+    linker
+        .func_wrap("ic0", "msg_deadline", {
+            move |mut caller: Caller<'_, StoreData>| {
+                charge_for_cpu(&mut caller, overhead!(MSG_DEADLINE, metering_type))?;
+                with_system_api(&mut caller, |s| s.ic0_msg_deadline())
+            }
+        })
+        .unwrap();
+
     linker
         .func_wrap("ic0", "global_timer_set", {
             move |mut caller: Caller<'_, StoreData>, time: u64| {
@@ -27,6 +27,7 @@ pub mod overhead {
         pub const CALL_NEW: NumInstructions = NumInstructions::new(0);
         pub const CALL_ON_CLEANUP: NumInstructions = NumInstructions::new(0);
         pub const CALL_PERFORM: NumInstructions = NumInstructions::new(0);
+        pub const CALL_WITH_BEST_EFFORT_RESPONSE: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_CYCLE_BALANCE: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_CYCLE_BALANCE128: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_SELF_COPY: NumInstructions = NumInstructions::new(0);
@@ -52,6 +53,7 @@ pub mod overhead {
         pub const MSG_CYCLES_AVAILABLE128: NumInstructions = NumInstructions::new(0);
         pub const MSG_CYCLES_REFUNDED: NumInstructions = NumInstructions::new(0);
         pub const MSG_CYCLES_REFUNDED128: NumInstructions = NumInstructions::new(0);
+        pub const MSG_DEADLINE: NumInstructions = NumInstructions::new(0);
         pub const MSG_METHOD_NAME_COPY: NumInstructions = NumInstructions::new(20);
         pub const MSG_METHOD_NAME_SIZE: NumInstructions = NumInstructions::new(0);
         pub const MSG_REJECT_CODE: NumInstructions = NumInstructions::new(0);
@@ -82,6 +84,7 @@ pub mod overhead {
         pub const CALL_NEW: NumInstructions = NumInstructions::new(1_500);
         pub const CALL_ON_CLEANUP: NumInstructions = NumInstructions::new(500);
         pub const CALL_PERFORM: NumInstructions = NumInstructions::new(5_000);
+        pub const CALL_WITH_BEST_EFFORT_RESPONSE: NumInstructions = NumInstructions::new(500);
         pub const CANISTER_CYCLE_BALANCE: NumInstructions = NumInstructions::new(500);
         pub const CANISTER_CYCLE_BALANCE128: NumInstructions = NumInstructions::new(500);
         pub const CANISTER_SELF_COPY: NumInstructions = NumInstructions::new(500);
@@ -108,6 +111,7 @@ pub mod overhead {
         pub const MSG_CYCLES_AVAILABLE128: NumInstructions = NumInstructions::new(500);
         pub const MSG_CYCLES_REFUNDED: NumInstructions = NumInstructions::new(500);
         pub const MSG_CYCLES_REFUNDED128: NumInstructions = NumInstructions::new(500);
+        pub const MSG_DEADLINE: NumInstructions = NumInstructions::new(500);
         pub const MSG_METHOD_NAME_COPY: NumInstructions = NumInstructions::new(500);
         pub const MSG_METHOD_NAME_SIZE: NumInstructions = NumInstructions::new(500);
         pub const MSG_REJECT_CODE: NumInstructions = NumInstructions::new(500);
@@ -10,6 +10,7 @@ use ic_logger::replica_logger::no_op_logger;
 use ic_test_utilities::wasmtime_instance::WasmtimeInstanceBuilder;
 use ic_test_utilities_time::mock_time;
 use ic_types::{
+    messages::NO_DEADLINE,
     methods::{FuncRef, WasmMethod},
     Cycles, PrincipalId,
 };
@@ -171,6 +172,7 @@ fn run_go_export(wat: &str) -> Result<(), HypervisorError> {
             Cycles::from(0_u128),
             PrincipalId::new_user_test_id(0),
             0.into(),
+            NO_DEADLINE,
         ))
         .with_num_instructions(LARGE_INSTRUCTION_LIMIT.into())
         .try_build()
@@ -2,7 +2,10 @@ use canister_test::{Cycles, PrincipalId, WasmResult};
 use ic_interfaces::execution_environment::HypervisorResult;
 use ic_test_utilities::wasmtime_instance::WasmtimeInstanceBuilder;
 use ic_test_utilities_time::mock_time;
-use ic_types::methods::{FuncRef, WasmMethod};
+use ic_types::{
+    messages::NO_DEADLINE,
+    methods::{FuncRef, WasmMethod},
+};
 
 fn wat_with_imports(wat: &str) -> String {
     format!(
@@ -40,6 +43,7 @@ fn run_test(wat: &str) -> HypervisorResult<Option<WasmResult>> {
             Cycles::zero(),
             PrincipalId::new_user_test_id(0),
             0.into(),
+            NO_DEADLINE,
         ))
         .with_wat(wat)
         .build();
@@ -15,7 +15,7 @@ use ic_types::{
 };
 
 #[cfg(target_os = "linux")]
-use ic_types::{Cycles, PrincipalId};
+use ic_types::{messages::NO_DEADLINE, Cycles, PrincipalId};
 
 /// Ensures that attempts to execute messages on wasm modules that do not
 /// define memory fails.
@@ -573,6 +573,7 @@ fn read_before_write_stats() {
             Cycles::zero(),
             PrincipalId::new_user_test_id(0),
             0.into(),
+            NO_DEADLINE,
         ))
         .build();
     instance
@@ -601,6 +602,7 @@ fn read_before_write_stats() {
             Cycles::zero(),
             PrincipalId::new_user_test_id(0),
             0.into(),
+            NO_DEADLINE,
         ))
         .build();
     instance
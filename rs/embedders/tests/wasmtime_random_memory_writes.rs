@@ -20,7 +20,7 @@ use ic_test_utilities_logger::with_test_replica_logger;
 use ic_test_utilities_time::mock_time;
 use ic_types::MemoryAllocation;
 use ic_types::{
-    messages::RequestMetadata,
+    messages::{RequestMetadata, NO_DEADLINE},
     methods::{FuncRef, WasmMethod},
     ComputeAllocation, Cycles, NumBytes, NumInstructions, PrincipalId,
 };
@@ -83,6 +83,7 @@ fn test_api_for_update(
             Cycles::zero(),
             caller,
             call_context_test_id(13),
+            NO_DEADLINE,
         ),
         static_system_state,
         canister_current_memory_usage,
@@ -45,6 +45,12 @@ struct PrincipalContainer {
     pub value: Principal,
 }
 
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+struct OptPrincipalContainer {
+    #[cbor(n(0), with = "crate::cbor::principal::option")]
+    pub value: Option<Principal>,
+}
+
 #[derive(Debug, PartialEq, Eq, Encode, Decode)]
 struct U256NewtypeContainer {
     #[cbor(n(0))]
@@ -106,4 +112,11 @@ proptest! {
             value: Principal::from_slice(&p),
         })?;
     }
+
+    #[test]
+    fn opt_principal_encoding_roundtrip(p in proptest::option::of(pvec(any::<u8>(), 0..30))) {
+        check_roundtrip(&OptPrincipalContainer {
+            value: p.map(|p| Principal::from_slice(&p)),
+        })?;
+    }
 }
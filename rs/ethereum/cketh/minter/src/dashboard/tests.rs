@@ -618,6 +618,9 @@ fn initial_state() -> State {
         minimum_withdrawal_amount: Wei::TWO.into(),
         next_transaction_nonce: TransactionNonce::ZERO.into(),
         last_scraped_block_number: candid::Nat::from(3_956_206_u32),
+        rpc_providers: None,
+        withdrawal_finality_block_tag: None,
+        fee_estimation_block_tag: None,
     })
     .expect("valid init args")
 }
@@ -705,6 +708,7 @@ fn withdrawal_flow(
         gas_used: signed_tx.transaction().gas_limit,
         status: tx_status,
         transaction_hash: signed_tx.hash(),
+        l1_fee: None,
     };
     (withdrawal_request, transaction, signed_tx, tx_receipt)
 }
@@ -1,6 +1,6 @@
-use crate::eth_logs::{report_transaction_error, ReceivedEthEventError};
-use crate::eth_rpc::{BlockSpec, HttpOutcallError};
-use crate::eth_rpc_client::EthRpcClient;
+use crate::eth_logs::{report_transaction_error, ReceivedEthEventError, ReceivedEvent};
+use crate::eth_rpc::{BlockSpec, HttpOutcallError, LogEntry};
+use crate::eth_rpc_client::{EthRpcClient, MultiCallError};
 use crate::guard::TimerGuard;
 use crate::logs::{DEBUG, INFO};
 use crate::numeric::{BlockNumber, LedgerMintIndex};
@@ -13,7 +13,7 @@ use num_traits::ToPrimitive;
 use std::cmp::{min, Ordering};
 use std::time::Duration;
 
-async fn mint_cketh() {
+async fn mint() {
     use icrc_ledger_client_cdk::{CdkRuntime, ICRC1Client};
     use icrc_ledger_types::icrc1::transfer::TransferArg;
 
@@ -22,29 +22,64 @@ async fn mint_cketh() {
         Err(_) => return,
     };
 
-    let (ledger_canister_id, events) = read_state(|s| (s.ledger_id, s.events_to_mint.clone()));
-    let client = ICRC1Client {
-        runtime: CdkRuntime,
-        ledger_canister_id,
-    };
+    let (eth_ledger_canister_id, events) = read_state(|s| {
+        let events: Vec<_> = s
+            .events_to_mint
+            .values()
+            .cloned()
+            .map(ReceivedEvent::from)
+            .chain(
+                s.erc20_events_to_mint
+                    .values()
+                    .cloned()
+                    .map(ReceivedEvent::from),
+            )
+            .collect();
+        (s.ledger_id, events)
+    });
 
     let mut error_count = 0;
 
-    for (event_source, event) in events {
+    for event in events {
+        let (token_symbol, ledger_canister_id) = match &event {
+            ReceivedEvent::Eth(_) => ("ckETH".to_string(), eth_ledger_canister_id),
+            ReceivedEvent::Erc20(event) => {
+                match read_state(|s| s.ckerc20_tokens.get(&event.erc20_contract_address).cloned()) {
+                    Some(token) => (token.ckerc20_token_symbol, token.ckerc20_ledger_id),
+                    None => {
+                        log!(
+                            INFO,
+                            "BUG: unsupported ERC-20 token {} in event {event:?}",
+                            event.erc20_contract_address
+                        );
+                        error_count += 1;
+                        continue;
+                    }
+                }
+            }
+        };
+        let client = ICRC1Client {
+            runtime: CdkRuntime,
+            ledger_canister_id,
+        };
+        let (amount, memo) = match &event {
+            ReceivedEvent::Eth(event) => (candid::Nat::from(event.value), event.clone().into()),
+            ReceivedEvent::Erc20(event) => (candid::Nat::from(event.value), event.clone().into()),
+        };
         let block_index = match client
             .transfer(TransferArg {
                 from_subaccount: None,
-                to: event.principal.into(),
+                to: event.principal().into(),
                 fee: None,
                 created_at_time: None,
-                memo: Some(event.clone().into()),
-                amount: candid::Nat::from(event.value),
+                memo: Some(memo),
+                amount,
             })
             .await
         {
             Ok(Ok(block_index)) => block_index.0.to_u64().expect("nat does not fit into u64"),
             Ok(Err(err)) => {
-                log!(INFO, "Failed to mint ckETH: {event:?} {err}");
+                log!(INFO, "Failed to mint {token_symbol}: {event:?} {err}");
                 error_count += 1;
                 continue;
             }
@@ -60,17 +95,25 @@ async fn mint_cketh() {
         mutate_state(|s| {
             process_event(
                 s,
-                EventType::MintedCkEth {
-                    event_source,
-                    mint_block_index: LedgerMintIndex::new(block_index),
+                match &event {
+                    ReceivedEvent::Eth(event) => EventType::MintedCkEth {
+                        event_source: event.source(),
+                        mint_block_index: LedgerMintIndex::new(block_index),
+                    },
+                    ReceivedEvent::Erc20(event) => EventType::MintedCkErc20 {
+                        event_source: event.source(),
+                        mint_block_index: LedgerMintIndex::new(block_index),
+                        ckerc20_token_symbol: token_symbol.clone(),
+                        erc20_contract_address: event.erc20_contract_address,
+                    },
                 },
             )
         });
         log!(
             INFO,
-            "Minted {} ckWei to {} in block {block_index}",
-            event.value,
-            event.principal
+            "Minted {} {token_symbol} to {} in block {block_index}",
+            event.value_description(),
+            event.principal()
         );
     }
 
@@ -79,7 +122,63 @@ async fn mint_cketh() {
             INFO,
             "Failed to mint {error_count} events, rescheduling the minting"
         );
-        ic_cdk_timers::set_timer(crate::MINT_RETRY_DELAY, || ic_cdk::spawn(mint_cketh()));
+        ic_cdk_timers::set_timer(crate::MINT_RETRY_DELAY, || ic_cdk::spawn(mint()));
+    }
+}
+
+/// The smart contract whose logs are scrapped by the minter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Scrape {
+    /// The ETH helper smart contract emitting `ReceivedEth` events.
+    Eth,
+    /// The ERC-20 helper smart contract emitting `ReceivedErc20` events.
+    Erc20,
+}
+
+impl Scrape {
+    fn contract_address(&self, state: &State) -> Option<Address> {
+        match self {
+            Scrape::Eth => state.ethereum_contract_address,
+            Scrape::Erc20 => state.erc20_helper_contract_address,
+        }
+    }
+
+    fn last_scraped_block_number(&self, state: &State) -> BlockNumber {
+        match self {
+            Scrape::Eth => state.last_scraped_block_number,
+            Scrape::Erc20 => state.last_erc20_scraped_block_number,
+        }
+    }
+
+    fn set_last_scraped_block_number(&self, state: &mut State, block_number: BlockNumber) {
+        match self {
+            Scrape::Eth => state.last_scraped_block_number = block_number,
+            Scrape::Erc20 => state.last_erc20_scraped_block_number = block_number,
+        }
+    }
+
+    fn skipped_block_event(&self, block_number: BlockNumber) -> EventType {
+        match self {
+            Scrape::Eth => EventType::SkippedBlock(block_number),
+            Scrape::Erc20 => EventType::SkippedErc20Block(block_number),
+        }
+    }
+
+    async fn fetch_logs(
+        &self,
+        contract_address: Address,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<(Vec<ReceivedEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>>
+    {
+        match self {
+            Scrape::Eth => {
+                crate::eth_logs::last_received_eth_events(contract_address, from, to).await
+            }
+            Scrape::Erc20 => {
+                crate::eth_logs::last_received_erc20_events(contract_address, from, to).await
+            }
+        }
     }
 }
 
@@ -87,7 +186,8 @@ async fn mint_cketh() {
 /// require that the number of blocks queried is no greater than MAX_BLOCK_SPREAD.
 /// Returns the last block number that was scraped (which is `min(from + MAX_BLOCK_SPREAD, to)`) if there
 /// was no error when querying the providers, otherwise returns `None`.
-async fn scrap_eth_logs_range_inclusive(
+async fn scrap_logs_range_inclusive(
+    scrape: Scrape,
     contract_address: Address,
     from: BlockNumber,
     to: BlockNumber,
@@ -103,32 +203,29 @@ async fn scrap_eth_logs_range_inclusive(
             let mut last_block_number = min(max_to, to);
             log!(
                 DEBUG,
-                "Scrapping ETH logs from block {:?} to block {:?}...",
+                "Scrapping {scrape:?} logs from block {:?} to block {:?}...",
                 from,
                 last_block_number
             );
 
             let (transaction_events, errors) = loop {
-                match crate::eth_logs::last_received_eth_events(
-                    contract_address,
-                    from,
-                    last_block_number,
-                )
-                .await
+                match scrape
+                    .fetch_logs(contract_address, from, last_block_number)
+                    .await
                 {
                     Ok((events, errors)) => break (events, errors),
                     Err(e) => {
                         log!(
                         INFO,
-                        "Failed to get ETH logs from block {from} to block {last_block_number}: {e:?}",
+                        "Failed to get {scrape:?} logs from block {from} to block {last_block_number}: {e:?}",
                     );
                         if e.has_http_outcall_error_matching(
                             HttpOutcallError::is_response_too_large,
                         ) {
                             if from == last_block_number {
                                 mutate_state(|s| {
-                                    process_event(s, EventType::SkippedBlock(last_block_number));
-                                    s.last_scraped_block_number = last_block_number;
+                                    process_event(s, scrape.skipped_block_event(last_block_number));
+                                    scrape.set_last_scraped_block_number(s, last_block_number);
                                 });
                                 return Some(last_block_number);
                             } else {
@@ -151,35 +248,63 @@ async fn scrap_eth_logs_range_inclusive(
             for event in transaction_events {
                 log!(
                     INFO,
-                    "Received event {event:?}; will mint {} wei to {}",
-                    event.value,
-                    event.principal
+                    "Received event {event:?}; will mint {} to {}",
+                    event.value_description(),
+                    event.principal()
                 );
-                if crate::blocklist::is_blocked(event.from_address) {
+                if crate::blocklist::is_blocked(event.from_address()) {
                     log!(
                         INFO,
-                        "Received event from a blocked address: {} for {} WEI",
-                        event.from_address,
-                        event.value,
+                        "Received event from a blocked address: {} for {}",
+                        event.from_address(),
+                        event.value_description(),
                     );
                     mutate_state(|s| {
                         process_event(
                             s,
                             EventType::InvalidDeposit {
-                                event_source: crate::eth_logs::EventSource {
-                                    transaction_hash: event.transaction_hash,
-                                    log_index: event.log_index,
-                                },
-                                reason: format!("blocked address {}", event.from_address),
+                                event_source: event.source(),
+                                reason: format!("blocked address {}", event.from_address()),
                             },
                         )
                     });
-                } else {
-                    mutate_state(|s| process_event(s, EventType::AcceptedDeposit(event)));
+                    continue;
+                }
+                match event {
+                    ReceivedEvent::Eth(event) => {
+                        mutate_state(|s| process_event(s, EventType::AcceptedDeposit(event)));
+                    }
+                    ReceivedEvent::Erc20(event) => {
+                        if read_state(|s| {
+                            s.ckerc20_tokens.contains_key(&event.erc20_contract_address)
+                        }) {
+                            mutate_state(|s| {
+                                process_event(s, EventType::AcceptedErc20Deposit(event))
+                            });
+                        } else {
+                            log!(
+                                INFO,
+                                "Received event for an unsupported ERC-20 token {}: {event:?}",
+                                event.erc20_contract_address
+                            );
+                            mutate_state(|s| {
+                                process_event(
+                                    s,
+                                    EventType::InvalidDeposit {
+                                        event_source: event.source(),
+                                        reason: format!(
+                                            "unsupported ERC-20 token {}",
+                                            event.erc20_contract_address
+                                        ),
+                                    },
+                                )
+                            });
+                        }
+                    }
                 }
             }
             if read_state(State::has_events_to_mint) {
-                ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(mint_cketh()));
+                ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(mint()));
             }
             for error in errors {
                 if let ReceivedEthEventError::InvalidEventSource { source, error } = &error {
@@ -195,7 +320,7 @@ async fn scrap_eth_logs_range_inclusive(
                 }
                 report_transaction_error(error);
             }
-            mutate_state(|s| s.last_scraped_block_number = last_block_number);
+            mutate_state(|s| scrape.set_last_scraped_block_number(s, last_block_number));
             Some(last_block_number)
         }
         Ordering::Greater => {
@@ -212,33 +337,40 @@ pub async fn scrap_eth_logs() {
         Ok(guard) => guard,
         Err(_) => return,
     };
-    let contract_address = match read_state(|s| s.ethereum_contract_address) {
-        Some(address) => address,
+    let last_block_number = match update_last_observed_block_number().await {
+        Some(block_number) => block_number,
         None => {
             log!(
                 DEBUG,
-                "[scrap_eth_logs]: skipping scrapping ETH logs: no contract address"
+                "[scrap_eth_logs]: skipping scrapping logs: no last observed block number"
             );
             return;
         }
     };
-    let last_block_number = match update_last_observed_block_number().await {
-        Some(block_number) => block_number,
+    for scrape in [Scrape::Eth, Scrape::Erc20] {
+        scrap_contract_logs(scrape, last_block_number).await;
+    }
+}
+
+async fn scrap_contract_logs(scrape: Scrape, last_block_number: BlockNumber) {
+    let contract_address = match read_state(|s| scrape.contract_address(s)) {
+        Some(address) => address,
         None => {
             log!(
                 DEBUG,
-                "[scrap_eth_logs]: skipping scrapping ETH logs: no last observed block number"
+                "[scrap_contract_logs]: skipping scrapping {scrape:?} logs: no contract address"
             );
             return;
         }
     };
-    let mut last_scraped_block_number = read_state(|s| s.last_scraped_block_number);
+    let mut last_scraped_block_number = read_state(|s| scrape.last_scraped_block_number(s));
 
     while last_scraped_block_number < last_block_number {
         let next_block_to_query = last_scraped_block_number
             .checked_increment()
             .unwrap_or(BlockNumber::MAX);
-        last_scraped_block_number = match scrap_eth_logs_range_inclusive(
+        last_scraped_block_number = match scrap_logs_range_inclusive(
+            scrape,
             contract_address,
             next_block_to_query,
             last_block_number,
@@ -0,0 +1,92 @@
+//! Module dealing with ERC-20 tokens bridged via the ckETH minter.
+use crate::endpoints::AddCkErc20Token;
+use crate::lifecycle::EthereumNetwork;
+use crate::numeric::Erc20Value;
+use candid::Principal;
+use hex_literal::hex;
+use ic_ethereum_types::Address;
+use minicbor::{Decode, Encode};
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+/// Function selector of `transfer(address,uint256)`,
+/// i.e., the first 4 bytes of `keccak256("transfer(address,uint256)")`.
+const ERC_20_TRANSFER_FUNCTION_SELECTOR: [u8; 4] = hex!("a9059cbb");
+
+/// An ERC-20 token supported by the minter.
+///
+/// Each supported token is backed by a dedicated ckERC20 ledger
+/// spawned by the ledger suite orchestrator.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+pub struct CkErc20Token {
+    #[n(0)]
+    pub erc20_ethereum_network: EthereumNetwork,
+    #[n(1)]
+    pub erc20_contract_address: Address,
+    #[n(2)]
+    pub ckerc20_token_symbol: String,
+    #[cbor(n(3), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+}
+
+impl fmt::Debug for CkErc20Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CkErc20Token")
+            .field("erc20_ethereum_network", &self.erc20_ethereum_network)
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .field("ckerc20_token_symbol", &self.ckerc20_token_symbol)
+            .field(
+                "ckerc20_ledger_id",
+                &format_args!("{}", self.ckerc20_ledger_id),
+            )
+            .finish()
+    }
+}
+
+impl TryFrom<AddCkErc20Token> for CkErc20Token {
+    type Error = String;
+
+    fn try_from(value: AddCkErc20Token) -> Result<Self, Self::Error> {
+        use num_traits::ToPrimitive;
+
+        let erc20_ethereum_network = EthereumNetwork::try_from(
+            value
+                .chain_id
+                .0
+                .to_u64()
+                .ok_or_else(|| format!("chain_id {} is not a valid u64", value.chain_id))?,
+        )?;
+        let erc20_contract_address = Address::from_str(&value.address)?;
+        if erc20_contract_address == Address::ZERO {
+            return Err("ERC-20 contract address cannot be the zero address".to_string());
+        }
+        let ckerc20_token_symbol = value.ckerc20_token_symbol.trim().to_string();
+        if ckerc20_token_symbol.is_empty() {
+            return Err("ckERC20 token symbol cannot be blank".to_string());
+        }
+        if value.ckerc20_ledger_id == Principal::anonymous() {
+            return Err("ckERC20 ledger id cannot be the anonymous principal".to_string());
+        }
+        Ok(Self {
+            erc20_ethereum_network,
+            erc20_contract_address,
+            ckerc20_token_symbol,
+            ckerc20_ledger_id: value.ckerc20_ledger_id,
+        })
+    }
+}
+
+/// Encodes the data of a call to the ERC-20 function `transfer(address to, uint256 value)`.
+///
+/// See <https://eips.ethereum.org/EIPS/eip-20#transfer>.
+pub fn encode_transfer_data(to: &Address, value: Erc20Value) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4 + 32 + 32);
+    data.extend_from_slice(&ERC_20_TRANSFER_FUNCTION_SELECTOR);
+    data.extend_from_slice(&[0_u8; 12]);
+    data.extend_from_slice(to.as_ref());
+    data.extend_from_slice(&value.to_be_bytes());
+    data
+}
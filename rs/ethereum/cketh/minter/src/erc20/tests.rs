@@ -0,0 +1,82 @@
+mod encode_transfer_data {
+    use crate::erc20::encode_transfer_data;
+    use crate::numeric::Erc20Value;
+    use ic_ethereum_types::Address;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_encode_transfer_call() {
+        let to = Address::from_str("0xdd2851Cdd40aE6536831558DD46db62fAc7A844d").unwrap();
+        let value = Erc20Value::from(1_000_000_u64);
+
+        let data = encode_transfer_data(&to, value);
+
+        assert_eq!(
+            hex::encode(data),
+            "a9059cbb\
+             000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d\
+             00000000000000000000000000000000000000000000000000000000000f4240"
+        );
+    }
+}
+
+mod ckerc20_token {
+    use crate::endpoints::AddCkErc20Token;
+    use crate::erc20::CkErc20Token;
+    use crate::lifecycle::EthereumNetwork;
+    use assert_matches::assert_matches;
+    use candid::{Nat, Principal};
+
+    #[test]
+    fn should_convert_valid_add_ckerc20_token() {
+        let token = CkErc20Token::try_from(valid_add_ckerc20_token()).unwrap();
+
+        assert_eq!(token.erc20_ethereum_network, EthereumNetwork::Sepolia);
+        assert_eq!(
+            token.erc20_contract_address.to_string(),
+            "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
+        );
+        assert_eq!(token.ckerc20_token_symbol, "ckSepoliaUSDC");
+    }
+
+    #[test]
+    fn should_fail_on_invalid_add_ckerc20_token() {
+        assert_matches!(
+            CkErc20Token::try_from(AddCkErc20Token {
+                chain_id: Nat::from(5_u8),
+                ..valid_add_ckerc20_token()
+            }),
+            Err(_)
+        );
+        assert_matches!(
+            CkErc20Token::try_from(AddCkErc20Token {
+                address: "0x0000000000000000000000000000000000000000".to_string(),
+                ..valid_add_ckerc20_token()
+            }),
+            Err(_)
+        );
+        assert_matches!(
+            CkErc20Token::try_from(AddCkErc20Token {
+                ckerc20_token_symbol: "  ".to_string(),
+                ..valid_add_ckerc20_token()
+            }),
+            Err(_)
+        );
+        assert_matches!(
+            CkErc20Token::try_from(AddCkErc20Token {
+                ckerc20_ledger_id: Principal::anonymous(),
+                ..valid_add_ckerc20_token()
+            }),
+            Err(_)
+        );
+    }
+
+    fn valid_add_ckerc20_token() -> AddCkErc20Token {
+        AddCkErc20Token {
+            chain_id: Nat::from(11155111_u64),
+            address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238".to_string(),
+            ckerc20_token_symbol: "ckSepoliaUSDC".to_string(),
+            ckerc20_ledger_id: Principal::from_text("mxzaz-hqaaa-aaaar-qaada-cai").unwrap(),
+        }
+    }
+}
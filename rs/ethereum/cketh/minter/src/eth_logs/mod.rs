@@ -4,7 +4,7 @@ mod tests;
 use crate::eth_rpc::{FixedSizeData, Hash, LogEntry};
 use crate::eth_rpc_client::{EthRpcClient, MultiCallError};
 use crate::logs::{DEBUG, INFO};
-use crate::numeric::{BlockNumber, LogIndex, Wei};
+use crate::numeric::{BlockNumber, Erc20Value, LogIndex, Wei};
 use crate::state::read_state;
 use candid::Principal;
 use hex_literal::hex;
@@ -17,6 +17,9 @@ use thiserror::Error;
 pub(crate) const RECEIVED_ETH_EVENT_TOPIC: [u8; 32] =
     hex!("257e057bb61920d8d0ed2cb7b720ac7f9c513cd1110bc9fa543079154f45f435");
 
+pub(crate) const RECEIVED_ERC20_EVENT_TOPIC: [u8; 32] =
+    hex!("4d69d0bd4287b7f66c548f90154dc81bc98f65a1b362775df5ae171a2ccd262b");
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
 pub struct ReceivedEthEvent {
     #[n(0)]
@@ -46,6 +49,90 @@ impl fmt::Debug for ReceivedEthEvent {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct ReceivedErc20Event {
+    #[n(0)]
+    pub transaction_hash: Hash,
+    #[n(1)]
+    pub block_number: BlockNumber,
+    #[cbor(n(2))]
+    pub log_index: LogIndex,
+    #[n(3)]
+    pub from_address: Address,
+    #[n(4)]
+    pub value: Erc20Value,
+    #[cbor(n(5), with = "crate::cbor::principal")]
+    pub principal: Principal,
+    #[n(6)]
+    pub erc20_contract_address: Address,
+}
+
+impl fmt::Debug for ReceivedErc20Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReceivedErc20Event")
+            .field("transaction_hash", &self.transaction_hash)
+            .field("block_number", &self.block_number)
+            .field("log_index", &self.log_index)
+            .field("from_address", &self.from_address)
+            .field("value", &self.value)
+            .field("principal", &format_args!("{}", self.principal))
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .finish()
+    }
+}
+
+/// A deposit discovered in the logs of one of the helper smart contracts.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ReceivedEvent {
+    Eth(ReceivedEthEvent),
+    Erc20(ReceivedErc20Event),
+}
+
+impl From<ReceivedEthEvent> for ReceivedEvent {
+    fn from(event: ReceivedEthEvent) -> Self {
+        ReceivedEvent::Eth(event)
+    }
+}
+
+impl From<ReceivedErc20Event> for ReceivedEvent {
+    fn from(event: ReceivedErc20Event) -> Self {
+        ReceivedEvent::Erc20(event)
+    }
+}
+
+impl ReceivedEvent {
+    pub fn source(&self) -> EventSource {
+        match self {
+            ReceivedEvent::Eth(evt) => evt.source(),
+            ReceivedEvent::Erc20(evt) => evt.source(),
+        }
+    }
+
+    pub fn from_address(&self) -> Address {
+        match self {
+            ReceivedEvent::Eth(evt) => evt.from_address,
+            ReceivedEvent::Erc20(evt) => evt.from_address,
+        }
+    }
+
+    pub fn principal(&self) -> Principal {
+        match self {
+            ReceivedEvent::Eth(evt) => evt.principal,
+            ReceivedEvent::Erc20(evt) => evt.principal,
+        }
+    }
+
+    /// Human-readable description of the deposited amount.
+    pub fn value_description(&self) -> String {
+        match self {
+            ReceivedEvent::Eth(evt) => format!("{} wei", evt.value),
+            ReceivedEvent::Erc20(evt) => {
+                format!("{} of ERC-20 token {}", evt.value, evt.erc20_contract_address)
+            }
+        }
+    }
+}
+
 /// A unique identifier of the event source: the source transaction hash and the log
 /// entry index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
@@ -71,11 +158,55 @@ impl ReceivedEthEvent {
     }
 }
 
+impl ReceivedErc20Event {
+    pub fn source(&self) -> EventSource {
+        EventSource {
+            transaction_hash: self.transaction_hash,
+            log_index: self.log_index,
+        }
+    }
+}
+
 pub async fn last_received_eth_events(
     contract_address: Address,
     from: BlockNumber,
     to: BlockNumber,
-) -> Result<(Vec<ReceivedEthEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>> {
+) -> Result<(Vec<ReceivedEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>> {
+    last_received_events(
+        contract_address,
+        RECEIVED_ETH_EVENT_TOPIC,
+        from,
+        to,
+        |entry| ReceivedEthEvent::try_from(entry).map(ReceivedEvent::from),
+    )
+    .await
+}
+
+pub async fn last_received_erc20_events(
+    contract_address: Address,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> Result<(Vec<ReceivedEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>> {
+    last_received_events(
+        contract_address,
+        RECEIVED_ERC20_EVENT_TOPIC,
+        from,
+        to,
+        |entry| ReceivedErc20Event::try_from(entry).map(ReceivedEvent::from),
+    )
+    .await
+}
+
+async fn last_received_events<F>(
+    contract_address: Address,
+    topic: [u8; 32],
+    from: BlockNumber,
+    to: BlockNumber,
+    parse: F,
+) -> Result<(Vec<ReceivedEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>>
+where
+    F: Fn(LogEntry) -> Result<ReceivedEvent, ReceivedEthEventError>,
+{
     use crate::eth_rpc::GetLogsParam;
 
     if from > to {
@@ -90,15 +221,12 @@ pub async fn last_received_eth_events(
             from_block: from.into(),
             to_block: to.into(),
             address: vec![contract_address],
-            topics: vec![FixedSizeData(RECEIVED_ETH_EVENT_TOPIC)],
+            topics: vec![FixedSizeData(topic)],
         })
         .await?;
 
-    let (ok, not_ok): (Vec<_>, Vec<_>) = result
-        .into_iter()
-        .map(ReceivedEthEvent::try_from)
-        .partition(Result::is_ok);
-    let valid_transactions: Vec<ReceivedEthEvent> = ok.into_iter().map(Result::unwrap).collect();
+    let (ok, not_ok): (Vec<_>, Vec<_>) = result.into_iter().map(parse).partition(Result::is_ok);
+    let valid_transactions: Vec<ReceivedEvent> = ok.into_iter().map(Result::unwrap).collect();
     let errors: Vec<ReceivedEthEventError> = not_ok.into_iter().map(Result::unwrap_err).collect();
     Ok((valid_transactions, errors))
 }
@@ -141,79 +269,17 @@ impl TryFrom<LogEntry> for ReceivedEthEvent {
     type Error = ReceivedEthEventError;
 
     fn try_from(entry: LogEntry) -> Result<Self, Self::Error> {
-        let _block_hash = entry
-            .block_hash
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let block_number = entry
-            .block_number
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let transaction_hash = entry
-            .transaction_hash
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let _transaction_index = entry
-            .transaction_index
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let log_index = entry
-            .log_index
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let event_source = EventSource {
-            transaction_hash,
-            log_index,
-        };
-
-        if entry.removed {
-            return Err(ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidEvent(
-                    "this event has been removed from the chain".to_string(),
-                ),
-            });
-        }
+        let (block_number, event_source) = ensure_not_pending_nor_removed(&entry)?;
+        ensure_topics(&entry, event_source, 3)?;
 
-        if entry.topics.len() != 3 {
-            return Err(ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidEvent(format!(
-                    "Expected exactly 3 topics, got {}",
-                    entry.topics.len()
-                )),
-            });
-        }
-        let from_address = Address::try_from(&entry.topics[1].0).map_err(|err| {
-            ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidEvent(format!(
-                    "Invalid address in log entry: {}",
-                    err
-                )),
-            }
-        })?;
-        let principal = parse_principal_from_slice(entry.topics[2].as_ref()).map_err(|_err| {
-            ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidPrincipal {
-                    invalid_principal: entry.topics[2].clone(),
-                },
-            }
-        })?;
-        let value_bytes: [u8; 32] =
-            entry
-                .data
-                .0
-                .try_into()
-                .map_err(|data| ReceivedEthEventError::InvalidEventSource {
-                    source: event_source,
-                    error: EventSourceError::InvalidEvent(format!(
-                        "Invalid data length; expected 32-byte value, got {}",
-                        hex::encode(data)
-                    )),
-                })?;
-        let value = Wei::from_be_bytes(value_bytes);
+        let from_address = parse_address(&entry.topics[1], event_source)?;
+        let principal = parse_principal(&entry.topics[2], event_source)?;
+        let value = Wei::from_be_bytes(parse_value(entry.data.0, event_source)?);
 
         Ok(ReceivedEthEvent {
-            transaction_hash,
+            transaction_hash: event_source.transaction_hash,
             block_number,
-            log_index,
+            log_index: event_source.log_index,
             from_address,
             value,
             principal,
@@ -221,6 +287,116 @@ impl TryFrom<LogEntry> for ReceivedEthEvent {
     }
 }
 
+impl TryFrom<LogEntry> for ReceivedErc20Event {
+    type Error = ReceivedEthEventError;
+
+    fn try_from(entry: LogEntry) -> Result<Self, Self::Error> {
+        let (block_number, event_source) = ensure_not_pending_nor_removed(&entry)?;
+        ensure_topics(&entry, event_source, 4)?;
+
+        let erc20_contract_address = parse_address(&entry.topics[1], event_source)?;
+        let from_address = parse_address(&entry.topics[2], event_source)?;
+        let principal = parse_principal(&entry.topics[3], event_source)?;
+        let value = Erc20Value::from_be_bytes(parse_value(entry.data.0, event_source)?);
+
+        Ok(ReceivedErc20Event {
+            transaction_hash: event_source.transaction_hash,
+            block_number,
+            log_index: event_source.log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+        })
+    }
+}
+
+fn ensure_not_pending_nor_removed(
+    entry: &LogEntry,
+) -> Result<(BlockNumber, EventSource), ReceivedEthEventError> {
+    let _block_hash = entry
+        .block_hash
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let block_number = entry
+        .block_number
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let transaction_hash = entry
+        .transaction_hash
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let _transaction_index = entry
+        .transaction_index
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let log_index = entry
+        .log_index
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let event_source = EventSource {
+        transaction_hash,
+        log_index,
+    };
+
+    if entry.removed {
+        return Err(ReceivedEthEventError::InvalidEventSource {
+            source: event_source,
+            error: EventSourceError::InvalidEvent(
+                "this event has been removed from the chain".to_string(),
+            ),
+        });
+    }
+    Ok((block_number, event_source))
+}
+
+fn ensure_topics(
+    entry: &LogEntry,
+    event_source: EventSource,
+    expected_num_topics: usize,
+) -> Result<(), ReceivedEthEventError> {
+    if entry.topics.len() != expected_num_topics {
+        return Err(ReceivedEthEventError::InvalidEventSource {
+            source: event_source,
+            error: EventSourceError::InvalidEvent(format!(
+                "Expected exactly {expected_num_topics} topics, got {}",
+                entry.topics.len()
+            )),
+        });
+    }
+    Ok(())
+}
+
+fn parse_address(
+    topic: &FixedSizeData,
+    event_source: EventSource,
+) -> Result<Address, ReceivedEthEventError> {
+    Address::try_from(&topic.0).map_err(|err| ReceivedEthEventError::InvalidEventSource {
+        source: event_source,
+        error: EventSourceError::InvalidEvent(format!("Invalid address in log entry: {}", err)),
+    })
+}
+
+fn parse_principal(
+    topic: &FixedSizeData,
+    event_source: EventSource,
+) -> Result<Principal, ReceivedEthEventError> {
+    parse_principal_from_slice(topic.as_ref()).map_err(|_err| {
+        ReceivedEthEventError::InvalidEventSource {
+            source: event_source,
+            error: EventSourceError::InvalidPrincipal {
+                invalid_principal: topic.clone(),
+            },
+        }
+    })
+}
+
+fn parse_value(data: Vec<u8>, event_source: EventSource) -> Result<[u8; 32], ReceivedEthEventError> {
+    data.try_into()
+        .map_err(|data| ReceivedEthEventError::InvalidEventSource {
+            source: event_source,
+            error: EventSourceError::InvalidEvent(format!(
+                "Invalid data length; expected 32-byte value, got {}",
+                hex::encode(data)
+            )),
+        })
+}
+
 /// Decode a candid::Principal from a slice of at most 32 bytes
 /// encoded as follows
 /// - the first byte is the number of bytes in the principal
@@ -169,6 +169,8 @@ impl std::str::FromStr for Hash {
 
 impl HttpResponsePayload for Hash {}
 
+impl HttpResponsePayload for Data {}
+
 /// Block tags.
 /// See <https://ethereum.org/en/developers/docs/apis/json-rpc/#default-block>
 #[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -370,6 +372,15 @@ pub struct FeeHistory {
     pub reward: Vec<Vec<WeiPerGas>>,
 }
 
+impl FeeHistory {
+    /// Highest number block of the returned range.
+    pub fn newest_block(&self) -> Option<BlockNumber> {
+        let block_count = self.reward.len().checked_sub(1)?;
+        self.oldest_block
+            .checked_add(BlockNumber::from(block_count as u64))
+    }
+}
+
 impl HttpResponsePayload for FeeHistory {
     fn response_transform() -> Option<ResponseTransform> {
         Some(ResponseTransform::FeeHistory)
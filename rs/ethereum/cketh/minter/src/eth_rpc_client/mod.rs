@@ -1,10 +1,10 @@
 use crate::eth_rpc::{
-    self, are_errors_consistent, Block, BlockSpec, FeeHistory, FeeHistoryParams, GetLogsParam,
-    Hash, HttpOutcallError, HttpOutcallResult, HttpResponsePayload, JsonRpcResult, LogEntry,
-    ResponseSizeEstimate, SendRawTransactionResult,
+    self, are_errors_consistent, Block, BlockSpec, Data, FeeHistory, FeeHistoryParams,
+    GetLogsParam, Hash, HttpOutcallError, HttpOutcallResult, HttpResponsePayload, JsonRpcResult,
+    LogEntry, ResponseSizeEstimate, SendRawTransactionResult,
 };
-use crate::eth_rpc_client::providers::{RpcNodeProvider, MAINNET_PROVIDERS, SEPOLIA_PROVIDERS};
-use crate::eth_rpc_client::requests::GetTransactionCountParams;
+use crate::eth_rpc_client::providers::{default_providers, RpcNodeProvider};
+use crate::eth_rpc_client::requests::{EthCallParams, GetTransactionCountParams};
 use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::lifecycle::EthereumNetwork;
 use crate::logs::{DEBUG, INFO};
@@ -22,27 +22,45 @@ pub mod responses;
 #[cfg(test)]
 mod tests;
 
+/// Returns true if the minter knows some JSON-RPC providers for the given network
+/// without them being explicitly configured.
+pub fn has_default_providers(network: EthereumNetwork) -> bool {
+    !default_providers(network).is_empty()
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EthRpcClient {
     chain: EthereumNetwork,
+    providers: Vec<RpcNodeProvider>,
 }
 
 impl EthRpcClient {
-    const fn new(chain: EthereumNetwork) -> Self {
-        Self { chain }
+    fn new(chain: EthereumNetwork) -> Self {
+        Self {
+            chain,
+            providers: default_providers(chain).to_vec(),
+        }
     }
 
-    pub const fn from_state(state: &State) -> Self {
-        Self::new(state.ethereum_network())
+    fn with_custom_providers(chain: EthereumNetwork, urls: &[String]) -> Self {
+        Self {
+            chain,
+            providers: urls.iter().cloned().map(RpcNodeProvider::Custom).collect(),
+        }
     }
 
-    fn providers(&self) -> &[RpcNodeProvider] {
-        match self.chain {
-            EthereumNetwork::Mainnet => &MAINNET_PROVIDERS,
-            EthereumNetwork::Sepolia => &SEPOLIA_PROVIDERS,
+    pub fn from_state(state: &State) -> Self {
+        if state.rpc_providers.is_empty() {
+            Self::new(state.ethereum_network())
+        } else {
+            Self::with_custom_providers(state.ethereum_network(), &state.rpc_providers)
         }
     }
 
+    fn providers(&self) -> &[RpcNodeProvider] {
+        &self.providers
+    }
+
     /// Query all providers in sequence until one returns an ok result
     /// (which could still be a JsonRpcResult::Error).
     /// If none of the providers return an ok result, return the last error.
@@ -142,6 +160,9 @@ impl EthRpcClient {
         let expected_block_size = match self.chain {
             EthereumNetwork::Sepolia => 12 * 1024,
             EthereumNetwork::Mainnet => 24 * 1024,
+            EthereumNetwork::ArbitrumOne | EthereumNetwork::Base | EthereumNetwork::Optimism => {
+                32 * 1024
+            }
         };
 
         let results: MultiCallResults<Block> = self
@@ -207,6 +228,12 @@ impl EthRpcClient {
         )
         .await
     }
+
+    pub async fn eth_call(&self, params: EthCallParams) -> MultiCallResults<Data> {
+        // The result of the contract calls made by the minter is at most a few ABI-encoded words.
+        self.parallel_call("eth_call", params, ResponseSizeEstimate::new(256))
+            .await
+    }
 }
 
 /// Aggregates responses of different providers to the same query.
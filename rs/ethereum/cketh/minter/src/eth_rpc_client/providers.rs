@@ -1,3 +1,5 @@
+use crate::lifecycle::EthereumNetwork;
+
 pub(crate) const MAINNET_PROVIDERS: [RpcNodeProvider; 3] = [
     RpcNodeProvider::Ethereum(EthereumProvider::Ankr),
     RpcNodeProvider::Ethereum(EthereumProvider::PublicNode),
@@ -9,10 +11,23 @@ pub(crate) const SEPOLIA_PROVIDERS: [RpcNodeProvider; 2] = [
     RpcNodeProvider::Sepolia(SepoliaProvider::PublicNode),
 ];
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+/// Returns the providers used by default on the given network.
+/// Layer 2 networks have no default providers, they must be specified
+/// in the minter's init or upgrade arguments.
+pub(crate) fn default_providers(network: EthereumNetwork) -> &'static [RpcNodeProvider] {
+    match network {
+        EthereumNetwork::Mainnet => &MAINNET_PROVIDERS,
+        EthereumNetwork::Sepolia => &SEPOLIA_PROVIDERS,
+        EthereumNetwork::ArbitrumOne | EthereumNetwork::Base | EthereumNetwork::Optimism => &[],
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub(crate) enum RpcNodeProvider {
     Ethereum(EthereumProvider),
     Sepolia(SepoliaProvider),
+    /// Provider given by its endpoint URL in the minter's init or upgrade arguments.
+    Custom(String),
 }
 
 impl RpcNodeProvider {
@@ -20,6 +35,7 @@ impl RpcNodeProvider {
         match self {
             Self::Ethereum(provider) => provider.ethereum_mainnet_endpoint_url(),
             Self::Sepolia(provider) => provider.ethereum_sepolia_endpoint_url(),
+            Self::Custom(url) => url,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::eth_rpc::BlockSpec;
+use crate::eth_rpc::{BlockSpec, Data};
 use ic_ethereum_types::Address;
 use serde::Serialize;
 
@@ -17,3 +17,28 @@ impl From<GetTransactionCountParams> for (Address, BlockSpec) {
         (params.address, params.block)
     }
 }
+
+/// Transaction object of the [`eth_call`](https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_call) call.
+#[derive(Debug, Serialize, Clone)]
+pub struct CallTransaction {
+    /// The address the transaction is directed to.
+    pub to: Address,
+    /// The hash of the method signature and encoded parameters.
+    pub data: Data,
+}
+
+/// Parameters of the [`eth_call`](https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_call) call.
+#[derive(Debug, Serialize, Clone)]
+#[serde(into = "(CallTransaction, BlockSpec)")]
+pub struct EthCallParams {
+    /// The transaction call object.
+    pub transaction: CallTransaction,
+    /// Integer block number, or "latest", "safe", "finalized" for the corresponding block tags.
+    pub block: BlockSpec,
+}
+
+impl From<EthCallParams> for (CallTransaction, BlockSpec) {
+    fn from(params: EthCallParams) -> Self {
+        (params.transaction, params.block)
+    }
+}
@@ -30,14 +30,28 @@ pub struct TransactionReceipt {
     /// The hash of the transaction
     #[n(5)]
     pub transaction_hash: Hash,
+
+    /// The fee paid to post the transaction data to Ethereum.
+    /// Only present on OP Stack networks (Base, Optimism).
+    #[n(6)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_fee: Option<Wei>,
 }
 
 impl TransactionReceipt {
-    pub fn effective_transaction_fee(&self) -> Wei {
+    /// The fee paid for the gas used by the transaction.
+    pub fn effective_gas_fee(&self) -> Wei {
         self.effective_gas_price
             .transaction_cost(self.gas_used)
             .expect("ERROR: overflow during transaction fee calculation")
     }
+
+    /// The total fee paid by the transaction, including the L1 data fee on OP Stack networks.
+    pub fn effective_transaction_fee(&self) -> Wei {
+        self.effective_gas_fee()
+            .checked_add(self.l1_fee.unwrap_or(Wei::ZERO))
+            .expect("ERROR: overflow during transaction fee calculation")
+    }
 }
 
 impl HttpResponsePayload for TransactionReceipt {
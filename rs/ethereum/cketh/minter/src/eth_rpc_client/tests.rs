@@ -1,6 +1,6 @@
 mod eth_rpc_client {
     use crate::eth_rpc_client::providers::{EthereumProvider, RpcNodeProvider, SepoliaProvider};
-    use crate::eth_rpc_client::EthRpcClient;
+    use crate::eth_rpc_client::{has_default_providers, EthRpcClient};
     use crate::lifecycle::EthereumNetwork;
 
     #[test]
@@ -33,6 +33,42 @@ mod eth_rpc_client {
             ]
         );
     }
+
+    #[test]
+    fn should_not_have_default_providers_for_layer_2_networks() {
+        for network in [
+            EthereumNetwork::ArbitrumOne,
+            EthereumNetwork::Base,
+            EthereumNetwork::Optimism,
+        ] {
+            assert!(!has_default_providers(network));
+            assert!(EthRpcClient::new(network).providers().is_empty());
+        }
+        assert!(has_default_providers(EthereumNetwork::Mainnet));
+        assert!(has_default_providers(EthereumNetwork::Sepolia));
+    }
+
+    #[test]
+    fn should_use_custom_providers_in_given_order() {
+        let client = EthRpcClient::with_custom_providers(
+            EthereumNetwork::ArbitrumOne,
+            &[
+                "https://arbitrum.example.com".to_string(),
+                "https://rpc.example.org/arbitrum".to_string(),
+            ],
+        );
+
+        let providers = client.providers();
+
+        assert_eq!(
+            providers,
+            &[
+                RpcNodeProvider::Custom("https://arbitrum.example.com".to_string()),
+                RpcNodeProvider::Custom("https://rpc.example.org/arbitrum".to_string())
+            ]
+        );
+        assert_eq!(providers[1].url(), "https://rpc.example.org/arbitrum");
+    }
 }
 
 mod multi_call_results {
@@ -501,7 +537,7 @@ mod multi_call_results {
 mod eth_get_transaction_receipt {
     use crate::eth_rpc::Hash;
     use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
-    use crate::numeric::{BlockNumber, GasAmount, WeiPerGas};
+    use crate::numeric::{BlockNumber, GasAmount, Wei, WeiPerGas};
     use assert_matches::assert_matches;
     use proptest::proptest;
     use std::str::FromStr;
@@ -542,10 +578,43 @@ mod eth_get_transaction_receipt {
                     "0x0e59bd032b9b22aca5e2784e4cf114783512db00988c716cf17a1cc755a0a93d"
                 )
                 .unwrap(),
+                l1_fee: None,
             }
         )
     }
 
+    #[test]
+    fn should_deserialize_l1_fee_of_op_stack_transaction_receipt() {
+        const RECEIPT: &str = r#"{
+        "transactionHash": "0x0e59bd032b9b22aca5e2784e4cf114783512db00988c716cf17a1cc755a0a93d",
+        "blockHash": "0x82005d2f17b251900968f01b0ed482cb49b7e1d797342bc504904d442b64dbe4",
+        "blockNumber": "0x4132ec",
+        "effectiveGasPrice": "0xfefbee3e",
+        "gasUsed": "0x5208",
+        "l1Fee": "0x2bbc3a8e5c",
+        "l1GasPrice": "0x3b9aca00",
+        "l1GasUsed": "0x640",
+        "status": "0x1",
+        "type": "0x2"
+    }"#;
+
+        let receipt: TransactionReceipt = serde_json::from_str(RECEIPT).unwrap();
+
+        assert_eq!(receipt.l1_fee, Some(Wei::new(0x2bbc3a8e5c)));
+        assert_eq!(
+            receipt.effective_transaction_fee(),
+            receipt
+                .effective_gas_fee()
+                .checked_add(Wei::new(0x2bbc3a8e5c))
+                .unwrap()
+        );
+        assert_eq!(
+            serde_json::from_str::<TransactionReceipt>(&serde_json::to_string(&receipt).unwrap())
+                .unwrap(),
+            receipt
+        );
+    }
+
     #[test]
     fn should_deserialize_transaction_status() {
         let status: TransactionStatus = serde_json::from_str("\"0x01\"").unwrap();
@@ -0,0 +1,236 @@
+//! Estimation of the fee charged by layer 2 networks to post the data of a transaction
+//! to Ethereum (L1 data fee).
+//!
+//! * On OP Stack networks (Base, Optimism), the L1 data fee is charged on top of the gas
+//!   used by the transaction and is estimated with `GasPriceOracle.getL1Fee`.
+//! * On Arbitrum, the L1 data fee is charged as additional L2 gas
+//!   and is estimated with `NodeInterface.gasEstimateComponents`.
+//!
+//! Like the other transaction fees, the L1 data fee is paid by the user withdrawing.
+use crate::erc20::encode_transfer_data;
+use crate::eth_rpc::{BlockSpec, Data};
+use crate::eth_rpc_client::requests::{CallTransaction, EthCallParams};
+use crate::eth_rpc_client::EthRpcClient;
+use crate::lifecycle::EthereumNetwork;
+use crate::numeric::{Erc20Value, GasAmount, TransactionNonce, Wei};
+use crate::tx::{Eip1559TransactionRequest, TransactionPrice};
+use hex_literal::hex;
+use ic_ethereum_types::Address;
+use std::cmp::Reverse;
+
+#[cfg(test)]
+mod tests;
+
+/// Address of the `GasPriceOracle` predeploy on OP Stack networks.
+///
+/// See <https://docs.optimism.io/stack/smart-contracts#gaspriceoracle>.
+pub const OP_STACK_GAS_PRICE_ORACLE_ADDRESS: Address =
+    Address::new(hex!("420000000000000000000000000000000000000F"));
+
+/// Address of the `NodeInterface` virtual contract on Arbitrum.
+///
+/// See <https://docs.arbitrum.io/build-decentralized-apps/nodeinterface/overview>.
+pub const ARBITRUM_NODE_INTERFACE_ADDRESS: Address =
+    Address::new(hex!("00000000000000000000000000000000000000C8"));
+
+/// Function selector of `getL1Fee(bytes)`,
+/// i.e., the first 4 bytes of `keccak256("getL1Fee(bytes)")`.
+const GET_L1_FEE_FUNCTION_SELECTOR: [u8; 4] = hex!("49948e0e");
+
+/// Function selector of `gasEstimateComponents(address,bool,bytes)`,
+/// i.e., the first 4 bytes of `keccak256("gasEstimateComponents(address,bool,bytes)")`.
+const GAS_ESTIMATE_COMPONENTS_FUNCTION_SELECTOR: [u8; 4] = hex!("c94e6eeb");
+
+/// The price of posting data to Ethereum may increase between the estimation
+/// and the inclusion of the transaction, the estimate is therefore multiplied by this factor.
+const L1_DATA_FEE_SAFETY_MARGIN: u8 = 2;
+
+const ABI_WORD_SIZE: usize = 32;
+
+/// Cost of posting the data of a transaction to Ethereum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L1DataFee {
+    /// Gas to add to the gas limit of the transaction (Arbitrum).
+    pub additional_gas_limit: GasAmount,
+    /// Fee charged on top of the gas used by the transaction (OP Stack).
+    pub fee: Wei,
+}
+
+impl L1DataFee {
+    pub const ZERO: Self = Self {
+        additional_gas_limit: GasAmount::ZERO,
+        fee: Wei::ZERO,
+    };
+}
+
+/// Estimates the L1 data fee of `transaction`, including a safety margin.
+/// The estimate is zero on networks that do not post their data to Ethereum.
+pub async fn estimate_l1_data_fee(
+    client: &EthRpcClient,
+    network: EthereumNetwork,
+    block: BlockSpec,
+    transaction: &Eip1559TransactionRequest,
+) -> Result<L1DataFee, String> {
+    match network {
+        EthereumNetwork::Mainnet | EthereumNetwork::Sepolia => Ok(L1DataFee::ZERO),
+        EthereumNetwork::Base | EthereumNetwork::Optimism => {
+            let fee = eth_call(
+                client,
+                OP_STACK_GAS_PRICE_ORACLE_ADDRESS,
+                encode_get_l1_fee_data(transaction),
+                block,
+                decode_get_l1_fee_result,
+            )
+            .await?;
+            Ok(L1DataFee {
+                additional_gas_limit: GasAmount::ZERO,
+                fee: fee
+                    .checked_mul(L1_DATA_FEE_SAFETY_MARGIN)
+                    .ok_or_else(|| format!("L1 data fee {fee} is too large"))?,
+            })
+        }
+        EthereumNetwork::ArbitrumOne => {
+            let gas = eth_call(
+                client,
+                ARBITRUM_NODE_INTERFACE_ADDRESS,
+                encode_gas_estimate_components_data(transaction),
+                block,
+                decode_gas_estimate_components_result,
+            )
+            .await?;
+            Ok(L1DataFee {
+                additional_gas_limit: gas
+                    .checked_mul(L1_DATA_FEE_SAFETY_MARGIN)
+                    .ok_or_else(|| format!("L1 gas estimate {gas} is too large"))?,
+                fee: Wei::ZERO,
+            })
+        }
+    }
+}
+
+async fn eth_call<T: Ord>(
+    client: &EthRpcClient,
+    to: Address,
+    data: Vec<u8>,
+    block: BlockSpec,
+    decode: fn(&[u8]) -> Result<T, String>,
+) -> Result<T, String> {
+    let result = client
+        .eth_call(EthCallParams {
+            transaction: CallTransaction {
+                to,
+                data: Data(data),
+            },
+            block,
+        })
+        .await
+        // Providers may have seen different blocks, keep the highest valid estimate.
+        .reduce_with_min_by_key(|result| Reverse(decode(result.as_ref()).ok()))
+        .map_err(|e| format!("eth_call to {to} failed: {e:?}"))?;
+    decode(result.as_ref())
+}
+
+/// Transaction of the same size as the largest ckETH withdrawal transaction.
+pub fn cketh_withdrawal_template(
+    network: EthereumNetwork,
+    transaction_price: &TransactionPrice,
+) -> Eip1559TransactionRequest {
+    withdrawal_template(network, transaction_price, Wei::new(u128::MAX), Vec::new())
+}
+
+/// Transaction of the same size as the largest ckERC20 withdrawal transaction.
+pub fn ckerc20_withdrawal_template(
+    network: EthereumNetwork,
+    transaction_price: &TransactionPrice,
+) -> Eip1559TransactionRequest {
+    withdrawal_template(
+        network,
+        transaction_price,
+        Wei::ZERO,
+        encode_transfer_data(&Address::new([0xff; 20]), Erc20Value::MAX),
+    )
+}
+
+fn withdrawal_template(
+    network: EthereumNetwork,
+    transaction_price: &TransactionPrice,
+    amount: Wei,
+    data: Vec<u8>,
+) -> Eip1559TransactionRequest {
+    Eip1559TransactionRequest {
+        chain_id: network.chain_id(),
+        nonce: TransactionNonce::from(u64::MAX),
+        max_priority_fee_per_gas: transaction_price.max_priority_fee_per_gas,
+        max_fee_per_gas: transaction_price.max_fee_per_gas,
+        gas_limit: transaction_price.gas_limit,
+        destination: Address::new([0xff; 20]),
+        amount,
+        data,
+        access_list: Default::default(),
+    }
+}
+
+/// Encodes a call to `getL1Fee(bytes _data)` on the unsigned transaction.
+///
+/// See <https://github.com/ethereum-optimism/optimism/blob/develop/packages/contracts-bedrock/src/L2/GasPriceOracle.sol>.
+pub fn encode_get_l1_fee_data(transaction: &Eip1559TransactionRequest) -> Vec<u8> {
+    let mut data = GET_L1_FEE_FUNCTION_SELECTOR.to_vec();
+    // offset of the dynamic `bytes` argument
+    data.extend_from_slice(&encode_uint(ABI_WORD_SIZE as u64));
+    encode_bytes(&mut data, &transaction.unsigned_bytes());
+    data
+}
+
+/// Encodes a call to `gasEstimateComponents(address to, bool contractCreation, bytes data)`
+/// for the given transaction.
+///
+/// See <https://docs.arbitrum.io/build-decentralized-apps/nodeinterface/reference>.
+pub fn encode_gas_estimate_components_data(transaction: &Eip1559TransactionRequest) -> Vec<u8> {
+    let mut data = GAS_ESTIMATE_COMPONENTS_FUNCTION_SELECTOR.to_vec();
+    data.extend_from_slice(&[0_u8; 12]);
+    data.extend_from_slice(transaction.destination.as_ref());
+    // contractCreation = false
+    data.extend_from_slice(&encode_uint(0));
+    // offset of the dynamic `bytes` argument
+    data.extend_from_slice(&encode_uint(3 * ABI_WORD_SIZE as u64));
+    encode_bytes(&mut data, &transaction.data);
+    data
+}
+
+/// Decodes the `uint256` L1 fee in Wei returned by `getL1Fee`.
+pub fn decode_get_l1_fee_result(result: &[u8]) -> Result<Wei, String> {
+    Ok(Wei::from_be_bytes(decode_word(result, 0)?))
+}
+
+/// Decodes the `gasEstimateForL1` returned by `gasEstimateComponents`,
+/// whose result is `(uint64 gasEstimate, uint64 gasEstimateForL1, uint256 baseFee, uint256 l1BaseFeeEstimate)`.
+pub fn decode_gas_estimate_components_result(result: &[u8]) -> Result<GasAmount, String> {
+    Ok(GasAmount::from_be_bytes(decode_word(result, 1)?))
+}
+
+fn encode_uint(value: u64) -> [u8; ABI_WORD_SIZE] {
+    let mut word = [0_u8; ABI_WORD_SIZE];
+    word[ABI_WORD_SIZE - 8..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn encode_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.extend_from_slice(&encode_uint(bytes.len() as u64));
+    data.extend_from_slice(bytes);
+    let padding = (ABI_WORD_SIZE - bytes.len() % ABI_WORD_SIZE) % ABI_WORD_SIZE;
+    data.extend(std::iter::repeat(0_u8).take(padding));
+}
+
+fn decode_word(result: &[u8], index: usize) -> Result<[u8; ABI_WORD_SIZE], String> {
+    result
+        .get(index * ABI_WORD_SIZE..(index + 1) * ABI_WORD_SIZE)
+        .map(|word| <[u8; ABI_WORD_SIZE]>::try_from(word).unwrap())
+        .ok_or_else(|| {
+            format!(
+                "expected at least {} bytes, got {}: 0x{}",
+                (index + 1) * ABI_WORD_SIZE,
+                result.len(),
+                hex::encode(result)
+            )
+        })
+}
@@ -0,0 +1,127 @@
+use crate::l1_data_fee::{
+    ckerc20_withdrawal_template, cketh_withdrawal_template, decode_gas_estimate_components_result,
+    decode_get_l1_fee_result, encode_gas_estimate_components_data, encode_get_l1_fee_data,
+};
+use crate::lifecycle::EthereumNetwork;
+use crate::numeric::{GasAmount, TransactionNonce, Wei, WeiPerGas};
+use crate::tx::{Eip1559TransactionRequest, TransactionPrice};
+use ic_ethereum_types::Address;
+use std::str::FromStr;
+
+mod encode {
+    use super::*;
+
+    #[test]
+    fn should_encode_get_l1_fee_call_on_unsigned_transaction() {
+        let transaction = transaction(vec![]);
+        let unsigned_bytes = transaction.unsigned_bytes();
+
+        let data = encode_get_l1_fee_data(&transaction);
+
+        assert_eq!(hex::encode(&data[..4]), "49948e0e");
+        assert_eq!(
+            hex::encode(&data[4..36]),
+            "0000000000000000000000000000000000000000000000000000000000000020"
+        );
+        assert_eq!(
+            data[36..68],
+            super::word(unsigned_bytes.len() as u8)[..],
+            "length of the bytes argument"
+        );
+        assert_eq!(data[68..68 + unsigned_bytes.len()], unsigned_bytes[..]);
+        assert_eq!((data.len() - 4) % 32, 0, "bytes argument must be padded");
+        assert!(data[68 + unsigned_bytes.len()..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn should_encode_gas_estimate_components_call_on_calldata() {
+        let transaction = transaction(vec![0xa9, 0x05, 0x9c, 0xbb]);
+
+        let data = encode_gas_estimate_components_data(&transaction);
+
+        assert_eq!(
+            hex::encode(data),
+            "c94e6eeb\
+             000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d\
+             0000000000000000000000000000000000000000000000000000000000000000\
+             0000000000000000000000000000000000000000000000000000000000000060\
+             0000000000000000000000000000000000000000000000000000000000000004\
+             a9059cbb00000000000000000000000000000000000000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn should_use_largest_withdrawal_transactions_as_templates() {
+        let price = TransactionPrice {
+            gas_limit: GasAmount::from(21_000_u32),
+            max_fee_per_gas: WeiPerGas::from(1_000_000_000_u64),
+            max_priority_fee_per_gas: WeiPerGas::from(1_000_000_u64),
+        };
+        let cketh = cketh_withdrawal_template(EthereumNetwork::Base, &price);
+        let ckerc20 = ckerc20_withdrawal_template(EthereumNetwork::Base, &price);
+
+        for template in [&cketh, &ckerc20] {
+            assert_eq!(template.chain_id, EthereumNetwork::Base.chain_id());
+            assert_eq!(template.transaction_price(), price);
+        }
+
+        let mut small_withdrawal = cketh.clone();
+        small_withdrawal.nonce = TransactionNonce::ZERO;
+        small_withdrawal.amount = Wei::ONE;
+        assert!(small_withdrawal.unsigned_bytes().len() < cketh.unsigned_bytes().len());
+        assert_eq!(ckerc20.data.len(), 4 + 32 + 32);
+    }
+}
+
+mod decode {
+    use super::*;
+
+    #[test]
+    fn should_decode_l1_fee() {
+        let result = super::word(0x2a);
+
+        assert_eq!(decode_get_l1_fee_result(&result), Ok(Wei::from(0x2a_u8)));
+    }
+
+    #[test]
+    fn should_decode_gas_estimate_for_l1() {
+        let result: Vec<u8> = [
+            super::word(100),
+            super::word(42),
+            super::word(10),
+            super::word(20),
+        ]
+        .concat();
+
+        assert_eq!(
+            decode_gas_estimate_components_result(&result),
+            Ok(GasAmount::from(42_u8))
+        );
+    }
+
+    #[test]
+    fn should_fail_to_decode_truncated_results() {
+        assert!(decode_get_l1_fee_result(&[0_u8; 31]).is_err());
+        assert!(decode_gas_estimate_components_result(&super::word(42)).is_err());
+    }
+}
+
+fn word(value: u8) -> [u8; 32] {
+    let mut word = [0_u8; 32];
+    word[31] = value;
+    word
+}
+
+fn transaction(data: Vec<u8>) -> Eip1559TransactionRequest {
+    Eip1559TransactionRequest {
+        chain_id: EthereumNetwork::Base.chain_id(),
+        nonce: TransactionNonce::from(6_u8),
+        max_priority_fee_per_gas: WeiPerGas::from(1_000_000_u64),
+        max_fee_per_gas: WeiPerGas::from(1_000_000_000_u64),
+        gas_limit: GasAmount::from(65_000_u32),
+        destination: Address::from_str("0xdd2851Cdd40aE6536831558DD46db62fAc7A844d").unwrap(),
+        amount: Wei::ZERO,
+        data,
+        access_list: Default::default(),
+    }
+}
@@ -28,6 +28,12 @@ pub enum EthereumNetwork {
     #[n(11155111)]
     #[default]
     Sepolia,
+    #[n(42161)]
+    ArbitrumOne,
+    #[n(8453)]
+    Base,
+    #[n(10)]
+    Optimism,
 }
 
 impl EthereumNetwork {
@@ -35,6 +41,34 @@ impl EthereumNetwork {
         match self {
             EthereumNetwork::Mainnet => 1,
             EthereumNetwork::Sepolia => 11155111,
+            EthereumNetwork::ArbitrumOne => 42161,
+            EthereumNetwork::Base => 8453,
+            EthereumNetwork::Optimism => 10,
+        }
+    }
+
+    /// Returns true if the network is a layer 2 rollup settling on Ethereum.
+    pub fn is_layer_2(&self) -> bool {
+        match self {
+            EthereumNetwork::Mainnet | EthereumNetwork::Sepolia => false,
+            EthereumNetwork::ArbitrumOne | EthereumNetwork::Base | EthereumNetwork::Optimism => {
+                true
+            }
+        }
+    }
+}
+
+impl TryFrom<u64> for EthereumNetwork {
+    type Error = String;
+
+    fn try_from(chain_id: u64) -> Result<Self, Self::Error> {
+        match chain_id {
+            1 => Ok(EthereumNetwork::Mainnet),
+            11155111 => Ok(EthereumNetwork::Sepolia),
+            42161 => Ok(EthereumNetwork::ArbitrumOne),
+            8453 => Ok(EthereumNetwork::Base),
+            10 => Ok(EthereumNetwork::Optimism),
+            _ => Err(format!("unsupported chain id {chain_id}")),
         }
     }
 }
@@ -44,6 +78,9 @@ impl Display for EthereumNetwork {
         match self {
             EthereumNetwork::Mainnet => write!(f, "Ethereum Mainnet"),
             EthereumNetwork::Sepolia => write!(f, "Ethereum Testnet Sepolia"),
+            EthereumNetwork::ArbitrumOne => write!(f, "Arbitrum One"),
+            EthereumNetwork::Base => write!(f, "Base"),
+            EthereumNetwork::Optimism => write!(f, "OP Mainnet"),
         }
     }
 }
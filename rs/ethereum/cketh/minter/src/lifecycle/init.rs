@@ -28,6 +28,12 @@ pub struct InitArg {
     pub next_transaction_nonce: Nat,
     #[cbor(n(8), with = "crate::cbor::nat")]
     pub last_scraped_block_number: Nat,
+    #[n(9)]
+    pub rpc_providers: Option<Vec<String>>,
+    #[n(10)]
+    pub withdrawal_finality_block_tag: Option<CandidBlockTag>,
+    #[n(11)]
+    pub fee_estimation_block_tag: Option<CandidBlockTag>,
 }
 
 impl TryFrom<InitArg> for State {
@@ -42,6 +48,9 @@ impl TryFrom<InitArg> for State {
             minimum_withdrawal_amount,
             next_transaction_nonce,
             last_scraped_block_number,
+            rpc_providers,
+            withdrawal_finality_block_tag,
+            fee_estimation_block_tag,
         }: InitArg,
     ) -> Result<Self, Self::Error> {
         use std::str::FromStr;
@@ -90,6 +99,22 @@ impl TryFrom<InitArg> for State {
             active_tasks: Default::default(),
             http_request_counter: 0,
             last_transaction_price_estimate: None,
+            last_l1_data_fee_estimate: None,
+            ledger_suite_orchestrator_id: None,
+            erc20_helper_contract_address: None,
+            ckerc20_tokens: Default::default(),
+            last_erc20_scraped_block_number: last_scraped_block_number,
+            erc20_skipped_blocks: Default::default(),
+            erc20_events_to_mint: Default::default(),
+            minted_erc20_events: Default::default(),
+            erc20_balances: Default::default(),
+            rpc_providers: rpc_providers.unwrap_or_default(),
+            withdrawal_finality_block_tag: withdrawal_finality_block_tag
+                .map(BlockTag::from)
+                .unwrap_or(BlockTag::Finalized),
+            fee_estimation_block_tag: fee_estimation_block_tag
+                .map(BlockTag::from)
+                .unwrap_or(BlockTag::Latest),
         };
         state.validate_config()?;
         Ok(state)
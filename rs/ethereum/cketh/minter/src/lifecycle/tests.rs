@@ -1,5 +1,8 @@
 mod init {
+    use crate::endpoints::CandidBlockTag;
+    use crate::eth_rpc::BlockTag;
     use crate::lifecycle::init::InitArg;
+    use crate::lifecycle::EthereumNetwork;
     use crate::numeric::{TransactionNonce, Wei};
     use crate::state::{InvalidStateError, State};
     use assert_matches::assert_matches;
@@ -69,6 +72,51 @@ mod init {
             }),
             Err(InvalidStateError::InvalidLastScrapedBlockNumber(_))
         );
+
+        assert_matches!(
+            State::try_from(InitArg {
+                ethereum_network: EthereumNetwork::ArbitrumOne,
+                ..valid_init_arg()
+            }),
+            Err(InvalidStateError::InvalidRpcProviders(_))
+        );
+
+        assert_matches!(
+            State::try_from(InitArg {
+                rpc_providers: Some(vec!["http://localhost:8545".to_string()]),
+                ..valid_init_arg()
+            }),
+            Err(InvalidStateError::InvalidRpcProviders(_))
+        );
+
+        assert_matches!(
+            State::try_from(InitArg {
+                rpc_providers: Some(vec![
+                    "https://eth.example.com".to_string(),
+                    "https://eth.example.com".to_string(),
+                ]),
+                ..valid_init_arg()
+            }),
+            Err(InvalidStateError::InvalidRpcProviders(_))
+        );
+    }
+
+    #[test]
+    fn should_succeed_on_layer_2_with_rpc_providers() {
+        let providers = vec![
+            "https://base.example.com".to_string(),
+            "https://base-backup.example.com".to_string(),
+        ];
+        let state = State::try_from(InitArg {
+            ethereum_network: EthereumNetwork::Base,
+            rpc_providers: Some(providers.clone()),
+            ..valid_init_arg()
+        })
+        .expect("valid init args");
+
+        assert_eq!(state.ethereum_network, EthereumNetwork::Base);
+        assert_eq!(state.ethereum_network.chain_id(), 8453);
+        assert_eq!(state.rpc_providers, providers);
     }
 
     #[test]
@@ -86,6 +134,21 @@ mod init {
             state.eth_transactions.next_transaction_nonce(),
             TransactionNonce::ZERO
         );
+        assert_eq!(state.withdrawal_finality_block_tag, BlockTag::Finalized);
+        assert_eq!(state.fee_estimation_block_tag, BlockTag::Latest);
+    }
+
+    #[test]
+    fn should_use_configured_block_tags() {
+        let state = State::try_from(InitArg {
+            withdrawal_finality_block_tag: Some(CandidBlockTag::Safe),
+            fee_estimation_block_tag: Some(CandidBlockTag::Finalized),
+            ..valid_init_arg()
+        })
+        .expect("valid init args");
+
+        assert_eq!(state.withdrawal_finality_block_tag, BlockTag::Safe);
+        assert_eq!(state.fee_estimation_block_tag, BlockTag::Finalized);
     }
 
     fn valid_init_arg() -> InitArg {
@@ -99,6 +162,9 @@ mod init {
             minimum_withdrawal_amount: Wei::TWO.into(),
             next_transaction_nonce: TransactionNonce::ZERO.into(),
             last_scraped_block_number: Default::default(),
+            rpc_providers: None,
+            withdrawal_finality_block_tag: None,
+            fee_estimation_block_tag: None,
         }
     }
 }
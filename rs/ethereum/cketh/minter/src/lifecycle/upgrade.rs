@@ -4,7 +4,7 @@ use crate::state::audit::{process_event, replay_events, EventType};
 use crate::state::mutate_state;
 use crate::state::STATE;
 use crate::storage::total_event_count;
-use candid::{CandidType, Deserialize, Nat};
+use candid::{CandidType, Deserialize, Nat, Principal};
 use ic_canister_log::log;
 use minicbor::{Decode, Encode};
 
@@ -18,6 +18,18 @@ pub struct UpgradeArg {
     pub ethereum_contract_address: Option<String>,
     #[n(3)]
     pub ethereum_block_height: Option<CandidBlockTag>,
+    #[n(4)]
+    pub erc20_helper_contract_address: Option<String>,
+    #[cbor(n(5), with = "crate::cbor::nat::option")]
+    pub last_erc20_scraped_block_number: Option<Nat>,
+    #[cbor(n(6), with = "crate::cbor::principal::option")]
+    pub ledger_suite_orchestrator_id: Option<Principal>,
+    #[n(7)]
+    pub rpc_providers: Option<Vec<String>>,
+    #[n(8)]
+    pub withdrawal_finality_block_tag: Option<CandidBlockTag>,
+    #[n(9)]
+    pub fee_estimation_block_tag: Option<CandidBlockTag>,
 }
 
 pub fn post_upgrade(upgrade_args: Option<UpgradeArg>) {
@@ -1,10 +1,10 @@
 #[cfg(test)]
 mod tests;
 
-use crate::eth_logs::ReceivedEthEvent;
+use crate::eth_logs::{ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::Hash;
-use crate::numeric::LogIndex;
-use crate::state::transactions::ReimbursementRequest;
+use crate::numeric::{Erc20Value, LedgerBurnIndex, LogIndex};
+use crate::state::transactions::{Erc20ReimbursementRequest, ReimbursementRequest};
 use ic_ethereum_types::Address;
 use icrc_ledger_types::icrc1::transfer::Memo;
 use minicbor::{Decode, Encode, Encoder};
@@ -39,6 +39,14 @@ pub enum MintMemo {
         /// Hash of the failed transaction.
         tx_hash: Hash,
     },
+    #[n(2)]
+    /// The minter failed to process a withdrawal request,
+    /// so no transaction was issued, but some reimbursement was made.
+    ReimburseWithdrawal {
+        #[n(0)]
+        /// The id corresponding to the withdrawal request.
+        withdrawal_id: u64,
+    },
 }
 
 impl From<MintMemo> for Memo {
@@ -56,6 +64,30 @@ pub enum BurnMemo {
         /// The destination of the withdraw request.
         to_address: Address,
     },
+    #[n(1)]
+    /// The minter processed a ckERC20 withdrawal request
+    /// and that burn pays the transaction fee.
+    Erc20GasFee {
+        #[n(0)]
+        /// ckERC20 token symbol of the withdrawal request.
+        ckerc20_token_symbol: String,
+        #[n(1)]
+        /// The amount of the ckERC20 withdrawal request.
+        ckerc20_withdraw_amount: Erc20Value,
+        #[n(2)]
+        /// The destination of the withdrawal request.
+        to_address: Address,
+    },
+    #[n(2)]
+    /// The minter processed a ckERC20 withdrawal request.
+    Erc20Convert {
+        #[n(0)]
+        /// ckETH ledger burn index identifying the burn to pay for the transaction fee.
+        ckerc20_withdrawal_id: u64,
+        #[n(1)]
+        /// The destination of the withdrawal request.
+        to_address: Address,
+    },
 }
 
 impl From<BurnMemo> for Memo {
@@ -74,13 +106,43 @@ impl From<ReceivedEthEvent> for Memo {
     }
 }
 
+impl From<ReceivedErc20Event> for Memo {
+    fn from(event: ReceivedErc20Event) -> Self {
+        Memo::from(MintMemo::Convert {
+            from_address: event.from_address,
+            tx_hash: event.transaction_hash,
+            log_index: event.log_index,
+        })
+    }
+}
+
 impl From<ReimbursementRequest> for Memo {
     fn from(reimbursement_request: ReimbursementRequest) -> Self {
+        match reimbursement_request.transaction_hash {
+            Some(tx_hash) => Memo::from(MintMemo::Reimburse {
+                withdrawal_id: reimbursement_request.withdrawal_id.get(),
+                tx_hash,
+            }),
+            None => Memo::from(MintMemo::ReimburseWithdrawal {
+                withdrawal_id: reimbursement_request.withdrawal_id.get(),
+            }),
+        }
+    }
+}
+
+impl From<Erc20ReimbursementRequest> for Memo {
+    fn from(reimbursement_request: Erc20ReimbursementRequest) -> Self {
         Memo::from(MintMemo::Reimburse {
             withdrawal_id: reimbursement_request.withdrawal_id.get(),
-            tx_hash: reimbursement_request
-                .transaction_hash
-                .expect("A hash should be set for reimbursement memos."),
+            tx_hash: reimbursement_request.transaction_hash,
         })
     }
 }
+
+/// Memo of the ckERC20 burn of the withdrawal request identified by `withdrawal_id`.
+pub fn erc20_convert_memo(withdrawal_id: LedgerBurnIndex, to_address: Address) -> Memo {
+    Memo::from(BurnMemo::Erc20Convert {
+        ckerc20_withdrawal_id: withdrawal_id.get(),
+        to_address,
+    })
+}
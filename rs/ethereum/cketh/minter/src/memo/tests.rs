@@ -3,7 +3,7 @@ mod decode_memo {
     use crate::eth_rpc::Hash;
     use crate::memo::{Address, ReceivedEthEvent};
     use crate::memo::{BurnMemo, MintMemo};
-    use crate::numeric::{BlockNumber, LedgerBurnIndex, LogIndex, Wei};
+    use crate::numeric::{BlockNumber, Erc20Value, LedgerBurnIndex, LogIndex, Wei};
     use crate::state::transactions::ReimbursementRequest;
     use candid::Principal;
     use icrc_ledger_types::icrc1::transfer::Memo;
@@ -44,6 +44,15 @@ mod decode_memo {
             })?;
         }
 
+        #[test]
+        fn mint_reimburse_withdrawal_memo_round_trip(
+            withdrawal_id in any::<u64>(),
+        ) {
+            check_roundtrip(&MintMemo::ReimburseWithdrawal {
+                withdrawal_id,
+            })?;
+        }
+
         #[test]
         fn burn_memo_round_trip(
             to_address in arb_address(),
@@ -52,6 +61,29 @@ mod decode_memo {
                 to_address
             })?;
         }
+
+        #[test]
+        fn burn_erc20_gas_fee_memo_round_trip(
+            ckerc20_withdraw_amount in any::<u128>(),
+            to_address in arb_address(),
+        ) {
+            check_roundtrip(&BurnMemo::Erc20GasFee {
+                ckerc20_token_symbol: "ckUSDC".to_string(),
+                ckerc20_withdraw_amount: Erc20Value::from(ckerc20_withdraw_amount),
+                to_address,
+            })?;
+        }
+
+        #[test]
+        fn burn_erc20_convert_memo_round_trip(
+            ckerc20_withdrawal_id in any::<u64>(),
+            to_address in arb_address(),
+        ) {
+            check_roundtrip(&BurnMemo::Erc20Convert {
+                ckerc20_withdrawal_id,
+                to_address,
+            })?;
+        }
     }
 
     #[test]
@@ -12,6 +12,10 @@ pub type Wei = CheckedAmountOf<WeiTag>;
 pub enum WeiPerGasUnit {}
 pub type WeiPerGas = CheckedAmountOf<WeiPerGasUnit>;
 
+pub enum Erc20Tag {}
+/// Amount of ERC-20 tokens in the smallest unit of the token.
+pub type Erc20Value = CheckedAmountOf<Erc20Tag>;
+
 pub fn wei_from_milli_ether(value: u128) -> Wei {
     const MILLI_ETHER: u64 = 1_000_000_000_000_000_000;
     Wei::new(value)
@@ -1,12 +1,16 @@
 use crate::address::ecdsa_public_key_to_address;
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::erc20::CkErc20Token;
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::BlockTag;
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
 use crate::lifecycle::upgrade::UpgradeArg;
 use crate::lifecycle::EthereumNetwork;
 use crate::logs::DEBUG;
-use crate::numeric::{BlockNumber, LedgerBurnIndex, LedgerMintIndex, TransactionNonce, Wei};
+use crate::numeric::{
+    BlockNumber, Erc20Value, LedgerBurnIndex, LedgerMintIndex, TransactionNonce, Wei,
+};
 use crate::tx::TransactionPriceEstimate;
+use crate::withdraw::L1DataFeeEstimate;
 use candid::Principal;
 use ic_canister_log::log;
 use ic_cdk::api::management_canister::ecdsa::EcdsaPublicKeyResponse;
@@ -40,6 +44,19 @@ impl MintedEvent {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintedErc20Event {
+    pub deposit_event: ReceivedErc20Event,
+    pub mint_block_index: LedgerMintIndex,
+    pub ckerc20_token_symbol: String,
+}
+
+impl MintedErc20Event {
+    pub fn source(&self) -> EventSource {
+        self.deposit_event.source()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct State {
     pub ethereum_network: EthereumNetwork,
@@ -72,6 +89,33 @@ pub struct State {
     pub http_request_counter: u64,
 
     pub last_transaction_price_estimate: Option<(u64, TransactionPriceEstimate)>,
+    /// L1 data fee estimate refreshed together with `last_transaction_price_estimate`.
+    pub last_l1_data_fee_estimate: Option<L1DataFeeEstimate>,
+
+    /// Canister ID of the ledger suite orchestrator that
+    /// can add new ERC-20 tokens to the minter.
+    pub ledger_suite_orchestrator_id: Option<Principal>,
+    /// Address of the helper smart contract for ERC-20 deposits.
+    pub erc20_helper_contract_address: Option<Address>,
+    /// ERC-20 tokens supported by the minter, indexed by their contract address.
+    pub ckerc20_tokens: BTreeMap<Address, CkErc20Token>,
+    pub last_erc20_scraped_block_number: BlockNumber,
+    pub erc20_skipped_blocks: BTreeSet<BlockNumber>,
+    pub erc20_events_to_mint: BTreeMap<EventSource, ReceivedErc20Event>,
+    pub minted_erc20_events: BTreeMap<EventSource, MintedErc20Event>,
+    /// Current balance of ERC-20 tokens held by the minter, indexed by contract address.
+    /// Computed based on audit events.
+    pub erc20_balances: BTreeMap<Address, Erc20Value>,
+
+    /// URLs of the JSON-RPC providers used to interact with the network.
+    /// If empty, the default providers of the network are used.
+    pub rpc_providers: Vec<String>,
+
+    /// Block at which the transaction count of the minter is read
+    /// to decide whether a sent transaction is finalized.
+    pub withdrawal_finality_block_tag: BlockTag,
+    /// Block up to which the fee history is read to estimate transaction fees.
+    pub fee_estimation_block_tag: BlockTag,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -82,6 +126,9 @@ pub enum InvalidStateError {
     InvalidEthereumContractAddress(String),
     InvalidMinimumWithdrawalAmount(String),
     InvalidLastScrapedBlockNumber(String),
+    InvalidErc20HelperContractAddress(String),
+    InvalidLedgerSuiteOrchestratorId(String),
+    InvalidRpcProviders(String),
 }
 
 impl State {
@@ -105,11 +152,55 @@ impl State {
                 "ethereum_contract_address cannot be the zero address".to_string(),
             ));
         }
+        if self
+            .erc20_helper_contract_address
+            .iter()
+            .any(|address| address == &Address::ZERO)
+        {
+            return Err(InvalidStateError::InvalidErc20HelperContractAddress(
+                "erc20_helper_contract_address cannot be the zero address".to_string(),
+            ));
+        }
+        if self
+            .ledger_suite_orchestrator_id
+            .iter()
+            .any(|id| id == &Principal::anonymous())
+        {
+            return Err(InvalidStateError::InvalidLedgerSuiteOrchestratorId(
+                "ledger_suite_orchestrator_id cannot be the anonymous principal".to_string(),
+            ));
+        }
         if self.minimum_withdrawal_amount == Wei::ZERO {
             return Err(InvalidStateError::InvalidMinimumWithdrawalAmount(
                 "minimum_withdrawal_amount must be positive".to_string(),
             ));
         }
+        self.validate_rpc_providers()
+    }
+
+    fn validate_rpc_providers(&self) -> Result<(), InvalidStateError> {
+        if self.rpc_providers.is_empty() {
+            if !crate::eth_rpc_client::has_default_providers(self.ethereum_network) {
+                return Err(InvalidStateError::InvalidRpcProviders(format!(
+                    "rpc_providers must be specified for {}",
+                    self.ethereum_network
+                )));
+            }
+            return Ok(());
+        }
+        let mut seen = BTreeSet::new();
+        for url in &self.rpc_providers {
+            if !url.starts_with("https://") {
+                return Err(InvalidStateError::InvalidRpcProviders(format!(
+                    "rpc provider URL {url} must use HTTPS"
+                )));
+            }
+            if !seen.insert(url) {
+                return Err(InvalidStateError::InvalidRpcProviders(format!(
+                    "duplicate rpc provider URL {url}"
+                )));
+            }
+        }
         Ok(())
     }
 
@@ -135,17 +226,82 @@ impl State {
         self.update_eth_balance_upon_deposit(event)
     }
 
+    fn record_erc20_event_to_mint(&mut self, event: &ReceivedErc20Event) {
+        let event_source = event.source();
+        assert!(
+            !self.erc20_events_to_mint.contains_key(&event_source),
+            "there must be no two different events with the same source"
+        );
+        assert!(!self.minted_erc20_events.contains_key(&event_source));
+        assert!(!self.invalid_events.contains_key(&event_source));
+        assert!(
+            self.ckerc20_tokens
+                .contains_key(&event.erc20_contract_address),
+            "BUG: unsupported ERC-20 token {}",
+            event.erc20_contract_address
+        );
+
+        self.erc20_events_to_mint
+            .insert(event_source, event.clone());
+
+        self.erc20_balance_add(event.erc20_contract_address, event.value);
+    }
+
     pub fn has_events_to_mint(&self) -> bool {
-        !self.events_to_mint.is_empty()
+        !self.events_to_mint.is_empty() || !self.erc20_events_to_mint.is_empty()
+    }
+
+    pub fn find_ck_erc20_token_by_ledger_id(&self, ledger_id: &Principal) -> Option<CkErc20Token> {
+        self.ckerc20_tokens
+            .values()
+            .find(|token| &token.ckerc20_ledger_id == ledger_id)
+            .cloned()
+    }
+
+    fn record_add_ckerc20_token(&mut self, ckerc20_token: CkErc20Token) {
+        assert_eq!(
+            self.ethereum_network, ckerc20_token.erc20_ethereum_network,
+            "ERROR: Expected {}, but got {}",
+            self.ethereum_network, ckerc20_token.erc20_ethereum_network
+        );
+        assert!(
+            self.find_ck_erc20_token_by_ledger_id(&ckerc20_token.ckerc20_ledger_id)
+                .is_none(),
+            "ERROR: ckERC20 ledger {} is already used by another token",
+            ckerc20_token.ckerc20_ledger_id
+        );
+        assert!(
+            !self
+                .ckerc20_tokens
+                .values()
+                .any(|token| token.ckerc20_token_symbol == ckerc20_token.ckerc20_token_symbol),
+            "ERROR: ckERC20 token symbol {} is already used by another token",
+            ckerc20_token.ckerc20_token_symbol
+        );
+        match self
+            .ckerc20_tokens
+            .entry(ckerc20_token.erc20_contract_address)
+        {
+            btree_map::Entry::Occupied(entry) => panic!(
+                "ERROR: ERC-20 token {} is already supported as {:?}",
+                entry.key(),
+                entry.get()
+            ),
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(ckerc20_token);
+            }
+        }
     }
 
     fn record_invalid_deposit(&mut self, source: EventSource, error: String) -> bool {
         assert!(
-            !self.events_to_mint.contains_key(&source),
+            !self.events_to_mint.contains_key(&source)
+                && !self.erc20_events_to_mint.contains_key(&source),
             "attempted to mark an accepted event as invalid"
         );
         assert!(
-            !self.minted_events.contains_key(&source),
+            !self.minted_events.contains_key(&source)
+                && !self.minted_erc20_events.contains_key(&source),
             "attempted to mark a minted event {source:?} as invalid"
         );
 
@@ -181,14 +337,52 @@ impl State {
         );
     }
 
+    fn record_successful_erc20_mint(
+        &mut self,
+        source: EventSource,
+        ckerc20_token_symbol: &str,
+        mint_block_index: LedgerMintIndex,
+    ) {
+        assert!(
+            !self.invalid_events.contains_key(&source),
+            "attempted to mint an event previously marked as invalid {source:?}"
+        );
+        let deposit_event = match self.erc20_events_to_mint.remove(&source) {
+            Some(event) => event,
+            None => panic!("attempted to mint ckERC20 for an unknown event {source:?}"),
+        };
+
+        assert_eq!(
+            self.minted_erc20_events.insert(
+                source,
+                MintedErc20Event {
+                    deposit_event,
+                    mint_block_index,
+                    ckerc20_token_symbol: ckerc20_token_symbol.to_string(),
+                }
+            ),
+            None,
+            "attempted to mint ckERC20 twice for the same event {source:?}"
+        );
+    }
+
     pub fn record_finalized_transaction(
         &mut self,
         withdrawal_id: &LedgerBurnIndex,
         receipt: &TransactionReceipt,
     ) {
+        let erc20_withdrawal_request = self
+            .eth_transactions
+            .get_erc20_withdrawal_request(withdrawal_id)
+            .cloned();
         self.eth_transactions
             .record_finalized_transaction(*withdrawal_id, receipt.clone());
         self.update_eth_balance_upon_withdrawal(withdrawal_id, receipt);
+        if let Some(request) = erc20_withdrawal_request {
+            if receipt.status == TransactionStatus::Success {
+                self.erc20_balance_sub(request.erc20_contract_address, request.withdrawal_amount);
+            }
+        }
     }
 
     pub fn next_request_id(&mut self) -> u64 {
@@ -214,8 +408,12 @@ impl State {
             .finalized_tx
             .get_alt(withdrawal_id)
             .expect("BUG: missing finalized transaction");
+        // The L1 data fee is not part of the transaction price,
+        // so that only the fee paid for the gas is compared to the charged fee.
         let charged_tx_fee = tx.transaction_price().max_transaction_fee();
-        let unspent_tx_fee = charged_tx_fee.checked_sub(tx_fee).expect(
+        let unspent_tx_fee = charged_tx_fee
+            .checked_sub(receipt.effective_gas_fee())
+            .expect(
             "BUG: charged transaction fee MUST always be at least the effective transaction fee",
         );
         let debited_amount = match receipt.status {
@@ -231,6 +429,26 @@ impl State {
         self.eth_balance.total_unspent_tx_fees_add(unspent_tx_fee);
     }
 
+    fn erc20_balance_add(&mut self, erc20_contract_address: Address, value: Erc20Value) {
+        let balance = self
+            .erc20_balances
+            .entry(erc20_contract_address)
+            .or_insert(Erc20Value::ZERO);
+        *balance = balance.checked_add(value).unwrap_or_else(|| {
+            panic!("BUG: overflow when adding {value} to {balance} for ERC-20 token {erc20_contract_address}")
+        });
+    }
+
+    fn erc20_balance_sub(&mut self, erc20_contract_address: Address, value: Erc20Value) {
+        let balance = self
+            .erc20_balances
+            .get_mut(&erc20_contract_address)
+            .unwrap_or_else(|| panic!("BUG: no balance for ERC-20 token {erc20_contract_address}"));
+        *balance = balance.checked_sub(value).unwrap_or_else(|| {
+            panic!("BUG: underflow when subtracting {value} from {balance} for ERC-20 token {erc20_contract_address}")
+        });
+    }
+
     pub fn record_skipped_block(&mut self, block_number: BlockNumber) {
         assert!(
             self.skipped_blocks.insert(block_number),
@@ -239,6 +457,14 @@ impl State {
         );
     }
 
+    pub fn record_skipped_erc20_block(&mut self, block_number: BlockNumber) {
+        assert!(
+            self.erc20_skipped_blocks.insert(block_number),
+            "BUG: block {} was already skipped for ERC-20 deposits",
+            block_number
+        );
+    }
+
     pub const fn ethereum_network(&self) -> EthereumNetwork {
         self.ethereum_network
     }
@@ -255,6 +481,12 @@ impl State {
             minimum_withdrawal_amount,
             ethereum_contract_address,
             ethereum_block_height,
+            erc20_helper_contract_address,
+            last_erc20_scraped_block_number,
+            ledger_suite_orchestrator_id,
+            rpc_providers,
+            withdrawal_finality_block_tag,
+            fee_estimation_block_tag,
         } = upgrade_args;
         if let Some(nonce) = next_transaction_nonce {
             let nonce = TransactionNonce::try_from(nonce)
@@ -276,6 +508,30 @@ impl State {
         if let Some(block_height) = ethereum_block_height {
             self.ethereum_block_height = block_height.into();
         }
+        if let Some(address) = erc20_helper_contract_address {
+            let erc20_helper_contract_address = Address::from_str(&address).map_err(|e| {
+                InvalidStateError::InvalidErc20HelperContractAddress(format!("ERROR: {}", e))
+            })?;
+            self.erc20_helper_contract_address = Some(erc20_helper_contract_address);
+        }
+        if let Some(block_number) = last_erc20_scraped_block_number {
+            self.last_erc20_scraped_block_number =
+                BlockNumber::try_from(block_number).map_err(|e| {
+                    InvalidStateError::InvalidLastScrapedBlockNumber(format!("ERROR: {}", e))
+                })?;
+        }
+        if let Some(orchestrator_id) = ledger_suite_orchestrator_id {
+            self.ledger_suite_orchestrator_id = Some(orchestrator_id);
+        }
+        if let Some(rpc_providers) = rpc_providers {
+            self.rpc_providers = rpc_providers;
+        }
+        if let Some(block_tag) = withdrawal_finality_block_tag {
+            self.withdrawal_finality_block_tag = block_tag.into();
+        }
+        if let Some(block_tag) = fee_estimation_block_tag {
+            self.fee_estimation_block_tag = block_tag.into();
+        }
         self.validate_config()
     }
 
@@ -291,6 +547,7 @@ impl State {
         use ic_utils_ensure::ensure_eq;
 
         ensure_eq!(self.ethereum_network, other.ethereum_network);
+        ensure_eq!(self.rpc_providers, other.rpc_providers);
         ensure_eq!(self.ledger_id, other.ledger_id);
         ensure_eq!(self.ecdsa_key_name, other.ecdsa_key_name);
         ensure_eq!(
@@ -310,9 +567,33 @@ impl State {
             other.last_scraped_block_number
         );
         ensure_eq!(self.ethereum_block_height, other.ethereum_block_height);
+        ensure_eq!(
+            self.withdrawal_finality_block_tag,
+            other.withdrawal_finality_block_tag
+        );
+        ensure_eq!(
+            self.fee_estimation_block_tag,
+            other.fee_estimation_block_tag
+        );
         ensure_eq!(self.events_to_mint, other.events_to_mint);
         ensure_eq!(self.minted_events, other.minted_events);
         ensure_eq!(self.invalid_events, other.invalid_events);
+        ensure_eq!(
+            self.ledger_suite_orchestrator_id,
+            other.ledger_suite_orchestrator_id
+        );
+        ensure_eq!(
+            self.erc20_helper_contract_address,
+            other.erc20_helper_contract_address
+        );
+        ensure_eq!(self.ckerc20_tokens, other.ckerc20_tokens);
+        ensure_eq!(
+            self.last_erc20_scraped_block_number,
+            other.last_erc20_scraped_block_number
+        );
+        ensure_eq!(self.erc20_events_to_mint, other.erc20_events_to_mint);
+        ensure_eq!(self.minted_erc20_events, other.minted_erc20_events);
+        ensure_eq!(self.erc20_balances, other.erc20_balances);
 
         self.eth_transactions
             .is_equivalent_to(&other.eth_transactions)
@@ -1,6 +1,6 @@
 use crate::checked_amount::CheckedAmountOf;
 use crate::endpoints::CandidBlockTag;
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::{BlockTag, Hash};
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
 use crate::lifecycle::init::InitArg;
@@ -57,6 +57,9 @@ fn a_state() -> State {
         minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
         next_transaction_nonce: Default::default(),
         last_scraped_block_number: Default::default(),
+        rpc_providers: None,
+        withdrawal_finality_block_tag: None,
+        fee_estimation_block_tag: None,
     })
     .expect("init args should be valid")
 }
@@ -184,6 +187,9 @@ mod mint_transaction {
             minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
             next_transaction_nonce: Default::default(),
             last_scraped_block_number: Default::default(),
+            rpc_providers: None,
+            withdrawal_finality_block_tag: None,
+            fee_estimation_block_tag: None,
         })
         .expect("init args should be valid")
     }
@@ -209,7 +215,7 @@ fn received_eth_event() -> ReceivedEthEvent {
 mod upgrade {
     use crate::eth_rpc::BlockTag;
     use crate::lifecycle::upgrade::UpgradeArg;
-    use crate::numeric::{wei_from_milli_ether, TransactionNonce, Wei};
+    use crate::numeric::{wei_from_milli_ether, BlockNumber, TransactionNonce, Wei};
     use crate::state::{InvalidStateError, State};
     use assert_matches::assert_matches;
     use candid::Nat;
@@ -258,6 +264,15 @@ mod upgrade {
             }),
             Err(InvalidStateError::InvalidEthereumContractAddress(_))
         );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                rpc_providers: Some(vec!["http://ethereum-rpc.example.com".to_string()]),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidRpcProviders(_))
+        );
     }
 
     #[test]
@@ -271,7 +286,17 @@ mod upgrade {
                 "0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34".to_string(),
             ),
             ethereum_block_height: Some(CandidBlockTag::Safe),
+            erc20_helper_contract_address: Some(
+                "0xE1788E4834c896F1932188645cc36c54d1b80AC1".to_string(),
+            ),
+            last_erc20_scraped_block_number: Some(Nat::from(5_000_000_u32)),
+            ledger_suite_orchestrator_id: Some("vxkom-oyaaa-aaaar-qafda-cai".parse().unwrap()),
+            rpc_providers: Some(vec!["https://ethereum-rpc.example.com".to_string()]),
+            withdrawal_finality_block_tag: Some(CandidBlockTag::Safe),
+            fee_estimation_block_tag: Some(CandidBlockTag::Finalized),
         };
+        assert_eq!(state.withdrawal_finality_block_tag, BlockTag::Finalized);
+        assert_eq!(state.fee_estimation_block_tag, BlockTag::Latest);
 
         state.upgrade(upgrade_arg).expect("valid upgrade args");
 
@@ -285,6 +310,24 @@ mod upgrade {
             Some(Address::from_str("0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34").unwrap())
         );
         assert_eq!(state.ethereum_block_height, BlockTag::Safe);
+        assert_eq!(
+            state.erc20_helper_contract_address,
+            Some(Address::from_str("0xE1788E4834c896F1932188645cc36c54d1b80AC1").unwrap())
+        );
+        assert_eq!(
+            state.last_erc20_scraped_block_number,
+            BlockNumber::new(5_000_000)
+        );
+        assert_eq!(
+            state.ledger_suite_orchestrator_id,
+            Some("vxkom-oyaaa-aaaar-qafda-cai".parse().unwrap())
+        );
+        assert_eq!(
+            state.rpc_providers,
+            vec!["https://ethereum-rpc.example.com".to_string()]
+        );
+        assert_eq!(state.withdrawal_finality_block_tag, BlockTag::Safe);
+        assert_eq!(state.fee_estimation_block_tag, BlockTag::Finalized);
     }
 
     fn initial_state() -> State {
@@ -300,6 +343,9 @@ mod upgrade {
             minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
             next_transaction_nonce: Default::default(),
             last_scraped_block_number: Default::default(),
+            rpc_providers: None,
+            withdrawal_finality_block_tag: None,
+            fee_estimation_block_tag: None,
         })
         .expect("valid init args")
     }
@@ -368,6 +414,8 @@ prop_compose! {
         ledger_id in arb_principal(),
         ecdsa_key_name in "[a-z_]*",
         last_scraped_block_number in arb_nat(),
+        withdrawal_finality_block_tag in proptest::option::of(arb_block_tag()),
+        fee_estimation_block_tag in proptest::option::of(arb_block_tag()),
     ) -> InitArg {
         InitArg {
             ethereum_network: EthereumNetwork::Sepolia,
@@ -377,7 +425,10 @@ prop_compose! {
             ethereum_block_height,
             minimum_withdrawal_amount,
             next_transaction_nonce,
-            last_scraped_block_number
+            last_scraped_block_number,
+            rpc_providers: None,
+            withdrawal_finality_block_tag,
+            fee_estimation_block_tag,
         }
     }
 }
@@ -388,12 +439,46 @@ prop_compose! {
         ethereum_block_height in proptest::option::of(arb_block_tag()),
         minimum_withdrawal_amount in proptest::option::of(arb_nat()),
         next_transaction_nonce in proptest::option::of(arb_nat()),
+        erc20_helper_contract_address in proptest::option::of(arb_address()),
+        last_erc20_scraped_block_number in proptest::option::of(arb_nat()),
+        ledger_suite_orchestrator_id in proptest::option::of(arb_principal()),
+        rpc_providers in proptest::option::of(pvec("https://[a-z]{1,10}\\.com", 0..3)),
+        withdrawal_finality_block_tag in proptest::option::of(arb_block_tag()),
+        fee_estimation_block_tag in proptest::option::of(arb_block_tag()),
     ) -> UpgradeArg {
         UpgradeArg {
             ethereum_contract_address: contract_address.map(|addr| addr.to_string()),
             ethereum_block_height,
             minimum_withdrawal_amount,
             next_transaction_nonce,
+            erc20_helper_contract_address: erc20_helper_contract_address.map(|addr| addr.to_string()),
+            last_erc20_scraped_block_number,
+            ledger_suite_orchestrator_id,
+            rpc_providers,
+            withdrawal_finality_block_tag,
+            fee_estimation_block_tag,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_received_erc20_event()(
+        transaction_hash in arb_hash(),
+        block_number in arb_checked_amount_of(),
+        log_index in arb_checked_amount_of(),
+        from_address in arb_address(),
+        value in arb_checked_amount_of(),
+        principal in arb_principal(),
+        erc20_contract_address in arb_address(),
+    ) -> ReceivedErc20Event {
+        ReceivedErc20Event {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
         }
     }
 }
@@ -485,6 +570,7 @@ prop_compose! {
             gas_used,
             status,
             transaction_hash,
+            l1_fee: None,
         }
     }
 }
@@ -529,6 +615,18 @@ fn arb_event_type() -> impl Strategy<Value = EventType> {
                 transaction_receipt,
             }
         }),
+        arb_received_erc20_event().prop_map(EventType::AcceptedErc20Deposit),
+        (arb_event_source(), any::<u64>(), arb_address()).prop_map(
+            |(event_source, index, erc20_contract_address)| EventType::MintedCkErc20 {
+                event_source,
+                mint_block_index: index.into(),
+                ckerc20_token_symbol: "ckUSDC".to_string(),
+                erc20_contract_address,
+            }
+        ),
+        arb_checked_amount_of()
+            .prop_map(|block_number| EventType::SyncedErc20ToBlock { block_number }),
+        arb_checked_amount_of().prop_map(EventType::SkippedErc20Block),
     ]
 }
 
@@ -673,6 +771,7 @@ fn state_equivalence() {
                     "0x06afc3c693dc2ba2c19b5c287c4dddce040d766bea5fd13c8a7268b04aa94f2d"
                         .parse()
                         .unwrap(),
+                l1_fee: None,
             })
             .expect("valid receipt"),
         ),
@@ -708,6 +807,10 @@ fn state_equivalence() {
                 withdrawal_id: LedgerBurnIndex::new(6),
             },
         },
+        erc20_withdrawal_requests: Default::default(),
+        erc20_maybe_reimburse: Default::default(),
+        erc20_reimbursement_requests: Default::default(),
+        erc20_reimbursed: Default::default(),
     };
     let state = State {
         ethereum_network: EthereumNetwork::Mainnet,
@@ -760,6 +863,18 @@ fn state_equivalence() {
         eth_balance: Default::default(),
         skipped_blocks: Default::default(),
         last_transaction_price_estimate: None,
+        last_l1_data_fee_estimate: None,
+        ledger_suite_orchestrator_id: None,
+        erc20_helper_contract_address: None,
+        ckerc20_tokens: Default::default(),
+        last_erc20_scraped_block_number: BlockNumber::new(1_000_000),
+        erc20_skipped_blocks: Default::default(),
+        erc20_events_to_mint: Default::default(),
+        minted_erc20_events: Default::default(),
+        erc20_balances: Default::default(),
+        rpc_providers: Default::default(),
+        withdrawal_finality_block_tag: BlockTag::Finalized,
+        fee_estimation_block_tag: BlockTag::Latest,
     };
 
     assert_eq!(
@@ -827,6 +942,24 @@ fn state_equivalence() {
         "changing essential fields should break equivalence",
     );
 
+    assert_ne!(
+        Ok(()),
+        state.is_equivalent_to(&State {
+            withdrawal_finality_block_tag: BlockTag::Safe,
+            ..state.clone()
+        }),
+        "changing essential fields should break equivalence",
+    );
+
+    assert_ne!(
+        Ok(()),
+        state.is_equivalent_to(&State {
+            fee_estimation_block_tag: BlockTag::Safe,
+            ..state.clone()
+        }),
+        "changing essential fields should break equivalence",
+    );
+
     assert_ne!(
         Ok(()),
         state.is_equivalent_to(&State {
@@ -1090,6 +1223,56 @@ mod eth_balance {
         );
     }
 
+    #[test]
+    fn should_debit_l1_fee_after_withdrawal_on_layer_2() {
+        let mut state_before_withdrawal = a_state();
+        apply_state_transition(
+            &mut state_before_withdrawal,
+            &EventType::AcceptedDeposit(received_eth_event()),
+        );
+        let withdrawal_flow = WithdrawalFlow {
+            withdrawal_amount: Wei::new(10_000_000_000_000_000),
+            tx_fee: TransactionPrice {
+                gas_limit: GasAmount::from(21_000_u32),
+                max_fee_per_gas: WeiPerGas::from(7_828_365_474_u64),
+                max_priority_fee_per_gas: WeiPerGas::from(1_500_000_000_u64),
+            },
+            effective_gas_price: WeiPerGas::from(0x1176e9eb9_u64),
+            ..Default::default()
+        };
+        let l1_fee = Wei::from(1_234_567_890_u64);
+
+        let mut state_without_l1_fee = state_before_withdrawal.clone();
+        let receipt_without_l1_fee = withdrawal_flow.clone().apply(&mut state_without_l1_fee);
+        let mut state_with_l1_fee = state_before_withdrawal.clone();
+        let receipt_with_l1_fee = WithdrawalFlow {
+            l1_fee: Some(l1_fee),
+            ..withdrawal_flow
+        }
+        .apply(&mut state_with_l1_fee);
+
+        assert_eq!(
+            receipt_with_l1_fee.effective_transaction_fee(),
+            receipt_without_l1_fee
+                .effective_transaction_fee()
+                .checked_add(l1_fee)
+                .unwrap()
+        );
+        assert_eq!(
+            state_with_l1_fee.eth_balance,
+            EthBalance {
+                // the L1 fee was deducted from the transaction amount
+                eth_balance: state_without_l1_fee.eth_balance.eth_balance,
+                total_effective_tx_fees: state_without_l1_fee
+                    .eth_balance
+                    .total_effective_tx_fees
+                    .checked_add(l1_fee)
+                    .unwrap(),
+                total_unspent_tx_fees: state_without_l1_fee.eth_balance.total_unspent_tx_fees,
+            }
+        );
+    }
+
     #[derive(Clone)]
     struct WithdrawalFlow {
         ledger_burn_index: LedgerBurnIndex,
@@ -1098,6 +1281,7 @@ mod eth_balance {
         tx_fee: TransactionPrice,
         effective_gas_price: WeiPerGas,
         tx_status: TransactionStatus,
+        l1_fee: Option<Wei>,
     }
 
     impl Default for WithdrawalFlow {
@@ -1113,6 +1297,7 @@ mod eth_balance {
                 },
                 effective_gas_price: WeiPerGas::ONE,
                 tx_status: TransactionStatus::Success,
+                l1_fee: None,
             }
         }
     }
@@ -1136,7 +1321,11 @@ mod eth_balance {
                 &EventType::AcceptedEthWithdrawalRequest(withdrawal_request.clone()),
             );
 
-            let max_fee = self.tx_fee.max_transaction_fee();
+            let max_fee = self
+                .tx_fee
+                .max_transaction_fee()
+                .checked_add(self.l1_fee.unwrap_or(Wei::ZERO))
+                .unwrap();
             let transaction = Eip1559TransactionRequest {
                 chain_id: EthereumNetwork::Sepolia.chain_id(),
                 nonce: self.nonce,
@@ -1183,6 +1372,7 @@ mod eth_balance {
                 gas_used: signed_tx.transaction().gas_limit,
                 status: self.tx_status,
                 transaction_hash: signed_tx.hash(),
+                l1_fee: self.l1_fee,
             };
             apply_state_transition(
                 state,
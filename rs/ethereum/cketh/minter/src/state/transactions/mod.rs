@@ -2,12 +2,15 @@
 mod tests;
 
 use crate::endpoints::{EthTransaction, RetrieveEthStatus, TxFinalizedStatus};
+use crate::erc20::encode_transfer_data;
 use crate::eth_rpc::Hash;
 use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::eth_rpc_client::responses::TransactionStatus;
 use crate::lifecycle::EthereumNetwork;
 use crate::map::MultiKeyMap;
-use crate::numeric::{LedgerBurnIndex, LedgerMintIndex, TransactionCount, TransactionNonce, Wei};
+use crate::numeric::{
+    Erc20Value, LedgerBurnIndex, LedgerMintIndex, TransactionCount, TransactionNonce, Wei,
+};
 use crate::tx::{
     Eip1559TransactionRequest, FinalizedEip1559Transaction, SignedEip1559TransactionRequest,
     TransactionPrice,
@@ -42,6 +45,44 @@ pub struct EthWithdrawalRequest {
     pub created_at: Option<u64>,
 }
 
+/// ERC-20 withdrawal request issued by the user.
+///
+/// The transaction fees are paid with ckETH, which was burned beforehand,
+/// while the withdrawn amount corresponds to the burned ckERC20.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20WithdrawalRequest {
+    /// Amount of burned ckETH that can be used to pay for the Ethereum transaction fees.
+    #[n(0)]
+    pub max_transaction_fee: Wei,
+    /// The ERC-20 amount that the receiver will get.
+    #[n(1)]
+    pub withdrawal_amount: Erc20Value,
+    /// The recipient's address of the sent ERC-20 tokens.
+    #[n(2)]
+    pub destination: Address,
+    /// The transaction ID of the ckETH burn operation on the ckETH ledger.
+    #[cbor(n(3), with = "crate::cbor::id")]
+    pub cketh_ledger_burn_index: LedgerBurnIndex,
+    /// Address of the ERC-20 smart contract that is the target of the withdrawal.
+    #[n(4)]
+    pub erc20_contract_address: Address,
+    /// The ckERC20 ledger on which the minter burned the ckERC20 tokens.
+    #[cbor(n(5), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+    /// The transaction ID of the ckERC20 burn operation on the ckERC20 ledger.
+    #[cbor(n(6), with = "crate::cbor::id")]
+    pub ckerc20_ledger_burn_index: LedgerBurnIndex,
+    /// The owner of the account from which the minter burned ckETH and ckERC20.
+    #[cbor(n(7), with = "crate::cbor::principal")]
+    pub from: Principal,
+    /// The subaccount from which the minter burned ckETH and ckERC20.
+    #[n(8)]
+    pub from_subaccount: Option<Subaccount>,
+    /// The IC time at which the withdrawal request arrived.
+    #[n(9)]
+    pub created_at: u64,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
 pub struct ReimbursementRequest {
     #[cbor(n(0), with = "crate::cbor::id")]
@@ -71,6 +112,42 @@ pub struct Reimbursed {
     pub transaction_hash: Option<Hash>,
 }
 
+/// Reimbursement of the ckERC20 burned for an ERC-20 withdrawal whose transaction failed.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20ReimbursementRequest {
+    #[cbor(n(0), with = "crate::cbor::id")]
+    pub withdrawal_id: LedgerBurnIndex,
+    #[cbor(n(1), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+    #[cbor(n(2), with = "crate::cbor::id")]
+    pub burn_in_block: LedgerBurnIndex,
+    #[n(3)]
+    pub reimbursed_amount: Erc20Value,
+    #[cbor(n(4), with = "crate::cbor::principal")]
+    pub to: Principal,
+    #[n(5)]
+    pub to_subaccount: Option<Subaccount>,
+    /// Transaction hash of the failed ERC-20 transaction.
+    #[n(6)]
+    pub transaction_hash: Hash,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20Reimbursed {
+    #[cbor(n(0), with = "crate::cbor::id")]
+    pub withdrawal_id: LedgerBurnIndex,
+    #[cbor(n(1), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+    #[cbor(n(2), with = "crate::cbor::id")]
+    pub burn_in_block: LedgerBurnIndex,
+    #[cbor(n(3), with = "crate::cbor::id")]
+    pub reimbursed_in_block: LedgerMintIndex,
+    #[n(4)]
+    pub reimbursed_amount: Erc20Value,
+    #[n(5)]
+    pub transaction_hash: Option<Hash>,
+}
+
 #[derive(Clone, Eq, PartialEq, Encode, Decode)]
 #[cbor(transparent)]
 pub struct Subaccount(#[cbor(n(0), with = "minicbor::bytes")] pub [u8; 32]);
@@ -101,6 +178,25 @@ impl fmt::Debug for EthWithdrawalRequest {
     }
 }
 
+impl fmt::Debug for Erc20WithdrawalRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Erc20WithdrawalRequest")
+            .field("max_transaction_fee", &self.max_transaction_fee)
+            .field("withdrawal_amount", &self.withdrawal_amount)
+            .field("destination", &self.destination)
+            .field("cketh_ledger_burn_index", &self.cketh_ledger_burn_index)
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .field(
+                "ckerc20_ledger_id",
+                &DebugPrincipal(&self.ckerc20_ledger_id),
+            )
+            .field("ckerc20_ledger_burn_index", &self.ckerc20_ledger_burn_index)
+            .field("from", &DebugPrincipal(&self.from))
+            .field("from_subaccount", &self.from_subaccount)
+            .finish()
+    }
+}
+
 /// State machine holding Ethereum transactions issued by the minter.
 /// Overall the transaction lifecycle is as follows:
 /// 1. The user's withdrawal request is enqueued and processed in a FIFO order.
@@ -115,6 +211,10 @@ impl fmt::Debug for EthWithdrawalRequest {
 ///    The others sent transactions for that nonce were never mined and can be discarded.
 /// 6. If a given transaction fails the minter will reimburse the user who requested the
 ///    withdrawal with the corresponding amount minus fees.
+///
+/// ERC-20 withdrawal requests follow the same lifecycle and share the transaction nonces
+/// with ETH withdrawal requests. They are identified by the burn index of the ckETH
+/// that was burned to pay for the transaction fees.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EthTransactions {
     pub(in crate::state) withdrawal_requests: VecDeque<EthWithdrawalRequest>,
@@ -129,6 +229,12 @@ pub struct EthTransactions {
     pub(in crate::state) maybe_reimburse: BTreeMap<LedgerBurnIndex, EthWithdrawalRequest>,
     pub(in crate::state) reimbursement_requests: BTreeMap<LedgerBurnIndex, ReimbursementRequest>,
     pub(in crate::state) reimbursed: BTreeMap<LedgerBurnIndex, Reimbursed>,
+
+    pub(in crate::state) erc20_withdrawal_requests: VecDeque<Erc20WithdrawalRequest>,
+    pub(in crate::state) erc20_maybe_reimburse: BTreeMap<LedgerBurnIndex, Erc20WithdrawalRequest>,
+    pub(in crate::state) erc20_reimbursement_requests:
+        BTreeMap<LedgerBurnIndex, Erc20ReimbursementRequest>,
+    pub(in crate::state) erc20_reimbursed: BTreeMap<LedgerBurnIndex, Erc20Reimbursed>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -138,6 +244,11 @@ pub enum CreateTransactionError {
         withdrawal_amount: Wei,
         max_transaction_fee: Wei,
     },
+    InsufficientTransactionFee {
+        ledger_burn_index: LedgerBurnIndex,
+        allowed_max_transaction_fee: Wei,
+        actual_max_transaction_fee: Wei,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -148,6 +259,12 @@ pub enum ResubmitTransactionError {
         transaction_amount: Wei,
         max_transaction_fee: Wei,
     },
+    InsufficientTransactionFee {
+        ledger_burn_index: LedgerBurnIndex,
+        transaction_nonce: TransactionNonce,
+        allowed_max_transaction_fee: Wei,
+        max_transaction_fee: Wei,
+    },
 }
 
 impl EthTransactions {
@@ -161,6 +278,10 @@ impl EthTransactions {
             maybe_reimburse: Default::default(),
             reimbursement_requests: Default::default(),
             reimbursed: Default::default(),
+            erc20_withdrawal_requests: VecDeque::new(),
+            erc20_maybe_reimburse: Default::default(),
+            erc20_reimbursement_requests: Default::default(),
+            erc20_reimbursed: Default::default(),
         }
     }
 
@@ -180,21 +301,55 @@ impl EthTransactions {
         self.reimbursed.values().cloned().collect()
     }
 
+    pub fn get_erc20_reimbursement_requests(&self) -> Vec<Erc20ReimbursementRequest> {
+        self.erc20_reimbursement_requests
+            .values()
+            .cloned()
+            .collect()
+    }
+
     pub fn record_withdrawal_request(&mut self, request: EthWithdrawalRequest) {
         let burn_index = request.ledger_burn_index;
-        if self
-            .withdrawal_requests
-            .iter()
-            .any(|r| r.ledger_burn_index == burn_index)
-            || self.created_tx.contains_alt(&burn_index)
-            || self.sent_tx.contains_alt(&burn_index)
-            || self.finalized_tx.contains_alt(&burn_index)
-        {
+        if self.is_known_withdrawal_id(&burn_index) {
             panic!("BUG: duplicate ledger burn index {burn_index}");
         }
         self.withdrawal_requests.push_back(request);
     }
 
+    pub fn record_erc20_withdrawal_request(&mut self, request: Erc20WithdrawalRequest) {
+        let burn_index = request.cketh_ledger_burn_index;
+        if self.is_known_withdrawal_id(&burn_index) {
+            panic!("BUG: duplicate ckETH ledger burn index {burn_index}");
+        }
+        self.erc20_withdrawal_requests.push_back(request);
+    }
+
+    /// Records that the ckERC20 burn of an ERC-20 withdrawal request failed after the ckETH
+    /// to pay for the transaction fees was burned, so that the burned ckETH is reimbursed.
+    pub fn record_failed_erc20_withdrawal_request(&mut self, request: ReimbursementRequest) {
+        let burn_index = request.withdrawal_id;
+        if self.is_known_withdrawal_id(&burn_index)
+            || self.reimbursement_requests.contains_key(&burn_index)
+            || self.reimbursed.contains_key(&burn_index)
+        {
+            panic!("BUG: duplicate ckETH ledger burn index {burn_index}");
+        }
+        self.reimbursement_requests.insert(burn_index, request);
+    }
+
+    fn is_known_withdrawal_id(&self, burn_index: &LedgerBurnIndex) -> bool {
+        self.withdrawal_requests
+            .iter()
+            .any(|r| &r.ledger_burn_index == burn_index)
+            || self
+                .erc20_withdrawal_requests
+                .iter()
+                .any(|r| &r.cketh_ledger_burn_index == burn_index)
+            || self.created_tx.contains_alt(burn_index)
+            || self.sent_tx.contains_alt(burn_index)
+            || self.finalized_tx.contains_alt(burn_index)
+    }
+
     /// Move an existing withdrawal request to the back of the queue.
     pub fn reschedule_withdrawal_request(&mut self, request: EthWithdrawalRequest) {
         assert_eq!(
@@ -210,11 +365,34 @@ impl EthTransactions {
         self.record_withdrawal_request(request);
     }
 
+    /// Move an existing ERC-20 withdrawal request to the back of the queue.
+    pub fn reschedule_erc20_withdrawal_request(&mut self, request: Erc20WithdrawalRequest) {
+        assert_eq!(
+            self.erc20_withdrawal_requests
+                .iter()
+                .filter(|r| r.cketh_ledger_burn_index == request.cketh_ledger_burn_index)
+                .count(),
+            1,
+            "BUG: expected exactly one ERC-20 withdrawal request with ckETH ledger burn index {}",
+            request.cketh_ledger_burn_index
+        );
+        self.erc20_withdrawal_requests.retain(|r| r != &request);
+        self.record_erc20_withdrawal_request(request);
+    }
+
     pub fn record_created_transaction(
         &mut self,
         withdrawal_id: LedgerBurnIndex,
         transaction: Eip1559TransactionRequest,
     ) {
+        if let Some(erc20_request) = self
+            .erc20_withdrawal_requests
+            .iter()
+            .find(|req| req.cketh_ledger_burn_index == withdrawal_id)
+            .cloned()
+        {
+            return self.record_created_erc20_transaction(erc20_request, transaction);
+        }
         let withdrawal_request = self
             .withdrawal_requests
             .iter()
@@ -249,6 +427,51 @@ impl EthTransactions {
             .insert(withdrawal_id, withdrawal_request);
     }
 
+    fn record_created_erc20_transaction(
+        &mut self,
+        withdrawal_request: Erc20WithdrawalRequest,
+        transaction: Eip1559TransactionRequest,
+    ) {
+        let withdrawal_id = withdrawal_request.cketh_ledger_burn_index;
+        assert_eq!(
+            withdrawal_request.erc20_contract_address, transaction.destination,
+            "BUG: ERC-20 withdrawal request contract address and transaction destination mismatch"
+        );
+        assert_eq!(
+            transaction.amount,
+            Wei::ZERO,
+            "BUG: ERC-20 transfer transaction should not transfer any ETH"
+        );
+        assert_eq!(
+            transaction.data,
+            encode_transfer_data(
+                &withdrawal_request.destination,
+                withdrawal_request.withdrawal_amount
+            ),
+            "BUG: ERC-20 withdrawal request and transaction data mismatch"
+        );
+        assert!(
+            transaction.transaction_price().max_transaction_fee()
+                <= withdrawal_request.max_transaction_fee,
+            "BUG: transaction fee should be covered by the burned ckETH"
+        );
+        let nonce = self.next_nonce;
+        assert_eq!(transaction.nonce, nonce, "BUG: transaction nonce mismatch");
+        self.next_nonce = self
+            .next_nonce
+            .checked_increment()
+            .expect("Transaction nonce overflow");
+        self.erc20_withdrawal_requests
+            .retain(|r| r != &withdrawal_request);
+        assert_eq!(
+            self.created_tx
+                .try_insert(nonce, withdrawal_id, transaction),
+            Ok(())
+        );
+        self.erc20_maybe_reimburse
+            .insert(withdrawal_id, withdrawal_request);
+    }
+
     pub fn record_signed_transaction(
         &mut self,
         signed_transaction: SignedEip1559TransactionRequest,
@@ -305,27 +528,50 @@ impl EthTransactions {
             let last_tx_price = last_tx.transaction_price();
             let last_tx_max_fee = last_tx_price.max_transaction_fee();
             if last_tx_price.is_fee_increased(&current_transaction_price) {
-                let new_tx_price = last_tx_price
-                    .increase_by_10_percent()
-                    .max(current_transaction_price.clone());
-                let new_amount = match last_tx.amount.checked_sub(
-                    new_tx_price
-                        .max_transaction_fee()
-                        .checked_sub(last_tx_max_fee)
-                        .expect("BUG: new price was increased by at least 10%"),
-                ) {
-                    Some(amount) => amount,
-                    None => {
-                        transactions_to_resubmit.push(Err(
-                            ResubmitTransactionError::InsufficientTransactionAmount {
-                                ledger_burn_index: *burn_index,
-                                transaction_nonce: *nonce,
-                                transaction_amount: last_tx.amount,
-                                max_transaction_fee: new_tx_price.max_transaction_fee(),
-                            },
-                        ));
-                        return transactions_to_resubmit;
+                // The gas limit is left unchanged since it is part of the original transaction.
+                let new_tx_price = TransactionPrice {
+                    gas_limit: last_tx_price.gas_limit,
+                    ..last_tx_price
+                        .clone()
+                        .increase_by_10_percent()
+                        .max(current_transaction_price.clone())
+                };
+                let new_amount = match self.erc20_maybe_reimburse.get(burn_index) {
+                    Some(erc20_request) => {
+                        // The fees of ERC-20 transactions are paid with the burned ckETH,
+                        // the transaction amount (zero) is therefore left unchanged.
+                        if new_tx_price.max_transaction_fee() > erc20_request.max_transaction_fee {
+                            transactions_to_resubmit.push(Err(
+                                ResubmitTransactionError::InsufficientTransactionFee {
+                                    ledger_burn_index: *burn_index,
+                                    transaction_nonce: *nonce,
+                                    allowed_max_transaction_fee: erc20_request.max_transaction_fee,
+                                    max_transaction_fee: new_tx_price.max_transaction_fee(),
+                                },
+                            ));
+                            return transactions_to_resubmit;
+                        }
+                        last_tx.amount
                     }
+                    None => match last_tx.amount.checked_sub(
+                        new_tx_price
+                            .max_transaction_fee()
+                            .checked_sub(last_tx_max_fee)
+                            .expect("BUG: new price was increased by at least 10%"),
+                    ) {
+                        Some(amount) => amount,
+                        None => {
+                            transactions_to_resubmit.push(Err(
+                                ResubmitTransactionError::InsufficientTransactionAmount {
+                                    ledger_burn_index: *burn_index,
+                                    transaction_nonce: *nonce,
+                                    transaction_amount: last_tx.amount,
+                                    max_transaction_fee: new_tx_price.max_transaction_fee(),
+                                },
+                            ));
+                            return transactions_to_resubmit;
+                        }
+                    },
                 };
                 let new_tx = Eip1559TransactionRequest {
                     max_priority_fee_per_gas: new_tx_price.max_priority_fee_per_gas,
@@ -408,6 +654,23 @@ impl EthTransactions {
             Ok(())
         );
 
+        if let Some(erc20_request) = self.erc20_maybe_reimburse.remove(&ledger_burn_index) {
+            if receipt.status == TransactionStatus::Failure {
+                self.erc20_reimbursement_requests.insert(
+                    ledger_burn_index,
+                    Erc20ReimbursementRequest {
+                        withdrawal_id: ledger_burn_index,
+                        ckerc20_ledger_id: erc20_request.ckerc20_ledger_id,
+                        burn_in_block: erc20_request.ckerc20_ledger_burn_index,
+                        reimbursed_amount: erc20_request.withdrawal_amount,
+                        to: erc20_request.from,
+                        to_subaccount: erc20_request.from_subaccount,
+                        transaction_hash: receipt.transaction_hash,
+                    },
+                );
+            }
+            return;
+        }
         let maybe_reimburse = self.maybe_reimburse.remove(&ledger_burn_index).expect(
             "failed to remove entry from maybe_reimburse map with block index: {ledger_burn_index}",
         );
@@ -448,11 +711,52 @@ impl EthTransactions {
         );
     }
 
+    pub fn record_finalized_erc20_reimbursement(
+        &mut self,
+        withdrawal_id: LedgerBurnIndex,
+        reimbursed_in_block: LedgerMintIndex,
+    ) {
+        let reimbursement_request = self
+            .erc20_reimbursement_requests
+            .remove(&withdrawal_id)
+            .expect("failed to remove ERC-20 reimbursement request");
+        assert_eq!(
+            self.erc20_reimbursed.insert(
+                withdrawal_id,
+                Erc20Reimbursed {
+                    withdrawal_id,
+                    ckerc20_ledger_id: reimbursement_request.ckerc20_ledger_id,
+                    burn_in_block: reimbursement_request.burn_in_block,
+                    reimbursed_in_block,
+                    reimbursed_amount: reimbursement_request.reimbursed_amount,
+                    transaction_hash: Some(reimbursement_request.transaction_hash),
+                },
+            ),
+            None
+        );
+    }
+
+    /// Returns the ERC-20 withdrawal request identified by the given ckETH burn index,
+    /// as long as its transaction is not finalized.
+    pub fn get_erc20_withdrawal_request(
+        &self,
+        burn_index: &LedgerBurnIndex,
+    ) -> Option<&Erc20WithdrawalRequest> {
+        self.erc20_withdrawal_requests
+            .iter()
+            .find(|r| &r.cketh_ledger_burn_index == burn_index)
+            .or_else(|| self.erc20_maybe_reimburse.get(burn_index))
+    }
+
     pub fn transaction_status(&self, burn_index: &LedgerBurnIndex) -> RetrieveEthStatus {
         if self
             .withdrawal_requests
             .iter()
             .any(|r| &r.ledger_burn_index == burn_index)
+            || self
+                .erc20_withdrawal_requests
+                .iter()
+                .any(|r| &r.cketh_ledger_burn_index == burn_index)
         {
             return RetrieveEthStatus::Pending;
         }
@@ -466,6 +770,13 @@ impl EthTransactions {
         }
 
         if let Some(tx) = self.finalized_tx.get_alt(burn_index) {
+            if let Some(reimbursed) = self.erc20_reimbursed.get(burn_index) {
+                return RetrieveEthStatus::TxFinalized(TxFinalizedStatus::Reimbursed {
+                    reimbursed_in_block: reimbursed.reimbursed_in_block.get().into(),
+                    transaction_hash: tx.transaction_hash().to_string(),
+                    reimbursed_amount: reimbursed.reimbursed_amount.into(),
+                });
+            }
             if let Some(reimbursed) = self.reimbursed.get(burn_index) {
                 return RetrieveEthStatus::TxFinalized(TxFinalizedStatus::Reimbursed {
                     reimbursed_in_block: reimbursed.reimbursed_in_block.get().into(),
@@ -493,6 +804,24 @@ impl EthTransactions {
         &self,
         requested_batch_size: usize,
     ) -> Vec<EthWithdrawalRequest> {
+        self.requests_batch(self.withdrawal_requests_iter(), requested_batch_size)
+    }
+
+    /// Same as [`Self::withdrawal_requests_batch`] but for ERC-20 withdrawal requests.
+    /// The limit on the number of pending transaction nonces is shared between
+    /// ETH and ERC-20 withdrawal requests.
+    pub fn erc20_withdrawal_requests_batch(
+        &self,
+        requested_batch_size: usize,
+    ) -> Vec<Erc20WithdrawalRequest> {
+        self.requests_batch(self.erc20_withdrawal_requests_iter(), requested_batch_size)
+    }
+
+    fn requests_batch<'a, R: Clone + 'a>(
+        &self,
+        requests: impl Iterator<Item = &'a R>,
+        requested_batch_size: usize,
+    ) -> Vec<R> {
         // The number of pending transaction nonces is counted and not the number of pending transactions
         // because a nonce may be associated with several distinct transactions (due to re-submission and dynamic fees).
         // However, once a nonce is chosen for a withdrawal request, it's in our interest that the corresponding transaction be finalized asap.
@@ -505,10 +834,11 @@ impl EthTransactions {
                 .saturating_sub(unique_pending_transaction_nonces.len()),
             requested_batch_size,
         );
-        self.withdrawal_requests_iter()
-            .take(actual_batch_size)
-            .cloned()
-            .collect()
+        requests.take(actual_batch_size).cloned().collect()
+    }
+
+    pub fn erc20_withdrawal_requests_iter(&self) -> impl Iterator<Item = &Erc20WithdrawalRequest> {
+        self.erc20_withdrawal_requests.iter()
     }
 
     pub fn withdrawal_requests_iter(&self) -> impl Iterator<Item = &EthWithdrawalRequest> {
@@ -516,7 +846,7 @@ impl EthTransactions {
     }
 
     pub fn withdrawal_requests_len(&self) -> usize {
-        self.withdrawal_requests.len()
+        self.withdrawal_requests.len() + self.erc20_withdrawal_requests.len()
     }
 
     pub fn transactions_to_sign_iter(
@@ -590,6 +920,7 @@ impl EthTransactions {
 
     pub fn has_pending_requests(&self) -> bool {
         !self.withdrawal_requests.is_empty()
+            || !self.erc20_withdrawal_requests.is_empty()
             || !self.created_tx.is_empty()
             || !self.sent_tx.is_empty()
     }
@@ -650,6 +981,17 @@ impl EthTransactions {
         ensure_eq!(self.reimbursement_requests, other.reimbursement_requests);
         ensure_eq!(self.reimbursed, other.reimbursed);
 
+        ensure_eq!(
+            self.erc20_withdrawal_requests,
+            other.erc20_withdrawal_requests
+        );
+        ensure_eq!(self.erc20_maybe_reimburse, other.erc20_maybe_reimburse);
+        ensure_eq!(
+            self.erc20_reimbursement_requests,
+            other.erc20_reimbursement_requests
+        );
+        ensure_eq!(self.erc20_reimbursed, other.erc20_reimbursed);
+
         Ok(())
     }
 
@@ -658,12 +1000,18 @@ impl EthTransactions {
             .iter()
             .chain(self.maybe_reimburse.values())
             .flat_map(|req| req.created_at.into_iter())
+            .chain(
+                self.erc20_withdrawal_requests
+                    .iter()
+                    .chain(self.erc20_maybe_reimburse.values())
+                    .map(|req| req.created_at),
+            )
             .min()
     }
 }
 
 /// Creates an EIP-1559 transaction for the given withdrawal request.
-/// The transaction fees are paid by the beneficiary,
+/// The transaction fees, including the L1 data fee on layer 2 networks, are paid by the beneficiary,
 /// meaning that the fees will be deducted from the withdrawal amount.
 ///
 /// # Errors
@@ -672,9 +1020,13 @@ pub fn create_transaction(
     withdrawal_request: &EthWithdrawalRequest,
     nonce: TransactionNonce,
     transaction_price: TransactionPrice,
+    l1_data_fee: Wei,
     ethereum_network: EthereumNetwork,
 ) -> Result<Eip1559TransactionRequest, CreateTransactionError> {
-    let max_transaction_fee = transaction_price.max_transaction_fee();
+    let max_transaction_fee = transaction_price
+        .max_transaction_fee()
+        .checked_add(l1_data_fee)
+        .expect("BUG: transaction fee always fits into U256");
     let tx_amount = match withdrawal_request
         .withdrawal_amount
         .checked_sub(max_transaction_fee)
@@ -701,6 +1053,47 @@ pub fn create_transaction(
     })
 }
 
+/// Creates an EIP-1559 transaction calling the ERC-20 `transfer` function
+/// for the given ERC-20 withdrawal request.
+/// The transaction fees, including the L1 data fee on layer 2 networks, were paid upfront
+/// by the user by burning ckETH, so that the transaction does not transfer any ETH.
+///
+/// # Errors
+/// * `CreateTransactionError::InsufficientTransactionFee` if the burned ckETH does not cover the transaction fee.
+pub fn create_erc20_transaction(
+    withdrawal_request: &Erc20WithdrawalRequest,
+    nonce: TransactionNonce,
+    transaction_price: TransactionPrice,
+    l1_data_fee: Wei,
+    ethereum_network: EthereumNetwork,
+) -> Result<Eip1559TransactionRequest, CreateTransactionError> {
+    let max_transaction_fee = transaction_price
+        .max_transaction_fee()
+        .checked_add(l1_data_fee)
+        .expect("BUG: transaction fee always fits into U256");
+    if max_transaction_fee > withdrawal_request.max_transaction_fee {
+        return Err(CreateTransactionError::InsufficientTransactionFee {
+            ledger_burn_index: withdrawal_request.cketh_ledger_burn_index,
+            allowed_max_transaction_fee: withdrawal_request.max_transaction_fee,
+            actual_max_transaction_fee: max_transaction_fee,
+        });
+    }
+    Ok(Eip1559TransactionRequest {
+        chain_id: ethereum_network.chain_id(),
+        nonce,
+        max_priority_fee_per_gas: transaction_price.max_priority_fee_per_gas,
+        max_fee_per_gas: transaction_price.max_fee_per_gas,
+        gas_limit: transaction_price.gas_limit,
+        destination: withdrawal_request.erc20_contract_address,
+        amount: Wei::ZERO,
+        data: encode_transfer_data(
+            &withdrawal_request.destination,
+            withdrawal_request.withdrawal_amount,
+        ),
+        access_list: Default::default(),
+    })
+}
+
 /// Returns true if the two transactions are equal ignoring the transaction fee and amount.
 /// The following fields are ignored:
 /// * `max_fee_per_gas`
@@ -281,7 +281,7 @@ mod eth_transactions {
 
     mod record_created_transaction {
         use crate::lifecycle::EthereumNetwork;
-        use crate::numeric::{LedgerBurnIndex, TransactionNonce};
+        use crate::numeric::{LedgerBurnIndex, TransactionNonce, Wei};
         use crate::state::transactions::tests::{
             create_and_record_transaction, expect_panic_with_message, transaction_price,
             withdrawal_request_with_index,
@@ -300,6 +300,7 @@ mod eth_transactions {
                 &withdrawal_request,
                 TransactionNonce::ZERO,
                 transaction_price(),
+                Wei::ZERO,
                 EthereumNetwork::Sepolia,
             )
             .unwrap();
@@ -322,6 +323,7 @@ mod eth_transactions {
                 &withdrawal_request,
                 TransactionNonce::ZERO,
                 transaction_price(),
+                Wei::ZERO,
                 EthereumNetwork::Sepolia,
             )
             .unwrap();
@@ -373,6 +375,7 @@ mod eth_transactions {
                     &withdrawal_request,
                     wrong_nonce,
                     transaction_price(),
+                    Wei::ZERO,
                     EthereumNetwork::Sepolia,
                 )
                 .unwrap();
@@ -736,6 +739,36 @@ mod eth_transactions {
             }
         }
 
+        #[test]
+        fn should_keep_gas_limit_of_resubmitted_transaction() {
+            let mut transactions = EthTransactions::new(TransactionNonce::ZERO);
+            let withdrawal_request =
+                create_and_record_withdrawal_request(&mut transactions, LedgerBurnIndex::new(15));
+            let initial_tx = create_and_record_transaction(
+                &mut transactions,
+                withdrawal_request,
+                transaction_price(),
+            );
+            let _signed_tx =
+                create_and_record_signed_transaction(&mut transactions, initial_tx.clone());
+            let higher_price_and_gas_limit = TransactionPrice {
+                gas_limit: initial_tx.gas_limit.checked_mul(2_u8).unwrap(),
+                max_fee_per_gas: initial_tx.max_fee_per_gas.checked_mul(2_u8).unwrap(),
+                ..transaction_price()
+            };
+
+            let resubmitted_txs = transactions
+                .create_resubmit_transactions(TransactionCount::ZERO, higher_price_and_gas_limit);
+
+            let (_withdrawal_id, resubmitted_tx) = resubmitted_txs
+                .into_iter()
+                .next()
+                .unwrap()
+                .unwrap();
+            assert_eq!(resubmitted_tx.gas_limit, initial_tx.gas_limit);
+            transactions.record_resubmit_transaction(resubmitted_tx);
+        }
+
         struct ParameterizedTest {
             price_at_tx_creation: TransactionPrice,
             price_at_tx_resubmission: TransactionPrice,
@@ -1604,6 +1637,7 @@ mod create_transaction {
                 &withdrawal_request,
                 TransactionNonce::TWO,
                 transaction_price,
+                Wei::ZERO,
                 EthereumNetwork::Sepolia,
             );
 
@@ -1638,6 +1672,7 @@ mod create_transaction {
                 &withdrawal_request,
                 TransactionNonce::TWO,
                 transaction_price.clone(),
+                Wei::ZERO,
                 EthereumNetwork::Sepolia,
             );
 
@@ -1654,13 +1689,186 @@ mod create_transaction {
             }))
         }
     }
+
+    #[test]
+    fn should_deduct_l1_data_fee_from_withdrawal_amount() {
+        let transaction_price = transaction_price();
+        let l1_data_fee = Wei::from(1_000_000_000_000_u64);
+        let max_transaction_fee = transaction_price
+            .max_transaction_fee()
+            .checked_add(l1_data_fee)
+            .unwrap();
+        let ledger_burn_index = LedgerBurnIndex::new(15);
+        let withdrawal_request = withdrawal_request_with_index(ledger_burn_index);
+
+        let transaction = create_transaction(
+            &withdrawal_request,
+            TransactionNonce::TWO,
+            transaction_price.clone(),
+            l1_data_fee,
+            EthereumNetwork::Base,
+        )
+        .unwrap();
+
+        assert_eq!(transaction.chain_id, EthereumNetwork::Base.chain_id());
+        assert_eq!(transaction.transaction_price(), transaction_price);
+        assert_eq!(
+            transaction.amount,
+            withdrawal_request
+                .withdrawal_amount
+                .checked_sub(max_transaction_fee)
+                .unwrap()
+        );
+
+        let insufficient_request = EthWithdrawalRequest {
+            withdrawal_amount: max_transaction_fee.checked_sub(Wei::ONE).unwrap(),
+            ..withdrawal_request
+        };
+        assert_eq!(
+            create_transaction(
+                &insufficient_request,
+                TransactionNonce::TWO,
+                transaction_price,
+                l1_data_fee,
+                EthereumNetwork::Base,
+            ),
+            Err(CreateTransactionError::InsufficientAmount {
+                ledger_burn_index,
+                withdrawal_amount: insufficient_request.withdrawal_amount,
+                max_transaction_fee,
+            })
+        );
+    }
+}
+
+mod create_erc20_transaction {
+    use crate::erc20::encode_transfer_data;
+    use crate::lifecycle::EthereumNetwork;
+    use crate::numeric::{Erc20Value, LedgerBurnIndex, TransactionNonce, Wei};
+    use crate::state::transactions::tests::transaction_price;
+    use crate::state::transactions::{
+        create_erc20_transaction, CreateTransactionError, Erc20WithdrawalRequest,
+    };
+    use crate::tx::{AccessList, Eip1559TransactionRequest};
+    use std::str::FromStr;
+
+    #[test]
+    fn should_fail_when_burned_fee_does_not_cover_transaction_fee() {
+        let transaction_price = transaction_price();
+        let max_transaction_fee = transaction_price.max_transaction_fee();
+        let withdrawal_request = Erc20WithdrawalRequest {
+            max_transaction_fee: max_transaction_fee.checked_sub(Wei::ONE).unwrap(),
+            ..erc20_withdrawal_request()
+        };
+
+        let result = create_erc20_transaction(
+            &withdrawal_request,
+            TransactionNonce::TWO,
+            transaction_price,
+            Wei::ZERO,
+            EthereumNetwork::Sepolia,
+        );
+
+        assert_eq!(
+            result,
+            Err(CreateTransactionError::InsufficientTransactionFee {
+                ledger_burn_index: withdrawal_request.cketh_ledger_burn_index,
+                allowed_max_transaction_fee: withdrawal_request.max_transaction_fee,
+                actual_max_transaction_fee: max_transaction_fee,
+            })
+        );
+    }
+
+    #[test]
+    fn should_create_transaction_calling_erc20_transfer() {
+        let transaction_price = transaction_price();
+        let withdrawal_request = erc20_withdrawal_request();
+
+        let result = create_erc20_transaction(
+            &withdrawal_request,
+            TransactionNonce::TWO,
+            transaction_price.clone(),
+            Wei::ZERO,
+            EthereumNetwork::Sepolia,
+        );
+
+        assert_eq!(
+            result,
+            Ok(Eip1559TransactionRequest {
+                chain_id: EthereumNetwork::Sepolia.chain_id(),
+                nonce: TransactionNonce::TWO,
+                max_priority_fee_per_gas: transaction_price.max_priority_fee_per_gas,
+                max_fee_per_gas: transaction_price.max_fee_per_gas,
+                gas_limit: transaction_price.gas_limit,
+                destination: withdrawal_request.erc20_contract_address,
+                amount: Wei::ZERO,
+                data: encode_transfer_data(
+                    &withdrawal_request.destination,
+                    withdrawal_request.withdrawal_amount
+                ),
+                access_list: AccessList::new()
+            })
+        );
+    }
+
+    #[test]
+    fn should_fail_when_burned_fee_does_not_cover_l1_data_fee() {
+        let transaction_price = transaction_price();
+        let withdrawal_request = Erc20WithdrawalRequest {
+            max_transaction_fee: transaction_price.max_transaction_fee(),
+            ..erc20_withdrawal_request()
+        };
+        let l1_data_fee = Wei::ONE;
+
+        let result = create_erc20_transaction(
+            &withdrawal_request,
+            TransactionNonce::TWO,
+            transaction_price.clone(),
+            l1_data_fee,
+            EthereumNetwork::Base,
+        );
+
+        assert_eq!(
+            result,
+            Err(CreateTransactionError::InsufficientTransactionFee {
+                ledger_burn_index: withdrawal_request.cketh_ledger_burn_index,
+                allowed_max_transaction_fee: withdrawal_request.max_transaction_fee,
+                actual_max_transaction_fee: transaction_price
+                    .max_transaction_fee()
+                    .checked_add(l1_data_fee)
+                    .unwrap(),
+            })
+        );
+    }
+
+    fn erc20_withdrawal_request() -> Erc20WithdrawalRequest {
+        Erc20WithdrawalRequest {
+            max_transaction_fee: Wei::new(40_000_000_000_000),
+            withdrawal_amount: Erc20Value::new(1_000_000),
+            destination: "0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34"
+                .parse()
+                .unwrap(),
+            cketh_ledger_burn_index: LedgerBurnIndex::new(7),
+            erc20_contract_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
+                .parse()
+                .unwrap(),
+            ckerc20_ledger_id: candid::Principal::from_str("sa4so-piaaa-aaaar-qacnq-cai").unwrap(),
+            ckerc20_ledger_burn_index: LedgerBurnIndex::new(2),
+            from: candid::Principal::from_str(
+                "k2t6j-2nvnp-4zjm3-25dtz-6xhaa-c7boj-5gayf-oj3xs-i43lp-teztq-6ae",
+            )
+            .unwrap(),
+            from_subaccount: None,
+            created_at: 1_711_138_972_460_345_032,
+        }
+    }
 }
 
 mod withdrawal_flow {
     use super::arbitrary::{
         arb_checked_amount_of, arb_non_overflowing_transaction_price, arb_withdrawal_request,
     };
-    use crate::numeric::TransactionNonce;
+    use crate::numeric::{TransactionNonce, Wei};
     use crate::state::transactions::tests::sign_transaction;
     use crate::state::transactions::{create_transaction, EthTransactions, EthereumNetwork};
     use proptest::proptest;
@@ -1689,6 +1897,7 @@ mod withdrawal_flow {
                     &request,
                     nonce,
                     transaction_price.clone(),
+                    Wei::ZERO,
                     EthereumNetwork::Sepolia,
                 ){
                     wrapped_txs.borrow_mut().record_created_transaction(request.ledger_burn_index, created_tx);
@@ -1941,6 +2150,7 @@ fn create_and_record_transaction(
         &withdrawal_request,
         transactions.next_transaction_nonce(),
         transaction_price,
+        Wei::ZERO,
         EthereumNetwork::Sepolia,
     )
     .expect("failed to create transaction");
@@ -1993,6 +2203,7 @@ fn transaction_receipt(
         gas_used: signed_tx.transaction().gas_limit,
         status,
         transaction_hash: signed_tx.hash(),
+        l1_fee: None,
     }
 }
 
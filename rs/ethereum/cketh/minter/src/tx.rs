@@ -3,6 +3,7 @@ mod tests;
 
 use crate::eth_rpc::{FeeHistory, Hash};
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
+use crate::lifecycle::EthereumNetwork;
 use crate::numeric::{BlockNumber, GasAmount, TransactionNonce, Wei, WeiPerGas};
 use crate::state::{lazy_call_ecdsa_public_key, read_state};
 use ethnum::u256;
@@ -321,10 +322,16 @@ impl Eip1559TransactionRequest {
     /// keccak256(0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, destination, amount, data, access_list])),
     /// where `||` denotes string concatenation.
     pub fn hash(&self) -> Hash {
+        Hash(ic_crypto_sha3::Keccak256::hash(self.unsigned_bytes()))
+    }
+
+    /// Serialization of the unsigned transaction as a typed transaction envelope,
+    /// i.e., `0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, destination, amount, data, access_list])`.
+    pub fn unsigned_bytes(&self) -> Vec<u8> {
         use rlp::Encodable;
         let mut bytes = self.rlp_bytes().to_vec();
         bytes.insert(0, self.transaction_type());
-        Hash(ic_crypto_sha3::Keccak256::hash(bytes))
+        bytes
     }
 
     pub fn transaction_price(&self) -> TransactionPrice {
@@ -455,11 +462,9 @@ pub enum TransactionPriceEstimationError {
 }
 pub fn estimate_transaction_price(
     fee_history: &FeeHistory,
+    ethereum_network: EthereumNetwork,
 ) -> Result<TransactionPriceEstimate, TransactionPriceEstimationError> {
-    // average value between the `minSuggestedMaxPriorityFeePerGas`
-    // used by Metamask, see
-    // https://github.com/MetaMask/core/blob/f5a4f52e17f407c6411e4ef9bd6685aab184b91d/packages/gas-fee-controller/src/fetchGasEstimatesViaEthFeeHistory/calculateGasFeeEstimatesForPriorityLevels.ts#L14
-    const MIN_MAX_PRIORITY_FEE_PER_GAS: WeiPerGas = WeiPerGas::new(1_500_000_000); //1.5 gwei
+    let min_max_priority_fee_per_gas = min_max_priority_fee_per_gas(ethereum_network);
     let base_fee_of_next_finalized_block = *fee_history.base_fee_per_gas.last().ok_or(
         TransactionPriceEstimationError::InvalidFeeHistory(
             "base_fee_per_gas should not be empty to be able to evaluate transaction price"
@@ -472,7 +477,7 @@ pub fn estimate_transaction_price(
             **median(&mut rewards).ok_or(TransactionPriceEstimationError::InvalidFeeHistory(
                 "should be non-empty with rewards of the last 5 blocks".to_string(),
             ))?;
-        historic_max_priority_fee_per_gas.max(MIN_MAX_PRIORITY_FEE_PER_GAS)
+        historic_max_priority_fee_per_gas.max(min_max_priority_fee_per_gas)
     };
     let max_fee_per_gas = base_fee_of_next_finalized_block
         .checked_mul(2_u8)
@@ -486,6 +491,21 @@ pub fn estimate_transaction_price(
     })
 }
 
+/// Lower bound on the priority fee (tip) of a transaction.
+///
+/// On layer 2 networks, blocks are produced by a single sequencer
+/// and tips are typically orders of magnitude lower than on Ethereum.
+fn min_max_priority_fee_per_gas(ethereum_network: EthereumNetwork) -> WeiPerGas {
+    if ethereum_network.is_layer_2() {
+        WeiPerGas::new(1_000_000) //0.001 gwei
+    } else {
+        // average value between the `minSuggestedMaxPriorityFeePerGas`
+        // used by Metamask, see
+        // https://github.com/MetaMask/core/blob/f5a4f52e17f407c6411e4ef9bd6685aab184b91d/packages/gas-fee-controller/src/fetchGasEstimatesViaEthFeeHistory/calculateGasFeeEstimatesForPriorityLevels.ts#L14
+        WeiPerGas::new(1_500_000_000) //1.5 gwei
+    }
+}
+
 fn median<T: Ord>(values: &mut [T]) -> Option<&T> {
     if values.is_empty() {
         return None;
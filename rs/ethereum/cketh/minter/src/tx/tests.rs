@@ -3,6 +3,7 @@ use proptest::strategy::Strategy;
 
 mod estimate_transaction_price {
     use crate::eth_rpc::FeeHistory;
+    use crate::lifecycle::EthereumNetwork;
     use crate::numeric::{BlockNumber, WeiPerGas};
     use crate::tx::{
         estimate_transaction_price, TransactionPriceEstimate, TransactionPriceEstimationError,
@@ -30,7 +31,7 @@ mod estimate_transaction_price {
             let expected_max_fee_per_gas =
                 2_u128 * (expected_base_fee_per_gas as u128) + (expected_max_priority_fee_per_gas as u128);
 
-            let result = estimate_transaction_price(&fee_history);
+            let result = estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet);
 
             prop_assert_eq!(
                 result,
@@ -42,6 +43,36 @@ mod estimate_transaction_price {
         }
     }
 
+    #[test]
+    fn should_use_lower_minimum_priority_fee_on_layer_2() {
+        let fee_history = fee_history(
+            vec![100_000_u64, 100_000, 100_000, 100_000, 100_000, 100_000],
+            vec![0_u8, 0, 0, 0, 0],
+        );
+
+        let mainnet_estimate =
+            estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet).unwrap();
+        assert_eq!(
+            mainnet_estimate.max_priority_fee_per_gas,
+            WeiPerGas::new(1_500_000_000)
+        );
+
+        for network in [
+            EthereumNetwork::ArbitrumOne,
+            EthereumNetwork::Base,
+            EthereumNetwork::Optimism,
+        ] {
+            let estimate = estimate_transaction_price(&fee_history, network).unwrap();
+            assert_eq!(
+                estimate,
+                TransactionPriceEstimate {
+                    max_fee_per_gas: WeiPerGas::new(1_200_000),
+                    max_priority_fee_per_gas: WeiPerGas::new(1_000_000),
+                }
+            );
+        }
+    }
+
     #[test]
     fn should_fail_when_base_fee_per_gas_overflows() {
         let fee_history = fee_history(
@@ -56,7 +87,7 @@ mod estimate_transaction_price {
             vec![0_u8, 0, 0, 0, 0],
         );
 
-        let result = estimate_transaction_price(&fee_history);
+        let result = estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet);
 
         assert_matches!(result, Err(TransactionPriceEstimationError::Overflow(_)));
     }
@@ -64,7 +95,7 @@ mod estimate_transaction_price {
     #[test]
     fn should_fail_when_max_priority_fee_per_gas_overflows() {
         let fee_history = fee_history(vec![0_u8, 0, 0, 0, 0, 1], [WeiPerGas::MAX; 5].to_vec());
-        let result = estimate_transaction_price(&fee_history);
+        let result = estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet);
         assert_matches!(result, Err(TransactionPriceEstimationError::Overflow(_)));
     }
 
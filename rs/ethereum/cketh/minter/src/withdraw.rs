@@ -7,14 +7,18 @@ use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::eth_rpc_client::EthRpcClient;
 use crate::eth_rpc_client::MultiCallError;
 use crate::guard::TimerGuard;
+use crate::l1_data_fee::{
+    ckerc20_withdrawal_template, cketh_withdrawal_template, estimate_l1_data_fee, L1DataFee,
+};
 use crate::logs::{DEBUG, INFO};
-use crate::numeric::{GasAmount, LedgerBurnIndex, LedgerMintIndex, TransactionCount};
+use crate::numeric::{GasAmount, LedgerBurnIndex, LedgerMintIndex, TransactionCount, Wei};
 use crate::state::audit::{process_event, EventType};
 use crate::state::transactions::{
-    create_transaction, CreateTransactionError, Reimbursed, ReimbursementRequest,
+    create_erc20_transaction, create_transaction, CreateTransactionError, Erc20Reimbursed,
+    Erc20ReimbursementRequest, Reimbursed, ReimbursementRequest,
 };
 use crate::state::{mutate_state, read_state, State, TaskType};
-use crate::tx::{estimate_transaction_price, TransactionPrice};
+use crate::tx::{estimate_transaction_price, TransactionPrice, TransactionPriceEstimate};
 use candid::Nat;
 use futures::future::join_all;
 use ic_canister_log::log;
@@ -29,6 +33,47 @@ const TRANSACTIONS_TO_SIGN_BATCH_SIZE: usize = 5;
 const TRANSACTIONS_TO_SEND_BATCH_SIZE: usize = 5;
 
 pub const CKETH_WITHDRAWAL_TRANSACTION_GAS_LIMIT: GasAmount = GasAmount::new(21_000);
+pub const CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT: GasAmount = GasAmount::new(65_000);
+
+/// Estimated L1 data fees of withdrawal transactions on layer 2 networks.
+/// The fees are zero on Ethereum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L1DataFeeEstimate {
+    pub cketh: L1DataFee,
+    pub ckerc20: L1DataFee,
+}
+
+impl L1DataFeeEstimate {
+    /// Price of a ckETH withdrawal transaction, where the gas limit covers the L1 data on Arbitrum.
+    pub fn cketh_transaction_price(&self, estimate: TransactionPriceEstimate) -> TransactionPrice {
+        estimate.to_price(
+            CKETH_WITHDRAWAL_TRANSACTION_GAS_LIMIT
+                .checked_add(self.cketh.additional_gas_limit)
+                .expect("BUG: gas limit always fits into U256"),
+        )
+    }
+
+    /// Price of a ckERC20 withdrawal transaction, where the gas limit covers the L1 data on Arbitrum.
+    pub fn ckerc20_transaction_price(
+        &self,
+        estimate: TransactionPriceEstimate,
+    ) -> TransactionPrice {
+        estimate.to_price(
+            CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT
+                .checked_add(self.ckerc20.additional_gas_limit)
+                .expect("BUG: gas limit always fits into U256"),
+        )
+    }
+
+    /// Maximum fee charged to the user for a ckERC20 withdrawal,
+    /// including the L1 data fee on OP Stack networks.
+    pub fn ckerc20_max_transaction_fee(&self, estimate: TransactionPriceEstimate) -> Wei {
+        self.ckerc20_transaction_price(estimate)
+            .max_transaction_fee()
+            .checked_add(self.ckerc20.fee)
+            .expect("BUG: transaction fee always fits into U256")
+    }
+}
 
 pub async fn process_reimbursement() {
     let _guard = match TimerGuard::new(TaskType::Reimbursement) {
@@ -41,7 +86,9 @@ pub async fn process_reimbursement() {
 
     let reimbursement_requests: Vec<ReimbursementRequest> =
         read_state(|s| s.eth_transactions.get_reimbursement_requests());
-    if reimbursement_requests.is_empty() {
+    let erc20_reimbursement_requests: Vec<Erc20ReimbursementRequest> =
+        read_state(|s| s.eth_transactions.get_erc20_reimbursement_requests());
+    if reimbursement_requests.is_empty() && erc20_reimbursement_requests.is_empty() {
         return;
     }
 
@@ -98,6 +145,62 @@ pub async fn process_reimbursement() {
             )
         });
     }
+    for reimbursement_request in erc20_reimbursement_requests {
+        let ledger_canister_id = reimbursement_request.ckerc20_ledger_id;
+        let client = ICRC1Client {
+            runtime: CdkRuntime,
+            ledger_canister_id,
+        };
+        let args = TransferArg {
+            from_subaccount: None,
+            to: Account {
+                owner: reimbursement_request.to,
+                subaccount: reimbursement_request
+                    .to_subaccount
+                    .as_ref()
+                    .map(|subaccount| subaccount.0),
+            },
+            fee: None,
+            created_at_time: None,
+            memo: Some(reimbursement_request.clone().into()),
+            amount: Nat::from(reimbursement_request.reimbursed_amount),
+        };
+        let block_index = match client.transfer(args).await {
+            Ok(Ok(block_index)) => block_index
+                .0
+                .to_u64()
+                .expect("block index should fit into u64"),
+            Ok(Err(err)) => {
+                log!(
+                    INFO,
+                    "[process_reimbursement] Failed to mint ckERC20 on ledger {ledger_canister_id}: {err}"
+                );
+                error_count += 1;
+                continue;
+            }
+            Err(err) => {
+                log!(
+                    INFO,
+                    "[process_reimbursement] Failed to send a message to the ledger ({ledger_canister_id}): {err:?}"
+                );
+                error_count += 1;
+                continue;
+            }
+        };
+        mutate_state(|s| {
+            process_event(
+                s,
+                EventType::ReimbursedErc20Withdrawal(Erc20Reimbursed {
+                    withdrawal_id: reimbursement_request.withdrawal_id,
+                    ckerc20_ledger_id: reimbursement_request.ckerc20_ledger_id,
+                    burn_in_block: reimbursement_request.burn_in_block,
+                    reimbursed_in_block: LedgerMintIndex::new(block_index),
+                    reimbursed_amount: reimbursement_request.reimbursed_amount,
+                    transaction_hash: Some(reimbursement_request.transaction_hash),
+                }),
+            )
+        });
+    }
     if error_count > 0 {
         log!(
             INFO,
@@ -106,6 +209,91 @@ pub async fn process_reimbursement() {
     }
 }
 
+/// Returns the last transaction price and L1 data fee estimates if they are recent enough,
+/// otherwise refreshes them by querying the latest fee history.
+pub async fn lazy_refresh_gas_fee_estimate() -> Option<(TransactionPriceEstimate, L1DataFeeEstimate)>
+{
+    const MAX_AGE_NS: u64 = 60_000_000_000_u64; //60 seconds
+
+    let now_ns = ic_cdk::api::time();
+    if let (Some((last_estimate_ns, estimate)), Some(l1_data_fee_estimate)) = read_state(|s| {
+        (
+            s.last_transaction_price_estimate.clone(),
+            s.last_l1_data_fee_estimate.clone(),
+        )
+    }) {
+        if now_ns.saturating_sub(last_estimate_ns) < MAX_AGE_NS {
+            return Some((estimate, l1_data_fee_estimate));
+        }
+    }
+    refresh_gas_fee_estimate().await
+}
+
+async fn refresh_gas_fee_estimate() -> Option<(TransactionPriceEstimate, L1DataFeeEstimate)> {
+    let fee_history = match eth_fee_history().await {
+        Ok(fee_history) => fee_history,
+        Err(e) => {
+            log!(
+                INFO,
+                "Failed retrieving fee history to estimate transaction price: {e:?}",
+            );
+            return None;
+        }
+    };
+    let ethereum_network = read_state(|s| s.ethereum_network);
+    let estimate = match estimate_transaction_price(&fee_history, ethereum_network) {
+        Ok(estimate) => estimate,
+        Err(e) => {
+            log!(INFO, "Failed estimating transaction price: {e:?}",);
+            return None;
+        }
+    };
+    // The L1 data fee is estimated at the newest block of the fee history,
+    // so that all replicas query the same state.
+    let newest_block = match fee_history.newest_block() {
+        Some(block_number) => BlockSpec::Number(block_number),
+        None => {
+            log!(INFO, "Failed estimating L1 data fee: empty fee history");
+            return None;
+        }
+    };
+    let client = read_state(EthRpcClient::from_state);
+    let cketh_template = cketh_withdrawal_template(
+        ethereum_network,
+        &estimate
+            .clone()
+            .to_price(CKETH_WITHDRAWAL_TRANSACTION_GAS_LIMIT),
+    );
+    let ckerc20_template = ckerc20_withdrawal_template(
+        ethereum_network,
+        &estimate
+            .clone()
+            .to_price(CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT),
+    );
+    let l1_data_fee_estimate = match futures::future::try_join(
+        estimate_l1_data_fee(
+            &client,
+            ethereum_network,
+            newest_block.clone(),
+            &cketh_template,
+        ),
+        estimate_l1_data_fee(&client, ethereum_network, newest_block, &ckerc20_template),
+    )
+    .await
+    {
+        Ok((cketh, ckerc20)) => L1DataFeeEstimate { cketh, ckerc20 },
+        Err(e) => {
+            log!(INFO, "Failed estimating L1 data fee: {e}");
+            return None;
+        }
+    };
+    mutate_state(|s| {
+        s.last_transaction_price_estimate = Some((ic_cdk::api::time(), estimate.clone()));
+        s.last_l1_data_fee_estimate = Some(l1_data_fee_estimate.clone());
+    });
+    Some((estimate, l1_data_fee_estimate))
+}
+
 pub async fn process_retrieve_eth_requests() {
     let _guard = match TimerGuard::new(TaskType::RetrieveEth) {
         Ok(guard) => guard,
@@ -122,42 +310,35 @@ pub async fn process_retrieve_eth_requests() {
         return;
     }
 
-    let fee_history = match eth_fee_history().await {
-        Ok(fee_history) => fee_history,
-        Err(e) => {
-            log!(
-                INFO,
-                "Failed retrieving fee history to process ETH requests: {e:?}",
-            );
-            return;
-        }
-    };
     // Transaction price is estimated everytime since the estimate uses the latest fee history
     // and a block on Ethereum is produced every 12s while making an HTTPs outcall on fiduciary subnet takes around 15s.
-    let transaction_price = match estimate_transaction_price(&fee_history) {
-        Ok(estimate) => {
-            mutate_state(|s| {
-                s.last_transaction_price_estimate = Some((ic_cdk::api::time(), estimate.clone()));
-            });
-            estimate.to_price(CKETH_WITHDRAWAL_TRANSACTION_GAS_LIMIT)
-        }
-        Err(e) => {
+    let (transaction_price_estimate, l1_data_fee_estimate) = match refresh_gas_fee_estimate().await
+    {
+        Some(estimates) => estimates,
+        None => {
             log!(
                 INFO,
-                "Failed estimating transaction price to process ETH requests: {e:?}",
+                "Failed estimating transaction price to process ETH requests",
             );
             return;
         }
     };
+    let transaction_price =
+        l1_data_fee_estimate.cketh_transaction_price(transaction_price_estimate.clone());
     let max_transaction_fee = transaction_price.max_transaction_fee();
     log!(
         INFO,
-        "[withdraw]: Estimated max transaction fee: {:?}",
+        "[withdraw]: Estimated max transaction fee: {:?}, L1 data fee: {:?}",
         max_transaction_fee,
+        l1_data_fee_estimate,
     );
     let latest_transaction_count = latest_transaction_count().await;
     resubmit_transactions_batch(latest_transaction_count, &transaction_price).await;
-    create_transactions_batch(transaction_price);
+    create_transactions_batch(transaction_price, l1_data_fee_estimate.cketh.fee);
+    create_erc20_transactions_batch(
+        l1_data_fee_estimate.ckerc20_transaction_price(transaction_price_estimate),
+        l1_data_fee_estimate.ckerc20.fee,
+    );
     sign_transactions_batch().await;
     send_transactions_batch(latest_transaction_count).await;
     finalize_transactions_batch().await;
@@ -227,7 +408,7 @@ async fn resubmit_transactions_batch(
     }
 }
 
-fn create_transactions_batch(transaction_price: TransactionPrice) {
+fn create_transactions_batch(transaction_price: TransactionPrice, l1_data_fee: Wei) {
     for request in read_state(|s| {
         s.eth_transactions
             .withdrawal_requests_batch(WITHDRAWAL_REQUESTS_BATCH_SIZE)
@@ -235,7 +416,13 @@ fn create_transactions_batch(transaction_price: TransactionPrice) {
         log!(DEBUG, "[create_transactions_batch]: processing {request:?}",);
         let ethereum_network = read_state(State::ethereum_network);
         let nonce = read_state(|s| s.eth_transactions.next_transaction_nonce());
-        match create_transaction(&request, nonce, transaction_price.clone(), ethereum_network) {
+        match create_transaction(
+            &request,
+            nonce,
+            transaction_price.clone(),
+            l1_data_fee,
+            ethereum_network,
+        ) {
             Ok(transaction) => {
                 log!(
                     DEBUG,
@@ -265,6 +452,77 @@ fn create_transactions_batch(transaction_price: TransactionPrice) {
                 );
                 mutate_state(|s| s.eth_transactions.reschedule_withdrawal_request(request));
             }
+            Err(e @ CreateTransactionError::InsufficientTransactionFee { .. }) => {
+                log!(
+                    INFO,
+                    "[create_transactions_batch]: BUG: unexpected error {e:?} for ETH withdrawal request {request:?}, which pays its fee from the withdrawn amount. Request moved back to end of queue."
+                );
+                mutate_state(|s| s.eth_transactions.reschedule_withdrawal_request(request));
+            }
+        };
+    }
+}
+
+fn create_erc20_transactions_batch(transaction_price: TransactionPrice, l1_data_fee: Wei) {
+    for request in read_state(|s| {
+        s.eth_transactions
+            .erc20_withdrawal_requests_batch(WITHDRAWAL_REQUESTS_BATCH_SIZE)
+    }) {
+        log!(
+            DEBUG,
+            "[create_erc20_transactions_batch]: processing {request:?}",
+        );
+        let ethereum_network = read_state(State::ethereum_network);
+        let nonce = read_state(|s| s.eth_transactions.next_transaction_nonce());
+        match create_erc20_transaction(
+            &request,
+            nonce,
+            transaction_price.clone(),
+            l1_data_fee,
+            ethereum_network,
+        ) {
+            Ok(transaction) => {
+                log!(
+                    DEBUG,
+                    "[create_erc20_transactions_batch]: created transaction {transaction:?}",
+                );
+
+                mutate_state(|s| {
+                    process_event(
+                        s,
+                        EventType::CreatedTransaction {
+                            withdrawal_id: request.cketh_ledger_burn_index,
+                            transaction,
+                        },
+                    );
+                });
+            }
+            Err(CreateTransactionError::InsufficientTransactionFee {
+                ledger_burn_index,
+                allowed_max_transaction_fee,
+                actual_max_transaction_fee,
+            }) => {
+                log!(
+                    INFO,
+                    "[create_erc20_transactions_batch]: Withdrawal request with burn index {ledger_burn_index} has insufficient
+                transaction fee {allowed_max_transaction_fee:?} to cover the current transaction fee: {actual_max_transaction_fee:?}.
+                Request moved back to end of queue."
+                );
+                mutate_state(|s| {
+                    s.eth_transactions
+                        .reschedule_erc20_withdrawal_request(request)
+                });
+            }
+            Err(e @ CreateTransactionError::InsufficientAmount { .. }) => {
+                log!(
+                    INFO,
+                    "[create_erc20_transactions_batch]: BUG: unexpected error {e:?} for ERC-20 withdrawal request {request:?}, which does not transfer any ETH. Request moved back to end of queue."
+                );
+                mutate_state(|s| {
+                    s.eth_transactions
+                        .reschedule_erc20_withdrawal_request(request)
+                });
+            }
         };
     }
 }
@@ -427,7 +685,7 @@ async fn finalized_transaction_count() -> Result<TransactionCount, MultiCallErro
     read_state(EthRpcClient::from_state)
         .eth_get_transaction_count(GetTransactionCountParams {
             address: crate::state::minter_address().await,
-            block: BlockSpec::Tag(BlockTag::Finalized),
+            block: BlockSpec::Tag(read_state(|s| s.withdrawal_finality_block_tag)),
         })
         .await
         .reduce_with_equality()
@@ -437,7 +695,7 @@ pub async fn eth_fee_history() -> Result<FeeHistory, MultiCallError<FeeHistory>>
     read_state(EthRpcClient::from_state)
         .eth_fee_history(FeeHistoryParams {
             block_count: Quantity::from(5_u8),
-            highest_block: BlockSpec::Tag(BlockTag::Latest),
+            highest_block: BlockSpec::Tag(read_state(|s| s.fee_estimation_block_tag)),
             reward_percentiles: vec![20],
         })
         .await
@@ -1,5 +1,6 @@
 use crate::mock::{
-    JsonRpcMethod, JsonRpcProvider, MockJsonRpcProviders, MockJsonRpcProvidersBuilder,
+    ethereum_rpc_providers, JsonRpcMethod, JsonRpcProvider, MockJsonRpcProviders,
+    MockJsonRpcProvidersBuilder,
 };
 use assert_matches::assert_matches;
 use candid::{Decode, Encode, Nat, Principal};
@@ -210,6 +211,77 @@ fn should_retrieve_cache_transaction_price() {
     assert_eq!(price, second_price);
 }
 
+#[test]
+fn should_deduct_l1_data_fee_when_withdrawing_on_layer_2() {
+    const L1_FEE: u64 = 0x2bbc3a8e5c;
+    let cketh = CkEthSetup::new_with_minter_init_arg(|init_arg| MinterInitArgs {
+        ethereum_network: EthereumNetwork::Base,
+        rpc_providers: Some(vec![
+            "https://mainnet.base.org".to_string(),
+            "https://base.publicnode.com".to_string(),
+            "https://base.llamarpc.com".to_string(),
+        ]),
+        ..init_arg
+    });
+    let caller: Principal = cketh.caller.into();
+    let withdrawal_amount = Nat::from(EXPECTED_BALANCE - CKETH_TRANSFER_FEE);
+    let destination = DEFAULT_WITHDRAWAL_DESTINATION_ADDRESS.to_string();
+
+    let cketh = cketh
+        .deposit(DepositParams::default())
+        .expect_mint()
+        .call_ledger_approve_minter(caller, EXPECTED_BALANCE, None)
+        .expect_ok(1)
+        .call_minter_withdraw_eth(caller, withdrawal_amount.clone(), destination.clone())
+        .expect_withdrawal_request_accepted();
+    let withdrawal_id = cketh.withdrawal_id().clone();
+
+    let cketh = cketh
+        .start_processing_withdrawals()
+        .retrieve_fee_history(identity)
+        .retrieve_l1_data_fee(L1_FEE)
+        .expect_status(RetrieveEthStatus::Pending)
+        .retrieve_latest_transaction_count(identity)
+        .expect_status(RetrieveEthStatus::TxCreated)
+        .send_raw_transaction(identity)
+        .expect_status_sent()
+        .retrieve_finalized_transaction_count(identity)
+        .expect_finalized_transaction();
+    let transaction_hash = cketh.sent_transaction_hash.clone();
+
+    let cketh = cketh
+        .retrieve_transaction_receipt(|mock| {
+            mock.modify_response_for_all(&mut |receipt: &mut serde_json::Value| {
+                receipt["l1Fee"] = json!(format!("{:#x}", L1_FEE));
+            })
+        })
+        .expect_finalized_status(TxFinalizedStatus::Success(EthTransaction {
+            transaction_hash,
+        }));
+
+    let max_fee_per_gas = Nat::from(33003708258u64);
+    let gas_limit = Nat::from(21_000_u32);
+    let estimated_l1_fee = Nat::from(2 * L1_FEE);
+    let transaction = cketh
+        .get_all_events()
+        .into_iter()
+        .find_map(|event| match event.payload {
+            EventPayload::CreatedTransaction {
+                withdrawal_id: id,
+                transaction,
+            } if id == withdrawal_id => Some(transaction),
+            _ => None,
+        })
+        .expect("missing CreatedTransaction event");
+    assert_eq!(transaction.chain_id, Nat::from(8453_u32));
+    assert_eq!(transaction.max_fee_per_gas, max_fee_per_gas);
+    assert_eq!(transaction.gas_limit, gas_limit);
+    assert_eq!(
+        transaction.value,
+        withdrawal_amount - max_fee_per_gas * gas_limit - estimated_l1_fee
+    );
+}
+
 #[test]
 fn should_block_deposit_from_blocked_address() {
     let cketh = CkEthSetup::new();
@@ -1152,8 +1224,19 @@ fn minter_wasm() -> Vec<u8> {
     )
 }
 
-fn install_minter(env: &StateMachine, ledger_id: CanisterId, minter_id: CanisterId) -> CanisterId {
-    let args = MinterInitArgs {
+fn install_minter(
+    env: &StateMachine,
+    minter_id: CanisterId,
+    init_arg: MinterInitArgs,
+) -> CanisterId {
+    let minter_arg = MinterArg::InitArg(init_arg);
+    env.install_existing_canister(minter_id, minter_wasm(), Encode!(&minter_arg).unwrap())
+        .unwrap();
+    minter_id
+}
+
+fn default_minter_init_arg(ledger_id: CanisterId) -> MinterInitArgs {
+    MinterInitArgs {
         ecdsa_key_name: "master_ecdsa_public_key".parse().unwrap(),
         ethereum_network: EthereumNetwork::Mainnet,
         ledger_id: ledger_id.get().0,
@@ -1162,11 +1245,10 @@ fn install_minter(env: &StateMachine, ledger_id: CanisterId, minter_id: Canister
         ethereum_contract_address: Some(HELPER_SMART_CONTRACT_ADDRESS.to_string()),
         minimum_withdrawal_amount: CKETH_TRANSFER_FEE.into(),
         last_scraped_block_number: LAST_SCRAPED_BLOCK_NUMBER_AT_INSTALL.into(),
-    };
-    let minter_arg = MinterArg::InitArg(args);
-    env.install_existing_canister(minter_id, minter_wasm(), Encode!(&minter_arg).unwrap())
-        .unwrap();
-    minter_id
+        rpc_providers: None,
+        withdrawal_finality_block_tag: None,
+        fee_estimation_block_tag: None,
+    }
 }
 
 fn default_deposit_from_address() -> Address {
@@ -1307,6 +1389,10 @@ fn transaction_receipt(transaction_hash: String) -> ethers_core::types::Transact
     serde_json::from_value(json_value).expect("BUG: invalid transaction receipt")
 }
 
+fn get_l1_fee_response(l1_fee: u64) -> String {
+    format!("0x{:064x}", l1_fee)
+}
+
 fn transaction_count_response(count: u32) -> String {
     format!("{:#x}", count)
 }
@@ -1396,6 +1482,7 @@ pub struct CkEthSetup {
     pub caller: PrincipalId,
     pub ledger_id: CanisterId,
     pub minter_id: CanisterId,
+    pub rpc_providers: Vec<String>,
 }
 
 impl Default for CkEthSetup {
@@ -1406,6 +1493,12 @@ impl Default for CkEthSetup {
 
 impl CkEthSetup {
     pub fn new() -> Self {
+        Self::new_with_minter_init_arg(identity)
+    }
+
+    pub fn new_with_minter_init_arg<F: FnOnce(MinterInitArgs) -> MinterInitArgs>(
+        override_init_arg: F,
+    ) -> Self {
         let env = StateMachineBuilder::new()
             .with_default_canister_range()
             .build();
@@ -1428,7 +1521,12 @@ impl CkEthSetup {
             .unwrap(),
         )
         .unwrap();
-        let minter_id = install_minter(&env, ledger_id, minter_id);
+        let init_arg = override_init_arg(default_minter_init_arg(ledger_id));
+        let rpc_providers = init_arg
+            .rpc_providers
+            .clone()
+            .unwrap_or_else(ethereum_rpc_providers);
+        let minter_id = install_minter(&env, minter_id, init_arg);
         let caller = PrincipalId::new_user_test_id(DEFAULT_PRINCIPAL_ID);
 
         let cketh = Self {
@@ -1436,6 +1534,7 @@ impl CkEthSetup {
             caller,
             ledger_id,
             minter_id,
+            rpc_providers,
         };
 
         assert_eq!(
@@ -2106,6 +2205,17 @@ impl FeeHistoryProcessWithdrawal {
         self
     }
 
+    pub fn retrieve_l1_data_fee(self, l1_fee: u64) -> Self {
+        // the L1 data fee is estimated for a ckETH and for a ckERC20 withdrawal
+        for _ in 0..2 {
+            MockJsonRpcProviders::when(JsonRpcMethod::EthCall)
+                .respond_for_all_with(get_l1_fee_response(l1_fee))
+                .build()
+                .expect_rpc_calls(&self.setup);
+        }
+        self
+    }
+
     pub fn expect_status(
         self,
         status: RetrieveEthStatus,
@@ -2336,7 +2446,7 @@ mod mock {
     use strum::IntoEnumIterator;
 
     trait Matcher {
-        fn matches(&self, context: &CanisterHttpRequestContext) -> bool;
+        fn matches(&self, context: &CanisterHttpRequestContext, provider_urls: &[String]) -> bool;
     }
     pub struct MockJsonRpcProviders {
         stubs: Vec<StubOnce>,
@@ -2363,6 +2473,9 @@ mod mock {
 
         #[strum(serialize = "eth_sendRawTransaction")]
         EthSendRawTransaction,
+
+        #[strum(serialize = "eth_call")]
+        EthCall,
     }
 
     #[derive(Copy, Debug, PartialEq, Eq, Clone, PartialOrd, Ord, strum_macros::EnumIter)]
@@ -2374,15 +2487,23 @@ mod mock {
     }
 
     impl JsonRpcProvider {
-        fn url(&self) -> &str {
-            match self {
-                JsonRpcProvider::Ankr => "https://rpc.ankr.com/eth",
-                JsonRpcProvider::PublicNode => "https://ethereum.publicnode.com",
-                JsonRpcProvider::Cloudflare => "https://cloudflare-eth.com",
-            }
+        /// URL of the provider among the ones used by the minter,
+        /// the variants being named after the default Ethereum providers.
+        fn url<'a>(&self, provider_urls: &'a [String]) -> &'a str {
+            provider_urls
+                .get(*self as usize)
+                .unwrap_or_else(|| panic!("BUG: no URL for provider {:?}", self))
         }
     }
 
+    pub fn ethereum_rpc_providers() -> Vec<String> {
+        vec![
+            "https://rpc.ankr.com/eth".to_string(),
+            "https://ethereum.publicnode.com".to_string(),
+            "https://cloudflare-eth.com".to_string(),
+        ]
+    }
+
     #[derive(Debug)]
     pub struct JsonRpcRequest {
         pub method: JsonRpcMethod,
@@ -2444,7 +2565,7 @@ mod mock {
     }
 
     impl Matcher for JsonRpcRequestMatcher {
-        fn matches(&self, context: &CanisterHttpRequestContext) -> bool {
+        fn matches(&self, context: &CanisterHttpRequestContext, provider_urls: &[String]) -> bool {
             let has_json_content_type_header = context
                 .headers
                 .iter()
@@ -2464,7 +2585,7 @@ mod mock {
                 JsonRpcRequest::from_str(request_body).expect("BUG: invalid JSON RPC request");
 
             self.http_method == context.http_method
-                && self.provider.url() == context.url
+                && self.provider.url(provider_urls) == context.url
                 && has_expected_max_response_bytes
                 && has_json_content_type_header
                 && self.json_rpc_method == json_rpc_request.method
@@ -2483,12 +2604,17 @@ mod mock {
     }
 
     impl StubOnce {
-        fn expect_rpc_call(self, env: &StateMachine, canister_id_cleanup_response: CanisterId) {
+        fn expect_rpc_call(
+            self,
+            env: &StateMachine,
+            canister_id_cleanup_response: CanisterId,
+            provider_urls: &[String],
+        ) {
             self.tick_until_next_http_request(env);
             let (id, context) = env
                 .canister_http_request_contexts()
                 .into_iter()
-                .find(|(_id, context)| self.matcher.matches(context))
+                .find(|(_id, context)| self.matcher.matches(context, provider_urls))
                 .unwrap_or_else(|| panic!("no request found matching the stub {:?}", self));
             let request_id = {
                 let request_body = context
@@ -2611,7 +2737,7 @@ mod mock {
 
         pub fn expect_rpc_calls(self, cketh: &CkEthSetup) {
             for stub in self.stubs {
-                stub.expect_rpc_call(&cketh.env, cketh.minter_id);
+                stub.expect_rpc_call(&cketh.env, cketh.minter_id, &cketh.rpc_providers);
             }
         }
     }
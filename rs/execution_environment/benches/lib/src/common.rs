@@ -32,7 +32,7 @@ use ic_test_utilities::{
 use ic_test_utilities_execution_environment::generate_network_topology;
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallbackId, CanisterMessage, Payload, RejectContext, RequestMetadata},
+    messages::{CallbackId, CanisterMessage, Payload, RejectContext, RequestMetadata, NO_DEADLINE},
     methods::{Callback, WasmClosure},
     Cycles, MemoryAllocation, NumBytes, NumInstructions, Time,
 };
@@ -107,8 +107,11 @@ where
     canister_state.system_state.freeze_threshold = 0.into();
 
     // Create call context and callback
-    let call_origin =
-        CallOrigin::CanisterUpdate(canister_test_id(REMOTE_CANISTER_ID), CallbackId::new(0));
+    let call_origin = CallOrigin::CanisterUpdate(
+        canister_test_id(REMOTE_CANISTER_ID),
+        CallbackId::new(0),
+        NO_DEADLINE,
+    );
     let call_context_id = canister_state
         .system_state
         .call_context_manager_mut()
@@ -129,6 +132,7 @@ where
         WasmClosure::new(0, 1),
         WasmClosure::new(0, 1),
         None,
+        NO_DEADLINE,
     );
 
     // Create an Ingress message
@@ -20,22 +20,30 @@ use ic_interfaces::execution_environment::{
 };
 use ic_logger::{error, fatal, info, ReplicaLogger};
 use ic_management_canister_types::{
-    CanisterChangeDetails, CanisterChangeOrigin, CanisterInstallModeV2, CanisterStatusResultV2,
-    CanisterStatusType, ChunkHash, InstallChunkedCodeArgs, InstallCodeArgsV2, Method as Ic00Method,
-    StoredChunksReply, UploadChunkReply,
+    CanisterChangeDetails, CanisterChangeOrigin, CanisterInstallModeV2, CanisterSnapshotDataKind,
+    CanisterSnapshotDataOffset, CanisterStatusResultV2, CanisterStatusType, ChunkHash,
+    InstallChunkedCodeArgs, InstallCodeArgsV2, MemoryMetrics, Method as Ic00Method,
+    ReadCanisterSnapshotDataArgs, ReadCanisterSnapshotDataResponse,
+    ReadCanisterSnapshotMetadataArgs, ReadCanisterSnapshotMetadataResponse, StoredChunksReply,
+    UploadCanisterSnapshotDataArgs, UploadCanisterSnapshotMetadataArgs,
+    UploadCanisterSnapshotMetadataResponse, UploadChunkReply,
 };
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::canister_state::system_state::ReservationError;
 use ic_replicated_state::{
-    canister_state::system_state::{
-        wasm_chunk_store::{self, WasmChunkStore},
-        CyclesUseCase,
+    canister_snapshots::{CanisterSnapshot, SnapshotId},
+    canister_state::{
+        system_state::{
+            wasm_chunk_store::{self, WasmChunkStore},
+            CyclesUseCase,
+        },
+        WASM_PAGE_SIZE_IN_BYTES,
     },
     metadata_state::subnet_call_context_manager::InstallCodeCallId,
-    page_map::PageAllocatorFileDescriptor,
-    CallOrigin, CanisterState, CanisterStatus, NetworkTopology, ReplicatedState, SchedulerState,
-    SystemState,
+    page_map::{Buffer, PageAllocatorFileDescriptor},
+    CallOrigin, CanisterState, CanisterStatus, NetworkTopology, NumWasmPages, PageMap,
+    ReplicatedState, SchedulerState, SystemState,
 };
 use ic_system_api::ExecutionParameters;
 use ic_types::{
@@ -47,7 +55,7 @@ use ic_types::{
     nominal_cycles::NominalCycles,
     CanisterId, CanisterTimer, ComputeAllocation, Cycles, InvalidComputeAllocationError,
     InvalidMemoryAllocationError, MemoryAllocation, NumBytes, NumInstructions, PrincipalId,
-    SubnetId, Time,
+    SubnetId, Time, MAX_STABLE_MEMORY_IN_BYTES, MAX_WASM_MEMORY_IN_BYTES,
 };
 use ic_wasm_types::{CanisterModule, WasmHash};
 use num_traits::cast::ToPrimitive;
@@ -116,6 +124,7 @@ pub(crate) struct CanisterMgrConfig {
     rate_limiting_of_heap_delta: FlagStatus,
     heap_delta_rate_limit: NumBytes,
     upload_wasm_chunk_instructions: NumInstructions,
+    max_number_of_snapshots_per_canister: usize,
 }
 
 impl CanisterMgrConfig {
@@ -134,6 +143,7 @@ impl CanisterMgrConfig {
         rate_limiting_of_heap_delta: FlagStatus,
         heap_delta_rate_limit: NumBytes,
         upload_wasm_chunk_instructions: NumInstructions,
+        max_number_of_snapshots_per_canister: usize,
     ) -> Self {
         Self {
             subnet_memory_capacity,
@@ -149,6 +159,7 @@ impl CanisterMgrConfig {
             rate_limiting_of_heap_delta,
             heap_delta_rate_limit,
             upload_wasm_chunk_instructions,
+            max_number_of_snapshots_per_canister,
         }
     }
 }
@@ -455,6 +466,10 @@ impl CanisterManager {
             | Ok(Ic00Method::SetupInitialDKG)
             | Ok(Ic00Method::SignWithECDSA)
             | Ok(Ic00Method::ComputeInitialEcdsaDealings)
+            | Ok(Ic00Method::SchnorrPublicKey)
+            | Ok(Ic00Method::SignWithSchnorr)
+            | Ok(Ic00Method::VetKdPublicKey)
+            | Ok(Ic00Method::VetKdDeriveEncryptedKey)
             // "DepositCycles" can be called by anyone however as ingress message
             // cannot carry cycles, it does not make sense to allow them from users.
             | Ok(Ic00Method::DepositCycles)
@@ -490,7 +505,11 @@ impl CanisterManager {
             | Ok(Ic00Method::TakeCanisterSnapshot)
             | Ok(Ic00Method::LoadCanisterSnapshot)
             | Ok(Ic00Method::ListCanisterSnapshots)
-            | Ok(Ic00Method::DeleteCanisterSnapshot) => {
+            | Ok(Ic00Method::DeleteCanisterSnapshot)
+            | Ok(Ic00Method::ReadCanisterSnapshotMetadata)
+            | Ok(Ic00Method::ReadCanisterSnapshotData)
+            | Ok(Ic00Method::UploadCanisterSnapshotMetadata)
+            | Ok(Ic00Method::UploadCanisterSnapshotData) => {
                 // Reject large install methods if the flag is not enabled, or
                 // they are not implemented.
                 match method {
@@ -651,6 +670,11 @@ impl CanisterManager {
         if let Some(log_visibility) = settings.log_visibility() {
             canister.system_state.log_visibility = log_visibility;
         }
+        if let Some(wasm_memory_limit) = settings.wasm_memory_limit() {
+            // A limit of zero means that the canister has no Wasm memory limit.
+            canister.system_state.wasm_memory_limit =
+                (wasm_memory_limit.get() > 0).then_some(wasm_memory_limit);
+        }
     }
 
     /// Tries to apply the requested settings on the canister identified by
@@ -1170,10 +1194,14 @@ impl CanisterManager {
     }
 
     /// Fetches the current status of the canister.
+    ///
+    /// `snapshots_memory_usage` is the memory taken by the snapshots of the
+    /// canister, which are stored outside of the canister state.
     pub(crate) fn get_canister_status(
         &self,
         sender: PrincipalId,
         canister: &mut CanisterState,
+        snapshots_memory_usage: NumBytes,
         subnet_size: usize,
     ) -> Result<CanisterStatusResultV2, CanisterManagerError> {
         // Skip the controller check if the canister itself is requesting its
@@ -1195,7 +1223,19 @@ impl CanisterManager {
         let memory_allocation = canister.memory_allocation();
         let freeze_threshold = canister.system_state.freeze_threshold;
         let reserved_cycles_limit = canister.system_state.reserved_balance_limit();
-        let log_visibility = canister.system_state.log_visibility;
+        let log_visibility = canister.system_state.log_visibility.clone();
+        let wasm_memory_limit = canister.system_state.wasm_memory_limit;
+        let memory_metrics = MemoryMetrics::new(
+            canister.wasm_memory_usage(),
+            canister.stable_memory_usage(),
+            canister.global_memory_usage(),
+            canister.wasm_binary_memory_usage(),
+            canister.wasm_custom_sections_memory_usage(),
+            canister.canister_history_memory_usage(),
+            canister.wasm_chunk_store_memory_usage(),
+            snapshots_memory_usage,
+            canister_message_memory_usage,
+        );
 
         Ok(CanisterStatusResultV2::new(
             canister.status(),
@@ -1206,12 +1246,14 @@ impl CanisterManager {
             *controller,
             controllers,
             canister_memory_usage,
+            memory_metrics,
             canister.system_state.balance().get(),
             compute_allocation.as_percent(),
             Some(memory_allocation.bytes().get()),
             freeze_threshold.get(),
             reserved_cycles_limit.map(|x| x.get()),
             log_visibility,
+            wasm_memory_limit.map(|x| x.get()),
             self.cycles_account_manager
                 .idle_cycles_burned_rate(
                     memory_allocation,
@@ -1804,6 +1846,398 @@ impl CanisterManager {
             .collect();
         Ok(StoredChunksReply(keys))
     }
+
+    /// Returns the snapshot identified by `snapshot_id`, provided it belongs to
+    /// the given canister.
+    fn get_snapshot<'a>(
+        &self,
+        canister_id: CanisterId,
+        snapshot_id: &[u8],
+        state: &'a ReplicatedState,
+    ) -> Result<(SnapshotId, &'a Arc<CanisterSnapshot>), CanisterManagerError> {
+        let snapshot_id = snapshot_id_from_bytes(snapshot_id)?;
+        let snapshot = state.canister_snapshots.get(snapshot_id).ok_or(
+            CanisterManagerError::CanisterSnapshotNotFound {
+                canister_id,
+                snapshot_id,
+            },
+        )?;
+        if *snapshot.canister_id() != canister_id {
+            return Err(CanisterManagerError::CanisterSnapshotInvalidOwnership {
+                canister_id,
+                snapshot_id,
+            });
+        }
+        Ok((snapshot_id, snapshot))
+    }
+
+    /// Returns the metadata of a canister snapshot, which describes the sizes
+    /// of the data that can be read with `read_canister_snapshot_data`.
+    pub(crate) fn read_canister_snapshot_metadata(
+        &self,
+        sender: PrincipalId,
+        args: &ReadCanisterSnapshotMetadataArgs,
+        state: &ReplicatedState,
+    ) -> Result<ReadCanisterSnapshotMetadataResponse, CanisterManagerError> {
+        let canister_id = args.get_canister_id();
+        let canister = get_canister(canister_id, state)?;
+        validate_controller(canister, &sender)?;
+
+        let (_, snapshot) = self.get_snapshot(canister_id, &args.snapshot_id, state)?;
+        Ok(ReadCanisterSnapshotMetadataResponse {
+            taken_at_timestamp: snapshot.taken_at_timestamp().as_nanos_since_unix_epoch(),
+            canister_version: snapshot.canister_version(),
+            certified_data: snapshot.certified_data().clone(),
+            wasm_module_size: snapshot
+                .wasm_binary()
+                .as_ref()
+                .map_or(0, |wasm_binary| wasm_binary.len() as u64),
+            wasm_memory_size: wasm_pages_to_bytes(snapshot.wasm_memory_size()),
+            stable_memory_size: wasm_pages_to_bytes(snapshot.stable_memory_size()),
+            wasm_chunk_store: snapshot
+                .chunk_store()
+                .keys()
+                .map(|hash| ChunkHash {
+                    hash: hash.to_vec(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Returns a slice of the Wasm module or of a memory of a canister
+    /// snapshot, or a chunk of its Wasm chunk store.
+    pub(crate) fn read_canister_snapshot_data(
+        &self,
+        sender: PrincipalId,
+        args: &ReadCanisterSnapshotDataArgs,
+        state: &ReplicatedState,
+    ) -> Result<ReadCanisterSnapshotDataResponse, CanisterManagerError> {
+        let canister_id = args.get_canister_id();
+        let canister = get_canister(canister_id, state)?;
+        validate_controller(canister, &sender)?;
+
+        let (_, snapshot) = self.get_snapshot(canister_id, &args.snapshot_id, state)?;
+        let chunk = match &args.kind {
+            CanisterSnapshotDataKind::WasmModule { offset, size } => {
+                let wasm_binary = snapshot
+                    .wasm_binary()
+                    .as_ref()
+                    .map_or(&[][..], |wasm_binary| wasm_binary.as_slice());
+                validate_snapshot_slice(*offset, *size, wasm_binary.len() as u64)?;
+                wasm_binary[*offset as usize..(*offset + *size) as usize].to_vec()
+            }
+            CanisterSnapshotDataKind::WasmMemory { offset, size } => read_page_map(
+                snapshot.wasm_memory(),
+                snapshot.wasm_memory_size(),
+                *offset,
+                *size,
+            )?,
+            CanisterSnapshotDataKind::StableMemory { offset, size } => read_page_map(
+                snapshot.stable_memory(),
+                snapshot.stable_memory_size(),
+                *offset,
+                *size,
+            )?,
+            CanisterSnapshotDataKind::WasmChunk { hash } => {
+                let hash = <[u8; 32]>::try_from(hash.as_slice()).map_err(|_| {
+                    CanisterManagerError::InvalidCanisterSnapshotData {
+                        message: format!("Invalid chunk hash of length {}", hash.len()),
+                    }
+                })?;
+                snapshot
+                    .chunk_store()
+                    .get_chunk_data(&hash)
+                    .ok_or_else(|| CanisterManagerError::InvalidCanisterSnapshotData {
+                        message: "The snapshot has no chunk with the given hash".to_string(),
+                    })?
+                    .flatten()
+                    .copied()
+                    .collect()
+            }
+        };
+        Ok(ReadCanisterSnapshotDataResponse { chunk })
+    }
+
+    /// Creates a new snapshot of the canister from the uploaded metadata. The
+    /// Wasm module and memories of the snapshot have the given sizes and are
+    /// zeroed until their data is uploaded with `upload_canister_snapshot_data`.
+    ///
+    /// If `replace_snapshot` is set, that snapshot of the canister is deleted.
+    pub(crate) fn upload_canister_snapshot_metadata(
+        &self,
+        sender: PrincipalId,
+        args: &UploadCanisterSnapshotMetadataArgs,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+    ) -> Result<UploadCanisterSnapshotMetadataResponse, CanisterManagerError> {
+        let canister_id = args.get_canister_id();
+        let canister = get_canister(canister_id, state)?;
+        validate_controller(canister, &sender)?;
+        let canister_version = canister.system_state.canister_version;
+
+        let replace_snapshot = match &args.replace_snapshot {
+            Some(snapshot_id) => Some(self.get_snapshot(canister_id, snapshot_id, state)?.0),
+            None => None,
+        };
+        if replace_snapshot.is_none()
+            && state.canister_snapshots.count_by_canister(&canister_id)
+                >= self.config.max_number_of_snapshots_per_canister
+        {
+            return Err(CanisterManagerError::CanisterSnapshotLimitExceeded {
+                canister_id,
+                limit: self.config.max_number_of_snapshots_per_canister,
+            });
+        }
+
+        if args.wasm_module_size > wasm_chunk_store::DEFAULT_MAX_SIZE.get() {
+            return Err(CanisterManagerError::InvalidCanisterSnapshotData {
+                message: format!(
+                    "Wasm module size {} exceeds the maximum of {}",
+                    args.wasm_module_size,
+                    wasm_chunk_store::DEFAULT_MAX_SIZE
+                ),
+            });
+        }
+        let wasm_memory_size = memory_size_in_wasm_pages(
+            "Wasm memory",
+            args.wasm_memory_size,
+            MAX_WASM_MEMORY_IN_BYTES,
+        )?;
+        let stable_memory_size = memory_size_in_wasm_pages(
+            "Stable memory",
+            args.stable_memory_size,
+            MAX_STABLE_MEMORY_IN_BYTES,
+        )?;
+
+        let snapshot_size =
+            NumBytes::from(args.wasm_module_size + args.wasm_memory_size + args.stable_memory_size);
+        round_limits
+            .subnet_available_memory
+            .try_decrement(snapshot_size, NumBytes::from(0), NumBytes::from(0))
+            .map_err(
+                |_| CanisterManagerError::SubnetMemoryCapacityOverSubscribed {
+                    requested: snapshot_size,
+                    available: NumBytes::from(
+                        round_limits
+                            .subnet_available_memory
+                            .get_execution_memory()
+                            .max(0) as u64,
+                    ),
+                },
+            )?;
+
+        let mut snapshot = CanisterSnapshot::new(
+            canister_id,
+            state.time(),
+            canister_version,
+            args.certified_data.clone(),
+            Some(PageMap::new(Arc::clone(&self.fd_factory))),
+            stable_memory_size,
+            Some(PageMap::new(Arc::clone(&self.fd_factory))),
+            wasm_memory_size,
+            WasmChunkStore::new(Arc::clone(&self.fd_factory)),
+            None,
+        );
+        snapshot.start_wasm_module_upload(args.wasm_module_size);
+        if let Some(replace_snapshot) = replace_snapshot {
+            state.canister_snapshots.remove(replace_snapshot);
+        }
+        let snapshot_id = state.canister_snapshots.push(Arc::new(snapshot));
+        Ok(UploadCanisterSnapshotMetadataResponse {
+            snapshot_id: snapshot_id_to_bytes(snapshot_id),
+        })
+    }
+
+    /// Writes an uploaded chunk to the Wasm module or a memory of a snapshot
+    /// created with `upload_canister_snapshot_metadata`, or inserts it into
+    /// its Wasm chunk store. The chunks of the Wasm module must be uploaded in
+    /// order. Returns the number of bytes written.
+    pub(crate) fn upload_canister_snapshot_data(
+        &self,
+        sender: PrincipalId,
+        args: &UploadCanisterSnapshotDataArgs,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+    ) -> Result<NumBytes, CanisterManagerError> {
+        let canister_id = args.get_canister_id();
+        let canister = get_canister(canister_id, state)?;
+        validate_controller(canister, &sender)?;
+        let (snapshot_id, _) = self.get_snapshot(canister_id, &args.snapshot_id, state)?;
+
+        // Charge for copying the uploaded bytes into the snapshot.
+        let chunk_size = args.chunk.len() as u64;
+        let instructions = NumInstructions::from(chunk_size);
+        let canister = state
+            .canister_state_mut(&canister_id)
+            .expect("The canister was looked up above");
+        let memory_usage = canister.memory_usage();
+        let message_memory_usage = canister.message_memory_usage();
+        let compute_allocation = canister.compute_allocation();
+        let prepaid_cycles = self
+            .cycles_account_manager
+            .prepay_execution_cycles(
+                &mut canister.system_state,
+                memory_usage,
+                message_memory_usage,
+                compute_allocation,
+                instructions,
+                subnet_size,
+                true, // the sender is a controller
+            )
+            .map_err(CanisterManagerError::CanisterSnapshotNotEnoughCycles)?;
+        // To keep the invariant that `prepay_execution_cycles` is always paired
+        // with `refund_unused_execution_cycles` we refund zero immediately.
+        self.cycles_account_manager.refund_unused_execution_cycles(
+            &mut canister.system_state,
+            NumInstructions::from(0),
+            instructions,
+            prepaid_cycles,
+            // This counter is incremented if we refund more
+            // instructions than initially charged, which is impossible
+            // here.
+            &IntCounter::new("no_op", "no_op").unwrap(),
+            subnet_size,
+            &self.log,
+        );
+        round_limits.instructions -= as_round_instructions(instructions);
+
+        let snapshot = state
+            .canister_snapshots
+            .get_mut(snapshot_id)
+            .expect("The snapshot was looked up above");
+        match &args.kind {
+            CanisterSnapshotDataOffset::WasmModule { offset } => {
+                snapshot
+                    .append_wasm_module_chunk(*offset, &args.chunk)
+                    .map_err(
+                        |message| CanisterManagerError::InvalidCanisterSnapshotData { message },
+                    )?;
+            }
+            CanisterSnapshotDataOffset::WasmMemory { offset } => {
+                let size = snapshot.wasm_memory_size();
+                write_page_map(snapshot.wasm_memory_mut(), size, *offset, &args.chunk)?;
+            }
+            CanisterSnapshotDataOffset::StableMemory { offset } => {
+                let size = snapshot.stable_memory_size();
+                write_page_map(snapshot.stable_memory_mut(), size, *offset, &args.chunk)?;
+            }
+            CanisterSnapshotDataOffset::WasmChunk => {
+                let chunk_store = snapshot.chunk_store_mut();
+                chunk_store
+                    .can_insert_chunk(&args.chunk)
+                    .map_err(|err| CanisterManagerError::WasmChunkStoreError { message: err })?;
+                chunk_store
+                    .insert_chunk(&args.chunk)
+                    .expect("Error: Insert chunk cannot fail after checking `can_insert_chunk`");
+            }
+        }
+        Ok(NumBytes::from(chunk_size))
+    }
+}
+
+/// The maximum size of a slice of snapshot data that can be read at once, so
+/// that the reply stays below the maximum payload size.
+const MAX_SNAPSHOT_DATA_SLICE_SIZE: u64 = 2_000_000;
+
+fn get_canister(
+    canister_id: CanisterId,
+    state: &ReplicatedState,
+) -> Result<&CanisterState, CanisterManagerError> {
+    state
+        .canister_state(&canister_id)
+        .ok_or(CanisterManagerError::CanisterNotFound(canister_id))
+}
+
+/// Snapshot IDs are exposed as the big-endian encoding of their number.
+fn snapshot_id_to_bytes(snapshot_id: SnapshotId) -> Vec<u8> {
+    snapshot_id.get().to_be_bytes().to_vec()
+}
+
+fn snapshot_id_from_bytes(bytes: &[u8]) -> Result<SnapshotId, CanisterManagerError> {
+    <[u8; 8]>::try_from(bytes)
+        .map(|bytes| SnapshotId::new(u64::from_be_bytes(bytes)))
+        .map_err(|_| CanisterManagerError::InvalidCanisterSnapshotData {
+            message: format!("Invalid snapshot ID of length {}", bytes.len()),
+        })
+}
+
+fn wasm_pages_to_bytes(size: NumWasmPages) -> u64 {
+    (size.get() * WASM_PAGE_SIZE_IN_BYTES) as u64
+}
+
+/// Validates the size of an uploaded memory and converts it to Wasm pages.
+fn memory_size_in_wasm_pages(
+    memory: &str,
+    size: u64,
+    max_size: u64,
+) -> Result<NumWasmPages, CanisterManagerError> {
+    if size > max_size || size % WASM_PAGE_SIZE_IN_BYTES as u64 != 0 {
+        return Err(CanisterManagerError::InvalidCanisterSnapshotData {
+            message: format!(
+                "{} size {} is not a multiple of the Wasm page size {} of at most {}",
+                memory, size, WASM_PAGE_SIZE_IN_BYTES, max_size
+            ),
+        });
+    }
+    Ok(NumWasmPages::new(
+        (size / WASM_PAGE_SIZE_IN_BYTES as u64) as usize,
+    ))
+}
+
+/// Checks that the slice of the given size at the given offset lies within
+/// data of size `total_size` and can be returned in a single reply.
+fn validate_snapshot_slice(
+    offset: u64,
+    size: u64,
+    total_size: u64,
+) -> Result<(), CanisterManagerError> {
+    if size > MAX_SNAPSHOT_DATA_SLICE_SIZE {
+        return Err(CanisterManagerError::InvalidCanisterSnapshotData {
+            message: format!(
+                "Slice size {} exceeds the maximum of {}",
+                size, MAX_SNAPSHOT_DATA_SLICE_SIZE
+            ),
+        });
+    }
+    match offset.checked_add(size) {
+        Some(end) if end <= total_size => Ok(()),
+        _ => Err(CanisterManagerError::InvalidCanisterSnapshotData {
+            message: format!(
+                "Slice of size {} at offset {} exceeds the data size {}",
+                size, offset, total_size
+            ),
+        }),
+    }
+}
+
+fn read_page_map(
+    page_map: &Option<PageMap>,
+    memory_size: NumWasmPages,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, CanisterManagerError> {
+    validate_snapshot_slice(offset, size, wasm_pages_to_bytes(memory_size))?;
+    let mut slice = vec![0; size as usize];
+    if let Some(page_map) = page_map {
+        Buffer::new(page_map.clone()).read(&mut slice, offset as usize);
+    }
+    Ok(slice)
+}
+
+fn write_page_map(
+    page_map: &mut Option<PageMap>,
+    memory_size: NumWasmPages,
+    offset: u64,
+    chunk: &[u8],
+) -> Result<(), CanisterManagerError> {
+    validate_snapshot_slice(offset, chunk.len() as u64, wasm_pages_to_bytes(memory_size))?;
+    if let Some(page_map) = page_map {
+        let mut buffer = Buffer::new(page_map.clone());
+        buffer.write(chunk, offset as usize);
+        *page_map = buffer.into_page_map();
+    }
+    Ok(())
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -1885,6 +2319,22 @@ pub(crate) enum CanisterManagerError {
     WasmChunkStoreError {
         message: String,
     },
+    CanisterSnapshotNotFound {
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+    },
+    CanisterSnapshotInvalidOwnership {
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+    },
+    CanisterSnapshotLimitExceeded {
+        canister_id: CanisterId,
+        limit: usize,
+    },
+    InvalidCanisterSnapshotData {
+        message: String,
+    },
+    CanisterSnapshotNotEnoughCycles(CanisterOutOfCyclesError),
 }
 
 impl From<CanisterManagerError> for UserError {
@@ -2121,6 +2571,44 @@ impl From<CanisterManagerError> for UserError {
                     )
                 )
             }
+            CanisterSnapshotNotFound { canister_id, snapshot_id } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Could not find the snapshot ID {} for canister {}", snapshot_id, canister_id
+                    )
+                )
+            }
+            CanisterSnapshotInvalidOwnership { canister_id, snapshot_id } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "The snapshot {} does not belong to canister {}", snapshot_id, canister_id
+                    )
+                )
+            }
+            CanisterSnapshotLimitExceeded { canister_id, limit } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Canister {} has reached the maximum number of {} snapshots. Replace an existing snapshot instead.", canister_id, limit
+                    )
+                )
+            }
+            InvalidCanisterSnapshotData { message } => {
+                Self::new(
+                    ErrorCode::CanisterContractViolation,
+                    format!(
+                        "Invalid canister snapshot data: {}", message
+                    )
+                )
+            }
+            CanisterSnapshotNotEnoughCycles(err) => {
+                Self::new(
+                    ErrorCode::CanisterOutOfCycles,
+                    format!("Canister snapshot upload failed with `{}`", err),
+                )
+            }
         }
     }
 }
@@ -2206,7 +2694,7 @@ pub fn uninstall_canister(
                         },
                     }));
                 }
-                CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
+                CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
                     rejects.push(Response::Canister(CanisterResponse {
                         originator: *caller_canister_id,
                         respondent: canister_id,
@@ -2216,6 +2704,7 @@ pub fn uninstall_canister(
                             RejectCode::CanisterReject,
                             "Canister has been uninstalled.",
                         )),
+                        deadline: *deadline,
                     }));
                 }
                 CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => fatal!(
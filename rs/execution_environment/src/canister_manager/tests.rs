@@ -14,7 +14,9 @@ use assert_matches::assert_matches;
 use candid::Decode;
 use ic_base_types::{NumSeconds, PrincipalId};
 use ic_config::{
-    execution_environment::Config, flag_status::FlagStatus, subnet_config::SchedulerConfig,
+    execution_environment::{Config, MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER},
+    flag_status::FlagStatus,
+    subnet_config::SchedulerConfig,
 };
 use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
 use ic_cycles_account_manager::{CyclesAccountManager, ResourceSaturation};
@@ -25,9 +27,13 @@ use ic_logger::replica_logger::no_op_logger;
 use ic_management_canister_types::{
     CanisterChange, CanisterChangeDetails, CanisterChangeOrigin, CanisterIdRecord,
     CanisterInstallMode, CanisterInstallModeV2, CanisterSettingsArgsBuilder,
-    CanisterStatusResultV2, CanisterStatusType, ChunkHash, ClearChunkStoreArgs, CreateCanisterArgs,
-    EmptyBlob, InstallCodeArgsV2, Method, Payload, SkipPreUpgrade, StoredChunksArgs,
-    StoredChunksReply, UpdateSettingsArgs, UploadChunkArgs, UploadChunkReply,
+    CanisterSnapshotDataKind, CanisterSnapshotDataOffset, CanisterStatusResultV2,
+    CanisterStatusType, ChunkHash, ClearChunkStoreArgs, CreateCanisterArgs, EmptyBlob,
+    InstallCodeArgsV2, Method, Payload, ReadCanisterSnapshotDataArgs,
+    ReadCanisterSnapshotDataResponse, ReadCanisterSnapshotMetadataArgs,
+    ReadCanisterSnapshotMetadataResponse, SkipPreUpgrade, StoredChunksArgs, StoredChunksReply,
+    UpdateSettingsArgs, UploadCanisterSnapshotDataArgs, UploadCanisterSnapshotMetadataArgs,
+    UploadCanisterSnapshotMetadataResponse, UploadChunkArgs, UploadChunkReply,
 };
 use ic_metrics::MetricsRegistry;
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
@@ -65,7 +71,7 @@ use ic_test_utilities_execution_environment::{
 use ic_test_utilities_time::mock_time;
 use ic_types::{
     ingress::{IngressState, IngressStatus, WasmResult},
-    messages::{CallbackId, CanisterCall, StopCanisterCallId, StopCanisterContext},
+    messages::{CallbackId, CanisterCall, StopCanisterCallId, StopCanisterContext, NO_DEADLINE},
     nominal_cycles::NominalCycles,
     CanisterId, CanisterTimer, ComputeAllocation, Cycles, MemoryAllocation, NumBytes,
     NumInstructions, SubnetId, Time, UserId,
@@ -288,6 +294,7 @@ fn canister_manager_config(
         // 10 MiB should be enough for all the tests.
         NumBytes::from(10 * 1024 * 1024),
         SchedulerConfig::application_subnet().upload_wasm_chunk_instructions,
+        MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER,
     )
 }
 
@@ -1716,6 +1723,7 @@ fn stop_a_running_canister() {
             reply_callback: CallbackId::new(0),
             call_id: Some(StopCanisterCallId::new(0)),
             cycles: Cycles::zero(),
+            deadline: NO_DEADLINE,
         };
         assert_eq!(
             canister_manager.stop_canister(canister_id, stop_context.clone(), &mut state),
@@ -1762,6 +1770,7 @@ fn stop_a_stopped_canister() {
             sender,
             message_id: message_test_id(0),
             call_id: Some(StopCanisterCallId::new(0)),
+            deadline: NO_DEADLINE,
         };
         assert_eq!(
             canister_manager.stop_canister(canister_id, stop_context, &mut state),
@@ -1798,6 +1807,7 @@ fn stop_a_stopped_canister_from_another_canister() {
             reply_callback: CallbackId::from(0),
             call_id: Some(StopCanisterCallId::new(0)),
             cycles: Cycles::from(cycles),
+            deadline: NO_DEADLINE,
         };
         assert_eq!(
             canister_manager.stop_canister(canister_id, stop_context, &mut state),
@@ -1853,6 +1863,7 @@ fn stop_a_canister_with_incorrect_controller() {
             sender: other_sender,
             message_id: msg_id,
             call_id: Some(StopCanisterCallId::new(0)),
+            deadline: NO_DEADLINE,
         };
 
         assert_eq!(
@@ -1881,6 +1892,7 @@ fn stop_a_non_existing_canister() {
                     sender: user_test_id(1),
                     message_id: message_test_id(0),
                     call_id: Some(StopCanisterCallId::new(0)),
+                    deadline: NO_DEADLINE,
                 },
                 &mut state
             ),
@@ -2029,6 +2041,7 @@ fn start_a_stopping_canister_with_stop_contexts() {
             sender: user_test_id(1),
             message_id: message_test_id(0),
             call_id: Some(StopCanisterCallId::new(0)),
+            deadline: NO_DEADLINE,
         };
         canister.system_state.add_stop_context(stop_context.clone());
 
@@ -2072,7 +2085,12 @@ fn get_canister_status_with_incorrect_controller() {
         let other_sender = user_test_id(1).get();
         let canister = state.canister_state_mut(&canister_id).unwrap();
         assert_eq!(
-            canister_manager.get_canister_status(other_sender, canister, SMALL_APP_SUBNET_MAX_SIZE),
+            canister_manager.get_canister_status(
+                other_sender,
+                canister,
+                NumBytes::new(0),
+                SMALL_APP_SUBNET_MAX_SIZE
+            ),
             Err(CanisterManagerError::CanisterInvalidController {
                 canister_id,
                 controllers_expected: btreeset! {sender},
@@ -2110,7 +2128,12 @@ fn get_canister_status_of_running_canister() {
 
         let canister = state.canister_state_mut(&canister_id).unwrap();
         let status = canister_manager
-            .get_canister_status(sender, canister, SMALL_APP_SUBNET_MAX_SIZE)
+            .get_canister_status(
+                sender,
+                canister,
+                NumBytes::new(0),
+                SMALL_APP_SUBNET_MAX_SIZE,
+            )
             .unwrap()
             .status();
         assert_eq!(status, CanisterStatusType::Running);
@@ -2145,7 +2168,12 @@ fn get_canister_status_of_self() {
 
         let canister = state.canister_state_mut(&canister_id).unwrap();
         let status = canister_manager
-            .get_canister_status(canister_id.get(), canister, SMALL_APP_SUBNET_MAX_SIZE)
+            .get_canister_status(
+                canister_id.get(),
+                canister,
+                NumBytes::new(0),
+                SMALL_APP_SUBNET_MAX_SIZE,
+            )
             .unwrap()
             .status();
         assert_eq!(status, CanisterStatusType::Running);
@@ -2162,7 +2190,12 @@ fn get_canister_status_of_stopped_canister() {
 
         let canister = state.canister_state_mut(&canister_id).unwrap();
         let status = canister_manager
-            .get_canister_status(sender, canister, SMALL_APP_SUBNET_MAX_SIZE)
+            .get_canister_status(
+                sender,
+                canister,
+                NumBytes::new(0),
+                SMALL_APP_SUBNET_MAX_SIZE,
+            )
             .unwrap()
             .status();
         assert_eq!(status, CanisterStatusType::Stopped);
@@ -2179,7 +2212,12 @@ fn get_canister_status_of_stopping_canister() {
 
         let canister = state.canister_state_mut(&canister_id).unwrap();
         let status = canister_manager
-            .get_canister_status(sender, canister, SMALL_APP_SUBNET_MAX_SIZE)
+            .get_canister_status(
+                sender,
+                canister,
+                NumBytes::new(0),
+                SMALL_APP_SUBNET_MAX_SIZE,
+            )
             .unwrap()
             .status();
         assert_eq!(status, CanisterStatusType::Stopping);
@@ -2613,7 +2651,7 @@ fn can_get_canister_balance() {
 
         let canister = state.canister_state_mut(&canister_id).unwrap();
         assert_matches!(
-            canister_manager.get_canister_status( sender, canister, SMALL_APP_SUBNET_MAX_SIZE),
+            canister_manager.get_canister_status(sender, canister, NumBytes::new(0), SMALL_APP_SUBNET_MAX_SIZE),
             Ok(res) if res.cycles() == cycles.get()
         );
     });
@@ -6792,6 +6830,65 @@ fn canister_status_contains_reserved_cycles_limit() {
     );
 }
 
+#[test]
+fn canister_status_contains_wasm_memory_limit() {
+    const CYCLES: Cycles = Cycles::new(1_000_000_000_000_000);
+
+    let mut test = ExecutionTestBuilder::new().build();
+
+    let canister_id = test.create_canister(CYCLES);
+    let result = test.canister_status(canister_id);
+    let reply = get_reply(result);
+    let status = CanisterStatusResultV2::decode(&reply).unwrap();
+    assert_eq!(
+        status.settings().wasm_memory_limit(),
+        candid::Nat::from(0_u32)
+    );
+
+    test.canister_update_wasm_memory_limit(canister_id, NumBytes::new(1 << 30))
+        .unwrap();
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .wasm_memory_limit,
+        Some(NumBytes::new(1 << 30))
+    );
+
+    let result = test.canister_status(canister_id);
+    let reply = get_reply(result);
+    let status = CanisterStatusResultV2::decode(&reply).unwrap();
+    assert_eq!(
+        status.settings().wasm_memory_limit(),
+        candid::Nat::from(1_u64 << 30),
+    );
+
+    // Setting the limit to zero removes it.
+    test.canister_update_wasm_memory_limit(canister_id, NumBytes::new(0))
+        .unwrap();
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .wasm_memory_limit,
+        None
+    );
+}
+
+#[test]
+fn update_settings_rejects_out_of_range_wasm_memory_limit() {
+    const CYCLES: Cycles = Cycles::new(1_000_000_000_000_000);
+
+    let mut test = ExecutionTestBuilder::new().build();
+
+    let canister_id = test.create_canister(CYCLES);
+    let err = test
+        .canister_update_wasm_memory_limit(canister_id, NumBytes::new((1 << 48) + 1))
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+    assert!(err
+        .description()
+        .contains("Wasm memory limit expected to be in the range of [0..2^48]"));
+}
+
 #[test]
 fn upload_chunk_works_from_white_list() {
     const CYCLES: Cycles = Cycles::new(1_000_000_000_000_000);
@@ -7719,3 +7816,329 @@ fn chunk_store_counts_against_subnet_memory_in_initial_round_computation() {
         .unwrap_err();
     assert_eq!(error.code(), ErrorCode::SubnetOversubscribed);
 }
+
+fn upload_canister_snapshot_metadata(
+    test: &mut ExecutionTest,
+    canister_id: CanisterId,
+    replace_snapshot: Option<Vec<u8>>,
+) -> Result<Vec<u8>, UserError> {
+    let args = UploadCanisterSnapshotMetadataArgs {
+        canister_id: canister_id.into(),
+        replace_snapshot: replace_snapshot.map(serde_bytes::ByteBuf::from),
+        wasm_module_size: 8,
+        wasm_memory_size: WASM_PAGE_SIZE_IN_BYTES,
+        stable_memory_size: 2 * WASM_PAGE_SIZE_IN_BYTES,
+        certified_data: vec![7; 32],
+    };
+    test.subnet_message("upload_canister_snapshot_metadata", args.encode())
+        .map(|result| {
+            UploadCanisterSnapshotMetadataResponse::decode(&get_reply(Ok(result)))
+                .unwrap()
+                .snapshot_id
+        })
+}
+
+fn upload_canister_snapshot_data(
+    test: &mut ExecutionTest,
+    canister_id: CanisterId,
+    snapshot_id: &[u8],
+    kind: CanisterSnapshotDataOffset,
+    chunk: Vec<u8>,
+) -> Result<WasmResult, UserError> {
+    let args = UploadCanisterSnapshotDataArgs {
+        canister_id: canister_id.into(),
+        snapshot_id: snapshot_id.to_vec(),
+        kind,
+        chunk,
+    };
+    test.subnet_message("upload_canister_snapshot_data", args.encode())
+}
+
+fn read_canister_snapshot_data(
+    test: &mut ExecutionTest,
+    canister_id: CanisterId,
+    snapshot_id: &[u8],
+    kind: CanisterSnapshotDataKind,
+) -> Result<Vec<u8>, UserError> {
+    let args = ReadCanisterSnapshotDataArgs {
+        canister_id: canister_id.into(),
+        snapshot_id: snapshot_id.to_vec(),
+        kind,
+    };
+    test.subnet_message("read_canister_snapshot_data", args.encode())
+        .map(|result| {
+            ReadCanisterSnapshotDataResponse::decode(&get_reply(Ok(result)))
+                .unwrap()
+                .chunk
+        })
+}
+
+#[test]
+fn uploaded_canister_snapshot_can_be_read() {
+    const CYCLES: Cycles = Cycles::new(1_000_000_000_000_000);
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.create_canister(CYCLES);
+
+    let snapshot_id = upload_canister_snapshot_metadata(&mut test, canister_id, None).unwrap();
+    let balance_before = test.canister_state(canister_id).system_state.balance();
+    upload_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataOffset::WasmModule { offset: 0 },
+        vec![1, 2, 3, 4],
+    )
+    .unwrap();
+    // Uploading data is charged.
+    assert!(test.canister_state(canister_id).system_state.balance() < balance_before);
+    // The chunks of the Wasm module must be uploaded in order.
+    let err = upload_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataOffset::WasmModule { offset: 6 },
+        vec![7, 8],
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+    upload_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataOffset::WasmModule { offset: 4 },
+        vec![5, 6, 7, 8],
+    )
+    .unwrap();
+    upload_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataOffset::StableMemory {
+            offset: WASM_PAGE_SIZE_IN_BYTES - 2,
+        },
+        vec![5, 6, 7, 8],
+    )
+    .unwrap();
+    upload_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataOffset::WasmChunk,
+        vec![9, 10],
+    )
+    .unwrap();
+
+    let args = ReadCanisterSnapshotMetadataArgs {
+        canister_id: canister_id.into(),
+        snapshot_id: snapshot_id.clone(),
+    };
+    let result = test.subnet_message("read_canister_snapshot_metadata", args.encode());
+    let metadata = ReadCanisterSnapshotMetadataResponse::decode(&get_reply(result)).unwrap();
+    assert_eq!(metadata.certified_data, vec![7; 32]);
+    assert_eq!(metadata.wasm_module_size, 8);
+    assert_eq!(metadata.wasm_memory_size, WASM_PAGE_SIZE_IN_BYTES);
+    assert_eq!(metadata.stable_memory_size, 2 * WASM_PAGE_SIZE_IN_BYTES);
+    let chunk_hash = ic_crypto_sha2::Sha256::hash(&[9, 10]).to_vec();
+    assert_eq!(
+        metadata.wasm_chunk_store,
+        vec![ChunkHash {
+            hash: chunk_hash.clone()
+        }]
+    );
+
+    assert_eq!(
+        read_canister_snapshot_data(
+            &mut test,
+            canister_id,
+            &snapshot_id,
+            CanisterSnapshotDataKind::WasmModule { offset: 0, size: 8 },
+        ),
+        Ok(vec![1, 2, 3, 4, 5, 6, 7, 8])
+    );
+    assert_eq!(
+        read_canister_snapshot_data(
+            &mut test,
+            canister_id,
+            &snapshot_id,
+            CanisterSnapshotDataKind::StableMemory {
+                offset: WASM_PAGE_SIZE_IN_BYTES - 4,
+                size: 8
+            },
+        ),
+        Ok(vec![0, 0, 5, 6, 7, 8, 0, 0])
+    );
+    assert_eq!(
+        read_canister_snapshot_data(
+            &mut test,
+            canister_id,
+            &snapshot_id,
+            CanisterSnapshotDataKind::WasmMemory { offset: 0, size: 4 },
+        ),
+        Ok(vec![0; 4])
+    );
+    assert_eq!(
+        read_canister_snapshot_data(
+            &mut test,
+            canister_id,
+            &snapshot_id,
+            CanisterSnapshotDataKind::WasmChunk { hash: chunk_hash },
+        ),
+        Ok(vec![9, 10])
+    );
+
+    // Reading or writing beyond the end of the data fails.
+    let err = read_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataKind::WasmModule { offset: 4, size: 5 },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+    let err = upload_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataOffset::WasmMemory {
+            offset: WASM_PAGE_SIZE_IN_BYTES,
+        },
+        vec![1],
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+}
+
+#[test]
+fn canister_snapshot_upload_is_limited_to_controllers_and_snapshot_limit() {
+    const CYCLES: Cycles = Cycles::new(1_000_000_000_000_000);
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.create_canister(CYCLES);
+    let other_canister_id = test.create_canister(CYCLES);
+
+    let snapshot_id = upload_canister_snapshot_metadata(&mut test, canister_id, None).unwrap();
+
+    // Only one snapshot per canister unless an existing one is replaced.
+    let err = upload_canister_snapshot_metadata(&mut test, canister_id, None).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+    let new_snapshot_id =
+        upload_canister_snapshot_metadata(&mut test, canister_id, Some(snapshot_id.clone()))
+            .unwrap();
+    assert_ne!(new_snapshot_id, snapshot_id);
+    let err = read_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &snapshot_id,
+        CanisterSnapshotDataKind::WasmModule { offset: 0, size: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+
+    // The snapshot cannot be accessed through another canister.
+    let err = read_canister_snapshot_data(
+        &mut test,
+        other_canister_id,
+        &new_snapshot_id,
+        CanisterSnapshotDataKind::WasmModule { offset: 0, size: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+
+    // Non-controllers cannot read or upload snapshots.
+    test.set_user_id(user_test_id(42));
+    let err = read_canister_snapshot_data(
+        &mut test,
+        canister_id,
+        &new_snapshot_id,
+        CanisterSnapshotDataKind::WasmModule { offset: 0, size: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterInvalidController);
+    let err = upload_canister_snapshot_metadata(&mut test, canister_id, Some(new_snapshot_id))
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterInvalidController);
+}
+
+#[test]
+fn canister_snapshot_upload_fails_when_feature_is_disabled() {
+    const CYCLES: Cycles = Cycles::new(1_000_000_000_000_000);
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Disabled)
+        .build();
+    let canister_id = test.create_canister(CYCLES);
+
+    let err = upload_canister_snapshot_metadata(&mut test, canister_id, None).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+    assert_eq!(err.description(), "This API is not enabled on this subnet");
+}
+
+#[test]
+fn canister_status_contains_memory_metrics() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test
+        .canister_from_wat(
+            r#"
+            (module
+                (memory $memory 2)
+                (global (export "g1") (mut i64) (i64.const 1))
+                (global (export "g2") (mut i64) (i64.const 2))
+                (func (export "canister_update test")
+                    (drop (memory.grow (i32.const 1)))
+                )
+            )"#,
+        )
+        .unwrap();
+
+    let result = test.canister_status(canister_id);
+    let status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    let memory_metrics = status.memory_metrics().unwrap();
+    let canister = test.canister_state(canister_id);
+    assert_eq!(
+        memory_metrics.wasm_memory_size(),
+        NumBytes::new(2 * WASM_PAGE_SIZE_IN_BYTES)
+    );
+    assert_eq!(memory_metrics.stable_memory_size(), NumBytes::new(0));
+    assert_eq!(memory_metrics.global_memory_size(), NumBytes::new(2 * 8));
+    assert_eq!(
+        memory_metrics.wasm_binary_size(),
+        canister.wasm_binary_memory_usage()
+    );
+    assert_eq!(
+        memory_metrics.custom_sections_size(),
+        canister.wasm_custom_sections_memory_usage()
+    );
+    assert_eq!(
+        memory_metrics.canister_history_size(),
+        canister.canister_history_memory_usage()
+    );
+    assert_eq!(memory_metrics.wasm_chunk_store_size(), NumBytes::new(0));
+    assert_eq!(memory_metrics.snapshots_size(), NumBytes::new(0));
+    assert_eq!(
+        memory_metrics.queued_messages_size(),
+        canister.message_memory_usage()
+    );
+    assert_eq!(
+        status.memory_size(),
+        memory_metrics.wasm_memory_size()
+            + memory_metrics.stable_memory_size()
+            + memory_metrics.global_memory_size()
+            + memory_metrics.wasm_binary_size()
+            + memory_metrics.custom_sections_size()
+            + memory_metrics.canister_history_size()
+            + memory_metrics.wasm_chunk_store_size()
+    );
+
+    // Snapshots are reported separately from the canister memory.
+    upload_canister_snapshot_metadata(&mut test, canister_id, None).unwrap();
+    let result = test.canister_status(canister_id);
+    let status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    assert_eq!(
+        status.memory_metrics().unwrap().snapshots_size(),
+        NumBytes::new(3 * WASM_PAGE_SIZE_IN_BYTES + 8 + 32)
+    );
+}
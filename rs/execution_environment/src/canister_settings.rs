@@ -12,6 +12,12 @@ use std::convert::TryFrom;
 
 use crate::canister_manager::CanisterManagerError;
 
+/// The upper bound on the value of the `wasm_memory_limit` setting (2^48 bytes).
+const MAX_WASM_MEMORY_LIMIT: u64 = 1 << 48;
+
+/// The maximum number of principals in the `allowed_viewers` log visibility.
+const MAX_ALLOWED_LOG_VIEWERS: usize = 10;
+
 /// Struct used for decoding CanisterSettingsArgs
 #[derive(Default)]
 pub(crate) struct CanisterSettings {
@@ -22,9 +28,11 @@ pub(crate) struct CanisterSettings {
     pub(crate) freezing_threshold: Option<NumSeconds>,
     pub(crate) reserved_cycles_limit: Option<Cycles>,
     pub(crate) log_visibility: Option<LogVisibility>,
+    pub(crate) wasm_memory_limit: Option<NumBytes>,
 }
 
 impl CanisterSettings {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         controller: Option<PrincipalId>,
         controllers: Option<Vec<PrincipalId>>,
@@ -33,6 +41,7 @@ impl CanisterSettings {
         freezing_threshold: Option<NumSeconds>,
         reserved_cycles_limit: Option<Cycles>,
         log_visibility: Option<LogVisibility>,
+        wasm_memory_limit: Option<NumBytes>,
     ) -> Self {
         Self {
             controller,
@@ -42,6 +51,7 @@ impl CanisterSettings {
             freezing_threshold,
             reserved_cycles_limit,
             log_visibility,
+            wasm_memory_limit,
         }
     }
 
@@ -70,7 +80,11 @@ impl CanisterSettings {
     }
 
     pub fn log_visibility(&self) -> Option<LogVisibility> {
-        self.log_visibility
+        self.log_visibility.clone()
+    }
+
+    pub fn wasm_memory_limit(&self) -> Option<NumBytes> {
+        self.wasm_memory_limit
     }
 }
 
@@ -109,6 +123,18 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
             None => None,
         };
 
+        let wasm_memory_limit = match input.wasm_memory_limit {
+            Some(limit) => match limit.0.to_u64() {
+                Some(limit_value) if limit_value <= MAX_WASM_MEMORY_LIMIT => {
+                    Some(NumBytes::new(limit_value))
+                }
+                _ => {
+                    return Err(UpdateSettingsError::WasmMemoryLimitOutOfRange { provided: limit });
+                }
+            },
+            None => None,
+        };
+
         Ok(CanisterSettings::new(
             controller,
             input
@@ -119,6 +145,7 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
             freezing_threshold,
             reserved_cycles_limit,
             input.log_visibility,
+            wasm_memory_limit,
         ))
     }
 }
@@ -142,6 +169,7 @@ pub(crate) struct CanisterSettingsBuilder {
     freezing_threshold: Option<NumSeconds>,
     reserved_cycles_limit: Option<Cycles>,
     log_visibility: Option<LogVisibility>,
+    wasm_memory_limit: Option<NumBytes>,
 }
 
 #[allow(dead_code)]
@@ -155,6 +183,7 @@ impl CanisterSettingsBuilder {
             freezing_threshold: None,
             reserved_cycles_limit: None,
             log_visibility: None,
+            wasm_memory_limit: None,
         }
     }
 
@@ -167,6 +196,7 @@ impl CanisterSettingsBuilder {
             freezing_threshold: self.freezing_threshold,
             reserved_cycles_limit: self.reserved_cycles_limit,
             log_visibility: self.log_visibility,
+            wasm_memory_limit: self.wasm_memory_limit,
         }
     }
 
@@ -218,6 +248,13 @@ impl CanisterSettingsBuilder {
             ..self
         }
     }
+
+    pub fn with_wasm_memory_limit(self, wasm_memory_limit: NumBytes) -> Self {
+        Self {
+            wasm_memory_limit: Some(wasm_memory_limit),
+            ..self
+        }
+    }
 }
 
 pub enum UpdateSettingsError {
@@ -225,6 +262,7 @@ pub enum UpdateSettingsError {
     MemoryAllocation(InvalidMemoryAllocationError),
     FreezingThresholdOutOfRange { provided: candid::Nat },
     ReservedCyclesLimitOutOfRange { provided: candid::Nat },
+    WasmMemoryLimitOutOfRange { provided: candid::Nat },
 }
 
 impl From<UpdateSettingsError> for UserError {
@@ -260,6 +298,13 @@ impl From<UpdateSettingsError> for UserError {
                     provided
                 ),
             ),
+            UpdateSettingsError::WasmMemoryLimitOutOfRange { provided } => UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!(
+                    "Wasm memory limit expected to be in the range of [0..2^48], got {}",
+                    provided
+                ),
+            ),
         }
     }
 }
@@ -285,6 +330,7 @@ pub(crate) struct ValidatedCanisterSettings {
     reserved_cycles_limit: Option<Cycles>,
     reservation_cycles: Cycles,
     log_visibility: Option<LogVisibility>,
+    wasm_memory_limit: Option<NumBytes>,
 }
 
 impl ValidatedCanisterSettings {
@@ -317,7 +363,11 @@ impl ValidatedCanisterSettings {
     }
 
     pub fn log_visibility(&self) -> Option<LogVisibility> {
-        self.log_visibility
+        self.log_visibility.clone()
+    }
+
+    pub fn wasm_memory_limit(&self) -> Option<NumBytes> {
+        self.wasm_memory_limit
     }
 }
 
@@ -332,6 +382,8 @@ impl ValidatedCanisterSettings {
 ///     - there must be enough cycles to avoid freezing the canister.
 /// - controllers:
 ///     - the number of controllers cannot exceed the given maximum.
+/// - log visibility:
+///     - the number of allowed log viewers cannot exceed the maximum.
 /// Keep this function in sync with `do_update_settings()`.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn validate_canister_settings(
@@ -423,6 +475,15 @@ pub(crate) fn validate_canister_settings(
         None => {}
     }
 
+    if let Some(LogVisibility::AllowedViewers(viewers)) = &settings.log_visibility {
+        if viewers.len() > MAX_ALLOWED_LOG_VIEWERS {
+            return Err(CanisterManagerError::InvalidSettings {
+                message: "Invalid settings: 'allowed_viewers' length exceeds maximum size allowed"
+                    .to_string(),
+            });
+        }
+    }
+
     let new_memory_allocation = settings
         .memory_allocation
         .unwrap_or(canister_memory_allocation);
@@ -516,5 +577,6 @@ pub(crate) fn validate_canister_settings(
         reserved_cycles_limit: settings.reserved_cycles_limit(),
         reservation_cycles,
         log_visibility: settings.log_visibility(),
+        wasm_memory_limit: settings.wasm_memory_limit(),
     })
 }
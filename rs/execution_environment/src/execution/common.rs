@@ -28,7 +28,7 @@ use ic_types::messages::{
     Response,
 };
 use ic_types::methods::{Callback, WasmMethod};
-use ic_types::{Cycles, NumInstructions, Time, UserId};
+use ic_types::{CoarseTime, Cycles, NumInstructions, Time, UserId};
 
 lazy_static! {
     /// Track how many system task errors have been encountered
@@ -73,8 +73,8 @@ pub(crate) fn action_to_response(
             log,
             ingress_with_cycles_error,
         ),
-        CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
-            action_to_request_response(canister, action, caller_canister_id, callback_id)
+        CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
+            action_to_request_response(canister, action, caller_canister_id, callback_id, deadline)
         }
         CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => fatal!(
             log,
@@ -95,6 +95,7 @@ pub(crate) fn action_to_request_response(
     action: CallContextAction,
     originator: CanisterId,
     reply_callback_id: CallbackId,
+    deadline: CoarseTime,
 ) -> ExecutionResponse {
     let response_payload_and_refund = match action {
         CallContextAction::NotYetResponded | CallContextAction::AlreadyResponded => None,
@@ -126,6 +127,7 @@ pub(crate) fn action_to_request_response(
             originator_reply_callback: reply_callback_id,
             refund,
             response_payload,
+            deadline,
         })
     } else {
         ExecutionResponse::Empty
@@ -239,13 +241,14 @@ pub(crate) fn wasm_result_to_query_response(
         CallOrigin::Ingress(user_id, message_id) => {
             wasm_result_to_ingress_response(result, canister, user_id, message_id, time)
         }
-        CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
+        CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
             let response = Response {
                 originator: caller_canister_id,
                 respondent: canister.canister_id(),
                 originator_reply_callback: callback_id,
                 refund,
                 response_payload: Payload::from(result),
+                deadline,
             };
             ExecutionResponse::Request(response)
         }
@@ -528,6 +531,7 @@ pub(crate) fn finish_call_with_error(
                 originator_reply_callback: request.sender_reply_callback,
                 refund: request.payment,
                 response_payload: Payload::from(Err(user_error)),
+                deadline: request.deadline,
             };
             ExecutionResponse::Request(response)
         }
@@ -580,7 +584,7 @@ mod test {
     use ic_logger::LoggerImpl;
     use ic_logger::ReplicaLogger;
     use ic_replicated_state::{CanisterState, SchedulerState, SystemState};
-    use ic_types::messages::CallbackId;
+    use ic_types::messages::{CallbackId, NO_DEADLINE};
     use ic_types::Cycles;
     use ic_types::Time;
 
@@ -607,6 +611,7 @@ mod test {
             ic_replicated_state::CallOrigin::CanisterUpdate(
                 CanisterId::from(123u64),
                 CallbackId::new(2),
+                NO_DEADLINE,
             ),
             &log,
             Cycles::from(1000u128),
@@ -927,9 +927,9 @@ pub fn execute_response(
     };
 
     let func_ref = match original.call_origin {
-        CallOrigin::Ingress(_, _) | CallOrigin::CanisterUpdate(_, _) | CallOrigin::SystemTask => {
-            FuncRef::UpdateClosure(closure)
-        }
+        CallOrigin::Ingress(_, _)
+        | CallOrigin::CanisterUpdate(_, _, _)
+        | CallOrigin::SystemTask => FuncRef::UpdateClosure(closure),
         CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => FuncRef::QueryClosure(closure),
     };
 
@@ -940,6 +940,7 @@ pub fn execute_response(
             payload.to_vec(),
             helper.refund_for_sent_cycles(),
             call_context_id,
+            response.deadline,
             call_context.has_responded(),
             execution_parameters.execution_mode.clone(),
             call_context.instructions_executed(),
@@ -950,6 +951,7 @@ pub fn execute_response(
             context.clone(),
             helper.refund_for_sent_cycles(),
             call_context_id,
+            response.deadline,
             call_context.has_responded(),
             execution_parameters.execution_mode.clone(),
             call_context.instructions_executed(),
@@ -1017,9 +1019,9 @@ fn execute_response_cleanup(
         .instruction_limits
         .update(instructions_left);
     let func_ref = match original.call_origin {
-        CallOrigin::Ingress(_, _) | CallOrigin::CanisterUpdate(_, _) | CallOrigin::SystemTask => {
-            FuncRef::UpdateClosure(cleanup_closure)
-        }
+        CallOrigin::Ingress(_, _)
+        | CallOrigin::CanisterUpdate(_, _, _)
+        | CallOrigin::SystemTask => FuncRef::UpdateClosure(cleanup_closure),
         CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
             FuncRef::QueryClosure(cleanup_closure)
         }
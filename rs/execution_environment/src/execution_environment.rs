@@ -23,7 +23,8 @@ use ic_base_types::PrincipalId;
 use ic_config::execution_environment::Config as ExecutionConfig;
 use ic_config::flag_status::FlagStatus;
 use ic_constants::{LOG_CANISTER_OPERATION_CYCLES_THRESHOLD, SMALL_APP_SUBNET_MAX_SIZE};
-use ic_crypto_tecdsa::derive_tecdsa_public_key;
+use ic_crypto_tecdsa::{derive_tecdsa_public_key, derive_threshold_schnorr_public_key};
+use ic_crypto_vetkd::{derive_vetkd_public_key, validate_encryption_public_key};
 use ic_cycles_account_manager::{
     is_delayed_ingress_induction_cost, CyclesAccountManager, IngressInductionCost,
     ResourceSaturation,
@@ -38,10 +39,13 @@ use ic_management_canister_types::{
     CanisterInfoResponse, CanisterSettingsArgs, CanisterStatusType, ClearChunkStoreArgs,
     ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs, ECDSAPublicKeyArgs,
     ECDSAPublicKeyResponse, EcdsaKeyId, EmptyBlob, InstallChunkedCodeArgs, InstallCodeArgsV2,
-    Method as Ic00Method, NodeMetricsHistoryArgs, Payload as Ic00Payload,
-    ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs, SetupInitialDKGArgs,
-    SignWithECDSAArgs, StoredChunksArgs, UninstallCodeArgs, UpdateSettingsArgs, UploadChunkArgs,
-    IC_00,
+    MasterPublicKeyId, Method as Ic00Method, NodeMetricsHistoryArgs, Payload as Ic00Payload,
+    ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs,
+    ReadCanisterSnapshotDataArgs, ReadCanisterSnapshotMetadataArgs, SchnorrKeyId,
+    SchnorrPublicKeyArgs, SchnorrPublicKeyResponse, SetupInitialDKGArgs, SignWithECDSAArgs,
+    SignWithSchnorrArgs, StoredChunksArgs, UninstallCodeArgs, UpdateSettingsArgs,
+    UploadCanisterSnapshotDataArgs, UploadCanisterSnapshotMetadataArgs, UploadChunkArgs,
+    VetKdDeriveEncryptedKeyArgs, VetKdKeyId, VetKdPublicKeyArgs, VetKdPublicKeyResult, IC_00,
 };
 use ic_metrics::MetricsRegistry;
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
@@ -51,7 +55,8 @@ use ic_replicated_state::{
     canister_state::{system_state::CyclesUseCase, NextExecution},
     metadata_state::subnet_call_context_manager::{
         EcdsaDealingsContext, InstallCodeCall, InstallCodeCallId, SetupInitialDkgContext,
-        SignWithEcdsaContext, StopCanisterCall, SubnetCallContext,
+        SignWithEcdsaContext, SignWithSchnorrContext, StopCanisterCall, SubnetCallContext,
+        VetKdDeriveEncryptedKeyContext,
     },
     page_map::PageAllocatorFileDescriptor,
     CanisterState, CanisterStatus, ExecutionTask, NetworkTopology, ReplicatedState,
@@ -59,7 +64,7 @@ use ic_replicated_state::{
 use ic_system_api::{ExecutionParameters, InstructionLimits};
 use ic_types::{
     canister_http::CanisterHttpRequestContext,
-    crypto::canister_threshold_sig::{ExtendedDerivationPath, MasterEcdsaPublicKey},
+    crypto::canister_threshold_sig::{ExtendedDerivationPath, MasterPublicKey},
     crypto::threshold_sig::ni_dkg::NiDkgTargetId,
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{
@@ -373,6 +378,7 @@ impl ExecutionEnvironment {
             config.rate_limiting_of_heap_delta,
             heap_delta_rate_limit,
             upload_wasm_chunk_instructions,
+            config.max_number_of_snapshots_per_canister,
         );
         let metrics = ExecutionEnvironmentMetrics::new(metrics_registry);
         let canister_manager = CanisterManager::new(
@@ -442,7 +448,7 @@ impl ExecutionEnvironment {
         mut state: ReplicatedState,
         instruction_limits: InstructionLimits,
         rng: &mut dyn RngCore,
-        ecdsa_subnet_public_keys: &BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
         registry_settings: &RegistryExecutionSettings,
         round_limits: &mut RoundLimits,
     ) -> (ReplicatedState, Option<NumInstructions>) {
@@ -484,6 +490,7 @@ impl ExecutionEnvironment {
                                 originator_reply_callback: request.sender_reply_callback,
                                 refund: request.payment,
                                 response_payload: response.response_payload.clone(),
+                                deadline: request.deadline,
                             }
                             .into(),
                         );
@@ -559,6 +566,7 @@ impl ExecutionEnvironment {
                                         "An empty message cannot be signed",
                                     ),
                                 ),
+                                deadline: request.deadline,
                             }
                             .into(),
                         );
@@ -567,41 +575,33 @@ impl ExecutionEnvironment {
 
                     match SignWithECDSAArgs::decode(payload) {
                         Err(err) => Some((Err(err), msg.take_cycles())),
-                        Ok(args) => {
-                            match get_master_ecdsa_public_key(
-                                ecdsa_subnet_public_keys,
-                                self.own_subnet_id,
-                                &args.key_id,
-                            ) {
-                                Err(err) => Some((Err(err), msg.take_cycles())),
-                                Ok(_) => match self.sign_with_ecdsa(
-                                    (**request).clone(),
-                                    args.message_hash,
-                                    args.derivation_path
-                                        .get()
-                                        .clone()
-                                        .into_iter()
-                                        .map(|x| x.into_vec())
-                                        .collect(),
-                                    args.key_id,
-                                    registry_settings.max_ecdsa_queue_size,
-                                    &mut state,
-                                    rng,
-                                    registry_settings.subnet_size,
-                                ) {
-                                    Err(err) => Some((Err(err), msg.take_cycles())),
-                                    Ok(()) => {
-                                        self.metrics.observe_message_with_label(
-                                            &request.method_name,
-                                            since.elapsed().as_secs_f64(),
-                                            SUBMITTED_OUTCOME_LABEL.into(),
-                                            SUCCESS_STATUS_LABEL.into(),
-                                        );
-                                        None
-                                    }
-                                },
+                        Ok(args) => match self.sign_with_ecdsa(
+                            (**request).clone(),
+                            args.message_hash,
+                            args.derivation_path
+                                .get()
+                                .clone()
+                                .into_iter()
+                                .map(|x| x.into_vec())
+                                .collect(),
+                            args.key_id,
+                            chain_key_subnet_public_keys,
+                            registry_settings.max_ecdsa_queue_size,
+                            &mut state,
+                            rng,
+                            registry_settings.subnet_size,
+                        ) {
+                            Err(err) => Some((Err(err), msg.take_cycles())),
+                            Ok(()) => {
+                                self.metrics.observe_message_with_label(
+                                    &request.method_name,
+                                    since.elapsed().as_secs_f64(),
+                                    SUBMITTED_OUTCOME_LABEL.into(),
+                                    SUCCESS_STATUS_LABEL.into(),
+                                );
+                                None
                             }
-                        }
+                        },
                     }
                 }
                 CanisterCall::Ingress(_) => {
@@ -609,6 +609,105 @@ impl ExecutionEnvironment {
                 }
             },
 
+            Ok(Ic00Method::SignWithSchnorr) => match self.config.threshold_schnorr {
+                FlagStatus::Enabled => match &msg {
+                    CanisterCall::Request(request) => match SignWithSchnorrArgs::decode(payload) {
+                        Err(err) => Some((Err(err), msg.take_cycles())),
+                        Ok(args) => match self.sign_with_schnorr(
+                            (**request).clone(),
+                            args.message,
+                            args.derivation_path
+                                .get()
+                                .clone()
+                                .into_iter()
+                                .map(|x| x.into_vec())
+                                .collect(),
+                            args.key_id,
+                            chain_key_subnet_public_keys,
+                            registry_settings.max_schnorr_queue_size,
+                            &mut state,
+                            rng,
+                            registry_settings.subnet_size,
+                        ) {
+                            Err(err) => Some((Err(err), msg.take_cycles())),
+                            Ok(()) => {
+                                self.metrics.observe_message_with_label(
+                                    &request.method_name,
+                                    since.elapsed().as_secs_f64(),
+                                    SUBMITTED_OUTCOME_LABEL.into(),
+                                    SUCCESS_STATUS_LABEL.into(),
+                                );
+                                None
+                            }
+                        },
+                    },
+                    CanisterCall::Ingress(_) => {
+                        self.reject_unexpected_ingress(Ic00Method::SignWithSchnorr)
+                    }
+                },
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        format!(
+                            "{} API is not enabled on this subnet",
+                            Ic00Method::SignWithSchnorr
+                        ),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
+            Ok(Ic00Method::VetKdDeriveEncryptedKey) => match self.config.vetkd {
+                FlagStatus::Enabled => match &msg {
+                    CanisterCall::Request(request) => {
+                        match VetKdDeriveEncryptedKeyArgs::decode(payload) {
+                            Err(err) => Some((Err(err), msg.take_cycles())),
+                            Ok(args) => match self.vetkd_derive_encrypted_key(
+                                (**request).clone(),
+                                args.derivation_path
+                                    .get()
+                                    .clone()
+                                    .into_iter()
+                                    .map(|x| x.into_vec())
+                                    .collect(),
+                                args.derivation_id,
+                                args.encryption_public_key,
+                                args.key_id,
+                                chain_key_subnet_public_keys,
+                                registry_settings.max_vetkd_queue_size,
+                                &mut state,
+                                rng,
+                                registry_settings.subnet_size,
+                            ) {
+                                Err(err) => Some((Err(err), msg.take_cycles())),
+                                Ok(()) => {
+                                    self.metrics.observe_message_with_label(
+                                        &request.method_name,
+                                        since.elapsed().as_secs_f64(),
+                                        SUBMITTED_OUTCOME_LABEL.into(),
+                                        SUCCESS_STATUS_LABEL.into(),
+                                    );
+                                    None
+                                }
+                            },
+                        }
+                    }
+                    CanisterCall::Ingress(_) => {
+                        self.reject_unexpected_ingress(Ic00Method::VetKdDeriveEncryptedKey)
+                    }
+                },
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        format!(
+                            "{} API is not enabled on this subnet",
+                            Ic00Method::VetKdDeriveEncryptedKey
+                        ),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
             Ok(Ic00Method::CreateCanister) => {
                 match &mut msg {
                     CanisterCall::Ingress(_) => {
@@ -911,10 +1010,10 @@ impl ExecutionEnvironment {
                     CanisterCall::Request(request) => {
                         let res = match ECDSAPublicKeyArgs::decode(request.method_payload()) {
                             Err(err) => Some(Err(err)),
-                            Ok(args) => match get_master_ecdsa_public_key(
-                                ecdsa_subnet_public_keys,
+                            Ok(args) => match get_master_public_key(
+                                chain_key_subnet_public_keys,
                                 self.own_subnet_id,
-                                &args.key_id,
+                                &MasterPublicKeyId::Ecdsa(args.key_id.clone()),
                             ) {
                                 Err(err) => Some(Err(err)),
                                 Ok(pubkey) => {
@@ -947,6 +1046,108 @@ impl ExecutionEnvironment {
                 }
             }
 
+            Ok(Ic00Method::SchnorrPublicKey) => match self.config.threshold_schnorr {
+                FlagStatus::Enabled => {
+                    let cycles = msg.take_cycles();
+                    match &msg {
+                        CanisterCall::Request(request) => {
+                            let res = match SchnorrPublicKeyArgs::decode(request.method_payload()) {
+                                Err(err) => Err(err),
+                                Ok(args) => match get_master_public_key(
+                                    chain_key_subnet_public_keys,
+                                    self.own_subnet_id,
+                                    &MasterPublicKeyId::Schnorr(args.key_id.clone()),
+                                ) {
+                                    Err(err) => Err(err),
+                                    Ok(pubkey) => {
+                                        let canister_id = match args.canister_id {
+                                            Some(id) => id.into(),
+                                            None => *msg.sender(),
+                                        };
+                                        self.get_schnorr_public_key(
+                                            pubkey,
+                                            canister_id,
+                                            args.derivation_path
+                                                .get()
+                                                .clone()
+                                                .into_iter()
+                                                .map(|x| x.into_vec())
+                                                .collect(),
+                                        )
+                                        .map(|res| res.encode())
+                                    }
+                                },
+                            };
+                            Some((res, cycles))
+                        }
+                        CanisterCall::Ingress(_) => {
+                            self.reject_unexpected_ingress(Ic00Method::SchnorrPublicKey)
+                        }
+                    }
+                }
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        format!(
+                            "{} API is not enabled on this subnet",
+                            Ic00Method::SchnorrPublicKey
+                        ),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
+            Ok(Ic00Method::VetKdPublicKey) => match self.config.vetkd {
+                FlagStatus::Enabled => {
+                    let cycles = msg.take_cycles();
+                    match &msg {
+                        CanisterCall::Request(request) => {
+                            let res = match VetKdPublicKeyArgs::decode(request.method_payload()) {
+                                Err(err) => Err(err),
+                                Ok(args) => match get_master_public_key(
+                                    chain_key_subnet_public_keys,
+                                    self.own_subnet_id,
+                                    &MasterPublicKeyId::VetKd(args.key_id.clone()),
+                                ) {
+                                    Err(err) => Err(err),
+                                    Ok(pubkey) => {
+                                        let canister_id = match args.canister_id {
+                                            Some(id) => id.into(),
+                                            None => *msg.sender(),
+                                        };
+                                        self.get_vetkd_public_key(
+                                            pubkey,
+                                            canister_id,
+                                            args.derivation_path
+                                                .get()
+                                                .clone()
+                                                .into_iter()
+                                                .map(|x| x.into_vec())
+                                                .collect(),
+                                        )
+                                        .map(|res| res.encode())
+                                    }
+                                },
+                            };
+                            Some((res, cycles))
+                        }
+                        CanisterCall::Ingress(_) => {
+                            self.reject_unexpected_ingress(Ic00Method::VetKdPublicKey)
+                        }
+                    }
+                }
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        format!(
+                            "{} API is not enabled on this subnet",
+                            Ic00Method::VetKdPublicKey
+                        ),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
             Ok(Ic00Method::ComputeInitialEcdsaDealings) => {
                 let cycles = msg.take_cycles();
                 match &msg {
@@ -954,10 +1155,10 @@ impl ExecutionEnvironment {
                         let result =
                             match ComputeInitialEcdsaDealingsArgs::decode(request.method_payload())
                             {
-                                Ok(args) => match get_master_ecdsa_public_key(
-                                    ecdsa_subnet_public_keys,
+                                Ok(args) => match get_master_public_key(
+                                    chain_key_subnet_public_keys,
                                     self.own_subnet_id,
-                                    &args.key_id,
+                                    &MasterPublicKeyId::Ecdsa(args.key_id.clone()),
                                 ) {
                                     Ok(_) => self
                                         .compute_initial_ecdsa_dealings(&mut state, args, request)
@@ -1213,6 +1414,84 @@ impl ExecutionEnvironment {
                 }
             },
 
+            Ok(Ic00Method::ReadCanisterSnapshotMetadata) => match self.config.canister_snapshots {
+                FlagStatus::Enabled => {
+                    let res = ReadCanisterSnapshotMetadataArgs::decode(payload).and_then(|args| {
+                        self.read_canister_snapshot_metadata(*msg.sender(), &state, args)
+                    });
+                    Some((res, msg.take_cycles()))
+                }
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        "This API is not enabled on this subnet".to_string(),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
+            Ok(Ic00Method::ReadCanisterSnapshotData) => match self.config.canister_snapshots {
+                FlagStatus::Enabled => {
+                    let res = ReadCanisterSnapshotDataArgs::decode(payload).and_then(|args| {
+                        self.read_canister_snapshot_data(*msg.sender(), &state, args)
+                    });
+                    Some((res, msg.take_cycles()))
+                }
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        "This API is not enabled on this subnet".to_string(),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
+            Ok(Ic00Method::UploadCanisterSnapshotMetadata) => {
+                match self.config.canister_snapshots {
+                    FlagStatus::Enabled => {
+                        let res =
+                            UploadCanisterSnapshotMetadataArgs::decode(payload).and_then(|args| {
+                                self.upload_canister_snapshot_metadata(
+                                    *msg.sender(),
+                                    &mut state,
+                                    args,
+                                    round_limits,
+                                )
+                            });
+                        Some((res, msg.take_cycles()))
+                    }
+                    FlagStatus::Disabled => {
+                        let err = Err(UserError::new(
+                            ErrorCode::CanisterContractViolation,
+                            "This API is not enabled on this subnet".to_string(),
+                        ));
+                        Some((err, msg.take_cycles()))
+                    }
+                }
+            }
+
+            Ok(Ic00Method::UploadCanisterSnapshotData) => match self.config.canister_snapshots {
+                FlagStatus::Enabled => {
+                    let res = UploadCanisterSnapshotDataArgs::decode(payload).and_then(|args| {
+                        self.upload_canister_snapshot_data(
+                            *msg.sender(),
+                            &mut state,
+                            args,
+                            round_limits,
+                            registry_settings.subnet_size,
+                        )
+                    });
+                    Some((res, msg.take_cycles()))
+                }
+                FlagStatus::Disabled => {
+                    let err = Err(UserError::new(
+                        ErrorCode::CanisterContractViolation,
+                        "This API is not enabled on this subnet".to_string(),
+                    ));
+                    Some((err, msg.take_cycles()))
+                }
+            },
+
             Err(ParseError::VariantNotFound) => {
                 let res = Err(UserError::new(
                     ErrorCode::CanisterMethodNotFound,
@@ -1613,10 +1892,13 @@ impl ExecutionEnvironment {
         state: &mut ReplicatedState,
         subnet_size: usize,
     ) -> Result<Vec<u8>, UserError> {
+        let snapshots_memory_usage = state
+            .canister_snapshots
+            .memory_taken_by_canister(&canister_id);
         let canister = get_canister_mut(canister_id, state)?;
 
         self.canister_manager
-            .get_canister_status(sender, canister, subnet_size)
+            .get_canister_status(sender, canister, snapshots_memory_usage, subnet_size)
             .map(|status| status.encode())
             .map_err(|err| err.into())
     }
@@ -1749,6 +2031,60 @@ impl ExecutionEnvironment {
             .map_err(|err| err.into())
     }
 
+    fn read_canister_snapshot_metadata(
+        &self,
+        sender: PrincipalId,
+        state: &ReplicatedState,
+        args: ReadCanisterSnapshotMetadataArgs,
+    ) -> Result<Vec<u8>, UserError> {
+        self.canister_manager
+            .read_canister_snapshot_metadata(sender, &args, state)
+            .map(|reply| reply.encode())
+            .map_err(|err| err.into())
+    }
+
+    fn read_canister_snapshot_data(
+        &self,
+        sender: PrincipalId,
+        state: &ReplicatedState,
+        args: ReadCanisterSnapshotDataArgs,
+    ) -> Result<Vec<u8>, UserError> {
+        self.canister_manager
+            .read_canister_snapshot_data(sender, &args, state)
+            .map(|reply| reply.encode())
+            .map_err(|err| err.into())
+    }
+
+    fn upload_canister_snapshot_metadata(
+        &self,
+        sender: PrincipalId,
+        state: &mut ReplicatedState,
+        args: UploadCanisterSnapshotMetadataArgs,
+        round_limits: &mut RoundLimits,
+    ) -> Result<Vec<u8>, UserError> {
+        self.canister_manager
+            .upload_canister_snapshot_metadata(sender, &args, state, round_limits)
+            .map(|reply| reply.encode())
+            .map_err(|err| err.into())
+    }
+
+    fn upload_canister_snapshot_data(
+        &self,
+        sender: PrincipalId,
+        state: &mut ReplicatedState,
+        args: UploadCanisterSnapshotDataArgs,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+    ) -> Result<Vec<u8>, UserError> {
+        self.canister_manager
+            .upload_canister_snapshot_data(sender, &args, state, round_limits, subnet_size)
+            .map(|heap_delta_increase| {
+                state.metadata.heap_delta_estimate += heap_delta_increase;
+                EmptyBlob.encode()
+            })
+            .map_err(|err| err.into())
+    }
+
     fn node_metrics_history(
         &self,
         state: &ReplicatedState,
@@ -2053,6 +2389,7 @@ impl ExecutionEnvironment {
                     originator_reply_callback: req.sender_reply_callback,
                     refund,
                     response_payload: payload,
+                    deadline: req.deadline,
                 };
 
                 state.push_subnet_output_response(response.into());
@@ -2130,6 +2467,7 @@ impl ExecutionEnvironment {
                     reply_callback,
                     call_id,
                     cycles,
+                    deadline,
                 } => {
                     // Rejecting a stop_canister request from a canister.
                     let subnet_id_as_canister_id = CanisterId::from(self.own_subnet_id);
@@ -2144,6 +2482,7 @@ impl ExecutionEnvironment {
                             RejectCode::CanisterError,
                             format!("Canister {}'s stop request cancelled", canister_id),
                         )),
+                        deadline,
                     };
                     state.push_subnet_output_response(response.into());
                 }
@@ -2189,7 +2528,7 @@ impl ExecutionEnvironment {
 
     fn get_ecdsa_public_key(
         &self,
-        subnet_public_key: &MasterEcdsaPublicKey,
+        subnet_public_key: &MasterPublicKey,
         principal_id: PrincipalId,
         derivation_path: Vec<Vec<u8>>,
         // TODO EXC-1060: get the right public key.
@@ -2207,13 +2546,123 @@ impl ExecutionEnvironment {
             })
     }
 
+    fn get_schnorr_public_key(
+        &self,
+        subnet_public_key: &MasterPublicKey,
+        principal_id: PrincipalId,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<SchnorrPublicKeyResponse, UserError> {
+        let path = ExtendedDerivationPath {
+            caller: principal_id,
+            derivation_path,
+        };
+        derive_threshold_schnorr_public_key(subnet_public_key, &path)
+            .map_err(|err| UserError::new(ErrorCode::CanisterRejectedMessage, format!("{}", err)))
+            .map(|res| SchnorrPublicKeyResponse {
+                public_key: res.public_key,
+                chain_code: res.chain_key,
+            })
+    }
+
+    fn get_vetkd_public_key(
+        &self,
+        subnet_public_key: &MasterPublicKey,
+        principal_id: PrincipalId,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<VetKdPublicKeyResult, UserError> {
+        let path = ExtendedDerivationPath {
+            caller: principal_id,
+            derivation_path,
+        };
+        derive_vetkd_public_key(subnet_public_key, &path)
+            .map_err(|err| UserError::new(ErrorCode::CanisterRejectedMessage, format!("{}", err)))
+            .map(|public_key| VetKdPublicKeyResult { public_key })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn vetkd_derive_encrypted_key(
+        &self,
+        request: Request,
+        derivation_path: Vec<Vec<u8>>,
+        derivation_id: Vec<u8>,
+        encryption_public_key: Vec<u8>,
+        key_id: VetKdKeyId,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
+        max_queue_size: u32,
+        state: &mut ReplicatedState,
+        rng: &mut dyn RngCore,
+        subnet_size: usize,
+    ) -> Result<(), UserError> {
+        // Reject a malformed encryption public key right away, the nodes
+        // could not encrypt their key shares under it.
+        validate_encryption_public_key(&encryption_public_key).map_err(|err| {
+            UserError::new(ErrorCode::CanisterRejectedMessage, format!("{}", err))
+        })?;
+
+        self.submit_threshold_request(
+            request,
+            &MasterPublicKeyId::VetKd(key_id.clone()),
+            chain_key_subnet_public_keys,
+            max_queue_size,
+            state,
+            rng,
+            subnet_size,
+            |request, pseudo_random_id, batch_time| {
+                SubnetCallContext::VetKdDeriveEncryptedKey(VetKdDeriveEncryptedKeyContext {
+                    request,
+                    key_id,
+                    derivation_path,
+                    derivation_id,
+                    encryption_public_key,
+                    pseudo_random_id,
+                    batch_time,
+                })
+            },
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn sign_with_schnorr(
+        &self,
+        request: Request,
+        message: Vec<u8>,
+        derivation_path: Vec<Vec<u8>>,
+        key_id: SchnorrKeyId,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
+        max_queue_size: u32,
+        state: &mut ReplicatedState,
+        rng: &mut dyn RngCore,
+        subnet_size: usize,
+    ) -> Result<(), UserError> {
+        self.submit_threshold_request(
+            request,
+            &MasterPublicKeyId::Schnorr(key_id.clone()),
+            chain_key_subnet_public_keys,
+            max_queue_size,
+            state,
+            rng,
+            subnet_size,
+            |request, pseudo_random_id, batch_time| {
+                SubnetCallContext::SignWithSchnorr(SignWithSchnorrContext {
+                    request,
+                    key_id,
+                    message,
+                    derivation_path,
+                    pseudo_random_id,
+                    batch_time,
+                })
+            },
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn sign_with_ecdsa(
         &self,
-        mut request: Request,
+        request: Request,
         message_hash: [u8; 32],
         derivation_path: Vec<Vec<u8>>,
         key_id: EcdsaKeyId,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
         max_queue_size: u32,
         state: &mut ReplicatedState,
         rng: &mut dyn RngCore,
@@ -2221,32 +2670,90 @@ impl ExecutionEnvironment {
     ) -> Result<(), UserError> {
         // We already ensured message_hash is 32 byte statically, so there is
         // no need to check length here.
+        self.submit_threshold_request(
+            request,
+            &MasterPublicKeyId::Ecdsa(key_id.clone()),
+            chain_key_subnet_public_keys,
+            max_queue_size,
+            state,
+            rng,
+            subnet_size,
+            |request, pseudo_random_id, batch_time| {
+                SubnetCallContext::SignWithEcdsa(SignWithEcdsaContext {
+                    request,
+                    key_id,
+                    message_hash,
+                    derivation_path,
+                    pseudo_random_id,
+                    batch_time,
+                    matched_quadruple: None,
+                    nonce: None,
+                })
+            },
+        )
+    }
+
+    /// Submits a request for the threshold key `key_id` to consensus.
+    ///
+    /// Checks that the subnet holds the key and that the queue of requests
+    /// for the key's type isn't full. If the request isn't from the NNS, the
+    /// fee is deducted from the payment of the request and recorded in the
+    /// subnet metrics once the request has been accepted; consensus will
+    /// return any remaining cycles. The context pushed to the subnet call
+    /// context manager is built by `make_context` from the charged request,
+    /// a fresh pseudo-random id and the batch time.
+    #[allow(clippy::too_many_arguments)]
+    fn submit_threshold_request(
+        &self,
+        mut request: Request,
+        key_id: &MasterPublicKeyId,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
+        max_queue_size: u32,
+        state: &mut ReplicatedState,
+        rng: &mut dyn RngCore,
+        subnet_size: usize,
+        make_context: impl FnOnce(Request, [u8; 32], Time) -> SubnetCallContext,
+    ) -> Result<(), UserError> {
+        get_master_public_key(chain_key_subnet_public_keys, self.own_subnet_id, key_id)?;
+
+        let contexts = &state.metadata.subnet_call_context_manager;
+        let (fee, queue_len, queue_name) = match key_id {
+            MasterPublicKeyId::Ecdsa(_) => (
+                self.cycles_account_manager.ecdsa_signature_fee(subnet_size),
+                contexts.sign_with_ecdsa_contexts.len(),
+                "ECDSA signature queue",
+            ),
+            MasterPublicKeyId::Schnorr(_) => (
+                self.cycles_account_manager
+                    .schnorr_signature_fee(subnet_size),
+                contexts.sign_with_schnorr_contexts.len(),
+                "Schnorr signature queue",
+            ),
+            MasterPublicKeyId::VetKd(_) => (
+                self.cycles_account_manager.vetkd_fee(subnet_size),
+                contexts.vetkd_derive_encrypted_key_contexts.len(),
+                "vetKD queue",
+            ),
+        };
 
-        // If the request isn't from the NNS, then we need to charge for it.
-        // Consensus will return any remaining cycles.
+        let mut charged_fee = None;
         let source_subnet = state
             .metadata
             .network_topology
             .routing_table
             .route(request.sender.get());
         if source_subnet != Some(state.metadata.network_topology.nns_subnet_id) {
-            let signature_fee = self.cycles_account_manager.ecdsa_signature_fee(subnet_size);
-            if request.payment < signature_fee {
+            if request.payment < fee {
                 return Err(UserError::new(
                     ErrorCode::CanisterRejectedMessage,
                     format!(
-                        "sign_with_ecdsa request sent with {} cycles, but {} cycles are required.",
-                        request.payment, signature_fee
+                        "{} request sent with {} cycles, but {} cycles are required.",
+                        request.method_name, request.payment, fee
                     ),
                 ));
             } else {
-                request.payment -= signature_fee;
-                let ecdsa_fee = NominalCycles::from(signature_fee);
-                state.metadata.subnet_metrics.consumed_cycles_ecdsa_outcalls += ecdsa_fee;
-                state
-                    .metadata
-                    .subnet_metrics
-                    .observe_consumed_cycles_with_use_case(CyclesUseCase::ECDSAOutcalls, ecdsa_fee);
+                request.payment -= fee;
+                charged_fee = Some(NominalCycles::from(fee));
             }
         }
 
@@ -2255,38 +2762,46 @@ impl ExecutionEnvironment {
 
         info!(
             self.log,
-            "Assigned the pseudo_random_id {:?} to the new sign_with_ECDSA request from {:?}",
+            "Assigned the pseudo_random_id {:?} to the new {} request from {:?}",
             pseudo_random_id,
+            request.method_name,
             request.sender()
         );
 
-        if state
-            .metadata
-            .subnet_call_context_manager
-            .sign_with_ecdsa_contexts
-            .len()
-            >= max_queue_size as usize
-        {
+        if queue_len >= max_queue_size as usize {
             return Err(UserError::new(
                 ErrorCode::CanisterRejectedMessage,
-                "sign_with_ecdsa request could not be handled, the ECDSA signature queue is full."
-                    .to_string(),
+                format!(
+                    "{} request could not be handled, the {} is full.",
+                    request.method_name, queue_name
+                ),
             ));
         }
 
+        if let Some(fee) = charged_fee {
+            let subnet_metrics = &mut state.metadata.subnet_metrics;
+            let use_case = match key_id {
+                MasterPublicKeyId::Ecdsa(_) => {
+                    subnet_metrics.consumed_cycles_ecdsa_outcalls += fee;
+                    CyclesUseCase::ECDSAOutcalls
+                }
+                MasterPublicKeyId::Schnorr(_) => {
+                    subnet_metrics.consumed_cycles_schnorr_outcalls += fee;
+                    CyclesUseCase::SchnorrOutcalls
+                }
+                MasterPublicKeyId::VetKd(_) => {
+                    subnet_metrics.consumed_cycles_vetkd += fee;
+                    CyclesUseCase::VetKd
+                }
+            };
+            subnet_metrics.observe_consumed_cycles_with_use_case(use_case, fee);
+        }
+
+        let batch_time = state.metadata.batch_time;
         state
             .metadata
             .subnet_call_context_manager
-            .push_context(SubnetCallContext::SignWithEcdsa(SignWithEcdsaContext {
-                request,
-                key_id,
-                message_hash,
-                derivation_path,
-                pseudo_random_id,
-                batch_time: state.metadata.batch_time,
-                matched_quadruple: None,
-                nonce: None,
-            }));
+            .push_context(make_context(request, pseudo_random_id, batch_time));
         Ok(())
     }
 
@@ -2895,6 +3410,7 @@ impl ExecutionEnvironment {
                 sender,
                 reply_callback,
                 cycles,
+                deadline,
                 ..
             } => {
                 // Responding to stop_canister request from a canister.
@@ -2912,6 +3428,7 @@ impl ExecutionEnvironment {
                     originator_reply_callback: *reply_callback,
                     refund: *cycles,
                     response_payload,
+                    deadline: *deadline,
                 };
                 state.push_subnet_output_response(response.into());
             }
@@ -3326,16 +3843,26 @@ pub fn execute_canister(
     )
 }
 
-fn get_master_ecdsa_public_key<'a>(
-    ecdsa_subnet_public_keys: &'a BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+fn get_master_public_key<'a>(
+    chain_key_subnet_public_keys: &'a BTreeMap<MasterPublicKeyId, MasterPublicKey>,
     subnet_id: SubnetId,
-    key_id: &EcdsaKeyId,
-) -> Result<&'a MasterEcdsaPublicKey, UserError> {
-    match ecdsa_subnet_public_keys.get(key_id) {
-        None => Err(UserError::new(
-            ErrorCode::CanisterRejectedMessage,
-            format!("Subnet {} does not hold ECDSA key {}.", subnet_id, key_id),
-        )),
+    key_id: &MasterPublicKeyId,
+) -> Result<&'a MasterPublicKey, UserError> {
+    match chain_key_subnet_public_keys.get(key_id) {
+        None => {
+            let message = match key_id {
+                MasterPublicKeyId::Ecdsa(key_id) => {
+                    format!("Subnet {} does not hold ECDSA key {}.", subnet_id, key_id)
+                }
+                MasterPublicKeyId::Schnorr(key_id) => {
+                    format!("Subnet {} does not hold Schnorr key {}.", subnet_id, key_id)
+                }
+                MasterPublicKeyId::VetKd(key_id) => {
+                    format!("Subnet {} does not hold vetKD key {}.", subnet_id, key_id)
+                }
+            };
+            Err(UserError::new(ErrorCode::CanisterRejectedMessage, message))
+        }
         Some(master_key) => Ok(master_key),
     }
 }
@@ -13,7 +13,8 @@ use ic_management_canister_types::{
     CanisterHttpRequestArgs, CanisterIdRecord, CanisterStatusResultV2, CanisterStatusType,
     DerivationPath, EcdsaCurve, EcdsaKeyId, EmptyBlob, FetchCanisterLogsRequest, HttpMethod,
     LogVisibility, Method, Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs,
-    ProvisionalTopUpCanisterArgs, TransformContext, TransformFunc, IC_00,
+    ProvisionalTopUpCanisterArgs, SchnorrAlgorithm, SchnorrKeyId, TransformContext, TransformFunc,
+    VetKdCurve, VetKdKeyId, IC_00,
 };
 use ic_registry_routing_table::canister_id_into_u64;
 use ic_registry_routing_table::CanisterIdRange;
@@ -36,6 +37,7 @@ use ic_types::{
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{
         CallbackId, Payload, RejectContext, RequestOrResponse, Response, MAX_RESPONSE_COUNT_BYTES,
+        NO_DEADLINE,
     },
     CanisterId, Cycles, PrincipalId, RegistryVersion,
 };
@@ -1471,7 +1473,8 @@ fn setup_initial_dkg_sender_not_on_nns() {
                     ic00::Method::SetupInitialDKG,
                     other_canister,
                 )
-            ))
+            )),
+            deadline: NO_DEADLINE,
         }
         .into()
     );
@@ -2421,6 +2424,610 @@ fn ecdsa_signature_queue_fills_up() {
     );
 }
 
+fn make_schnorr_key(name: &str) -> SchnorrKeyId {
+    SchnorrKeyId {
+        algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn schnorr_signature_fee_charged() {
+    let fee = 1_000_000;
+    let payment = 2_000_000;
+    let schnorr_key = make_schnorr_key("bip340");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_schnorr_signature_fee(fee)
+        .with_threshold_schnorr(FlagStatus::Enabled)
+        .with_schnorr_key(schnorr_key.clone())
+        .build();
+
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: schnorr_key.clone(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(payment),
+        )
+        .build();
+
+    let (_, ingress_status) = test.ingress_raw(canister_id, "update", run);
+    assert_eq!(
+        ingress_status,
+        IngressStatus::Known {
+            receiver: canister_id.get(),
+            user_id: test.user_id(),
+            time: test.time(),
+            state: IngressState::Processing,
+        }
+    );
+    let (_, context) = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .sign_with_schnorr_contexts
+        .iter()
+        .next()
+        .unwrap();
+    assert_eq!(context.request.payment.get(), payment - fee);
+    assert_eq!(context.key_id, schnorr_key);
+    assert_eq!(context.message, vec![1; 16]);
+
+    assert_eq!(
+        test.state()
+            .metadata
+            .subnet_metrics
+            .consumed_cycles_schnorr_outcalls,
+        NominalCycles::from(fee)
+    );
+    assert_eq!(
+        test.state()
+            .metadata
+            .subnet_metrics
+            .consumed_cycles_ecdsa_outcalls,
+        NominalCycles::from(0)
+    );
+    assert_eq!(
+        *test
+            .state()
+            .metadata
+            .subnet_metrics
+            .get_consumed_cycles_by_use_case()
+            .get(&CyclesUseCase::SchnorrOutcalls)
+            .unwrap(),
+        NominalCycles::from(fee)
+    );
+}
+
+#[test]
+fn schnorr_signature_rejected_when_disabled() {
+    let schnorr_key = make_schnorr_key("bip340");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_threshold_schnorr(FlagStatus::Disabled)
+        .with_schnorr_key(schnorr_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: schnorr_key,
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(1_000_000_000u128),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        result,
+        WasmResult::Reject("sign_with_schnorr API is not enabled on this subnet".to_string())
+    );
+    assert!(test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .sign_with_schnorr_contexts
+        .is_empty());
+}
+
+#[test]
+fn schnorr_signature_rejected_without_fee() {
+    let fee = 2_000_000;
+    let schnorr_key = make_schnorr_key("bip340");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_schnorr_signature_fee(fee)
+        .with_threshold_schnorr(FlagStatus::Enabled)
+        .with_schnorr_key(schnorr_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: schnorr_key,
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(fee - 1),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            "sign_with_schnorr request sent with 1_999_999 cycles, but 2_000_000 cycles are required."
+                .into()
+        ),
+        result
+    );
+}
+
+#[test]
+fn schnorr_signature_with_unknown_key_rejected() {
+    let correct_key = make_schnorr_key("correct_key");
+    let wrong_key = make_schnorr_key("wrong_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_threshold_schnorr(FlagStatus::Enabled)
+        .with_schnorr_key(correct_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: wrong_key.clone(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(1_000_000_000u128),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            format!("Unable to route management canister request sign_with_schnorr: SchnorrKeyError(\"Requested Schnorr key: {}, existing keys with signing enabled: [{}]\")", wrong_key, correct_key
+        )),
+        result
+    );
+}
+
+#[test]
+fn schnorr_signature_queue_fills_up() {
+    let fee = 1_000_000;
+    let payment = 2_000_000u128;
+    let schnorr_key = make_schnorr_key("bip340");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_schnorr_signature_fee(fee)
+        .with_threshold_schnorr(FlagStatus::Enabled)
+        .with_schnorr_key(schnorr_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::SignWithSchnorrArgs {
+        message: vec![1; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: schnorr_key,
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::SignWithSchnorr,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(payment),
+        )
+        .build();
+
+    for _i in 0..1_004 {
+        test.ingress_raw(canister_id, "update", run.clone());
+    }
+    let result = test.ingress(canister_id, "update", run).unwrap();
+
+    assert_eq!(
+        result,
+        WasmResult::Reject(
+            "sign_with_schnorr request could not be handled, the Schnorr signature queue is full."
+                .to_string()
+        )
+    );
+
+    // Only the fees of the accepted requests are accounted for.
+    let accepted = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .sign_with_schnorr_contexts
+        .len() as u128;
+    assert_eq!(
+        test.state()
+            .metadata
+            .subnet_metrics
+            .consumed_cycles_schnorr_outcalls,
+        NominalCycles::from(accepted * fee)
+    );
+}
+
+fn make_vetkd_key(name: &str) -> VetKdKeyId {
+    VetKdKeyId {
+        curve: VetKdCurve::Bls12_381_G2,
+        name: name.to_string(),
+    }
+}
+
+// The compressed encoding of the BLS12-381 G1 generator.
+fn make_encryption_public_key() -> Vec<u8> {
+    hex::decode("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb").unwrap()
+}
+
+#[test]
+fn vetkd_public_key_returns_derived_key() {
+    let vetkd_key = make_vetkd_key("some_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd(FlagStatus::Enabled)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdPublicKeyArgs {
+        canister_id: None,
+        derivation_path: DerivationPath::new(vec![serde_bytes::ByteBuf::from(vec![1; 8])]),
+        key_id: vetkd_key,
+    };
+    let run = wasm()
+        .call_simple(
+            ic00::IC_00,
+            Method::VetKdPublicKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    let bytes = match result {
+        WasmResult::Reply(bytes) => bytes,
+        WasmResult::Reject(msg) => panic!("Unexpected reject: {}", msg),
+    };
+    let response = ic00::VetKdPublicKeyResult::decode(&bytes).unwrap();
+    assert_eq!(response.public_key.len(), 96);
+}
+
+#[test]
+fn vetkd_derive_encrypted_key_fee_charged() {
+    let fee = 1_000_000;
+    let payment = 2_000_000;
+    let vetkd_key = make_vetkd_key("some_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd_fee(fee)
+        .with_vetkd(FlagStatus::Enabled)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdDeriveEncryptedKeyArgs {
+        derivation_id: vec![2; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: vetkd_key.clone(),
+        encryption_public_key: make_encryption_public_key(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdDeriveEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(payment),
+        )
+        .build();
+
+    let (_, ingress_status) = test.ingress_raw(canister_id, "update", run);
+    assert_eq!(
+        ingress_status,
+        IngressStatus::Known {
+            receiver: canister_id.get(),
+            user_id: test.user_id(),
+            time: test.time(),
+            state: IngressState::Processing,
+        }
+    );
+    let (_, context) = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_derive_encrypted_key_contexts
+        .iter()
+        .next()
+        .unwrap();
+    assert_eq!(context.request.payment.get(), payment - fee);
+    assert_eq!(context.key_id, vetkd_key);
+    assert_eq!(context.derivation_id, vec![2; 16]);
+    assert_eq!(context.encryption_public_key, make_encryption_public_key());
+
+    assert_eq!(
+        test.state().metadata.subnet_metrics.consumed_cycles_vetkd,
+        NominalCycles::from(fee)
+    );
+    assert_eq!(
+        test.state()
+            .metadata
+            .subnet_metrics
+            .consumed_cycles_ecdsa_outcalls,
+        NominalCycles::from(0)
+    );
+    assert_eq!(
+        *test
+            .state()
+            .metadata
+            .subnet_metrics
+            .get_consumed_cycles_by_use_case()
+            .get(&CyclesUseCase::VetKd)
+            .unwrap(),
+        NominalCycles::from(fee)
+    );
+}
+
+#[test]
+fn vetkd_derive_encrypted_key_rejected_when_disabled() {
+    let vetkd_key = make_vetkd_key("some_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd(FlagStatus::Disabled)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdDeriveEncryptedKeyArgs {
+        derivation_id: vec![2; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: vetkd_key,
+        encryption_public_key: make_encryption_public_key(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdDeriveEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(1_000_000_000u128),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        result,
+        WasmResult::Reject(
+            "vetkd_derive_encrypted_key API is not enabled on this subnet".to_string()
+        )
+    );
+    assert!(test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_derive_encrypted_key_contexts
+        .is_empty());
+}
+
+#[test]
+fn vetkd_derive_encrypted_key_rejects_invalid_encryption_public_key() {
+    let vetkd_key = make_vetkd_key("some_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd(FlagStatus::Enabled)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdDeriveEncryptedKeyArgs {
+        derivation_id: vec![2; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: vetkd_key,
+        encryption_public_key: vec![3; 48],
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdDeriveEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(1_000_000_000u128),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        result,
+        WasmResult::Reject("vetKD encryption public key is not a valid G1 point".to_string())
+    );
+    assert!(test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_derive_encrypted_key_contexts
+        .is_empty());
+}
+
+#[test]
+fn vetkd_derive_encrypted_key_rejected_without_fee() {
+    let fee = 2_000_000;
+    let vetkd_key = make_vetkd_key("some_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd_fee(fee)
+        .with_vetkd(FlagStatus::Enabled)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdDeriveEncryptedKeyArgs {
+        derivation_id: vec![2; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: vetkd_key,
+        encryption_public_key: make_encryption_public_key(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdDeriveEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(fee - 1),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            "vetkd_derive_encrypted_key request sent with 1_999_999 cycles, but 2_000_000 cycles are required."
+                .into()
+        ),
+        result
+    );
+}
+
+#[test]
+fn vetkd_derive_encrypted_key_with_unknown_key_rejected() {
+    let correct_key = make_vetkd_key("correct_key");
+    let wrong_key = make_vetkd_key("wrong_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd(FlagStatus::Enabled)
+        .with_vetkd_key(correct_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdDeriveEncryptedKeyArgs {
+        derivation_id: vec![2; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: wrong_key.clone(),
+        encryption_public_key: make_encryption_public_key(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdDeriveEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(1_000_000_000u128),
+        )
+        .build();
+
+    let result = test.ingress(canister_id, "update", run).unwrap();
+    assert_eq!(
+        WasmResult::Reject(
+            format!("Unable to route management canister request vetkd_derive_encrypted_key: VetKdKeyError(\"Requested vetKD key: {}, existing keys with key derivation enabled: [{}]\")", wrong_key, correct_key
+        )),
+        result
+    );
+}
+
+#[test]
+fn vetkd_derive_encrypted_key_queue_fills_up() {
+    let fee = 1_000_000;
+    let payment = 2_000_000u128;
+    let vetkd_key = make_vetkd_key("some_key");
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_type(SubnetType::System)
+        .with_own_subnet_id(subnet_test_id(1))
+        .with_nns_subnet_id(subnet_test_id(2))
+        .with_vetkd_fee(fee)
+        .with_vetkd(FlagStatus::Enabled)
+        .with_vetkd_key(vetkd_key.clone())
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let args = ic00::VetKdDeriveEncryptedKeyArgs {
+        derivation_id: vec![2; 16],
+        derivation_path: DerivationPath::new(vec![]),
+        key_id: vetkd_key,
+        encryption_public_key: make_encryption_public_key(),
+    };
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            Method::VetKdDeriveEncryptedKey,
+            call_args()
+                .other_side(args.encode())
+                .on_reject(wasm().reject_message().reject()),
+            Cycles::from(payment),
+        )
+        .build();
+
+    for _i in 0..1_004 {
+        test.ingress_raw(canister_id, "update", run.clone());
+    }
+    let result = test.ingress(canister_id, "update", run).unwrap();
+
+    assert_eq!(
+        result,
+        WasmResult::Reject(
+            "vetkd_derive_encrypted_key request could not be handled, the vetKD queue is full."
+                .to_string()
+        )
+    );
+
+    // Only the fees of the accepted requests are accounted for.
+    let accepted = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .vetkd_derive_encrypted_key_contexts
+        .len() as u128;
+    assert_eq!(
+        test.state().metadata.subnet_metrics.consumed_cycles_vetkd,
+        NominalCycles::from(accepted * fee)
+    );
+}
+
 #[test]
 fn canister_output_queue_does_not_overflow_when_calling_ic00() {
     let own_subnet = subnet_test_id(1);
@@ -3285,10 +3892,7 @@ fn test_fetch_canister_logs_should_accept_ingress_message_disabled() {
     let result = test.should_accept_ingress_message(
         test.state().metadata.own_subnet_id.into(),
         Method::FetchCanisterLogs,
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect error because the API is disabled.
@@ -3318,10 +3922,7 @@ fn test_fetch_canister_logs_should_accept_ingress_message_enabled() {
     let result = test.should_accept_ingress_message(
         test.state().metadata.own_subnet_id.into(),
         Method::FetchCanisterLogs,
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect error since `should_accept_ingress_message` is only called in replicated mode which is not supported.
@@ -26,9 +26,13 @@ use ic_test_utilities_metrics::{fetch_histogram_stats, HistogramStats};
 use ic_types::{
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::CanisterTask,
-    messages::MAX_INTER_CANISTER_PAYLOAD_IN_BYTES,
+    messages::{
+        RequestOrResponse, MAX_CALL_TIMEOUT_SECONDS, MAX_INTER_CANISTER_PAYLOAD_IN_BYTES,
+        NO_DEADLINE,
+    },
     methods::WasmMethod,
-    CanisterId, ComputeAllocation, Cycles, NumBytes, NumInstructions, MAX_STABLE_MEMORY_IN_BYTES,
+    CanisterId, CoarseTime, ComputeAllocation, Cycles, NumBytes, NumInstructions,
+    MAX_STABLE_MEMORY_IN_BYTES,
 };
 use ic_universal_canister::{call_args, wasm, UNIVERSAL_CANISTER_WASM};
 use proptest::prelude::*;
@@ -1995,6 +1999,137 @@ fn ic0_call_perform_has_no_effect_on_trap() {
     assert_eq!(0, test.xnet_messages().len());
 }
 
+fn best_effort_call_wat(timeout_seconds: u32) -> String {
+    format!(
+        r#"
+        (module
+            (import "ic0" "call_new"
+                (func $ic0_call_new
+                    (param i32 i32)
+                    (param $method_name_src i32)    (param $method_name_len i32)
+                    (param $reply_fun i32)          (param $reply_env i32)
+                    (param $reject_fun i32)         (param $reject_env i32)
+                )
+            )
+            (import "ic0" "call_with_best_effort_response"
+                (func $ic0_call_with_best_effort_response (param $timeout_seconds i32))
+            )
+            (import "ic0" "call_perform" (func $ic0_call_perform (result i32)))
+            (func (export "canister_update test")
+                (call $ic0_call_new
+                    (i32.const 100) (i32.const 10)  ;; callee canister id = 777
+                    (i32.const 0) (i32.const 18)    ;; refers to "some_remote_method" on the heap
+                    (i32.const 11) (i32.const 22)   ;; fictive on_reply closure
+                    (i32.const 33) (i32.const 44)   ;; fictive on_reject closure
+                )
+                (call $ic0_call_with_best_effort_response (i32.const {timeout_seconds}))
+                (drop (call $ic0_call_perform))
+            )
+            (memory 1)
+            (data (i32.const 0) "some_remote_method XYZ")
+            (data (i32.const 100) "\09\03\00\00\00\00\00\00\ff\01")
+        )"#
+    )
+}
+
+#[test]
+fn ic0_call_with_best_effort_response_sets_request_deadline() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.canister_from_wat(best_effort_call_wat(10)).unwrap();
+    test.advance_time(Duration::from_millis(5_500));
+    test.ingress_raw(canister_id, "test", vec![]);
+    assert_eq!(1, test.xnet_messages().len());
+    match &test.xnet_messages()[0] {
+        RequestOrResponse::Request(request) => {
+            assert_eq!(
+                CoarseTime::floor(test.time()).saturating_add_secs(10),
+                request.deadline
+            );
+            let callback = test
+                .canister_state(canister_id)
+                .system_state
+                .call_context_manager()
+                .unwrap()
+                .callback(request.sender_reply_callback)
+                .unwrap();
+            assert_eq!(request.deadline, callback.deadline);
+        }
+        msg => panic!("Expected a request, got {:?}", msg),
+    }
+}
+
+#[test]
+fn ic0_call_with_best_effort_response_caps_timeout() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test
+        .canister_from_wat(best_effort_call_wat(1_000_000))
+        .unwrap();
+    test.ingress_raw(canister_id, "test", vec![]);
+    assert_eq!(1, test.xnet_messages().len());
+    match &test.xnet_messages()[0] {
+        RequestOrResponse::Request(request) => assert_eq!(
+            CoarseTime::floor(test.time()).saturating_add_secs(MAX_CALL_TIMEOUT_SECONDS),
+            request.deadline
+        ),
+        msg => panic!("Expected a request, got {:?}", msg),
+    }
+}
+
+#[test]
+fn ic0_call_without_best_effort_response_has_no_deadline() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat = r#"
+        (module
+            (import "ic0" "call_new"
+                (func $ic0_call_new
+                    (param i32 i32)
+                    (param $method_name_src i32)    (param $method_name_len i32)
+                    (param $reply_fun i32)          (param $reply_env i32)
+                    (param $reject_fun i32)         (param $reject_env i32)
+                )
+            )
+            (import "ic0" "call_perform" (func $ic0_call_perform (result i32)))
+            (func (export "canister_update test")
+                (call $ic0_call_new
+                    (i32.const 100) (i32.const 10)  ;; callee canister id = 777
+                    (i32.const 0) (i32.const 18)    ;; refers to "some_remote_method" on the heap
+                    (i32.const 11) (i32.const 22)   ;; fictive on_reply closure
+                    (i32.const 33) (i32.const 44)   ;; fictive on_reject closure
+                )
+                (drop (call $ic0_call_perform))
+            )
+            (memory 1)
+            (data (i32.const 0) "some_remote_method XYZ")
+            (data (i32.const 100) "\09\03\00\00\00\00\00\00\ff\01")
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    test.ingress_raw(canister_id, "test", vec![]);
+    assert_eq!(1, test.xnet_messages().len());
+    match &test.xnet_messages()[0] {
+        RequestOrResponse::Request(request) => assert_eq!(NO_DEADLINE, request.deadline),
+        msg => panic!("Expected a request, got {:?}", msg),
+    }
+}
+
+#[test]
+fn ic0_msg_deadline_is_zero_for_ingress_messages() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat = r#"
+        (module
+            (import "ic0" "msg_deadline" (func $msg_deadline (result i64)))
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (func (export "canister_update test")
+                (if (i64.ne (call $msg_deadline) (i64.const 0))
+                    (then (unreachable))
+                )
+                (call $msg_reply)
+            )
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "test", vec![]).unwrap();
+    assert_eq!(result, WasmResult::Reply(vec![]));
+}
+
 #[test]
 fn ic0_call_cycles_add_deducts_cycles() {
     let mut test = ExecutionTestBuilder::new()
@@ -6514,6 +6649,69 @@ fn stable_memory_grow_respects_reserved_cycles_limit() {
         .contains("due to its reserved cycles limit"));
 }
 
+const WASM_MEMORY_LIMIT_WAT: &str = r#"
+    (module
+        (import "ic0" "msg_reply" (func $msg_reply))
+        (func $grow
+            ;; Grow the Wasm memory from 1 page to 11 pages (704KiB).
+            (if (i32.eq (memory.grow (i32.const 10)) (i32.const -1))
+              (then (unreachable))
+            )
+            (call $msg_reply)
+        )
+        (memory $memory 1)
+        (export "canister_update update" (func $grow))
+        (export "canister_query query" (func $grow))
+    )"#;
+
+#[test]
+fn wasm_memory_grow_respects_wasm_memory_limit() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wasm = wat::parse_str(WASM_MEMORY_LIMIT_WAT).unwrap();
+    let canister_id = test.canister_from_binary(wasm).unwrap();
+
+    test.canister_state_mut(canister_id)
+        .system_state
+        .wasm_memory_limit = Some(NumBytes::new(10 * WASM_PAGE_SIZE_IN_BYTES as u64));
+
+    let err = test.ingress(canister_id, "update", vec![]).unwrap_err();
+
+    assert_eq!(err.code(), ErrorCode::CanisterWasmMemoryLimitExceeded);
+    assert!(err
+        .description()
+        .contains("Canister exceeded its current Wasm memory limit of 655360 bytes"));
+    assert!(err
+        .description()
+        .contains("The peak Wasm memory usage was 720896 bytes"));
+
+    // The failed execution must not change the Wasm memory size.
+    assert_eq!(
+        test.execution_state(canister_id).wasm_memory.size,
+        NumWasmPages::new(1)
+    );
+
+    // Raising the limit allows the canister to grow its memory again.
+    test.canister_state_mut(canister_id)
+        .system_state
+        .wasm_memory_limit = Some(NumBytes::new(11 * WASM_PAGE_SIZE_IN_BYTES as u64));
+    let result = test.ingress(canister_id, "update", vec![]);
+    assert_eq!(result, Ok(WasmResult::Reply(vec![])));
+}
+
+#[test]
+fn wasm_memory_limit_is_not_enforced_in_queries() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wasm = wat::parse_str(WASM_MEMORY_LIMIT_WAT).unwrap();
+    let canister_id = test.canister_from_binary(wasm).unwrap();
+
+    test.canister_state_mut(canister_id)
+        .system_state
+        .wasm_memory_limit = Some(NumBytes::new(WASM_PAGE_SIZE_IN_BYTES as u64));
+
+    let result = test.non_replicated_query(canister_id, "query", vec![]);
+    assert_eq!(result, Ok(WasmResult::Reply(vec![])));
+}
+
 #[test]
 fn stable_memory_grow_does_not_reserve_cycles_on_out_of_memory() {
     const CYCLES: Cycles = Cycles::new(200_000_000_000_000);
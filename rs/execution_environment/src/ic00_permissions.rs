@@ -103,6 +103,26 @@ impl Ic00MethodPermissions {
                 allow_remote_subnet_sender: true,
                 allow_only_nns_subnet_sender: true,
             },
+            Ic00Method::SchnorrPublicKey => Self {
+                method,
+                allow_remote_subnet_sender: true,
+                allow_only_nns_subnet_sender: false,
+            },
+            Ic00Method::SignWithSchnorr => Self {
+                method,
+                allow_remote_subnet_sender: true,
+                allow_only_nns_subnet_sender: false,
+            },
+            Ic00Method::VetKdPublicKey => Self {
+                method,
+                allow_remote_subnet_sender: true,
+                allow_only_nns_subnet_sender: false,
+            },
+            Ic00Method::VetKdDeriveEncryptedKey => Self {
+                method,
+                allow_remote_subnet_sender: true,
+                allow_only_nns_subnet_sender: false,
+            },
             Ic00Method::BitcoinGetBalance => Self {
                 method,
                 allow_remote_subnet_sender: true,
@@ -166,7 +186,11 @@ impl Ic00MethodPermissions {
             Ic00Method::TakeCanisterSnapshot
             | Ic00Method::LoadCanisterSnapshot
             | Ic00Method::ListCanisterSnapshots
-            | Ic00Method::DeleteCanisterSnapshot => Self {
+            | Ic00Method::DeleteCanisterSnapshot
+            | Ic00Method::ReadCanisterSnapshotMetadata
+            | Ic00Method::ReadCanisterSnapshotData
+            | Ic00Method::UploadCanisterSnapshotMetadata
+            | Ic00Method::UploadCanisterSnapshotData => Self {
                 method,
                 allow_remote_subnet_sender: true,
                 allow_only_nns_subnet_sender: false,
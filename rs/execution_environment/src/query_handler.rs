@@ -55,7 +55,7 @@ use tower::{util::BoxCloneService, Service};
 pub(crate) use self::query_scheduler::{QueryScheduler, QuerySchedulerFlag};
 use ic_management_canister_types::{
     BitcoinGetBalanceArgs, BitcoinGetUtxosArgs, FetchCanisterLogsRequest,
-    FetchCanisterLogsResponse, LogVisibility, Payload, QueryMethod,
+    FetchCanisterLogsResponse, Payload, QueryMethod,
 };
 use ic_replicated_state::NetworkTopology;
 
@@ -345,21 +345,31 @@ fn fetch_canister_logs(
         )
     })?;
 
-    match canister.log_visibility() {
-        LogVisibility::Public => Ok(()),
-        LogVisibility::Controllers if canister.controllers().contains(&sender) => Ok(()),
-        LogVisibility::Controllers => Err(UserError::new(
+    if !canister
+        .log_visibility()
+        .allows(&sender, canister.controllers())
+    {
+        return Err(UserError::new(
             ErrorCode::CanisterRejectedMessage,
             format!(
                 "Caller {} is not allowed to query ic00 method {}",
                 sender,
                 QueryMethod::FetchCanisterLogs
             ),
-        )),
-    }?;
+        ));
+    }
 
+    let records = &canister.system_state.canister_log_records;
+    let canister_log_records = match args.filter {
+        None => records.clone(),
+        Some(filter) => records
+            .iter()
+            .filter(|record| filter.matches(record))
+            .cloned()
+            .collect(),
+    };
     let response = FetchCanisterLogsResponse {
-        canister_log_records: canister.system_state.canister_log_records.clone(),
+        canister_log_records,
     };
     Ok(WasmResult::Reply(Encode!(&response).unwrap()))
 }
@@ -31,7 +31,7 @@ use ic_types::{
     ingress::WasmResult,
     messages::{
         CallContextId, CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response,
-        UserQuery,
+        UserQuery, NO_DEADLINE,
     },
     methods::WasmMethod,
     CanisterId, Cycles, NumInstructions, NumMessages, Time,
@@ -562,7 +562,7 @@ impl<'a> QueryContext<'a> {
         };
         let func_ref = match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => unreachable!("Unreachable in the QueryContext."),
             CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
                 FuncRef::QueryClosure(closure)
@@ -586,6 +586,7 @@ impl<'a> QueryContext<'a> {
                 payload.to_vec(),
                 incoming_cycles,
                 call_context_id,
+                response.deadline,
                 call_responded,
                 execution_parameters.execution_mode.clone(),
                 call_context.instructions_executed(),
@@ -596,6 +597,7 @@ impl<'a> QueryContext<'a> {
                 context,
                 incoming_cycles,
                 call_context_id,
+                response.deadline,
                 call_responded,
                 execution_parameters.execution_mode.clone(),
                 call_context.instructions_executed(),
@@ -693,7 +695,7 @@ impl<'a> QueryContext<'a> {
     ) -> (NumInstructions, Result<Option<WasmResult>, HypervisorError>) {
         let func_ref = match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => unreachable!("Unreachable in the QueryContext."),
             CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
                 FuncRef::QueryClosure(cleanup_closure)
@@ -764,6 +766,7 @@ impl<'a> QueryContext<'a> {
                 originator_reply_callback: request.sender_reply_callback,
                 response_payload: payload,
                 refund: Cycles::zero(),
+                deadline: request.deadline,
             })
         };
 
@@ -916,7 +919,7 @@ impl<'a> QueryContext<'a> {
             };
 
         match call_origin {
-            CallOrigin::CanisterUpdate(_, _)
+            CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::Ingress(_, _)
             | CallOrigin::SystemTask => {
                 error!(
@@ -951,6 +954,7 @@ impl<'a> QueryContext<'a> {
                         originator_reply_callback: callback_id,
                         refund: Cycles::zero(),
                         response_payload: payload,
+                        deadline: NO_DEADLINE,
                     };
                     QueryResponse::CanisterResponse(response)
                 };
@@ -1001,7 +1005,7 @@ impl<'a> QueryContext<'a> {
         );
         match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => {
                 unreachable!("Expected a query call context");
             }
@@ -1013,6 +1017,7 @@ impl<'a> QueryContext<'a> {
                     originator_reply_callback: callback_id,
                     refund: Cycles::zero(),
                     response_payload: Payload::Reject(RejectContext::from(error)),
+                    deadline: NO_DEADLINE,
                 };
                 QueryResponse::CanisterResponse(response)
             }
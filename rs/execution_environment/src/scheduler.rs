@@ -17,7 +17,9 @@ use ic_interfaces::execution_environment::{
     IngressHistoryWriter, Scheduler, SubnetAvailableMemory,
 };
 use ic_logger::{debug, error, fatal, info, new_logger, warn, ReplicaLogger};
-use ic_management_canister_types::{CanisterStatusType, EcdsaKeyId, Method as Ic00Method};
+use ic_management_canister_types::{
+    CanisterStatusType, EcdsaKeyId, MasterPublicKeyId, Method as Ic00Method,
+};
 use ic_metrics::MetricsRegistry;
 use ic_replicated_state::{
     canister_state::{
@@ -29,7 +31,7 @@ use ic_replicated_state::{
 use ic_system_api::InstructionLimits;
 use ic_types::{
     consensus::ecdsa::QuadrupleId,
-    crypto::canister_threshold_sig::MasterEcdsaPublicKey,
+    crypto::canister_threshold_sig::MasterPublicKey,
     ingress::{IngressState, IngressStatus},
     messages::{CanisterMessage, Ingress, MessageId, StopCanisterContext},
     AccumulatedPriority, CanisterId, ComputeAllocation, Cycles, ExecutionRound, LongExecutionMode,
@@ -478,7 +480,7 @@ impl SchedulerImpl {
         ongoing_long_install_code: bool,
         long_running_canister_ids: BTreeSet<CanisterId>,
         registry_settings: &RegistryExecutionSettings,
-        ecdsa_subnet_public_keys: &BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
     ) -> ReplicatedState {
         loop {
             let mut available_subnet_messages = false;
@@ -505,7 +507,7 @@ impl SchedulerImpl {
                     round_limits,
                     registry_settings,
                     measurement_scope,
-                    ecdsa_subnet_public_keys,
+                    chain_key_subnet_public_keys,
                 );
                 state = new_state;
 
@@ -537,7 +539,7 @@ impl SchedulerImpl {
         round_limits: &mut RoundLimits,
         registry_settings: &RegistryExecutionSettings,
         measurement_scope: &MeasurementScope,
-        ecdsa_subnet_public_keys: &BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
     ) -> (ReplicatedState, Option<NumInstructions>) {
         let instruction_limits = get_instructions_limits_for_subnet_message(
             self.deterministic_time_slicing,
@@ -551,7 +553,7 @@ impl SchedulerImpl {
             state,
             instruction_limits,
             csprng,
-            ecdsa_subnet_public_keys,
+            chain_key_subnet_public_keys,
             registry_settings,
             round_limits,
         );
@@ -644,7 +646,7 @@ impl SchedulerImpl {
         root_measurement_scope: &MeasurementScope<'a>,
         scheduler_round_limits: &mut SchedulerRoundLimits,
         registry_settings: &RegistryExecutionSettings,
-        ecdsa_subnet_public_keys: &BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        chain_key_subnet_public_keys: &BTreeMap<MasterPublicKeyId, MasterPublicKey>,
     ) -> (ReplicatedState, BTreeSet<CanisterId>) {
         let measurement_scope =
             MeasurementScope::nested(&self.metrics.round_inner, root_measurement_scope);
@@ -697,7 +699,7 @@ impl SchedulerImpl {
                         ongoing_long_install_code,
                         long_running_canister_ids,
                         registry_settings,
-                        ecdsa_subnet_public_keys,
+                        chain_key_subnet_public_keys,
                     );
                     scheduler_round_limits.update_subnet_round_limits(&subnet_round_limits);
                 }
@@ -1084,6 +1086,7 @@ impl SchedulerImpl {
     ) {
         let state_time = state.time();
         let mut all_rejects = Vec::new();
+        let snapshots_memory_taken = state.canister_snapshots.memory_taken_by_canisters();
         for canister in state.canisters_iter_mut() {
             // Postpone charging for resources when a canister has a paused execution
             // to avoid modifying the balance of a canister during an unfinished operation.
@@ -1111,6 +1114,10 @@ impl SchedulerImpl {
                         &self.log,
                         canister,
                         duration_since_last_charge,
+                        snapshots_memory_taken
+                            .get(&canister.canister_id())
+                            .copied()
+                            .unwrap_or_default(),
                         subnet_size,
                     )
                     .is_err()
@@ -1397,7 +1404,7 @@ impl Scheduler for SchedulerImpl {
         &self,
         mut state: ReplicatedState,
         randomness: Randomness,
-        ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey>,
         ecdsa_quadruple_ids: BTreeMap<EcdsaKeyId, BTreeSet<QuadrupleId>>,
         current_round: ExecutionRound,
         current_round_type: ExecutionRoundType,
@@ -1565,7 +1572,7 @@ impl Scheduler for SchedulerImpl {
                     &mut subnet_round_limits,
                     registry_settings,
                     &measurement_scope,
-                    &ecdsa_subnet_public_keys,
+                    &chain_key_subnet_public_keys,
                 );
                 state = new_state;
                 if subnet_round_limits.reached() {
@@ -1602,7 +1609,7 @@ impl Scheduler for SchedulerImpl {
                     &mut subnet_round_limits,
                     registry_settings,
                     &measurement_scope,
-                    &ecdsa_subnet_public_keys,
+                    &chain_key_subnet_public_keys,
                 );
                 state = new_state;
             }
@@ -1661,7 +1668,7 @@ impl Scheduler for SchedulerImpl {
             &root_measurement_scope,
             &mut scheduler_round_limits,
             registry_settings,
-            &ecdsa_subnet_public_keys,
+            &chain_key_subnet_public_keys,
         );
 
         // Update [`SignWithEcdsaContext`]s by assigning randomness and matching quadruples.
@@ -2172,6 +2179,15 @@ fn observe_replicated_state_metrics(
     // Add the consumed cycles in ecdsa outcalls.
     consumed_cycles_total += state.metadata.subnet_metrics.consumed_cycles_ecdsa_outcalls;
 
+    // Add the consumed cycles in schnorr outcalls.
+    consumed_cycles_total += state
+        .metadata
+        .subnet_metrics
+        .consumed_cycles_schnorr_outcalls;
+
+    // Add the consumed cycles in vetKD requests.
+    consumed_cycles_total += state.metadata.subnet_metrics.consumed_cycles_vetkd;
+
     // Add the consumed cycles in http outcalls.
     consumed_cycles_total += state.metadata.subnet_metrics.consumed_cycles_http_outcalls;
 
@@ -2315,6 +2331,10 @@ fn get_instructions_limits_for_subnet_message(
             | SetupInitialDKG
             | SignWithECDSA
             | ComputeInitialEcdsaDealings
+            | SchnorrPublicKey
+            | SignWithSchnorr
+            | VetKdPublicKey
+            | VetKdDeriveEncryptedKey
             | StartCanister
             | StopCanister
             | UninstallCode
@@ -2336,7 +2356,11 @@ fn get_instructions_limits_for_subnet_message(
             | TakeCanisterSnapshot
             | LoadCanisterSnapshot
             | ListCanisterSnapshots
-            | DeleteCanisterSnapshot => default_limits,
+            | DeleteCanisterSnapshot
+            | ReadCanisterSnapshotMetadata
+            | ReadCanisterSnapshotData
+            | UploadCanisterSnapshotMetadata
+            | UploadCanisterSnapshotData => default_limits,
             InstallCode | InstallChunkedCode => InstructionLimits::new(
                 dts,
                 config.max_instructions_per_install_code,
@@ -25,7 +25,8 @@ use ic_interfaces::execution_environment::{
 };
 use ic_logger::{replica_logger::no_op_logger, ReplicaLogger};
 use ic_management_canister_types::{
-    CanisterInstallMode, CanisterStatusType, EcdsaKeyId, InstallCodeArgs, Method, Payload, IC_00,
+    CanisterInstallMode, CanisterStatusType, EcdsaKeyId, InstallCodeArgs, MasterPublicKeyId,
+    Method, Payload, IC_00,
 };
 use ic_metrics::MetricsRegistry;
 use ic_registry_routing_table::{CanisterIdRange, RoutingTable};
@@ -50,9 +51,11 @@ use ic_test_utilities::{
 use ic_test_utilities_execution_environment::{generate_subnets, test_registry_settings};
 use ic_types::{
     consensus::ecdsa::QuadrupleId,
-    crypto::{canister_threshold_sig::MasterEcdsaPublicKey, AlgorithmId},
+    crypto::{canister_threshold_sig::MasterPublicKey, AlgorithmId},
     ingress::{IngressState, IngressStatus},
-    messages::{CallContextId, Ingress, MessageId, Request, RequestOrResponse, Response},
+    messages::{
+        CallContextId, Ingress, MessageId, Request, RequestOrResponse, Response, NO_DEADLINE,
+    },
     methods::{Callback, FuncRef, SystemMethod, WasmClosure, WasmMethod},
     CanisterTimer, ComputeAllocation, Cycles, ExecutionRound, MemoryAllocation, NumInstructions,
     Randomness, Time, UserId,
@@ -109,7 +112,7 @@ pub(crate) struct SchedulerTest {
     // Metrics Registry.
     metrics_registry: MetricsRegistry,
     // ECDSA subnet public keys.
-    ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+    chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey>,
     // ECDSA quadruple IDs.
     ecdsa_quadruple_ids: BTreeMap<EcdsaKeyId, BTreeSet<QuadrupleId>>,
 }
@@ -472,7 +475,7 @@ impl SchedulerTest {
         let state = self.scheduler.execute_round(
             state,
             Randomness::from([0; 32]),
-            self.ecdsa_subnet_public_keys.clone(),
+            self.chain_key_subnet_public_keys.clone(),
             self.ecdsa_quadruple_ids.clone(),
             self.round,
             round_type,
@@ -738,13 +741,13 @@ impl SchedulerTestBuilder {
                 .ecdsa_keys_held
                 .insert(ecdsa_key.clone());
         }
-        let ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey> = self
+        let chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey> = self
             .ecdsa_keys
             .into_iter()
             .map(|key| {
                 (
-                    key,
-                    MasterEcdsaPublicKey {
+                    MasterPublicKeyId::Ecdsa(key),
+                    MasterPublicKey {
                         algorithm_id: AlgorithmId::Secp256k1,
                         public_key: b"abababab".to_vec(),
                     },
@@ -841,7 +844,7 @@ impl SchedulerTestBuilder {
             wasm_executor,
             registry_settings: self.registry_settings,
             metrics_registry: self.metrics_registry,
-            ecdsa_subnet_public_keys,
+            chain_key_subnet_public_keys,
             ecdsa_quadruple_ids: BTreeMap::new(),
         }
     }
@@ -1222,6 +1225,7 @@ impl TestWasmExecutorCore {
                 on_reply: closure.clone(),
                 on_reject: closure,
                 on_cleanup: None,
+                deadline: NO_DEADLINE,
             })
             .map_err(|err| err.to_string())?;
         let request = Request {
@@ -1232,6 +1236,7 @@ impl TestWasmExecutorCore {
             method_name: "update".into(),
             method_payload: encode_message_id_as_payload(call_message_id),
             metadata: None,
+            deadline: NO_DEADLINE,
         };
         if let Err(req) = system_state.push_output_request(
             canister_current_memory_usage,
@@ -2,8 +2,9 @@ use ic_config::execution_environment::Config as ExecutionConfig;
 use ic_config::flag_status::FlagStatus;
 use ic_config::subnet_config::SubnetConfig;
 use ic_management_canister_types::{
-    CanisterInstallMode, CanisterSettingsArgsBuilder, FetchCanisterLogsRequest,
-    FetchCanisterLogsResponse, LogVisibility, Payload,
+    CanisterInstallMode, CanisterLogRecord, CanisterSettingsArgsBuilder, FetchCanisterLogsFilter,
+    FetchCanisterLogsRange, FetchCanisterLogsRequest, FetchCanisterLogsResponse, LogVisibility,
+    Payload,
 };
 use ic_registry_subnet_type::SubnetType;
 use ic_state_machine_tests::{
@@ -13,6 +14,7 @@ use ic_state_machine_tests::{
 use ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM;
 use ic_test_utilities_execution_environment::get_reply;
 use ic_types::{CanisterId, Cycles};
+use std::sync::Arc;
 
 fn setup(canister_logging: FlagStatus) -> (StateMachine, CanisterId) {
     let subnet_type = SubnetType::Application;
@@ -41,6 +43,26 @@ fn setup(canister_logging: FlagStatus) -> (StateMachine, CanisterId) {
     (env, canister_id)
 }
 
+fn log_records() -> Vec<CanisterLogRecord> {
+    (0..5)
+        .map(|idx| CanisterLogRecord {
+            idx,
+            timestamp_nanos: 1_000 + 100 * idx,
+            content: format!("message {idx}").into_bytes(),
+        })
+        .collect()
+}
+
+fn set_log_records(env: &StateMachine, canister_id: CanisterId, records: Vec<CanisterLogRecord>) {
+    let mut state = (*env.get_latest_state()).clone();
+    state
+        .canister_state_mut(&canister_id)
+        .unwrap()
+        .system_state
+        .canister_log_records = records;
+    env.replace_canister_state(Arc::new(state), canister_id);
+}
+
 #[test]
 fn test_fetch_canister_logs_disabled_submit_ingress_fails() {
     // Arrange.
@@ -60,10 +82,7 @@ fn test_fetch_canister_logs_disabled_submit_ingress_fails() {
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect to get an error because the fetch_canister_logs API is disabled,
@@ -97,10 +116,7 @@ fn test_fetch_canister_logs_disabled_execute_ingress_fails() {
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect to get an error because the fetch_canister_logs API is disabled,
@@ -133,10 +149,7 @@ fn test_fetch_canister_logs_enabled_submit_ingress_rejected() {
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect error because an update calls are not allowed.
@@ -169,10 +182,7 @@ fn test_fetch_canister_logs_enabled_execute_ingress_rejected() {
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect error because an update calls are not allowed.
@@ -205,10 +215,7 @@ fn test_fetch_canister_logs_disabled_query_fails() {
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect to get an error because the fetch_canister_logs API is disabled,
@@ -242,10 +249,7 @@ fn test_fetch_canister_logs_enabled_query_log_visibility_public_succeeds() {
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect some non-empty result.
@@ -277,10 +281,7 @@ fn test_fetch_canister_logs_enabled_query_log_visibility_invalid_controller_fail
         not_a_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect an error because the caller is not a controller.
@@ -317,10 +318,7 @@ fn test_fetch_canister_logs_enabled_query_log_visibility_valid_controller_succee
         new_controller,
         CanisterId::ic_00(),
         "fetch_canister_logs",
-        FetchCanisterLogsRequest {
-            canister_id: canister_id.into(),
-        }
-        .encode(),
+        FetchCanisterLogsRequest::new(canister_id).encode(),
     );
     // Assert.
     // Expect some non-empty result.
@@ -331,3 +329,235 @@ fn test_fetch_canister_logs_enabled_query_log_visibility_valid_controller_succee
         }
     );
 }
+
+#[test]
+fn test_fetch_canister_logs_enabled_query_log_visibility_allowed_viewer_succeeds() {
+    // Arrange.
+    // - enable the fetch_canister_logs API
+    // - restrict log visibility to a list of allowed viewers
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let allowed_viewer = PrincipalId::new_user_test_id(42);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::AllowedViewers(vec![allowed_viewer]))
+            .build(),
+    )
+    .unwrap();
+    // Act.
+    // Make a query call from an allowed viewer that is not a controller.
+    let result = env.query_as(
+        allowed_viewer,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest::new(canister_id).encode(),
+    );
+    // Assert.
+    assert_eq!(
+        FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap(),
+        FetchCanisterLogsResponse {
+            canister_log_records: vec![]
+        }
+    );
+}
+
+#[test]
+fn test_fetch_canister_logs_enabled_query_log_visibility_not_allowed_viewer_fails() {
+    // Arrange.
+    // - enable the fetch_canister_logs API
+    // - restrict log visibility to a list of allowed viewers
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let allowed_viewer = PrincipalId::new_user_test_id(42);
+    let not_allowed_viewer = PrincipalId::new_user_test_id(43);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::AllowedViewers(vec![allowed_viewer]))
+            .build(),
+    )
+    .unwrap();
+    // Act.
+    // Make a query call from a principal that is neither a viewer nor a controller.
+    let result = env.query_as(
+        not_allowed_viewer,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest::new(canister_id).encode(),
+    );
+    // Assert.
+    assert_eq!(
+        result,
+        Err(UserError::new(
+            ErrorCode::CanisterRejectedMessage,
+            format!(
+                "Caller {not_allowed_viewer} is not allowed to query ic00 method fetch_canister_logs"
+            ),
+        ))
+    );
+}
+
+#[test]
+fn test_fetch_canister_logs_enabled_query_log_visibility_allowed_viewers_controller_succeeds() {
+    // Arrange.
+    // - enable the fetch_canister_logs API
+    // - restrict log visibility to a list of allowed viewers
+    // - add new controller that is not in the list
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let allowed_viewer = PrincipalId::new_user_test_id(42);
+    let new_controller = PrincipalId::new_user_test_id(43);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::AllowedViewers(vec![allowed_viewer]))
+            .with_controller(new_controller)
+            .build(),
+    )
+    .unwrap();
+    // Act.
+    // Make a query call from a controller.
+    let result = env.query_as(
+        new_controller,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest::new(canister_id).encode(),
+    );
+    // Assert.
+    // Controllers can always read the logs.
+    assert_eq!(
+        FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap(),
+        FetchCanisterLogsResponse {
+            canister_log_records: vec![]
+        }
+    );
+}
+
+#[test]
+fn test_update_settings_too_many_allowed_viewers_fails() {
+    // Arrange.
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let allowed_viewers = (0..11).map(PrincipalId::new_user_test_id).collect();
+    // Act.
+    let result = env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::AllowedViewers(allowed_viewers))
+            .build(),
+    );
+    // Assert.
+    assert_eq!(
+        result,
+        Err(UserError::new(
+            ErrorCode::CanisterContractViolation,
+            "Could not validate the settings: Invalid settings: 'allowed_viewers' length exceeds maximum size allowed "
+        ))
+    );
+}
+
+#[test]
+fn test_fetch_canister_logs_filter_by_idx() {
+    // Arrange.
+    // - enable the fetch_canister_logs API
+    // - set the log visibility to public so that any user can read the logs
+    // - populate the canister log
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let not_a_controller = PrincipalId::new_user_test_id(42);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::Public)
+            .build(),
+    )
+    .unwrap();
+    let records = log_records();
+    set_log_records(&env, canister_id, records.clone());
+    // Act.
+    let result = env.query_as(
+        not_a_controller,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest::new(canister_id)
+            .with_filter(FetchCanisterLogsFilter::ByIdx(FetchCanisterLogsRange::new(
+                1, 3,
+            )))
+            .encode(),
+    );
+    // Assert.
+    // The start of the range is inclusive and the end is exclusive.
+    assert_eq!(
+        FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap(),
+        FetchCanisterLogsResponse {
+            canister_log_records: records[1..3].to_vec()
+        }
+    );
+}
+
+#[test]
+fn test_fetch_canister_logs_filter_by_timestamp_nanos() {
+    // Arrange.
+    // - enable the fetch_canister_logs API
+    // - set the log visibility to public so that any user can read the logs
+    // - populate the canister log
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let not_a_controller = PrincipalId::new_user_test_id(42);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::Public)
+            .build(),
+    )
+    .unwrap();
+    let records = log_records();
+    set_log_records(&env, canister_id, records.clone());
+    // Act.
+    let result = env.query_as(
+        not_a_controller,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest::new(canister_id)
+            .with_filter(FetchCanisterLogsFilter::ByTimestampNanos(
+                FetchCanisterLogsRange::new(1_150, 1_400),
+            ))
+            .encode(),
+    );
+    // Assert.
+    // Records with timestamps 1_200 and 1_300 fall into the range.
+    assert_eq!(
+        FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap(),
+        FetchCanisterLogsResponse {
+            canister_log_records: records[2..4].to_vec()
+        }
+    );
+}
+
+#[test]
+fn test_fetch_canister_logs_without_filter_returns_all_records() {
+    // Arrange.
+    // - enable the fetch_canister_logs API
+    // - set the log visibility to public so that any user can read the logs
+    // - populate the canister log
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let not_a_controller = PrincipalId::new_user_test_id(42);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::Public)
+            .build(),
+    )
+    .unwrap();
+    let records = log_records();
+    set_log_records(&env, canister_id, records.clone());
+    // Act.
+    let result = env.query_as(
+        not_a_controller,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest::new(canister_id).encode(),
+    );
+    // Assert.
+    assert_eq!(
+        FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap(),
+        FetchCanisterLogsResponse {
+            canister_log_records: records
+        }
+    );
+}
@@ -0,0 +1,182 @@
+use ic_crypto_sha2::Sha256;
+use ic_management_canister_types::{
+    self as ic00, CanisterInstallMode, DerivationPath, Method, Payload, SchnorrAlgorithm,
+    SchnorrKeyId, SchnorrPublicKeyArgs, SchnorrPublicKeyResponse, SignWithSchnorrArgs,
+    SignWithSchnorrReply,
+};
+use ic_state_machine_tests::{StateMachine, StateMachineBuilder};
+use ic_test_utilities::universal_canister::{call_args, wasm, UNIVERSAL_CANISTER_WASM};
+use ic_test_utilities_execution_environment::get_reply;
+use ic_types::{CanisterId, Cycles};
+
+const INITIAL_CYCLES_BALANCE: Cycles = Cycles::new(100_000_000_000_000);
+const SIGNATURE_PAYMENT: Cycles = Cycles::new(100_000_000_000);
+
+fn setup(key_id: &SchnorrKeyId) -> (StateMachine, CanisterId) {
+    let env = StateMachineBuilder::new()
+        .with_schnorr_key(key_id.clone())
+        .with_checkpoints_enabled(false)
+        .build();
+    let canister_id = env.create_canister_with_cycles(None, INITIAL_CYCLES_BALANCE, None);
+    env.install_wasm_in_mode(
+        canister_id,
+        CanisterInstallMode::Install,
+        UNIVERSAL_CANISTER_WASM.to_vec(),
+        vec![],
+    )
+    .unwrap();
+    (env, canister_id)
+}
+
+fn call_ic00(
+    env: &StateMachine,
+    canister_id: CanisterId,
+    method: Method,
+    payload: Vec<u8>,
+    cycles: Cycles,
+) -> Vec<u8> {
+    let run = wasm()
+        .call_with_cycles(
+            ic00::IC_00,
+            method,
+            call_args()
+                .other_side(payload)
+                .on_reply(wasm().message_payload().append_and_reply())
+                .on_reject(wasm().reject_message().reject()),
+            cycles,
+        )
+        .build();
+    get_reply(env.execute_ingress(canister_id, "update", run))
+}
+
+/// Signs `message` from a fresh canister with the key derived along
+/// `derivation_path` and returns the signature together with the public key
+/// that `schnorr_public_key` returns for the same canister and path.
+fn sign_and_get_public_key(
+    key_id: &SchnorrKeyId,
+    message: Vec<u8>,
+    derivation_path: Vec<Vec<u8>>,
+) -> (Vec<u8>, Vec<u8>) {
+    let (env, canister_id) = setup(key_id);
+
+    let sign_args = SignWithSchnorrArgs {
+        message,
+        derivation_path: DerivationPath::new(
+            derivation_path
+                .iter()
+                .cloned()
+                .map(serde_bytes::ByteBuf::from)
+                .collect(),
+        ),
+        key_id: key_id.clone(),
+    };
+    let reply = call_ic00(
+        &env,
+        canister_id,
+        Method::SignWithSchnorr,
+        sign_args.encode(),
+        SIGNATURE_PAYMENT,
+    );
+    let signature = SignWithSchnorrReply::decode(&reply).unwrap().signature;
+
+    let public_key_args = SchnorrPublicKeyArgs {
+        canister_id: None,
+        derivation_path: DerivationPath::new(
+            derivation_path
+                .into_iter()
+                .map(serde_bytes::ByteBuf::from)
+                .collect(),
+        ),
+        key_id: key_id.clone(),
+    };
+    let reply = call_ic00(
+        &env,
+        canister_id,
+        Method::SchnorrPublicKey,
+        public_key_args.encode(),
+        Cycles::zero(),
+    );
+    let public_key = SchnorrPublicKeyResponse::decode(&reply).unwrap().public_key;
+
+    (signature, public_key)
+}
+
+fn bip340_tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::hash(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.write(&tag_hash);
+    hasher.write(&tag_hash);
+    for chunk in data {
+        hasher.write(chunk);
+    }
+    hasher.finish()
+}
+
+/// Verifies a BIP340 `signature` on `message` under the SEC1-encoded
+/// `public_key`, interpreting the public key as x-only as BIP340 does.
+fn verify_bip340_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    use k256::elliptic_curve::{ops::Reduce, point::AffineCoordinates, PrimeField};
+    use k256::{FieldBytes, ProjectivePoint, Scalar, U256};
+
+    assert_eq!(public_key.len(), 33);
+    assert_eq!(signature.len(), 64);
+
+    let mut even_public_key = vec![0x02];
+    even_public_key.extend_from_slice(&public_key[1..]);
+    let public_key_point = k256::PublicKey::from_sec1_bytes(&even_public_key)
+        .expect("invalid BIP340 public key")
+        .to_projective();
+
+    let s = match Option::<Scalar>::from(Scalar::from_repr(FieldBytes::clone_from_slice(
+        &signature[32..],
+    ))) {
+        Some(s) => s,
+        None => return false,
+    };
+    let challenge_hash = bip340_tagged_hash(
+        "BIP0340/challenge",
+        &[&signature[..32], &public_key[1..], message],
+    );
+    let e = <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(challenge_hash));
+
+    let nonce_point = (ProjectivePoint::GENERATOR * s - public_key_point * e).to_affine();
+    !bool::from(nonce_point.y_is_odd()) && nonce_point.x().as_slice() == &signature[..32]
+}
+
+#[test]
+fn bip340_signature_verifies_under_schnorr_public_key() {
+    let key_id = SchnorrKeyId {
+        algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+        name: "bip340_key".to_string(),
+    };
+    let message = vec![7; 32];
+
+    let (signature, public_key) =
+        sign_and_get_public_key(&key_id, message.clone(), vec![vec![1, 2, 3], vec![4]]);
+
+    assert!(verify_bip340_signature(&public_key, &message, &signature));
+    // The signature must not verify for a different message.
+    assert!(!verify_bip340_signature(&public_key, &[8; 32], &signature));
+}
+
+#[test]
+fn ed25519_signature_verifies_under_schnorr_public_key() {
+    let key_id = SchnorrKeyId {
+        algorithm: SchnorrAlgorithm::Ed25519,
+        name: "ed25519_key".to_string(),
+    };
+    let message = b"a message of arbitrary length".to_vec();
+
+    let (signature, public_key) =
+        sign_and_get_public_key(&key_id, message.clone(), vec![vec![1, 2, 3], vec![4]]);
+
+    let verification_key =
+        ed25519_consensus::VerificationKey::try_from(<[u8; 32]>::try_from(public_key).unwrap())
+            .unwrap();
+    let signature = ed25519_consensus::Signature::from(<[u8; 64]>::try_from(signature).unwrap());
+    assert!(verification_key.verify(&signature, &message).is_ok());
+    // The signature must not verify for a different message.
+    assert!(verification_key
+        .verify(&signature, b"another message")
+        .is_err());
+}
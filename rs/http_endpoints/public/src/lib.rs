@@ -20,7 +20,10 @@ mod types;
 
 pub use call::CallServiceBuilder;
 pub use query::QueryServiceBuilder;
-pub use read_state::canister::{CanisterReadStateService, CanisterReadStateServiceBuilder};
+pub use read_state::canister::{
+    can_read_canister_metadata, verify_paths, CanisterReadStateService,
+    CanisterReadStateServiceBuilder,
+};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "fuzzing_code")] {
@@ -272,8 +272,8 @@ impl Service<Request<Bytes>> for CanisterReadStateService {
     }
 }
 
-// Verifies that the `user` is authorized to retrieve the `paths` requested.
-fn verify_paths(
+/// Verifies that the `user` is authorized to retrieve the `paths` requested.
+pub fn verify_paths(
     state: &ReplicatedState,
     user: &UserId,
     paths: &[Path],
@@ -380,7 +380,9 @@ fn verify_paths(
     Ok(())
 }
 
-fn can_read_canister_metadata(
+/// Verifies that the `user` is authorized to retrieve the custom section
+/// `custom_section_name` of the canister's metadata.
+pub fn can_read_canister_metadata(
     user: &UserId,
     canister_id: &CanisterId,
     custom_section_name: &str,
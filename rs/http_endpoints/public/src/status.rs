@@ -9,7 +9,7 @@ use ic_crypto_utils_threshold_sig_der::public_key_to_der;
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{warn, ReplicaLogger};
 use ic_types::{
-    messages::{Blob, HttpStatusResponse, ReplicaHealthStatus},
+    messages::{Blob, HttpStatusResponse, ReplicaHealthStatus, IC_API_VERSION},
     replica_version::REPLICA_BINARY_HASH,
     ReplicaVersion, SubnetId,
 };
@@ -24,10 +24,6 @@ use tower::{
     limit::concurrency::GlobalConcurrencyLimitLayer, util::BoxCloneService, Service, ServiceBuilder,
 };
 
-// TODO(NET-776)
-// The IC API version reported on status requests.
-const IC_API_VERSION: &str = "0.18.0";
-
 #[derive(Clone)]
 pub(crate) struct StatusService {
     log: ReplicaLogger,
@@ -200,6 +200,8 @@ pub fn default_get_latest_state() -> Labeled<Arc<ReplicatedState>> {
         canister_migrations: Arc::new(CanisterMigrations::default()),
         nns_subnet_id: subnet_test_id(1),
         ecdsa_signing_subnets: Default::default(),
+        schnorr_signing_subnets: Default::default(),
+        vetkd_enabled_subnets: Default::default(),
         bitcoin_mainnet_canister_id: None,
         bitcoin_testnet_canister_id: None,
     };
@@ -5,13 +5,13 @@ pub use errors::{CanisterOutOfCyclesError, HypervisorError, TrapCode};
 use ic_base_types::NumBytes;
 use ic_error_types::UserError;
 use ic_interfaces_state_manager::Labeled;
-use ic_management_canister_types::EcdsaKeyId;
+use ic_management_canister_types::{EcdsaKeyId, MasterPublicKeyId};
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_sys::{PageBytes, PageIndex};
 use ic_types::{
     consensus::ecdsa::QuadrupleId,
-    crypto::canister_threshold_sig::MasterEcdsaPublicKey,
+    crypto::canister_threshold_sig::MasterPublicKey,
     ingress::{IngressStatus, WasmResult},
     messages::{
         AnonymousQuery, AnonymousQueryResponse, CertificateDelegation, HttpQueryResponse,
@@ -101,6 +101,8 @@ pub enum SystemApiCallId {
     CallOnCleanup,
     /// Tracker for `ic0.call_perform()`
     CallPerform,
+    /// Tracker for `ic0.call_with_best_effort_response()`
+    CallWithBestEffortResponse,
     /// Tracker for `ic0.canister_cycle_balance()`
     CanisterCycleBalance,
     /// Tracker for `ic0.canister_cycle_balance128()`
@@ -151,6 +153,8 @@ pub enum SystemApiCallId {
     MsgCyclesRefunded,
     /// Tracker for `ic0.msg_cycles_refunded128()`
     MsgCyclesRefunded128,
+    /// Tracker for `ic0.msg_deadline()`
+    MsgDeadline,
     /// Tracker for `ic0.msg_method_name_copy()`
     MsgMethodNameCopy,
     /// Tracker for `ic0.msg_method_name_size()`
@@ -734,6 +738,12 @@ pub trait SystemApi {
     /// See <https://sdk.dfinity.org/docs/interface-spec/index.html#system-api-call>
     fn ic0_call_on_cleanup(&mut self, fun: u32, env: u32) -> HypervisorResult<()>;
 
+    /// Turns the call under construction into a best-effort call that is
+    /// rejected with `SYS_UNKNOWN` if no response arrives within
+    /// `timeout_seconds` (capped at `MAX_CALL_TIMEOUT_SECONDS`). Can be called
+    /// at most once between `ic0.call_new` and `ic0.call_perform`.
+    fn ic0_call_with_best_effort_response(&mut self, timeout_seconds: u32) -> HypervisorResult<()>;
+
     /// (deprecated) Please use `ic0_call_cycles_add128` instead, as this API
     /// can only add a 64-bit value.
     ///
@@ -874,6 +884,10 @@ pub trait SystemApi {
     /// The canister can query the IC for the current time.
     fn ic0_time(&mut self) -> HypervisorResult<Time>;
 
+    /// Returns the deadline (in nanoseconds since the Unix epoch) of the
+    /// message being executed, or 0 if it is a guaranteed response message.
+    fn ic0_msg_deadline(&self) -> HypervisorResult<u64>;
+
     /// The canister can set a global one-off timer at the specific time.
     fn ic0_global_timer_set(&mut self, time: Time) -> HypervisorResult<Time>;
 
@@ -1117,6 +1131,8 @@ pub struct RegistryExecutionSettings {
     pub max_number_of_canisters: u64,
     pub provisional_whitelist: ProvisionalWhitelist,
     pub max_ecdsa_queue_size: u32,
+    pub max_schnorr_queue_size: u32,
+    pub max_vetkd_queue_size: u32,
     pub quadruples_to_create_in_advance: u32,
     pub subnet_size: usize,
 }
@@ -1175,7 +1191,7 @@ pub trait Scheduler: Send {
         &self,
         state: Self::State,
         randomness: Randomness,
-        ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+        chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey>,
         ecdsa_quadruple_ids: BTreeMap<EcdsaKeyId, BTreeSet<QuadrupleId>>,
         current_round: ExecutionRound,
         current_round_type: ExecutionRoundType,
@@ -151,6 +151,12 @@ pub enum HypervisorError {
         available: Cycles,
         threshold: Cycles,
     },
+    /// A `memory.grow` would have increased the Wasm memory size beyond the
+    /// Wasm memory limit set in the canister settings.
+    WasmMemoryLimitExceeded {
+        bytes: NumBytes,
+        limit: NumBytes,
+    },
 }
 
 impl From<WasmInstrumentationError> for HypervisorError {
@@ -356,6 +362,20 @@ impl HypervisorError {
                      bytes,
                      threshold - available)
             ),
+            Self::WasmMemoryLimitExceeded { bytes, limit } => UserError::new(
+                E::CanisterWasmMemoryLimitExceeded,
+                format!(
+                    "Canister exceeded its current Wasm memory limit of {} bytes. \
+                     The peak Wasm memory usage was {} bytes. \
+                     If the canister reaches 4GiB, then it may stop functioning and may become unrecoverable. \
+                     Please reach out to the canister owner to investigate the reason for the increased memory usage. \
+                     It might be necessary to move data from the Wasm memory to the stable memory. \
+                     If such high Wasm memory usage is expected and safe, then the developer can increase \
+                     the Wasm memory limit in the canister settings.",
+                    limit.get(),
+                    bytes.get(),
+                ),
+            ),
         }
     }
 
@@ -394,6 +414,7 @@ impl HypervisorError {
             HypervisorError::InsufficientCyclesInMessageMemoryGrow { .. } => {
                 "InsufficientCyclesInMessageMemoryGrow"
             }
+            HypervisorError::WasmMemoryLimitExceeded { .. } => "WasmMemoryLimitExceeded",
         }
     }
 }
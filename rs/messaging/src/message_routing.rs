@@ -14,7 +14,7 @@ use ic_interfaces_certified_stream_store::CertifiedStreamStore;
 use ic_interfaces_registry::RegistryClient;
 use ic_interfaces_state_manager::{CertificationScope, StateManager, StateManagerError};
 use ic_logger::{debug, fatal, info, warn, ReplicaLogger};
-use ic_management_canister_types::EcdsaKeyId;
+use ic_management_canister_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_metrics::buckets::{add_bucket, decimal_buckets, decimal_buckets_with_zero};
 use ic_metrics::MetricsRegistry;
 use ic_protobuf::proxy::ProxyDecodeError;
@@ -26,7 +26,9 @@ use ic_registry_client_helpers::{
     node::NodeRegistry,
     provisional_whitelist::ProvisionalWhitelistRegistry,
     routing_table::RoutingTableRegistry,
+    schnorr_keys::SchnorrKeysRegistry,
     subnet::{get_node_ids_from_subnet_record, SubnetListRegistry, SubnetRegistry},
+    vetkd_keys::VetKdKeysRegistry,
 };
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_features::SubnetFeatures;
@@ -86,6 +88,7 @@ const PHASE_COMMIT: &str = "commit";
 const METRIC_PROCESS_BATCH_DURATION: &str = "mr_process_batch_duration_seconds";
 const METRIC_PROCESS_BATCH_PHASE_DURATION: &str = "mr_process_batch_phase_duration_seconds";
 const METRIC_TIMED_OUT_REQUESTS_TOTAL: &str = "mr_timed_out_requests_total";
+const METRIC_TIMED_OUT_CALLBACKS_TOTAL: &str = "mr_timed_out_callbacks_total";
 const METRIC_SUBNET_SPLIT_HEIGHT: &str = "mr_subnet_split_height";
 const BLOCKS_PROPOSED_TOTAL: &str = "mr_blocks_proposed_total";
 const BLOCKS_NOT_PROPOSED_TOTAL: &str = "mr_blocks_not_proposed_total";
@@ -271,6 +274,8 @@ pub(crate) struct MessageRoutingMetrics {
     pub process_batch_phase_duration: HistogramVec,
     /// Number of timed out requests.
     pub timed_out_requests_total: IntCounter,
+    /// Number of timed out best-effort callbacks.
+    pub timed_out_callbacks_total: IntCounter,
     /// Height at which the subnet last split (if during the lifetime of this
     /// replica process; otherwise zero).
     pub subnet_split_height: IntGaugeVec,
@@ -354,6 +359,10 @@ impl MessageRoutingMetrics {
                 METRIC_TIMED_OUT_REQUESTS_TOTAL,
                 "Count of timed out requests.",
             ),
+            timed_out_callbacks_total: metrics_registry.int_counter(
+                METRIC_TIMED_OUT_CALLBACKS_TOTAL,
+                "Count of timed out best-effort callbacks.",
+            ),
             subnet_split_height: metrics_registry.int_gauge_vec(
                 METRIC_SUBNET_SPLIT_HEIGHT,
                 "Height at which the subnet last split (if during the lifetime of this replica process).",
@@ -724,6 +733,14 @@ impl BatchProcessorImpl {
             .ecdsa_config
             .map(|c| (c.max_queue_size, c.quadruples_to_create_in_advance))
             .unwrap_or_default();
+        let max_schnorr_queue_size = subnet_record
+            .schnorr_config
+            .map(|c| c.max_queue_size)
+            .unwrap_or_default();
+        let max_vetkd_queue_size = subnet_record
+            .vetkd_config
+            .map(|c| c.max_queue_size)
+            .unwrap_or_default();
 
         let subnet_size = if subnet_record.membership.is_empty() {
             self.metrics.critical_error_missing_subnet_size.inc();
@@ -748,6 +765,8 @@ impl BatchProcessorImpl {
                 max_number_of_canisters,
                 provisional_whitelist,
                 max_ecdsa_queue_size,
+                max_schnorr_queue_size,
+                max_vetkd_queue_size,
                 quadruples_to_create_in_advance,
                 subnet_size,
             },
@@ -845,6 +864,42 @@ impl BatchProcessorImpl {
                 })
                 .transpose()?
                 .unwrap_or_default();
+            let schnorr_keys_held = subnet_record
+                .schnorr_config
+                .map(|schnorr_config| {
+                    schnorr_config
+                        .key_ids
+                        .into_iter()
+                        .map(|k| {
+                            SchnorrKeyId::try_from(k).map_err(|err: ProxyDecodeError| {
+                                Persistent(format!(
+                                    "'Schnorr key ID from subnet record for subnet {}', err: {}",
+                                    *subnet_id, err,
+                                ))
+                            })
+                        })
+                        .collect::<Result<BTreeSet<_>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+            let vetkd_keys_held = subnet_record
+                .vetkd_config
+                .map(|vetkd_config| {
+                    vetkd_config
+                        .key_ids
+                        .into_iter()
+                        .map(|k| {
+                            VetKdKeyId::try_from(k).map_err(|err: ProxyDecodeError| {
+                                Persistent(format!(
+                                    "'VetKD key ID from subnet record for subnet {}', err: {}",
+                                    *subnet_id, err,
+                                ))
+                            })
+                        })
+                        .collect::<Result<BTreeSet<_>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
 
             subnets.insert(
                 *subnet_id,
@@ -854,6 +909,8 @@ impl BatchProcessorImpl {
                     subnet_type,
                     subnet_features,
                     ecdsa_keys_held,
+                    schnorr_keys_held,
+                    vetkd_keys_held,
                 },
             );
         }
@@ -879,6 +936,16 @@ impl BatchProcessorImpl {
             .get_ecdsa_signing_subnets(registry_version)
             .map_err(|err| registry_error("ECDSA signing subnets", None, err))?
             .unwrap_or_default();
+        let schnorr_signing_subnets = self
+            .registry
+            .get_schnorr_signing_subnets(registry_version)
+            .map_err(|err| registry_error("Schnorr signing subnets", None, err))?
+            .unwrap_or_default();
+        let vetkd_enabled_subnets = self
+            .registry
+            .get_vetkd_enabled_subnets(registry_version)
+            .map_err(|err| registry_error("VetKD enabled subnets", None, err))?
+            .unwrap_or_default();
 
         Ok(NetworkTopology {
             subnets,
@@ -886,6 +953,8 @@ impl BatchProcessorImpl {
             nns_subnet_id,
             canister_migrations: Arc::new(canister_migrations),
             ecdsa_signing_subnets,
+            schnorr_signing_subnets,
+            vetkd_enabled_subnets,
             bitcoin_testnet_canister_id: self.bitcoin_config.testnet_canister_id,
             bitcoin_mainnet_canister_id: self.bitcoin_config.mainnet_canister_id,
         })
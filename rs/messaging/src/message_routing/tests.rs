@@ -529,6 +529,8 @@ fn make_batch_processor(
         max_number_of_canisters: 0,
         provisional_whitelist: ProvisionalWhitelist::All,
         max_ecdsa_queue_size: 0,
+        max_schnorr_queue_size: 0,
+        max_vetkd_queue_size: 0,
         quadruples_to_create_in_advance: 0,
         subnet_size: 0,
     }));
@@ -868,7 +870,7 @@ fn try_read_registry_succeeds_with_fully_specified_registry_records() {
             requires_full_state_hash: false,
             messages: BatchMessages::default(),
             randomness: Randomness::new([123; 32]),
-            ecdsa_subnet_public_keys: BTreeMap::default(),
+            chain_key_subnet_public_keys: BTreeMap::default(),
             ecdsa_quadruple_ids: BTreeMap::new(),
             registry_version: fixture.registry.get_latest_version(),
             time: Time::from_nanos_since_unix_epoch(0),
@@ -1650,7 +1652,7 @@ fn process_batch_updates_subnet_metrics() {
             requires_full_state_hash: false,
             messages: BatchMessages::default(),
             randomness: Randomness::new([123; 32]),
-            ecdsa_subnet_public_keys: BTreeMap::default(),
+            chain_key_subnet_public_keys: BTreeMap::default(),
             ecdsa_quadruple_ids: BTreeMap::new(),
             registry_version: fixture.registry.get_latest_version(),
             time: Time::from_nanos_since_unix_epoch(0),
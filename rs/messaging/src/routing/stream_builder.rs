@@ -1,4 +1,5 @@
 use crate::message_routing::LatencyMetrics;
+use ic_certification_version::CertificationVersion;
 use ic_constants::SYSTEM_SUBNET_STREAM_MSG_LIMIT;
 use ic_error_types::RejectCode;
 use ic_logger::{error, warn, ReplicaLogger};
@@ -73,6 +74,7 @@ const LABEL_VALUE_TYPE_RESPONSE: &str = "response";
 const LABEL_VALUE_STATUS_SUCCESS: &str = "success";
 const LABEL_VALUE_STATUS_CANISTER_NOT_FOUND: &str = "canister_not_found";
 const LABEL_VALUE_STATUS_PAYLOAD_TOO_LARGE: &str = "payload_too_large";
+const LABEL_VALUE_STATUS_BEST_EFFORT_NOT_SUPPORTED: &str = "best_effort_not_supported";
 
 const CRITICAL_ERROR_INFINITE_LOOP: &str = "mr_stream_builder_infinite_loop";
 const CRITICAL_ERROR_PAYLOAD_TOO_LARGE: &str = "mr_stream_builder_payload_too_large";
@@ -197,6 +199,7 @@ impl StreamBuilderImpl {
                             MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
                         ),
                     ),
+                    deadline: req.deadline,
                 }
                 .into(),
                 // Arbitrary large amount, pushing a response always returns memory.
@@ -296,8 +299,14 @@ impl StreamBuilderImpl {
             .map(|(subnet_id, topology)| (*subnet_id, topology.subnet_type))
             .collect();
 
+        // Deadlines are only certified from `CertificationVersion::V17` onwards, so
+        // best-effort requests are kept subnet-local until then.
+        let remote_best_effort_calls_supported =
+            state.metadata.certification_version >= CertificationVersion::V17;
+
         let mut requests_to_reject = Vec::new();
         let mut oversized_requests = Vec::new();
+        let mut remote_best_effort_requests = Vec::new();
 
         let mut output_iter = state.output_into_iter();
         let mut last_output_size = usize::MAX;
@@ -366,6 +375,19 @@ impl StreamBuilderImpl {
                             oversized_requests.push(req);
                         }
 
+                        // Remote best-effort request, while deadlines cannot be certified.
+                        RequestOrResponse::Request(req)
+                            if dst_net_id != self.subnet_id
+                                && req.is_best_effort()
+                                && !remote_best_effort_calls_supported =>
+                        {
+                            self.observe_message_type_status(
+                                LABEL_VALUE_TYPE_REQUEST,
+                                LABEL_VALUE_STATUS_BEST_EFFORT_NOT_SUPPORTED,
+                            );
+                            remote_best_effort_requests.push(req);
+                        }
+
                         // Response above the payload size limit.
                         RequestOrResponse::Response(ref mut rep)
                             if rep.payload_size_bytes() > MAX_INTER_CANISTER_PAYLOAD_IN_BYTES =>
@@ -465,6 +487,19 @@ impl StreamBuilderImpl {
             );
         }
 
+        for req in remote_best_effort_requests {
+            let dst_canister_id = req.receiver;
+            self.reject_local_request(
+                &mut state,
+                &req,
+                RejectCode::DestinationInvalid,
+                format!(
+                    "Best-effort calls to canister {} on another subnet are not supported yet",
+                    dst_canister_id
+                ),
+            );
+        }
+
         // Export the total number of enqueued messages and byte size, per stream.
         streams
             .iter()
@@ -24,10 +24,10 @@ use ic_test_utilities_time::mock_time;
 use ic_types::{
     messages::{
         CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response,
-        MAX_INTER_CANISTER_PAYLOAD_IN_BYTES_U64,
+        MAX_INTER_CANISTER_PAYLOAD_IN_BYTES_U64, NO_DEADLINE,
     },
     xnet::{StreamIndex, StreamIndexedQueue},
-    CanisterId, Cycles, SubnetId, Time,
+    CanisterId, CoarseTime, Cycles, SubnetId, Time,
 };
 use lazy_static::lazy_static;
 use maplit::btreemap;
@@ -122,6 +122,7 @@ fn reject_local_request() {
                     originator_reply_callback: msg.sender_reply_callback,
                     refund: msg.payment,
                     response_payload: Payload::Reject(expected_reject_context),
+                    deadline: msg.deadline,
                 }
                 .into(),
                 &mut (i64::MAX / 2),
@@ -185,6 +186,7 @@ fn reject_local_request_for_subnet() {
                         RejectCode::SysFatal,
                         reject_message,
                     )),
+                    deadline: msg.deadline,
                 }
                 .into(),
                 &mut (i64::MAX / 2),
@@ -681,6 +683,87 @@ fn build_streams_with_messages_targeted_to_other_subnets() {
     });
 }
 
+// Tests that best-effort requests to other subnets are rejected, unless the
+// certification version supports deadlines.
+#[test]
+fn build_streams_with_best_effort_requests_targeted_to_other_subnets() {
+    with_test_replica_logger(|log| {
+        let msgs = vec![RequestBuilder::default()
+            .sender(canister_test_id(0))
+            .receiver(CanisterId::from(REMOTE_SUBNET))
+            .sender_reply_callback(CallbackId::from(1))
+            .method_name(Method::CanisterStatus.to_string())
+            .deadline(CoarseTime::from_secs_since_unix_epoch(10))
+            .build()];
+
+        let (stream_builder, mut provided_state, metrics_registry) = new_fixture(&log);
+        provided_state.metadata.certification_version = CertificationVersion::V16;
+
+        // Ensure the routing table knows about the `REMOTE_SUBNET`.
+        provided_state.metadata.network_topology.routing_table = Arc::new(RoutingTable::try_from(
+            btreemap! {
+                CanisterIdRange{ start: CanisterId::from(0), end: CanisterId::from(0xfff) } => REMOTE_SUBNET,
+            },
+        ).unwrap());
+
+        // Set up the provided_canister_states.
+        let provided_canister_states = canister_states_with_outputs(msgs.clone());
+        provided_state.put_canister_states(provided_canister_states);
+
+        // With `CertificationVersion::V17` the request is routed into the remote stream.
+        let mut v17_state = provided_state.clone();
+        v17_state.metadata.certification_version = CertificationVersion::V17;
+        let result_state = stream_builder.build_streams(v17_state);
+        assert_eq!(
+            Some(1),
+            result_state
+                .get_stream(&REMOTE_SUBNET)
+                .map(|stream| stream.messages().len())
+        );
+
+        // Below `CertificationVersion::V17` a reject response is generated instead.
+        let mut expected_state = consume_output_queues(&provided_state);
+        for msg in msgs {
+            let receiver = msg.receiver;
+            stream_builder.reject_local_request(
+                &mut expected_state,
+                &msg,
+                RejectCode::DestinationInvalid,
+                format!(
+                    "Best-effort calls to canister {} on another subnet are not supported yet",
+                    receiver
+                ),
+            );
+        }
+
+        let result_state = stream_builder.build_streams(provided_state);
+
+        assert_eq!(result_state.canister_states, expected_state.canister_states);
+        assert_eq!(result_state.metadata, expected_state.metadata);
+        assert_eq!(result_state, expected_state);
+
+        assert_routed_messages_eq(
+            metric_vec(&[
+                (
+                    &[
+                        (LABEL_TYPE, LABEL_VALUE_TYPE_REQUEST),
+                        (LABEL_STATUS, LABEL_VALUE_STATUS_SUCCESS),
+                    ],
+                    1,
+                ),
+                (
+                    &[
+                        (LABEL_TYPE, LABEL_VALUE_TYPE_REQUEST),
+                        (LABEL_STATUS, LABEL_VALUE_STATUS_BEST_EFFORT_NOT_SUPPORTED),
+                    ],
+                    1,
+                ),
+            ]),
+            &metrics_registry,
+        );
+    });
+}
+
 // Tests that remote requests and all responses with oversized payloads are rejected.
 #[test]
 fn build_streams_with_oversized_payloads() {
@@ -711,6 +794,7 @@ fn build_streams_with_oversized_payloads() {
             method_name: method_name.clone(),
             method_payload: oversized_request_payload.clone(),
             metadata: None,
+            deadline: NO_DEADLINE,
         };
         assert!(local_request.payload_size_bytes() > MAX_INTER_CANISTER_PAYLOAD_IN_BYTES);
 
@@ -723,6 +807,7 @@ fn build_streams_with_oversized_payloads() {
             method_name,
             method_payload: oversized_request_payload,
             metadata: None,
+            deadline: NO_DEADLINE,
         };
         assert!(remote_request.payload_size_bytes() > MAX_INTER_CANISTER_PAYLOAD_IN_BYTES);
         let remote_request_reject = Response {
@@ -739,6 +824,7 @@ fn build_streams_with_oversized_payloads() {
                     MAX_INTER_CANISTER_PAYLOAD_IN_BYTES
                 ),
             )),
+            deadline: NO_DEADLINE,
         };
 
         // Oversized response: will be replaced with a reject response.
@@ -748,6 +834,7 @@ fn build_streams_with_oversized_payloads() {
             originator_reply_callback: CallbackId::from(3),
             refund: Cycles::new(3),
             response_payload: Payload::Data(oversized_response_payload),
+            deadline: NO_DEADLINE,
         };
         assert!(data_response.payload_size_bytes() > MAX_INTER_CANISTER_PAYLOAD_IN_BYTES);
         let data_response_reject = Response {
@@ -764,6 +851,7 @@ fn build_streams_with_oversized_payloads() {
                     MAX_INTER_CANISTER_PAYLOAD_IN_BYTES
                 ),
             )),
+            deadline: NO_DEADLINE,
         };
 
         // Oversized reject response: will be replaced with a reject response.
@@ -777,6 +865,7 @@ fn build_streams_with_oversized_payloads() {
                 RejectCode::SysTransient,
                 oversized_error_message,
             )),
+            deadline: NO_DEADLINE,
         };
         assert!(reject_response.payload_size_bytes() > MAX_INTER_CANISTER_PAYLOAD_IN_BYTES);
         let reject_response_reject = Response {
@@ -789,6 +878,7 @@ fn build_streams_with_oversized_payloads() {
                 // Long enough message to be properly truncated by the constructor.
                 "x".repeat(10 * 1024),
             )),
+            deadline: NO_DEADLINE,
         };
 
         let (stream_builder, mut provided_state, metrics_registry) = new_fixture(&log);
@@ -1373,6 +1373,7 @@ fn generate_reject_response_queue_full() {
             originator_reply_callback: msg.sender_reply_callback,
             refund: msg.payment,
             response_payload: Payload::Reject(RejectContext::new(RejectCode::SysTransient, &err)),
+            deadline: msg.deadline,
         }
         .into(),
     );
@@ -1413,6 +1414,7 @@ fn generate_reject_response_canister_not_found() {
                 RejectCode::DestinationInvalid,
                 &err,
             )),
+            deadline: msg.deadline,
         }
         .into(),
     );
@@ -17,6 +17,7 @@ const PHASE_INDUCTION: &str = "induction";
 const PHASE_EXECUTION: &str = "execution";
 const PHASE_MESSAGE_ROUTING: &str = "message_routing";
 const PHASE_TIME_OUT_REQUESTS: &str = "time_out_requests";
+const PHASE_TIME_OUT_CALLBACKS: &str = "time_out_callbacks";
 
 pub(crate) trait StateMachine: Send {
     fn execute_round(
@@ -129,6 +130,14 @@ impl StateMachine for StateMachineImpl {
             .inc_by(timed_out_requests);
         self.observe_phase_duration(PHASE_TIME_OUT_REQUESTS, &since);
 
+        // Time out expired best-effort callbacks.
+        let since = Instant::now();
+        let timed_out_callbacks = state.time_out_callbacks();
+        self.metrics
+            .timed_out_callbacks_total
+            .inc_by(timed_out_callbacks);
+        self.observe_phase_duration(PHASE_TIME_OUT_CALLBACKS, &since);
+
         // Preprocess messages and add messages to the induction pool through the Demux.
         let since = Instant::now();
         let mut state_with_messages = self.demux.process_payload(state, batch.messages);
@@ -151,7 +160,7 @@ impl StateMachine for StateMachineImpl {
         let state_after_execution = self.scheduler.execute_round(
             state_with_messages,
             batch.randomness,
-            batch.ecdsa_subnet_public_keys,
+            batch.chain_key_subnet_public_keys,
             batch.ecdsa_quadruple_ids,
             ExecutionRound::from(batch.batch_number.get()),
             execution_round_type,
@@ -6,7 +6,7 @@ use crate::{
 };
 use ic_interfaces::execution_environment::Scheduler;
 use ic_interfaces_state_manager::StateManager;
-use ic_management_canister_types::EcdsaKeyId;
+use ic_management_canister_types::{EcdsaKeyId, MasterPublicKeyId};
 use ic_metrics::MetricsRegistry;
 use ic_registry_subnet_features::SubnetFeatures;
 use ic_registry_subnet_type::SubnetType;
@@ -20,7 +20,7 @@ use ic_test_utilities_logger::with_test_replica_logger;
 use ic_test_utilities_metrics::fetch_int_counter_vec;
 use ic_types::consensus::ecdsa::QuadrupleId;
 use ic_types::messages::SignedIngress;
-use ic_types::{batch::BatchMessages, crypto::canister_threshold_sig::MasterEcdsaPublicKey};
+use ic_types::{batch::BatchMessages, crypto::canister_threshold_sig::MasterPublicKey};
 use ic_types::{Height, PrincipalId, SubnetId, Time};
 use maplit::btreemap;
 use mockall::{mock, predicate::*, Sequence};
@@ -34,7 +34,7 @@ mock! {
             &self,
             state: ic_replicated_state::ReplicatedState,
             randomness: ic_types::Randomness,
-            ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+            chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey>,
             ecdsa_quadruple_ids: BTreeMap<EcdsaKeyId, BTreeSet<QuadrupleId>>,
             current_round: ExecutionRound,
             current_round_type: ExecutionRoundType,
@@ -89,7 +89,7 @@ fn test_fixture(provided_batch: &Batch) -> StateMachineTestFixture {
         .with(
             always(),
             eq(provided_batch.randomness),
-            eq(provided_batch.ecdsa_subnet_public_keys.clone()),
+            eq(provided_batch.chain_key_subnet_public_keys.clone()),
             eq(provided_batch.ecdsa_quadruple_ids.clone()),
             eq(round),
             eq(round_type),
@@ -114,6 +114,8 @@ fn test_fixture(provided_batch: &Batch) -> StateMachineTestFixture {
             subnet_type: SubnetType::Application,
             subnet_features: SubnetFeatures::default(),
             ecdsa_keys_held: BTreeSet::new(),
+            schnorr_keys_held: Default::default(),
+            vetkd_keys_held: Default::default(),
         },
     );
 
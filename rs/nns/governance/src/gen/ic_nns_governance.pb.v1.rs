@@ -521,6 +521,234 @@ pub struct SetSnsTokenSwapOpenTimeWindow {
     #[prost(message, optional, tag = "2")]
     pub request: ::core::option::Option<::ic_sns_swap::pb::v1::SetOpenTimeWindowRequest>,
 }
+/// Installs, reinstalls or upgrades the code of an NNS canister.
+///
+/// Unlike ExecuteNnsFunction with NnsCanisterUpgrade, the fields are typed, so
+/// that voters can see what the proposal does without decoding a payload.
+#[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InstallCode {
+    /// The canister whose code is to be installed. Must be an NNS canister.
+    #[prost(message, optional, tag = "1")]
+    pub canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// The install mode to use.
+    #[prost(enumeration = "install_code::CanisterInstallMode", optional, tag = "2")]
+    pub install_mode: ::core::option::Option<i32>,
+    /// The wasm module to install.
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub wasm_module: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The argument passed to the canister's init or post_upgrade method.
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub arg: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// If true, the canister is not stopped before installing the code. This is
+    /// useful in emergencies, e.g. when the canister cannot be stopped because of
+    /// outstanding calls, but it is otherwise not recommended.
+    #[prost(bool, optional, tag = "5")]
+    pub skip_stopping_before_installing: ::core::option::Option<bool>,
+    /// The SHA-256 hash of `wasm_module`. Set by governance when the proposal is
+    /// submitted; any value supplied by the proposer is overwritten.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub wasm_module_hash: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The SHA-256 hash of `arg`. Set by governance when the proposal is
+    /// submitted; any value supplied by the proposer is overwritten.
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub arg_hash: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Nested message and enum types in `InstallCode`.
+pub mod install_code {
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        serde::Serialize,
+        comparable::Comparable,
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration,
+    )]
+    #[repr(i32)]
+    pub enum CanisterInstallMode {
+        Unspecified = 0,
+        Install = 1,
+        Reinstall = 2,
+        Upgrade = 3,
+    }
+    impl CanisterInstallMode {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                CanisterInstallMode::Unspecified => "CANISTER_INSTALL_MODE_UNSPECIFIED",
+                CanisterInstallMode::Install => "CANISTER_INSTALL_MODE_INSTALL",
+                CanisterInstallMode::Reinstall => "CANISTER_INSTALL_MODE_REINSTALL",
+                CanisterInstallMode::Upgrade => "CANISTER_INSTALL_MODE_UPGRADE",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "CANISTER_INSTALL_MODE_UNSPECIFIED" => Some(Self::Unspecified),
+                "CANISTER_INSTALL_MODE_INSTALL" => Some(Self::Install),
+                "CANISTER_INSTALL_MODE_REINSTALL" => Some(Self::Reinstall),
+                "CANISTER_INSTALL_MODE_UPGRADE" => Some(Self::Upgrade),
+                _ => None,
+            }
+        }
+    }
+}
+/// Stops or starts an NNS canister.
+#[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StopOrStartCanister {
+    /// The canister to stop or start. Must be an NNS canister.
+    #[prost(message, optional, tag = "1")]
+    pub canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// Whether the canister should be stopped or started.
+    #[prost(
+        enumeration = "stop_or_start_canister::CanisterAction",
+        optional,
+        tag = "2"
+    )]
+    pub action: ::core::option::Option<i32>,
+}
+/// Nested message and enum types in `StopOrStartCanister`.
+pub mod stop_or_start_canister {
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        serde::Serialize,
+        comparable::Comparable,
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration,
+    )]
+    #[repr(i32)]
+    pub enum CanisterAction {
+        Unspecified = 0,
+        Stop = 1,
+        Start = 2,
+    }
+    impl CanisterAction {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                CanisterAction::Unspecified => "CANISTER_ACTION_UNSPECIFIED",
+                CanisterAction::Stop => "CANISTER_ACTION_STOP",
+                CanisterAction::Start => "CANISTER_ACTION_START",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "CANISTER_ACTION_UNSPECIFIED" => Some(Self::Unspecified),
+                "CANISTER_ACTION_STOP" => Some(Self::Stop),
+                "CANISTER_ACTION_START" => Some(Self::Start),
+                _ => None,
+            }
+        }
+    }
+}
+/// Changes the settings of an NNS canister. Only the settings that are set are
+/// changed; the others keep their current values.
+#[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateCanisterSettings {
+    /// The canister whose settings are to be changed. Must be an NNS canister.
+    #[prost(message, optional, tag = "1")]
+    pub canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// The new settings.
+    #[prost(message, optional, tag = "2")]
+    pub settings: ::core::option::Option<update_canister_settings::CanisterSettings>,
+}
+/// Nested message and enum types in `UpdateCanisterSettings`.
+pub mod update_canister_settings {
+    /// Wraps the list of controllers, so that "no change" can be distinguished
+    /// from "no controllers".
+    #[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Controllers {
+        #[prost(message, repeated, tag = "1")]
+        pub controllers: ::prost::alloc::vec::Vec<::ic_base_types::PrincipalId>,
+    }
+    #[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CanisterSettings {
+        #[prost(message, optional, tag = "1")]
+        pub controllers: ::core::option::Option<Controllers>,
+        #[prost(uint64, optional, tag = "2")]
+        pub compute_allocation: ::core::option::Option<u64>,
+        #[prost(uint64, optional, tag = "3")]
+        pub memory_allocation: ::core::option::Option<u64>,
+        #[prost(uint64, optional, tag = "4")]
+        pub freezing_threshold: ::core::option::Option<u64>,
+        #[prost(enumeration = "LogVisibility", optional, tag = "5")]
+        pub log_visibility: ::core::option::Option<i32>,
+    }
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        serde::Serialize,
+        comparable::Comparable,
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration,
+    )]
+    #[repr(i32)]
+    pub enum LogVisibility {
+        Unspecified = 0,
+        Controllers = 1,
+        Public = 2,
+    }
+    impl LogVisibility {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                LogVisibility::Unspecified => "LOG_VISIBILITY_UNSPECIFIED",
+                LogVisibility::Controllers => "LOG_VISIBILITY_CONTROLLERS",
+                LogVisibility::Public => "LOG_VISIBILITY_PUBLIC",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "LOG_VISIBILITY_UNSPECIFIED" => Some(Self::Unspecified),
+                "LOG_VISIBILITY_CONTROLLERS" => Some(Self::Controllers),
+                "LOG_VISIBILITY_PUBLIC" => Some(Self::Public),
+                _ => None,
+            }
+        }
+    }
+}
 /// A proposal is the immutable input of a proposal submission. This contains
 /// all the information from the original proposal submission.
 ///
@@ -550,7 +778,7 @@ pub struct Proposal {
     /// take.
     #[prost(
         oneof = "proposal::Action",
-        tags = "10, 12, 13, 14, 15, 16, 17, 18, 19, 21, 22, 23, 24"
+        tags = "10, 12, 13, 14, 15, 16, 17, 18, 19, 21, 22, 23, 24, 25, 26, 27"
     )]
     pub action: ::core::option::Option<proposal::Action>,
 }
@@ -635,6 +863,15 @@ pub mod proposal {
         /// Create a new SNS.
         #[prost(message, tag = "24")]
         CreateServiceNervousSystem(super::CreateServiceNervousSystem),
+        /// Install, reinstall or upgrade the code of an NNS canister.
+        #[prost(message, tag = "25")]
+        InstallCode(super::InstallCode),
+        /// Stop or start an NNS canister.
+        #[prost(message, tag = "26")]
+        StopOrStartCanister(super::StopOrStartCanister),
+        /// Update the settings of an NNS canister.
+        #[prost(message, tag = "27")]
+        UpdateCanisterSettings(super::UpdateCanisterSettings),
     }
 }
 /// Empty message to use in oneof fields that represent empty
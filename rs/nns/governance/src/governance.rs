@@ -48,7 +48,9 @@ use crate::{
         SettleNeuronsFundParticipationResponse, Tally, Topic, UpdateNodeProvider, Vote,
         WaitForQuietState,
     },
-    proposals::create_service_nervous_system::ExecutedCreateServiceNervousSystemProposal,
+    proposals::{
+        create_service_nervous_system::ExecutedCreateServiceNervousSystemProposal, CallCanister,
+    },
     storage::with_stable_neuron_store,
 };
 use async_trait::async_trait;
@@ -771,6 +773,9 @@ impl Proposal {
                     Topic::SnsAndCommunityFund
                 }
                 Action::CreateServiceNervousSystem(_) => Topic::SnsAndCommunityFund,
+                Action::InstallCode(_)
+                | Action::StopOrStartCanister(_)
+                | Action::UpdateCanisterSettings(_) => Topic::NetworkCanisterManagement,
             }
         } else {
             println!("{}ERROR: No action -> no topic.", LOG_PREFIX);
@@ -805,6 +810,7 @@ impl Action {
                     None => false,
                 }
             }
+            Action::InstallCode(install_code) => install_code.allowed_when_resources_are_low(),
             _ => false,
         }
     }
@@ -831,6 +837,9 @@ impl Action {
                 }
                 Action::ExecuteNnsFunction(execute_nns_function)
             }
+            Action::InstallCode(install_code) => {
+                Action::InstallCode(install_code.omit_large_fields())
+            }
             action => action,
         }
     }
@@ -3369,11 +3378,17 @@ impl Governance {
 
         // If this is part of a "multi" query and an ExecuteNnsFunction
         // proposal then remove the payload if the payload is larger
-        // than EXECUTE_NNS_FUNCTION_PAYLOAD_LISTING_BYTES_MAX.
+        // than EXECUTE_NNS_FUNCTION_PAYLOAD_LISTING_BYTES_MAX. For an
+        // InstallCode proposal, remove the wasm module and arg, but keep
+        // their hashes.
         let proposal = if multi_query {
             if let Some(
                 proposal @ Proposal {
-                    action: Some(proposal::Action::ExecuteNnsFunction(_)),
+                    action:
+                        Some(
+                            proposal::Action::ExecuteNnsFunction(_)
+                            | proposal::Action::InstallCode(_),
+                        ),
                     ..
                 },
             ) = data.proposal.clone()
@@ -4208,6 +4223,15 @@ impl Governance {
                 self.create_service_nervous_system(pid, create_service_nervous_system)
                     .await;
             }
+            Action::InstallCode(ref install_code) => {
+                self.call_canister(pid, install_code).await;
+            }
+            Action::StopOrStartCanister(ref stop_or_start_canister) => {
+                self.call_canister(pid, stop_or_start_canister).await;
+            }
+            Action::UpdateCanisterSettings(ref update_canister_settings) => {
+                self.call_canister(pid, update_canister_settings).await;
+            }
 
             Action::SetSnsTokenSwapOpenTimeWindow(obsolete_action) => {
                 self.perform_obsolete_action(pid, obsolete_action);
@@ -4218,6 +4242,36 @@ impl Governance {
         }
     }
 
+    /// Executes a proposal whose action is to call a method of another canister
+    /// (typically root or lifeline), and sets the execution status of the proposal
+    /// based on the outcome of the call.
+    async fn call_canister(&mut self, proposal_id: u64, action: &impl CallCanister) {
+        let result = self.do_call_canister(action).await;
+        self.set_proposal_execution_status(proposal_id, result);
+    }
+
+    async fn do_call_canister(
+        &mut self,
+        action: &impl CallCanister,
+    ) -> Result<(), GovernanceError> {
+        let (canister_id, function) = action.canister_and_function()?;
+        let payload = action.payload()?;
+        let reply = self
+            .env
+            .call_canister_method(canister_id, function, payload)
+            .await
+            .map_err(|(code, message)| {
+                GovernanceError::new_with_message(
+                    ErrorType::External,
+                    format!(
+                        "Error calling method {} of canister {}. Code: {:?}. Message: {}",
+                        function, canister_id, code, message
+                    ),
+                )
+            })?;
+        action.process_reply(reply)
+    }
+
     /// Fails immediately, because this type of proposal is obsolete.
     fn perform_obsolete_action<T>(&mut self, proposal_id: u64, obsolete_action: T)
     where
@@ -4559,6 +4613,13 @@ impl Governance {
             Action::ManageNeuron(manage_neuron) => {
                 self.validate_manage_neuron_proposal(manage_neuron)
             }
+            Action::InstallCode(install_code) => install_code.validate(),
+            Action::StopOrStartCanister(stop_or_start_canister) => {
+                stop_or_start_canister.validate()
+            }
+            Action::UpdateCanisterSettings(update_canister_settings) => {
+                update_canister_settings.validate()
+            }
             Action::ManageNetworkEconomics(_)
             | Action::ApproveGenesisKyc(_)
             | Action::AddOrRemoveNodeProvider(_)
@@ -4897,6 +4958,13 @@ impl Governance {
                 title,
                 ..proposal.clone()
             }
+        } else if let Action::InstallCode(ref install_code) = action {
+            // The hashes are computed here rather than trusted from the proposer,
+            // so that they can be shown in place of the wasm module and arg.
+            Proposal {
+                action: Some(Action::InstallCode(install_code.clone().with_hashes())),
+                ..proposal.clone()
+            }
         } else {
             proposal.clone()
         };
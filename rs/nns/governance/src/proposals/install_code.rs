@@ -0,0 +1,246 @@
+use crate::{
+    pb::v1::{install_code::CanisterInstallMode, GovernanceError, InstallCode},
+    proposals::{invalid_proposal_error, valid_nns_canister_id, CallCanister},
+};
+
+use candid::Encode;
+use ic_base_types::CanisterId;
+use ic_crypto_sha2::Sha256;
+use ic_management_canister_types::CanisterInstallMode as RootCanisterInstallMode;
+use ic_nervous_system_root::change_canister::ChangeCanisterRequest;
+use ic_nns_constants::{LIFELINE_CANISTER_ID, ROOT_CANISTER_ID};
+use ic_nns_handler_lifeline_interface::UpgradeRootProposal;
+
+impl InstallCode {
+    pub fn validate(&self) -> Result<(), GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        let install_mode = self.valid_install_mode()?;
+        let _ = self.valid_wasm_module()?;
+
+        // The root canister is changed through the lifeline canister, which only knows how to
+        // upgrade it.
+        if canister_id == ROOT_CANISTER_ID && install_mode != RootCanisterInstallMode::Upgrade {
+            return Err(invalid_proposal_error(
+                "The root canister can only be upgraded, not installed or reinstalled",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn valid_canister_id(&self) -> Result<CanisterId, GovernanceError> {
+        valid_nns_canister_id(&self.canister_id)
+    }
+
+    fn valid_install_mode(&self) -> Result<RootCanisterInstallMode, GovernanceError> {
+        let install_mode = self
+            .install_mode
+            .and_then(|install_mode| CanisterInstallMode::try_from(install_mode).ok());
+        match install_mode {
+            Some(CanisterInstallMode::Install) => Ok(RootCanisterInstallMode::Install),
+            Some(CanisterInstallMode::Reinstall) => Ok(RootCanisterInstallMode::Reinstall),
+            Some(CanisterInstallMode::Upgrade) => Ok(RootCanisterInstallMode::Upgrade),
+            Some(CanisterInstallMode::Unspecified) | None => Err(invalid_proposal_error(
+                "Unspecified or unknown install mode",
+            )),
+        }
+    }
+
+    fn valid_wasm_module(&self) -> Result<&Vec<u8>, GovernanceError> {
+        match &self.wasm_module {
+            Some(wasm_module) if !wasm_module.is_empty() => Ok(wasm_module),
+            _ => Err(invalid_proposal_error("Wasm module is required")),
+        }
+    }
+
+    /// The argument to install the code with. An unset argument means "no arguments", i.e. the
+    /// Candid encoding of the empty tuple.
+    fn arg_or_default(&self) -> Vec<u8> {
+        self.arg.clone().unwrap_or_else(|| Encode!().unwrap())
+    }
+
+    /// Replaces `wasm_module_hash` and `arg_hash` with the hashes of `wasm_module` and `arg`,
+    /// so that the proposal can still be recognized after the large fields are omitted.
+    pub(crate) fn with_hashes(self) -> Self {
+        let wasm_module_hash = self
+            .wasm_module
+            .as_ref()
+            .map(|wasm_module| Sha256::hash(wasm_module).to_vec());
+        let arg_hash = Some(Sha256::hash(&self.arg_or_default()).to_vec());
+        Self {
+            wasm_module_hash,
+            arg_hash,
+            ..self
+        }
+    }
+
+    /// Removes `wasm_module` and `arg`, keeping their hashes.
+    pub(crate) fn omit_large_fields(self) -> Self {
+        Self {
+            wasm_module: None,
+            arg: None,
+            ..self
+        }
+    }
+
+    /// Upgrades are allowed when resources are low, so that a fix can still be shipped.
+    pub(crate) fn allowed_when_resources_are_low(&self) -> bool {
+        self.valid_install_mode().ok() == Some(RootCanisterInstallMode::Upgrade)
+    }
+}
+
+impl CallCanister for InstallCode {
+    fn canister_and_function(&self) -> Result<(CanisterId, &str), GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        if canister_id == ROOT_CANISTER_ID {
+            Ok((LIFELINE_CANISTER_ID, "upgrade_root"))
+        } else {
+            Ok((ROOT_CANISTER_ID, "change_nns_canister"))
+        }
+    }
+
+    fn payload(&self) -> Result<Vec<u8>, GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        let install_mode = self.valid_install_mode()?;
+        let wasm_module = self.valid_wasm_module()?.clone();
+        let arg = self.arg_or_default();
+        let stop_before_installing = !self.skip_stopping_before_installing.unwrap_or(false);
+
+        let payload = if canister_id == ROOT_CANISTER_ID {
+            Encode!(&UpgradeRootProposal {
+                wasm_module,
+                module_arg: arg,
+                stop_upgrade_start: stop_before_installing,
+            })
+        } else {
+            Encode!(
+                &ChangeCanisterRequest::new(stop_before_installing, install_mode, canister_id)
+                    .with_wasm(wasm_module)
+                    .with_arg(arg)
+            )
+        };
+        payload.map_err(|e| invalid_proposal_error(&format!("Failed to encode payload: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Decode;
+    use ic_base_types::PrincipalId;
+    use ic_nns_constants::{GOVERNANCE_CANISTER_ID, REGISTRY_CANISTER_ID};
+
+    fn basic_install_code() -> InstallCode {
+        InstallCode {
+            canister_id: Some(REGISTRY_CANISTER_ID.get()),
+            install_mode: Some(CanisterInstallMode::Upgrade as i32),
+            wasm_module: Some(vec![1, 2, 3]),
+            arg: Some(vec![4, 5, 6]),
+            skip_stopping_before_installing: None,
+            wasm_module_hash: None,
+            arg_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_invalid_install_code() {
+        let is_invalid = |install_code: InstallCode| install_code.validate().is_err();
+
+        assert!(is_invalid(InstallCode {
+            canister_id: None,
+            ..basic_install_code()
+        }));
+        assert!(is_invalid(InstallCode {
+            canister_id: Some(PrincipalId::new_user_test_id(1)),
+            ..basic_install_code()
+        }));
+        assert!(is_invalid(InstallCode {
+            install_mode: None,
+            ..basic_install_code()
+        }));
+        assert!(is_invalid(InstallCode {
+            install_mode: Some(CanisterInstallMode::Unspecified as i32),
+            ..basic_install_code()
+        }));
+        assert!(is_invalid(InstallCode {
+            wasm_module: Some(vec![]),
+            ..basic_install_code()
+        }));
+        assert!(is_invalid(InstallCode {
+            canister_id: Some(ROOT_CANISTER_ID.get()),
+            install_mode: Some(CanisterInstallMode::Reinstall as i32),
+            ..basic_install_code()
+        }));
+    }
+
+    #[test]
+    fn test_upgrade_nns_canister_through_root() {
+        let install_code = InstallCode {
+            canister_id: Some(GOVERNANCE_CANISTER_ID.get()),
+            ..basic_install_code()
+        };
+        assert_eq!(install_code.validate(), Ok(()));
+
+        assert_eq!(
+            install_code.canister_and_function(),
+            Ok((ROOT_CANISTER_ID, "change_nns_canister"))
+        );
+        let payload = install_code.payload().unwrap();
+        let request = Decode!(&payload, ChangeCanisterRequest).unwrap();
+        assert!(request.stop_before_installing);
+        assert_eq!(request.mode, RootCanisterInstallMode::Upgrade);
+        assert_eq!(request.canister_id, GOVERNANCE_CANISTER_ID);
+        assert_eq!(request.wasm_module, vec![1, 2, 3]);
+        assert_eq!(request.arg, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_upgrade_root_through_lifeline() {
+        let install_code = InstallCode {
+            canister_id: Some(ROOT_CANISTER_ID.get()),
+            skip_stopping_before_installing: Some(true),
+            ..basic_install_code()
+        };
+        assert_eq!(install_code.validate(), Ok(()));
+
+        assert_eq!(
+            install_code.canister_and_function(),
+            Ok((LIFELINE_CANISTER_ID, "upgrade_root"))
+        );
+        let payload = install_code.payload().unwrap();
+        let request = Decode!(&payload, UpgradeRootProposal).unwrap();
+        assert_eq!(
+            request,
+            UpgradeRootProposal {
+                wasm_module: vec![1, 2, 3],
+                module_arg: vec![4, 5, 6],
+                stop_upgrade_start: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_hashes_and_omit_large_fields() {
+        let install_code = InstallCode {
+            wasm_module_hash: Some(vec![0; 32]),
+            arg: None,
+            ..basic_install_code()
+        }
+        .with_hashes();
+
+        assert_eq!(
+            install_code.wasm_module_hash,
+            Some(Sha256::hash(&[1, 2, 3]).to_vec())
+        );
+        assert_eq!(
+            install_code.arg_hash,
+            Some(Sha256::hash(&Encode!().unwrap()).to_vec())
+        );
+
+        let install_code = install_code.omit_large_fields();
+        assert_eq!(install_code.wasm_module, None);
+        assert_eq!(install_code.arg, None);
+        assert!(install_code.wasm_module_hash.is_some());
+        assert!(install_code.arg_hash.is_some());
+    }
+}
@@ -1,2 +1,43 @@
+use crate::pb::v1::{governance_error::ErrorType, GovernanceError};
+
+use ic_base_types::{CanisterId, PrincipalId};
+use ic_nns_constants::ALL_NNS_CANISTER_IDS;
+
 pub mod create_service_nervous_system;
+pub mod install_code;
 pub mod proposal_submission;
+pub mod stop_or_start_canister;
+pub mod update_canister_settings;
+
+/// A proposal action that is executed by calling a method of another canister.
+pub trait CallCanister {
+    /// The canister and method to call when the proposal is executed.
+    fn canister_and_function(&self) -> Result<(CanisterId, &str), GovernanceError>;
+
+    /// The (Candid-encoded) argument of the call.
+    fn payload(&self) -> Result<Vec<u8>, GovernanceError>;
+
+    /// Interprets the reply of the call. By default, any reply means success.
+    fn process_reply(&self, _reply: Vec<u8>) -> Result<(), GovernanceError> {
+        Ok(())
+    }
+}
+
+fn invalid_proposal_error(reason: &str) -> GovernanceError {
+    GovernanceError::new_with_message(ErrorType::InvalidProposal, reason)
+}
+
+/// Returns the canister ID if it is set and refers to an NNS canister.
+fn valid_nns_canister_id(canister_id: &Option<PrincipalId>) -> Result<CanisterId, GovernanceError> {
+    let canister_id =
+        canister_id.ok_or_else(|| invalid_proposal_error("Canister ID is required"))?;
+    let canister_id = CanisterId::try_from(canister_id)
+        .map_err(|_| invalid_proposal_error(&format!("Invalid canister ID {}", canister_id)))?;
+    if !ALL_NNS_CANISTER_IDS.contains(&&canister_id) {
+        return Err(invalid_proposal_error(&format!(
+            "Canister {} is not an NNS canister",
+            canister_id
+        )));
+    }
+    Ok(canister_id)
+}
@@ -0,0 +1,120 @@
+use crate::{
+    pb::v1::{stop_or_start_canister::CanisterAction, GovernanceError, StopOrStartCanister},
+    proposals::{invalid_proposal_error, valid_nns_canister_id, CallCanister},
+};
+
+use candid::Encode;
+use ic_base_types::CanisterId;
+use ic_nervous_system_root::change_canister::{
+    CanisterAction as RootCanisterAction, StopOrStartCanisterRequest,
+};
+use ic_nns_constants::{GOVERNANCE_CANISTER_ID, LIFELINE_CANISTER_ID, ROOT_CANISTER_ID};
+
+impl StopOrStartCanister {
+    pub fn validate(&self) -> Result<(), GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        let action = self.valid_action()?;
+
+        // Same as in the root canister: a stopped governance, root or lifeline canister could only
+        // be started again by executing a proposal, which requires all of them to be running.
+        let is_canister_disallowed_to_stop = [
+            GOVERNANCE_CANISTER_ID,
+            ROOT_CANISTER_ID,
+            LIFELINE_CANISTER_ID,
+        ]
+        .contains(&canister_id);
+        if action == RootCanisterAction::Stop && is_canister_disallowed_to_stop {
+            return Err(invalid_proposal_error(
+                "Stopping the governance, root, or lifeline canister is not allowed",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn valid_canister_id(&self) -> Result<CanisterId, GovernanceError> {
+        valid_nns_canister_id(&self.canister_id)
+    }
+
+    fn valid_action(&self) -> Result<RootCanisterAction, GovernanceError> {
+        let action = self
+            .action
+            .and_then(|action| CanisterAction::try_from(action).ok());
+        match action {
+            Some(CanisterAction::Stop) => Ok(RootCanisterAction::Stop),
+            Some(CanisterAction::Start) => Ok(RootCanisterAction::Start),
+            Some(CanisterAction::Unspecified) | None => {
+                Err(invalid_proposal_error("Unspecified or unknown action"))
+            }
+        }
+    }
+}
+
+impl CallCanister for StopOrStartCanister {
+    fn canister_and_function(&self) -> Result<(CanisterId, &str), GovernanceError> {
+        Ok((ROOT_CANISTER_ID, "stop_or_start_nns_canister"))
+    }
+
+    fn payload(&self) -> Result<Vec<u8>, GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        let action = self.valid_action()?;
+
+        Encode!(&StopOrStartCanisterRequest {
+            canister_id,
+            action,
+        })
+        .map_err(|e| invalid_proposal_error(&format!("Failed to encode payload: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Decode;
+    use ic_nns_constants::SNS_WASM_CANISTER_ID;
+
+    #[test]
+    fn test_invalid_stop_or_start_canister() {
+        let is_invalid = |canister_id: Option<CanisterId>, action: Option<CanisterAction>| {
+            StopOrStartCanister {
+                canister_id: canister_id.map(|canister_id| canister_id.get()),
+                action: action.map(|action| action as i32),
+            }
+            .validate()
+            .is_err()
+        };
+
+        assert!(is_invalid(None, Some(CanisterAction::Stop)));
+        assert!(is_invalid(Some(SNS_WASM_CANISTER_ID), None));
+        assert!(is_invalid(
+            Some(SNS_WASM_CANISTER_ID),
+            Some(CanisterAction::Unspecified)
+        ));
+        for canister_id in [
+            GOVERNANCE_CANISTER_ID,
+            ROOT_CANISTER_ID,
+            LIFELINE_CANISTER_ID,
+        ] {
+            assert!(is_invalid(Some(canister_id), Some(CanisterAction::Stop)));
+            assert!(!is_invalid(Some(canister_id), Some(CanisterAction::Start)));
+        }
+    }
+
+    #[test]
+    fn test_stop_or_start_canister_payload() {
+        let stop_or_start_canister = StopOrStartCanister {
+            canister_id: Some(SNS_WASM_CANISTER_ID.get()),
+            action: Some(CanisterAction::Stop as i32),
+        };
+        assert_eq!(stop_or_start_canister.validate(), Ok(()));
+
+        assert_eq!(
+            stop_or_start_canister.canister_and_function(),
+            Ok((ROOT_CANISTER_ID, "stop_or_start_nns_canister"))
+        );
+        let payload = stop_or_start_canister.payload().unwrap();
+        let request = Decode!(&payload, StopOrStartCanisterRequest).unwrap();
+        assert_eq!(request.canister_id, SNS_WASM_CANISTER_ID);
+        assert_eq!(request.action, RootCanisterAction::Stop);
+    }
+}
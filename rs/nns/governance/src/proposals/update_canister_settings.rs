@@ -0,0 +1,223 @@
+use crate::{
+    pb::v1::{
+        governance_error::ErrorType,
+        update_canister_settings::{CanisterSettings, LogVisibility},
+        GovernanceError, UpdateCanisterSettings,
+    },
+    proposals::{invalid_proposal_error, valid_nns_canister_id, CallCanister},
+};
+
+use candid::{Decode, Encode, Nat};
+use ic_base_types::CanisterId;
+use ic_nervous_system_clients::update_settings::{
+    CanisterSettings as RootCanisterSettings, LogVisibility as RootLogVisibility,
+};
+use ic_nns_constants::ROOT_CANISTER_ID;
+use ic_nns_handler_root_interface::{
+    UpdateCanisterSettingsRequest, UpdateCanisterSettingsResponse,
+};
+
+impl UpdateCanisterSettings {
+    pub fn validate(&self) -> Result<(), GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        let _ = self.valid_settings()?;
+
+        // The root canister is controlled by the lifeline canister, which does not support
+        // changing canister settings.
+        if canister_id == ROOT_CANISTER_ID {
+            return Err(invalid_proposal_error(
+                "Changing the settings of the root canister is not supported",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn valid_canister_id(&self) -> Result<CanisterId, GovernanceError> {
+        valid_nns_canister_id(&self.canister_id)
+    }
+
+    fn valid_settings(&self) -> Result<RootCanisterSettings, GovernanceError> {
+        let CanisterSettings {
+            controllers,
+            compute_allocation,
+            memory_allocation,
+            freezing_threshold,
+            log_visibility,
+        } = self
+            .settings
+            .as_ref()
+            .ok_or_else(|| invalid_proposal_error("Settings are required"))?;
+
+        let controllers = controllers
+            .as_ref()
+            .map(|controllers| controllers.controllers.clone());
+        let log_visibility = match log_visibility {
+            None => None,
+            Some(log_visibility) => match LogVisibility::try_from(*log_visibility).ok() {
+                Some(LogVisibility::Controllers) => Some(RootLogVisibility::Controllers),
+                Some(LogVisibility::Public) => Some(RootLogVisibility::Public),
+                Some(LogVisibility::Unspecified) | None => {
+                    return Err(invalid_proposal_error(
+                        "Unspecified or unknown log visibility",
+                    ));
+                }
+            },
+        };
+
+        let settings = RootCanisterSettings {
+            controllers,
+            compute_allocation: compute_allocation.map(Nat::from),
+            memory_allocation: memory_allocation.map(Nat::from),
+            freezing_threshold: freezing_threshold.map(Nat::from),
+            reserved_cycles_limit: None,
+            log_visibility,
+        };
+        if settings == RootCanisterSettings::default() {
+            return Err(invalid_proposal_error(
+                "At least one setting must be changed",
+            ));
+        }
+
+        Ok(settings)
+    }
+}
+
+impl CallCanister for UpdateCanisterSettings {
+    fn canister_and_function(&self) -> Result<(CanisterId, &str), GovernanceError> {
+        Ok((ROOT_CANISTER_ID, "update_canister_settings"))
+    }
+
+    fn payload(&self) -> Result<Vec<u8>, GovernanceError> {
+        let canister_id = self.valid_canister_id()?;
+        let settings = self.valid_settings()?;
+
+        Encode!(&UpdateCanisterSettingsRequest {
+            canister_id: canister_id.get(),
+            settings,
+        })
+        .map_err(|e| invalid_proposal_error(&format!("Failed to encode payload: {}", e)))
+    }
+
+    fn process_reply(&self, reply: Vec<u8>) -> Result<(), GovernanceError> {
+        let response = Decode!(&reply, UpdateCanisterSettingsResponse).map_err(|e| {
+            GovernanceError::new_with_message(
+                ErrorType::External,
+                format!("Failed to decode UpdateCanisterSettingsResponse: {}", e),
+            )
+        })?;
+        match response {
+            UpdateCanisterSettingsResponse::Ok(()) => Ok(()),
+            UpdateCanisterSettingsResponse::Err(err) => Err(GovernanceError::new_with_message(
+                ErrorType::External,
+                format!(
+                    "Failed to update canister settings (code {:?}): {}",
+                    err.code, err.description
+                ),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::v1::update_canister_settings::Controllers;
+    use ic_base_types::PrincipalId;
+    use ic_nns_constants::LEDGER_CANISTER_ID;
+    use ic_nns_handler_root_interface::UpdateCanisterSettingsError;
+
+    fn basic_update_canister_settings() -> UpdateCanisterSettings {
+        UpdateCanisterSettings {
+            canister_id: Some(LEDGER_CANISTER_ID.get()),
+            settings: Some(CanisterSettings {
+                controllers: Some(Controllers {
+                    controllers: vec![ROOT_CANISTER_ID.get()],
+                }),
+                compute_allocation: None,
+                memory_allocation: Some(1 << 32),
+                freezing_threshold: None,
+                log_visibility: Some(LogVisibility::Public as i32),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_invalid_update_canister_settings() {
+        let is_invalid = |update_canister_settings: UpdateCanisterSettings| {
+            update_canister_settings.validate().is_err()
+        };
+
+        assert!(is_invalid(UpdateCanisterSettings {
+            canister_id: None,
+            ..basic_update_canister_settings()
+        }));
+        assert!(is_invalid(UpdateCanisterSettings {
+            canister_id: Some(PrincipalId::new_user_test_id(1)),
+            ..basic_update_canister_settings()
+        }));
+        assert!(is_invalid(UpdateCanisterSettings {
+            canister_id: Some(ROOT_CANISTER_ID.get()),
+            ..basic_update_canister_settings()
+        }));
+        assert!(is_invalid(UpdateCanisterSettings {
+            settings: None,
+            ..basic_update_canister_settings()
+        }));
+        assert!(is_invalid(UpdateCanisterSettings {
+            settings: Some(CanisterSettings::default()),
+            ..basic_update_canister_settings()
+        }));
+        assert!(is_invalid(UpdateCanisterSettings {
+            settings: Some(CanisterSettings {
+                log_visibility: Some(LogVisibility::Unspecified as i32),
+                ..CanisterSettings::default()
+            }),
+            ..basic_update_canister_settings()
+        }));
+    }
+
+    #[test]
+    fn test_update_canister_settings_payload() {
+        let update_canister_settings = basic_update_canister_settings();
+        assert_eq!(update_canister_settings.validate(), Ok(()));
+
+        assert_eq!(
+            update_canister_settings.canister_and_function(),
+            Ok((ROOT_CANISTER_ID, "update_canister_settings"))
+        );
+        let payload = update_canister_settings.payload().unwrap();
+        let request = Decode!(&payload, UpdateCanisterSettingsRequest).unwrap();
+        assert_eq!(
+            request,
+            UpdateCanisterSettingsRequest {
+                canister_id: LEDGER_CANISTER_ID.get(),
+                settings: RootCanisterSettings {
+                    controllers: Some(vec![ROOT_CANISTER_ID.get()]),
+                    memory_allocation: Some(Nat::from(1_u64 << 32)),
+                    log_visibility: Some(RootLogVisibility::Public),
+                    ..Default::default()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_update_canister_settings_process_reply() {
+        let update_canister_settings = basic_update_canister_settings();
+
+        let ok = Encode!(&UpdateCanisterSettingsResponse::Ok(())).unwrap();
+        assert_eq!(update_canister_settings.process_reply(ok), Ok(()));
+
+        let err = Encode!(&UpdateCanisterSettingsResponse::Err(
+            UpdateCanisterSettingsError {
+                code: Some(3),
+                description: "Canister not found".to_string(),
+            }
+        ))
+        .unwrap();
+        let err = update_canister_settings.process_reply(err).unwrap_err();
+        assert_eq!(err.error_type, ErrorType::External as i32);
+        assert!(err.error_message.contains("Canister not found"));
+    }
+}
@@ -58,7 +58,7 @@ use ic_nns_governance::{
         governance_error::ErrorType::{
             self, InsufficientFunds, NotAuthorized, NotFound, PreconditionFailed, ResourceExhausted,
         },
-        manage_neuron,
+        install_code, manage_neuron,
         manage_neuron::{
             claim_or_refresh::{By, MemoAndController},
             configure::Operation,
@@ -73,11 +73,11 @@ use ic_nns_governance::{
         neurons_fund_snapshot::NeuronsFundNeuronPortion,
         proposal::{self, Action, ActionDesc},
         reward_node_provider::{RewardMode, RewardToAccount, RewardToNeuron},
-        settle_neurons_fund_participation_request, swap_background_information,
-        AddOrRemoveNodeProvider, ApproveGenesisKyc, Ballot, BallotChange, BallotInfo,
-        BallotInfoChange, CreateServiceNervousSystem, Empty, ExecuteNnsFunction,
-        Governance as GovernanceProto, GovernanceChange, GovernanceError,
-        IdealMatchedParticipationFunction, KnownNeuron, KnownNeuronData, ListNeurons,
+        settle_neurons_fund_participation_request, stop_or_start_canister,
+        swap_background_information, AddOrRemoveNodeProvider, ApproveGenesisKyc, Ballot,
+        BallotChange, BallotInfo, BallotInfoChange, CreateServiceNervousSystem, Empty,
+        ExecuteNnsFunction, Governance as GovernanceProto, GovernanceChange, GovernanceError,
+        IdealMatchedParticipationFunction, InstallCode, KnownNeuron, KnownNeuronData, ListNeurons,
         ListNeuronsResponse, ListProposalInfo, ListProposalInfoResponse, ManageNeuron,
         ManageNeuronResponse, MostRecentMonthlyNodeProviderRewards, Motion, NetworkEconomics,
         Neuron, NeuronChange, NeuronState, NeuronType, NeuronsFundData, NeuronsFundParticipation,
@@ -86,9 +86,9 @@ use ic_nns_governance::{
         ProposalRewardStatus::{self, AcceptVotes, ReadyToSettle},
         ProposalStatus::{self, Rejected},
         RewardEvent, RewardNodeProvider, RewardNodeProviders, SetDefaultFollowees,
-        SettleNeuronsFundParticipationRequest, SwapBackgroundInformation, SwapParticipationLimits,
-        Tally, TallyChange, Topic, UpdateNodeProvider, Vote, WaitForQuietState,
-        WaitForQuietStateDesc,
+        SettleNeuronsFundParticipationRequest, StopOrStartCanister, SwapBackgroundInformation,
+        SwapParticipationLimits, Tally, TallyChange, Topic, UpdateCanisterSettings,
+        UpdateNodeProvider, Vote, WaitForQuietState, WaitForQuietStateDesc,
     },
     proposals::create_service_nervous_system::ExecutedCreateServiceNervousSystemProposal,
 };
@@ -7606,6 +7606,121 @@ fn test_list_proposals_retains_execute_nns_function_payload() {
     );
 }
 
+#[test]
+fn test_install_code_proposal_hashes_and_listing() {
+    // ARRANGE
+    let fake_driver = fake::FakeDriver::default();
+    let mut gov = Governance::new(
+        fixture_two_neurons_second_is_bigger(),
+        fake_driver.get_fake_env(),
+        fake_driver.get_fake_ledger(),
+        fake_driver.get_fake_cmc(),
+    );
+    let wasm_module = vec![0, 97, 115, 109, 1, 0, 0, 0];
+    let arg = vec![42; 100];
+    let install_code = InstallCode {
+        canister_id: Some(ICP_LEDGER_CANISTER_ID.get()),
+        install_mode: Some(install_code::CanisterInstallMode::Upgrade as i32),
+        wasm_module: Some(wasm_module.clone()),
+        arg: Some(arg.clone()),
+        skip_stopping_before_installing: None,
+        // Supplied hashes are ignored.
+        wasm_module_hash: Some(vec![1; 32]),
+        arg_hash: None,
+    };
+
+    // ACT
+    // Submitted by the smaller neuron, so that the proposal stays open.
+    let proposal_id = gov
+        .make_proposal(
+            &NeuronId { id: 1 },
+            &principal(1),
+            &Proposal {
+                title: Some("Upgrade the ledger".to_string()),
+                summary: "Upgrade the ledger canister".to_string(),
+                action: Some(proposal::Action::InstallCode(install_code.clone())),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    // ASSERT
+    let expected_hashes = InstallCode {
+        wasm_module_hash: Some(Sha256::hash(&wasm_module).to_vec()),
+        arg_hash: Some(Sha256::hash(&arg).to_vec()),
+        ..install_code
+    };
+    let proposal_info = gov.get_proposal_info(&principal(1), proposal_id).unwrap();
+    assert_eq!(proposal_info.topic, Topic::NetworkCanisterManagement as i32);
+    assert_eq!(
+        proposal_info.proposal.unwrap().action,
+        Some(proposal::Action::InstallCode(expected_hashes.clone()))
+    );
+
+    let results = gov.list_proposals(&principal(1), &ListProposalInfo::default());
+    assert_eq!(
+        results.proposal_info[0].proposal.as_ref().unwrap().action,
+        Some(proposal::Action::InstallCode(InstallCode {
+            wasm_module: None,
+            arg: None,
+            ..expected_hashes
+        }))
+    );
+}
+
+#[test]
+fn test_invalid_canister_management_proposals_are_rejected() {
+    // ARRANGE
+    let fake_driver = fake::FakeDriver::default();
+    let mut gov = Governance::new(
+        fixture_two_neurons_second_is_bigger(),
+        fake_driver.get_fake_env(),
+        fake_driver.get_fake_ledger(),
+        fake_driver.get_fake_cmc(),
+    );
+    let mut make_proposal = |action| {
+        gov.make_proposal(
+            &NeuronId { id: 1 },
+            &principal(1),
+            &Proposal {
+                title: Some("A Reasonable Title".to_string()),
+                summary: "Manage an NNS canister".to_string(),
+                action: Some(action),
+                ..Default::default()
+            },
+        )
+    };
+
+    // ACT & ASSERT
+    let not_an_nns_canister = InstallCode {
+        canister_id: Some(principal(1)),
+        install_mode: Some(install_code::CanisterInstallMode::Upgrade as i32),
+        wasm_module: Some(vec![1, 2, 3]),
+        ..Default::default()
+    };
+    let stop_governance = StopOrStartCanister {
+        canister_id: Some(GOVERNANCE_CANISTER_ID.get()),
+        action: Some(stop_or_start_canister::CanisterAction::Stop as i32),
+    };
+    let no_settings = UpdateCanisterSettings {
+        canister_id: Some(ICP_LEDGER_CANISTER_ID.get()),
+        settings: None,
+    };
+    for action in [
+        proposal::Action::InstallCode(not_an_nns_canister),
+        proposal::Action::StopOrStartCanister(stop_governance),
+        proposal::Action::UpdateCanisterSettings(no_settings),
+    ] {
+        let err = make_proposal(action).unwrap_err();
+        assert_eq!(
+            err.error_type,
+            ErrorType::InvalidProposal as i32,
+            "{:?}",
+            err
+        );
+    }
+}
+
 #[test]
 fn test_get_pending_proposals_removes_execute_nns_function_payload() {
     // ARRANGE
@@ -19,6 +19,7 @@ use ic_nns_common::{
 use ic_nns_constants::SNS_WASM_CANISTER_ID;
 use ic_nns_handler_root_interface::{
     ChangeCanisterControllersRequest, ChangeCanisterControllersResponse,
+    UpdateCanisterSettingsError, UpdateCanisterSettingsRequest, UpdateCanisterSettingsResponse,
 };
 use ic_protobuf::{
     registry::nns::v1::{NnsCanisterRecord, NnsCanisterRecords},
@@ -233,3 +234,29 @@ pub async fn change_canister_controllers(
         }
     }
 }
+
+/// Changes the settings of a canister controlled by NNS Root. Settings that are not set in
+/// `request` keep their current values.
+pub async fn update_canister_settings(
+    request: UpdateCanisterSettingsRequest,
+    management_canister_client: &mut impl ManagementCanisterClient,
+) -> UpdateCanisterSettingsResponse {
+    let update_settings_args = UpdateSettings {
+        canister_id: request.canister_id,
+        settings: request.settings,
+        sender_canister_version: management_canister_client.canister_version(),
+    };
+
+    match management_canister_client
+        .update_settings(update_settings_args)
+        .await
+    {
+        Ok(()) => UpdateCanisterSettingsResponse::Ok(()),
+        Err((code, description)) => {
+            UpdateCanisterSettingsResponse::Err(UpdateCanisterSettingsError {
+                code: Some(code),
+                description,
+            })
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use candid::CandidType;
 use ic_base_types::PrincipalId;
+use ic_nervous_system_clients::update_settings::CanisterSettings;
 use serde::Deserialize;
 
 pub mod client;
@@ -61,3 +62,35 @@ impl ChangeCanisterControllersResponse {
         }
     }
 }
+
+/// The request structure to the `update_canister_settings` API.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, CandidType)]
+pub struct UpdateCanisterSettingsRequest {
+    /// The principal of the canister whose settings are to be changed. This canister must be
+    /// controlled by NNS Root.
+    pub canister_id: PrincipalId,
+
+    /// The settings to change. Settings that are not set keep their current values.
+    pub settings: CanisterSettings,
+}
+
+/// The response of the `update_canister_settings` API.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, CandidType)]
+pub enum UpdateCanisterSettingsResponse {
+    /// The settings were changed.
+    Ok(()),
+
+    /// The settings could not be changed.
+    Err(UpdateCanisterSettingsError),
+}
+
+/// The structure encapsulating errors encountered in the `update_canister_settings` API.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, CandidType)]
+pub struct UpdateCanisterSettingsError {
+    /// The optional error code encountered during execution. This maps to the IC replica error
+    /// codes.
+    pub code: Option<i32>,
+
+    /// A description of the encountered error.
+    pub description: String,
+}
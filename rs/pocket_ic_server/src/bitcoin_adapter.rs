@@ -0,0 +1,393 @@
+//! An in-process stand-in for the bitcoin adapter of the bitcoin subnet.
+//!
+//! Instead of connecting to the Bitcoin network, the stand-in serves a local
+//! regtest blockchain whose blocks are mined on demand via [`LocalBitcoinAdapter::mine_blocks`].
+//! Transactions sent by the bitcoin canister are recorded (and included in the next
+//! mined block) so that tests can inspect them.
+
+use bitcoin::{
+    blockdata::constants::genesis_block,
+    blockdata::script::Builder,
+    consensus::{deserialize, serialize},
+    hashes::Hash,
+    Address, Block, BlockHash, BlockHeader, OutPoint, Script, Transaction, TxIn, TxMerkleNode,
+    TxOut, Txid, Witness,
+};
+use ic_btc_interface::Network;
+use ic_btc_types_internal::{
+    BitcoinAdapterRequestWrapper, BitcoinAdapterResponseWrapper, BitcoinReject,
+    GetSuccessorsRequestInitial, GetSuccessorsResponseComplete, SendTransactionRequest,
+    SendTransactionResponse,
+};
+use ic_state_machine_tests::{BitcoinAdapter, RejectCode};
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+// The maximum number of blocks (and block headers) returned in a single response.
+// NOTE: Should be = the `MAX_BLOCKS_LENGTH` defined in the bitcoin adapter.
+const MAX_BLOCKS_LENGTH: usize = 100;
+
+// The maximum total size of the blocks returned in a single response.
+// NOTE: Should be < the `MAX_RESPONSE_SIZE` defined in `replicated_state/bitcoin.rs`.
+const MAX_BLOCKS_BYTES: usize = 1_000_000;
+
+// The block subsidy is halved every 150 blocks on regtest.
+const SUBSIDY_HALVING_INTERVAL: u32 = 150;
+
+const INITIAL_BLOCK_SUBSIDY: u64 = 50 * 100_000_000;
+
+#[derive(Default)]
+struct ChainState {
+    /// The mined blocks, excluding the genesis block.
+    blocks: Vec<Block>,
+    /// Transactions sent by the bitcoin canister which have not been mined yet.
+    mempool: Vec<Transaction>,
+    /// All transactions sent by the bitcoin canister (in their raw encoding).
+    sent_transactions: Vec<Vec<u8>>,
+}
+
+/// A local regtest blockchain answering the requests of the bitcoin canister.
+pub struct LocalBitcoinAdapter {
+    genesis: Block,
+    state: Mutex<ChainState>,
+}
+
+impl Default for LocalBitcoinAdapter {
+    fn default() -> Self {
+        Self {
+            genesis: genesis_block(bitcoin::Network::Regtest),
+            state: Mutex::new(ChainState::default()),
+        }
+    }
+}
+
+impl LocalBitcoinAdapter {
+    /// Restores the stand-in from the raw blocks and sent transactions
+    /// previously returned by [`LocalBitcoinAdapter::blocks`] and
+    /// [`LocalBitcoinAdapter::sent_transactions`].
+    pub fn from_raw(blocks: Vec<Vec<u8>>, sent_transactions: Vec<Vec<u8>>) -> Result<Self, String> {
+        let blocks = blocks
+            .iter()
+            .map(|block| deserialize(block).map_err(|e| format!("Invalid bitcoin block: {}", e)))
+            .collect::<Result<Vec<Block>, String>>()?;
+        let adapter = Self::default();
+        let mut state = adapter.state.lock().unwrap();
+        state.blocks = blocks;
+        for transaction in sent_transactions {
+            state.record_transaction(transaction);
+        }
+        // Only the transactions which have not been mined yet remain in the mempool.
+        let mined_txids: BTreeSet<Txid> = state
+            .blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter().map(|tx| tx.txid()))
+            .collect();
+        state.mempool.retain(|tx| !mined_txids.contains(&tx.txid()));
+        drop(state);
+        Ok(adapter)
+    }
+
+    /// Mines one block per address on top of the current tip. The coinbase
+    /// transaction of each block pays the block subsidy to the corresponding
+    /// address. The first block also contains all transactions sent by the
+    /// bitcoin canister which have not been mined yet.
+    /// Returns the hashes of the new blocks.
+    pub fn mine_blocks(&self, addresses: Vec<String>, time: u32) -> Result<Vec<Vec<u8>>, String> {
+        let script_pubkeys = addresses
+            .iter()
+            .map(|address| {
+                Address::from_str(address)
+                    .map(|address| address.script_pubkey())
+                    .map_err(|e| format!("Invalid bitcoin address {}: {}", address, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut state = self.state.lock().unwrap();
+        let mut block_hashes = vec![];
+        for script_pubkey in script_pubkeys {
+            let tip = state.blocks.last().unwrap_or(&self.genesis).header;
+            let height = state.blocks.len() as u32 + 1;
+            let mut txdata = vec![coinbase_transaction(height, script_pubkey)];
+            txdata.append(&mut state.mempool);
+            let mut block = Block {
+                header: BlockHeader {
+                    version: 1,
+                    prev_blockhash: tip.block_hash(),
+                    merkle_root: TxMerkleNode::default(),
+                    time: time.max(tip.time + 1),
+                    bits: self.genesis.header.bits,
+                    nonce: 0,
+                },
+                txdata,
+            };
+            block.header.merkle_root = block.compute_merkle_root().unwrap_or_default();
+            solve_proof_of_work(&mut block.header);
+            block_hashes.push(block.block_hash().into_inner().to_vec());
+            state.blocks.push(block);
+        }
+        Ok(block_hashes)
+    }
+
+    /// Returns all mined blocks (excluding the genesis block) in their raw encoding.
+    pub fn blocks(&self) -> Vec<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        state.blocks.iter().map(serialize).collect()
+    }
+
+    /// Returns all transactions sent by the bitcoin canister in their raw encoding.
+    pub fn sent_transactions(&self) -> Vec<Vec<u8>> {
+        self.state.lock().unwrap().sent_transactions.clone()
+    }
+
+    /// Returns the hash of the current tip and the number of sent transactions
+    /// which together identify the state of the stand-in.
+    pub fn state_summary(&self) -> (Vec<u8>, usize) {
+        let state = self.state.lock().unwrap();
+        let tip = state.blocks.last().unwrap_or(&self.genesis);
+        (
+            tip.block_hash().into_inner().to_vec(),
+            state.sent_transactions.len(),
+        )
+    }
+
+    fn get_successors(
+        &self,
+        request: GetSuccessorsRequestInitial,
+    ) -> Result<GetSuccessorsResponseComplete, String> {
+        let anchor = BlockHash::from_slice(&request.anchor)
+            .map_err(|_| "Failed to parse anchor hash.".to_string())?;
+        let processed_block_hashes = request
+            .processed_block_hashes
+            .iter()
+            .map(|hash| BlockHash::from_slice(hash))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "Failed to parse processed block hashes.".to_string())?;
+
+        let state = self.state.lock().unwrap();
+        // The blocks following the anchor (the genesis block is not contained in `blocks`).
+        let successors = if anchor == self.genesis.block_hash() {
+            &state.blocks[..]
+        } else {
+            match state
+                .blocks
+                .iter()
+                .position(|block| block.block_hash() == anchor)
+            {
+                Some(index) => &state.blocks[index + 1..],
+                None => &[],
+            }
+        };
+        let mut successors = successors
+            .iter()
+            .filter(|block| !processed_block_hashes.contains(&block.block_hash()))
+            .peekable();
+
+        let mut blocks = vec![];
+        let mut blocks_bytes = 0;
+        while let Some(block) = successors.peek() {
+            let block = serialize(*block);
+            if blocks.len() >= MAX_BLOCKS_LENGTH
+                || (!blocks.is_empty() && blocks_bytes + block.len() > MAX_BLOCKS_BYTES)
+            {
+                break;
+            }
+            blocks_bytes += block.len();
+            blocks.push(block);
+            successors.next();
+        }
+        let next = successors
+            .take(MAX_BLOCKS_LENGTH)
+            .map(|block| serialize(&block.header))
+            .collect();
+
+        Ok(GetSuccessorsResponseComplete { blocks, next })
+    }
+
+    fn send_transaction(&self, request: SendTransactionRequest) {
+        self.state
+            .lock()
+            .unwrap()
+            .record_transaction(request.transaction);
+    }
+}
+
+impl ChainState {
+    fn record_transaction(&mut self, transaction: Vec<u8>) {
+        // Transactions that cannot be decoded are recorded, but never mined.
+        if let Ok(tx) = deserialize::<Transaction>(&transaction) {
+            self.mempool.push(tx);
+        }
+        self.sent_transactions.push(transaction);
+    }
+}
+
+impl BitcoinAdapter for LocalBitcoinAdapter {
+    fn handle_request(
+        &self,
+        request: BitcoinAdapterRequestWrapper,
+    ) -> BitcoinAdapterResponseWrapper {
+        if !matches!(request.network(), Network::Regtest) {
+            let reject = BitcoinReject {
+                reject_code: RejectCode::CanisterReject,
+                message: "The bitcoin adapter of PocketIC only supports the regtest network."
+                    .to_string(),
+            };
+            return match request {
+                BitcoinAdapterRequestWrapper::GetSuccessorsRequest(_) => {
+                    BitcoinAdapterResponseWrapper::GetSuccessorsReject(reject)
+                }
+                BitcoinAdapterRequestWrapper::SendTransactionRequest(_) => {
+                    BitcoinAdapterResponseWrapper::SendTransactionReject(reject)
+                }
+            };
+        }
+        match request {
+            BitcoinAdapterRequestWrapper::GetSuccessorsRequest(request) => {
+                match self.get_successors(request) {
+                    Ok(response) => BitcoinAdapterResponseWrapper::GetSuccessorsResponse(response),
+                    Err(message) => {
+                        BitcoinAdapterResponseWrapper::GetSuccessorsReject(BitcoinReject {
+                            reject_code: RejectCode::CanisterReject,
+                            message,
+                        })
+                    }
+                }
+            }
+            BitcoinAdapterRequestWrapper::SendTransactionRequest(request) => {
+                self.send_transaction(request);
+                BitcoinAdapterResponseWrapper::SendTransactionResponse(SendTransactionResponse {})
+            }
+        }
+    }
+}
+
+/// Returns a coinbase transaction paying the block subsidy at the given height to the given script.
+fn coinbase_transaction(height: u32, script_pubkey: Script) -> Transaction {
+    let halvings = height / SUBSIDY_HALVING_INTERVAL;
+    let value = if halvings >= 64 {
+        0
+    } else {
+        INITIAL_BLOCK_SUBSIDY >> halvings
+    };
+    Transaction {
+        version: 1,
+        lock_time: 0,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            // The height makes the coinbase transactions of different blocks unique (BIP34).
+            script_sig: Builder::new().push_int(height as i64).into_script(),
+            sequence: 0xffffffff,
+            witness: Witness::default(),
+        }],
+        output: vec![TxOut {
+            value,
+            script_pubkey,
+        }],
+    }
+}
+
+/// Solves the (trivial on regtest) proof-of-work puzzle of the given header.
+fn solve_proof_of_work(header: &mut BlockHeader) {
+    let target = header.target();
+    while header.validate_pow(&target).is_err() {
+        header.nonce += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address() -> String {
+        Address::p2wsh(&Script::new(), bitcoin::Network::Regtest).to_string()
+    }
+
+    fn get_successors(
+        adapter: &LocalBitcoinAdapter,
+        anchor: BlockHash,
+        processed_block_hashes: Vec<BlockHash>,
+    ) -> GetSuccessorsResponseComplete {
+        let request = GetSuccessorsRequestInitial {
+            network: Network::Regtest,
+            anchor: anchor.into_inner().to_vec(),
+            processed_block_hashes: processed_block_hashes
+                .into_iter()
+                .map(|hash| hash.into_inner().to_vec())
+                .collect(),
+        };
+        match adapter.handle_request(BitcoinAdapterRequestWrapper::GetSuccessorsRequest(request)) {
+            BitcoinAdapterResponseWrapper::GetSuccessorsResponse(response) => response,
+            response => panic!("Unexpected response: {:?}", response),
+        }
+    }
+
+    #[test]
+    fn mined_blocks_are_served_as_successors() {
+        let adapter = LocalBitcoinAdapter::default();
+        let genesis_hash = adapter.genesis.block_hash();
+        assert!(get_successors(&adapter, genesis_hash, vec![])
+            .blocks
+            .is_empty());
+
+        let hashes = adapter
+            .mine_blocks(vec![address(), address(), address()], 1_700_000_000)
+            .unwrap();
+        assert_eq!(hashes.len(), 3);
+
+        let blocks: Vec<Block> = get_successors(&adapter, genesis_hash, vec![])
+            .blocks
+            .iter()
+            .map(|block| deserialize(block).unwrap())
+            .collect();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].header.prev_blockhash, genesis_hash);
+        assert_eq!(blocks[1].header.prev_blockhash, blocks[0].block_hash());
+        assert!(blocks[2].header.time > blocks[1].header.time);
+
+        // Processed blocks and blocks up to the anchor are not returned again.
+        let response = get_successors(
+            &adapter,
+            blocks[0].block_hash(),
+            vec![blocks[1].block_hash()],
+        );
+        assert_eq!(response.blocks, vec![serialize(&blocks[2])]);
+    }
+
+    #[test]
+    fn sent_transactions_are_recorded_and_mined() {
+        let adapter = LocalBitcoinAdapter::default();
+        let transaction = serialize(&coinbase_transaction(42, Script::new()));
+        let request = SendTransactionRequest {
+            network: Network::Regtest,
+            transaction: transaction.clone(),
+        };
+        assert_eq!(
+            adapter.handle_request(BitcoinAdapterRequestWrapper::SendTransactionRequest(
+                request
+            )),
+            BitcoinAdapterResponseWrapper::SendTransactionResponse(SendTransactionResponse {})
+        );
+        assert_eq!(adapter.sent_transactions(), vec![transaction.clone()]);
+
+        adapter.mine_blocks(vec![address()], 0).unwrap();
+        let block: Block = deserialize(&adapter.blocks()[0]).unwrap();
+        assert_eq!(block.txdata.len(), 2);
+        assert_eq!(serialize(&block.txdata[1]), transaction);
+
+        // The state is restored from the raw blocks and transactions.
+        let restored =
+            LocalBitcoinAdapter::from_raw(adapter.blocks(), adapter.sent_transactions()).unwrap();
+        assert_eq!(restored.state_summary(), adapter.state_summary());
+        assert!(restored.state.lock().unwrap().mempool.is_empty());
+    }
+
+    #[test]
+    fn invalid_addresses_are_rejected() {
+        let adapter = LocalBitcoinAdapter::default();
+        assert!(adapter
+            .mine_blocks(vec!["not an address".to_string()], 0)
+            .is_err());
+        assert!(adapter.blocks().is_empty());
+    }
+}
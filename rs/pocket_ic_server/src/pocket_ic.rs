@@ -1,32 +1,46 @@
+use crate::bitcoin_adapter::LocalBitcoinAdapter;
 use crate::state_api::state::{HasStateLabel, OpOut, PocketIcError, StateLabel};
 use crate::OpId;
 use crate::Operation;
 use crate::{copy_dir, BlobStore};
+use candid::Encode;
 use ic_config::execution_environment;
 use ic_config::subnet_config::SubnetConfig;
 use ic_crypto_sha2::Sha256;
-use ic_management_canister_types::CanisterInstallMode;
+use ic_management_canister_types::{
+    CanisterHttpResponsePayload, CanisterInstallMode, HttpHeader, Payload, TransformArgs,
+};
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_registry_routing_table::{CanisterIdRange, RoutingTable, CANISTER_IDS_PER_SUBNET};
 use ic_registry_subnet_type::SubnetType;
 use ic_state_machine_tests::{
-    finalize_registry, IngressState, IngressStatus, StateMachine, StateMachineBuilder,
-    StateMachineConfig, SubmitIngressError, Time,
+    finalize_registry, CanisterHttpMethod, ErrorCode, IngressState, IngressStatus, PayloadBuilder,
+    RejectCode, StateMachine, StateMachineBuilder, StateMachineConfig, SubmitIngressError, Time,
+    WasmResult,
+};
+use ic_types::crypto::Signable;
+use ic_types::messages::{
+    Blob, CallbackId, CertificateDelegation, HttpQueryResponse, HttpQueryResponseReply,
+    HttpReadStateResponse, HttpSignedQueryResponse, HttpStatusResponse, NodeSignature,
+    QueryResponseHash, ReadState, ReplicaHealthStatus, SignedIngress, UserQuery, IC_API_VERSION,
 };
-use ic_types::messages::CertificateDelegation;
 use ic_types::{CanisterId, PrincipalId, SubnetId};
 use itertools::Itertools;
 use pocket_ic::common::rest::{
-    self, BinaryBlob, BlobCompression, ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall,
-    RawEffectivePrincipal, RawSetStableMemory, SubnetKind, SubnetSpec, Topology,
+    self, BinaryBlob, BlobCompression, CanisterHttpHeader, CanisterHttpResponse,
+    ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall, RawCanisterHttpRequest,
+    RawEffectivePrincipal, RawMessageId, RawMockCanisterHttpResponse, RawSetStableMemory,
+    SubnetKind, SubnetSpec, Topology,
 };
 use rand::rngs::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     sync::{Arc, RwLock},
     time::SystemTime,
 };
@@ -42,6 +56,10 @@ pub struct PocketIc {
     routing_table: RoutingTable,
     /// Constant, created on initialization.
     pub topology: Topology,
+    /// The subnets in the order of their creation, persisted on checkpointing.
+    checkpointed_subnets: Vec<CheckpointedSubnet>,
+    /// The stand-in for the bitcoin adapter if the instance has a bitcoin subnet.
+    bitcoin_adapter: Option<Arc<LocalBitcoinAdapter>>,
     // Used for choosing a random subnet when the user does not specify
     // where a canister should be created. This value is seeded,
     // so reproducibility is maintained.
@@ -66,9 +84,8 @@ impl PocketIc {
 
         let mut range_gen = RangeGen::new();
         let mut subnet_config_info: Vec<SubnetConfigInfo> = vec![];
-        let mut routing_table = RoutingTable::new();
 
-        let mut nns_subnet_id = subnet_configs.nns.and_then(|x| {
+        let nns_subnet_id = subnet_configs.nns.and_then(|x| {
             x.get_subnet_id()
                 .map(|y| SubnetId::new(PrincipalId(y.into())))
         });
@@ -91,7 +108,15 @@ impl PocketIc {
                 None
             };
 
+            // Only the NNS subnet ID can currently be specified by the client.
+            let subnet_id = if subnet_kind == SubnetKind::NNS {
+                nns_subnet_id
+            } else {
+                None
+            };
+
             subnet_config_info.push(SubnetConfigInfo {
+                subnet_id,
                 ranges,
                 alloc_range,
                 subnet_kind,
@@ -99,15 +124,82 @@ impl PocketIc {
             });
         }
 
+        Self::from_subnet_config_info(runtime, subnet_config_info, LocalBitcoinAdapter::default())
+    }
+
+    /// Restores a PocketIC instance from a checkpoint directory
+    /// previously written by [`PocketIc::checkpoint`].
+    pub fn from_checkpoint(
+        runtime: Arc<Runtime>,
+        checkpoint_dir: impl AsRef<Path>,
+    ) -> Result<Self, String> {
+        let checkpoint_dir = checkpoint_dir.as_ref();
+        let topology_file = File::open(checkpoint_dir.join(CHECKPOINT_TOPOLOGY_FILE))
+            .map_err(|e| format!("Failed to open checkpoint topology: {}", e))?;
+        let CheckpointTopology {
+            subnets,
+            time,
+            bitcoin_blocks,
+            bitcoin_transactions,
+        } = serde_json::from_reader(topology_file)
+            .map_err(|e| format!("Failed to parse checkpoint topology: {}", e))?;
+        if subnets.is_empty() {
+            return Err("Checkpoint does not contain any subnets".to_string());
+        }
+
+        let mut subnet_config_info = vec![];
+        for CheckpointedSubnet {
+            subnet_id,
+            subnet_kind,
+            ranges,
+            alloc_range,
+        } in subnets
+        {
+            let tmp_dir = TempDir::new().expect("Failed to create temporary directory");
+            copy_dir(checkpoint_dir.join(subnet_id.to_string()), tmp_dir.path()).map_err(|e| {
+                format!(
+                    "Failed to copy state directory of subnet {}: {}",
+                    subnet_id, e
+                )
+            })?;
+            subnet_config_info.push(SubnetConfigInfo {
+                subnet_id: Some(subnet_id),
+                ranges,
+                alloc_range,
+                subnet_kind,
+                state_dir: Some(tmp_dir),
+            });
+        }
+
+        let bitcoin_adapter = LocalBitcoinAdapter::from_raw(bitcoin_blocks, bitcoin_transactions)?;
+        let pocket_ic = Self::from_subnet_config_info(runtime, subnet_config_info, bitcoin_adapter);
+        let time = Time::from_nanos_since_unix_epoch(time).into();
+        for subnet in pocket_ic.subnets.read().unwrap().values() {
+            subnet.set_time(time);
+        }
+        Ok(pocket_ic)
+    }
+
+    fn from_subnet_config_info(
+        runtime: Arc<Runtime>,
+        subnet_config_info: Vec<SubnetConfigInfo>,
+        bitcoin_adapter: LocalBitcoinAdapter,
+    ) -> Self {
         let registry_data_provider = Arc::new(ProtoRegistryDataProvider::new());
         let subnets: Arc<RwLock<BTreeMap<SubnetId, Arc<StateMachine>>>> =
             Arc::new(RwLock::new(BTreeMap::new()));
+        let mut routing_table = RoutingTable::new();
         let mut topology = Topology(HashMap::new());
+        let mut checkpointed_subnets = vec![];
+        let mut nns_subnet_id = None;
+        let bitcoin_adapter = Arc::new(bitcoin_adapter);
+        let mut has_bitcoin_subnet = false;
 
         // Create all StateMachines and the topology from the subnet config infos.
         for (
             subnet_seq_no,
             SubnetConfigInfo {
+                subnet_id,
                 ranges,
                 alloc_range,
                 subnet_kind,
@@ -126,13 +218,21 @@ impl PocketIc {
                 .with_subnet_size(subnet_size.try_into().unwrap())
                 .with_registry_data_provider(registry_data_provider.clone())
                 .with_multisubnet_ecdsa_key()
+                .with_multisubnet_schnorr_keys()
+                .with_multisubnet_vetkd_keys()
                 .with_use_cost_scaling_flag(true);
 
             if subnet_kind == SubnetKind::NNS {
                 builder = builder.with_root_subnet_config();
-                if let Some(nns_subnet_id) = nns_subnet_id {
-                    builder = builder.with_subnet_id(nns_subnet_id);
-                }
+            }
+
+            if subnet_kind == SubnetKind::Bitcoin {
+                builder = builder.with_bitcoin_adapter(bitcoin_adapter.clone());
+                has_bitcoin_subnet = true;
+            }
+
+            if let Some(subnet_id) = subnet_id {
+                builder = builder.with_subnet_id(subnet_id);
             }
 
             if let Some(state_dir) = state_dir {
@@ -142,8 +242,8 @@ impl PocketIc {
             let sm = builder.build_with_subnets(subnets.clone());
             let subnet_id = sm.get_subnet_id();
 
-            // Store the actual NNS subnet ID if none was provided by the client.
-            if let (SubnetKind::NNS, None) = (subnet_kind, nns_subnet_id) {
+            // Store the actual NNS subnet ID.
+            if subnet_kind == SubnetKind::NNS {
                 nns_subnet_id = Some(subnet_id);
             };
 
@@ -162,6 +262,14 @@ impl PocketIc {
                 canister_ranges: ranges.iter().map(from_range).collect(),
             };
             topology.0.insert(subnet_id.get().0, subnet_config);
+
+            // What will be persisted when writing a checkpoint:
+            checkpointed_subnets.push(CheckpointedSubnet {
+                subnet_id,
+                subnet_kind,
+                ranges,
+                alloc_range,
+            });
         }
 
         // Finalize registry with subnet IDs that are only available now that we created
@@ -184,10 +292,51 @@ impl PocketIc {
             subnets,
             routing_table,
             topology,
+            checkpointed_subnets,
+            bitcoin_adapter: has_bitcoin_subnet.then_some(bitcoin_adapter),
             randomness: StdRng::seed_from_u64(42),
         }
     }
 
+    /// Writes a checkpoint of every subnet into `checkpoint_dir` together with
+    /// the topology and the current time so that the whole instance can be
+    /// restored later via [`PocketIc::from_checkpoint`].
+    pub fn checkpoint(&self, checkpoint_dir: impl AsRef<Path>) -> Result<(), String> {
+        let checkpoint_dir = checkpoint_dir.as_ref();
+        for CheckpointedSubnet { subnet_id, .. } in &self.checkpointed_subnets {
+            let subnet = self.get_subnet_with_id(*subnet_id).unwrap();
+            subnet.checkpointed_tick();
+            copy_dir(
+                subnet.state_dir.path(),
+                checkpoint_dir.join(subnet_id.to_string()),
+            )
+            .map_err(|e| {
+                format!(
+                    "Failed to copy state directory of subnet {}: {}",
+                    subnet_id, e
+                )
+            })?;
+        }
+        let time = systemtime_to_unix_epoch_nanos(self.any_subnet().time());
+        let (bitcoin_blocks, bitcoin_transactions) = match &self.bitcoin_adapter {
+            Some(bitcoin_adapter) => (
+                bitcoin_adapter.blocks(),
+                bitcoin_adapter.sent_transactions(),
+            ),
+            None => (vec![], vec![]),
+        };
+        let topology = CheckpointTopology {
+            subnets: self.checkpointed_subnets.clone(),
+            time,
+            bitcoin_blocks,
+            bitcoin_transactions,
+        };
+        let topology_file = File::create(checkpoint_dir.join(CHECKPOINT_TOPOLOGY_FILE))
+            .map_err(|e| format!("Failed to create checkpoint topology: {}", e))?;
+        serde_json::to_writer(topology_file, &topology)
+            .map_err(|e| format!("Failed to write checkpoint topology: {}", e))
+    }
+
     fn try_route_canister(&self, canister_id: CanisterId) -> Option<Arc<StateMachine>> {
         let subnet_id = self.routing_table.route(canister_id.into());
         subnet_id.map(|subnet_id| self.get_subnet_with_id(subnet_id).unwrap())
@@ -295,6 +444,11 @@ impl HasStateLabel for PocketIc {
             hasher.write(&subnet_state_hash[..]);
             hasher.write(&nanos.to_be_bytes());
         }
+        if let Some(bitcoin_adapter) = &self.bitcoin_adapter {
+            let (tip_hash, num_transactions) = bitcoin_adapter.state_summary();
+            hasher.write(&tip_hash[..]);
+            hasher.write(&num_transactions.to_be_bytes());
+        }
         StateLabel(hasher.finish())
     }
 }
@@ -435,12 +589,39 @@ struct RangeConfig {
 
 /// Internal struct used during initialization.
 struct SubnetConfigInfo {
+    pub subnet_id: Option<SubnetId>,
     pub ranges: Vec<CanisterIdRange>,
     pub alloc_range: Option<CanisterIdRange>,
     pub subnet_kind: SubnetKind,
     pub state_dir: Option<TempDir>,
 }
 
+/// Name of the file in a checkpoint directory holding the [`CheckpointTopology`].
+const CHECKPOINT_TOPOLOGY_FILE: &str = "topology.json";
+
+/// A subnet as persisted in a checkpoint. The subnets must be restored
+/// in the same order as they were created because the subnet sequence
+/// number determines the subnet's keys.
+#[derive(Clone, Serialize, Deserialize)]
+struct CheckpointedSubnet {
+    subnet_id: SubnetId,
+    subnet_kind: SubnetKind,
+    ranges: Vec<CanisterIdRange>,
+    alloc_range: Option<CanisterIdRange>,
+}
+
+/// The contents of the topology file in a checkpoint directory.
+#[derive(Serialize, Deserialize)]
+struct CheckpointTopology {
+    subnets: Vec<CheckpointedSubnet>,
+    /// Time of the instance in nanoseconds since the Unix epoch.
+    time: u64,
+    /// The raw blocks mined on the local bitcoin blockchain (excluding the genesis block).
+    bitcoin_blocks: Vec<Vec<u8>>,
+    /// The raw transactions sent by the bitcoin canister.
+    bitcoin_transactions: Vec<Vec<u8>>,
+}
+
 // ---------------------------------------------------------------------------------------- //
 // Operations on PocketIc
 
@@ -527,10 +708,35 @@ impl Operation for Tick {
     }
 }
 
+/// Advances the time on all subnets to the current wall-clock time (unless the time of a subnet
+/// is already ahead of it) and executes a round on all subnets. Used to make progress on an
+/// instance in live mode.
+#[derive(Clone, Debug, Copy)]
+pub struct AutoTick;
+
+impl Operation for AutoTick {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let now = SystemTime::now();
+        for subnet in pic.subnets.read().unwrap().values() {
+            if subnet.time() < now {
+                subnet.set_time(now);
+            }
+            subnet.execute_round();
+        }
+        OpOut::NoOutput
+    }
+
+    fn id(&self) -> OpId {
+        OpId("auto_tick".to_string())
+    }
+}
+
 #[derive(Clone, Debug)]
-pub struct ExecuteIngressMessage(pub CanisterCall);
+pub struct SubmitIngressMessage(pub CanisterCall);
 
-impl Operation for ExecuteIngressMessage {
+impl Operation for SubmitIngressMessage {
     type TargetType = PocketIc;
 
     fn compute(self, pic: &mut PocketIc) -> OpOut {
@@ -548,39 +754,12 @@ impl Operation for ExecuteIngressMessage {
                         eprintln!("Failed to submit ingress message: {}", e);
                         OpOut::Error(PocketIcError::BadIngressMessage(e))
                     }
-                    Err(ic_state_machine_tests::SubmitIngressError::UserError(e)) => {
+                    Err(SubmitIngressError::UserError(e)) => {
                         eprintln!("Failed to submit ingress message: {:?}", e);
                         Err::<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>(e).into()
                     }
                     Ok(msg_id) => {
-                        // Now, we execute on all subnets until we have the result
-                        let max_rounds = 100;
-                        for _i in 0..max_rounds {
-                            for subnet_ in pic.subnets.read().unwrap().values() {
-                                subnet_.execute_round();
-                            }
-                            match subnet.ingress_status(&msg_id) {
-                                IngressStatus::Known {
-                                    state: IngressState::Completed(result),
-                                    ..
-                                } => return Ok(result).into(),
-                                IngressStatus::Known {
-                                    state: IngressState::Failed(error),
-                                    ..
-                                } => {
-                                    return Err::<
-                                        ic_state_machine_tests::WasmResult,
-                                        ic_state_machine_tests::UserError,
-                                    >(error)
-                                    .into()
-                                }
-                                _ => {}
-                            }
-                        }
-                        panic!(
-                            "Failed to answer to ingress {} after {} xnet rounds.",
-                            msg_id, max_rounds
-                        );
+                        OpOut::MessageId((subnet.get_subnet_id(), msg_id.as_bytes().to_vec()))
                     }
                 }
             }
@@ -588,6 +767,149 @@ impl Operation for ExecuteIngressMessage {
         }
     }
 
+    fn id(&self) -> OpId {
+        let call_id = self.0.id();
+        OpId(format!("submit_update_{}", call_id.0))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MessageId {
+    pub effective_principal: EffectivePrincipal,
+    pub msg_id: ic_types::messages::MessageId,
+}
+
+impl TryFrom<RawMessageId> for MessageId {
+    type Error = ConversionError;
+    fn try_from(
+        RawMessageId {
+            effective_principal,
+            message_id,
+        }: RawMessageId,
+    ) -> Result<Self, Self::Error> {
+        let effective_principal = EffectivePrincipal::try_from(effective_principal)?;
+        let msg_id = match ic_types::messages::MessageId::try_from(&message_id[..]) {
+            Ok(msg_id) => msg_id,
+            Err(_) => {
+                return Err(ConversionError {
+                    message: "Bad message id".to_string(),
+                })
+            }
+        };
+        Ok(MessageId {
+            effective_principal,
+            msg_id,
+        })
+    }
+}
+
+fn route_message_id(pic: &PocketIc, message_id: &MessageId) -> Result<Arc<StateMachine>, String> {
+    match message_id.effective_principal {
+        EffectivePrincipal::SubnetId(subnet_id) => pic
+            .get_subnet_with_id(subnet_id)
+            .ok_or(format!("Subnet with ID {subnet_id} not found")),
+        EffectivePrincipal::CanisterId(effective_canister_id) => {
+            pic.try_route_canister(effective_canister_id).ok_or(format!(
+                "Effective canister ID {effective_canister_id} not contained on any subnet"
+            ))
+        }
+        EffectivePrincipal::None => Err("The effective principal must be specified".to_string()),
+    }
+}
+
+fn ingress_status_to_op_out(ingress_status: IngressStatus) -> Option<OpOut> {
+    match ingress_status {
+        IngressStatus::Known {
+            state: IngressState::Completed(result),
+            ..
+        } => Some(Ok(result).into()),
+        IngressStatus::Known {
+            state: IngressState::Failed(error),
+            ..
+        } => Some(
+            Err::<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>(error)
+                .into(),
+        ),
+        _ => None,
+    }
+}
+
+/// Executes rounds on all subnets until the given update call completes.
+#[derive(Clone, Debug)]
+pub struct AwaitIngressMessage(pub MessageId);
+
+impl Operation for AwaitIngressMessage {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match route_message_id(pic, &self.0) {
+            Ok(subnet) => subnet,
+            Err(e) => return OpOut::Error(PocketIcError::BadIngressMessage(e)),
+        };
+        // Now, we execute on all subnets until we have the result
+        let max_rounds = 100;
+        for _i in 0..max_rounds {
+            if let Some(op_out) = ingress_status_to_op_out(subnet.ingress_status(&self.0.msg_id)) {
+                return op_out;
+            }
+            for subnet_ in pic.subnets.read().unwrap().values() {
+                subnet_.execute_round();
+            }
+        }
+        if let Some(op_out) = ingress_status_to_op_out(subnet.ingress_status(&self.0.msg_id)) {
+            return op_out;
+        }
+        OpOut::Error(PocketIcError::BadIngressMessage(format!(
+            "Failed to answer to ingress {} after {} rounds.",
+            self.0.msg_id, max_rounds
+        )))
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("await_update_{}", self.0.msg_id))
+    }
+}
+
+/// Returns the result of the given update call if it has completed, without executing rounds.
+#[derive(Clone, Debug)]
+pub struct IngressMessageStatus(pub MessageId);
+
+impl Operation for IngressMessageStatus {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match route_message_id(pic, &self.0) {
+            Ok(subnet) => subnet,
+            Err(e) => return OpOut::Error(PocketIcError::BadIngressMessage(e)),
+        };
+        ingress_status_to_op_out(subnet.ingress_status(&self.0.msg_id)).unwrap_or(OpOut::NoOutput)
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("ingress_status_{}", self.0.msg_id))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExecuteIngressMessage(pub CanisterCall);
+
+impl Operation for ExecuteIngressMessage {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        match SubmitIngressMessage(self.0).compute(pic) {
+            OpOut::MessageId((subnet_id, message_id)) => {
+                let msg_id = ic_types::messages::MessageId::try_from(&message_id[..]).unwrap();
+                AwaitIngressMessage(MessageId {
+                    effective_principal: EffectivePrincipal::SubnetId(subnet_id),
+                    msg_id,
+                })
+                .compute(pic)
+            }
+            op_out => op_out,
+        }
+    }
+
     fn id(&self) -> OpId {
         let call_id = self.0.id();
         OpId(format!("canister_update_{}", call_id.0))
@@ -631,6 +953,32 @@ pub enum EffectivePrincipal {
     CanisterId(CanisterId),
 }
 
+impl TryFrom<RawEffectivePrincipal> for EffectivePrincipal {
+    type Error = ConversionError;
+    fn try_from(effective_principal: RawEffectivePrincipal) -> Result<Self, Self::Error> {
+        match effective_principal {
+            RawEffectivePrincipal::SubnetId(subnet_id) => {
+                let sid = PrincipalId::try_from(subnet_id);
+                match sid {
+                    Ok(sid) => Ok(EffectivePrincipal::SubnetId(SubnetId::new(sid))),
+                    Err(_) => Err(ConversionError {
+                        message: "Bad subnet id".to_string(),
+                    }),
+                }
+            }
+            RawEffectivePrincipal::CanisterId(canister_id) => {
+                match CanisterId::try_from(canister_id) {
+                    Ok(canister_id) => Ok(EffectivePrincipal::CanisterId(canister_id)),
+                    Err(_) => Err(ConversionError {
+                        message: "Bad effective canister id".to_string(),
+                    }),
+                }
+            }
+            RawEffectivePrincipal::None => Ok(EffectivePrincipal::None),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CanisterCall {
     pub effective_principal: EffectivePrincipal,
@@ -651,30 +999,7 @@ impl TryFrom<RawCanisterCall> for CanisterCall {
             effective_principal,
         }: RawCanisterCall,
     ) -> Result<Self, Self::Error> {
-        let effective_principal = match effective_principal {
-            RawEffectivePrincipal::SubnetId(subnet_id) => {
-                let sid = PrincipalId::try_from(subnet_id);
-                match sid {
-                    Ok(sid) => EffectivePrincipal::SubnetId(SubnetId::new(sid)),
-                    Err(_) => {
-                        return Err(ConversionError {
-                            message: "Bad subnet id".to_string(),
-                        })
-                    }
-                }
-            }
-            RawEffectivePrincipal::CanisterId(canister_id) => {
-                match CanisterId::try_from(canister_id) {
-                    Ok(canister_id) => EffectivePrincipal::CanisterId(canister_id),
-                    Err(_) => {
-                        return Err(ConversionError {
-                            message: "Bad effective canister id".to_string(),
-                        })
-                    }
-                }
-            }
-            RawEffectivePrincipal::None => EffectivePrincipal::None,
-        };
+        let effective_principal = EffectivePrincipal::try_from(effective_principal)?;
         let sender = match PrincipalId::try_from(sender) {
             Ok(sender) => sender,
             Err(_) => {
@@ -899,6 +1224,229 @@ impl Operation for AddCycles {
     }
 }
 
+/// Mines one block per address on the local bitcoin blockchain of the bitcoin subnet.
+/// The bitcoin canister fetches the new blocks in subsequent rounds.
+#[derive(Clone, Debug)]
+pub struct MineBitcoinBlocks {
+    pub addresses: Vec<String>,
+}
+
+impl Operation for MineBitcoinBlocks {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let bitcoin_adapter = match &pic.bitcoin_adapter {
+            Some(bitcoin_adapter) => bitcoin_adapter,
+            None => return OpOut::Error(PocketIcError::BitcoinSubnetNotFound),
+        };
+        let time_secs = systemtime_to_unix_epoch_nanos(pic.any_subnet().time()) / 1_000_000_000;
+        match bitcoin_adapter.mine_blocks(self.addresses, time_secs as u32) {
+            Ok(_) => OpOut::NoOutput,
+            Err(e) => OpOut::Error(PocketIcError::InvalidBitcoinAddress(e)),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("mine_bitcoin_blocks_{}", self.addresses.join("_")))
+    }
+}
+
+/// Lists all transactions which the bitcoin canister sent to the bitcoin network.
+#[derive(Clone, Debug)]
+pub struct GetBitcoinTransactions;
+
+impl Operation for GetBitcoinTransactions {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        match &pic.bitcoin_adapter {
+            Some(bitcoin_adapter) => {
+                OpOut::BitcoinTransactions(bitcoin_adapter.sent_transactions())
+            }
+            None => OpOut::Error(PocketIcError::BitcoinSubnetNotFound),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId("get_bitcoin_transactions".to_string())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GetCanisterHttp;
+
+impl Operation for GetCanisterHttp {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let mut res = vec![];
+        for (subnet_id, subnet) in pic.subnets.read().unwrap().iter() {
+            for (callback_id, context) in subnet.canister_http_request_contexts() {
+                let http_method = match context.http_method {
+                    CanisterHttpMethod::GET => rest::CanisterHttpMethod::GET,
+                    CanisterHttpMethod::POST => rest::CanisterHttpMethod::POST,
+                    CanisterHttpMethod::HEAD => rest::CanisterHttpMethod::HEAD,
+                };
+                let headers = context
+                    .headers
+                    .into_iter()
+                    .map(|h| CanisterHttpHeader {
+                        name: h.name,
+                        value: h.value,
+                    })
+                    .collect();
+                res.push(RawCanisterHttpRequest {
+                    subnet_id: subnet_id.get().to_vec(),
+                    request_id: callback_id.get(),
+                    http_method,
+                    url: context.url,
+                    headers,
+                    body: context.body.unwrap_or_default(),
+                    max_response_bytes: context.max_response_bytes.map(|b| b.get()),
+                });
+            }
+        }
+        OpOut::CanisterHttp(res)
+    }
+
+    fn id(&self) -> OpId {
+        OpId("get_canister_http".to_string())
+    }
+}
+
+/// Delivers a canned response to a pending canister HTTP outcall.
+/// If the outcall specified a transform function, the transform is applied
+/// to a mocked reply before the reply is delivered to the calling canister.
+#[derive(Clone, Debug)]
+pub struct MockCanisterHttp {
+    pub subnet_id: SubnetId,
+    pub request_id: u64,
+    pub response: CanisterHttpResponse,
+}
+
+impl TryFrom<RawMockCanisterHttpResponse> for MockCanisterHttp {
+    type Error = ConversionError;
+    fn try_from(
+        RawMockCanisterHttpResponse {
+            subnet_id,
+            request_id,
+            response,
+        }: RawMockCanisterHttpResponse,
+    ) -> Result<Self, Self::Error> {
+        match PrincipalId::try_from(subnet_id) {
+            Ok(subnet_id) => Ok(MockCanisterHttp {
+                subnet_id: SubnetId::new(subnet_id),
+                request_id,
+                response,
+            }),
+            Err(_) => Err(ConversionError {
+                message: "Bad subnet id".to_string(),
+            }),
+        }
+    }
+}
+
+impl Operation for MockCanisterHttp {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match pic.get_subnet_with_id(self.subnet_id) {
+            Some(subnet) => subnet,
+            None => return OpOut::Error(PocketIcError::SubnetNotFound(self.subnet_id.get().0)),
+        };
+        let callback_id = CallbackId::from(self.request_id);
+        let contexts = subnet.canister_http_request_contexts();
+        let context = match contexts.get(&callback_id) {
+            Some(context) => context,
+            None => {
+                return OpOut::Error(PocketIcError::InvalidCanisterHttpRequestId((
+                    self.subnet_id,
+                    self.request_id,
+                )))
+            }
+        };
+        let payload = match self.response {
+            CanisterHttpResponse::CanisterHttpReply(reply) => {
+                let response = CanisterHttpResponsePayload {
+                    status: reply.status as u128,
+                    headers: reply
+                        .headers
+                        .into_iter()
+                        .map(|h| HttpHeader {
+                            name: h.name,
+                            value: h.value,
+                        })
+                        .collect(),
+                    body: reply.body,
+                };
+                match &context.transform {
+                    Some(transform) => {
+                        let transform_args = TransformArgs {
+                            response,
+                            context: transform.context.clone(),
+                        };
+                        let transform_result = subnet.query_as(
+                            CanisterId::ic_00().get(),
+                            context.request.sender,
+                            transform.method_name.clone(),
+                            Encode!(&transform_args).unwrap(),
+                        );
+                        match transform_result {
+                            Ok(WasmResult::Reply(data)) => {
+                                match CanisterHttpResponsePayload::decode(&data) {
+                                    Ok(transformed) => PayloadBuilder::new()
+                                        .http_response(callback_id, &transformed),
+                                    Err(e) => PayloadBuilder::new().http_response_failure(
+                                        callback_id,
+                                        RejectCode::SysFatal,
+                                        format!("Failed to decode transformed response: {}", e),
+                                    ),
+                                }
+                            }
+                            Ok(WasmResult::Reject(message)) => PayloadBuilder::new()
+                                .http_response_failure(
+                                    callback_id,
+                                    RejectCode::CanisterReject,
+                                    message,
+                                ),
+                            Err(user_error) => PayloadBuilder::new().http_response_failure(
+                                callback_id,
+                                user_error.reject_code(),
+                                user_error.description(),
+                            ),
+                        }
+                    }
+                    None => PayloadBuilder::new().http_response(callback_id, &response),
+                }
+            }
+            CanisterHttpResponse::CanisterHttpReject(reject) => {
+                match RejectCode::try_from(reject.reject_code) {
+                    Ok(reject_code) => PayloadBuilder::new().http_response_failure(
+                        callback_id,
+                        reject_code,
+                        reject.message,
+                    ),
+                    Err(_) => {
+                        return OpOut::Error(PocketIcError::InvalidRejectCode(reject.reject_code))
+                    }
+                }
+            }
+        };
+        subnet.execute_payload(payload);
+        OpOut::NoOutput
+    }
+
+    fn id(&self) -> OpId {
+        let mut hasher = Sha256::new();
+        hasher.write(format!("{:?}", self.response).as_bytes());
+        let hash = Digest(hasher.finish());
+        OpId(format!(
+            "mock_canister_http({},{},{})",
+            self.subnet_id, self.request_id, hash
+        ))
+    }
+}
+
 struct Digest([u8; 32]);
 
 impl std::fmt::Debug for Digest {
@@ -915,6 +1463,194 @@ impl std::fmt::Display for Digest {
     }
 }
 
+// ---------------------------------------------------------------------------------------- //
+// Operations backing the IC HTTP interface (`/api/v2/...`) of a PocketIC instance
+
+/// Returns the CBOR-encoded response to `/api/v2/status`. The root key is the public key
+/// of the NNS subnet if there is one and of an arbitrary subnet otherwise.
+#[derive(Clone, Debug, Copy)]
+pub struct StatusRequest;
+
+impl Operation for StatusRequest {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let root_subnet = pic.nns_subnet().unwrap_or_else(|| pic.any_subnet());
+        let status = HttpStatusResponse {
+            ic_api_version: IC_API_VERSION.to_string(),
+            root_key: Some(Blob(root_subnet.root_key_der())),
+            impl_version: None,
+            impl_hash: None,
+            replica_health_status: Some(ReplicaHealthStatus::Healthy),
+            certified_height: None,
+        };
+        OpOut::Bytes(into_cbor(&status))
+    }
+
+    fn id(&self) -> OpId {
+        OpId("status_request".to_string())
+    }
+}
+
+/// Submits a signed ingress message received via `/api/v2/canister/<ecid>/call`
+/// into the ingress pool of the subnet hosting the effective canister.
+/// The message is executed by subsequent rounds.
+#[derive(Clone, Debug)]
+pub struct CallRequest {
+    pub effective_canister_id: CanisterId,
+    pub msg: SignedIngress,
+}
+
+impl Operation for CallRequest {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match pic.try_route_canister(self.effective_canister_id) {
+            Some(subnet) => subnet,
+            None => {
+                return OpOut::Error(PocketIcError::CanisterNotFound(self.effective_canister_id))
+            }
+        };
+        match subnet.submit_signed_ingress(self.msg) {
+            Ok(_) => OpOut::NoOutput,
+            Err(SubmitIngressError::HttpError(e)) => {
+                OpOut::Error(PocketIcError::BadIngressMessage(e))
+            }
+            Err(SubmitIngressError::UserError(e)) => {
+                OpOut::Error(PocketIcError::BadIngressMessage(e.to_string()))
+            }
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("call_request({})", self.msg.id()))
+    }
+}
+
+/// Executes a query received via `/api/v2/canister/<ecid>/query` and returns the
+/// CBOR-encoded response.
+#[derive(Clone, Debug)]
+pub struct QueryRequest {
+    pub effective_canister_id: CanisterId,
+    pub query: UserQuery,
+}
+
+impl Operation for QueryRequest {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match pic.try_route_canister(self.effective_canister_id) {
+            Some(subnet) => subnet,
+            None => {
+                return OpOut::Error(PocketIcError::CanisterNotFound(self.effective_canister_id))
+            }
+        };
+        let delegation = pic.get_nns_delegation_for_subnet(subnet.get_subnet_id());
+        let timestamp = subnet.get_time();
+        let response = match subnet.query_as_with_delegation(
+            self.query.source.get(),
+            self.query.receiver,
+            self.query.method_name.clone(),
+            self.query.method_payload.clone(),
+            delegation,
+        ) {
+            Ok(WasmResult::Reply(arg)) => HttpQueryResponse::Replied {
+                reply: HttpQueryResponseReply { arg: Blob(arg) },
+            },
+            Ok(WasmResult::Reject(reject_message)) => HttpQueryResponse::Rejected {
+                error_code: ErrorCode::CanisterRejectedMessage.to_string(),
+                reject_code: RejectCode::CanisterReject as u64,
+                reject_message,
+            },
+            Err(user_error) => HttpQueryResponse::Rejected {
+                error_code: user_error.code().to_string(),
+                reject_code: user_error.reject_code() as u64,
+                reject_message: user_error.to_string(),
+            },
+        };
+        // Agents verify the node signature of query responses against the node public keys
+        // in the certified state tree, so we sign with the key of the first node.
+        let response_hash = QueryResponseHash::new(&response, &self.query, timestamp);
+        let (identity, signature) = subnet
+            .compute_node_signature(0, &response_hash.as_signed_bytes())
+            .unwrap();
+        let signed_response = HttpSignedQueryResponse {
+            response,
+            node_signature: NodeSignature {
+                timestamp,
+                signature: Blob(signature.to_vec()),
+                identity,
+            },
+        };
+        OpOut::Bytes(into_cbor(&signed_response))
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("query_request({})", self.query.id()))
+    }
+}
+
+/// Reads the certified state tree of the subnet hosting the effective canister at the paths
+/// of a request received via `/api/v2/canister/<ecid>/read_state` and returns the
+/// CBOR-encoded response. The `targets` are the canister IDs that the sender's
+/// delegations are restricted to (`None` if they are not restricted).
+#[derive(Clone, Debug)]
+pub struct ReadStateRequest {
+    pub effective_canister_id: CanisterId,
+    pub read_state: ReadState,
+    pub targets: Option<BTreeSet<CanisterId>>,
+}
+
+impl Operation for ReadStateRequest {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match pic.try_route_canister(self.effective_canister_id) {
+            Some(subnet) => subnet,
+            None => {
+                return OpOut::Error(PocketIcError::CanisterNotFound(self.effective_canister_id))
+            }
+        };
+        let delegation = pic.get_nns_delegation_for_subnet(subnet.get_subnet_id());
+        match subnet.read_state(
+            &self.read_state,
+            self.effective_canister_id.get(),
+            self.targets.as_ref(),
+            delegation,
+        ) {
+            Ok(certificate) => OpOut::Bytes(into_cbor(&HttpReadStateResponse {
+                certificate: Blob(certificate),
+            })),
+            Err(e) => OpOut::Error(PocketIcError::InvalidReadStateRequest(e)),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("read_state_request({})", self.read_state.id()))
+    }
+}
+
+/// Writes a checkpoint of the whole instance into the given directory.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub checkpoint_dir: PathBuf,
+}
+
+impl Operation for Checkpoint {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        match pic.checkpoint(&self.checkpoint_dir) {
+            Ok(()) => OpOut::NoOutput,
+            Err(e) => OpOut::Error(PocketIcError::CheckpointFailed(e)),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!("checkpoint({})", self.checkpoint_dir.display()))
+    }
+}
+
 // TODO: deprecate this as an Op; implement it as a client library convenience function
 
 /// A convenience method that installs the given wasm module at the given canister id. The first
@@ -970,6 +1706,13 @@ fn route_call(
     }
 }
 
+fn into_cbor<R: Serialize>(r: &R) -> Vec<u8> {
+    let mut ser = serde_cbor::Serializer::new(Vec::new());
+    ser.self_describe().expect("Could not write magic tag.");
+    r.serialize(&mut ser).expect("Serialization failed.");
+    ser.into_inner()
+}
+
 fn systemtime_to_unix_epoch_nanos(st: SystemTime) -> u64 {
     st.duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
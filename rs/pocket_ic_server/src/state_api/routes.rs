@@ -7,34 +7,50 @@
 use super::state::{InstanceState, OpOut, PocketIcApiState, PocketIcError, UpdateReply};
 use crate::pocket_ic::GetSubnet;
 use crate::pocket_ic::{
-    AddCycles, ExecuteIngressMessage, GetCyclesBalance, GetStableMemory, GetTime, PubKey, Query,
-    SetStableMemory, SetTime, Tick,
+    AddCycles, AutoTick, AwaitIngressMessage, CallRequest, Checkpoint, ExecuteIngressMessage,
+    GetBitcoinTransactions, GetCanisterHttp, GetCyclesBalance, GetStableMemory, GetTime,
+    IngressMessageStatus, MineBitcoinBlocks, MockCanisterHttp, PubKey, Query, QueryRequest,
+    ReadStateRequest, SetStableMemory, SetTime, StatusRequest, SubmitIngressMessage, Tick,
 };
 use crate::{pocket_ic::PocketIc, BindOperation, BlobStore, InstanceId, Operation};
 use aide::axum::routing::{delete, get, post, ApiMethodRouter};
 use aide::axum::ApiRouter;
 use axum::{
+    body::Bytes,
     extract::{self, Path, State},
     http::{self, HeaderMap, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use axum_extra::headers;
 use axum_extra::headers::HeaderMapExt;
+use ic_types::messages::{
+    Authentication, HttpCallContent, HttpQueryContent, HttpReadStateContent, HttpRequest,
+    HttpRequestEnvelope, HttpRequestError, ReadState, SignedIngress, UserQuery,
+};
 use ic_types::CanisterId;
 use pocket_ic::common::rest::{
-    self, ApiResponse, ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall, RawCanisterId,
-    RawCanisterResult, RawCycles, RawSetStableMemory, RawStableMemory, RawSubnetId, RawTime,
-    RawWasmResult,
+    self, ApiResponse, ExtendedSubnetConfigSet, RawAddCycles, RawBitcoinTransaction,
+    RawCanisterCall, RawCanisterHttpRequest, RawCanisterId, RawCanisterResult, RawCheckpoint,
+    RawCycles, RawEffectivePrincipal, RawMessageId, RawMineBitcoinBlocks,
+    RawMockCanisterHttpResponse, RawSetStableMemory, RawStableMemory, RawSubmitIngressResult,
+    RawSubnetId, RawTime, RawWasmResult,
 };
-use pocket_ic::WasmResult;
-use serde::Serialize;
-use std::{sync::Arc, time::Duration};
+use pocket_ic::{UserError, WasmResult};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::BTreeSet, str::FromStr, sync::Arc, time::Duration};
 use tokio::{runtime::Runtime, sync::RwLock, time::Instant};
 
 /// Name of a header that allows clients to specify for how long their are willing to wait for a
 /// response on a open http request.
 pub static TIMEOUT_HEADER_NAME: HeaderName = HeaderName::from_static("processing-timeout-ms");
 
+const CONTENT_TYPE_CBOR: &str = "application/cbor";
+// The maximum duration an operation of the IC HTTP interface may take.
+const HTTP_INTERFACE_TIMEOUT: Duration = Duration::from_secs(300);
+// The interval at which an operation of the IC HTTP interface is retried if the instance is busy.
+const BUSY_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
 pub type ApiState = PocketIcApiState<PocketIc>;
 
 #[derive(Clone)]
@@ -57,6 +73,12 @@ where
         .directory_route("/get_stable_memory", post(handler_get_stable_memory))
         .directory_route("/get_subnet", post(handler_get_subnet))
         .directory_route("/pub_key", post(handler_pub_key))
+        .directory_route("/get_canister_http", post(handler_get_canister_http))
+        .directory_route(
+            "/get_bitcoin_transactions",
+            get(handler_get_bitcoin_transactions),
+        )
+        .directory_route("/ingress_status", post(handler_ingress_status))
 }
 
 pub fn instance_update_routes<S>() -> ApiRouter<S>
@@ -69,10 +91,21 @@ where
             "/execute_ingress_message",
             post(handler_execute_ingress_message),
         )
+        .directory_route(
+            "/submit_ingress_message",
+            post(handler_submit_ingress_message),
+        )
+        .directory_route(
+            "/await_ingress_message",
+            post(handler_await_ingress_message),
+        )
         .directory_route("/set_time", post(handler_set_time))
         .directory_route("/add_cycles", post(handler_add_cycles))
         .directory_route("/set_stable_memory", post(handler_set_stable_memory))
         .directory_route("/tick", post(handler_tick))
+        .directory_route("/mock_canister_http", post(handler_mock_canister_http))
+        .directory_route("/checkpoint", post(handler_checkpoint))
+        .directory_route("/mine_bitcoin_blocks", post(handler_mine_bitcoin_blocks))
 }
 
 pub fn instances_routes<S>() -> ApiRouter<S>
@@ -89,9 +122,23 @@ where
         // Returns an InstanceId.
         .api_route("/", post(create_instance))
         //
+        // Create a new IC instance from a checkpoint written by the `checkpoint` endpoint.
+        // Returns an InstanceId.
+        .api_route("/from_checkpoint", post(create_instance_from_checkpoint))
+        //
         // Deletes an instance.
         .directory_route("/:id", delete(delete_instance))
         //
+        // Puts an instance into live mode, i.e., rounds are executed periodically
+        // and the time of the instance follows the wall-clock time.
+        .directory_route("/:id/auto_progress", post(auto_progress))
+        //
+        // Stops live mode of an instance.
+        .directory_route("/:id/stop_progress", post(stop_progress))
+        //
+        // The IC HTTP interface of an instance.
+        .nest("/:id/api/v2", instance_http_interface_routes())
+        //
         // All the read-only endpoints
         .nest("/:id/read", instance_read_routes())
         //
@@ -99,6 +146,28 @@ where
         .nest("/:id/update", instance_update_routes())
 }
 
+/// The endpoints of the IC HTTP interface as described in the
+/// [IC interface-spec](https://internetcomputer.org/docs/current/references/ic-interface-spec#http-interface).
+/// Requests and responses are CBOR-encoded, hence these routes are not documented
+/// in the OpenAPI spec.
+pub fn instance_http_interface_routes<S>() -> ApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+    AppState: extract::FromRef<S>,
+{
+    ApiRouter::new()
+        .route("/status", axum::routing::get(handler_status))
+        .route("/canister/:ecid/call", axum::routing::post(handler_call))
+        .route(
+            "/canister/:ecid/query",
+            axum::routing::post(handler_query_request),
+        )
+        .route(
+            "/canister/:ecid/read_state",
+            axum::routing::post(handler_read_state),
+        )
+}
+
 async fn run_operation<T: Serialize>(
     api_state: &ApiState,
     instance_id: InstanceId,
@@ -159,10 +228,72 @@ impl From<OpOut> for (StatusCode, ApiResponse<RawTime>) {
     }
 }
 
+impl From<OpOut> for (StatusCode, ApiResponse<Option<RawCanisterResult>>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::CanisterResult(wasm_result) => (
+                StatusCode::OK,
+                ApiResponse::Success(Some(to_raw_canister_result(wasm_result))),
+            ),
+            OpOut::NoOutput => (StatusCode::OK, ApiResponse::Success(None)),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("{:?}", e),
+                },
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
+impl From<OpOut> for (StatusCode, ApiResponse<RawSubmitIngressResult>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::MessageId((subnet_id, message_id)) => (
+                StatusCode::OK,
+                ApiResponse::Success(RawSubmitIngressResult::Ok(RawMessageId {
+                    effective_principal: RawEffectivePrincipal::SubnetId(
+                        subnet_id.get().as_slice().to_vec(),
+                    ),
+                    message_id,
+                })),
+            ),
+            OpOut::CanisterResult(Err(user_error)) => (
+                StatusCode::OK,
+                ApiResponse::Success(RawSubmitIngressResult::Err(user_error)),
+            ),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("{:?}", e),
+                },
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
 impl From<OpOut> for (StatusCode, ApiResponse<()>) {
     fn from(value: OpOut) -> Self {
         match value {
             OpOut::NoOutput => (StatusCode::OK, ApiResponse::Success(())),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("{:?}", e),
+                },
+            ),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 ApiResponse::Error {
@@ -206,21 +337,25 @@ impl From<OpOut> for (StatusCode, ApiResponse<RawStableMemory>) {
     }
 }
 
+fn to_raw_canister_result(wasm_result: Result<WasmResult, UserError>) -> RawCanisterResult {
+    match wasm_result {
+        Ok(WasmResult::Reply(wasm_result)) => {
+            RawCanisterResult::Ok(RawWasmResult::Reply(wasm_result))
+        }
+        Ok(WasmResult::Reject(error_message)) => {
+            RawCanisterResult::Ok(RawWasmResult::Reject(error_message))
+        }
+        Err(user_error) => RawCanisterResult::Err(user_error),
+    }
+}
+
 impl From<OpOut> for (StatusCode, ApiResponse<RawCanisterResult>) {
     fn from(value: OpOut) -> Self {
         match value {
-            OpOut::CanisterResult(wasm_result) => {
-                let inner = match wasm_result {
-                    Ok(WasmResult::Reply(wasm_result)) => {
-                        RawCanisterResult::Ok(RawWasmResult::Reply(wasm_result))
-                    }
-                    Ok(WasmResult::Reject(error_message)) => {
-                        RawCanisterResult::Ok(RawWasmResult::Reject(error_message))
-                    }
-                    Err(user_error) => RawCanisterResult::Err(user_error),
-                };
-                (StatusCode::OK, ApiResponse::Success(inner))
-            }
+            OpOut::CanisterResult(wasm_result) => (
+                StatusCode::OK,
+                ApiResponse::Success(to_raw_canister_result(wasm_result)),
+            ),
             OpOut::Error(e) => (
                 StatusCode::BAD_REQUEST,
                 ApiResponse::Error {
@@ -298,6 +433,50 @@ impl From<OpOut> for (StatusCode, ApiResponse<Vec<u8>>) {
     }
 }
 
+impl From<OpOut> for (StatusCode, ApiResponse<Vec<RawCanisterHttpRequest>>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::CanisterHttp(canister_http_requests) => {
+                (StatusCode::OK, ApiResponse::Success(canister_http_requests))
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
+impl From<OpOut> for (StatusCode, ApiResponse<Vec<RawBitcoinTransaction>>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::BitcoinTransactions(transactions) => (
+                StatusCode::OK,
+                ApiResponse::Success(
+                    transactions
+                        .into_iter()
+                        .map(|transaction| RawBitcoinTransaction { transaction })
+                        .collect(),
+                ),
+            ),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("{:?}", e),
+                },
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------------------------------------------- //
 // Read handlers
 
@@ -325,6 +504,28 @@ pub async fn handler_query(
     }
 }
 
+pub async fn handler_ingress_status(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_message_id): extract::Json<RawMessageId>,
+) -> (StatusCode, Json<ApiResponse<Option<RawCanisterResult>>>) {
+    let timeout = timeout_or_default(headers);
+    match crate::pocket_ic::MessageId::try_from(raw_message_id) {
+        Ok(message_id) => {
+            let op = IngressMessageStatus(message_id);
+            let (code, response) = run_operation(&api_state, instance_id, timeout, op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
 pub async fn handler_get_time(
     State(AppState { api_state, .. }): State<AppState>,
     headers: HeaderMap,
@@ -417,6 +618,28 @@ pub async fn handler_pub_key(
     (code, Json(res))
 }
 
+pub async fn handler_get_canister_http(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<Vec<RawCanisterHttpRequest>>>) {
+    let timeout = timeout_or_default(headers);
+    let op = GetCanisterHttp;
+    let (code, res) = run_operation(&api_state, instance_id, timeout, op).await;
+    (code, Json(res))
+}
+
+pub async fn handler_get_bitcoin_transactions(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<Vec<RawBitcoinTransaction>>>) {
+    let timeout = timeout_or_default(headers);
+    let op = GetBitcoinTransactions;
+    let (code, res) = run_operation(&api_state, instance_id, timeout, op).await;
+    (code, Json(res))
+}
+
 // ----------------------------------------------------------------------------------------------------------------- //
 // Update handlers
 
@@ -443,6 +666,51 @@ pub async fn handler_execute_ingress_message(
     }
 }
 
+pub async fn handler_submit_ingress_message(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_canister_call): extract::Json<RawCanisterCall>,
+) -> (StatusCode, Json<ApiResponse<RawSubmitIngressResult>>) {
+    let timeout = timeout_or_default(headers);
+    match crate::pocket_ic::CanisterCall::try_from(raw_canister_call) {
+        Ok(canister_call) => {
+            let ingress_op = SubmitIngressMessage(canister_call);
+            let (code, response) =
+                run_operation(&api_state, instance_id, timeout, ingress_op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
+pub async fn handler_await_ingress_message(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_message_id): extract::Json<RawMessageId>,
+) -> (StatusCode, Json<ApiResponse<RawCanisterResult>>) {
+    let timeout = timeout_or_default(headers);
+    match crate::pocket_ic::MessageId::try_from(raw_message_id) {
+        Ok(message_id) => {
+            let op = AwaitIngressMessage(message_id);
+            let (code, response) = run_operation(&api_state, instance_id, timeout, op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
 pub async fn handler_set_time(
     State(AppState { api_state, .. }): State<AppState>,
     Path(instance_id): Path<InstanceId>,
@@ -515,6 +783,245 @@ pub async fn handler_tick(
     (code, Json(res))
 }
 
+pub async fn handler_mock_canister_http(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    axum::extract::Json(raw): axum::extract::Json<RawMockCanisterHttpResponse>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let timeout = timeout_or_default(headers);
+    match MockCanisterHttp::try_from(raw) {
+        Ok(op) => {
+            let (code, res) = run_operation(&api_state, instance_id, timeout, op).await;
+            (code, Json(res))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
+pub async fn handler_mine_bitcoin_blocks(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    axum::extract::Json(RawMineBitcoinBlocks { addresses }): axum::extract::Json<
+        RawMineBitcoinBlocks,
+    >,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let timeout = timeout_or_default(headers);
+    let op = MineBitcoinBlocks { addresses };
+    let (code, res) = run_operation(&api_state, instance_id, timeout, op).await;
+    (code, Json(res))
+}
+
+pub async fn handler_checkpoint(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    axum::extract::Json(RawCheckpoint { checkpoint_dir }): axum::extract::Json<RawCheckpoint>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let timeout = timeout_or_default(headers);
+    let op = Checkpoint { checkpoint_dir };
+    let (code, res) = run_operation(&api_state, instance_id, timeout, op).await;
+    (code, Json(res))
+}
+
+// ----------------------------------------------------------------------------------------------------------------- //
+// IC HTTP interface handlers
+
+/// Runs an operation backing the IC HTTP interface. If the instance is busy with another
+/// operation (e.g., a round executed in live mode), the operation is retried until the
+/// instance becomes available.
+async fn run_http_interface_operation(
+    api_state: &ApiState,
+    instance_id: InstanceId,
+    op: impl Operation<TargetType = PocketIc> + Clone + Send + Sync + 'static,
+) -> Response {
+    loop {
+        match api_state
+            .update_with_timeout(
+                op.clone().on_instance(instance_id),
+                Some(HTTP_INTERFACE_TIMEOUT),
+            )
+            .await
+        {
+            Err(e) => return (StatusCode::NOT_FOUND, format!("{:?}", e)).into_response(),
+            Ok(UpdateReply::Busy { .. }) => tokio::time::sleep(BUSY_RETRY_INTERVAL).await,
+            Ok(UpdateReply::Started { .. }) => {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "The request timed out.".to_string(),
+                )
+                    .into_response()
+            }
+            Ok(UpdateReply::Output(OpOut::NoOutput)) => {
+                return StatusCode::ACCEPTED.into_response()
+            }
+            Ok(UpdateReply::Output(OpOut::Bytes(bytes))) => {
+                return (
+                    StatusCode::OK,
+                    [(http::header::CONTENT_TYPE, CONTENT_TYPE_CBOR)],
+                    bytes,
+                )
+                    .into_response()
+            }
+            Ok(UpdateReply::Output(op_out @ OpOut::Error(_))) => {
+                return (StatusCode::BAD_REQUEST, format!("{:?}", op_out)).into_response()
+            }
+            Ok(UpdateReply::Output(_)) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "operation returned invalid type".to_string(),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+fn parse_effective_canister_id(effective_canister_id: &str) -> Result<CanisterId, Response> {
+    CanisterId::from_str(effective_canister_id).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid effective canister ID: {}", e),
+        )
+            .into_response()
+    })
+}
+
+fn parse_envelope<C: DeserializeOwned>(body: &[u8]) -> Result<HttpRequestEnvelope<C>, Response> {
+    serde_cbor::from_slice(body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Could not parse body as request envelope: {}", e),
+        )
+            .into_response()
+    })
+}
+
+/// Returns the canister IDs that the sender's delegations are restricted to, i.e., the
+/// intersection of the targets of all delegations (`None` if no delegation is restricted).
+fn delegation_targets(
+    authentication: &Authentication,
+) -> Result<Option<BTreeSet<CanisterId>>, Response> {
+    let delegations = match authentication {
+        Authentication::Authenticated(signature) => signature.sender_delegation.as_deref(),
+        Authentication::Anonymous => None,
+    };
+    let mut targets: Option<BTreeSet<CanisterId>> = None;
+    for signed_delegation in delegations.unwrap_or_default() {
+        let delegation_targets = signed_delegation
+            .delegation()
+            .targets()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e).into_response())?;
+        if let Some(delegation_targets) = delegation_targets {
+            targets = Some(match targets {
+                None => delegation_targets,
+                Some(targets) => targets.intersection(&delegation_targets).cloned().collect(),
+            });
+        }
+    }
+    Ok(targets)
+}
+
+fn bad_request(e: HttpRequestError) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        format!("Malformed request: {:?}", e),
+    )
+        .into_response()
+}
+
+pub async fn handler_status(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+) -> Response {
+    run_http_interface_operation(&api_state, instance_id, StatusRequest).await
+}
+
+/// Unlike the replica's `/api/v2/canister/<ecid>/call` endpoint, this handler does not
+/// verify the signature of the request nor the signatures of its sender delegations:
+/// PocketIC accepts calls on behalf of any sender.
+pub async fn handler_call(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path((instance_id, effective_canister_id)): Path<(InstanceId, String)>,
+    body: Bytes,
+) -> Response {
+    let effective_canister_id = match parse_effective_canister_id(&effective_canister_id) {
+        Ok(effective_canister_id) => effective_canister_id,
+        Err(response) => return response,
+    };
+    let msg = match parse_envelope::<HttpCallContent>(&body).map(SignedIngress::try_from) {
+        Ok(Ok(msg)) => msg,
+        Ok(Err(e)) => return bad_request(e),
+        Err(response) => return response,
+    };
+    let op = CallRequest {
+        effective_canister_id,
+        msg,
+    };
+    run_http_interface_operation(&api_state, instance_id, op).await
+}
+
+/// Unlike the replica's `/api/v2/canister/<ecid>/query` endpoint, this handler does not
+/// verify the signature of the request nor the signatures of its sender delegations:
+/// PocketIC executes queries on behalf of any sender.
+pub async fn handler_query_request(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path((instance_id, effective_canister_id)): Path<(InstanceId, String)>,
+    body: Bytes,
+) -> Response {
+    let effective_canister_id = match parse_effective_canister_id(&effective_canister_id) {
+        Ok(effective_canister_id) => effective_canister_id,
+        Err(response) => return response,
+    };
+    let query =
+        match parse_envelope::<HttpQueryContent>(&body).map(HttpRequest::<UserQuery>::try_from) {
+            Ok(Ok(request)) => request.content().clone(),
+            Ok(Err(e)) => return bad_request(e),
+            Err(response) => return response,
+        };
+    let op = QueryRequest {
+        effective_canister_id,
+        query,
+    };
+    run_http_interface_operation(&api_state, instance_id, op).await
+}
+
+/// As for calls and queries, the signature of the request and the signatures of its
+/// sender delegations are not verified. The delegation targets are still enforced.
+pub async fn handler_read_state(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path((instance_id, effective_canister_id)): Path<(InstanceId, String)>,
+    body: Bytes,
+) -> Response {
+    let effective_canister_id = match parse_effective_canister_id(&effective_canister_id) {
+        Ok(effective_canister_id) => effective_canister_id,
+        Err(response) => return response,
+    };
+    let request = match parse_envelope::<HttpReadStateContent>(&body)
+        .map(HttpRequest::<ReadState>::try_from)
+    {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => return bad_request(e),
+        Err(response) => return response,
+    };
+    let targets = match delegation_targets(request.authentication()) {
+        Ok(targets) => targets,
+        Err(response) => return response,
+    };
+    let op = ReadStateRequest {
+        effective_canister_id,
+        read_state: request.take_content(),
+        targets,
+    };
+    run_http_interface_operation(&api_state, instance_id, op).await
+}
+
 // ----------------------------------------------------------------------------------------------------------------- //
 // Other handlers
 
@@ -586,6 +1093,39 @@ pub async fn create_instance(
     )
 }
 
+pub async fn create_instance_from_checkpoint(
+    State(AppState {
+        api_state,
+        min_alive_until: _,
+        runtime,
+        blob_store: _,
+    }): State<AppState>,
+    extract::Json(RawCheckpoint { checkpoint_dir }): extract::Json<RawCheckpoint>,
+) -> (StatusCode, Json<rest::CreateInstanceResponse>) {
+    let pocket_ic =
+        tokio::task::spawn_blocking(move || PocketIc::from_checkpoint(runtime, checkpoint_dir))
+            .await
+            .expect("Failed to launch PocketIC");
+
+    match pocket_ic {
+        Ok(pocket_ic) => {
+            let topology = pocket_ic.topology.clone();
+            let instance_id = api_state.add_instance(pocket_ic).await;
+            (
+                StatusCode::CREATED,
+                Json(rest::CreateInstanceResponse::Created {
+                    instance_id,
+                    topology,
+                }),
+            )
+        }
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(rest::CreateInstanceResponse::Error { message }),
+        ),
+    }
+}
+
 pub async fn list_instances(
     State(AppState { api_state, .. }): State<AppState>,
 ) -> Json<Vec<String>> {
@@ -603,6 +1143,24 @@ pub async fn list_instances(
     Json(instances)
 }
 
+pub async fn auto_progress(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(id): Path<InstanceId>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    match api_state.auto_progress(id, AutoTick).await {
+        Ok(()) => (StatusCode::OK, Json(ApiResponse::Success(()))),
+        Err(message) => (StatusCode::NOT_FOUND, Json(ApiResponse::Error { message })),
+    }
+}
+
+pub async fn stop_progress(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(id): Path<InstanceId>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    api_state.stop_progress(id).await;
+    (StatusCode::OK, Json(ApiResponse::Success(())))
+}
+
 pub async fn delete_instance(
     State(AppState { api_state, .. }): State<AppState>,
     Path(id): Path<InstanceId>,
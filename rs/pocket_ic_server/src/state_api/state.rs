@@ -3,16 +3,17 @@
 /// interface guarantees consistency and determinism.
 ///
 use crate::InstanceId;
-use crate::{Computation, OpId, Operation};
+use crate::{BindOperation, Computation, OpId, Operation};
 use base64;
 use ic_types::{CanisterId, SubnetId};
 use ic_utils::thread::JoinOnDrop;
+use pocket_ic::common::rest::RawCanisterHttpRequest;
 use pocket_ic::{ErrorCode, UserError, WasmResult};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc, thread::Builder as ThreadBuilder, time::Duration};
 use tokio::{
     sync::{mpsc, Mutex, RwLock},
-    task::spawn_blocking,
+    task::{spawn, spawn_blocking, JoinHandle},
     time,
 };
 use tracing::trace;
@@ -20,6 +21,9 @@ use tracing::trace;
 // The maximum wait time for a computation to finish synchronously.
 const DEFAULT_SYNC_WAIT_DURATION: Duration = Duration::from_secs(10);
 
+// The interval between two consecutive operations executed by a progress thread.
+const AUTO_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
 pub const STATE_LABEL_HASH_SIZE: usize = 32;
 
 /// Uniquely identifies a state.
@@ -79,6 +83,14 @@ struct InnerApiState<T> {
     // PocketIC instance to a background worker and drop it there.
     drop_sender: mpsc::UnboundedSender<T>,
     _drop_worker_handle: JoinOnDrop<()>,
+    // Background tasks making progress on instances in live mode.
+    progress_threads: Mutex<HashMap<InstanceId, ProgressThread>>,
+}
+
+/// A background task that periodically executes an operation on an instance.
+struct ProgressThread {
+    handle: JoinHandle<()>,
+    stop_sender: mpsc::Sender<()>,
 }
 
 pub struct PocketIcApiStateBuilder<T> {
@@ -142,6 +154,7 @@ where
             sync_wait_time,
             drop_sender,
             _drop_worker_handle: JoinOnDrop::new(drop_handle),
+            progress_threads: Mutex::new(HashMap::new()),
         });
         PocketIcApiState { inner }
     }
@@ -165,6 +178,9 @@ pub enum OpOut {
     Cycles(u128),
     Bytes(Vec<u8>),
     SubnetId(SubnetId),
+    CanisterHttp(Vec<RawCanisterHttpRequest>),
+    MessageId((SubnetId, Vec<u8>)),
+    BitcoinTransactions(Vec<Vec<u8>>),
     Error(PocketIcError),
 }
 
@@ -173,6 +189,12 @@ pub enum PocketIcError {
     CanisterNotFound(CanisterId),
     BadIngressMessage(String),
     SubnetNotFound(candid::Principal),
+    InvalidCanisterHttpRequestId((SubnetId, u64)),
+    InvalidRejectCode(u64),
+    CheckpointFailed(String),
+    InvalidReadStateRequest(String),
+    BitcoinSubnetNotFound,
+    InvalidBitcoinAddress(String),
 }
 
 impl From<Result<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>> for OpOut {
@@ -227,8 +249,39 @@ impl std::fmt::Debug for OpOut {
             OpOut::Error(PocketIcError::SubnetNotFound(sid)) => {
                 write!(f, "SubnetNotFound({})", sid)
             }
+            OpOut::Error(PocketIcError::InvalidCanisterHttpRequestId((subnet_id, request_id))) => {
+                write!(
+                    f,
+                    "InvalidCanisterHttpRequestId({},{})",
+                    subnet_id, request_id
+                )
+            }
+            OpOut::Error(PocketIcError::InvalidRejectCode(code)) => {
+                write!(f, "InvalidRejectCode({})", code)
+            }
+            OpOut::Error(PocketIcError::CheckpointFailed(msg)) => {
+                write!(f, "CheckpointFailed({})", msg)
+            }
+            OpOut::Error(PocketIcError::InvalidReadStateRequest(msg)) => {
+                write!(f, "InvalidReadStateRequest({})", msg)
+            }
+            OpOut::Error(PocketIcError::BitcoinSubnetNotFound) => {
+                write!(f, "BitcoinSubnetNotFound")
+            }
+            OpOut::Error(PocketIcError::InvalidBitcoinAddress(msg)) => {
+                write!(f, "InvalidBitcoinAddress({})", msg)
+            }
             OpOut::Bytes(bytes) => write!(f, "Bytes({})", base64::encode(bytes)),
             OpOut::SubnetId(subnet_id) => write!(f, "SubnetId({})", subnet_id),
+            OpOut::CanisterHttp(canister_http) => write!(f, "CanisterHttp({:?})", canister_http),
+            OpOut::MessageId((subnet_id, message_id)) => {
+                write!(f, "MessageId({},{})", subnet_id, hex::encode(message_id))
+            }
+            OpOut::BitcoinTransactions(transactions) => write!(
+                f,
+                "BitcoinTransactions({:?})",
+                transactions.iter().map(hex::encode).collect::<Vec<_>>()
+            ),
         }
     }
 }
@@ -329,6 +382,7 @@ where
     }
 
     pub async fn delete_instance(&self, instance_id: InstanceId) {
+        self.stop_progress(instance_id).await;
         let instances = self.inner.instances.read().await;
         let mut instance_state = instances[instance_id].lock().await;
         if let InstanceState::Available(pocket_ic) =
@@ -338,6 +392,69 @@ where
         }
     }
 
+    /// Puts the instance into live mode: A background task executes the given operation
+    /// (e.g., a round on all subnets) on the instance every [AUTO_PROGRESS_INTERVAL] until
+    /// [Self::stop_progress] is called or the instance is deleted. If the instance is busy
+    /// with another operation, the background task skips that iteration.
+    pub async fn auto_progress<S>(&self, instance_id: InstanceId, op: S) -> Result<(), String>
+    where
+        S: Operation<TargetType = T> + Clone + Send + 'static,
+    {
+        if instance_id >= self.inner.instances.read().await.len() {
+            return Err("Instance not found".to_string());
+        }
+        let mut progress_threads = self.inner.progress_threads.lock().await;
+        if progress_threads.contains_key(&instance_id) {
+            return Ok(());
+        }
+        let (stop_sender, mut stop_receiver) = mpsc::channel::<()>(1);
+        let api_state = self.clone();
+        let handle = spawn(async move {
+            loop {
+                if api_state
+                    .update(op.clone().on_instance(instance_id))
+                    .await
+                    .is_err()
+                {
+                    // The instance has been deleted.
+                    break;
+                }
+                if time::timeout(AUTO_PROGRESS_INTERVAL, stop_receiver.recv())
+                    .await
+                    .is_ok()
+                {
+                    break;
+                }
+            }
+        });
+        progress_threads.insert(
+            instance_id,
+            ProgressThread {
+                handle,
+                stop_sender,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stops the background task started by [Self::auto_progress], if any.
+    pub async fn stop_progress(&self, instance_id: InstanceId) {
+        let progress_thread = self
+            .inner
+            .progress_threads
+            .lock()
+            .await
+            .remove(&instance_id);
+        if let Some(ProgressThread {
+            handle,
+            stop_sender,
+        }) = progress_thread
+        {
+            let _ = stop_sender.send(()).await;
+            let _ = handle.await;
+        }
+    }
+
     pub async fn list_instances(&self) -> Vec<InstanceState<()>> {
         let instances = self.inner.instances.read().await;
         let mut res = vec![];
@@ -1,4 +1,7 @@
-use pocket_ic::common::rest::SubnetConfigSet;
+use pocket_ic::common::rest::{
+    CanisterHttpReply, CanisterHttpResponse, CreateInstanceResponse, ExtendedSubnetConfigSet,
+    RawCanisterHttpRequest, RawMockCanisterHttpResponse, SubnetConfigSet,
+};
 use reqwest::{StatusCode, Url};
 
 use std::path::PathBuf;
@@ -18,7 +21,6 @@ fn test_status() {
 
 #[test]
 fn test_creation_of_instance_extended() {
-    use pocket_ic::common::rest::ExtendedSubnetConfigSet;
     let url = start_server();
     let client = reqwest::blocking::Client::new();
     let response = client
@@ -34,6 +36,60 @@ fn test_creation_of_instance_extended() {
     assert!(!response.text().unwrap().is_empty());
 }
 
+#[test]
+fn test_canister_http_endpoints() {
+    let url = start_server();
+    let client = reqwest::blocking::Client::new();
+    let response = client
+        .post(url.join("instances").unwrap())
+        .json(&Into::<ExtendedSubnetConfigSet>::into(SubnetConfigSet {
+            application: 1,
+            ..Default::default()
+        }))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let (instance_id, topology) = match response.json::<CreateInstanceResponse>().unwrap() {
+        CreateInstanceResponse::Created {
+            instance_id,
+            topology,
+        } => (instance_id, topology),
+        CreateInstanceResponse::Error { message } => panic!("{}", message),
+    };
+    let instance_url = url.join(&format!("instances/{instance_id}/")).unwrap();
+
+    // No canister HTTP outcalls are pending on a new instance.
+    let response = client
+        .post(instance_url.join("read/get_canister_http").unwrap())
+        .json("")
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let canister_http_requests: Vec<RawCanisterHttpRequest> = response.json().unwrap();
+    assert!(canister_http_requests.is_empty());
+
+    // Mocking a response to an outcall that is not pending is a bad request.
+    let subnet_id = topology.get_app_subnets()[0];
+    let response = client
+        .post(instance_url.join("update/mock_canister_http").unwrap())
+        .json(&RawMockCanisterHttpResponse {
+            subnet_id: subnet_id.as_slice().to_vec(),
+            request_id: 0,
+            response: CanisterHttpResponse::CanisterHttpReply(CanisterHttpReply {
+                status: 200,
+                headers: vec![],
+                body: vec![],
+            }),
+        })
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response
+        .text()
+        .unwrap()
+        .contains("InvalidCanisterHttpRequestId"));
+}
+
 #[test]
 fn test_blob_store() {
     let url = start_server();
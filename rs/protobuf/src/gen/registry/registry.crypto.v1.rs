@@ -41,6 +41,42 @@ pub struct EcdsaKeyId {
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize, serde::Deserialize, candid::CandidType, Eq)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrKeyId {
+    #[prost(enumeration = "SchnorrAlgorithm", tag = "1")]
+    pub algorithm: i32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+/// A list of subnets that can sign with this Schnorr key.
+/// This allows replicas to route their signing requests to the right subnets.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrSigningSubnetList {
+    #[prost(message, repeated, tag = "1")]
+    pub subnets: ::prost::alloc::vec::Vec<super::super::super::types::v1::SubnetId>,
+}
+#[derive(serde::Serialize, serde::Deserialize, candid::CandidType, Eq)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdKeyId {
+    #[prost(enumeration = "VetKdCurve", tag = "1")]
+    pub curve: i32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+/// A list of subnets that can derive keys with this vetKD key.
+/// This allows replicas to route their key derivation requests to the right subnets.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdEnabledSubnetList {
+    #[prost(message, repeated, tag = "1")]
+    pub subnets: ::prost::alloc::vec::Vec<super::super::super::types::v1::SubnetId>,
+}
 /// An algorithm ID. This is used to specify the signature algorithm associated with a public key.
 #[derive(
     serde::Serialize,
@@ -76,6 +112,7 @@ pub enum AlgorithmId {
     MegaSecp256k1 = 16,
     ThresholdEcdsaSecp256r1 = 17,
     ThresholdSchnorrBip340 = 18,
+    ThresholdEd25519 = 19,
 }
 impl AlgorithmId {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -103,6 +140,7 @@ impl AlgorithmId {
             AlgorithmId::MegaSecp256k1 => "ALGORITHM_ID_MEGA_SECP_256K1",
             AlgorithmId::ThresholdEcdsaSecp256r1 => "ALGORITHM_ID_THRESHOLD_ECDSA_SECP_256R1",
             AlgorithmId::ThresholdSchnorrBip340 => "ALGORITHM_ID_THRESHOLD_SCHNORR_BIP340",
+            AlgorithmId::ThresholdEd25519 => "ALGORITHM_ID_THRESHOLD_ED25519",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -127,6 +165,7 @@ impl AlgorithmId {
             "ALGORITHM_ID_MEGA_SECP_256K1" => Some(Self::MegaSecp256k1),
             "ALGORITHM_ID_THRESHOLD_ECDSA_SECP_256R1" => Some(Self::ThresholdEcdsaSecp256r1),
             "ALGORITHM_ID_THRESHOLD_SCHNORR_BIP340" => Some(Self::ThresholdSchnorrBip340),
+            "ALGORITHM_ID_THRESHOLD_ED25519" => Some(Self::ThresholdEd25519),
             _ => None,
         }
     }
@@ -171,3 +210,86 @@ impl EcdsaCurve {
         }
     }
 }
+/// Types of algorithms that can be used for Schnorr signatures.
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    candid::CandidType,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum SchnorrAlgorithm {
+    Unspecified = 0,
+    Bip340secp256k1 = 1,
+    Ed25519 = 2,
+}
+impl SchnorrAlgorithm {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SchnorrAlgorithm::Unspecified => "SCHNORR_ALGORITHM_UNSPECIFIED",
+            SchnorrAlgorithm::Bip340secp256k1 => "SCHNORR_ALGORITHM_BIP340SECP256K1",
+            SchnorrAlgorithm::Ed25519 => "SCHNORR_ALGORITHM_ED25519",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SCHNORR_ALGORITHM_UNSPECIFIED" => Some(Self::Unspecified),
+            "SCHNORR_ALGORITHM_BIP340SECP256K1" => Some(Self::Bip340secp256k1),
+            "SCHNORR_ALGORITHM_ED25519" => Some(Self::Ed25519),
+            _ => None,
+        }
+    }
+}
+/// Types of curves that can be used for vetKD key derivation.
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    candid::CandidType,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum VetKdCurve {
+    Unspecified = 0,
+    Bls12381G2 = 1,
+}
+impl VetKdCurve {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            VetKdCurve::Unspecified => "VET_KD_CURVE_UNSPECIFIED",
+            VetKdCurve::Bls12381G2 => "VET_KD_CURVE_BLS12_381_G2",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "VET_KD_CURVE_UNSPECIFIED" => Some(Self::Unspecified),
+            "VET_KD_CURVE_BLS12_381_G2" => Some(Self::Bls12381G2),
+            _ => None,
+        }
+    }
+}
@@ -33,6 +33,9 @@ pub mod call_context {
         pub canister_id: ::core::option::Option<super::super::super::super::types::v1::CanisterId>,
         #[prost(uint64, tag = "2")]
         pub callback_id: u64,
+        /// If non-zero, this originates from a best-effort canister update call.
+        #[prost(uint32, tag = "3")]
+        pub deadline_seconds: u32,
     }
     /// System task is either a Heartbeat or a GlobalTimer.
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -91,6 +94,9 @@ pub struct Callback {
     #[prost(message, optional, tag = "9")]
     pub prepayment_for_response_transmission:
         ::core::option::Option<super::super::queues::v1::Cycles>,
+    /// If non-zero, this is a best-effort call.
+    #[prost(uint32, tag = "10")]
+    pub deadline_seconds: u32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -111,6 +117,14 @@ pub struct CallContextManager {
     pub call_contexts: ::prost::alloc::vec::Vec<CallContextEntry>,
     #[prost(message, repeated, tag = "4")]
     pub callbacks: ::prost::alloc::vec::Vec<CallbackEntry>,
+    /// Callbacks of best-effort calls that were already rejected because their
+    /// deadline expired.
+    #[prost(uint64, repeated, tag = "5")]
+    pub expired_callbacks: ::prost::alloc::vec::Vec<u64>,
+    /// All best-effort callbacks with deadlines at or before this time (in seconds
+    /// since UNIX epoch) have expired or received a response.
+    #[prost(uint32, tag = "6")]
+    pub deadlines_expired_up_to_seconds: u32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -274,6 +288,8 @@ pub mod stop_canister_context {
         pub cycles: ::core::option::Option<super::super::super::queues::v1::Cycles>,
         #[prost(uint64, optional, tag = "5")]
         pub call_id: ::core::option::Option<u64>,
+        #[prost(uint32, tag = "6")]
+        pub deadline_seconds: u32,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
@@ -526,6 +542,37 @@ pub struct WasmChunkStoreMetadata {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LogVisibilityControllers {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LogVisibilityPublic {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LogVisibilityAllowedViewers {
+    #[prost(message, repeated, tag = "1")]
+    pub principals: ::prost::alloc::vec::Vec<super::super::super::types::v1::PrincipalId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LogVisibilityV2 {
+    #[prost(oneof = "log_visibility_v2::LogVisibilityV2", tags = "1, 2, 3")]
+    pub log_visibility_v2: ::core::option::Option<log_visibility_v2::LogVisibilityV2>,
+}
+/// Nested message and enum types in `LogVisibilityV2`.
+pub mod log_visibility_v2 {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum LogVisibilityV2 {
+        #[prost(message, tag = "1")]
+        Controllers(super::LogVisibilityControllers),
+        #[prost(message, tag = "2")]
+        Public(super::LogVisibilityPublic),
+        #[prost(message, tag = "3")]
+        AllowedViewers(super::LogVisibilityAllowedViewers),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CanisterLogRecord {
     #[prost(uint64, tag = "1")]
     pub idx: u64,
@@ -613,12 +660,20 @@ pub struct CanisterStateBits {
     /// Statistics on query execution for entire lifetime of canister.
     #[prost(message, optional, tag = "41")]
     pub total_query_stats: ::core::option::Option<TotalQueryStats>,
-    /// Log visibility for the canister.
+    /// Log visibility for the canister. Superseded by `log_visibility_v2`, but
+    /// still populated (with `allowed_viewers` mapped to controllers) so that
+    /// the state remains readable by replicas that predate `log_visibility_v2`.
     #[prost(enumeration = "LogVisibility", tag = "42")]
     pub log_visibility: i32,
     /// Log records of the canister.
     #[prost(message, repeated, tag = "43")]
     pub canister_log_records: ::prost::alloc::vec::Vec<CanisterLogRecord>,
+    /// The user-specified upper limit on the Wasm memory size of the canister.
+    #[prost(uint64, optional, tag = "44")]
+    pub wasm_memory_limit: ::core::option::Option<u64>,
+    /// Log visibility for the canister, including the list of allowed viewers.
+    #[prost(message, optional, tag = "45")]
+    pub log_visibility_v2: ::core::option::Option<LogVisibilityV2>,
     #[prost(oneof = "canister_state_bits::CanisterStatus", tags = "11, 12, 13")]
     pub canister_status: ::core::option::Option<canister_state_bits::CanisterStatus>,
 }
@@ -712,6 +767,8 @@ pub enum CyclesUseCase {
     DeletedCanisters = 10,
     NonConsumed = 11,
     BurnedCycles = 12,
+    SchnorrOutcalls = 13,
+    VetKd = 14,
 }
 impl CyclesUseCase {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -735,6 +792,8 @@ impl CyclesUseCase {
             CyclesUseCase::DeletedCanisters => "CYCLES_USE_CASE_DELETED_CANISTERS",
             CyclesUseCase::NonConsumed => "CYCLES_USE_CASE_NON_CONSUMED",
             CyclesUseCase::BurnedCycles => "CYCLES_USE_CASE_BURNED_CYCLES",
+            CyclesUseCase::SchnorrOutcalls => "CYCLES_USE_CASE_SCHNORR_OUTCALLS",
+            CyclesUseCase::VetKd => "CYCLES_USE_CASE_VET_KD",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -755,6 +814,8 @@ impl CyclesUseCase {
             "CYCLES_USE_CASE_DELETED_CANISTERS" => Some(Self::DeletedCanisters),
             "CYCLES_USE_CASE_NON_CONSUMED" => Some(Self::NonConsumed),
             "CYCLES_USE_CASE_BURNED_CYCLES" => Some(Self::BurnedCycles),
+            "CYCLES_USE_CASE_SCHNORR_OUTCALLS" => Some(Self::SchnorrOutcalls),
+            "CYCLES_USE_CASE_VET_KD" => Some(Self::VetKd),
             _ => None,
         }
     }
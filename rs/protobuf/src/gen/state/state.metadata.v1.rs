@@ -30,6 +30,12 @@ pub struct SubnetTopology {
     #[prost(message, repeated, tag = "5")]
     pub ecdsa_keys_held:
         ::prost::alloc::vec::Vec<super::super::super::registry::crypto::v1::EcdsaKeyId>,
+    #[prost(message, repeated, tag = "6")]
+    pub schnorr_keys_held:
+        ::prost::alloc::vec::Vec<super::super::super::registry::crypto::v1::SchnorrKeyId>,
+    #[prost(message, repeated, tag = "7")]
+    pub vetkd_keys_held:
+        ::prost::alloc::vec::Vec<super::super::super::registry::crypto::v1::VetKdKeyId>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -49,6 +55,22 @@ pub struct EcdsaKeyEntry {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrKeyEntry {
+    #[prost(message, optional, tag = "1")]
+    pub key_id: ::core::option::Option<super::super::super::registry::crypto::v1::SchnorrKeyId>,
+    #[prost(message, repeated, tag = "2")]
+    pub subnet_ids: ::prost::alloc::vec::Vec<super::super::super::types::v1::SubnetId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdKeyEntry {
+    #[prost(message, optional, tag = "1")]
+    pub key_id: ::core::option::Option<super::super::super::registry::crypto::v1::VetKdKeyId>,
+    #[prost(message, repeated, tag = "2")]
+    pub subnet_ids: ::prost::alloc::vec::Vec<super::super::super::types::v1::SubnetId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NetworkTopology {
     #[prost(message, repeated, tag = "1")]
     pub subnets: ::prost::alloc::vec::Vec<SubnetsEntry>,
@@ -69,6 +91,10 @@ pub struct NetworkTopology {
     #[prost(message, repeated, tag = "7")]
     pub bitcoin_mainnet_canister_ids:
         ::prost::alloc::vec::Vec<super::super::super::types::v1::CanisterId>,
+    #[prost(message, repeated, tag = "8")]
+    pub schnorr_signing_subnets: ::prost::alloc::vec::Vec<SchnorrKeyEntry>,
+    #[prost(message, repeated, tag = "9")]
+    pub vetkd_enabled_subnets: ::prost::alloc::vec::Vec<VetKdKeyEntry>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -124,6 +150,56 @@ pub struct SignWithEcdsaContextTree {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignWithSchnorrContext {
+    #[prost(message, optional, tag = "1")]
+    pub request: ::core::option::Option<super::super::queues::v1::Request>,
+    #[prost(message, optional, tag = "2")]
+    pub key_id: ::core::option::Option<super::super::super::registry::crypto::v1::SchnorrKeyId>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub message: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub derivation_path_vec: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub pseudo_random_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "6")]
+    pub batch_time: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignWithSchnorrContextTree {
+    #[prost(uint64, tag = "1")]
+    pub callback_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub context: ::core::option::Option<SignWithSchnorrContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdDeriveEncryptedKeyContext {
+    #[prost(message, optional, tag = "1")]
+    pub request: ::core::option::Option<super::super::queues::v1::Request>,
+    #[prost(message, optional, tag = "2")]
+    pub key_id: ::core::option::Option<super::super::super::registry::crypto::v1::VetKdKeyId>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub derivation_path_vec: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub derivation_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub encryption_public_key: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub pseudo_random_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "7")]
+    pub batch_time: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdDeriveEncryptedKeyContextTree {
+    #[prost(uint64, tag = "1")]
+    pub callback_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub context: ::core::option::Option<VetKdDeriveEncryptedKeyContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HttpHeader {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
@@ -338,6 +414,11 @@ pub struct SubnetCallContextManager {
     pub stop_canister_calls: ::prost::alloc::vec::Vec<StopCanisterCallTree>,
     #[prost(message, repeated, tag = "16")]
     pub raw_rand_contexts: ::prost::alloc::vec::Vec<RawRandContext>,
+    #[prost(message, repeated, tag = "17")]
+    pub sign_with_schnorr_contexts: ::prost::alloc::vec::Vec<SignWithSchnorrContextTree>,
+    #[prost(message, repeated, tag = "18")]
+    pub vetkd_derive_encrypted_key_contexts:
+        ::prost::alloc::vec::Vec<VetKdDeriveEncryptedKeyContextTree>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -362,6 +443,12 @@ pub struct SubnetMetrics {
     pub canister_state_bytes: ::core::option::Option<u64>,
     #[prost(uint64, optional, tag = "10")]
     pub update_transactions_total: ::core::option::Option<u64>,
+    #[prost(message, optional, tag = "11")]
+    pub consumed_cycles_schnorr_outcalls:
+        ::core::option::Option<super::super::super::types::v1::NominalCycles>,
+    #[prost(message, optional, tag = "12")]
+    pub consumed_cycles_vetkd:
+        ::core::option::Option<super::super::super::types::v1::NominalCycles>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
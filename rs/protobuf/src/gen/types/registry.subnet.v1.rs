@@ -85,6 +85,14 @@ pub struct SubnetRecord {
     /// appropriate proposal which sets `is_halted` to `false` is approved.
     #[prost(bool, tag = "28")]
     pub halt_at_cup_height: bool,
+    /// Schnorr Config. Like `ecdsa_config`, this field cannot be set back to `None` once it has
+    /// been set to `Some`. To remove a key, the list of `key_ids` can be set to not include it.
+    #[prost(message, optional, tag = "29")]
+    pub schnorr_config: ::core::option::Option<SchnorrConfig>,
+    /// VetKD Config. Like `schnorr_config`, this field cannot be set back to `None` once it has
+    /// been set to `Some`. To remove a key, the list of `key_ids` can be set to not include it.
+    #[prost(message, optional, tag = "30")]
+    pub vetkd_config: ::core::option::Option<VetKdConfig>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -384,6 +392,33 @@ pub struct EcdsaConfig {
     #[prost(uint64, optional, tag = "6")]
     pub idkg_key_rotation_period_ms: ::core::option::Option<u64>,
 }
+/// Per subnet threshold Schnorr configuration
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchnorrConfig {
+    /// Identifiers for threshold Schnorr keys held by the subnet.
+    #[prost(message, repeated, tag = "1")]
+    pub key_ids: ::prost::alloc::vec::Vec<super::super::crypto::v1::SchnorrKeyId>,
+    /// The maximum number of signature requests that can be enqueued at once.
+    #[prost(uint32, tag = "2")]
+    pub max_queue_size: u32,
+    /// Signature requests will timeout after the given number of nano seconds.
+    #[prost(uint64, optional, tag = "3")]
+    pub signature_request_timeout_ns: ::core::option::Option<u64>,
+}
+/// Per subnet vetKD configuration
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VetKdConfig {
+    /// Identifiers for vetKD keys held by the subnet.
+    #[prost(message, repeated, tag = "1")]
+    pub key_ids: ::prost::alloc::vec::Vec<super::super::crypto::v1::VetKdKeyId>,
+    /// The maximum number of key derivation requests that can be enqueued at once.
+    #[prost(uint32, tag = "2")]
+    pub max_queue_size: u32,
+}
 #[derive(
     serde::Serialize,
     serde::Deserialize,
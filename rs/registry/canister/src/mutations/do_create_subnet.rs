@@ -353,6 +353,8 @@ impl From<CreateSubnetPayload> for SubnetRecord {
             ssh_readonly_access: val.ssh_readonly_access,
             ssh_backup_access: val.ssh_backup_access,
             ecdsa_config: val.ecdsa_config.map(|x| x.into()),
+            schnorr_config: None,
+            vetkd_config: None,
         }
     }
 }
@@ -542,6 +542,8 @@ mod tests {
             ssh_readonly_access: vec![],
             ssh_backup_access: vec![],
             ecdsa_config: None,
+            schnorr_config: None,
+            vetkd_config: None,
         };
 
         let payload = UpdateSubnetPayload {
@@ -643,6 +645,8 @@ mod tests {
                     }
                     .into()
                 ),
+                schnorr_config: None,
+                vetkd_config: None,
                 max_number_of_canisters: 10,
                 ssh_readonly_access: vec!["pub_key_0".to_string()],
                 ssh_backup_access: vec!["pub_key_1".to_string()],
@@ -684,6 +688,8 @@ mod tests {
             ssh_readonly_access: vec![],
             ssh_backup_access: vec![],
             ecdsa_config: None,
+            schnorr_config: None,
+            vetkd_config: None,
         };
 
         let payload = UpdateSubnetPayload {
@@ -759,6 +765,8 @@ mod tests {
                 ssh_readonly_access: vec![],
                 ssh_backup_access: vec![],
                 ecdsa_config: None,
+                schnorr_config: None,
+                vetkd_config: None,
             }
         );
     }
@@ -812,6 +820,8 @@ mod tests {
             ssh_readonly_access: vec![],
             ssh_backup_access: vec![],
             ecdsa_config: None,
+            schnorr_config: None,
+            vetkd_config: None,
         };
 
         let payload = UpdateSubnetPayload {
@@ -881,6 +891,8 @@ mod tests {
             ssh_readonly_access: vec![],
             ssh_backup_access: vec![],
             ecdsa_config: None,
+            schnorr_config: None,
+            vetkd_config: None,
         };
 
         let payload = UpdateSubnetPayload {
@@ -956,6 +968,8 @@ mod tests {
                 ssh_readonly_access: vec![],
                 ssh_backup_access: vec![],
                 ecdsa_config: None,
+                schnorr_config: None,
+                vetkd_config: None,
             }
         );
     }
@@ -994,6 +1008,8 @@ mod tests {
             ssh_readonly_access: vec![],
             ssh_backup_access: vec![],
             ecdsa_config: None,
+            schnorr_config: None,
+            vetkd_config: None,
         };
 
         let payload = UpdateSubnetPayload {
@@ -1069,6 +1085,8 @@ mod tests {
                 ssh_readonly_access: vec![],
                 ssh_backup_access: vec![],
                 ecdsa_config: None,
+                schnorr_config: None,
+                vetkd_config: None,
             }
         );
     }
@@ -12,9 +12,11 @@ pub mod node;
 pub mod node_operator;
 pub mod provisional_whitelist;
 pub mod routing_table;
+pub mod schnorr_keys;
 pub mod subnet;
 pub mod test_proto;
 pub mod unassigned_nodes;
+pub mod vetkd_keys;
 use ic_interfaces_registry::{RegistryClientResult, RegistryValue};
 use ic_types::registry::RegistryClientError::DecodeError;
 
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use ic_interfaces_registry::{RegistryClient, RegistryClientResult};
+use ic_management_canister_types::VetKdKeyId;
+use ic_protobuf::registry::crypto::v1::VetKdEnabledSubnetList;
+use ic_registry_keys::{
+    get_vetkd_key_id_from_enabled_subnet_list_key, VETKD_ENABLED_SUBNET_LIST_KEY_PREFIX,
+};
+use ic_types::{
+    registry::RegistryClientError, subnet_id_try_from_protobuf, RegistryVersion, SubnetId,
+};
+
+use crate::deserialize_registry_value;
+
+/// A trait that exposes which subnets are enabled to derive keys for each
+/// vetKD key.
+pub trait VetKdKeysRegistry {
+    /// Get a map from vetKD key ID -> list of subnets enabled to derive keys
+    /// with the key.  VetKD keys which have no enabled subnets are not
+    /// included in the result.
+    fn get_vetkd_enabled_subnets(
+        &self,
+        version: RegistryVersion,
+    ) -> RegistryClientResult<BTreeMap<VetKdKeyId, Vec<SubnetId>>>;
+}
+
+impl<T: RegistryClient + ?Sized> VetKdKeysRegistry for T {
+    fn get_vetkd_enabled_subnets(
+        &self,
+        version: RegistryVersion,
+    ) -> RegistryClientResult<BTreeMap<VetKdKeyId, Vec<SubnetId>>> {
+        let all_key_id_keys = self.get_key_family(VETKD_ENABLED_SUBNET_LIST_KEY_PREFIX, version)?;
+        let mut result = BTreeMap::new();
+        for registry_key in all_key_id_keys {
+            let bytes = self.get_value(&registry_key, version);
+            let subnets_proto =
+                deserialize_registry_value::<VetKdEnabledSubnetList>(bytes)?.unwrap_or_default();
+            let mut subnets = vec![];
+            for subnet_proto in subnets_proto.subnets.into_iter() {
+                subnets.push(subnet_id_try_from_protobuf(subnet_proto).map_err(|err| {
+                    RegistryClientError::DecodeError {
+                        error: err.to_string(),
+                    }
+                })?);
+            }
+            let key_id = get_vetkd_key_id_from_enabled_subnet_list_key(&registry_key)?;
+            if !subnets.is_empty() {
+                result.insert(key_id, subnets);
+            }
+        }
+        Ok(Some(result))
+    }
+}
@@ -6,7 +6,7 @@
 use candid::{CandidType, Deserialize};
 use core::fmt;
 use ic_base_types::{NodeId, SubnetId};
-use ic_management_canister_types::EcdsaKeyId;
+use ic_management_canister_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_types::crypto::KeyPurpose;
 use ic_types::registry::RegistryClientError;
 use ic_types::PrincipalId;
@@ -33,6 +33,8 @@ pub const CRYPTO_TLS_CERT_KEY_PREFIX: &str = "crypto_tls_cert_";
 pub const CRYPTO_THRESHOLD_SIGNING_KEY_PREFIX: &str = "crypto_threshold_signing_public_key_";
 pub const DATA_CENTER_KEY_PREFIX: &str = "data_center_record_";
 pub const ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX: &str = "key_id_";
+pub const SCHNORR_SIGNING_SUBNET_LIST_KEY_PREFIX: &str = "schnorr_key_id_";
+pub const VETKD_ENABLED_SUBNET_LIST_KEY_PREFIX: &str = "vetkd_key_id_";
 
 pub fn make_ecdsa_signing_subnet_list_key(key_id: &EcdsaKeyId) -> String {
     format!("{}{}", ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX, key_id)
@@ -59,6 +61,56 @@ pub fn get_ecdsa_key_id_from_signing_subnet_list_key(
         })
 }
 
+pub fn make_schnorr_signing_subnet_list_key(key_id: &SchnorrKeyId) -> String {
+    format!("{}{}", SCHNORR_SIGNING_SUBNET_LIST_KEY_PREFIX, key_id)
+}
+
+pub fn get_schnorr_key_id_from_signing_subnet_list_key(
+    signing_subnet_list_key: &str,
+) -> Result<SchnorrKeyId, RegistryClientError> {
+    let prefix_removed = signing_subnet_list_key
+        .strip_prefix(SCHNORR_SIGNING_SUBNET_LIST_KEY_PREFIX)
+        .ok_or_else(|| RegistryClientError::DecodeError {
+            error: format!(
+                "Schnorr Signing Subnet List key id {} does not start with prefix {}",
+                signing_subnet_list_key, SCHNORR_SIGNING_SUBNET_LIST_KEY_PREFIX
+            ),
+        })?;
+    prefix_removed
+        .parse::<SchnorrKeyId>()
+        .map_err(|error| RegistryClientError::DecodeError {
+            error: format!(
+                "Schnorr Signing Subnet List key id {} could not be converted to a SchnorrKeyId: {:?}",
+                signing_subnet_list_key, error
+            ),
+        })
+}
+
+pub fn make_vetkd_enabled_subnet_list_key(key_id: &VetKdKeyId) -> String {
+    format!("{}{}", VETKD_ENABLED_SUBNET_LIST_KEY_PREFIX, key_id)
+}
+
+pub fn get_vetkd_key_id_from_enabled_subnet_list_key(
+    enabled_subnet_list_key: &str,
+) -> Result<VetKdKeyId, RegistryClientError> {
+    let prefix_removed = enabled_subnet_list_key
+        .strip_prefix(VETKD_ENABLED_SUBNET_LIST_KEY_PREFIX)
+        .ok_or_else(|| RegistryClientError::DecodeError {
+            error: format!(
+                "VetKD Enabled Subnet List key id {} does not start with prefix {}",
+                enabled_subnet_list_key, VETKD_ENABLED_SUBNET_LIST_KEY_PREFIX
+            ),
+        })?;
+    prefix_removed
+        .parse::<VetKdKeyId>()
+        .map_err(|error| RegistryClientError::DecodeError {
+            error: format!(
+                "VetKD Enabled Subnet List key id {} could not be converted to a VetKdKeyId: {:?}",
+                enabled_subnet_list_key, error
+            ),
+        })
+}
+
 /// Returns the only key whose payload is the list of subnets.
 pub fn make_subnet_list_record_key() -> String {
     SUBNET_LIST_KEY.to_string()
@@ -344,7 +396,7 @@ pub fn make_nns_canister_records_key() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ic_management_canister_types::EcdsaCurve;
+    use ic_management_canister_types::{EcdsaCurve, SchnorrAlgorithm, VetKdCurve};
     use rand::Rng;
 
     #[test]
@@ -449,6 +501,45 @@ mod tests {
         )
     }
 
+    #[test]
+    fn schnorr_signing_subnet_list_key_round_trips() {
+        for algorithm in [SchnorrAlgorithm::Bip340Secp256k1, SchnorrAlgorithm::Ed25519] {
+            let key_id = SchnorrKeyId {
+                algorithm,
+                name: "some_key".to_string(),
+            };
+            let signing_subnet_list_key = make_schnorr_signing_subnet_list_key(&key_id);
+            assert_eq!(
+                get_schnorr_key_id_from_signing_subnet_list_key(&signing_subnet_list_key).unwrap(),
+                key_id
+            );
+        }
+    }
+
+    #[test]
+    fn schnorr_signing_subnet_list_key_is_not_in_ecdsa_key_family() {
+        let key_id = SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Ed25519,
+            name: "some_key".to_string(),
+        };
+        assert!(!make_schnorr_signing_subnet_list_key(&key_id)
+            .starts_with(ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX));
+    }
+
+    #[test]
+    fn vetkd_enabled_subnet_list_key_round_trips() {
+        let key_id = VetKdKeyId {
+            curve: VetKdCurve::Bls12_381_G2,
+            name: "some_key".to_string(),
+        };
+        let enabled_subnet_list_key = make_vetkd_enabled_subnet_list_key(&key_id);
+        assert_eq!(
+            get_vetkd_key_id_from_enabled_subnet_list_key(&enabled_subnet_list_key).unwrap(),
+            key_id
+        );
+        assert!(!enabled_subnet_list_key.starts_with(ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX));
+    }
+
     #[test]
     fn firewall_scope_parsing() {
         let id = PrincipalId::new_node_test_id(42);
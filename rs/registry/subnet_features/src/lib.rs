@@ -1,10 +1,12 @@
 use candid::CandidType;
-use ic_management_canister_types::EcdsaKeyId;
+use ic_management_canister_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_protobuf::{proxy::ProxyDecodeError, registry::subnet::v1 as pb};
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, str::FromStr};
 
 pub const DEFAULT_ECDSA_MAX_QUEUE_SIZE: u32 = 20;
+pub const DEFAULT_SCHNORR_MAX_QUEUE_SIZE: u32 = 20;
+pub const DEFAULT_VETKD_MAX_QUEUE_SIZE: u32 = 20;
 
 /// List of features that can be enabled or disabled on the given subnet.
 #[derive(CandidType, Clone, Copy, Deserialize, Debug, Eq, PartialEq, Serialize)]
@@ -123,6 +125,71 @@ impl TryFrom<pb::EcdsaConfig> for EcdsaConfig {
     }
 }
 
+#[derive(CandidType, Clone, Default, Deserialize, Debug, Eq, PartialEq, Serialize)]
+pub struct SchnorrConfig {
+    pub key_ids: Vec<SchnorrKeyId>,
+    pub max_queue_size: Option<u32>,
+    pub signature_request_timeout_ns: Option<u64>,
+}
+
+impl From<SchnorrConfig> for pb::SchnorrConfig {
+    fn from(item: SchnorrConfig) -> Self {
+        pb::SchnorrConfig {
+            key_ids: item.key_ids.iter().map(|key| key.into()).collect(),
+            max_queue_size: item
+                .max_queue_size
+                .unwrap_or(DEFAULT_SCHNORR_MAX_QUEUE_SIZE),
+            signature_request_timeout_ns: item.signature_request_timeout_ns,
+        }
+    }
+}
+
+impl TryFrom<pb::SchnorrConfig> for SchnorrConfig {
+    type Error = ProxyDecodeError;
+
+    fn try_from(value: pb::SchnorrConfig) -> Result<Self, Self::Error> {
+        let mut key_ids = vec![];
+        for key in value.key_ids {
+            key_ids.push(SchnorrKeyId::try_from(key)?);
+        }
+        Ok(SchnorrConfig {
+            key_ids,
+            max_queue_size: Some(value.max_queue_size),
+            signature_request_timeout_ns: value.signature_request_timeout_ns,
+        })
+    }
+}
+
+#[derive(CandidType, Clone, Default, Deserialize, Debug, Eq, PartialEq, Serialize)]
+pub struct VetKdConfig {
+    pub key_ids: Vec<VetKdKeyId>,
+    pub max_queue_size: Option<u32>,
+}
+
+impl From<VetKdConfig> for pb::VetKdConfig {
+    fn from(item: VetKdConfig) -> Self {
+        pb::VetKdConfig {
+            key_ids: item.key_ids.iter().map(|key| key.into()).collect(),
+            max_queue_size: item.max_queue_size.unwrap_or(DEFAULT_VETKD_MAX_QUEUE_SIZE),
+        }
+    }
+}
+
+impl TryFrom<pb::VetKdConfig> for VetKdConfig {
+    type Error = ProxyDecodeError;
+
+    fn try_from(value: pb::VetKdConfig) -> Result<Self, Self::Error> {
+        let mut key_ids = vec![];
+        for key in value.key_ids {
+            key_ids.push(VetKdKeyId::try_from(key)?);
+        }
+        Ok(VetKdConfig {
+            key_ids,
+            max_queue_size: Some(value.max_queue_size),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
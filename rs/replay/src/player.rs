@@ -744,7 +744,7 @@ impl Player {
             messages: BatchMessages::default(),
             // Use a fake randomness here since we don't have random tape for extra messages
             randomness,
-            ecdsa_subnet_public_keys: BTreeMap::new(),
+            chain_key_subnet_public_keys: BTreeMap::new(),
             ecdsa_quadruple_ids: BTreeMap::new(),
             registry_version,
             time,
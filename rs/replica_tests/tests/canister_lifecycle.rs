@@ -6,7 +6,7 @@ use ic_error_types::{ErrorCode, RejectCode};
 use ic_management_canister_types::{
     self as ic00, CanisterChange, CanisterIdRecord, CanisterInstallMode,
     CanisterSettingsArgsBuilder, CanisterStatusResultV2, CanisterStatusType, EmptyBlob,
-    InstallCodeArgs, LogVisibility, Method, Payload, UpdateSettingsArgs, IC_00,
+    InstallCodeArgs, LogVisibility, MemoryMetrics, Method, Payload, UpdateSettingsArgs, IC_00,
 };
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_replica_tests as utils;
@@ -689,6 +689,8 @@ fn can_get_canister_information() {
         );
 
         // Request the status of canister_b.
+        let canister_history_size =
+            NumBytes::from((2 * size_of::<CanisterChange>() + 2 * size_of::<PrincipalId>()) as u64);
         assert_matches!(
             test.ingress(
                 canister_a,
@@ -706,13 +708,25 @@ fn can_get_canister_information() {
                 None,
                 canister_a.get(),
                 vec![canister_a.get()],
-                NumBytes::from((2 * size_of::<CanisterChange>() + 2 * size_of::<PrincipalId>()) as u64),
+                canister_history_size,
+                MemoryMetrics::new(
+                    NumBytes::from(0),
+                    NumBytes::from(0),
+                    NumBytes::from(0),
+                    NumBytes::from(0),
+                    NumBytes::from(0),
+                    canister_history_size,
+                    NumBytes::from(0),
+                    NumBytes::from(0),
+                    NumBytes::from(0),
+                ),
                 num_cycles.get(),
                 ComputeAllocation::default().as_percent(),
                 None,
                 2592000,
                 Some(5_000_000_000_000u128),
                 LogVisibility::default(),
+                None,
                 0u128,
                 0u128,
                 0u128,
@@ -765,12 +779,14 @@ fn can_get_canister_information() {
                     // We don't assert a specific memory size since the universal canister's
                     // size changes between updates.
                     NumBytes::from(0),
+                    MemoryMetrics::default(),
                     num_cycles.get(),
                     ComputeAllocation::default().as_percent(),
                     None,
                     259200,
                     None,
                     LogVisibility::default(),
+                    None,
                     0u128,
                     0u128,
                     0u128,
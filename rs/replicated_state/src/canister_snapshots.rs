@@ -1,7 +1,10 @@
-use ic_types::{CanisterId, Time};
+use ic_types::{CanisterId, NumBytes, Time};
 use ic_wasm_types::CanisterModule;
 
-use crate::{canister_state::system_state::wasm_chunk_store::WasmChunkStore, PageMap};
+use crate::{
+    canister_state::system_state::wasm_chunk_store::WasmChunkStore, num_bytes_try_from,
+    NumWasmPages, PageMap,
+};
 
 use phantom_newtype::Id;
 use std::{collections::BTreeMap, sync::Arc};
@@ -76,6 +79,67 @@ impl CanisterSnapshots {
         }
     }
 
+    /// Returns a mutable reference to the snapshot identified by `snapshot_id`,
+    /// e.g. to write uploaded data to it. The snapshot is only cloned if it is
+    /// shared with another copy of the state.
+    ///
+    /// Additionally, adds new items to the `unflushed_changes` which represent
+    /// the deleted old backup and the new backup since the last flush to the disk,
+    /// unless they were just added.
+    pub fn get_mut(&mut self, snapshot_id: SnapshotId) -> Option<&mut CanisterSnapshot> {
+        let snapshot = self.snapshots.get_mut(&snapshot_id)?;
+        let changes = [
+            SnapshotOperation::Delete(snapshot_id),
+            SnapshotOperation::Backup(*snapshot.canister_id(), snapshot_id),
+        ];
+        if !self.unflushed_changes.ends_with(&changes) {
+            self.unflushed_changes.extend(changes);
+        }
+        Some(Arc::make_mut(snapshot))
+    }
+
+    /// Returns the snapshot identified by `snapshot_id`, if any.
+    pub fn get(&self, snapshot_id: SnapshotId) -> Option<&Arc<CanisterSnapshot>> {
+        self.snapshots.get(&snapshot_id)
+    }
+
+    /// Returns the number of snapshots that belong to the given canister.
+    pub fn count_by_canister(&self, canister_id: &CanisterId) -> usize {
+        self.snapshots
+            .values()
+            .filter(|snapshot| snapshot.canister_id() == canister_id)
+            .count()
+    }
+
+    /// Returns the total memory taken by the snapshots of the given canister.
+    pub fn memory_taken_by_canister(&self, canister_id: &CanisterId) -> NumBytes {
+        self.snapshots
+            .values()
+            .filter(|snapshot| snapshot.canister_id() == canister_id)
+            .map(|snapshot| snapshot.size())
+            .fold(NumBytes::new(0), |total, size| total + size)
+    }
+
+    /// Returns the total memory taken by the snapshots of each canister that
+    /// has snapshots.
+    pub fn memory_taken_by_canisters(&self) -> BTreeMap<CanisterId, NumBytes> {
+        let mut memory_taken = BTreeMap::new();
+        for snapshot in self.snapshots.values() {
+            *memory_taken
+                .entry(*snapshot.canister_id())
+                .or_insert(NumBytes::new(0)) += snapshot.size();
+        }
+        memory_taken
+    }
+
+    /// Returns the total memory taken by all snapshots.
+    pub fn memory_taken(&self) -> NumBytes {
+        self.snapshots
+            .values()
+            .map(|snapshot| snapshot.size())
+            .fold(NumBytes::new(0), |total, size| total + size)
+    }
+
     /// Take the unflushed changes.
     pub fn take_unflushed_changes(&mut self) -> Vec<SnapshotOperation> {
         std::mem::take(&mut self.unflushed_changes)
@@ -106,8 +170,23 @@ pub struct CanisterSnapshot {
     wasm_binary: Option<CanisterModule>,
     /// Snapshot of stable memory.
     stable_memory: Option<PageMap>,
+    /// The size of the stable memory.
+    stable_memory_size: NumWasmPages,
     /// Snapshot of wasm memory.
     wasm_memory: Option<PageMap>,
+    /// The size of the wasm memory.
+    wasm_memory_size: NumWasmPages,
+    /// The Wasm module while it is being uploaded.
+    wasm_module_upload: Option<WasmModuleUpload>,
+}
+
+/// The Wasm module of a snapshot while it is being uploaded in chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct WasmModuleUpload {
+    /// The size of the complete Wasm module.
+    size: u64,
+    /// The chunks uploaded so far, in order.
+    buffer: Vec<u8>,
 }
 
 impl CanisterSnapshot {
@@ -117,7 +196,9 @@ impl CanisterSnapshot {
         canister_version: u64,
         certified_data: Vec<u8>,
         stable_memory: Option<PageMap>,
+        stable_memory_size: NumWasmPages,
         wasm_memory: Option<PageMap>,
+        wasm_memory_size: NumWasmPages,
         chunk_store: WasmChunkStore,
         wasm_binary: Option<CanisterModule>,
     ) -> CanisterSnapshot {
@@ -127,9 +208,12 @@ impl CanisterSnapshot {
             canister_version,
             certified_data,
             stable_memory,
+            stable_memory_size,
             wasm_memory,
+            wasm_memory_size,
             chunk_store,
             wasm_binary,
+            wasm_module_upload: None,
         }
     }
 
@@ -145,17 +229,106 @@ impl CanisterSnapshot {
         &self.taken_at_timestamp
     }
 
+    pub fn certified_data(&self) -> &Vec<u8> {
+        &self.certified_data
+    }
+
     pub fn stable_memory(&self) -> &Option<PageMap> {
         &self.stable_memory
     }
 
+    pub fn stable_memory_mut(&mut self) -> &mut Option<PageMap> {
+        &mut self.stable_memory
+    }
+
+    pub fn stable_memory_size(&self) -> NumWasmPages {
+        self.stable_memory_size
+    }
+
     pub fn wasm_memory(&self) -> &Option<PageMap> {
         &self.wasm_memory
     }
 
+    pub fn wasm_memory_mut(&mut self) -> &mut Option<PageMap> {
+        &mut self.wasm_memory
+    }
+
+    pub fn wasm_memory_size(&self) -> NumWasmPages {
+        self.wasm_memory_size
+    }
+
     pub fn chunk_store(&self) -> &WasmChunkStore {
         &self.chunk_store
     }
+
+    pub fn chunk_store_mut(&mut self) -> &mut WasmChunkStore {
+        &mut self.chunk_store
+    }
+
+    pub fn wasm_binary(&self) -> &Option<CanisterModule> {
+        &self.wasm_binary
+    }
+
+    /// Starts the upload of a Wasm module of `size` bytes, which replaces the
+    /// Wasm module of the snapshot once all of its chunks were appended with
+    /// `append_wasm_module_chunk`.
+    pub fn start_wasm_module_upload(&mut self, size: u64) {
+        self.wasm_binary = None;
+        self.wasm_module_upload = (size > 0).then(|| WasmModuleUpload {
+            size,
+            buffer: vec![],
+        });
+    }
+
+    /// Appends the chunk at `offset` to the Wasm module that is being uploaded.
+    /// Chunks must be uploaded in order. The Wasm module is built once, when
+    /// its last chunk is appended.
+    pub fn append_wasm_module_chunk(&mut self, offset: u64, chunk: &[u8]) -> Result<(), String> {
+        let upload = self
+            .wasm_module_upload
+            .as_mut()
+            .ok_or_else(|| "No Wasm module is being uploaded to the snapshot".to_string())?;
+        let uploaded = upload.buffer.len() as u64;
+        if offset != uploaded {
+            return Err(format!(
+                "Expected the chunk of the Wasm module at offset {}, got offset {}",
+                uploaded, offset
+            ));
+        }
+        if uploaded + chunk.len() as u64 > upload.size {
+            return Err(format!(
+                "A chunk of {} bytes at offset {} exceeds the Wasm module size of {} bytes",
+                chunk.len(),
+                offset,
+                upload.size
+            ));
+        }
+        upload.buffer.extend_from_slice(chunk);
+        if upload.buffer.len() as u64 == upload.size {
+            let wasm_binary = std::mem::take(&mut upload.buffer);
+            self.wasm_binary = Some(CanisterModule::new(wasm_binary));
+            self.wasm_module_upload = None;
+        }
+        Ok(())
+    }
+
+    /// Returns the memory taken by the snapshot: the Wasm and stable memories,
+    /// the Wasm module, the chunk store and the certified data. A Wasm module
+    /// that is being uploaded counts with its complete size.
+    pub fn size(&self) -> NumBytes {
+        let wasm_binary_size = match (&self.wasm_binary, &self.wasm_module_upload) {
+            (_, Some(upload)) => upload.size,
+            (Some(wasm_binary), None) => wasm_binary.len() as u64,
+            (None, None) => 0,
+        };
+        num_bytes_try_from(self.wasm_memory_size)
+            .expect("could not convert from wasm memory number of pages to bytes")
+            + num_bytes_try_from(self.stable_memory_size)
+                .expect("could not convert from stable memory number of pages to bytes")
+            + NumBytes::new(wasm_binary_size)
+            + self.chunk_store.memory_usage()
+            + NumBytes::new(self.certified_data.len() as u64)
+    }
 }
 
 /// Describes the types of unflushed changes that can be stored by the `SnapshotManager`.
@@ -181,7 +354,9 @@ mod tests {
             0,
             vec![],
             Some(PageMap::new_for_testing()),
+            NumWasmPages::new(0),
             Some(PageMap::new_for_testing()),
+            NumWasmPages::new(0),
             WasmChunkStore::new_for_testing(NumBytes::from(20)),
             Some(CanisterModule::new(vec![1, 2, 3])),
         );
@@ -207,4 +382,96 @@ mod tests {
         assert_eq!(snapshot_manager.unflushed_changes.len(), 0);
         assert_eq!(unflushed_changes.len(), 1);
     }
+
+    #[test]
+    fn test_upload_to_snapshot() {
+        let snapshot = CanisterSnapshot::new(
+            canister_test_id(0),
+            mock_time(),
+            0,
+            vec![],
+            Some(PageMap::new_for_testing()),
+            NumWasmPages::new(0),
+            Some(PageMap::new_for_testing()),
+            NumWasmPages::new(0),
+            WasmChunkStore::new_for_testing(NumBytes::from(20)),
+            None,
+        );
+        let mut snapshot_manager = CanisterSnapshots::default();
+        let snapshot_id = snapshot_manager.push(Arc::new(snapshot));
+        assert_eq!(snapshot_manager.count_by_canister(&canister_test_id(0)), 1);
+        assert_eq!(snapshot_manager.count_by_canister(&canister_test_id(1)), 0);
+        snapshot_manager.take_unflushed_changes();
+
+        let snapshot = snapshot_manager.get_mut(snapshot_id).unwrap();
+        snapshot.start_wasm_module_upload(5);
+        assert_eq!(snapshot.size(), NumBytes::new(5));
+        snapshot.append_wasm_module_chunk(0, &[1, 2, 3]).unwrap();
+        assert_eq!(snapshot.wasm_binary(), &None);
+
+        // Chunks must be appended in order and within the size of the module.
+        let snapshot = snapshot_manager.get_mut(snapshot_id).unwrap();
+        assert!(snapshot.append_wasm_module_chunk(4, &[5]).is_err());
+        assert!(snapshot.append_wasm_module_chunk(3, &[4, 5, 6]).is_err());
+        snapshot.append_wasm_module_chunk(3, &[4, 5]).unwrap();
+        assert_eq!(
+            snapshot_manager.get(snapshot_id).unwrap().wasm_binary(),
+            &Some(CanisterModule::new(vec![1, 2, 3, 4, 5]))
+        );
+        assert_eq!(
+            snapshot_manager.get(snapshot_id).unwrap().size(),
+            NumBytes::new(5)
+        );
+
+        // Repeated writes to the same snapshot are recorded once.
+        assert_eq!(
+            snapshot_manager.take_unflushed_changes(),
+            vec![
+                SnapshotOperation::Delete(snapshot_id),
+                SnapshotOperation::Backup(canister_test_id(0), snapshot_id),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_memory_taken_by_canister() {
+        let snapshot = CanisterSnapshot::new(
+            canister_test_id(0),
+            mock_time(),
+            0,
+            vec![0; 10],
+            Some(PageMap::new_for_testing()),
+            NumWasmPages::new(2),
+            Some(PageMap::new_for_testing()),
+            NumWasmPages::new(1),
+            WasmChunkStore::new_for_testing(NumBytes::from(20)),
+            Some(CanisterModule::new(vec![1, 2, 3])),
+        );
+        let expected_size = NumBytes::new(3 * 64 * 1024 + 3 + 10);
+        assert_eq!(snapshot.size(), expected_size);
+
+        let mut snapshot_manager = CanisterSnapshots::default();
+        assert_eq!(
+            snapshot_manager.memory_taken_by_canister(&canister_test_id(0)),
+            NumBytes::new(0)
+        );
+        snapshot_manager.push(Arc::new(snapshot.clone()));
+        snapshot_manager.push(Arc::new(snapshot));
+        assert_eq!(
+            snapshot_manager.memory_taken_by_canister(&canister_test_id(0)),
+            expected_size + expected_size
+        );
+        assert_eq!(
+            snapshot_manager.memory_taken_by_canister(&canister_test_id(1)),
+            NumBytes::new(0)
+        );
+        assert_eq!(
+            snapshot_manager.memory_taken_by_canisters(),
+            BTreeMap::from([(canister_test_id(0), expected_size + expected_size)])
+        );
+        assert_eq!(
+            snapshot_manager.memory_taken(),
+            expected_size + expected_size
+        );
+    }
 }
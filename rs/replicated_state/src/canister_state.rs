@@ -154,8 +154,8 @@ impl CanisterState {
         &self.system_state.controllers
     }
 
-    pub fn log_visibility(&self) -> LogVisibility {
-        self.system_state.log_visibility
+    pub fn log_visibility(&self) -> &LogVisibility {
+        &self.system_state.log_visibility
     }
 
     /// Returns the difference in time since the canister was last charged for resource allocations.
@@ -407,6 +407,34 @@ impl CanisterState {
         self.system_state.message_memory_usage()
     }
 
+    /// Returns the size of the Wasm memory (heap) of the canister in bytes.
+    pub fn wasm_memory_usage(&self) -> NumBytes {
+        self.execution_state
+            .as_ref()
+            .map_or(NumBytes::from(0), |es| es.wasm_memory_usage())
+    }
+
+    /// Returns the size of the stable memory of the canister in bytes.
+    pub fn stable_memory_usage(&self) -> NumBytes {
+        self.execution_state
+            .as_ref()
+            .map_or(NumBytes::from(0), |es| es.stable_memory_usage())
+    }
+
+    /// Returns the memory used by the exported globals of the canister in bytes.
+    pub fn global_memory_usage(&self) -> NumBytes {
+        self.execution_state
+            .as_ref()
+            .map_or(NumBytes::from(0), |es| es.global_memory_usage())
+    }
+
+    /// Returns the size of the Wasm module of the canister in bytes.
+    pub fn wasm_binary_memory_usage(&self) -> NumBytes {
+        self.execution_state
+            .as_ref()
+            .map_or(NumBytes::from(0), |es| es.wasm_binary_memory_usage())
+    }
+
     /// Returns the amount of memory used by canisters that have custom Wasm
     /// sections defined.
     pub fn wasm_custom_sections_memory_usage(&self) -> NumBytes {
@@ -421,7 +449,7 @@ impl CanisterState {
     }
 
     /// Returns the memory usage of the wasm chunk store in bytes.
-    pub(super) fn wasm_chunk_store_memory_usage(&self) -> NumBytes {
+    pub fn wasm_chunk_store_memory_usage(&self) -> NumBytes {
         self.system_state.wasm_chunk_store.memory_usage()
     }
 
@@ -14,9 +14,10 @@ use ic_protobuf::{
 };
 use ic_types::{
     messages::{
-        CanisterMessage, Ingress, Payload, RejectContext, Request, RequestOrResponse, Response,
-        MAX_RESPONSE_COUNT_BYTES,
+        CallbackId, CanisterMessage, Ingress, Payload, RejectContext, Request, RequestOrResponse,
+        Response, MAX_RESPONSE_COUNT_BYTES,
     },
+    methods::Callback,
     xnet::{QueueId, SessionId},
     CanisterId, CountBytes, Cycles, Time,
 };
@@ -626,6 +627,7 @@ impl CanisterQueues {
             originator_reply_callback: request.sender_reply_callback,
             refund: request.payment,
             response_payload: Payload::Reject(reject_context),
+            deadline: request.deadline,
         }));
         self.push_input(response, InputQueueType::LocalSubnet)
             .map_err(|(e, _msg)| e)
@@ -1029,7 +1031,14 @@ impl CanisterQueues {
                 // Push response, update stats.
                 let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
                 let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
-                input_queue.push(response).unwrap();
+                if let Err((_, response)) = input_queue.push(response) {
+                    // The callback of a best-effort request may have already expired, with
+                    // its `SYS_UNKNOWN` reject response having consumed the reserved slot.
+                    debug_assert!(
+                        matches!(&response, RequestOrResponse::Response(rep) if rep.is_best_effort())
+                    );
+                    continue;
+                }
                 self.input_queues_stats += iq_stats_delta;
                 self.memory_usage_stats += mu_stats_delta;
 
@@ -1052,6 +1061,62 @@ impl CanisterQueues {
         timed_out_requests_count
     }
 
+    /// Returns `true` if a response to the given callback is already enqueued
+    /// in the input queue from `respondent`.
+    pub(crate) fn has_input_response_for(
+        &self,
+        respondent: &CanisterId,
+        callback_id: CallbackId,
+    ) -> bool {
+        self.canister_queues
+            .get(respondent)
+            .map_or(false, |(input_queue, _)| {
+                input_queue.has_response_for(callback_id)
+            })
+    }
+
+    /// Enqueues a `SYS_UNKNOWN` reject response for the given expired best-effort
+    /// callback into the input queue from the callback's respondent, consuming
+    /// the slot reserved for the response.
+    ///
+    /// Returns `false` (and enqueues nothing) if no slot was reserved.
+    pub(crate) fn push_deadline_expired_response(
+        &mut self,
+        callback_id: CallbackId,
+        callback: &Callback,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> bool {
+        let respondent = callback.respondent;
+        let input_queue = match self.canister_queues.get_mut(&respondent) {
+            Some((input_queue, _)) => input_queue,
+            None => return false,
+        };
+
+        let response = generate_deadline_expired_response(callback_id, callback);
+        let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
+        let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
+        if input_queue.push(response).is_err() {
+            return false;
+        }
+        self.input_queues_stats += iq_stats_delta;
+        self.memory_usage_stats += mu_stats_delta;
+
+        // If this was a previously empty input queue, add it to input queue schedule.
+        if input_queue.num_messages() == 1 {
+            if &respondent == own_canister_id || local_canisters.contains_key(&respondent) {
+                self.local_subnet_input_schedule.push_back(respondent);
+            } else {
+                self.remote_subnet_input_schedule.push_back(respondent);
+            }
+        }
+
+        debug_assert!(self.stats_ok());
+        debug_assert!(self.schedules_ok(own_canister_id, local_canisters));
+
+        true
+    }
+
     /// Re-partitions `self.local_subnet_input_schedule` and
     /// `self.remote_subnet_input_schedule` based on the set of all local canisters
     /// plus `own_canister_id` (since Rust's ownership rules would prevent us from
@@ -1096,6 +1161,26 @@ impl CanisterQueues {
     }
 }
 
+/// Generates a `SYS_UNKNOWN` reject response for an expired best-effort callback.
+/// No cycles are refunded, as the request may have been delivered.
+fn generate_deadline_expired_response(
+    callback_id: CallbackId,
+    callback: &Callback,
+) -> RequestOrResponse {
+    RequestOrResponse::Response(Arc::new(Response {
+        originator: callback.originator,
+        respondent: callback.respondent,
+        originator_reply_callback: callback_id,
+        refund: Cycles::zero(),
+        response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
+            RejectCode::SysUnknown,
+            "Call deadline has expired.",
+            MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
+        )),
+        deadline: callback.deadline,
+    }))
+}
+
 /// Generates a timeout reject response from a request, refunding its payment.
 fn generate_timeout_response(request: &Arc<Request>) -> RequestOrResponse {
     RequestOrResponse::Response(Arc::new(Response {
@@ -1108,6 +1193,7 @@ fn generate_timeout_response(request: &Arc<Request>) -> RequestOrResponse {
             "Request timed out.",
             MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
         )),
+        deadline: request.deadline,
     }))
 }
 
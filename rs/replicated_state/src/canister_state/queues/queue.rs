@@ -5,7 +5,7 @@ mod tests;
 use ic_base_types::CanisterId;
 use ic_protobuf::proxy::ProxyDecodeError;
 use ic_protobuf::state::{ingress::v1 as pb_ingress, queues::v1 as pb_queues};
-use ic_types::messages::{Ingress, Request, RequestOrResponse, Response};
+use ic_types::messages::{CallbackId, Ingress, Request, RequestOrResponse, Response};
 use ic_types::{CountBytes, Cycles, Time};
 use std::collections::BTreeMap;
 use std::{
@@ -380,6 +380,18 @@ impl InputQueue {
         self.queue.queue.len()
     }
 
+    /// Returns `true` if the queue holds a response to the given callback.
+    ///
+    /// Time complexity: O(num_messages).
+    pub(super) fn has_response_for(&self, callback_id: CallbackId) -> bool {
+        self.queue.queue.iter().any(|msg| match msg {
+            RequestOrResponse::Response(response) => {
+                response.originator_reply_callback == callback_id
+            }
+            RequestOrResponse::Request(_) => false,
+        })
+    }
+
     /// Returns the number of reserved slots in the queue.
     pub(super) fn reserved_slots(&self) -> usize {
         self.queue.reserved_slots()
@@ -64,6 +64,8 @@ pub enum CyclesUseCase {
     DeletedCanisters,
     NonConsumed,
     BurnedCycles,
+    SchnorrOutcalls,
+    VetKd,
 }
 
 impl CyclesUseCase {
@@ -83,6 +85,8 @@ impl CyclesUseCase {
             Self::DeletedCanisters => "DeletedCanisters",
             Self::NonConsumed => "NonConsumed",
             Self::BurnedCycles => "BurnedCycles",
+            Self::SchnorrOutcalls => "SchnorrOutcalls",
+            Self::VetKd => "VetKd",
         }
     }
 }
@@ -102,6 +106,8 @@ impl From<CyclesUseCase> for i32 {
             CyclesUseCase::DeletedCanisters => 10,
             CyclesUseCase::NonConsumed => 11,
             CyclesUseCase::BurnedCycles => 12,
+            CyclesUseCase::SchnorrOutcalls => 13,
+            CyclesUseCase::VetKd => 14,
         }
     }
 }
@@ -121,6 +127,8 @@ impl From<i32> for CyclesUseCase {
             10 => Self::DeletedCanisters,
             11 => Self::NonConsumed,
             12 => Self::BurnedCycles,
+            13 => Self::SchnorrOutcalls,
+            14 => Self::VetKd,
             _ => panic!("Unsupported value"),
         }
     }
@@ -339,6 +347,13 @@ pub struct SystemState {
 
     /// Log records of the canister.
     pub canister_log_records: Vec<CanisterLogRecord>,
+
+    /// The user-specified upper limit on the Wasm memory size of the canister.
+    ///
+    /// A `memory.grow` in an update, heartbeat or timer execution traps if
+    /// the resulting Wasm memory size would exceed this limit. `None` means
+    /// that there is no limit.
+    pub wasm_memory_limit: Option<NumBytes>,
 }
 
 /// A wrapper around the different canister statuses.
@@ -710,6 +725,7 @@ impl SystemState {
             wasm_chunk_store,
             log_visibility: LogVisibility::default(),
             canister_log_records: Vec::new(),
+            wasm_memory_limit: None,
         }
     }
 
@@ -755,6 +771,7 @@ impl SystemState {
         wasm_chunk_store_metadata: WasmChunkStoreMetadata,
         log_visibility: LogVisibility,
         canister_log_records: Vec<CanisterLogRecord>,
+        wasm_memory_limit: Option<NumBytes>,
     ) -> Self {
         Self {
             controllers,
@@ -779,6 +796,7 @@ impl SystemState {
             ),
             log_visibility,
             canister_log_records,
+            wasm_memory_limit,
         }
     }
 
@@ -1131,6 +1149,10 @@ impl SystemState {
                 },
             ) => {
                 if let RequestOrResponse::Response(response) = &msg {
+                    // Late best-effort responses are silently dropped.
+                    if call_context_manager.should_drop_response(response) {
+                        return Ok(());
+                    }
                     call_context_manager
                         .validate_response(response)
                         .map_err(|err| (err, msg.clone()))?;
@@ -1317,6 +1339,59 @@ impl SystemState {
             .time_out_requests(current_time, own_canister_id, local_canisters)
     }
 
+    /// Queries whether the call context manager has any best-effort callbacks
+    /// whose deadlines have expired.
+    pub fn has_expired_callbacks(&self, current_time: Time) -> bool {
+        self.call_context_manager()
+            .map_or(false, |ccm| ccm.has_expired_callbacks(current_time))
+    }
+
+    /// Expires all best-effort callbacks whose deadlines are at or before
+    /// `current_time` and for which no response has been enqueued yet,
+    /// enqueuing a `SYS_UNKNOWN` reject response for each. Returns the number
+    /// of callbacks that were timed out.
+    ///
+    /// See [`CanisterQueues::push_deadline_expired_response`] for further details.
+    pub fn time_out_callbacks(
+        &mut self,
+        current_time: Time,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> u64 {
+        let queues = &mut self.queues;
+        let call_context_manager = match &mut self.status {
+            CanisterStatus::Running {
+                call_context_manager,
+            }
+            | CanisterStatus::Stopping {
+                call_context_manager,
+                ..
+            } => call_context_manager,
+            CanisterStatus::Stopped => return 0,
+        };
+
+        let expired_callbacks = call_context_manager
+            .expire_callbacks(current_time, |id, callback| {
+                queues.has_input_response_for(&callback.respondent, id)
+            });
+
+        let mut timed_out_callbacks_count = 0;
+        for callback_id in expired_callbacks {
+            let callback = call_context_manager
+                .callback(callback_id)
+                .expect("Expired callback must exist");
+            if queues.push_deadline_expired_response(
+                callback_id,
+                callback,
+                own_canister_id,
+                local_canisters,
+            ) {
+                timed_out_callbacks_count += 1;
+            }
+        }
+        timed_out_callbacks_count
+    }
+
     /// Re-partitions the local and remote input schedules of `self.queues`
     /// following a canister migration, based on the updated set of local canisters.
     ///
@@ -1352,6 +1427,8 @@ impl SystemState {
             | CyclesUseCase::RequestAndResponseTransmission
             | CyclesUseCase::CanisterCreation
             | CyclesUseCase::ECDSAOutcalls
+            | CyclesUseCase::SchnorrOutcalls
+            | CyclesUseCase::VetKd
             | CyclesUseCase::HTTPOutcalls
             | CyclesUseCase::DeletedCanisters
             | CyclesUseCase::NonConsumed
@@ -1401,9 +1478,11 @@ impl SystemState {
         use_case: CyclesUseCase,
         consuming_cycles: ConsumingCycles,
     ) {
-        // The three CyclesUseCase below are not valid on the canister
+        // The five CyclesUseCase below are not valid on the canister
         // level, they should only appear on the subnet level.
         debug_assert_ne!(use_case, CyclesUseCase::ECDSAOutcalls);
+        debug_assert_ne!(use_case, CyclesUseCase::SchnorrOutcalls);
+        debug_assert_ne!(use_case, CyclesUseCase::VetKd);
         debug_assert_ne!(use_case, CyclesUseCase::HTTPOutcalls);
         debug_assert_ne!(use_case, CyclesUseCase::DeletedCanisters);
 
@@ -12,14 +12,14 @@ use ic_types::{
     ingress::WasmResult,
     messages::{
         CallContextId, CallbackId, CanisterCall, CanisterCallOrTask, MessageId, RequestMetadata,
-        Response,
+        Response, NO_DEADLINE,
     },
     methods::Callback,
-    user_id_into_protobuf, user_id_try_from_protobuf, CanisterId, Cycles, Funds, PrincipalId, Time,
-    UserId,
+    user_id_into_protobuf, user_id_try_from_protobuf, CanisterId, CoarseTime, Cycles, Funds,
+    PrincipalId, Time, UserId,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::{From, TryFrom, TryInto};
 use std::time::Duration;
 
@@ -237,19 +237,49 @@ pub enum CallContextAction {
 /// with the serialization of these pointers. In the future we might consider
 /// introducing an intermediate layer between the serialization and the actual
 /// working data structure, to separate these concerns.
-#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CallContextManager {
     next_call_context_id: u64,
     next_callback_id: u64,
     /// Maps call context to its responded status.
     call_contexts: BTreeMap<CallContextId, CallContext>,
     callbacks: BTreeMap<CallbackId, Callback>,
+
+    /// Deadlines and IDs of all best-effort callbacks that have not expired yet.
+    /// Derived from `callbacks` and `expired_callbacks`, not persisted.
+    unexpired_callbacks: BTreeSet<(CoarseTime, CallbackId)>,
+
+    /// Best-effort callbacks that have expired and for which a `SYS_UNKNOWN`
+    /// reject response was enqueued. Any subsequent response for them is dropped.
+    expired_callbacks: BTreeSet<CallbackId>,
+
+    /// All best-effort callbacks with deadlines at or before this time have
+    /// expired or received a response. A best-effort response for a callback
+    /// that is no longer known and whose deadline is at or before this time is
+    /// a late response to a callback that was already rejected and is dropped.
+    deadlines_expired_up_to: CoarseTime,
+}
+
+impl Default for CallContextManager {
+    fn default() -> Self {
+        Self {
+            next_call_context_id: 0,
+            next_callback_id: 0,
+            call_contexts: BTreeMap::new(),
+            callbacks: BTreeMap::new(),
+            unexpired_callbacks: BTreeSet::new(),
+            expired_callbacks: BTreeSet::new(),
+            deadlines_expired_up_to: NO_DEADLINE,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CallOrigin {
     Ingress(UserId, MessageId),
-    CanisterUpdate(CanisterId, CallbackId),
+    /// A canister update call, along with the deadline of the call
+    /// (`NO_DEADLINE` for guaranteed response calls).
+    CanisterUpdate(CanisterId, CallbackId, CoarseTime),
     Query(UserId),
     CanisterQuery(CanisterId, CallbackId),
     /// System task is either a `Heartbeat` or a `GlobalTimer`.
@@ -261,7 +291,7 @@ impl CallOrigin {
     pub fn get_principal(&self) -> PrincipalId {
         match self {
             CallOrigin::Ingress(user_id, _) => user_id.get(),
-            CallOrigin::CanisterUpdate(canister_id, _) => canister_id.get(),
+            CallOrigin::CanisterUpdate(canister_id, _, _) => canister_id.get(),
             CallOrigin::Query(user_id) => user_id.get(),
             CallOrigin::CanisterQuery(canister_id, _) => canister_id.get(),
             CallOrigin::SystemTask => IC_00.get(),
@@ -276,10 +306,11 @@ impl From<&CallOrigin> for pb::call_context::CallOrigin {
                 user_id: Some(user_id_into_protobuf(*user_id)),
                 message_id: message_id.as_bytes().to_vec(),
             }),
-            CallOrigin::CanisterUpdate(canister_id, callback_id) => {
+            CallOrigin::CanisterUpdate(canister_id, callback_id, deadline) => {
                 Self::CanisterUpdate(pb::call_context::CanisterUpdateOrQuery {
                     canister_id: Some(pb_types::CanisterId::from(*canister_id)),
                     callback_id: callback_id.get(),
+                    deadline_seconds: deadline.as_secs_since_unix_epoch(),
                 })
             }
             CallOrigin::Query(user_id) => Self::Query(user_id_into_protobuf(*user_id)),
@@ -287,6 +318,7 @@ impl From<&CallOrigin> for pb::call_context::CallOrigin {
                 Self::CanisterQuery(pb::call_context::CanisterUpdateOrQuery {
                     canister_id: Some(pb_types::CanisterId::from(*canister_id)),
                     callback_id: callback_id.get(),
+                    deadline_seconds: NO_DEADLINE.as_secs_since_unix_epoch(),
                 })
             }
             CallOrigin::SystemTask => Self::SystemTask(pb::call_context::SystemTask {}),
@@ -312,10 +344,12 @@ impl TryFrom<pb::call_context::CallOrigin> for CallOrigin {
                 pb::call_context::CanisterUpdateOrQuery {
                     canister_id,
                     callback_id,
+                    deadline_seconds,
                 },
             ) => Self::CanisterUpdate(
                 try_from_option_field(canister_id, "CallOrigin::CanisterUpdate::canister_id")?,
                 callback_id.into(),
+                CoarseTime::from_secs_since_unix_epoch(deadline_seconds),
             ),
             pb::call_context::CallOrigin::Query(user_id) => {
                 Self::Query(user_id_try_from_protobuf(user_id)?)
@@ -324,6 +358,7 @@ impl TryFrom<pb::call_context::CallOrigin> for CallOrigin {
                 pb::call_context::CanisterUpdateOrQuery {
                     canister_id,
                     callback_id,
+                    ..
                 },
             ) => Self::CanisterQuery(
                 try_from_option_field(canister_id, "CallOrigin::CanisterQuery::canister_id")?,
@@ -392,6 +427,29 @@ impl CallContextManager {
         self.callbacks.get(&callback_id)
     }
 
+    /// Returns `true` if `response` is a late response to a best-effort callback,
+    /// i.e. a callback whose deadline has passed and for which a `SYS_UNKNOWN`
+    /// reject response was already enqueued (and possibly executed). Such
+    /// responses should be silently dropped instead of being inducted.
+    ///
+    /// A response for a known callback is only dropped if the callback expired,
+    /// so that a response cannot bypass validation by claiming to be best-effort.
+    /// A best-effort response for a callback that was already unregistered is
+    /// dropped if its deadline is at or before `deadlines_expired_up_to`.
+    pub(crate) fn should_drop_response(&self, response: &Response) -> bool {
+        let callback_id = response.originator_reply_callback;
+        match self.callback(callback_id) {
+            Some(callback) => {
+                callback.deadline != NO_DEADLINE && self.expired_callbacks.contains(&callback_id)
+            }
+            None => {
+                callback_id.get() <= self.next_callback_id
+                    && response.deadline != NO_DEADLINE
+                    && response.deadline <= self.deadlines_expired_up_to
+            }
+        }
+    }
+
     /// Validates the given response before inducting it into the queue.
     /// Verifies that the stored respondent and originator associated with the
     /// `callback_id` match with details provided by the response.
@@ -546,6 +604,10 @@ impl CallContextManager {
     pub fn register_callback(&mut self, callback: Callback) -> CallbackId {
         self.next_callback_id += 1;
         let callback_id = CallbackId::from(self.next_callback_id);
+        if callback.deadline != NO_DEADLINE {
+            self.unexpired_callbacks
+                .insert((callback.deadline, callback_id));
+        }
         self.callbacks.insert(callback_id, callback);
         callback_id
     }
@@ -553,7 +615,59 @@ impl CallContextManager {
     /// If we get a response for one of the outstanding calls, we unregister
     /// the callback and return it.
     pub fn unregister_callback(&mut self, callback_id: CallbackId) -> Option<Callback> {
-        self.callbacks.remove(&callback_id)
+        let callback = self.callbacks.remove(&callback_id)?;
+        if callback.deadline != NO_DEADLINE {
+            self.unexpired_callbacks
+                .remove(&(callback.deadline, callback_id));
+            self.expired_callbacks.remove(&callback_id);
+        }
+        Some(callback)
+    }
+
+    /// Returns `true` if any best-effort callback has a deadline at or before
+    /// `current_time`.
+    pub fn has_expired_callbacks(&self, current_time: Time) -> bool {
+        self.unexpired_callbacks
+            .first()
+            .map_or(false, |(deadline, _)| {
+                *deadline <= CoarseTime::floor(current_time)
+            })
+    }
+
+    /// Expires all best-effort callbacks with deadlines at or before
+    /// `current_time`, returning their IDs in order of deadline.
+    ///
+    /// Callbacks for which `has_response` returns `true` (i.e. whose response
+    /// has already been enqueued) are no longer tracked for expiration, but are
+    /// not marked as expired nor returned.
+    pub fn expire_callbacks(
+        &mut self,
+        current_time: Time,
+        has_response: impl Fn(CallbackId, &Callback) -> bool,
+    ) -> Vec<CallbackId> {
+        let now = CoarseTime::floor(current_time);
+        self.deadlines_expired_up_to = self.deadlines_expired_up_to.max(now);
+        let mut expired = Vec::new();
+        while let Some((deadline, callback_id)) = self.unexpired_callbacks.first().cloned() {
+            if deadline > now {
+                break;
+            }
+            self.unexpired_callbacks.pop_first();
+            let callback = match self.callbacks.get(&callback_id) {
+                Some(callback) => callback,
+                None => continue,
+            };
+            if !has_response(callback_id, callback) {
+                self.expired_callbacks.insert(callback_id);
+                expired.push(callback_id);
+            }
+        }
+        expired
+    }
+
+    /// Returns `true` if the callback with the given ID has expired.
+    pub fn is_expired_callback(&self, callback_id: CallbackId) -> bool {
+        self.expired_callbacks.contains(&callback_id)
     }
 
     /// Returns the call origin, which is either the message id of the ingress
@@ -611,9 +725,11 @@ impl CallContextManager {
 impl From<&CanisterCall> for CallOrigin {
     fn from(msg: &CanisterCall) -> Self {
         match msg {
-            CanisterCall::Request(request) => {
-                CallOrigin::CanisterUpdate(request.sender, request.sender_reply_callback)
-            }
+            CanisterCall::Request(request) => CallOrigin::CanisterUpdate(
+                request.sender,
+                request.sender_reply_callback,
+                request.deadline,
+            ),
             CanisterCall::Ingress(ingress) => {
                 CallOrigin::Ingress(ingress.source, ingress.message_id.clone())
             }
@@ -651,6 +767,10 @@ impl From<&CallContextManager> for pb::CallContextManager {
                     callback: Some(callback.into()),
                 })
                 .collect(),
+            expired_callbacks: item.expired_callbacks.iter().map(|id| id.get()).collect(),
+            deadlines_expired_up_to_seconds: item
+                .deadlines_expired_up_to
+                .as_secs_since_unix_epoch(),
         }
     }
 }
@@ -681,11 +801,29 @@ impl TryFrom<pb::CallContextManager> for CallContextManager {
             );
         }
 
+        let expired_callbacks: BTreeSet<CallbackId> = value
+            .expired_callbacks
+            .into_iter()
+            .map(CallbackId::from)
+            .collect();
+        let unexpired_callbacks = callbacks
+            .iter()
+            .filter(|(id, callback)| {
+                callback.deadline != NO_DEADLINE && !expired_callbacks.contains(id)
+            })
+            .map(|(id, callback)| (callback.deadline, *id))
+            .collect();
+
         Ok(Self {
             next_call_context_id: value.next_call_context_id,
             next_callback_id: value.next_callback_id,
             call_contexts,
             callbacks,
+            unexpired_callbacks,
+            expired_callbacks,
+            deadlines_expired_up_to: CoarseTime::from_secs_since_unix_epoch(
+                value.deadlines_expired_up_to_seconds,
+            ),
         })
     }
 }
@@ -1,5 +1,5 @@
 use super::*;
-use ic_test_utilities::types::ids::canister_test_id;
+use ic_test_utilities::types::{ids::canister_test_id, messages::ResponseBuilder};
 use ic_test_utilities_time::mock_time;
 use ic_types::{messages::RequestMetadata, methods::WasmClosure};
 
@@ -9,14 +9,14 @@ fn call_context_origin() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(10),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
     );
     assert_eq!(
         ccm.call_contexts().get(&cc_id).unwrap().call_origin,
-        CallOrigin::CanisterUpdate(id, cb_id)
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE)
     );
 }
 
@@ -28,20 +28,20 @@ fn call_context_handling() {
 
     // On two incoming calls
     let call_context_id1 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
     );
     let call_context_id2 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(2)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(2), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
     );
 
     let call_context_id3 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(3)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(3), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -86,6 +86,7 @@ fn call_context_handling() {
         WasmClosure::new(0, 1),
         WasmClosure::new(2, 3),
         None,
+        NO_DEADLINE,
     ));
     let callback_id2 = call_context_manager.register_callback(Callback::new(
         call_context_id1,
@@ -97,6 +98,7 @@ fn call_context_handling() {
         WasmClosure::new(4, 5),
         WasmClosure::new(6, 7),
         None,
+        NO_DEADLINE,
     ));
 
     // There are 2 ougoing calls
@@ -113,6 +115,7 @@ fn call_context_handling() {
         WasmClosure::new(8, 9),
         WasmClosure::new(10, 11),
         None,
+        NO_DEADLINE,
     ));
     // There is 1 outgoing call
     assert_eq!(call_context_manager.outstanding_calls(call_context_id2), 1);
@@ -269,7 +272,7 @@ fn withdraw_cycles_fails_when_not_enough_available_cycles() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(30),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -292,7 +295,7 @@ fn withdraw_cycles_succeeds_when_enough_available_cycles() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(30),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -310,7 +313,7 @@ fn withdraw_cycles_succeeds_when_enough_available_cycles() {
 fn test_call_context_instructions_executed_is_updated() {
     let mut call_context_manager = CallContextManager::default();
     let call_context_id = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -326,6 +329,7 @@ fn test_call_context_instructions_executed_is_updated() {
         WasmClosure::new(0, 1),
         WasmClosure::new(2, 3),
         None,
+        NO_DEADLINE,
     ));
 
     // Finish a successful execution with 1K instructions.
@@ -363,3 +367,139 @@ fn test_call_context_instructions_executed_is_updated() {
         (1_000 + 2_000).into()
     );
 }
+
+fn register_callback_with_deadline(
+    call_context_manager: &mut CallContextManager,
+    call_context_id: CallContextId,
+    deadline: CoarseTime,
+) -> CallbackId {
+    call_context_manager.register_callback(Callback::new(
+        call_context_id,
+        canister_test_id(1),
+        canister_test_id(2),
+        Cycles::zero(),
+        Cycles::zero(),
+        Cycles::zero(),
+        WasmClosure::new(0, 1),
+        WasmClosure::new(2, 3),
+        None,
+        deadline,
+    ))
+}
+
+#[test]
+fn expire_callbacks_only_expires_best_effort_callbacks_past_their_deadline() {
+    let mut call_context_manager = CallContextManager::default();
+    let call_context_id = call_context_manager.new_call_context(
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
+        Cycles::zero(),
+        Time::from_nanos_since_unix_epoch(0),
+        RequestMetadata::new(0, mock_time()),
+    );
+    let guaranteed =
+        register_callback_with_deadline(&mut call_context_manager, call_context_id, NO_DEADLINE);
+    let early = register_callback_with_deadline(
+        &mut call_context_manager,
+        call_context_id,
+        CoarseTime::from_secs_since_unix_epoch(10),
+    );
+    let late = register_callback_with_deadline(
+        &mut call_context_manager,
+        call_context_id,
+        CoarseTime::from_secs_since_unix_epoch(20),
+    );
+
+    let time = |secs| Time::from_nanos_since_unix_epoch(secs * 1_000_000_000);
+    assert!(!call_context_manager.has_expired_callbacks(time(9)));
+    assert_eq!(
+        Vec::<CallbackId>::new(),
+        call_context_manager.expire_callbacks(time(9), |_, _| false)
+    );
+
+    // Only `early` is past its deadline.
+    assert!(call_context_manager.has_expired_callbacks(time(10)));
+    assert_eq!(
+        vec![early],
+        call_context_manager.expire_callbacks(time(15), |_, _| false)
+    );
+    assert!(call_context_manager.is_expired_callback(early));
+    assert!(!call_context_manager.is_expired_callback(late));
+    assert!(!call_context_manager.is_expired_callback(guaranteed));
+    assert!(!call_context_manager.has_expired_callbacks(time(15)));
+
+    // Late best-effort responses for the expired callback are dropped, guaranteed
+    // responses and responses for unexpired callbacks are not.
+    let best_effort_response = |callback_id| {
+        ResponseBuilder::new()
+            .originator_reply_callback(callback_id)
+            .deadline(CoarseTime::from_secs_since_unix_epoch(10))
+            .build()
+    };
+    assert!(call_context_manager.should_drop_response(&best_effort_response(early)));
+    assert!(!call_context_manager.should_drop_response(&best_effort_response(late)));
+    assert!(!call_context_manager.should_drop_response(
+        &ResponseBuilder::new()
+            .originator_reply_callback(guaranteed)
+            .build()
+    ));
+    // Responses claiming to be best-effort are not dropped unless their callback
+    // has expired.
+    assert!(!call_context_manager.should_drop_response(&best_effort_response(guaranteed)));
+    assert!(
+        !call_context_manager.should_drop_response(&best_effort_response(CallbackId::from(1000)))
+    );
+
+    // A callback whose response is already enqueued is not expired.
+    assert_eq!(
+        Vec::<CallbackId>::new(),
+        call_context_manager.expire_callbacks(time(100), |callback_id, _| callback_id == late)
+    );
+    assert!(!call_context_manager.is_expired_callback(late));
+
+    // Unregistering the callback also forgets that it expired.
+    call_context_manager.unregister_callback(early).unwrap();
+    assert!(!call_context_manager.is_expired_callback(early));
+
+    // But a late response that arrives after the `SYS_UNKNOWN` reject was
+    // executed (and the callback unregistered) is still dropped.
+    assert!(call_context_manager.should_drop_response(&best_effort_response(early)));
+    // Unlike a best-effort response with a deadline that did not pass yet.
+    assert!(!call_context_manager.should_drop_response(
+        &ResponseBuilder::new()
+            .originator_reply_callback(early)
+            .deadline(CoarseTime::from_secs_since_unix_epoch(200))
+            .build()
+    ));
+}
+
+#[test]
+fn expired_callbacks_survive_protobuf_roundtrip() {
+    let mut call_context_manager = CallContextManager::default();
+    let call_context_id = call_context_manager.new_call_context(
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
+        Cycles::zero(),
+        Time::from_nanos_since_unix_epoch(0),
+        RequestMetadata::new(0, mock_time()),
+    );
+    let expired = register_callback_with_deadline(
+        &mut call_context_manager,
+        call_context_id,
+        CoarseTime::from_secs_since_unix_epoch(10),
+    );
+    register_callback_with_deadline(
+        &mut call_context_manager,
+        call_context_id,
+        CoarseTime::from_secs_since_unix_epoch(20),
+    );
+    assert_eq!(
+        vec![expired],
+        call_context_manager
+            .expire_callbacks(Time::from_nanos_since_unix_epoch(15_000_000_000), |_, _| {
+                false
+            })
+    );
+
+    let pb = pb::CallContextManager::from(&call_context_manager);
+    let round_trip = CallContextManager::try_from(pb).unwrap();
+    assert_eq!(call_context_manager, round_trip);
+}
@@ -28,7 +28,7 @@ use ic_test_utilities_time::mock_time;
 use ic_types::{
     messages::{
         CallContextId, CallbackId, CanisterCall, RequestMetadata, StopCanisterCallId,
-        StopCanisterContext, MAX_RESPONSE_COUNT_BYTES,
+        StopCanisterContext, MAX_RESPONSE_COUNT_BYTES, NO_DEADLINE,
     },
     methods::{Callback, WasmClosure},
     nominal_cycles::NominalCycles,
@@ -98,7 +98,7 @@ impl CanisterStateFixture {
             .call_context_manager_mut()
             .unwrap()
             .new_call_context(
-                CallOrigin::CanisterUpdate(CANISTER_ID, CallbackId::from(1)),
+                CallOrigin::CanisterUpdate(CANISTER_ID, CallbackId::from(1), NO_DEADLINE),
                 Cycles::zero(),
                 Time::from_nanos_since_unix_epoch(0),
                 RequestMetadata::new(0, mock_time()),
@@ -117,6 +117,7 @@ impl CanisterStateFixture {
                 WasmClosure::new(0, 2),
                 WasmClosure::new(0, 2),
                 None,
+                NO_DEADLINE,
             ))
     }
 
@@ -569,6 +570,7 @@ fn canister_state_callback_round_trip() {
         WasmClosure::new(0, 2),
         WasmClosure::new(0, 2),
         None,
+        NO_DEADLINE,
     );
 
     let pb_callback = pb::Callback::from(&callback);
@@ -580,8 +582,9 @@ fn canister_state_callback_round_trip() {
 
 #[test]
 fn canister_state_log_visibility_i32_round_trip() {
-    for initial in LogVisibility::iter() {
-        let encoded = i32::from(initial);
+    // The legacy `i32` encoding only covers the variants without arguments.
+    for initial in [LogVisibility::Controllers, LogVisibility::Public] {
+        let encoded = i32::from(&initial);
         let round_trip = LogVisibility::try_from(encoded).unwrap();
 
         assert_eq!(initial, round_trip);
@@ -601,14 +604,28 @@ fn canister_state_log_visibility_i32_default() {
 fn canister_state_log_visibility_round_trip() {
     use ic_protobuf::state::canister_state_bits::v1 as pb;
 
-    for initial in LogVisibility::iter() {
-        let encoded = pb::LogVisibility::from(initial);
+    for initial in [LogVisibility::Controllers, LogVisibility::Public] {
+        let encoded = pb::LogVisibility::from(&initial);
         let round_trip = LogVisibility::from(encoded);
 
         assert_eq!(initial, round_trip);
     }
 }
 
+#[test]
+fn canister_state_log_visibility_v2_round_trip() {
+    use ic_protobuf::state::canister_state_bits::v1 as pb;
+
+    let allowed_viewers =
+        LogVisibility::AllowedViewers(vec![user_test_id(1).get(), canister_test_id(2).get()]);
+    for initial in LogVisibility::iter().chain(std::iter::once(allowed_viewers)) {
+        let encoded = pb::LogVisibilityV2::from(&initial);
+        let round_trip = LogVisibility::try_from(encoded).unwrap();
+
+        assert_eq!(initial, round_trip);
+    }
+}
+
 #[test]
 fn canister_state_canister_log_record_round_trip() {
     use ic_protobuf::state::canister_state_bits::v1 as pb;
@@ -10,7 +10,9 @@ use ic_btc_types_internal::BlockBlob;
 use ic_certification_version::{CertificationVersion, CURRENT_CERTIFICATION_VERSION};
 use ic_constants::MAX_INGRESS_TTL;
 use ic_error_types::{ErrorCode, RejectCode, UserError};
-use ic_management_canister_types::{EcdsaKeyId, NodeMetrics, NodeMetricsHistoryResponse};
+use ic_management_canister_types::{
+    EcdsaKeyId, NodeMetrics, NodeMetricsHistoryResponse, SchnorrKeyId, VetKdKeyId,
+};
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
     registry::subnet::v1 as pb_subnet,
@@ -185,6 +187,13 @@ pub struct NetworkTopology {
     /// Mapping from ECDSA key_id to a list of subnets which can sign with the
     /// given key. Keys without any signing subnets are not included in the map.
     pub ecdsa_signing_subnets: BTreeMap<EcdsaKeyId, Vec<SubnetId>>,
+    /// Mapping from Schnorr key_id to a list of subnets which can sign with the
+    /// given key. Keys without any signing subnets are not included in the map.
+    pub schnorr_signing_subnets: BTreeMap<SchnorrKeyId, Vec<SubnetId>>,
+    /// Mapping from vetKD key_id to a list of subnets which can derive keys
+    /// with the given key. Keys without any enabled subnets are not included
+    /// in the map.
+    pub vetkd_enabled_subnets: BTreeMap<VetKdKeyId, Vec<SubnetId>>,
 
     /// The ID of the canister to forward bitcoin testnet requests to.
     pub bitcoin_testnet_canister_id: Option<CanisterId>,
@@ -216,6 +225,8 @@ impl Default for NetworkTopology {
             canister_migrations: Default::default(),
             nns_subnet_id: SubnetId::new(PrincipalId::new_anonymous()),
             ecdsa_signing_subnets: Default::default(),
+            schnorr_signing_subnets: Default::default(),
+            vetkd_enabled_subnets: Default::default(),
             bitcoin_testnet_canister_id: None,
             bitcoin_mainnet_canister_id: None,
         }
@@ -231,6 +242,24 @@ impl NetworkTopology {
             .unwrap_or(&[])
     }
 
+    /// Returns a list of subnets where the threshold Schnorr feature is
+    /// enabled for the given key.
+    pub fn schnorr_signing_subnets(&self, key_id: &SchnorrKeyId) -> &[SubnetId] {
+        self.schnorr_signing_subnets
+            .get(key_id)
+            .map(|ids| &ids[..])
+            .unwrap_or(&[])
+    }
+
+    /// Returns a list of subnets where vetKD key derivation is enabled for
+    /// the given key.
+    pub fn vetkd_enabled_subnets(&self, key_id: &VetKdKeyId) -> &[SubnetId] {
+        self.vetkd_enabled_subnets
+            .get(key_id)
+            .map(|ids| &ids[..])
+            .unwrap_or(&[])
+    }
+
     /// Returns the size of the given subnet.
     pub fn get_subnet_size(&self, subnet_id: &SubnetId) -> Option<usize> {
         self.subnets
@@ -275,6 +304,34 @@ impl From<&NetworkTopology> for pb_metadata::NetworkTopology {
                 Some(c) => vec![pb_types::CanisterId::from(c)],
                 None => vec![],
             },
+            schnorr_signing_subnets: item
+                .schnorr_signing_subnets
+                .iter()
+                .map(|(key_id, subnet_ids)| {
+                    let subnet_ids = subnet_ids
+                        .iter()
+                        .map(|id| subnet_id_into_protobuf(*id))
+                        .collect();
+                    pb_metadata::SchnorrKeyEntry {
+                        key_id: Some(key_id.into()),
+                        subnet_ids,
+                    }
+                })
+                .collect(),
+            vetkd_enabled_subnets: item
+                .vetkd_enabled_subnets
+                .iter()
+                .map(|(key_id, subnet_ids)| {
+                    let subnet_ids = subnet_ids
+                        .iter()
+                        .map(|id| subnet_id_into_protobuf(*id))
+                        .collect();
+                    pb_metadata::VetKdKeyEntry {
+                        key_id: Some(key_id.into()),
+                        subnet_ids,
+                    }
+                })
+                .collect(),
         }
     }
 }
@@ -310,6 +367,30 @@ impl TryFrom<pb_metadata::NetworkTopology> for NetworkTopology {
             );
         }
 
+        let mut schnorr_signing_subnets = BTreeMap::new();
+        for entry in item.schnorr_signing_subnets {
+            let mut subnet_ids = vec![];
+            for subnet_id in entry.subnet_ids {
+                subnet_ids.push(subnet_id_try_from_protobuf(subnet_id)?);
+            }
+            schnorr_signing_subnets.insert(
+                try_from_option_field(entry.key_id, "SchnorrKeyEntry::key_id")?,
+                subnet_ids,
+            );
+        }
+
+        let mut vetkd_enabled_subnets = BTreeMap::new();
+        for entry in item.vetkd_enabled_subnets {
+            let mut subnet_ids = vec![];
+            for subnet_id in entry.subnet_ids {
+                subnet_ids.push(subnet_id_try_from_protobuf(subnet_id)?);
+            }
+            vetkd_enabled_subnets.insert(
+                try_from_option_field(entry.key_id, "VetKdKeyEntry::key_id")?,
+                subnet_ids,
+            );
+        }
+
         let bitcoin_testnet_canister_id = match item.bitcoin_testnet_canister_ids.first() {
             Some(canister) => Some(CanisterId::try_from(canister.clone())?),
             None => None,
@@ -336,6 +417,8 @@ impl TryFrom<pb_metadata::NetworkTopology> for NetworkTopology {
                 .into(),
             nns_subnet_id,
             ecdsa_signing_subnets,
+            schnorr_signing_subnets,
+            vetkd_enabled_subnets,
             bitcoin_testnet_canister_id,
             bitcoin_mainnet_canister_id,
         })
@@ -356,6 +439,12 @@ pub struct SubnetTopology {
     /// a backup. An additional NNS proposal will be needed to allow the subnet
     /// holding the key as backup to actually produce signatures.
     pub ecdsa_keys_held: BTreeSet<EcdsaKeyId>,
+    /// Threshold Schnorr keys held by this subnet. As for ECDSA keys, holding
+    /// a key doesn't imply that the subnet is enabled to sign with it.
+    pub schnorr_keys_held: BTreeSet<SchnorrKeyId>,
+    /// VetKD keys held by this subnet. As for ECDSA keys, holding a key
+    /// doesn't imply that the subnet is enabled to derive keys with it.
+    pub vetkd_keys_held: BTreeSet<VetKdKeyId>,
 }
 
 impl From<&SubnetTopology> for pb_metadata::SubnetTopology {
@@ -372,6 +461,8 @@ impl From<&SubnetTopology> for pb_metadata::SubnetTopology {
             subnet_type: i32::from(item.subnet_type),
             subnet_features: Some(pb_subnet::SubnetFeatures::from(item.subnet_features)),
             ecdsa_keys_held: item.ecdsa_keys_held.iter().map(|k| k.into()).collect(),
+            schnorr_keys_held: item.schnorr_keys_held.iter().map(|k| k.into()).collect(),
+            vetkd_keys_held: item.vetkd_keys_held.iter().map(|k| k.into()).collect(),
         }
     }
 }
@@ -389,6 +480,16 @@ impl TryFrom<pb_metadata::SubnetTopology> for SubnetTopology {
             ecdsa_keys_held.insert(EcdsaKeyId::try_from(key)?);
         }
 
+        let mut schnorr_keys_held = BTreeSet::new();
+        for key in item.schnorr_keys_held {
+            schnorr_keys_held.insert(SchnorrKeyId::try_from(key)?);
+        }
+
+        let mut vetkd_keys_held = BTreeSet::new();
+        for key in item.vetkd_keys_held {
+            vetkd_keys_held.insert(VetKdKeyId::try_from(key)?);
+        }
+
         Ok(Self {
             public_key: item.public_key,
             nodes,
@@ -401,6 +502,8 @@ impl TryFrom<pb_metadata::SubnetTopology> for SubnetTopology {
                 .map(SubnetFeatures::from)
                 .unwrap_or_default(),
             ecdsa_keys_held,
+            schnorr_keys_held,
+            vetkd_keys_held,
         })
     }
 }
@@ -410,6 +513,8 @@ pub struct SubnetMetrics {
     pub consumed_cycles_by_deleted_canisters: NominalCycles,
     pub consumed_cycles_http_outcalls: NominalCycles,
     pub consumed_cycles_ecdsa_outcalls: NominalCycles,
+    pub consumed_cycles_schnorr_outcalls: NominalCycles,
+    pub consumed_cycles_vetkd: NominalCycles,
     consumed_cycles_by_use_case: BTreeMap<CyclesUseCase, NominalCycles>,
     pub ecdsa_signature_agreements: u64,
     /// The number of canisters that exist on this subnet.
@@ -444,13 +549,18 @@ impl SubnetMetrics {
         total += self.consumed_cycles_by_deleted_canisters;
         total += self.consumed_cycles_http_outcalls;
         total += self.consumed_cycles_ecdsa_outcalls;
+        total += self.consumed_cycles_schnorr_outcalls;
+        total += self.consumed_cycles_vetkd;
 
         for (use_case, cycles) in self.consumed_cycles_by_use_case.iter() {
             match use_case {
-                // For ecdsa outcalls, http outcalls and deleted canisters, skip
-                // updating the total using the use case specific metric as the
-                // update above should be sufficient (the old metric is a superset).
+                // For ecdsa outcalls, schnorr outcalls, vetKD requests, http
+                // outcalls and deleted canisters, skip updating the total using
+                // the use case specific metric as the update above should be
+                // sufficient (the old metric is a superset).
                 CyclesUseCase::ECDSAOutcalls
+                | CyclesUseCase::SchnorrOutcalls
+                | CyclesUseCase::VetKd
                 | CyclesUseCase::HTTPOutcalls
                 | CyclesUseCase::DeletedCanisters => {}
                 // Non consumed cycles should not be counted towards the total consumed.
@@ -479,6 +589,8 @@ impl From<&SubnetMetrics> for pb_metadata::SubnetMetrics {
             ),
             consumed_cycles_http_outcalls: Some((&item.consumed_cycles_http_outcalls).into()),
             consumed_cycles_ecdsa_outcalls: Some((&item.consumed_cycles_ecdsa_outcalls).into()),
+            consumed_cycles_schnorr_outcalls: Some((&item.consumed_cycles_schnorr_outcalls).into()),
+            consumed_cycles_vetkd: Some((&item.consumed_cycles_vetkd).into()),
             ecdsa_signature_agreements: Some(item.ecdsa_signature_agreements),
             consumed_cycles_by_use_case: item
                 .consumed_cycles_by_use_case
@@ -514,6 +626,16 @@ impl TryFrom<pb_metadata::SubnetMetrics> for SubnetMetrics {
                 "SubnetMetrics::consumed_cycles_ecdsa_outcalls",
             )
             .unwrap_or_else(|_| NominalCycles::from(0_u128)),
+            consumed_cycles_schnorr_outcalls: try_from_option_field(
+                item.consumed_cycles_schnorr_outcalls,
+                "SubnetMetrics::consumed_cycles_schnorr_outcalls",
+            )
+            .unwrap_or_else(|_| NominalCycles::from(0_u128)),
+            consumed_cycles_vetkd: try_from_option_field(
+                item.consumed_cycles_vetkd,
+                "SubnetMetrics::consumed_cycles_vetkd",
+            )
+            .unwrap_or_else(|_| NominalCycles::from(0_u128)),
             ecdsa_signature_agreements: item.ecdsa_signature_agreements.unwrap_or_default(),
             consumed_cycles_by_use_case: item
                 .consumed_cycles_by_use_case
@@ -1142,6 +1264,7 @@ impl SystemMetadata {
                         RejectCode::SysTransient,
                         format!("Canister {} migrated during a subnet split", canister_id),
                     )),
+                    deadline: request.deadline,
                 };
                 subnet_queues.push_output_response(response.into());
             }
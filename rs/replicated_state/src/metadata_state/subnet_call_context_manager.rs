@@ -1,6 +1,6 @@
 use ic_btc_types_internal::{GetSuccessorsRequestInitial, SendTransactionRequest};
 use ic_logger::{info, ReplicaLogger};
-use ic_management_canister_types::EcdsaKeyId;
+use ic_management_canister_types::{EcdsaKeyId, SchnorrKeyId, VetKdKeyId};
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
     state::system_metadata::v1 as pb_metadata,
@@ -23,6 +23,8 @@ use std::{
 pub enum SubnetCallContext {
     SetupInitialDKG(SetupInitialDkgContext),
     SignWithEcdsa(SignWithEcdsaContext),
+    SignWithSchnorr(SignWithSchnorrContext),
+    VetKdDeriveEncryptedKey(VetKdDeriveEncryptedKeyContext),
     CanisterHttpRequest(CanisterHttpRequestContext),
     EcdsaDealings(EcdsaDealingsContext),
     BitcoinGetSuccessors(BitcoinGetSuccessorsContext),
@@ -34,6 +36,8 @@ impl SubnetCallContext {
         match &self {
             SubnetCallContext::SetupInitialDKG(context) => &context.request,
             SubnetCallContext::SignWithEcdsa(context) => &context.request,
+            SubnetCallContext::SignWithSchnorr(context) => &context.request,
+            SubnetCallContext::VetKdDeriveEncryptedKey(context) => &context.request,
             SubnetCallContext::CanisterHttpRequest(context) => &context.request,
             SubnetCallContext::EcdsaDealings(context) => &context.request,
             SubnetCallContext::BitcoinGetSuccessors(context) => &context.request,
@@ -45,6 +49,8 @@ impl SubnetCallContext {
         match &self {
             SubnetCallContext::SetupInitialDKG(context) => context.time,
             SubnetCallContext::SignWithEcdsa(context) => context.batch_time,
+            SubnetCallContext::SignWithSchnorr(context) => context.batch_time,
+            SubnetCallContext::VetKdDeriveEncryptedKey(context) => context.batch_time,
             SubnetCallContext::CanisterHttpRequest(context) => context.time,
             SubnetCallContext::EcdsaDealings(context) => context.time,
             SubnetCallContext::BitcoinGetSuccessors(context) => context.time,
@@ -199,6 +205,8 @@ pub struct SubnetCallContextManager {
     next_callback_id: u64,
     pub setup_initial_dkg_contexts: BTreeMap<CallbackId, SetupInitialDkgContext>,
     pub sign_with_ecdsa_contexts: BTreeMap<CallbackId, SignWithEcdsaContext>,
+    pub sign_with_schnorr_contexts: BTreeMap<CallbackId, SignWithSchnorrContext>,
+    pub vetkd_derive_encrypted_key_contexts: BTreeMap<CallbackId, VetKdDeriveEncryptedKeyContext>,
     pub canister_http_request_contexts: BTreeMap<CallbackId, CanisterHttpRequestContext>,
     pub ecdsa_dealings_contexts: BTreeMap<CallbackId, EcdsaDealingsContext>,
     pub bitcoin_get_successors_contexts: BTreeMap<CallbackId, BitcoinGetSuccessorsContext>,
@@ -224,6 +232,13 @@ impl SubnetCallContextManager {
             SubnetCallContext::SignWithEcdsa(context) => {
                 self.sign_with_ecdsa_contexts.insert(callback_id, context);
             }
+            SubnetCallContext::SignWithSchnorr(context) => {
+                self.sign_with_schnorr_contexts.insert(callback_id, context);
+            }
+            SubnetCallContext::VetKdDeriveEncryptedKey(context) => {
+                self.vetkd_derive_encrypted_key_contexts
+                    .insert(callback_id, context);
+            }
             SubnetCallContext::CanisterHttpRequest(context) => {
                 self.canister_http_request_contexts
                     .insert(callback_id, context);
@@ -272,6 +287,32 @@ impl SubnetCallContextManager {
                         SubnetCallContext::SignWithEcdsa(context)
                     })
             })
+            .or_else(|| {
+                self.sign_with_schnorr_contexts
+                    .remove(&callback_id)
+                    .map(|context| {
+                        info!(
+                            logger,
+                            "Received the response for SignWithSchnorr request with id {:?} from {:?}",
+                            context.pseudo_random_id,
+                            context.request.sender
+                        );
+                        SubnetCallContext::SignWithSchnorr(context)
+                    })
+            })
+            .or_else(|| {
+                self.vetkd_derive_encrypted_key_contexts
+                    .remove(&callback_id)
+                    .map(|context| {
+                        info!(
+                            logger,
+                            "Received the response for VetKdDeriveEncryptedKey request with id {:?} from {:?}",
+                            context.pseudo_random_id,
+                            context.request.sender
+                        );
+                        SubnetCallContext::VetKdDeriveEncryptedKey(context)
+                    })
+            })
             .or_else(|| {
                 self.ecdsa_dealings_contexts
                     .remove(&callback_id)
@@ -510,6 +551,26 @@ impl From<&SubnetCallContextManager> for pb_metadata::SubnetCallContextManager {
                 .iter()
                 .map(|context| context.into())
                 .collect(),
+            sign_with_schnorr_contexts: item
+                .sign_with_schnorr_contexts
+                .iter()
+                .map(
+                    |(callback_id, context)| pb_metadata::SignWithSchnorrContextTree {
+                        callback_id: callback_id.get(),
+                        context: Some(context.into()),
+                    },
+                )
+                .collect(),
+            vetkd_derive_encrypted_key_contexts: item
+                .vetkd_derive_encrypted_key_contexts
+                .iter()
+                .map(
+                    |(callback_id, context)| pb_metadata::VetKdDeriveEncryptedKeyContextTree {
+                        callback_id: callback_id.get(),
+                        context: Some(context.into()),
+                    },
+                )
+                .collect(),
         }
     }
 }
@@ -534,6 +595,23 @@ impl TryFrom<(Time, pb_metadata::SubnetCallContextManager)> for SubnetCallContex
             sign_with_ecdsa_contexts.insert(CallbackId::new(entry.callback_id), context);
         }
 
+        let mut sign_with_schnorr_contexts = BTreeMap::<CallbackId, SignWithSchnorrContext>::new();
+        for entry in item.sign_with_schnorr_contexts {
+            let context: SignWithSchnorrContext =
+                try_from_option_field(entry.context, "SystemMetadata::SignWithSchnorrContext")?;
+            sign_with_schnorr_contexts.insert(CallbackId::new(entry.callback_id), context);
+        }
+
+        let mut vetkd_derive_encrypted_key_contexts =
+            BTreeMap::<CallbackId, VetKdDeriveEncryptedKeyContext>::new();
+        for entry in item.vetkd_derive_encrypted_key_contexts {
+            let context: VetKdDeriveEncryptedKeyContext = try_from_option_field(
+                entry.context,
+                "SystemMetadata::VetKdDeriveEncryptedKeyContext",
+            )?;
+            vetkd_derive_encrypted_key_contexts.insert(CallbackId::new(entry.callback_id), context);
+        }
+
         let mut canister_http_request_contexts =
             BTreeMap::<CallbackId, CanisterHttpRequestContext>::new();
         for entry in item.canister_http_request_contexts {
@@ -614,6 +692,8 @@ impl TryFrom<(Time, pb_metadata::SubnetCallContextManager)> for SubnetCallContex
             next_callback_id: item.next_callback_id,
             setup_initial_dkg_contexts,
             sign_with_ecdsa_contexts,
+            sign_with_schnorr_contexts,
+            vetkd_derive_encrypted_key_contexts,
             canister_http_request_contexts,
             ecdsa_dealings_contexts,
             bitcoin_get_successors_contexts,
@@ -762,6 +842,111 @@ impl TryFrom<pb_metadata::SignWithEcdsaContext> for SignWithEcdsaContext {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignWithSchnorrContext {
+    pub request: Request,
+    pub key_id: SchnorrKeyId,
+    pub message: Vec<u8>,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub pseudo_random_id: [u8; 32],
+    pub batch_time: Time,
+}
+
+impl From<&SignWithSchnorrContext> for pb_metadata::SignWithSchnorrContext {
+    fn from(context: &SignWithSchnorrContext) -> Self {
+        pb_metadata::SignWithSchnorrContext {
+            request: Some((&context.request).into()),
+            key_id: Some((&context.key_id).into()),
+            message: context.message.clone(),
+            derivation_path_vec: context.derivation_path.clone(),
+            pseudo_random_id: context.pseudo_random_id.to_vec(),
+            batch_time: context.batch_time.as_nanos_since_unix_epoch(),
+        }
+    }
+}
+
+impl TryFrom<pb_metadata::SignWithSchnorrContext> for SignWithSchnorrContext {
+    type Error = ProxyDecodeError;
+    fn try_from(context: pb_metadata::SignWithSchnorrContext) -> Result<Self, Self::Error> {
+        let request: Request =
+            try_from_option_field(context.request, "SignWithSchnorrContext::request")?;
+        let key_id: SchnorrKeyId =
+            try_from_option_field(context.key_id, "SignWithSchnorrContext::key_id")?;
+        Ok(SignWithSchnorrContext {
+            request,
+            key_id,
+            message: context.message,
+            derivation_path: context.derivation_path_vec,
+            pseudo_random_id: {
+                if context.pseudo_random_id.len() != NiDkgTargetId::SIZE {
+                    return Err(Self::Error::Other(format!(
+                        "pseudo_random_id is not {} bytes.",
+                        NiDkgTargetId::SIZE
+                    )));
+                }
+                let mut id = [0; NiDkgTargetId::SIZE];
+                id.copy_from_slice(&context.pseudo_random_id);
+                id
+            },
+            batch_time: Time::from_nanos_since_unix_epoch(context.batch_time),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VetKdDeriveEncryptedKeyContext {
+    pub request: Request,
+    pub key_id: VetKdKeyId,
+    pub derivation_path: Vec<Vec<u8>>,
+    pub derivation_id: Vec<u8>,
+    pub encryption_public_key: Vec<u8>,
+    pub pseudo_random_id: [u8; 32],
+    pub batch_time: Time,
+}
+
+impl From<&VetKdDeriveEncryptedKeyContext> for pb_metadata::VetKdDeriveEncryptedKeyContext {
+    fn from(context: &VetKdDeriveEncryptedKeyContext) -> Self {
+        pb_metadata::VetKdDeriveEncryptedKeyContext {
+            request: Some((&context.request).into()),
+            key_id: Some((&context.key_id).into()),
+            derivation_path_vec: context.derivation_path.clone(),
+            derivation_id: context.derivation_id.clone(),
+            encryption_public_key: context.encryption_public_key.clone(),
+            pseudo_random_id: context.pseudo_random_id.to_vec(),
+            batch_time: context.batch_time.as_nanos_since_unix_epoch(),
+        }
+    }
+}
+
+impl TryFrom<pb_metadata::VetKdDeriveEncryptedKeyContext> for VetKdDeriveEncryptedKeyContext {
+    type Error = ProxyDecodeError;
+    fn try_from(context: pb_metadata::VetKdDeriveEncryptedKeyContext) -> Result<Self, Self::Error> {
+        let request: Request =
+            try_from_option_field(context.request, "VetKdDeriveEncryptedKeyContext::request")?;
+        let key_id: VetKdKeyId =
+            try_from_option_field(context.key_id, "VetKdDeriveEncryptedKeyContext::key_id")?;
+        Ok(VetKdDeriveEncryptedKeyContext {
+            request,
+            key_id,
+            derivation_path: context.derivation_path_vec,
+            derivation_id: context.derivation_id,
+            encryption_public_key: context.encryption_public_key,
+            pseudo_random_id: {
+                if context.pseudo_random_id.len() != NiDkgTargetId::SIZE {
+                    return Err(Self::Error::Other(format!(
+                        "pseudo_random_id is not {} bytes.",
+                        NiDkgTargetId::SIZE
+                    )));
+                }
+                let mut id = [0; NiDkgTargetId::SIZE];
+                id.copy_from_slice(&context.pseudo_random_id);
+                id
+            },
+            batch_time: Time::from_nanos_since_unix_epoch(context.batch_time),
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EcdsaDealingsContext {
     pub request: Request,
@@ -1101,6 +1286,8 @@ mod testing {
             next_callback_id: 0,
             setup_initial_dkg_contexts: Default::default(),
             sign_with_ecdsa_contexts: Default::default(),
+            sign_with_schnorr_contexts: Default::default(),
+            vetkd_derive_encrypted_key_contexts: Default::default(),
             canister_http_request_contexts: Default::default(),
             ecdsa_dealings_contexts: Default::default(),
             bitcoin_get_successors_contexts: Default::default(),
@@ -1461,6 +1461,8 @@ fn consumed_cycles_total_calculates_the_right_amount() {
     consumed_cycles_by_use_case.insert(CyclesUseCase::DeletedCanisters, NominalCycles::from(5));
     consumed_cycles_by_use_case.insert(CyclesUseCase::HTTPOutcalls, NominalCycles::from(12));
     consumed_cycles_by_use_case.insert(CyclesUseCase::ECDSAOutcalls, NominalCycles::from(30));
+    consumed_cycles_by_use_case.insert(CyclesUseCase::SchnorrOutcalls, NominalCycles::from(15));
+    consumed_cycles_by_use_case.insert(CyclesUseCase::VetKd, NominalCycles::from(25));
     consumed_cycles_by_use_case.insert(CyclesUseCase::Instructions, NominalCycles::from(100));
     consumed_cycles_by_use_case.insert(CyclesUseCase::Memory, NominalCycles::from(50));
     consumed_cycles_by_use_case.insert(CyclesUseCase::CanisterCreation, NominalCycles::from(40));
@@ -1470,13 +1472,15 @@ fn consumed_cycles_total_calculates_the_right_amount() {
         consumed_cycles_by_deleted_canisters: NominalCycles::from(10),
         consumed_cycles_http_outcalls: NominalCycles::from(20),
         consumed_cycles_ecdsa_outcalls: NominalCycles::from(30),
+        consumed_cycles_schnorr_outcalls: NominalCycles::from(15),
+        consumed_cycles_vetkd: NominalCycles::from(25),
         consumed_cycles_by_use_case,
         ..Default::default()
     };
 
     assert_eq!(
         subnet_metrics.consumed_cycles_total(),
-        NominalCycles::from(250)
+        NominalCycles::from(290)
     );
 }
 
@@ -6,7 +6,12 @@ use crate::{
     canister_snapshots::CanisterSnapshots,
     canister_state::queues::CanisterQueuesLoopDetector,
     canister_state::system_state::{push_input, CanisterOutputQueuesIterator},
-    metadata_state::{subnet_call_context_manager::SignWithEcdsaContext, StreamMap},
+    metadata_state::{
+        subnet_call_context_manager::{
+            SignWithEcdsaContext, SignWithSchnorrContext, VetKdDeriveEncryptedKeyContext,
+        },
+        StreamMap,
+    },
     CanisterQueues,
 };
 use ic_base_types::PrincipalId;
@@ -345,7 +350,7 @@ pub struct MemoryTaken {
     /// Execution memory accounts for canister memory reservation where
     /// specified and the actual canister memory usage (including
     /// Wasm custom sections) where no explicit memory reservation
-    /// has been made.
+    /// has been made, as well as for canister snapshots.
     execution: NumBytes,
     /// Memory taken by canister messages.
     messages: NumBytes,
@@ -353,6 +358,8 @@ pub struct MemoryTaken {
     wasm_custom_sections: NumBytes,
     /// Memory taken by canister history.
     canister_history: NumBytes,
+    /// Memory taken by canister snapshots.
+    canister_snapshots: NumBytes,
 }
 
 impl MemoryTaken {
@@ -375,6 +382,11 @@ impl MemoryTaken {
     pub fn canister_history(&self) -> NumBytes {
         self.canister_history
     }
+
+    /// Returns the amount of memory taken by canister snapshots.
+    pub fn canister_snapshots(&self) -> NumBytes {
+        self.canister_snapshots
+    }
 }
 
 /// ReplicatedState is the deterministic replicated state of the system.
@@ -581,6 +593,24 @@ impl ReplicatedState {
             .sign_with_ecdsa_contexts
     }
 
+    /// Returns all sign with Schnorr contexts
+    pub fn sign_with_schnorr_contexts(&self) -> &BTreeMap<CallbackId, SignWithSchnorrContext> {
+        &self
+            .metadata
+            .subnet_call_context_manager
+            .sign_with_schnorr_contexts
+    }
+
+    /// Returns all vetKD derive encrypted key contexts
+    pub fn vetkd_derive_encrypted_key_contexts(
+        &self,
+    ) -> &BTreeMap<CallbackId, VetKdDeriveEncryptedKeyContext> {
+        &self
+            .metadata
+            .subnet_call_context_manager
+            .vetkd_derive_encrypted_key_contexts
+    }
+
     /// Retrieves a reference to the stream from this subnet to the destination
     /// subnet, if such a stream exists.
     pub fn get_stream(&self, destination_subnet_id: &SubnetId) -> Option<&Stream> {
@@ -630,13 +660,19 @@ impl ReplicatedState {
 
         message_memory_taken += (self.subnet_queues.memory_usage() as u64).into();
 
+        // Snapshots are not covered by memory reservations, so they are always
+        // counted in addition to the memory taken by their canisters.
+        let canister_snapshots_memory_taken = self.canister_snapshots.memory_taken();
+
         MemoryTaken {
             execution: raw_memory_taken
                 + canister_history_memory_taken
-                + wasm_chunk_store_memory_usage,
+                + wasm_chunk_store_memory_usage
+                + canister_snapshots_memory_taken,
             messages: message_memory_taken,
             wasm_custom_sections: wasm_custom_sections_memory_taken,
             canister_history: canister_history_memory_taken,
+            canister_snapshots: canister_snapshots_memory_taken,
         }
     }
 
@@ -892,6 +928,40 @@ impl ReplicatedState {
         timed_out_requests_count
     }
 
+    /// Times out all best-effort callbacks with expired deadlines (given the
+    /// current time), enqueuing a `SYS_UNKNOWN` reject response for each.
+    /// Returns the number of timed out callbacks.
+    ///
+    /// See `SystemState::time_out_callbacks` for further details.
+    pub fn time_out_callbacks(&mut self) -> u64 {
+        let current_time = self.metadata.time();
+        // As above, only apply the costly remove-call-replace to canisters that
+        // actually have expired callbacks.
+        let canister_ids_with_expired_callbacks = self
+            .canister_states
+            .iter()
+            .filter(|(_, canister_state)| {
+                canister_state
+                    .system_state
+                    .has_expired_callbacks(current_time)
+            })
+            .map(|(canister_id, _)| *canister_id)
+            .collect::<Vec<_>>();
+
+        let mut timed_out_callbacks_count = 0;
+        for canister_id in canister_ids_with_expired_callbacks {
+            let mut canister = self.canister_states.remove(&canister_id).unwrap();
+            timed_out_callbacks_count += canister.system_state.time_out_callbacks(
+                current_time,
+                &canister_id,
+                &self.canister_states,
+            );
+            self.canister_states.insert(canister_id, canister);
+        }
+
+        timed_out_callbacks_count
+    }
+
     /// Splits the replicated state as part of subnet splitting phase 1, retaining
     /// only the canisters of `subnet_id` (as determined by the provided routing
     /// table).
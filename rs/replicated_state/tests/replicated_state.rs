@@ -15,10 +15,13 @@ use ic_replicated_state::metadata_state::subnet_call_context_manager::BitcoinSen
 use ic_replicated_state::replicated_state::testing::ReplicatedStateTesting;
 use ic_replicated_state::testing::{CanisterQueuesTesting, SystemStateTesting};
 use ic_replicated_state::{
+    canister_snapshots::CanisterSnapshot,
     canister_state::execution_state::{CustomSection, CustomSectionType, WasmMetadata},
+    canister_state::system_state::wasm_chunk_store::WasmChunkStore,
     metadata_state::subnet_call_context_manager::{BitcoinGetSuccessorsContext, SubnetCallContext},
     replicated_state::{MemoryTaken, PeekableOutputIterator, ReplicatedStateMessageRouting},
-    CanisterState, IngressHistoryState, ReplicatedState, SchedulerState, StateError, SystemState,
+    CallOrigin, CanisterState, IngressHistoryState, NumWasmPages, PageMap, ReplicatedState,
+    SchedulerState, StateError, SystemState,
 };
 use ic_test_utilities::state::{arb_replicated_state_with_queues, ExecutionStateBuilder};
 use ic_test_utilities::types::ids::{canister_test_id, message_test_id, user_test_id, SUBNET_1};
@@ -28,9 +31,11 @@ use ic_types::ingress::{IngressState, IngressStatus};
 use ic_types::messages::RejectContext;
 use ic_types::{
     messages::{
-        CanisterMessage, Payload, Request, RequestOrResponse, Response, MAX_RESPONSE_COUNT_BYTES,
+        CallbackId, CanisterMessage, Payload, Request, RequestMetadata, RequestOrResponse,
+        Response, MAX_RESPONSE_COUNT_BYTES, NO_DEADLINE,
     },
-    CountBytes, Cycles, MemoryAllocation, Time,
+    methods::{Callback, WasmClosure},
+    CoarseTime, CountBytes, Cycles, MemoryAllocation, Time,
 };
 use maplit::btreemap;
 use proptest::prelude::*;
@@ -451,6 +456,41 @@ fn memory_taken_by_canister_history() {
     assert_canister_history_memory_taken(canister_history_memory, &fixture);
 }
 
+#[test]
+fn memory_taken_by_canister_snapshots() {
+    let mut fixture = ReplicatedStateFixture::with_wasm_metadata(
+        &[CANISTER_ID],
+        WasmMetadata::new(BTreeMap::new()),
+    );
+    assert_execution_memory_taken(0, &fixture);
+
+    let snapshot = CanisterSnapshot::new(
+        CANISTER_ID,
+        mock_time(),
+        0,
+        vec![0; 32],
+        Some(PageMap::new_for_testing()),
+        NumWasmPages::new(1),
+        Some(PageMap::new_for_testing()),
+        NumWasmPages::new(2),
+        WasmChunkStore::new_for_testing(NumBytes::from(20)),
+        None,
+    );
+    let snapshot_size = 3 * 64 * 1024 + 32;
+    assert_eq!(snapshot.size().get(), snapshot_size as u64);
+    fixture.state.canister_snapshots.push(Arc::new(snapshot));
+    assert_execution_memory_taken(snapshot_size, &fixture);
+    assert_eq!(
+        fixture.memory_taken().canister_snapshots().get(),
+        snapshot_size as u64
+    );
+
+    // Snapshots are counted in addition to a memory reservation.
+    let canister_state = fixture.state.canister_state_mut(&CANISTER_ID).unwrap();
+    canister_state.system_state.memory_allocation = MemoryAllocation::Reserved(NumBytes::from(888));
+    assert_execution_memory_taken(888 + snapshot_size, &fixture);
+}
+
 #[test]
 fn push_subnet_queues_input_respects_subnet_available_memory() {
     let mut fixture = ReplicatedStateFixture::new();
@@ -685,6 +725,94 @@ fn time_out_requests_updates_subnet_input_schedules_correctly() {
     );
 }
 
+#[test]
+fn time_out_callbacks_enqueues_sys_unknown_reject_and_drops_late_response() {
+    let mut fixture = ReplicatedStateFixture::with_canisters(&[CANISTER_ID, OTHER_CANISTER_ID]);
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+
+    // Register a best-effort callback and push the matching request.
+    let call_context_manager = fixture
+        .state
+        .canister_state_mut(&CANISTER_ID)
+        .unwrap()
+        .system_state
+        .call_context_manager_mut()
+        .unwrap();
+    let call_context_id = call_context_manager.new_call_context(
+        CallOrigin::CanisterUpdate(OTHER_CANISTER_ID, CallbackId::from(1), NO_DEADLINE),
+        Cycles::zero(),
+        mock_time(),
+        RequestMetadata::new(0, mock_time()),
+    );
+    let callback_id = call_context_manager.register_callback(Callback::new(
+        call_context_id,
+        CANISTER_ID,
+        OTHER_CANISTER_ID,
+        Cycles::zero(),
+        Cycles::zero(),
+        Cycles::zero(),
+        WasmClosure::new(0, 1),
+        WasmClosure::new(2, 3),
+        None,
+        deadline,
+    ));
+    fixture
+        .push_output_request(
+            RequestBuilder::default()
+                .sender(CANISTER_ID)
+                .receiver(OTHER_CANISTER_ID)
+                .sender_reply_callback(callback_id)
+                .deadline(deadline)
+                .build(),
+            mock_time(),
+        )
+        .unwrap();
+
+    // Nothing to time out before the deadline.
+    fixture.state.metadata.batch_time = Time::from_nanos_since_unix_epoch(9_000_000_000);
+    assert_eq!(0, fixture.state.time_out_callbacks());
+
+    // At the deadline, a `SYS_UNKNOWN` reject response is enqueued.
+    fixture.state.metadata.batch_time = Time::from_nanos_since_unix_epoch(10_000_000_000);
+    assert_eq!(1, fixture.state.time_out_callbacks());
+    assert_eq!(0, fixture.state.time_out_callbacks());
+    match fixture.pop_input() {
+        Some(CanisterMessage::Response(response)) => {
+            assert_eq!(callback_id, response.originator_reply_callback);
+            assert_eq!(deadline, response.deadline);
+            match &response.response_payload {
+                Payload::Reject(context) => assert_eq!(RejectCode::SysUnknown, context.code()),
+                payload => panic!("Expected a reject response, got {:?}", payload),
+            }
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+
+    // The actual response arrives late and is silently dropped.
+    let late_response = ResponseBuilder::default()
+        .respondent(OTHER_CANISTER_ID)
+        .originator(CANISTER_ID)
+        .originator_reply_callback(callback_id)
+        .deadline(deadline)
+        .build();
+    assert_eq!(Ok(()), fixture.push_input(late_response.clone().into()));
+    assert_eq!(None, fixture.pop_input());
+
+    // Executing the reject response unregisters the callback. A late response
+    // arriving afterwards is still silently dropped.
+    fixture
+        .state
+        .canister_state_mut(&CANISTER_ID)
+        .unwrap()
+        .system_state
+        .call_context_manager_mut()
+        .unwrap()
+        .unregister_callback(callback_id)
+        .unwrap();
+    assert_eq!(Ok(()), fixture.push_input(late_response.into()));
+    assert_eq!(None, fixture.pop_input());
+}
+
 #[test]
 fn split() {
     // We will be splitting subnet A into A' and B.
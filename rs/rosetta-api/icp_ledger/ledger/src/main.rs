@@ -37,6 +37,12 @@ use icp_ledger::{
 use icrc_ledger_types::icrc1::transfer::TransferArg;
 use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc21::{
+    consent_message::{build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints, TokenInfo},
+    errors::Icrc21Error,
+    requests::ConsentMessageRequest,
+    responses::ConsentInfo,
+};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value, icrc3::archive::QueryArchiveFn,
 };
@@ -1499,6 +1505,30 @@ fn icrc2_allowance_candid() {
     over(candid_one, icrc2_allowance)
 }
 
+#[candid_method(update, rename = "icrc21_canister_call_consent_message")]
+fn icrc21_canister_call_consent_message(
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    let token_info = {
+        let ledger = LEDGER.read().unwrap();
+        TokenInfo {
+            symbol: ledger.token_symbol.clone(),
+            decimals: DECIMAL_PLACES as u8,
+            fee: Nat::from(ledger.transfer_fee.get_e8s()),
+        }
+    };
+    build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+        consent_msg_request,
+        caller().0,
+        token_info,
+    )
+}
+
+#[export_name = "canister_update icrc21_canister_call_consent_message"]
+fn icrc21_canister_call_consent_message_candid() {
+    over(candid_one, icrc21_canister_call_consent_message)
+}
+
 candid::export_service!();
 
 #[export_name = "canister_query __get_candid_interface_tmp_hack"]
@@ -657,6 +657,11 @@ fn test_transfer_from_burn() {
     ic_icrc1_ledger_sm_tests::test_transfer_from_burn(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_icrc21_standard() {
+    ic_icrc1_ledger_sm_tests::test_icrc21_standard(ledger_wasm(), encode_init_args);
+}
+
 #[test]
 fn test_balances_overflow() {
     ic_icrc1_ledger_sm_tests::test_balances_overflow(ledger_wasm(), encode_init_args);
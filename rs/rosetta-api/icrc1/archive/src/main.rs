@@ -1,4 +1,4 @@
-use candid::{candid_method, Principal};
+use candid::{candid_method, Nat, Principal};
 use ic_canisters_http_types::{HttpRequest, HttpResponse, HttpResponseBuilder};
 use ic_cdk_macros::{init, post_upgrade, query, update};
 use ic_icrc1::{blocks::encoded_block_to_generic_block, Block};
@@ -8,8 +8,12 @@ use ic_stable_structures::{
     cell::Cell as StableCell, log::Log as StableLog, memory_manager::MemoryManager,
     storable::Bound, DefaultMemoryImpl, RestrictedMemory, Storable,
 };
-use icrc_ledger_types::icrc3::blocks::BlockRange;
+use icrc_ledger_types::icrc::generic_value::ICRC3Value;
+use icrc_ledger_types::icrc3::archive::{GetArchivesArgs, GetArchivesResult};
 use icrc_ledger_types::icrc3::blocks::GenericBlock as IcrcBlock;
+use icrc_ledger_types::icrc3::blocks::{
+    BlockRange, BlockWithId, GetBlocksArgs, GetBlocksResult, SupportedBlockType,
+};
 
 use icrc_ledger_types::icrc3::transactions::Transaction;
 use icrc_ledger_types::icrc3::transactions::{GetTransactionsRequest, TransactionRange};
@@ -273,6 +277,60 @@ fn get_blocks(req: GetTransactionsRequest) -> BlockRange {
     BlockRange { blocks }
 }
 
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(reqs: GetBlocksArgs) -> GetBlocksResult {
+    let (block_index_offset, max_blocks) = with_archive_opts(|opts| {
+        (
+            opts.block_index_offset,
+            opts.max_transactions_per_response as usize,
+        )
+    });
+    let mut blocks = vec![];
+    with_blocks(|block_log| {
+        for req in reqs {
+            let (start, length) = match req.as_start_and_length() {
+                Ok(start_and_length) => start_and_length,
+                Err(_) => continue,
+            };
+            // Only return the blocks stored in this archive.
+            let end = start.saturating_add(length);
+            let start = start.max(block_index_offset) - block_index_offset;
+            let end = end.max(block_index_offset) - block_index_offset;
+            let end = end.min(block_log.len());
+            for i in start..end {
+                if blocks.len() >= max_blocks {
+                    return;
+                }
+                let block = decode_icrc1_block(i, block_log.get(i).unwrap());
+                blocks.push(BlockWithId {
+                    id: Nat::from(block_index_offset + i),
+                    block: ICRC3Value::from(block),
+                });
+            }
+        }
+    });
+    let log_length = with_blocks(|block_log| block_log.len()) + block_index_offset;
+    GetBlocksResult {
+        log_length: Nat::from(log_length),
+        blocks,
+        archived_blocks: vec![],
+    }
+}
+
+/// The archive doesn't have any archives of its own.
+#[query]
+#[candid_method(query)]
+fn icrc3_get_archives(_arg: GetArchivesArgs) -> GetArchivesResult {
+    vec![]
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    ic_icrc1::blocks::supported_block_types()
+}
+
 #[query(hidden = true)]
 fn __get_candid_interface_tmp_hack() -> &'static str {
     include_str!(env!("ARCHIVE_DID_PATH"))
@@ -20,11 +20,18 @@ use icrc_ledger_types::icrc1::transfer::{Memo, TransferArg, TransferError};
 use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
 use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+use icrc_ledger_types::icrc21::{
+    errors::Icrc21Error,
+    requests::{ConsentMessageMetadata, ConsentMessageRequest, ConsentMessageSpec},
+    responses::{ConsentInfo, ConsentMessage},
+};
 use icrc_ledger_types::icrc3;
-use icrc_ledger_types::icrc3::archive::ArchiveInfo;
+use icrc_ledger_types::icrc3::archive::{ArchiveInfo, GetArchivesArgs, GetArchivesResult};
 use icrc_ledger_types::icrc3::blocks::BlockRange;
 use icrc_ledger_types::icrc3::blocks::GenericBlock as IcrcBlock;
-use icrc_ledger_types::icrc3::blocks::GetBlocksResponse;
+use icrc_ledger_types::icrc3::blocks::{
+    GetBlocksRequest, GetBlocksResponse, GetBlocksResult, ICRC3DataCertificate, SupportedBlockType,
+};
 use icrc_ledger_types::icrc3::transactions::GetTransactionsRequest;
 use icrc_ledger_types::icrc3::transactions::GetTransactionsResponse;
 use icrc_ledger_types::icrc3::transactions::Transaction as Tx;
@@ -355,6 +362,87 @@ fn get_archive_blocks(
     get_transactions_as(env, archive, start, length, "get_blocks".to_string())
 }
 
+fn icrc3_get_blocks(
+    env: &StateMachine,
+    canister: Principal,
+    args: Vec<GetBlocksRequest>,
+) -> GetBlocksResult {
+    let canister_id = CanisterId::unchecked_from_principal(canister.into());
+    Decode!(
+        &env.query(canister_id, "icrc3_get_blocks", Encode!(&args).unwrap())
+            .expect("failed to query icrc3_get_blocks")
+            .bytes(),
+        GetBlocksResult
+    )
+    .expect("failed to decode icrc3_get_blocks response")
+}
+
+fn icrc3_get_archives(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: Option<Principal>,
+) -> GetArchivesResult {
+    Decode!(
+        &env.query(
+            ledger,
+            "icrc3_get_archives",
+            Encode!(&GetArchivesArgs { from }).unwrap()
+        )
+        .expect("failed to query icrc3_get_archives")
+        .bytes(),
+        GetArchivesResult
+    )
+    .expect("failed to decode icrc3_get_archives response")
+}
+
+fn icrc3_supported_block_types(env: &StateMachine, canister: Principal) -> Vec<SupportedBlockType> {
+    let canister_id = CanisterId::unchecked_from_principal(canister.into());
+    Decode!(
+        &env.query(
+            canister_id,
+            "icrc3_supported_block_types",
+            Encode!().unwrap()
+        )
+        .expect("failed to query icrc3_supported_block_types")
+        .bytes(),
+        Vec<SupportedBlockType>
+    )
+    .expect("failed to decode icrc3_supported_block_types response")
+}
+
+fn icrc3_get_tip_certificate(
+    env: &StateMachine,
+    ledger: CanisterId,
+) -> Option<ICRC3DataCertificate> {
+    Decode!(
+        &env.query(ledger, "icrc3_get_tip_certificate", Encode!().unwrap())
+            .expect("failed to query icrc3_get_tip_certificate")
+            .bytes(),
+        Option<ICRC3DataCertificate>
+    )
+    .expect("failed to decode icrc3_get_tip_certificate response")
+}
+
+pub fn icrc21_consent_message(
+    env: &StateMachine,
+    ledger: CanisterId,
+    caller: Principal,
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    Decode!(
+        &env.execute_ingress_as(
+            PrincipalId(caller),
+            ledger,
+            "icrc21_canister_call_consent_message",
+            Encode!(&consent_msg_request).unwrap()
+        )
+        .expect("failed to get the consent message")
+        .bytes(),
+        Result<ConsentInfo, Icrc21Error>
+    )
+    .expect("failed to decode icrc21_canister_call_consent_message response")
+}
+
 fn get_phash(block: &IcrcBlock) -> Result<Option<Hash>, String> {
     match block {
         IcrcBlock::Map(map) => {
@@ -821,7 +909,7 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(standards, vec!["ICRC-1", "ICRC-2", "ICRC-3"]);
 }
 
 pub fn test_total_supply<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
@@ -1567,6 +1655,120 @@ where
     assert_eq!(0, missing_blocks_reply.archived_blocks.len());
 }
 
+pub fn test_icrc3_get_blocks<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1.0), 10_000_000)],
+    );
+
+    for i in 0..ARCHIVE_TRIGGER_THRESHOLD {
+        transfer(&env, canister_id, p1.0, p2.0, 10_000 + i * 10_000).expect("transfer failed");
+    }
+
+    env.run_until_completion(/*max_ticks=*/ 10);
+
+    let archive_canister_id = list_archives(&env, canister_id)[0].canister_id;
+    assert_eq!(
+        icrc3_get_archives(&env, canister_id, None),
+        vec![icrc3::archive::ICRC3ArchiveInfo {
+            canister_id: archive_canister_id,
+            start: Nat::from(0_u8),
+            end: Nat::from(NUM_BLOCKS_TO_ARCHIVE - 1),
+        }]
+    );
+    assert_eq!(
+        icrc3_get_archives(&env, canister_id, Some(archive_canister_id)),
+        vec![]
+    );
+
+    let resp = icrc3_get_blocks(
+        &env,
+        canister_id.get().0,
+        vec![GetBlocksRequest {
+            start: Nat::from(0_u8),
+            length: Nat::from(1_000_000_u64),
+        }],
+    );
+    assert_eq!(resp.log_length, Nat::from(ARCHIVE_TRIGGER_THRESHOLD + 1));
+    assert_eq!(
+        resp.blocks.len(),
+        (ARCHIVE_TRIGGER_THRESHOLD - NUM_BLOCKS_TO_ARCHIVE + 1) as usize
+    );
+    assert_eq!(resp.archived_blocks.len(), 1);
+    let archived = &resp.archived_blocks[0];
+    assert_eq!(archived.callback.canister_id, archive_canister_id);
+    assert_eq!(archived.callback.method, "icrc3_get_blocks");
+    assert_eq!(
+        archived.args,
+        vec![GetBlocksRequest {
+            start: Nat::from(0_u8),
+            length: Nat::from(NUM_BLOCKS_TO_ARCHIVE),
+        }]
+    );
+
+    let archived_resp = icrc3_get_blocks(&env, archive_canister_id, archived.args.clone());
+    assert_eq!(archived_resp.blocks.len(), NUM_BLOCKS_TO_ARCHIVE as usize);
+    assert!(archived_resp.archived_blocks.is_empty());
+
+    // Check that the block ids are contiguous and that the hash chain is
+    // correct.
+    let mut prev_hash = None;
+    for (expected_id, block) in archived_resp
+        .blocks
+        .into_iter()
+        .chain(resp.blocks.into_iter())
+        .enumerate()
+    {
+        assert_eq!(block.id, Nat::from(expected_id));
+        let block = IcrcBlock::from(block.block);
+        assert_eq!(
+            prev_hash,
+            get_phash(&block).expect("cannot get the hash of the previous block")
+        );
+        prev_hash = Some(block.hash());
+    }
+
+    // Query calls get a certificate of the tip of the chain.
+    let tip_certificate = icrc3_get_tip_certificate(&env, canister_id).expect("no tip certificate");
+    assert!(!tip_certificate.certificate.is_empty());
+
+    // The ICRC-3 hash of the last block agrees with the legacy block hash.
+    let legacy_blocks = get_blocks(&env, canister_id.get().0, ARCHIVE_TRIGGER_THRESHOLD, 1);
+    assert_eq!(Some(legacy_blocks.blocks[0].hash()), prev_hash);
+
+    // Check that requesting non-existing blocks does not crash the ledger.
+    let missing_blocks_reply = icrc3_get_blocks(
+        &env,
+        canister_id.get().0,
+        vec![GetBlocksRequest {
+            start: Nat::from(1_000_u64),
+            length: Nat::from(5_u8),
+        }],
+    );
+    assert!(missing_blocks_reply.blocks.is_empty());
+    assert!(missing_blocks_reply.archived_blocks.is_empty());
+
+    let supported_block_types = icrc3_supported_block_types(&env, canister_id.get().0);
+    assert_eq!(
+        supported_block_types
+            .iter()
+            .map(|t| t.block_type.as_str())
+            .collect::<Vec<_>>(),
+        vec!["1burn", "1mint", "1xfer", "2approve", "2xfer"]
+    );
+    assert_eq!(
+        icrc3_supported_block_types(&env, archive_canister_id),
+        supported_block_types
+    );
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 pub fn block_encoding_agrees_with_the_schema() {
     use std::path::PathBuf;
@@ -2227,6 +2429,95 @@ pub fn icrc1_test_block_transformation<T>(
     }
 }
 
+pub fn test_icrc21_standard<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let from = PrincipalId::new_user_test_id(1).0;
+    let to = Account::from(PrincipalId::new_user_test_id(2).0);
+    let (env, canister_id) = setup(ledger_wasm, encode_init_args, vec![]);
+
+    let consent_msg_request = |method: &str, arg: Vec<u8>| ConsentMessageRequest {
+        method: method.to_string(),
+        arg: serde_bytes::ByteBuf::from(arg),
+        user_preferences: ConsentMessageSpec {
+            metadata: ConsentMessageMetadata {
+                language: "en".to_string(),
+                utc_offset_minutes: None,
+            },
+            device_spec: None,
+        },
+    };
+
+    let transfer_arg = TransferArg {
+        from_subaccount: None,
+        to,
+        fee: None,
+        created_at_time: None,
+        memo: None,
+        amount: Nat::from(1_500_000_000_u64),
+    };
+    let consent_info = icrc21_consent_message(
+        &env,
+        canister_id,
+        from,
+        consent_msg_request("icrc1_transfer", Encode!(&transfer_arg).unwrap()),
+    )
+    .expect("failed to get the consent message for icrc1_transfer");
+    assert_eq!(consent_info.metadata.language, "en");
+    assert_eq!(
+        consent_info.consent_message,
+        ConsentMessage::GenericDisplayMessage(format!(
+            "# Approve the transfer of funds\n\n**Amount:**\n15 {symbol}\n\n**From:**\n{from}\n\n**To:**\n{to}\n\n**Fee:**\n0.0001 {symbol}",
+            symbol = TOKEN_SYMBOL,
+        ))
+    );
+
+    let approve_arg = default_approve_args(to, 100_000_000);
+    let consent_info = icrc21_consent_message(
+        &env,
+        canister_id,
+        from,
+        consent_msg_request("icrc2_approve", Encode!(&approve_arg).unwrap()),
+    )
+    .expect("failed to get the consent message for icrc2_approve");
+    match consent_info.consent_message {
+        ConsentMessage::GenericDisplayMessage(message) => {
+            assert!(
+                message.starts_with("# Authorize another address to withdraw from your account")
+            );
+            assert!(message.contains(&format!("1 {}", TOKEN_SYMBOL)));
+        }
+        other => panic!("unexpected consent message {:?}", other),
+    }
+
+    let transfer_from_arg = default_transfer_from_args(from, to.owner, 50_000);
+    let consent_info = icrc21_consent_message(
+        &env,
+        canister_id,
+        to.owner,
+        consent_msg_request("icrc2_transfer_from", Encode!(&transfer_from_arg).unwrap()),
+    )
+    .expect("failed to get the consent message for icrc2_transfer_from");
+    match consent_info.consent_message {
+        ConsentMessage::GenericDisplayMessage(message) => {
+            assert!(message.starts_with("# Transfer from a withdrawal account"));
+            assert!(message.contains(&format!("0.0005 {}", TOKEN_SYMBOL)));
+        }
+        other => panic!("unexpected consent message {:?}", other),
+    }
+
+    assert!(matches!(
+        icrc21_consent_message(
+            &env,
+            canister_id,
+            from,
+            consent_msg_request("icrc1_balance_of", Encode!(&to).unwrap()),
+        ),
+        Err(Icrc21Error::UnsupportedCanisterCall(_))
+    ));
+}
+
 pub fn default_approve_args(spender: impl Into<Account>, amount: u64) -> ApproveArgs {
     ApproveArgs {
         from_subaccount: None,
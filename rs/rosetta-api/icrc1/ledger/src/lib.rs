@@ -29,10 +29,14 @@ use ic_ledger_core::{
 use ic_ledger_hash_of::HashOf;
 use icrc_ledger_types::icrc1::account::Account;
 use icrc_ledger_types::icrc3::transactions::Transaction as Tx;
-use icrc_ledger_types::icrc3::{blocks::GetBlocksResponse, transactions::GetTransactionsResponse};
+use icrc_ledger_types::icrc3::{
+    blocks::{ArchivedBlocks, BlockWithId, GetBlocksRequest, GetBlocksResponse, GetBlocksResult},
+    transactions::GetTransactionsResponse,
+};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value,
-    icrc3::archive::{ArchivedRange, QueryBlockArchiveFn, QueryTxArchiveFn},
+    icrc::generic_value::ICRC3Value,
+    icrc3::archive::{ArchivedRange, ICRC3ArchiveFn, QueryBlockArchiveFn, QueryTxArchiveFn},
 };
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
@@ -626,25 +630,67 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         self.construct_hash_tree().digest().0
     }
 
+    /// Constructs the hash tree certifying the tip of the chain with the
+    /// legacy labels, i.e., `tip_hash` and a big-endian `last_block_index`.
+    ///
+    /// The ICRC-3 labels are pruned from the tree, see
+    /// [`Self::construct_icrc3_hash_tree`].
     pub fn construct_hash_tree(&self) -> MixedHashTree {
-        match self.blockchain().last_hash {
-            Some(hash) => {
-                let last_block_index = self.blockchain().chain_length().checked_sub(1).unwrap();
-                MixedHashTree::Fork(Box::new((
-                    MixedHashTree::Labeled(
-                        Label::from("last_block_index"),
-                        Box::new(MixedHashTree::Leaf(last_block_index.to_be_bytes().to_vec())),
-                    ),
-                    MixedHashTree::Labeled(
-                        Label::from("tip_hash"),
-                        Box::new(MixedHashTree::Leaf(hash.as_slice().to_vec())),
-                    ),
-                )))
-            }
+        match self.tip_hash_trees() {
+            Some((icrc3_tree, legacy_tree)) => MixedHashTree::Fork(Box::new((
+                MixedHashTree::Pruned(icrc3_tree.digest()),
+                legacy_tree,
+            ))),
             None => MixedHashTree::Empty,
         }
     }
 
+    /// Constructs the hash tree certifying the tip of the chain as required
+    /// by ICRC-3, i.e., with `last_block_hash` and a LEB128-encoded
+    /// `last_block_index`.
+    ///
+    /// The legacy labels are pruned from the tree, see
+    /// [`Self::construct_hash_tree`]. Both trees have the same root hash.
+    pub fn construct_icrc3_hash_tree(&self) -> MixedHashTree {
+        match self.tip_hash_trees() {
+            Some((icrc3_tree, legacy_tree)) => MixedHashTree::Fork(Box::new((
+                icrc3_tree,
+                MixedHashTree::Pruned(legacy_tree.digest()),
+            ))),
+            None => MixedHashTree::Empty,
+        }
+    }
+
+    /// Returns the ICRC-3 and the legacy hash trees certifying the tip of
+    /// the chain, or `None` if the chain is empty.
+    fn tip_hash_trees(&self) -> Option<(MixedHashTree, MixedHashTree)> {
+        let hash = self.blockchain().last_hash?;
+        let last_block_index = self.blockchain().chain_length().checked_sub(1).unwrap();
+        let mut last_block_index_leb128 = vec![];
+        leb128::write::unsigned(&mut last_block_index_leb128, last_block_index).unwrap();
+        let icrc3_tree = MixedHashTree::Fork(Box::new((
+            MixedHashTree::Labeled(
+                Label::from("last_block_hash"),
+                Box::new(MixedHashTree::Leaf(hash.as_slice().to_vec())),
+            ),
+            MixedHashTree::Labeled(
+                Label::from("last_block_index"),
+                Box::new(MixedHashTree::Leaf(last_block_index_leb128)),
+            ),
+        )));
+        let legacy_tree = MixedHashTree::Fork(Box::new((
+            MixedHashTree::Labeled(
+                Label::from("last_block_index"),
+                Box::new(MixedHashTree::Leaf(last_block_index.to_be_bytes().to_vec())),
+            ),
+            MixedHashTree::Labeled(
+                Label::from("tip_hash"),
+                Box::new(MixedHashTree::Leaf(hash.as_slice().to_vec())),
+            ),
+        )));
+        Some((icrc3_tree, legacy_tree))
+    }
+
     fn query_blocks<ArchiveFn, B>(
         &self,
         start: BlockIndex,
@@ -715,4 +761,52 @@ impl<Tokens: TokensType> Ledger<Tokens> {
             archived_blocks,
         }
     }
+
+    /// Returns the blocks in the specified ranges as ICRC-3 generic blocks.
+    pub fn icrc3_get_blocks(&self, args: Vec<GetBlocksRequest>) -> GetBlocksResult {
+        let mut blocks = vec![];
+        let mut archived_ranges: BTreeMap<Principal, Vec<GetBlocksRequest>> = BTreeMap::new();
+        for arg in args {
+            let (start, length) = match arg.as_start_and_length() {
+                Ok(start_and_length) => start_and_length,
+                Err(_) => continue,
+            };
+            let max_length = MAX_TRANSACTIONS_PER_REQUEST.saturating_sub(blocks.len());
+            if max_length == 0 {
+                break;
+            }
+            let length = max_length.min(usize::try_from(length).unwrap_or(usize::MAX));
+            let locations = block_locations(self, start, length);
+            for (id, block) in (locations.local_blocks.start..)
+                .zip(self.blockchain.block_slice(locations.local_blocks.clone()))
+            {
+                blocks.push(BlockWithId {
+                    id: Nat::from(id),
+                    block: ICRC3Value::from(encoded_block_to_generic_block(block)),
+                });
+            }
+            for (canister_id, slice) in locations.archived_blocks {
+                archived_ranges
+                    .entry(canister_id.get().0)
+                    .or_default()
+                    .push(GetBlocksRequest {
+                        start: Nat::from(slice.start),
+                        length: Nat::from(range_utils::range_len(&slice)),
+                    });
+            }
+        }
+        let archived_blocks = archived_ranges
+            .into_iter()
+            .map(|(canister_id, args)| ArchivedBlocks {
+                args,
+                callback: ICRC3ArchiveFn::new(canister_id, "icrc3_get_blocks"),
+            })
+            .collect();
+
+        GetBlocksResult {
+            log_length: Nat::from(self.blockchain.chain_length()),
+            blocks,
+            archived_blocks,
+        }
+    }
 }
@@ -17,12 +17,21 @@ use ic_ledger_core::tokens::Zero;
 use ic_ledger_core::{approvals::Approvals, timestamp::TimeStamp};
 use icrc_ledger_types::icrc1::transfer::Memo;
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc21::{
+    consent_message::{build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints, TokenInfo},
+    errors::Icrc21Error,
+    requests::ConsentMessageRequest,
+    responses::ConsentInfo,
+};
 use icrc_ledger_types::icrc3::blocks::DataCertificate;
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value,
     icrc3::{
-        archive::ArchiveInfo,
-        blocks::{GetBlocksRequest, GetBlocksResponse},
+        archive::{ArchiveInfo, GetArchivesArgs, GetArchivesResult, ICRC3ArchiveInfo},
+        blocks::{
+            GetBlocksArgs, GetBlocksRequest, GetBlocksResponse, GetBlocksResult,
+            ICRC3DataCertificate, SupportedBlockType,
+        },
         transactions::{GetTransactionsRequest, GetTransactionsResponse},
     },
 };
@@ -117,6 +126,10 @@ fn post_upgrade(args: Option<LedgerArgument>) {
             }
         }
     }
+
+    // The layout of the certified tree might have changed with the new
+    // version, so the certified data is recomputed.
+    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
 }
 
 fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::io::Result<()> {
@@ -475,6 +488,10 @@ fn supported_standards() -> Vec<StandardRecord> {
             name: "ICRC-2".to_string(),
             url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
         },
+        StandardRecord {
+            name: "ICRC-3".to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3".to_string(),
+        },
     ];
     standards
 }
@@ -509,6 +526,80 @@ fn get_data_certificate() -> DataCertificate {
     }
 }
 
+#[update]
+#[candid_method(update)]
+fn icrc21_canister_call_consent_message(
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    let token_info = Access::with_ledger(|ledger| TokenInfo {
+        symbol: ledger.token_symbol().to_string(),
+        decimals: ledger.decimals(),
+        fee: ledger.transfer_fee().into(),
+    });
+    build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+        consent_msg_request,
+        ic_cdk::api::caller(),
+        token_info,
+    )
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_archives(args: GetArchivesArgs) -> GetArchivesResult {
+    Access::with_ledger(|ledger| {
+        ledger
+            .blockchain()
+            .archive
+            .read()
+            .unwrap()
+            .as_ref()
+            .iter()
+            .flat_map(|archive| {
+                archive
+                    .index()
+                    .into_iter()
+                    .filter_map(|((start, end), canister_id)| {
+                        if let Some(from) = args.from {
+                            if canister_id.get().0 <= from {
+                                return None;
+                            }
+                        }
+                        Some(ICRC3ArchiveInfo {
+                            canister_id: canister_id.get().0,
+                            start: Nat::from(start),
+                            end: Nat::from(end),
+                        })
+                    })
+            })
+            .collect()
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_tip_certificate() -> Option<ICRC3DataCertificate> {
+    let certificate = ByteBuf::from(ic_cdk::api::data_certificate()?);
+    let hash_tree = Access::with_ledger(|ledger| ledger.construct_icrc3_hash_tree());
+    let mut tree_buf = vec![];
+    ciborium::ser::into_writer(&hash_tree, &mut tree_buf).unwrap();
+    Some(ICRC3DataCertificate {
+        certificate,
+        hash_tree: ByteBuf::from(tree_buf),
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    ic_icrc1::blocks::supported_block_types()
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(args: GetBlocksArgs) -> GetBlocksResult {
+    Access::with_ledger(|ledger| ledger.icrc3_get_blocks(args))
+}
+
 #[update]
 #[candid_method(update)]
 async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
@@ -1,9 +1,12 @@
 use crate::{InitArgs, Ledger};
 use ic_base_types::PrincipalId;
 use ic_canister_log::Sink;
+use ic_crypto_tree_hash::{LookupStatus, MixedHashTree};
 use ic_icrc1::{Operation, Transaction};
 use ic_ledger_canister_core::archive::ArchiveOptions;
-use ic_ledger_canister_core::ledger::{LedgerContext, LedgerTransaction, TxApplyError};
+use ic_ledger_canister_core::ledger::{
+    apply_transaction, LedgerContext, LedgerData, LedgerTransaction, TxApplyError,
+};
 use ic_ledger_core::approvals::{Allowance, Approvals};
 use ic_ledger_core::timestamp::TimeStamp;
 use ic_ledger_core::Tokens;
@@ -615,3 +618,58 @@ fn test_approval_burn_from() {
     assert_eq!(ctx.balances().account_balance(&spender), Tokens::ZERO);
     assert_eq!(ctx.balances().total_supply().get_e8s(), 90_000);
 }
+
+#[test]
+fn test_hash_trees_certify_tip_with_legacy_and_icrc3_labels() {
+    let now = ts(1);
+
+    let mut ctx = Ledger::from_init_args(DummyLogger, default_init_args(), now);
+    assert_eq!(ctx.construct_hash_tree(), MixedHashTree::Empty);
+    assert_eq!(ctx.construct_icrc3_hash_tree(), MixedHashTree::Empty);
+
+    for n in 1..=2 {
+        let tr = Transaction {
+            operation: Operation::Mint {
+                to: test_account_id(n),
+                amount: tokens(100_000),
+            },
+            created_at_time: None,
+            memo: None,
+        };
+        apply_transaction(&mut ctx, tr, now, Tokens::ZERO).unwrap();
+    }
+
+    let tip_hash = ctx.blockchain().last_hash.unwrap();
+    let leaf = |tree: &MixedHashTree, label: &[u8]| match tree.lookup(&[label]) {
+        LookupStatus::Found(MixedHashTree::Leaf(value)) => value.clone(),
+        status => panic!("unexpected lookup status {:?}", status),
+    };
+
+    let legacy_tree = ctx.construct_hash_tree();
+    assert_eq!(
+        leaf(&legacy_tree, b"tip_hash"),
+        tip_hash.as_slice().to_vec()
+    );
+    assert_eq!(
+        leaf(&legacy_tree, b"last_block_index"),
+        1_u64.to_be_bytes().to_vec()
+    );
+    assert_eq!(
+        legacy_tree.lookup(&[b"last_block_hash"]),
+        LookupStatus::Unknown
+    );
+
+    let icrc3_tree = ctx.construct_icrc3_hash_tree();
+    assert_eq!(
+        leaf(&icrc3_tree, b"last_block_hash"),
+        tip_hash.as_slice().to_vec()
+    );
+    let last_block_index = leaf(&icrc3_tree, b"last_block_index");
+    let mut reader = last_block_index.as_slice();
+    assert_eq!(leb128::read::unsigned(&mut reader).unwrap(), 1);
+    assert!(reader.is_empty());
+    assert_eq!(icrc3_tree.lookup(&[b"tip_hash"]), LookupStatus::Unknown);
+
+    assert_eq!(ctx.root_hash(), legacy_tree.digest().0);
+    assert_eq!(ctx.root_hash(), icrc3_tree.digest().0);
+}
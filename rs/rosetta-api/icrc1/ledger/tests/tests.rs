@@ -142,6 +142,16 @@ fn test_get_blocks() {
     ic_icrc1_ledger_sm_tests::test_get_blocks(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_icrc3_get_blocks() {
+    ic_icrc1_ledger_sm_tests::test_icrc3_get_blocks(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_icrc21_standard() {
+    ic_icrc1_ledger_sm_tests::test_icrc21_standard(ledger_wasm(), encode_init_args);
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 #[test]
 fn block_encoding_agrees_with_the_schema() {
@@ -1,8 +1,12 @@
 use ic_icrc_rosetta::common::types::Error;
-use ic_icrc_rosetta::construction_api::types::ConstructionMetadataRequestOptions;
+use ic_icrc_rosetta::construction_api::types::{
+    ConstructionMetadataRequestOptions, ConstructionPayloadsRequestMetadata,
+};
+use ic_rosetta_api::models::{SearchTransactionsRequest, SearchTransactionsResponse};
 use reqwest::{Client, Url};
 use rosetta_core::identifiers::*;
 use rosetta_core::objects::Operation;
+use rosetta_core::objects::PublicKey;
 use rosetta_core::objects::Signature;
 use rosetta_core::request_types::*;
 use rosetta_core::response_types::*;
@@ -113,6 +117,27 @@ impl RosettaClient {
         .await
     }
 
+    pub async fn account_balance(
+        &self,
+        network_identifier: NetworkIdentifier,
+        account_identifier: AccountIdentifier,
+        block_identifier: Option<PartialBlockIdentifier>,
+    ) -> Result<AccountBalanceResponse, Error> {
+        self.call_endpoint(
+            "/account/balance",
+            &AccountBalanceRequest::new(network_identifier, account_identifier, block_identifier),
+        )
+        .await
+    }
+
+    pub async fn search_transactions(
+        &self,
+        search_transactions_request: SearchTransactionsRequest,
+    ) -> Result<SearchTransactionsResponse, Error> {
+        self.call_endpoint("/search/transactions", &search_transactions_request)
+            .await
+    }
+
     pub async fn mempool(
         &self,
         network_identifier: NetworkIdentifier,
@@ -225,4 +250,44 @@ impl RosettaClient {
         )
         .await
     }
+
+    pub async fn construction_payloads(
+        &self,
+        network_identifier: NetworkIdentifier,
+        operations: Vec<Operation>,
+        metadata: ConstructionPayloadsRequestMetadata,
+        public_keys: Vec<PublicKey>,
+    ) -> Result<ConstructionPayloadsResponse, Error> {
+        self.call_endpoint(
+            "/construction/payloads",
+            &ConstructionPayloadsRequest {
+                network_identifier,
+                operations,
+                metadata: Some(
+                    metadata
+                        .try_into()
+                        .map_err(|err| Error::parsing_unsuccessful(&err))?,
+                ),
+                public_keys: Some(public_keys),
+            },
+        )
+        .await
+    }
+
+    pub async fn construction_parse(
+        &self,
+        network_identifier: NetworkIdentifier,
+        transaction: String,
+        signed: bool,
+    ) -> Result<ConstructionParseResponse, Error> {
+        self.call_endpoint(
+            "/construction/parse",
+            &ConstructionParseRequest {
+                network_identifier,
+                signed,
+                transaction,
+            },
+        )
+        .await
+    }
 }
@@ -104,6 +104,44 @@ impl StorageClient {
         storage_operations::get_transaction_at_idx(&open_connection, block_idx)
     }
 
+    // Gets all blocks that contain a transaction with the given transaction hash, ordered from the most recent to the oldest block. Returns [] if no such block exists.
+    pub fn get_blocks_by_transaction_hash(
+        &self,
+        hash: ByteBuf,
+    ) -> anyhow::Result<Vec<RosettaBlock>> {
+        let open_connection = self.storage_connection.lock().unwrap();
+        storage_operations::get_blocks_by_transaction_hash(&open_connection, hash)
+    }
+
+    // Gets the blocks up to and including max_block_idx in which the given account was involved, ordered from the most recent to the oldest block.
+    // Skips the first `offset` blocks and returns at most `limit` blocks.
+    pub fn get_blocks_by_account(
+        &self,
+        account: &Account,
+        max_block_idx: u64,
+        offset: u64,
+        limit: u64,
+    ) -> anyhow::Result<Vec<RosettaBlock>> {
+        let open_connection = self.storage_connection.lock().unwrap();
+        storage_operations::get_blocks_by_account(
+            &open_connection,
+            account,
+            max_block_idx,
+            offset,
+            limit,
+        )
+    }
+
+    // Returns the number of blocks up to and including max_block_idx in which the given account was involved.
+    pub fn get_block_count_by_account(
+        &self,
+        account: &Account,
+        max_block_idx: u64,
+    ) -> anyhow::Result<u64> {
+        let open_connection = self.storage_connection.lock().unwrap();
+        storage_operations::get_block_count_by_account(&open_connection, account, max_block_idx)
+    }
+
     pub fn read_metadata(&self) -> anyhow::Result<Vec<MetadataEntry>> {
         let open_connection = self.storage_connection.lock().unwrap();
         storage_operations::get_metadata(&open_connection)
@@ -180,6 +218,36 @@ impl StorageClient {
             "#,
             [],
         )?;
+        open_connection.execute(
+            r#"
+            CREATE INDEX IF NOT EXISTS tx_hash_index
+            ON transactions(tx_hash)
+            "#,
+            [],
+        )?;
+        // Searching transactions by account matches any of the accounts
+        // involved in a transaction.
+        open_connection.execute(
+            r#"
+            CREATE INDEX IF NOT EXISTS from_account_index
+            ON transactions(from_principal, from_subaccount)
+            "#,
+            [],
+        )?;
+        open_connection.execute(
+            r#"
+            CREATE INDEX IF NOT EXISTS to_account_index
+            ON transactions(to_principal, to_subaccount)
+            "#,
+            [],
+        )?;
+        open_connection.execute(
+            r#"
+            CREATE INDEX IF NOT EXISTS spender_account_index
+            ON transactions(spender_principal, spender_subaccount)
+            "#,
+            [],
+        )?;
 
         Ok(())
     }
@@ -333,6 +401,33 @@ mod tests {
         assert!(storage_client_persistent.is_ok());
     }
 
+    #[test]
+    fn test_transaction_search_indexes_are_created() {
+        let storage_client_memory = StorageClient::new_in_memory().unwrap();
+        let open_connection = storage_client_memory.storage_connection.lock().unwrap();
+        let mut stmt = open_connection
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'transactions' ORDER BY name")
+            .unwrap();
+        let indexes: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for index in [
+            "from_account_index",
+            "spender_account_index",
+            "to_account_index",
+            "tx_hash_index",
+        ] {
+            assert!(
+                indexes.contains(&index.to_string()),
+                "missing index {} in {:?}",
+                index,
+                indexes
+            );
+        }
+    }
+
     proptest! {
           #[test]
           fn test_read_and_write_blocks_u64(blockchain in prop::collection::vec(blocks_strategy::<U64>(arb_amount()),0..5)){
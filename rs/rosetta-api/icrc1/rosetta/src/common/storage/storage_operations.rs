@@ -493,6 +493,78 @@ pub fn get_transactions_by_hash(
     read_transactions(&mut stmt, params![hash.as_slice().to_vec()])
 }
 
+// Returns all RosettaBlocks that contain a transaction with the given transaction hash.
+// Returns an Error if the query fails.
+pub fn get_blocks_by_transaction_hash(
+    connection: &Connection,
+    hash: ByteBuf,
+) -> anyhow::Result<Vec<RosettaBlock>> {
+    let mut stmt = connection.prepare(
+        "SELECT blocks.idx,blocks.serialized_block FROM blocks JOIN transactions ON blocks.idx = transactions.block_idx WHERE transactions.tx_hash = ?1 ORDER BY blocks.idx DESC",
+    )?;
+    read_blocks(&mut stmt, params![hash.as_slice().to_vec()])
+}
+
+// An account is involved in a block if it is the sender, receiver or spender of the transaction or if its balance changed at that block (e.g. as fee collector).
+// Subaccounts are compared by their effective value, as the transactions table stores the default subaccount as NULL.
+const BLOCKS_BY_ACCOUNT_FILTER: &str = "blocks.idx <= :max_block_idx AND (\
+     (transactions.from_principal = :principal AND COALESCE(transactions.from_subaccount, :default_subaccount) = :subaccount) \
+     OR (transactions.to_principal = :principal AND COALESCE(transactions.to_subaccount, :default_subaccount) = :subaccount) \
+     OR (transactions.spender_principal = :principal AND COALESCE(transactions.spender_subaccount, :default_subaccount) = :subaccount) \
+     OR blocks.idx IN (SELECT block_idx FROM account_balances WHERE principal = :principal AND subaccount = :subaccount))";
+
+// Returns the RosettaBlocks up to and including max_block_idx that involve the given account, ordered from the most recent to the oldest block.
+// Skips the first `offset` blocks and returns at most `limit` blocks.
+pub fn get_blocks_by_account(
+    connection: &Connection,
+    account: &Account,
+    max_block_idx: u64,
+    offset: u64,
+    limit: u64,
+) -> anyhow::Result<Vec<RosettaBlock>> {
+    let command = format!(
+        "SELECT blocks.idx,blocks.serialized_block FROM blocks JOIN transactions ON blocks.idx = transactions.block_idx WHERE {} ORDER BY blocks.idx DESC LIMIT :limit OFFSET :offset",
+        BLOCKS_BY_ACCOUNT_FILTER
+    );
+    let mut stmt = connection.prepare_cached(&command)?;
+    read_blocks(
+        &mut stmt,
+        named_params! {
+            ":max_block_idx": max_block_idx.min(i64::MAX as u64),
+            ":principal": account.owner.as_slice(),
+            ":subaccount": account.effective_subaccount().as_slice(),
+            ":default_subaccount": [0u8; 32].as_slice(),
+            ":limit": limit.min(i64::MAX as u64),
+            ":offset": offset.min(i64::MAX as u64),
+        },
+    )
+    .with_context(|| format!("Unable to fetch blocks of account {}", account))
+}
+
+// Returns the number of blocks up to and including max_block_idx that involve the given account.
+pub fn get_block_count_by_account(
+    connection: &Connection,
+    account: &Account,
+    max_block_idx: u64,
+) -> anyhow::Result<u64> {
+    let command = format!(
+        "SELECT COUNT(*) FROM blocks JOIN transactions ON blocks.idx = transactions.block_idx WHERE {}",
+        BLOCKS_BY_ACCOUNT_FILTER
+    );
+    connection
+        .prepare_cached(&command)?
+        .query_row(
+            named_params! {
+                ":max_block_idx": max_block_idx.min(i64::MAX as u64),
+                ":principal": account.owner.as_slice(),
+                ":subaccount": account.effective_subaccount().as_slice(),
+                ":default_subaccount": [0u8; 32].as_slice(),
+            },
+            |row| row.get(0),
+        )
+        .with_context(|| format!("Unable to count blocks of account {}", account))
+}
+
 pub fn get_account_balance_at_highest_block_idx(
     connection: &Connection,
     account: &Account,
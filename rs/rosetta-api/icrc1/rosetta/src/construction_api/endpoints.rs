@@ -85,3 +85,33 @@ pub async fn construction_combine(
         request.signatures,
     )?))
 }
+
+pub async fn construction_payloads(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ConstructionPayloadsRequest>,
+) -> Result<Json<ConstructionPayloadsResponse>> {
+    verify_network_id(&request.network_identifier, &state)
+        .map_err(|err| Error::invalid_network_id(&err))?;
+    Ok(Json(services::construction_payloads(
+        request.operations,
+        request
+            .metadata
+            .try_into()
+            .map_err(|err: String| Error::parsing_unsuccessful(&err))?,
+        &state.ledger_id,
+        request.public_keys.unwrap_or_default(),
+    )?))
+}
+
+pub async fn construction_parse(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ConstructionParseRequest>,
+) -> Result<Json<ConstructionParseResponse>> {
+    verify_network_id(&request.network_identifier, &state)
+        .map_err(|err| Error::invalid_network_id(&err))?;
+    Ok(Json(services::construction_parse(
+        request.transaction,
+        request.signed,
+        state.metadata.clone().into(),
+    )?))
+}
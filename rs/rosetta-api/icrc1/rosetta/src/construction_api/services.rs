@@ -1,12 +1,16 @@
-use super::types::{ConstructionMetadataRequestOptions, SignedTransaction, UnsignedTransaction};
+use super::types::{
+    ConstructionMetadataRequestOptions, ConstructionPayloadsRequestMetadata, SignedTransaction,
+    UnsignedTransaction,
+};
 use super::utils::{
-    handle_construction_combine, handle_construction_hash, handle_construction_submit,
+    handle_construction_combine, handle_construction_hash, handle_construction_parse,
+    handle_construction_payloads, handle_construction_submit,
 };
 use crate::common::types::Error;
 use ic_base_types::{CanisterId, PrincipalId};
 use icrc_ledger_agent::{CallMode, Icrc1Agent};
 use icrc_ledger_types::icrc1::account::Account;
-use rosetta_core::objects::{Amount, Currency, Signature};
+use rosetta_core::objects::{Amount, Currency, Operation, Signature};
 use rosetta_core::response_types::*;
 use rosetta_core::{
     convert::principal_id_from_public_key, objects::PublicKey,
@@ -88,10 +92,33 @@ pub fn construction_combine(
         .map_err(|err| Error::processing_construction_failed(&err))
 }
 
+pub fn construction_payloads(
+    operations: Vec<Operation>,
+    metadata: ConstructionPayloadsRequestMetadata,
+    ledger_id: &CanisterId,
+    public_keys: Vec<PublicKey>,
+) -> Result<ConstructionPayloadsResponse, Error> {
+    handle_construction_payloads(operations, metadata, &ledger_id.get().0, public_keys)
+        .map_err(|err| Error::processing_construction_failed(&err))
+}
+
+pub fn construction_parse(
+    transaction: String,
+    signed: bool,
+    currency: Currency,
+) -> Result<ConstructionParseResponse, Error> {
+    handle_construction_parse(transaction, signed, currency)
+        .map_err(|err| Error::processing_construction_failed(&err))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::types::TransactionMetadata;
+    use crate::common::utils::utils::icrc1_operation_to_rosetta_core_operation;
     use ic_canister_client_sender::{Ed25519KeyPair, Secp256k1KeyPair};
+    use ic_icrc1_test_utils::account_strategy;
+    use ic_icrc1_tokens_u64::U64;
     use proptest::prelude::any;
     use proptest::proptest;
     use rosetta_core::models::RosettaSupportedKeyPair;
@@ -115,7 +142,106 @@ mod tests {
         );
     }
 
+    fn call_construction_payloads_and_parse<T: RosettaSupportedKeyPair>(
+        key_pair: &T,
+        to: Account,
+        amount: u64,
+    ) {
+        let currency = Currency::default();
+        let ledger_id = CanisterId::from_u64(1);
+        let public_key = ic_rosetta_test_utils::to_public_key(key_pair);
+        let from: Account = key_pair.generate_principal_id().unwrap().0.into();
+        let operation = icrc1_operation_to_rosetta_core_operation(
+            ic_icrc1::Operation::Transfer {
+                from,
+                to,
+                spender: None,
+                amount: U64::new(amount).into(),
+                fee: None,
+            },
+            currency.clone(),
+        )
+        .unwrap();
+        let metadata = ConstructionPayloadsRequestMetadata {
+            memo: Some(vec![1, 2, 3].into()),
+            created_at_time: Some(1_000_000),
+            ingress_expiry: None,
+        };
+
+        // The payloads request fails if the public key of the caller is missing
+        assert!(construction_payloads(
+            vec![operation.clone()],
+            metadata.clone(),
+            &ledger_id,
+            vec![]
+        )
+        .is_err());
+
+        let payloads_response = construction_payloads(
+            vec![operation.clone()],
+            metadata.clone(),
+            &ledger_id,
+            vec![public_key.clone()],
+        )
+        .unwrap();
+        assert_eq!(payloads_response.payloads.len(), 2);
+
+        // Parsing the unsigned transaction returns the operations given to /construction/payloads
+        let parse_response = construction_parse(
+            payloads_response.unsigned_transaction.clone(),
+            false,
+            currency.clone(),
+        )
+        .unwrap();
+        assert_eq!(parse_response.operations, vec![operation.clone()]);
+        assert_eq!(parse_response.account_identifier_signers, None);
+        assert_eq!(
+            TransactionMetadata::try_from(parse_response.metadata).unwrap(),
+            TransactionMetadata {
+                memo: metadata.memo.clone(),
+                created_at_time: metadata.created_at_time,
+            }
+        );
+
+        // The payloads can be signed and combined into a signed transaction
+        let signatures = payloads_response
+            .payloads
+            .into_iter()
+            .map(|signing_payload| Signature {
+                hex_bytes: hex::encode(
+                    key_pair.sign(&hex::decode(&signing_payload.hex_bytes).unwrap()),
+                ),
+                signature_type: signing_payload.signature_type.unwrap(),
+                signing_payload,
+                public_key: public_key.clone(),
+            })
+            .collect();
+        let combine_response =
+            construction_combine(payloads_response.unsigned_transaction, signatures).unwrap();
+
+        // Parsing the signed transaction returns the operations and the signer
+        let parse_response =
+            construction_parse(combine_response.signed_transaction, true, currency).unwrap();
+        assert_eq!(parse_response.operations, vec![operation]);
+        assert_eq!(
+            parse_response.account_identifier_signers,
+            Some(vec![from.into()])
+        );
+    }
+
     proptest! {
+        #[test]
+        fn test_construction_payloads_and_parse_ed(seed in any::<u64>(), to in account_strategy(), amount in any::<u64>()) {
+            let key_pair = Ed25519KeyPair::generate_from_u64(seed);
+            call_construction_payloads_and_parse(&key_pair, to, amount);
+        }
+
+        #[test]
+        fn test_construction_payloads_and_parse_secp(seed in any::<u64>(), to in account_strategy(), amount in any::<u64>()) {
+            let key_pair = Secp256k1KeyPair::generate_from_u64(seed);
+            call_construction_payloads_and_parse(&key_pair, to, amount);
+        }
+
         #[test]
         fn test_construction_derive_ed(seed in any::<u64>()) {
             let key_pair = Ed25519KeyPair::generate_from_u64(seed);
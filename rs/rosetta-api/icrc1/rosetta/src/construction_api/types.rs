@@ -5,6 +5,7 @@ use ic_agent::agent::EnvelopeContent;
 use rosetta_core::objects::*;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_bytes::ByteBuf;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -63,6 +64,44 @@ impl<'a> FromStr for SignedTransaction<'a> {
     }
 }
 
+// The metadata that can be passed to the /construction/payloads endpoint.
+// The memo and created_at_time are set on the icrc1 ledger transaction, the ingress_expiry (in nanoseconds since the UNIX epoch) determines until when the signed envelopes can be submitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConstructionPayloadsRequestMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<ByteBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingress_expiry: Option<u64>,
+}
+
+impl TryFrom<ConstructionPayloadsRequestMetadata> for ObjectMap {
+    type Error = anyhow::Error;
+    fn try_from(d: ConstructionPayloadsRequestMetadata) -> Result<ObjectMap, Self::Error> {
+        match serde_json::to_value(d) {
+            Ok(v) => match v {
+                serde_json::Value::Object(ob) => Ok(ob),
+                _ => anyhow::bail!("Could not convert ConstructionPayloadsRequestMetadata to ObjectMap. Expected type Object but received: {:?}",v)
+            },Err(err) => anyhow::bail!("Could not convert ConstructionPayloadsRequestMetadata to ObjectMap: {:?}",err),
+        }
+    }
+}
+
+impl TryFrom<Option<ObjectMap>> for ConstructionPayloadsRequestMetadata {
+    type Error = String;
+    fn try_from(o: Option<ObjectMap>) -> Result<Self, Self::Error> {
+        serde_json::from_value(serde_json::Value::Object(o.unwrap_or_default())).map_err(|e| {
+            format!(
+                "Could not parse ConstructionPayloadsRequestMetadata from JSON object: {}",
+                e
+            )
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CanisterMethodName {
     Icrc2Approve,
@@ -84,6 +123,16 @@ impl CanisterMethodName {
     }
 }
 
+impl std::fmt::Display for CanisterMethodName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Icrc2Approve => write!(f, "icrc2_approve"),
+            Self::Icrc2TransferFrom => write!(f, "icrc2_transfer_from"),
+            Self::Icrc1Transfer => write!(f, "icrc1_transfer"),
+        }
+    }
+}
+
 impl FromStr for CanisterMethodName {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
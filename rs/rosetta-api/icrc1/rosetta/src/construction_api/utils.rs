@@ -1,8 +1,15 @@
-use super::types::{CanisterMethodName, EnvelopePair, SignedTransaction, UnsignedTransaction};
+use super::types::{
+    CanisterMethodName, ConstructionPayloadsRequestMetadata, EnvelopePair, SignedTransaction,
+    UnsignedTransaction,
+};
 use crate::common::storage::types::RosettaToken;
+use crate::common::types::TransactionMetadata;
+use crate::common::utils::utils::{
+    icrc1_operation_to_rosetta_core_operation, rosetta_core_operation_to_icrc1_operation,
+};
 use anyhow::anyhow;
 use anyhow::{bail, Context};
-use candid::{Decode, Nat, Principal};
+use candid::{Decode, Encode, Nat, Principal};
 use ic_agent::agent::{Envelope, EnvelopeContent};
 use ic_ledger_canister_core::ledger::LedgerTransaction;
 use icrc_ledger_agent::Icrc1Agent;
@@ -10,15 +17,24 @@ use icrc_ledger_types::icrc1::account::Account;
 use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
 use icrc_ledger_types::icrc2::approve::ApproveArgs;
 use icrc_ledger_types::icrc2::transfer_from::TransferFromArgs;
-use rosetta_core::objects::Signature;
+use rosetta_core::convert::principal_id_from_public_key;
+use rosetta_core::objects::{
+    Currency, CurveType, Operation, PublicKey, Signature, SignatureType, SigningPayload,
+};
 use rosetta_core::response_types::ConstructionCombineResponse;
 use rosetta_core::response_types::ConstructionHashResponse;
+use rosetta_core::response_types::{ConstructionParseResponse, ConstructionPayloadsResponse};
 use rosetta_core::{
     identifiers::TransactionIdentifier, response_types::ConstructionSubmitResponse,
 };
 use std::borrow::Cow;
 use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// The default time window in which the envelopes created by /construction/payloads can be submitted to the IC
+const DEFAULT_INGRESS_EXPIRY: Duration = Duration::from_secs(4 * 60);
 
 fn build_serialized_bytes<T: serde::Serialize + std::fmt::Debug>(
     object: &T,
@@ -207,6 +223,82 @@ pub fn build_icrc1_transaction_from_canister_method_args(
     })
 }
 
+// Converts an icrc1::Transaction into the name of the canister method, the caller and the candid encoded arguments that are needed to execute the transaction on the icrc1 ledger
+// This is the inverse of build_icrc1_transaction_from_canister_method_args
+// Fails if the transaction cannot be created by calling a method of the icrc1 ledger
+pub fn build_canister_method_args_from_icrc1_transaction(
+    transaction: ic_icrc1::Transaction<RosettaToken>,
+) -> anyhow::Result<(CanisterMethodName, Principal, Vec<u8>)> {
+    let ic_icrc1::Transaction {
+        operation,
+        memo,
+        created_at_time,
+    } = transaction;
+    Ok(match operation {
+        ic_icrc1::Operation::Transfer {
+            from,
+            to,
+            spender: None,
+            amount,
+            fee,
+        } => (
+            CanisterMethodName::Icrc1Transfer,
+            from.owner,
+            Encode!(&TransferArg {
+                from_subaccount: from.subaccount,
+                to,
+                fee: fee.map(Nat::from),
+                created_at_time,
+                memo,
+                amount: amount.into(),
+            })?,
+        ),
+        ic_icrc1::Operation::Transfer {
+            from,
+            to,
+            spender: Some(spender),
+            amount,
+            fee,
+        } => (
+            CanisterMethodName::Icrc2TransferFrom,
+            spender.owner,
+            Encode!(&TransferFromArgs {
+                spender_subaccount: spender.subaccount,
+                from,
+                to,
+                amount: amount.into(),
+                fee: fee.map(Nat::from),
+                memo,
+                created_at_time,
+            })?,
+        ),
+        ic_icrc1::Operation::Approve {
+            from,
+            spender,
+            amount,
+            expected_allowance,
+            expires_at,
+            fee,
+        } => (
+            CanisterMethodName::Icrc2Approve,
+            from.owner,
+            Encode!(&ApproveArgs {
+                from_subaccount: from.subaccount,
+                spender,
+                amount: amount.into(),
+                expected_allowance: expected_allowance.map(Nat::from),
+                expires_at,
+                fee: fee.map(Nat::from),
+                memo,
+                created_at_time,
+            })?,
+        ),
+        ic_icrc1::Operation::Mint { .. } | ic_icrc1::Operation::Burn { .. } => bail!(
+            "Mint and Burn operations have to be submitted as a Transfer from or to the minting account"
+        ),
+    })
+}
+
 pub fn build_transaction_hash_from_envelope_content(
     envelope_content: &EnvelopeContent,
 ) -> anyhow::Result<String> {
@@ -315,3 +407,136 @@ pub fn handle_construction_combine(
         signed_transaction: hex::encode(serde_cbor::to_vec(&SignedTransaction { envelope_pairs })?),
     })
 }
+
+pub fn handle_construction_payloads(
+    operations: Vec<Operation>,
+    metadata: ConstructionPayloadsRequestMetadata,
+    ledger_id: &Principal,
+    public_keys: Vec<PublicKey>,
+) -> anyhow::Result<ConstructionPayloadsResponse> {
+    // Every icrc1 ledger transaction is a single canister call, so a payloads
+    // request maps to exactly one operation.
+    if operations.len() != 1 {
+        bail!(
+            "Exactly one operation is supported per payloads request. Found {} operations.",
+            operations.len()
+        );
+    }
+
+    let icrc1_transaction = ic_icrc1::Transaction {
+        operation: rosetta_core_operation_to_icrc1_operation(operations[0].clone())?,
+        memo: metadata.memo.map(|memo| memo.into()),
+        created_at_time: metadata.created_at_time,
+    };
+    let (canister_method_name, caller, arg) =
+        build_canister_method_args_from_icrc1_transaction(icrc1_transaction)?;
+
+    // The caller of the ledger has to sign the envelopes, so one of the provided public keys has to belong to the caller
+    let public_key = public_keys
+        .into_iter()
+        .find(|public_key| {
+            principal_id_from_public_key(public_key)
+                .map(|principal_id| principal_id.0 == caller)
+                .unwrap_or(false)
+        })
+        .with_context(|| format!("No public key provided for the caller {}", caller))?;
+    let signature_type = match public_key.curve_type {
+        CurveType::Edwards25519 => SignatureType::Ed25519,
+        CurveType::Secp256K1 => SignatureType::Ecdsa,
+        _ => bail!("Curve type {:?} is not supported", public_key.curve_type),
+    };
+
+    let ingress_expiry = match metadata.ingress_expiry {
+        Some(ingress_expiry) => ingress_expiry,
+        None => u64::try_from(
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .saturating_add(DEFAULT_INGRESS_EXPIRY)
+                .as_nanos(),
+        )?,
+    };
+
+    let call_envelope_content = EnvelopeContent::Call {
+        nonce: None,
+        ingress_expiry,
+        sender: caller,
+        canister_id: *ledger_id,
+        method_name: canister_method_name.to_string(),
+        arg,
+    };
+    let read_state_envelope_content = EnvelopeContent::ReadState {
+        ingress_expiry,
+        sender: caller,
+        paths: vec![vec![
+            "request_status".into(),
+            call_envelope_content.to_request_id().to_vec().into(),
+        ]],
+    };
+
+    // The order of the payloads has to match the order of the envelope contents, as /construction/combine expects the signatures in that order
+    let payloads = [&call_envelope_content, &read_state_envelope_content]
+        .into_iter()
+        .map(|envelope_content| SigningPayload {
+            address: None,
+            account_identifier: Some(Account::from(caller).into()),
+            hex_bytes: build_signable_request_id_from_envelope_content(envelope_content),
+            signature_type: Some(signature_type),
+        })
+        .collect();
+
+    Ok(ConstructionPayloadsResponse::new(
+        UnsignedTransaction {
+            envelope_contents: vec![call_envelope_content, read_state_envelope_content],
+        }
+        .to_string(),
+        payloads,
+    ))
+}
+
+pub fn handle_construction_parse(
+    transaction: String,
+    signed: bool,
+    currency: Currency,
+) -> anyhow::Result<ConstructionParseResponse> {
+    // Both the signed and the unsigned transaction contain the content of the call to the icrc1 ledger
+    let call_envelope_content = if signed {
+        SignedTransaction::from_str(&transaction)?
+            .envelope_pairs
+            .first()
+            .context("No valid envelopes found in the signed transaction")?
+            .call_envelope
+            .content
+            .clone()
+            .into_owned()
+    } else {
+        UnsignedTransaction::from_str(&transaction)?
+            .envelope_contents
+            .into_iter()
+            .find(|envelope_content| matches!(envelope_content, EnvelopeContent::Call { .. }))
+            .context("No Call envelope content found in the unsigned transaction")?
+    };
+
+    let canister_method_name =
+        CanisterMethodName::new_from_envelope_content(&call_envelope_content)?;
+    let (caller, arg) = match call_envelope_content {
+        EnvelopeContent::Call { sender, arg, .. } => (sender, arg),
+        _ => bail!(
+            "Wrong EnvelopeContent type, expected EnvelopeContent::Call, got {:?}",
+            call_envelope_content
+        ),
+    };
+    let icrc1_transaction =
+        build_icrc1_transaction_from_canister_method_args(&canister_method_name, &caller, arg)?;
+
+    let metadata: TransactionMetadata = icrc1_transaction.clone().into();
+    Ok(ConstructionParseResponse {
+        operations: vec![icrc1_operation_to_rosetta_core_operation(
+            icrc1_transaction.operation,
+            currency,
+        )?],
+        account_identifier_signers: signed.then(|| vec![Account::from(caller).into()]),
+        metadata: (!metadata.is_empty())
+            .then(|| metadata.try_into())
+            .transpose()?,
+    })
+}
@@ -4,7 +4,9 @@ use crate::{
     AppState,
 };
 use axum::{extract::State, http::StatusCode, response::Result, Json};
-use ic_rosetta_api::models::MempoolResponse;
+use ic_rosetta_api::models::{
+    MempoolResponse, SearchTransactionsRequest, SearchTransactionsResponse,
+};
 use rosetta_core::{request_types::*, response_types::*};
 use std::sync::Arc;
 
@@ -85,3 +87,30 @@ pub async fn mempool_transaction(
         .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
     Err(Error::mempool_transaction_missing().into())
 }
+
+pub async fn account_balance(
+    State(state): State<Arc<AppState>>,
+    request: Json<AccountBalanceRequest>,
+) -> Result<Json<AccountBalanceResponse>> {
+    verify_network_id(&request.network_identifier, &state)
+        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    Ok(Json(services::account_balance(
+        state.storage.clone(),
+        request.account_identifier.clone(),
+        request.block_identifier.clone(),
+        state.metadata.clone(),
+    )?))
+}
+
+pub async fn search_transactions(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SearchTransactionsRequest>,
+) -> Result<Json<SearchTransactionsResponse>> {
+    verify_network_id(&request.network_identifier, &state)
+        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    Ok(Json(services::search_transactions(
+        state.storage.clone(),
+        request,
+        state.metadata.clone(),
+    )?))
+}
@@ -1,7 +1,7 @@
 use crate::{
     common::{
         constants::{NODE_VERSION, ROSETTA_VERSION},
-        storage::storage_client::StorageClient,
+        storage::{storage_client::StorageClient, types::Tokens},
         types::Error,
         utils::utils::{
             convert_timestamp_to_millis, get_rosetta_block_from_block_identifier,
@@ -13,10 +13,19 @@ use crate::{
     Metadata,
 };
 use candid::Principal;
+use ic_rosetta_api::models::{
+    BlockTransaction, Operator, SearchTransactionsRequest, SearchTransactionsResponse,
+};
 use ic_rosetta_api::DEFAULT_BLOCKCHAIN;
+use icrc_ledger_types::icrc1::account::Account;
+use num_traits::Zero;
 use rosetta_core::{identifiers::*, miscellaneous::Version, objects::*, response_types::*};
+use serde_bytes::ByteBuf;
 use std::sync::Arc;
 
+// The maximum number of transactions returned by a single /search/transactions request
+const MAX_SEARCH_LIMIT: u64 = 10_000;
+
 pub fn network_list(ledger_id: &Principal) -> NetworkListResponse {
     NetworkListResponse {
         network_identifiers: vec![NetworkIdentifier::new(
@@ -128,11 +137,187 @@ pub fn block(
     )))
 }
 
+pub fn account_balance(
+    storage_client: Arc<StorageClient>,
+    account_identifier: AccountIdentifier,
+    partial_block_identifier: Option<PartialBlockIdentifier>,
+    metadata: Metadata,
+) -> Result<AccountBalanceResponse, Error> {
+    let account: Account = account_identifier
+        .try_into()
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    // If no block is specified the balance at the most recent block is returned
+    let rosetta_block = match partial_block_identifier {
+        Some(partial_block_identifier)
+            if partial_block_identifier.index.is_some()
+                || partial_block_identifier.hash.is_some() =>
+        {
+            get_rosetta_block_from_partial_block_identifier(
+                partial_block_identifier,
+                storage_client.clone(),
+            )
+            .map_err(|err| Error::invalid_block_identifier(&err))?
+        }
+        _ => storage_client
+            .get_block_with_highest_block_idx()
+            .map_err(|e| Error::unable_to_find_block(&e))?
+            .ok_or_else(|| Error::unable_to_find_block(&"Current block not found".to_owned()))?,
+    };
+
+    // Accounts that have not received any tokens up to the given block have a balance of zero
+    let balance = storage_client
+        .get_account_balance_at_block_idx(&account, rosetta_block.index)
+        .map_err(|err| Error::request_processing_error(&err))?
+        .unwrap_or_else(Tokens::zero);
+
+    Ok(AccountBalanceResponse::new(
+        rosetta_block.get_block_identifier(),
+        vec![Amount::new(balance.to_string(), metadata.into())],
+    ))
+}
+
+// Checks whether the account is the sender, receiver, spender or approver of the operation
+fn operation_involves_account(operation: &ic_icrc1::Operation<Tokens>, account: &Account) -> bool {
+    match operation {
+        ic_icrc1::Operation::Mint { to, .. } => to == account,
+        ic_icrc1::Operation::Burn { from, spender, .. } => {
+            from == account || spender.as_ref() == Some(account)
+        }
+        ic_icrc1::Operation::Transfer {
+            from, to, spender, ..
+        } => from == account || to == account || spender.as_ref() == Some(account),
+        ic_icrc1::Operation::Approve { from, spender, .. } => from == account || spender == account,
+    }
+}
+
+pub fn search_transactions(
+    storage_client: Arc<StorageClient>,
+    request: SearchTransactionsRequest,
+    metadata: Metadata,
+) -> Result<SearchTransactionsResponse, Error> {
+    if let Some(Operator::Or) = request.operator {
+        return Err(Error::request_processing_error(
+            &"Operator OR not supported",
+        ));
+    }
+
+    if request.coin_identifier.is_some()
+        || request.currency.is_some()
+        || request.status.is_some()
+        || request._type.is_some()
+        || request.address.is_some()
+        || request.success.is_some()
+    {
+        return Err(Error::request_processing_error(
+            &"Only searching by transaction_identifier and account_identifier is supported",
+        ));
+    }
+
+    let max_block = request
+        .max_block
+        .map(u64::try_from)
+        .transpose()
+        .map_err(|err| Error::request_processing_error(&format!("Invalid max_block: {}", err)))?
+        .unwrap_or(u64::MAX);
+    let offset = request
+        .offset
+        .map(u64::try_from)
+        .transpose()
+        .map_err(|err| Error::request_processing_error(&format!("Invalid offset: {}", err)))?
+        .unwrap_or(0);
+    let limit = request
+        .limit
+        .map(u64::try_from)
+        .transpose()
+        .map_err(|err| Error::request_processing_error(&format!("Invalid limit: {}", err)))?
+        .unwrap_or(MAX_SEARCH_LIMIT)
+        .min(MAX_SEARCH_LIMIT);
+
+    let account: Option<Account> = request
+        .account_identifier
+        .map(|account_identifier| account_identifier.try_into())
+        .transpose()
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    let (rosetta_blocks, total_count) = match (request.transaction_identifier, account) {
+        (Some(transaction_identifier), account) => {
+            let transaction_hash = ByteBuf::try_from(transaction_identifier)
+                .map_err(|err| Error::parsing_unsuccessful(&err))?;
+            // Transaction hashes are not unique, but there are only ever a handful of blocks with the same transaction hash.
+            // Thus, the remaining filters can be applied in memory.
+            let rosetta_blocks = storage_client
+                .get_blocks_by_transaction_hash(transaction_hash)
+                .map_err(|err| Error::unable_to_find_block(&err))?
+                .into_iter()
+                .filter(|block| block.index <= max_block)
+                .map(|block| {
+                    Ok(match account {
+                        Some(account) => operation_involves_account(
+                            &block.get_transaction()?.operation,
+                            &account,
+                        )
+                        .then_some(block),
+                        None => Some(block),
+                    })
+                })
+                .filter_map(|block| block.transpose())
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map_err(|err| Error::parsing_unsuccessful(&err))?;
+            let total_count = rosetta_blocks.len() as u64;
+            (
+                rosetta_blocks
+                    .into_iter()
+                    .skip(offset as usize)
+                    .take(limit as usize)
+                    .collect::<Vec<_>>(),
+                total_count,
+            )
+        }
+        (None, Some(account)) => (
+            storage_client
+                .get_blocks_by_account(&account, max_block, offset, limit)
+                .map_err(|err| Error::unable_to_find_block(&err))?,
+            storage_client
+                .get_block_count_by_account(&account, max_block)
+                .map_err(|err| Error::unable_to_find_block(&err))?,
+        ),
+        (None, None) => {
+            return Err(Error::request_processing_error(
+                &"Either transaction_identifier or account_identifier has to be provided",
+            ))
+        }
+    };
+
+    let next_offset = offset.saturating_add(rosetta_blocks.len() as u64);
+    let next_offset = (next_offset < total_count)
+        .then(|| i64::try_from(next_offset))
+        .transpose()
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    let currency: Currency = metadata.into();
+    let mut transactions = vec![];
+    for rosetta_block in rosetta_blocks {
+        transactions.push(BlockTransaction::new(
+            rosetta_block.get_block_identifier(),
+            icrc1_rosetta_block_to_rosetta_core_transaction(rosetta_block, currency.clone())
+                .map_err(|err| Error::failed_to_build_block_response(&err))?,
+        ));
+    }
+
+    Ok(SearchTransactionsResponse::new(
+        transactions,
+        i64::try_from(total_count).map_err(|err| Error::parsing_unsuccessful(&err))?,
+        next_offset,
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::common::storage::types::{RosettaBlock, Tokens};
     use ic_icrc1_test_utils::valid_blockchain_strategy;
+    use ic_rosetta_api::models::SearchTransactionsRequest;
     use proptest::prelude::*;
 
     const BLOCKHAIN_LENGTH: usize = 1000;
@@ -370,5 +555,126 @@ mod test {
                     assert!(block_transaction_res.unwrap_err().0.description.unwrap().contains(format!("Both index {} and hash {} were provided but they do not match the same block",valid_block_idx.clone(),invalid_block_hash.clone()).as_str()));
                 }
         }
+
+            #[test]
+            fn test_account_balance_service(blockchain in valid_blockchain_strategy::<Tokens>(BLOCKHAIN_LENGTH)){
+                let storage_client_memory = Arc::new(StorageClient::new_in_memory().unwrap());
+                let mut rosetta_blocks = vec![];
+                for (index,block) in blockchain.clone().into_iter().enumerate(){
+                    rosetta_blocks.push(RosettaBlock::from_icrc_ledger_block(block,index as u64).unwrap());
+                }
+
+                let metadata = Metadata{
+                    symbol: "ICP".to_string(),
+                    decimals: 8
+                };
+                let unknown_account = Account{owner: Principal::management_canister(), subaccount: Some([1;32])};
+
+                // If the storage is empty the service should return an error
+                let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),None,metadata.clone());
+                assert!(account_balance_res.unwrap_err().0.description.unwrap().contains("Current block not found"));
+
+                if !blockchain.is_empty() {
+                    storage_client_memory.store_blocks(rosetta_blocks.clone()).unwrap();
+                    storage_client_memory.update_account_balances().unwrap();
+                    let highest_block = rosetta_blocks.last().unwrap().clone();
+
+                    // An account that never received any tokens has a balance of zero
+                    let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),None,metadata.clone()).unwrap();
+                    assert_eq!(account_balance_res, AccountBalanceResponse::new(highest_block.get_block_identifier(), vec![Amount::new("0".to_string(), metadata.clone().into())]));
+
+                    for rosetta_block in rosetta_blocks.iter() {
+                        let account = match rosetta_block.get_transaction().unwrap().operation {
+                            ic_icrc1::Operation::Mint { to, .. } => to,
+                            ic_icrc1::Operation::Transfer { from, .. } | ic_icrc1::Operation::Burn { from, .. } | ic_icrc1::Operation::Approve { from, .. } => from,
+                        };
+
+                        // Without a block identifier the balance at the highest block is returned
+                        let account_balance_res = account_balance(storage_client_memory.clone(),account.into(),Some(PartialBlockIdentifier{index: None, hash: None}),metadata.clone()).unwrap();
+                        assert_eq!(account_balance_res.block_identifier, highest_block.get_block_identifier());
+                        assert_eq!(account_balance_res.balances, vec![Amount::new(storage_client_memory.get_account_balance(&account).unwrap().unwrap_or_else(Tokens::zero).to_string(), metadata.clone().into())]);
+
+                        // With a block identifier the historical balance at that block is returned
+                        let account_balance_res = account_balance(storage_client_memory.clone(),account.into(),Some(PartialBlockIdentifier{index: Some(rosetta_block.index), hash: None}),metadata.clone()).unwrap();
+                        assert_eq!(account_balance_res.block_identifier, rosetta_block.get_block_identifier());
+                        assert_eq!(account_balance_res.balances, vec![Amount::new(storage_client_memory.get_account_balance_at_block_idx(&account,rosetta_block.index).unwrap().unwrap_or_else(Tokens::zero).to_string(), metadata.clone().into())]);
+                    }
+
+                    // If the block identifier does not exist the service should return an error
+                    let invalid_block_idx = blockchain.len() as u64 + 1;
+                    let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),Some(PartialBlockIdentifier{index: Some(invalid_block_idx), hash: None}),metadata.clone());
+                    assert!(account_balance_res.unwrap_err().0.description.unwrap().contains(&format!("Block at index {} could not be found",invalid_block_idx)));
+                }
+            }
+
+            #[test]
+            fn test_search_transactions_service(blockchain in valid_blockchain_strategy::<Tokens>(BLOCKHAIN_LENGTH)){
+                let storage_client_memory = Arc::new(StorageClient::new_in_memory().unwrap());
+                let mut rosetta_blocks = vec![];
+                for (index,block) in blockchain.clone().into_iter().enumerate(){
+                    rosetta_blocks.push(RosettaBlock::from_icrc_ledger_block(block,index as u64).unwrap());
+                }
+                storage_client_memory.store_blocks(rosetta_blocks.clone()).unwrap();
+                storage_client_memory.update_account_balances().unwrap();
+
+                let metadata = Metadata{
+                    symbol: "ICP".to_string(),
+                    decimals: 8
+                };
+                let currency: Currency = metadata.clone().into();
+                let network_identifier = NetworkIdentifier::new(DEFAULT_BLOCKCHAIN.to_owned(), Principal::anonymous().to_string());
+
+                // Searching without a transaction or account identifier is not supported
+                let search_res = search_transactions(storage_client_memory.clone(),SearchTransactionsRequest::new(network_identifier.clone(),None,None),metadata.clone());
+                assert!(search_res.is_err());
+
+                for rosetta_block in rosetta_blocks.iter() {
+                    // Searching by transaction hash returns all blocks with that transaction hash
+                    let search_res = search_transactions(storage_client_memory.clone(),SearchTransactionsRequest::new(network_identifier.clone(),Some(rosetta_block.get_transaction_identifier()),None),metadata.clone()).unwrap();
+                    assert!(search_res.transactions.contains(&BlockTransaction::new(rosetta_block.get_block_identifier(),icrc1_rosetta_block_to_rosetta_core_transaction(rosetta_block.clone(),currency.clone()).unwrap())));
+                    assert!(search_res.transactions.iter().all(|tx| tx.transaction.transaction_identifier == rosetta_block.get_transaction_identifier()));
+                    assert_eq!(search_res.total_count, search_res.transactions.len() as i64);
+                }
+
+                if let Some(first_block) = rosetta_blocks.first() {
+                    let account = match first_block.get_transaction().unwrap().operation {
+                        ic_icrc1::Operation::Mint { to, .. } => to,
+                        ic_icrc1::Operation::Transfer { from, .. } | ic_icrc1::Operation::Burn { from, .. } | ic_icrc1::Operation::Approve { from, .. } => from,
+                    };
+                    let expected_blocks: Vec<RosettaBlock> = rosetta_blocks.iter().rev().filter(|block| {
+                        let operation = block.get_transaction().unwrap().operation;
+                        // The fee collector is credited the fee of transfers
+                        let is_fee_collector = matches!(operation, ic_icrc1::Operation::Transfer { .. }) && block.get_fee_collector().unwrap() == Some(account);
+                        operation_involves_account(&operation,&account) || is_fee_collector
+                    }).cloned().collect();
+
+                    // Searching by account returns all blocks involving the account, starting with the most recent block
+                    let search_res = search_transactions(storage_client_memory.clone(),SearchTransactionsRequest::new(network_identifier.clone(),None,Some(account.into())),metadata.clone()).unwrap();
+                    assert_eq!(search_res.total_count, expected_blocks.len() as i64);
+                    assert_eq!(search_res.next_offset, None);
+                    assert_eq!(search_res.transactions.iter().map(|tx| tx.block_identifier.clone()).collect::<Vec<_>>(), expected_blocks.iter().map(|block| block.get_block_identifier()).collect::<Vec<_>>());
+
+                    // Paginating through the results returns the same blocks
+                    let mut paginated_blocks = vec![];
+                    let mut request = SearchTransactionsRequest::new(network_identifier.clone(),None,Some(account.into()));
+                    request.limit = Some(2);
+                    loop {
+                        let search_res = search_transactions(storage_client_memory.clone(),request.clone(),metadata.clone()).unwrap();
+                        assert_eq!(search_res.total_count, expected_blocks.len() as i64);
+                        paginated_blocks.extend(search_res.transactions.into_iter().map(|tx| tx.block_identifier));
+                        match search_res.next_offset {
+                            Some(next_offset) => request.offset = Some(next_offset),
+                            None => break,
+                        }
+                    }
+                    assert_eq!(paginated_blocks, expected_blocks.iter().map(|block| block.get_block_identifier()).collect::<Vec<_>>());
+
+                    // The max block limits the search to blocks with a lower or equal index
+                    let mut request = SearchTransactionsRequest::new(network_identifier.clone(),None,Some(account.into()));
+                    request.max_block = Some(0);
+                    let search_res = search_transactions(storage_client_memory.clone(),request,metadata.clone()).unwrap();
+                    assert_eq!(search_res.transactions.iter().map(|tx| tx.block_identifier.clone()).collect::<Vec<_>>(), vec![first_block.get_block_identifier()]);
+                }
+            }
     }
 }
@@ -312,13 +312,17 @@ async fn main() -> Result<()> {
         .route("/network/list", post(network_list))
         .route("/network/options", post(network_options))
         .route("/network/status", post(network_status))
+        .route("/account/balance", post(account_balance))
         .route("/block", post(block))
         .route("/block/transaction", post(block_transaction))
         .route("/mempool", post(mempool))
         .route("/mempool/transaction", post(mempool_transaction))
+        .route("/search/transactions", post(search_transactions))
         .route("/construction/derive", post(construction_derive))
         .route("/construction/preprocess", post(construction_preprocess))
         .route("/construction/metadata", post(construction_metadata))
+        .route("/construction/payloads", post(construction_payloads))
+        .route("/construction/parse", post(construction_parse))
         .route("/construction/combine", post(construction_combine))
         .route("/construction/submit", post(construction_submit))
         .route("/construction/hash", post(construction_hash))
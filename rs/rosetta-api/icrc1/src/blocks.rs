@@ -6,7 +6,7 @@ use ciborium::value::Value as CiboriumValue;
 use ic_ledger_core::block::{BlockType, EncodedBlock};
 use ic_ledger_core::tokens::TokensType;
 use icrc_ledger_types::icrc::generic_value::Value as GenericValue;
-use icrc_ledger_types::icrc3::blocks::GenericBlock;
+use icrc_ledger_types::icrc3::blocks::{GenericBlock, SupportedBlockType};
 use icrc_ledger_types::icrc3::transactions::GenericTransaction;
 use num_traits::{Signed, ToPrimitive};
 use serde_bytes::ByteBuf;
@@ -78,6 +78,20 @@ pub fn encoded_block_to_generic_block(encoded_block: &EncodedBlock) -> GenericBl
     icrc1_block_from_value(value, 0).expect("failed to decode encoded block")
 }
 
+/// Returns the ICRC-3 block types that the ICRC-1 ledger and its archives
+/// produce.
+pub fn supported_block_types() -> Vec<SupportedBlockType> {
+    const ICRC3_URL: &str =
+        "https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-3/README.md";
+    ["1burn", "1mint", "1xfer", "2approve", "2xfer"]
+        .into_iter()
+        .map(|block_type| SupportedBlockType {
+            block_type: block_type.to_string(),
+            url: ICRC3_URL.to_string(),
+        })
+        .collect()
+}
+
 #[derive(Debug, Error)]
 enum ValueDecodingError {
     #[error("CBOR value depth must not exceed {max_depth}")]
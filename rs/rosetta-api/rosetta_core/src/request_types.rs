@@ -88,6 +88,41 @@ impl BlockTransactionRequest {
     }
 }
 
+/// An AccountBalanceRequest is utilized to make a balance request on the
+/// /account/balance endpoint. If the block_identifier is populated, a
+/// historical balance query should be performed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct AccountBalanceRequest {
+    /// The network_identifier specifies which network a particular object is associated with.
+    pub network_identifier: NetworkIdentifier,
+
+    /// The account_identifier uniquely identifies an account within a network.
+    pub account_identifier: AccountIdentifier,
+
+    /// When fetching data by BlockIdentifier, it may be possible to only specify the index or hash. If neither property is specified, it is assumed that the client is making a request at the current block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_identifier: Option<PartialBlockIdentifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMap>,
+}
+
+impl AccountBalanceRequest {
+    pub fn new(
+        network_identifier: NetworkIdentifier,
+        account_identifier: AccountIdentifier,
+        block_identifier: Option<PartialBlockIdentifier>,
+    ) -> AccountBalanceRequest {
+        AccountBalanceRequest {
+            network_identifier,
+            account_identifier,
+            block_identifier,
+            metadata: None,
+        }
+    }
+}
+
 /// A MempoolTransactionRequest is utilized to retrieve a transaction from the
 /// mempool.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
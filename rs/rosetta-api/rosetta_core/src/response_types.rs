@@ -19,6 +19,33 @@ impl NetworkListResponse {
     }
 }
 
+/// An AccountBalanceResponse is returned on the /account/balance endpoint. If
+/// an account has a balance for each AccountIdentifier describing it (ex: an
+/// ERC-20 token balance on a few smart contracts), an account balance request
+/// must be made with each AccountIdentifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct AccountBalanceResponse {
+    /// The block_identifier uniquely identifies a block in a particular network.
+    pub block_identifier: BlockIdentifier,
+
+    /// A single account may have a balance in multiple currencies.
+    pub balances: Vec<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ObjectMap>,
+}
+
+impl AccountBalanceResponse {
+    pub fn new(block_identifier: BlockIdentifier, balances: Vec<Amount>) -> AccountBalanceResponse {
+        AccountBalanceResponse {
+            block_identifier,
+            balances,
+            metadata: None,
+        }
+    }
+}
+
 /// NetworkOptionsResponse contains information about the versioning of the node and the allowed operation statuses, operation types, and errors.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct NetworkOptionsResponse {
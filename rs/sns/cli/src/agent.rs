@@ -0,0 +1,408 @@
+//! Talks to the IC directly through its public HTTP endpoints (instead of
+//! going through dfx), signing requests with a PEM or HSM identity.
+
+use candid::IDLArgs;
+use clap::Parser;
+use ic_base_types::{CanisterId, PrincipalId};
+use ic_canister_client::{Agent, Sender};
+use ic_canister_client_sender::SigKeys;
+use ic_crypto_sha2::Sha256;
+use ic_crypto_utils_threshold_sig_der::parse_threshold_sig_key_from_der;
+use ic_management_canister_types::{
+    CanisterIdRecord, CanisterInstallMode, CanisterSettingsArgsBuilder, InstallCodeArgs, Method,
+    Payload, ProvisionalCreateCanisterWithCyclesArgs, UpdateSettingsArgs, IC_00,
+};
+use ic_nns_init::make_hsm_sender;
+use std::{path::PathBuf, time::SystemTime};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// The URL of the replica started by `dfx start`.
+const LOCAL_NETWORK_URL: &str = "http://127.0.0.1:8080";
+
+/// The URL of the IC mainnet.
+const IC_NETWORK_URL: &str = "https://ic0.app";
+
+/// The root public key of the IC mainnet, against which responses from
+/// mainnet are verified.
+const IC_ROOT_PUBLIC_KEY_BASE64: &str = r#"MIGCMB0GDSsGAQQBgtx8BQMBAgEGDCsGAQQBgtx8BQMCAQNhAIFMDm7HH6tYOwi9gTc8JVw8NxsuhIY8mKTx4It0I10U+12cDNVG2WhfkToMCyzFNBWDv0tDkuRn25bWW5u0y3FxEvhHLg1aTRRQX/10hLASkQkcX4e5iINGP5gJGguqrg=="#;
+
+/// Local networks consist of a single subnet, so any canister ID in its
+/// range (which starts at 0) routes a request for the management canister
+/// there.
+const LOCAL_EFFECTIVE_CANISTER_ID: CanisterId = CanisterId::from_u64(0);
+
+/// The arguments that determine where, and as whom, calls are made.
+#[derive(Debug, Parser)]
+pub struct AgentArgs {
+    /// The network to deploy to. This can be "local", "ic", or the URL of an IC network.
+    #[clap(default_value = "local", long)]
+    pub network: String,
+
+    /// The pem file containing the secret key of the identity that makes the
+    /// calls. If neither this nor --use-hsm is given, calls are anonymous.
+    #[clap(long, parse(from_os_str))]
+    pub pem_file: Option<PathBuf>,
+
+    /// Use an HSM to sign calls.
+    #[clap(long)]
+    pub use_hsm: bool,
+
+    /// The slot related to the HSM key that shall be used.
+    #[clap(
+        long = "slot",
+        help = "Only required if use-hsm is set. Ignored otherwise."
+    )]
+    pub hsm_slot: Option<String>,
+
+    /// The id of the key on the HSM that shall be used.
+    #[clap(
+        long = "key-id",
+        help = "Only required if use-hsm is set. Ignored otherwise."
+    )]
+    pub key_id: Option<String>,
+
+    /// The PIN used to unlock the HSM.
+    #[clap(
+        long = "pin",
+        help = "Only required if use-hsm is set. Ignored otherwise."
+    )]
+    pub pin: Option<String>,
+
+    /// Instead of making update calls, print their Candid arguments. Queries
+    /// are still made.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+impl AgentArgs {
+    /// The URL of the network given by --network.
+    pub fn network_url(&self) -> Result<Url, String> {
+        let url = match self.network.as_str() {
+            "local" => LOCAL_NETWORK_URL,
+            "ic" => IC_NETWORK_URL,
+            url => url,
+        };
+        Url::parse(url).map_err(|err| {
+            format!(
+                "Invalid --network {:?}. It must be \"local\", \"ic\", or a URL: {}",
+                self.network, err,
+            )
+        })
+    }
+
+    /// The sender given by --pem-file or --use-hsm, or the anonymous sender.
+    pub fn sender(&self) -> Result<Sender, String> {
+        match (&self.pem_file, self.use_hsm) {
+            (Some(_), true) => Err("Only one of --pem-file and --use-hsm can be set.".to_string()),
+            (Some(pem_file), false) => {
+                let contents = std::fs::read_to_string(pem_file)
+                    .map_err(|err| format!("Unable to read {:?}: {}", pem_file, err))?;
+                let sig_keys = SigKeys::from_pem(&contents)
+                    .map_err(|err| format!("Unable to parse {:?}: {}", pem_file, err))?;
+                Ok(Sender::SigKeys(sig_keys))
+            }
+            (None, true) => match (&self.hsm_slot, &self.key_id, &self.pin) {
+                (Some(hsm_slot), Some(key_id), Some(pin)) => {
+                    Ok(make_hsm_sender(hsm_slot, key_id, pin))
+                }
+                _ => Err("--slot, --key-id and --pin are required with --use-hsm.".to_string()),
+            },
+            (None, false) => Ok(Sender::Anonymous),
+        }
+    }
+
+    /// Creates an agent that makes calls as the sender given by these
+    /// arguments.
+    pub fn new_agent(&self) -> Result<CliAgent, String> {
+        self.new_agent_with_sender(self.sender()?)
+    }
+
+    /// Like `new_agent`, but makes calls as the given sender.
+    pub fn new_agent_with_sender(&self, sender: Sender) -> Result<CliAgent, String> {
+        let mut agent = Agent::new(self.network_url()?, sender);
+        if self.network == "ic" {
+            let root_public_key = base64::decode(IC_ROOT_PUBLIC_KEY_BASE64)
+                .map_err(|err| format!("Unable to decode the IC root public key: {}", err))?;
+            let root_public_key = parse_threshold_sig_key_from_der(&root_public_key)
+                .map_err(|err| format!("Unable to parse the IC root public key: {}", err))?;
+            agent = agent.with_nns_public_key(root_public_key);
+        }
+        let runtime =
+            Runtime::new().map_err(|err| format!("Unable to start a tokio runtime: {}", err))?;
+
+        Ok(CliAgent {
+            network: self.network.clone(),
+            agent,
+            dry_run: self.dry_run,
+            runtime,
+        })
+    }
+}
+
+/// Makes calls to the IC on behalf of the user of the CLI.
+///
+/// In a dry run, update calls are not made. Instead, their arguments are
+/// printed (in Candid text format), and `None` is returned in place of the
+/// reply.
+pub struct CliAgent {
+    network: String,
+    agent: Agent,
+    dry_run: bool,
+    runtime: Runtime,
+}
+
+impl CliAgent {
+    /// The network, as given by --network.
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+
+    /// The principal on whose behalf calls are made.
+    pub fn principal(&self) -> PrincipalId {
+        self.agent.sender.get_principal_id()
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Makes a query call and returns the (Candid-encoded) reply.
+    pub fn query(
+        &self,
+        canister_id: CanisterId,
+        method: &str,
+        arg: Vec<u8>,
+    ) -> Result<Vec<u8>, String> {
+        let reply = self
+            .runtime
+            .block_on(self.agent.execute_query(&canister_id, method, arg))?;
+        reply.ok_or_else(|| format!("Query {} on {} returned no reply.", method, canister_id))
+    }
+
+    /// Makes an update call and returns the (Candid-encoded) reply.
+    pub fn update(
+        &self,
+        canister_id: CanisterId,
+        method: &str,
+        arg: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, String> {
+        self.update_with_effective_canister_id(canister_id, canister_id, method, arg)
+    }
+
+    fn update_with_effective_canister_id(
+        &self,
+        effective_canister_id: CanisterId,
+        canister_id: CanisterId,
+        method: &str,
+        arg: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, String> {
+        if self.dry_run {
+            print_dry_run_call(canister_id, method, &arg);
+            return Ok(None);
+        }
+
+        let reply = self.runtime.block_on(self.agent.execute_update(
+            &effective_canister_id,
+            &canister_id,
+            method,
+            arg,
+            generate_nonce(),
+        ))?;
+        reply
+            .map(Some)
+            .ok_or_else(|| format!("Call {} on {} returned no reply.", method, canister_id))
+    }
+
+    /// Reads the controllers of a canister from the certified state of the
+    /// network. This is also done in a dry run.
+    pub fn canister_controllers(
+        &self,
+        canister_id: CanisterId,
+    ) -> Result<Vec<PrincipalId>, String> {
+        self.runtime
+            .block_on(self.agent.read_canister_controllers(&canister_id))
+    }
+
+    /// Adds `controller` to the controllers of the given canister.
+    pub fn add_controller(
+        &self,
+        canister_id: CanisterId,
+        controller: PrincipalId,
+    ) -> Result<(), String> {
+        self.update_controllers(canister_id, |controllers| {
+            if !controllers.contains(&controller) {
+                controllers.push(controller);
+            }
+        })
+    }
+
+    /// Removes `controller` from the controllers of the given canister.
+    pub fn remove_controller(
+        &self,
+        canister_id: CanisterId,
+        controller: PrincipalId,
+    ) -> Result<(), String> {
+        self.update_controllers(canister_id, |controllers| {
+            controllers.retain(|existing_controller| *existing_controller != controller);
+        })
+    }
+
+    /// The management canister can only replace the controllers of a canister
+    /// as a whole. Therefore, this reads the current controllers, applies
+    /// `update` to them and sets the result as the new controllers.
+    fn update_controllers(
+        &self,
+        canister_id: CanisterId,
+        update: impl FnOnce(&mut Vec<PrincipalId>),
+    ) -> Result<(), String> {
+        let mut controllers = self.canister_controllers(canister_id)?;
+        update(&mut controllers);
+
+        let settings = CanisterSettingsArgsBuilder::new()
+            .with_controllers(controllers)
+            .build();
+        self.update_with_effective_canister_id(
+            canister_id,
+            IC_00,
+            &Method::UpdateSettings.to_string(),
+            UpdateSettingsArgs::new(canister_id, settings).encode(),
+        )
+        .map(|_| ())
+    }
+
+    /// Installs `wasm_module` on an empty canister, and initializes it with
+    /// the (Candid-encoded) `arg`.
+    pub fn install_code(
+        &self,
+        canister_id: CanisterId,
+        wasm_module: Vec<u8>,
+        arg: Vec<u8>,
+    ) -> Result<(), String> {
+        if self.dry_run {
+            println!(
+                "Dry run: would install a WASM module of {} bytes (SHA-256: {}) on {} with argument:\n{}",
+                wasm_module.len(),
+                hex::encode(Sha256::hash(&wasm_module)),
+                canister_id,
+                format_candid(&arg),
+            );
+            return Ok(());
+        }
+
+        let install_args = InstallCodeArgs::new(
+            CanisterInstallMode::Install,
+            canister_id,
+            wasm_module,
+            arg,
+            None,
+            None,
+            None,
+        );
+        self.runtime
+            .block_on(self.agent.install_canister(install_args))
+    }
+
+    /// Creates an empty canister (controlled by the caller) with the given
+    /// amount of cycles, or the maximum amount if `None`. Because this uses the
+    /// provisional API, it only works on local and test networks.
+    pub fn provisional_create_canister(
+        &self,
+        cycles: Option<u128>,
+    ) -> Result<Option<CanisterId>, String> {
+        let reply = self.update_with_effective_canister_id(
+            LOCAL_EFFECTIVE_CANISTER_ID,
+            IC_00,
+            &Method::ProvisionalCreateCanisterWithCycles.to_string(),
+            ProvisionalCreateCanisterWithCyclesArgs::new(cycles, None).encode(),
+        )?;
+        reply
+            .map(|reply| {
+                CanisterIdRecord::decode(&reply)
+                    .map(|canister_id_record| canister_id_record.get_canister_id())
+                    .map_err(|err| format!("Unable to decode the created canister ID: {}", err))
+            })
+            .transpose()
+    }
+}
+
+/// Prints what a dry run does instead of making an update call.
+pub fn print_dry_run_call(canister_id: CanisterId, method: &str, arg: &[u8]) {
+    println!(
+        "Dry run: would call {} on {} with argument:\n{}",
+        method,
+        canister_id,
+        format_candid(arg),
+    );
+}
+
+/// Formats Candid-encoded bytes in Candid text format, falling back to hex if
+/// they cannot be decoded.
+pub fn format_candid(bytes: &[u8]) -> String {
+    match IDLArgs::from_bytes(bytes) {
+        Ok(args) => args.to_string(),
+        Err(_) => hex::encode(bytes),
+    }
+}
+
+fn generate_nonce() -> Vec<u8> {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos()
+        .to_le_bytes()
+        .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Encode;
+
+    fn agent_args(network: &str) -> AgentArgs {
+        AgentArgs::parse_from(["agent", "--network", network])
+    }
+
+    #[test]
+    fn test_network_url() {
+        assert_eq!(
+            agent_args("local").network_url(),
+            Ok(Url::parse(LOCAL_NETWORK_URL).unwrap())
+        );
+        assert_eq!(
+            agent_args("ic").network_url(),
+            Ok(Url::parse(IC_NETWORK_URL).unwrap())
+        );
+        assert_eq!(
+            agent_args("http://[::1]:4943").network_url(),
+            Ok(Url::parse("http://[::1]:4943").unwrap())
+        );
+        assert!(agent_args("not a url").network_url().is_err());
+    }
+
+    #[test]
+    fn test_sender() {
+        assert!(matches!(
+            agent_args("local").sender(),
+            Ok(Sender::Anonymous)
+        ));
+
+        let args = AgentArgs::parse_from(["agent", "--use-hsm", "--slot", "0"]);
+        assert!(args.sender().is_err());
+
+        let args = AgentArgs::parse_from(["agent", "--use-hsm", "--pem-file", "identity.pem"]);
+        assert!(args.sender().is_err());
+    }
+
+    #[test]
+    fn test_format_candid() {
+        let bytes = Encode!(&42_u64, &"hello").unwrap();
+        let text = format_candid(&bytes);
+        assert!(
+            text.contains("42") && text.contains("\"hello\""),
+            "{}",
+            text
+        );
+        assert_eq!(format_candid(&[1, 2, 3]), "010203");
+    }
+}
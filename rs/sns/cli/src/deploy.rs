@@ -6,34 +6,49 @@ use std::{
     fs::{create_dir_all, OpenOptions},
     io::{BufWriter, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    process::exit,
     str::FromStr,
 };
+#[cfg(test)]
+use tempfile::NamedTempFile;
 
 use anyhow::anyhow;
-use candid::{types::value::IDLValue, Decode, Encode};
+use candid::{CandidType, Decode, Deserialize, Encode, Nat};
 use serde_json::{json, Value as JsonValue};
-use tempfile::NamedTempFile;
 
 use crate::{
-    call_dfx, call_dfx_or_panic, get_identity, hex_encode_candid, DeployArgs, DeployTestflightArgs,
+    agent::{format_candid, print_dry_run_call, CliAgent},
+    DeployArgs, DeployTestflightArgs,
 };
 use ic_base_types::{CanisterId, PrincipalId};
 use ic_nns_constants::{ROOT_CANISTER_ID as NNS_ROOT_CANISTER_ID, SNS_WASM_CANISTER_ID};
-use ic_sns_governance::pb::v1::ListNeuronsResponse;
+use ic_sns_governance::pb::v1::{ListNeurons, ListNeuronsResponse};
 use ic_sns_init::{pb::v1::SnsInitPayload, SnsCanisterIds, SnsCanisterInitPayloads};
-use ic_sns_root::pb::v1::ListSnsCanistersResponse;
+use ic_sns_root::pb::v1::{ListSnsCanistersRequest, ListSnsCanistersResponse};
 use ic_sns_wasm::pb::v1::{
     DeployNewSnsRequest, DeployNewSnsResponse, SnsCanisterIds as SnsWSnsCanisterIds,
 };
 
+/// The names of the SNS canisters, as used in canister_ids.json.
+const SNS_CANISTER_NAMES: [&str; 5] = [
+    "sns_governance",
+    "sns_ledger",
+    "sns_root",
+    "sns_swap",
+    "sns_index",
+];
+
 /// If SNS canisters have already been created, return their canister IDs, else create the
 /// SNS canisters and return their canister IDs.
+///
+/// Returns `None` in a dry run if some SNS canisters don't exist yet, as they are not created.
 pub fn lookup_or_else_create_canisters(
+    agent: &CliAgent,
     verbose: bool,
-    network: &String,
     initial_cycles_per_canister: Option<u64>,
-) -> SnsCanisterIds {
-    let sns_canister_ids = match lookup(verbose, network) {
+    wallet_canister: Option<CanisterId>,
+) -> Option<SnsCanisterIds> {
+    let sns_canister_ids = match lookup(verbose, agent.network()) {
         Some(sns_canister_ids) => {
             println!("SNS canisters already allocated");
             sns_canister_ids
@@ -43,16 +58,16 @@ pub fn lookup_or_else_create_canisters(
                 "SNS canisters not found, creating SNS canisters with {:?} cycles each",
                 initial_cycles_per_canister
             );
-            create_canisters(verbose, network, initial_cycles_per_canister)
+            create_canisters(agent, verbose, initial_cycles_per_canister, wallet_canister)?
         }
     };
 
     println!("SNS canister IDs:\n{:?}", &sns_canister_ids);
-    sns_canister_ids
+    Some(sns_canister_ids)
 }
 
 /// If all the SNS canisters have already been created, return them.
-fn lookup(verbose: bool, network: &String) -> Option<SnsCanisterIds> {
+fn lookup(verbose: bool, network: &str) -> Option<SnsCanisterIds> {
     Some(SnsCanisterIds {
         governance: get_canister_id("sns_governance", verbose, network)?,
         ledger: get_canister_id("sns_ledger", verbose, network)?,
@@ -62,57 +77,189 @@ fn lookup(verbose: bool, network: &String) -> Option<SnsCanisterIds> {
     })
 }
 
-/// Call `dfx canister create` to allocate canister IDs for all SNS canisters.
+/// Create the SNS canisters that don't exist yet, and record their IDs in canister_ids.json.
+///
+/// In a dry run, the calls that would create the missing canisters are printed, and `None` is
+/// returned.
 fn create_canisters(
+    agent: &CliAgent,
     verbose: bool,
-    network: &String,
     initial_cycles_per_canister: Option<u64>,
-) -> SnsCanisterIds {
+    wallet_canister: Option<CanisterId>,
+) -> Option<SnsCanisterIds> {
     println!("Creating SNS canisters...");
-    let cycles = format!("{}", initial_cycles_per_canister.unwrap_or_default());
-
-    call_dfx_or_panic(&[
-        "canister",
-        "--network",
-        network,
-        "create",
-        "--all",
-        "--with-cycles",
-        &cycles,
-    ]);
-    lookup(verbose, network).expect("SNS canisters failed to be created")
-}
 
-/// Return the canister ID of the canister given by `canister_name`
-pub fn get_canister_id(
-    canister_name: &str,
-    verbose: bool,
-    network: &String,
-) -> Option<PrincipalId> {
-    println!("dfx canister --network {} id {}", &network, canister_name);
-    let output = call_dfx(&["canister", "--network", network, "id", canister_name]);
-
-    let canister_id = String::from_utf8(output.stdout)
-        .map_err(|e| {
-            if verbose {
-                println!(
-                    "Could not parse the output of 'dfx canister id {}' as a string, error: {}",
-                    canister_name, e
-                )
+    let network = agent.network();
+    for canister_name in SNS_CANISTER_NAMES {
+        if get_canister_id(canister_name, verbose, network).is_some() {
+            continue;
+        }
+
+        let canister_id = match create_canister(agent, initial_cycles_per_canister, wallet_canister)
+        {
+            Ok(Some(canister_id)) => canister_id,
+            Ok(None) => {
+                println!("(to create {})", canister_name);
+                continue;
             }
-        })
-        .ok()?;
-
-    PrincipalId::from_str(canister_id.trim())
-        .map_err(|e| {
-            if verbose {
-                println!(
-                    "Could not parse the output of 'dfx canister id {}' as a PrincipalId, error: {}",
-                    canister_name, e
+            Err(err) => {
+                eprintln!("Failed to create {}: {}", canister_name, err);
+                exit(1);
+            }
+        };
+        println!("Created {} with ID {}", canister_name, canister_id);
+
+        let canister_ids_file = canister_ids_file_to_save_to(network);
+        if let Some(dir) = canister_ids_file.parent() {
+            create_dir_all(dir).unwrap_or_else(|err| {
+                panic!(
+                    "Failed to create directory for {}: {err}",
+                    canister_ids_file.to_string_lossy()
                 )
+            });
+        }
+        let network_name = &network_key(network);
+        merge_into_json_file(
+            &canister_ids_file,
+            &json!({canister_name: json!({network_name: canister_id.to_string()})}),
+        )
+        .expect("cannot write SNS canister IDs to file");
+    }
+
+    if agent.is_dry_run() {
+        println!(
+            "Dry run: the remaining calls depend on the IDs of the SNS canisters, so they are \
+             only printed once the SNS canisters exist."
+        );
+        return None;
+    }
+    Some(lookup(verbose, network).expect("SNS canisters failed to be created"))
+}
+
+/// Creates an empty canister. If `wallet_canister` is given, the wallet creates the canister
+/// (paying for it with `initial_cycles_per_canister`), which is then controlled by both the
+/// caller and the wallet. Otherwise, the canister is created through the provisional API, which
+/// is only available on the local network.
+///
+/// In a dry run, the call that would create the canister is printed, and `None` is returned.
+fn create_canister(
+    agent: &CliAgent,
+    initial_cycles_per_canister: Option<u64>,
+    wallet_canister: Option<CanisterId>,
+) -> Result<Option<CanisterId>, String> {
+    let wallet_canister = match wallet_canister {
+        Some(wallet_canister) => wallet_canister,
+        None if agent.network() == "local" => {
+            return agent.provisional_create_canister(initial_cycles_per_canister.map(u128::from))
+        }
+        None => {
+            return Err(format!(
+                "--wallet-canister-override is required to create canisters on network {}",
+                agent.network()
+            ))
+        }
+    };
+    let cycles = initial_cycles_per_canister.ok_or_else(|| {
+        "--initial-cycles-per-canister is required to create canisters through a wallet".to_string()
+    })?;
+
+    let request = WalletCreateCanisterRequest {
+        cycles,
+        settings: WalletCanisterSettings {
+            controllers: Some(vec![agent.principal(), wallet_canister.get()]),
+            ..Default::default()
+        },
+    };
+    let request = Encode!(&request)
+        .map_err(|err| format!("Couldn't encode wallet_create_canister request: {}", err))?;
+    let response = match agent.update(wallet_canister, "wallet_create_canister", request)? {
+        Some(response) => response,
+        None => return Ok(None),
+    };
+    match Decode!(&response, WalletCreateCanisterResult) {
+        Ok(WalletCreateCanisterResult::Ok(WalletCreateCanisterReply { canister_id })) => {
+            Ok(Some(CanisterId::unchecked_from_principal(canister_id)))
+        }
+        Ok(WalletCreateCanisterResult::Err(err)) => Err(err),
+        Err(err) => Err(format!(
+            "cannot parse wallet response {}: {}",
+            format_candid(&response),
+            err
+        )),
+    }
+}
+
+/// The key under which the canister IDs of `network` are recorded in canister_ids.json.
+fn network_key(network: &str) -> String {
+    // this is what dfx does to make the network name "OS-friendly"
+    network.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+}
+
+/// The files in which canister IDs are looked up, in the same places as dfx
+/// keeps them: `.dfx/<network>/canister_ids.json` for the local network, and
+/// `canister_ids.json` for all networks.
+fn canister_ids_files(network: &str) -> Vec<PathBuf> {
+    vec![
+        Path::new(".dfx")
+            .join(network_key(network))
+            .join("canister_ids.json"),
+        PathBuf::from("canister_ids.json"),
+    ]
+}
+
+/// The file in which the IDs of newly created canisters are recorded.
+fn canister_ids_file_to_save_to(network: &str) -> PathBuf {
+    if network == "local" {
+        Path::new(".dfx").join("local").join("canister_ids.json")
+    } else {
+        PathBuf::from("canister_ids.json")
+    }
+}
+
+/// Return the canister ID of the canister given by `canister_name`, as recorded in
+/// canister_ids.json.
+pub fn get_canister_id(canister_name: &str, verbose: bool, network: &str) -> Option<PrincipalId> {
+    canister_ids_files(network)
+        .into_iter()
+        .filter(|path| path.exists())
+        .find_map(|path| {
+            let canister_id = read_canister_id(&path, canister_name, network);
+            if let Err(err) = &canister_id {
+                if verbose {
+                    println!(
+                        "Could not find the ID of {} in {}: {}",
+                        canister_name,
+                        path.display(),
+                        err
+                    );
+                }
             }
+            canister_id.ok()
         })
-        .ok()
+}
+
+fn read_canister_id(
+    path: &Path,
+    canister_name: &str,
+    network: &str,
+) -> anyhow::Result<PrincipalId> {
+    let contents = std::fs::read_to_string(path)?;
+    let canister_ids: JsonValue = serde_json::from_str(&contents)?;
+    let canister_id = canister_ids
+        .get(canister_name)
+        .and_then(|canister_id_by_network| canister_id_by_network.get(network_key(network)))
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| anyhow!("no entry for network {}", network))?;
+    Ok(PrincipalId::from_str(canister_id)?)
+}
+
+/// Return the canister ID given by `canister_id_or_name`, which is either a canister ID or
+/// a name in canister_ids.json.
+fn resolve_canister_id(canister_id_or_name: &str, network: &str) -> Option<CanisterId> {
+    CanisterId::from_str(canister_id_or_name).ok().or_else(|| {
+        get_canister_id(canister_id_or_name, true, network)
+            .map(CanisterId::unchecked_from_principal)
+    })
 }
 
 /// Merges the given JSON into a JSON file.
@@ -151,20 +298,9 @@ where
     Ok(())
 }
 
-fn parse_deploy_new_sns_response(buffer: &[u8]) -> anyhow::Result<SnsWSnsCanisterIds> {
-    let mut hex = buffer.to_vec();
-    while hex
-        .last()
-        .map(|&c| !c.is_ascii_hexdigit())
-        .unwrap_or_default()
-    {
-        hex.pop().unwrap();
-    }
-    let decoded = Decode!(
-        &hex::decode(hex).expect("cannot parse dfx output as hex"),
-        DeployNewSnsResponse
-    )
-    .expect("cannot parse dfx output as DeployNewSnsResponse");
+fn parse_deploy_new_sns_response(response: &[u8]) -> anyhow::Result<SnsWSnsCanisterIds> {
+    let decoded = Decode!(response, DeployNewSnsResponse)
+        .map_err(|err| anyhow!("cannot parse DeployNewSnsResponse: {}", err))?;
 
     let DeployNewSnsResponse {
         canisters, error, ..
@@ -181,8 +317,7 @@ fn dfx_canister_ids_json(
     network_name: &str,
     sns_canister_ids: SnsWSnsCanisterIds,
 ) -> anyhow::Result<JsonValue> {
-    // this is what dfx does to make the network name "OS-friendly"
-    let network_name = &network_name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let network_name = &network_key(network_name);
     Ok(json!({
         "sns_governance": json!({network_name: sns_canister_ids.governance.expect("SNS root does not return governance canister ID")}),
         "sns_index": json!({network_name: sns_canister_ids.index.expect("SNS root does not return index canister ID")}),
@@ -202,28 +337,86 @@ fn sns_quill_canister_ids_json(sns_canister_ids: SnsWSnsCanisterIds) -> anyhow::
     }))
 }
 
+/// The argument of the `wallet_call` method of the cycles wallet.
+#[derive(CandidType)]
+struct WalletCallRequest {
+    canister: PrincipalId,
+    method_name: String,
+    args: Vec<u8>,
+    cycles: u64,
+}
+
+/// The result of the `wallet_call` method of the cycles wallet.
+#[derive(CandidType, Deserialize)]
+enum WalletCallResult {
+    Ok(WalletCallReply),
+    Err(String),
+}
+
+#[derive(CandidType, Deserialize)]
+struct WalletCallReply {
+    #[serde(rename = "return")]
+    reply: Vec<u8>,
+}
+
+/// The argument of the `wallet_create_canister` method of the cycles wallet.
+#[derive(CandidType)]
+struct WalletCreateCanisterRequest {
+    cycles: u64,
+    settings: WalletCanisterSettings,
+}
+
+/// The settings of a canister created by the cycles wallet. Settings that are not set take
+/// their default values.
+#[derive(CandidType, Default)]
+struct WalletCanisterSettings {
+    controller: Option<PrincipalId>,
+    controllers: Option<Vec<PrincipalId>>,
+    compute_allocation: Option<Nat>,
+    memory_allocation: Option<Nat>,
+    freezing_threshold: Option<Nat>,
+}
+
+/// The result of the `wallet_create_canister` method of the cycles wallet.
+#[derive(CandidType, Deserialize)]
+enum WalletCreateCanisterResult {
+    Ok(WalletCreateCanisterReply),
+    Err(String),
+}
+
+#[derive(CandidType, Deserialize)]
+struct WalletCreateCanisterReply {
+    canister_id: PrincipalId,
+}
+
 /// Responsible for deploying using SNS-WASM canister (for protected SNS subnet)
 pub struct SnsWasmSnsDeployer {
     pub args: DeployArgs,
     pub sns_init_payload: SnsInitPayload,
-    pub sns_wasms_canister: String,
+    pub sns_wasms_canister: CanisterId,
     pub wallet_canister: CanisterId,
+    pub agent: CliAgent,
 }
 
 impl SnsWasmSnsDeployer {
-    pub fn new(args: DeployArgs, sns_init_payload: SnsInitPayload) -> Self {
-        let sns_wasms_canister = args
-            .override_sns_wasm_canister_id_for_tests
-            .as_ref()
-            .map(|id_or_name| id_or_name.to_string())
-            .unwrap_or_else(|| SNS_WASM_CANISTER_ID.get().to_string());
+    pub fn new(args: DeployArgs, sns_init_payload: SnsInitPayload, agent: CliAgent) -> Self {
+        let sns_wasms_canister = match args.override_sns_wasm_canister_id_for_tests.as_ref() {
+            Some(id_or_name) => {
+                resolve_canister_id(id_or_name, agent.network()).unwrap_or_else(|| {
+                    eprintln!("Unable to find the SNS-WASM canister {}.", id_or_name);
+                    exit(1);
+                })
+            }
+            None => SNS_WASM_CANISTER_ID,
+        };
 
         let wallet_canister = args
             .wallet_canister_override
             .as_ref()
             .map(|id| CanisterId::unchecked_from_principal(*id))
             .unwrap_or_else(|| {
-                CanisterId::unchecked_from_principal(get_identity("get-wallet", &args.network))
+                eprintln!("--wallet-canister-override is required to pay the SNS creation fee.");
+                exit(1);
             });
 
         Self {
@@ -231,6 +424,7 @@ impl SnsWasmSnsDeployer {
             sns_init_payload,
             sns_wasms_canister,
             wallet_canister,
+            agent,
         }
     }
 
@@ -239,55 +433,43 @@ impl SnsWasmSnsDeployer {
         let request = DeployNewSnsRequest {
             sns_init_payload: Some(self.sns_init_payload.clone()),
         };
+        let request = Encode!(&request).expect("Couldn't encode DeployNewSnsRequest");
 
-        // Get a string representing the IDL of a DeployNewSnsRequest by
-        // encoding it to bytes and decoding it to an IDLValue. The decoded
-        // IDLValue does not know the field names, but that's ok.
-        let request_idl = format!(
-            "({},)",
-            Decode!(
-                &Encode!(&request).expect("Couldn't encode DeployNewSnsRequest"),
-                IDLValue
+        // The SNS creation fee is attached by the wallet, which forwards the request.
+        if self.agent.is_dry_run() {
+            print_dry_run_call(self.sns_wasms_canister, "deploy_new_sns", &request);
+            println!(
+                "(through wallet {} with {} cycles)",
+                self.wallet_canister, self.args.with_cycles
+            );
+            return;
+        }
+        let wallet_call_request = WalletCallRequest {
+            canister: self.sns_wasms_canister.get(),
+            method_name: "deploy_new_sns".to_string(),
+            args: request,
+            cycles: self.args.with_cycles,
+        };
+        let response = self
+            .agent
+            .update(
+                self.wallet_canister,
+                "wallet_call",
+                Encode!(&wallet_call_request).expect("Couldn't encode wallet_call request"),
             )
-            .expect("Couldn't decode DeployNewSnsRequest")
-        );
-
-        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
-        writeln!(temp_file, "{}", request_idl).expect("Failed to write to temp file");
-
-        let output = {
-            let wallet_canister = format!("{}", self.wallet_canister);
-            let sns_creation_fee = self.args.with_cycles.to_string();
-            let mut args = vec![
-                "canister",
-                "--network",
-                &self.args.network,
-                "--wallet",
-                &wallet_canister,
-                "call",
-                "--with-cycles",
-                &sns_creation_fee,
-                &self.sns_wasms_canister,
-                "deploy_new_sns",
-                "--output",
-                "raw",
-                "--argument-file",
-                temp_file
-                    .path()
-                    .to_str()
-                    .expect("Expected temp file's path to exist"),
-            ];
-            if let Some(path) = self.args.candid.as_ref() {
-                args.push("--candid");
-                args.push(path);
-            }
-            call_dfx(&args)
+            .unwrap_or_else(|err| panic!("Failed to create SNS: {}", err))
+            .expect("The call is made unless in a dry run");
+        let response = match Decode!(&response, WalletCallResult) {
+            Ok(WalletCallResult::Ok(WalletCallReply { reply })) => reply,
+            Ok(WalletCallResult::Err(err)) => panic!("Failed to create SNS: {}", err),
+            Err(err) => panic!(
+                "Failed to create SNS: cannot parse wallet response {}: {}",
+                format_candid(&response),
+                err
+            ),
         };
 
-        if !output.status.success() {
-            panic!("Failed to create SNS");
-        }
-        self.save_canister_ids(&output.stdout)
+        self.save_canister_ids(&response)
             .expect("Failed to save to SNS canister IDs");
     }
 
@@ -311,7 +493,7 @@ impl SnsWasmSnsDeployer {
         };
         merge_into_json_file(
             canisters_file,
-            &dfx_canister_ids_json(&self.args.network, sns_canister_ids)?,
+            &dfx_canister_ids_json(self.agent.network(), sns_canister_ids)?,
         )
         .expect("cannot write SNS canister IDs to file");
 
@@ -370,7 +552,8 @@ fn should_save_canister_ids() {
 
     // Test the individual steps:
     // .. First parse the response and prepare the JSON representation:
-    let sns_canister_ids = parse_deploy_new_sns_response(sample_response.as_bytes()).unwrap();
+    let sample_response = hex::decode(sample_response.trim()).unwrap();
+    let sns_canister_ids = parse_deploy_new_sns_response(&sample_response).unwrap();
     let actual_dfx_json = dfx_canister_ids_json(network_name, sns_canister_ids).unwrap();
     let actual_sns_quill_json = sns_quill_canister_ids_json(sns_canister_ids).unwrap();
     // ... verify that the representation has no semantic differences
@@ -398,23 +581,29 @@ fn should_save_canister_ids() {
 
 /// Responsible for deploying SNS canisters
 pub struct DirectSnsDeployerForTests {
-    pub network: String,
     pub sns_canister_ids_save_to: PathBuf,
     pub wasms_dir: PathBuf,
     pub sns_canister_payloads: SnsCanisterInitPayloads,
     pub sns_canisters: SnsCanisterIds,
-    pub wallet_canister: PrincipalId,
-    pub dfx_identity: PrincipalId,
+    pub agent: CliAgent,
     pub testflight: bool,
 }
 
 impl DirectSnsDeployerForTests {
-    pub fn new(args: DeployArgs, sns_init_payload: SnsInitPayload) -> Self {
+    /// Returns `None` in a dry run if the SNS canisters don't exist yet, as nothing can be
+    /// deployed to them.
+    pub fn new(
+        args: DeployArgs,
+        sns_init_payload: SnsInitPayload,
+        agent: CliAgent,
+    ) -> Option<Self> {
         let sns_canisters = lookup_or_else_create_canisters(
+            &agent,
             args.verbose,
-            &args.network,
             args.initial_cycles_per_canister,
-        );
+            args.wallet_canister_override
+                .map(CanisterId::unchecked_from_principal),
+        )?;
         // TODO - add version hash to test upgrade path locally?  Where would we find that?
         // TODO[NNS1-2592]: set neurons_fund_participation_constraints to a non-trivial value.
         let sns_canister_payloads =
@@ -423,27 +612,30 @@ impl DirectSnsDeployerForTests {
                 Err(e) => panic!("Could not build canister init payloads: {}", e),
             };
 
-        let wallet_canister = get_identity("get-wallet", &args.network);
-        let dfx_identity = get_identity("get-principal", &args.network);
-
-        Self {
-            network: args.network,
+        Some(Self {
             sns_canister_ids_save_to: args.sns_canister_ids_save_to,
             wasms_dir: args.wasms_dir,
             sns_canister_payloads,
             sns_canisters,
-            wallet_canister,
-            dfx_identity,
+            agent,
             testflight: false,
-        }
+        })
     }
 
-    pub fn new_testflight(args: DeployTestflightArgs, sns_init_payload: SnsInitPayload) -> Self {
+    /// Returns `None` in a dry run if the SNS canisters don't exist yet, as nothing can be
+    /// deployed to them.
+    pub fn new_testflight(
+        args: DeployTestflightArgs,
+        sns_init_payload: SnsInitPayload,
+        agent: CliAgent,
+    ) -> Option<Self> {
         let sns_canisters = lookup_or_else_create_canisters(
+            &agent,
             args.verbose,
-            &args.network,
             Some(args.initial_cycles_per_canister),
-        );
+            args.wallet_canister_override
+                .map(CanisterId::unchecked_from_principal),
+        )?;
 
         // TODO - add version hash to test upgrade path locally?  Where would we find that?
         // TODO[NNS1-2592]: set neurons_fund_participation_constraints to a non-trivial value.
@@ -453,25 +645,24 @@ impl DirectSnsDeployerForTests {
                 Err(e) => panic!("Could not build canister init payloads: {}", e),
             };
 
-        let wallet_canister = get_identity("get-wallet", &args.network);
-        let dfx_identity = get_identity("get-principal", &args.network);
-
-        Self {
-            network: args.network,
+        Some(Self {
             sns_canister_ids_save_to: args.sns_canister_ids_save_to,
             wasms_dir: args.wasms_dir,
             sns_canister_payloads,
             sns_canisters,
-            wallet_canister,
-            dfx_identity,
+            agent,
             testflight: true,
-        }
+        })
     }
 
     /// Deploy an SNS
     pub fn deploy(&self) {
         self.install_sns_canisters();
         self.set_sns_canister_controllers();
+        // Nothing was installed in a dry run, so there is nothing to save or validate.
+        if self.agent.is_dry_run() {
+            return;
+        }
         self.save_canister_ids();
         self.validate_deployment();
     }
@@ -493,26 +684,13 @@ impl DirectSnsDeployerForTests {
             path
         };
 
-        let output = call_dfx(&[
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            "--output",
-            "raw",
-            "sns_root",
+        let response = self.query_or_panic(
+            self.sns_canisters.root,
             "list_sns_canisters",
-            "(record {})",
-        ]);
-        let mut hex = output.stdout;
-        while hex.last() == Some(&b'\n') || hex.last() == Some(&b'\r') {
-            hex.pop().unwrap();
-        }
-        let sns_canister_ids = Decode!(
-            &hex::decode(hex).expect("cannot parse dfx output as hex"),
-            ListSnsCanistersResponse
-        )
-        .expect("cannot parse dfx output as ListSnsCanistersResponse");
+            Encode!(&ListSnsCanistersRequest {}).unwrap(),
+        );
+        let sns_canister_ids = Decode!(&response, ListSnsCanistersResponse)
+            .expect("cannot parse the response as ListSnsCanistersResponse");
         let sns_quill_canister_ids_json = json!({
             "governance_canister_id": sns_canister_ids.governance.expect("SNS root does not return governance canister ID"),
             "index_canister_id": sns_canister_ids.index.expect("SNS root does not return index canister ID"),
@@ -534,88 +712,72 @@ impl DirectSnsDeployerForTests {
         self.print_developer_neuron_ids();
     }
 
+    /// Make a query call and return the response, or panic if the call fails.
+    fn query_or_panic(&self, canister_id: PrincipalId, method: &str, arg: Vec<u8>) -> Vec<u8> {
+        self.agent
+            .query(
+                CanisterId::unchecked_from_principal(canister_id),
+                method,
+                arg,
+            )
+            .unwrap_or_else(|err| panic!("Failed to call {} on {}: {}", method, canister_id, err))
+    }
+
     /// Call Governance's `get_nervous_system_parameters` method and print the result
     fn print_nervous_system_parameters(&self) {
         println!("Governance Nervous System Parameters:");
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            "sns_governance",
+        let response = self.query_or_panic(
+            self.sns_canisters.governance,
             "get_nervous_system_parameters",
-            "(null)",
-        ]);
+            Encode!(&()).unwrap(),
+        );
+        println!("{}", format_candid(&response));
     }
 
     /// Call the Ledger's `icrc1_metadata` method and print the result
     fn print_ledger_metadata(&self) {
         println!("Ledger metadata:");
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            "sns_ledger",
+        let response = self.query_or_panic(
+            self.sns_canisters.ledger,
             "icrc1_metadata",
-            "(record {})",
-        ]);
+            Encode!().unwrap(),
+        );
+        println!("{}", format_candid(&response));
     }
 
     /// Call the Ledger's `symbol` method and print the result
     fn print_token_symbol(&self) {
         println!("Ledger token symbol:");
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            "sns_ledger",
+        let response = self.query_or_panic(
+            self.sns_canisters.ledger,
             "icrc1_symbol",
-            "()",
-        ]);
+            Encode!().unwrap(),
+        );
+        println!("{}", format_candid(&response));
     }
 
     /// Call the Ledger's `name` method and print the result
     fn print_token_name(&self) {
         println!("Ledger token name:");
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            "sns_ledger",
-            "icrc1_name",
-            "()",
-        ]);
+        let response =
+            self.query_or_panic(self.sns_canisters.ledger, "icrc1_name", Encode!().unwrap());
+        println!("{}", format_candid(&response));
     }
 
     /// Call the Governance's `list_neurons` method and print the developer neuron IDs in hex
     fn print_developer_neuron_ids(&self) {
-        let arg = format!(
-            "(record {{of_principal = opt principal\"{}\"; limit = 0}})",
-            self.dfx_identity,
-        );
-        let output = call_dfx(&[
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            "--output",
-            "raw",
-            "sns_governance",
+        let request = ListNeurons {
+            limit: 0,
+            start_page_at: None,
+            of_principal: Some(self.agent.principal()),
+        };
+        let response = self.query_or_panic(
+            self.sns_canisters.governance,
             "list_neurons",
-            &arg,
-        ]);
-        let mut hex = output.stdout;
-        while hex.last() == Some(&b'\n') || hex.last() == Some(&b'\r') {
-            hex.pop().unwrap();
-        }
-        let neurons = Decode!(
-            &hex::decode(hex).expect("cannot parse dfx output as hex"),
-            ListNeuronsResponse
-        )
-        .expect("cannot parse dfx output as ListNeuronsResponse");
+            Encode!(&request).unwrap(),
+        );
+        let neurons = Decode!(&response, ListNeuronsResponse)
+            .expect("cannot parse the response as ListNeuronsResponse");
         let ids: Vec<String> = neurons
             .neurons
             .iter()
@@ -633,68 +795,64 @@ impl DirectSnsDeployerForTests {
         println!("Setting SNS canister controllers...");
 
         // Governance must be controlled by only Root
-        self.add_controller(self.sns_canisters.root, "sns_governance");
+        self.add_controller(self.sns_canisters.root, self.sns_canisters.governance);
 
         // Root must be controlled by only Governance
-        self.add_controller(self.sns_canisters.governance, "sns_root");
+        self.add_controller(self.sns_canisters.governance, self.sns_canisters.root);
 
         // Ledger must be controlled by only Root
-        self.add_controller(self.sns_canisters.root, "sns_ledger");
+        self.add_controller(self.sns_canisters.root, self.sns_canisters.ledger);
 
         // Swap must be controlled by the NNS root canister and control itself.
-        self.add_controller(NNS_ROOT_CANISTER_ID.get(), "sns_swap");
-        self.add_controller(self.sns_canisters.swap, "sns_swap");
+        self.add_controller(NNS_ROOT_CANISTER_ID.get(), self.sns_canisters.swap);
+        self.add_controller(self.sns_canisters.swap, self.sns_canisters.swap);
 
         // Index must be controlled by only Root
-        self.add_controller(self.sns_canisters.root, "sns_index");
+        self.add_controller(self.sns_canisters.root, self.sns_canisters.index);
 
-        // Remove default controllers from SNS canisters if not in testflight
+        // Remove the deployer from the controllers of the SNS canisters if not in testflight
         if !self.testflight {
             for sns_canister in [
-                "sns_governance",
-                "sns_root",
-                "sns_ledger",
-                "sns_swap",
-                "sns_index",
+                self.sns_canisters.governance,
+                self.sns_canisters.root,
+                self.sns_canisters.ledger,
+                self.sns_canisters.swap,
+                self.sns_canisters.index,
             ] {
-                self.remove_controller(self.wallet_canister, sns_canister);
-                self.remove_controller(self.dfx_identity, sns_canister);
+                self.remove_controller(self.agent.principal(), sns_canister);
             }
         }
     }
 
-    /// Add `controller` as a new controller of the canister given by `canister_name`.
+    /// Add `controller` as a new controller of the canister given by `canister_id`.
     /// Panics if the new controller can't be added.
-    fn add_controller(&self, controller: PrincipalId, canister_name: &str) {
-        let output = call_dfx(&[
-            "canister",
-            "--network",
-            &self.network,
-            "update-settings",
-            "--add-controller",
-            &controller.to_string(),
-            canister_name,
-        ]);
-
-        if !output.status.success() {
-            panic!(
-                "Failed to add {} as a controller of {}",
-                &controller, canister_name
-            );
-        }
+    fn add_controller(&self, controller: PrincipalId, canister_id: PrincipalId) {
+        self.agent
+            .add_controller(
+                CanisterId::unchecked_from_principal(canister_id),
+                controller,
+            )
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Failed to add {} as a controller of {}: {}",
+                    &controller, canister_id, err
+                )
+            });
     }
 
-    /// Remove `controller` as a controller of the canister given by `canister_name`
-    fn remove_controller(&self, controller: PrincipalId, canister_name: &str) {
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &self.network,
-            "update-settings",
-            "--remove-controller",
-            &controller.to_string(),
-            canister_name,
-        ]);
+    /// Remove `controller` as a controller of the canister given by `canister_id`
+    fn remove_controller(&self, controller: PrincipalId, canister_id: PrincipalId) {
+        self.agent
+            .remove_controller(
+                CanisterId::unchecked_from_principal(canister_id),
+                controller,
+            )
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Failed to remove {} as a controller of {}: {}",
+                    &controller, canister_id, err
+                )
+            });
     }
 
     /// Install the SNS canisters
@@ -708,49 +866,50 @@ impl DirectSnsDeployerForTests {
 
     /// Install and initialize Governance
     fn install_governance(&self) {
-        let init_args = hex_encode_candid(&self.sns_canister_payloads.governance);
-        self.install_canister("sns_governance", "sns-governance-canister", &init_args);
+        let init_args = Encode!(&self.sns_canister_payloads.governance).unwrap();
+        self.install_canister(
+            self.sns_canisters.governance,
+            "sns-governance-canister",
+            init_args,
+        );
     }
 
     /// Install and initialize Ledger
     fn install_ledger(&self) {
-        let init_args = hex_encode_candid(&self.sns_canister_payloads.ledger);
-        self.install_canister("sns_ledger", "ic-icrc1-ledger", &init_args);
+        let init_args = Encode!(&self.sns_canister_payloads.ledger).unwrap();
+        self.install_canister(self.sns_canisters.ledger, "ic-icrc1-ledger", init_args);
     }
 
     /// Install and initialize Root
     fn install_root(&self) {
-        let init_args = hex_encode_candid(&self.sns_canister_payloads.root);
-        self.install_canister("sns_root", "sns-root-canister", &init_args);
+        let init_args = Encode!(&self.sns_canister_payloads.root).unwrap();
+        self.install_canister(self.sns_canisters.root, "sns-root-canister", init_args);
     }
 
     /// Install and initialize Swap
     fn install_swap(&self) {
-        let init_args = hex_encode_candid(&self.sns_canister_payloads.swap);
-        self.install_canister("sns_swap", "sns-swap-canister", &init_args);
+        let init_args = Encode!(&self.sns_canister_payloads.swap).unwrap();
+        self.install_canister(self.sns_canisters.swap, "sns-swap-canister", init_args);
     }
 
     /// Install and initialize Index
     fn install_index(&self) {
-        let init_args = hex_encode_candid(&self.sns_canister_payloads.index);
-        self.install_canister("sns_index", "ic-icrc1-index", &init_args);
+        let init_args = Encode!(&self.sns_canister_payloads.index).unwrap();
+        self.install_canister(self.sns_canisters.index, "ic-icrc1-index", init_args);
     }
 
     /// Install the given canister
-    fn install_canister(&self, sns_canister_name: &str, wasm_name: &str, init_args: &str) {
+    fn install_canister(&self, canister_id: PrincipalId, wasm_name: &str, init_args: Vec<u8>) {
         let mut wasm = self.wasms_dir.clone();
         wasm.push(format!("{}.wasm", wasm_name));
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &self.network,
-            "install",
-            "--argument-type=raw",
-            "--argument",
-            init_args,
-            "--wasm",
-            &wasm.into_os_string().into_string().unwrap(),
-            sns_canister_name,
-        ]);
+        let wasm_module = std::fs::read(&wasm)
+            .unwrap_or_else(|err| panic!("Failed to read {}: {}", wasm.display(), err));
+        self.agent
+            .install_code(
+                CanisterId::unchecked_from_principal(canister_id),
+                wasm_module,
+                init_args,
+            )
+            .unwrap_or_else(|err| panic!("Failed to install {}: {}", wasm_name, err));
     }
 }
@@ -1,14 +1,14 @@
 use crate::{
+    agent::{format_candid, AgentArgs, CliAgent},
     deploy::{DirectSnsDeployerForTests, SnsWasmSnsDeployer},
     init_config_file::InitConfigFileArgs,
     prepare_canisters::PrepareCanistersArgs,
     propose::ProposeArgs,
 };
-use candid::{CandidType, Decode, Encode, IDLArgs};
+use candid::{CandidType, Decode, Encode, Nat};
 use clap::Parser;
-use ic_base_types::PrincipalId;
+use ic_base_types::{CanisterId, PrincipalId};
 use ic_crypto_sha2::Sha256;
-use ic_nervous_system_common_test_keys::TEST_NEURON_1_OWNER_KEYPAIR;
 use ic_nervous_system_proto::pb::v1::GlobalTimeOfDay;
 use ic_nns_constants::{GOVERNANCE_CANISTER_ID, SNS_WASM_CANISTER_ID};
 use ic_nns_governance::{
@@ -20,20 +20,19 @@ use ic_nns_governance::{
     proposals::create_service_nervous_system::ExecutedCreateServiceNervousSystemProposal,
 };
 use ic_sns_init::pb::v1::SnsInitPayload;
-use ic_sns_wasm::pb::v1::{AddWasmRequest, SnsCanisterType, SnsWasm};
-use icp_ledger::{AccountIdentifier, BinaryAccountBalanceArgs};
-use std::sync::Once;
+use ic_sns_wasm::pb::v1::{AddWasmRequest, AddWasmResponse, SnsCanisterType, SnsWasm};
+use icrc_ledger_types::icrc1::account::Account;
 use std::{
-    fmt::{Debug, Display},
+    fmt::Debug,
     fs::File,
-    io::{Read, Write},
+    io::Read,
     path::{Path, PathBuf},
-    process::{exit, Command, Output},
+    process::exit,
     str::FromStr,
     time::{SystemTime, UNIX_EPOCH},
 };
-use tempfile::NamedTempFile;
 
+pub mod agent;
 pub mod deploy;
 pub mod init_config_file;
 pub mod prepare_canisters;
@@ -43,11 +42,6 @@ pub mod unit_helpers;
 #[cfg(test)]
 mod tests;
 
-/// We use a giant tail to avoid colliding with/stomping on identity that a user
-/// might have created for themselves.
-const TEST_NEURON_1_OWNER_DFX_IDENTITY_NAME: &str =
-    "test-neuron-1-owner__b2ucp-4x6ou-zvxwi-niymn-pvllt-rdxqr-wi4zj-jat5l-ijt2s-vv4f5-4ae";
-
 #[derive(Debug, Parser)]
 #[clap(
     name = "sns-cli",
@@ -90,34 +84,28 @@ pub struct DeployArgs {
     #[structopt(long)]
     verbose: bool,
 
-    /// The network to deploy to. This can be "local", "ic", or the URL of an IC network.
-    #[structopt(default_value = "local", long)]
-    network: String,
+    #[clap(flatten)]
+    pub agent: AgentArgs,
 
     /// The initial config file, this file should have all the necessary parameters to deploy an SNS.
     /// See command "init-config-file"
     #[clap(long, parse(from_os_str))]
     pub init_config_file: PathBuf,
 
-    /// The canister ID or name (via dfx.json) of SNS-WASMS to use instead of the default
+    /// The canister ID or name (via canister_ids.json) of SNS-WASMS to use instead of the default
     ///
     /// This is useful for testing CLI commands against local replicas without fully deployed NNS
     #[clap(long)]
     pub override_sns_wasm_canister_id_for_tests: Option<String>,
 
-    /// The canister ID of the wallet to use on this subnet.
+    /// The canister ID of the cycles wallet that pays the SNS creation fee, or that creates the
+    /// SNS canisters when skipping SNS-WASM.
     ///
-    /// This is needed in testnet scenarios or if you want to use a different wallet than the one
-    /// currently set for your dfx identity.
+    /// Required by the deploy command, and on networks other than local. The identity given by
+    /// --pem-file or --use-hsm must be a custodian of this wallet.
     #[clap(long)]
     pub wallet_canister_override: Option<PrincipalId>,
 
-    /// The SNS-WASMS canister candid, used to parse canister IDs from the response.
-    ///
-    /// Corresponds to the dfx canister call --candid flag.
-    #[clap(long)]
-    pub candid: Option<String>,
-
     /// The amount of cycles to initialize each SNS canister with. This can be omitted when
     /// deploying locally.
     #[structopt(long)]
@@ -151,24 +139,23 @@ pub struct DeployTestflightArgs {
     #[structopt(long)]
     verbose: bool,
 
-    /// The network to deploy to. This can be "local", "ic", or the URL of an IC network.
-    #[structopt(default_value = "local", long)]
-    network: String,
+    #[clap(flatten)]
+    pub agent: AgentArgs,
 
     /// The initial config file, this file should have all the necessary parameters to deploy an SNS.
     /// See command "init-config-file"
     #[clap(long, parse(from_os_str))]
     pub init_config_file: Option<PathBuf>,
 
-    /// The canister ID of the wallet to use on this subnet.
+    /// The canister ID of the cycles wallet that creates the SNS canisters.
     ///
-    /// This is needed in testnet scenarios or if you want to use a different wallet than the one
-    /// currently set for your dfx identity.
+    /// Required on networks other than local, where canisters cannot be created through the
+    /// provisional API. The identity given by --pem-file or --use-hsm must be a custodian of
+    /// this wallet.
     #[clap(long)]
     pub wallet_canister_override: Option<PrincipalId>,
 
-    /// The amount of cycles to initialize each SNS canister with. This can be omitted when
-    /// deploying locally.
+    /// The amount of cycles to initialize each SNS canister with.
     #[structopt(default_value = "500000000000", long)]
     initial_cycles_per_canister: u64,
 
@@ -187,14 +174,13 @@ pub struct DeployTestflightArgs {
 #[derive(Debug, Parser)]
 pub struct AccountBalanceArgs {
     /// The principal ID of the account owner to display their main account balance (note that
-    /// subaccounts are not yet supported). If not specified, the principal of the current dfx
-    /// identity is used.
+    /// subaccounts are not yet supported). If not specified, the principal of the identity given
+    /// by --pem-file or --use-hsm is used.
     #[clap(long)]
     pub principal_id: Option<String>,
 
-    /// The network to deploy to. This can be "local", "ic", or the URL of an IC network.
-    #[structopt(default_value = "local", long)]
-    network: String,
+    #[clap(flatten)]
+    pub agent: AgentArgs,
 }
 
 #[derive(Debug, Parser)]
@@ -210,8 +196,8 @@ pub struct AddSnsWasmForTestsArgs {
     #[clap(long)]
     pub override_sns_wasm_canister_id_for_tests: Option<String>,
 
-    #[structopt(default_value = "local", long)]
-    network: String,
+    #[clap(flatten)]
+    pub agent: AgentArgs,
 }
 
 impl DeployArgs {
@@ -332,7 +318,8 @@ pub fn deploy(args: DeployArgs) {
         );
         exit(1);
     });
-    SnsWasmSnsDeployer::new(args, sns_init_payload).deploy();
+    let agent = new_agent_or_exit(&args.agent);
+    SnsWasmSnsDeployer::new(args, sns_init_payload, agent).deploy();
 }
 
 /// Deploy an SNS with the given DeployArgs, skipping sns-wasm.
@@ -344,7 +331,10 @@ pub fn deploy_skipping_sns_wasms_for_tests(args: DeployArgs) {
         );
         exit(1);
     });
-    DirectSnsDeployerForTests::new(args, sns_init_payload).deploy()
+    let agent = new_agent_or_exit(&args.agent);
+    if let Some(deployer) = DirectSnsDeployerForTests::new(args, sns_init_payload, agent) {
+        deployer.deploy()
+    }
 }
 
 /// Deploy an SNS with the given DeployTestflightArgs, skipping sns-wasm.
@@ -357,7 +347,11 @@ pub fn deploy_testflight(args: DeployTestflightArgs) {
         );
         exit(1);
     });
-    DirectSnsDeployerForTests::new_testflight(args, sns_init_payload).deploy()
+    let agent = new_agent_or_exit(&args.agent);
+    if let Some(deployer) = DirectSnsDeployerForTests::new_testflight(args, sns_init_payload, agent)
+    {
+        deployer.deploy()
+    }
 }
 
 pub fn add_sns_wasm_for_tests(args: AddSnsWasmForTestsArgs) {
@@ -389,153 +383,70 @@ pub fn add_sns_wasm_for_tests(args: AddSnsWasmForTestsArgs) {
     let sns_wasms_canister_id = args
         .override_sns_wasm_canister_id_for_tests
         .as_ref()
-        .map(|principal| PrincipalId::from_str(principal).unwrap())
-        .unwrap_or_else(|| SNS_WASM_CANISTER_ID.get());
-
-    let idl = IDLArgs::from_bytes(&Encode!(&add_sns_wasm_request).unwrap()).unwrap();
-    let mut argument_file = NamedTempFile::new().expect("Could not open temp file");
-    argument_file
-        .write_all(format!("{}", idl).as_bytes())
-        .expect("Could not write wasm to temp file");
-    let argument_path = argument_file.path().as_os_str().to_str().unwrap();
-
-    call_dfx_or_panic(&[
-        "canister",
-        "--network",
-        &args.network,
-        "call",
-        "--argument-file",
-        argument_path,
-        &sns_wasms_canister_id.to_string(),
-        "add_wasm",
-    ]);
+        .map(|principal| CanisterId::from_str(principal).unwrap())
+        .unwrap_or(SNS_WASM_CANISTER_ID);
+
+    impl Request for AddWasmRequest {
+        type Response = AddWasmResponse;
+        const METHOD_NAME: &'static str = "add_wasm";
+    }
+
+    let agent = new_agent_or_exit(&args.agent);
+    let response = Canister::new(&agent, sns_wasms_canister_id)
+        .call(&add_sns_wasm_request)
+        .unwrap_or_else(|err| panic!("Failed to add the SNS wasm: {:?}", err));
+    if let Some(response) = response {
+        println!("{:?}", response);
+    }
 }
 
-/// Print the Ledger account balance of the principal in `AccountBalanceArgs` if given, else
-/// print the account balance of the principal of the current dfx identity.
+/// Print the SNS Ledger account balance of the principal in `AccountBalanceArgs` if given, else
+/// print the account balance of the principal of the identity that makes the calls.
 pub fn print_account_balance(args: AccountBalanceArgs) {
+    let agent = new_agent_or_exit(&args.agent);
     let principal_id = if let Some(principal_str) = args.principal_id {
         PrincipalId::from_str(&principal_str)
             .unwrap_or_else(|_| panic!("Could not parse {} as a PrincipalId", principal_str))
     } else {
-        get_identity("get-principal", &args.network)
+        agent.principal()
     };
 
-    let account: AccountIdentifier = principal_id.into();
-    let account_balance_args = BinaryAccountBalanceArgs {
-        account: account.to_address(),
+    let sns_ledger_canister_id = deploy::get_canister_id("sns_ledger", true, agent.network())
+        .unwrap_or_else(|| {
+            eprintln!("Unable to find the ID of the sns_ledger canister.");
+            exit(1);
+        });
+    let account = Account {
+        owner: principal_id.0,
+        subaccount: None,
     };
 
-    let idl = IDLArgs::from_bytes(&Encode!(&account_balance_args).unwrap()).unwrap();
-
-    call_dfx_or_panic(&[
-        "canister",
-        "--network",
-        &args.network,
-        "call",
-        "sns_ledger",
-        "account_balance",
-        &format!("{}", idl),
-    ]);
-}
-
-/// Return the `PrincipalId` of the given dfx identity
-pub fn get_identity(identity: &str, network: &str) -> PrincipalId {
-    println!("dfx identity {}", identity);
-    let output = call_dfx(&["identity", "--network", network, identity]);
-
-    let canister_id = String::from_utf8(output.stdout).unwrap_or_else(|_| {
-        panic!(
-            "Could not parse the output of 'dfx identity {}' as a string",
-            identity
+    let balance = agent
+        .query(
+            CanisterId::unchecked_from_principal(sns_ledger_canister_id),
+            "icrc1_balance_of",
+            Encode!(&account).unwrap(),
         )
+        .unwrap_or_else(|err| {
+            eprintln!("Unable to fetch the balance of {}: {}", account, err);
+            exit(1);
+        });
+    let balance = Decode!(&balance, Nat).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to decode the balance {}: {}",
+            format_candid(&balance),
+            err,
+        );
+        exit(1);
     });
-
-    PrincipalId::from_str(canister_id.trim()).unwrap_or_else(|_| {
-        panic!(
-            "Could not parse the output of 'dfx identity {}' as a PrincipalId",
-            identity
-        )
-    })
-}
-
-#[must_use]
-struct SaveOriginalDfxIdentityAndRestoreOnExit {
-    original_identity: String,
-}
-
-impl SaveOriginalDfxIdentityAndRestoreOnExit {
-    fn new_or_panic() -> Self {
-        let original_identity = String::from_utf8(call_dfx(&["identity", "whoami"]).stdout)
-            .expect("Unable to determine which dfx identity is currently in use.")
-            .trim_end()
-            .to_string();
-
-        Self { original_identity }
-    }
-}
-
-impl Drop for SaveOriginalDfxIdentityAndRestoreOnExit {
-    fn drop(&mut self) {
-        // Restore the current identity to what it was originally (when self was
-        // created).
-        call_dfx_or_panic(&["identity", "use", &self.original_identity]);
-    }
-}
-
-/// The argument is not actually used. As the name implies, it is required just
-/// to make sure that the caller has saved and will restore the prior dfx
-/// identity.
-fn use_test_neuron_1_owner_identity(
-    _caller_must_checkpoint: &SaveOriginalDfxIdentityAndRestoreOnExit,
-) -> Result<(), String> {
-    import_test_neuron_1_owner()?;
-
-    let (_stdout, _stderr) = run_command(&[
-        "dfx",
-        "identity",
-        "use",
-        TEST_NEURON_1_OWNER_DFX_IDENTITY_NAME,
-    ])
-    .map_err(|err| err.new_report())?;
-
-    Ok(())
+    println!("{}", balance);
 }
 
-fn import_test_neuron_1_owner() -> Result<(), String> {
-    // Step 1: Save secret key belonging to TEST_NEURON_1_OWNER to a (temporary) pem file.
-    let contents: String = TEST_NEURON_1_OWNER_KEYPAIR.to_pem();
-    let mut pem_file = NamedTempFile::new().expect("Unable to create a temporary file.");
-    pem_file
-        .write_all(contents.as_bytes())
-        .map_err(|err| format!("{}\n\nUnable to write to (temporary) file.", err))?;
-    let pem_file_path = pem_file
-        .path()
-        .to_str()
-        .ok_or("Unable to convert path of TEST_NEURON_1_OWNER's pem file to a String?!")?;
-
-    // Step 2: Call dfx identity import.
-    let command = [
-        "dfx",
-        "identity",
-        "import",
-        "--force",
-        // Needed to avoid forcing the user to choose a password (with an
-        // invisible prompt).
-        "--storage-mode=plaintext",
-        TEST_NEURON_1_OWNER_DFX_IDENTITY_NAME,
-        pem_file_path,
-    ];
-    let result = run_command(&command);
-
-    // Step 3: Convert result.
-    result.map(|_ok| ()).map_err(|err| {
-        format!(
-            "{}\n\
-             \n\
-             Unable to import test-neuron-1-owner dfx identity from pem file.",
-            err,
-        )
+/// Connects to the network given by `args`, or exits if the arguments are invalid.
+fn new_agent_or_exit(args: &AgentArgs) -> CliAgent {
+    args.new_agent().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
     })
 }
 
@@ -557,7 +468,7 @@ trait Request {
     // Perhaps, we can also require fn name() and fn response_name() ?
 }
 
-/// A nice way to make canister calls (via dfx).
+/// A nice way to make canister calls.
 ///
 /// How to use this:
 ///
@@ -568,111 +479,58 @@ trait Request {
 ///      method calls nice.
 ///
 /// Example: see NnsGovernanceCanister::make_proposal.
-#[derive(Clone, Debug)]
-struct Canister {
-    network: String,
-    name: String,
+struct Canister<'a> {
+    agent: &'a CliAgent,
+    canister_id: CanisterId,
 }
 
 #[derive(Debug)]
 enum CanisterCallError {
-    UnableToPrepareDfxCall(String),
-    UnableToCallDfx(std::io::Error),
-    DfxBadExit(std::process::Output),
+    RequestEncodeFail(String),
+    CallFail(String),
     ResponseDecodeFail(String),
 }
 
-impl Canister {
-    /// Arguments are like those that are passed to `dfx canister`.
-    pub fn new(network: &str, name: &str) -> Self {
-        // TODO: Validate arguments.
-
-        let network = network.to_string();
-        let name = name.to_string();
-
-        Self { network, name }
+impl<'a> Canister<'a> {
+    pub fn new(agent: &'a CliAgent, canister_id: CanisterId) -> Self {
+        Self { agent, canister_id }
     }
 
-    pub fn call<Req>(&self, request: &Req) -> Result<Req::Response, CanisterCallError>
+    /// Makes an update call. Returns `None` in a dry run, in which case the
+    /// request is printed instead of sent.
+    pub fn call<Req>(&self, request: &Req) -> Result<Option<Req::Response>, CanisterCallError>
     where
         Req: Request + CandidType,
-        <Req as Request>::Response: CandidType + for<'a> candid::Deserialize<'a>,
+        <Req as Request>::Response: CandidType + for<'de> candid::Deserialize<'de>,
     {
-        // Step 1: Write request to temporary argument file, which we'll later
-        // pass to `dfx canister call --argument-file`.
-        let request = Encode!(&request).map_err(|err| {
-            CanisterCallError::UnableToPrepareDfxCall(format!(
-                "Unable to serialize request: {}",
-                err,
-            ))
-        })?;
-        let request = IDLArgs::from_bytes(&request).map_err(|err| {
-            CanisterCallError::UnableToPrepareDfxCall(format!("Unable to format request: {}", err,))
-        })?;
-        let request = format!("{}", request);
-        let mut argument_file = NamedTempFile::new().map_err(|err| {
-            CanisterCallError::UnableToPrepareDfxCall(format!(
-                "Could not create temporary argument file: {}",
-                err,
-            ))
-        })?;
-        argument_file.write_all(request.as_bytes()).map_err(|err| {
-            CanisterCallError::UnableToPrepareDfxCall(format!(
-                "Unable to write request to local file: {}",
-                err,
-            ))
-        })?;
-        let argument_file = argument_file.path().as_os_str().to_str().ok_or_else(|| {
-            CanisterCallError::UnableToPrepareDfxCall(
-                "Unable to determine the path of the argument file.".to_string(),
-            )
+        // Step 1: Encode request.
+        let request = Encode!(request).map_err(|err| {
+            CanisterCallError::RequestEncodeFail(format!("Unable to serialize request: {}", err))
         })?;
 
         // Step 2: The real work of making the call takes place here.
-        let command = [
-            "dfx",
-            "canister",
-            "--network",
-            &self.network,
-            "call",
-            &self.name,
-            Req::METHOD_NAME,
-            "--argument-file",
-            argument_file,
-            "--output=raw",
-        ];
-        let result = run_command(&command);
-
-        // Step 3: Handle errors.
-        let (stdout, _stderr) = result.map_err(|err| match err {
-            RunCommandError::UnableToRunCommand { error, .. } => {
-                CanisterCallError::UnableToCallDfx(error)
-            }
-            RunCommandError::UnsuccessfulExit { output, .. } => {
-                CanisterCallError::DfxBadExit(output)
-            }
-        })?;
-
-        // Step 4: Decode and return response (finally!).
-
-        let response = stdout.trim_end();
-        let response = hex::decode(response).map_err(|err| {
-            CanisterCallError::ResponseDecodeFail(format!(
-                "Unable to hex decode the response. reason: {}. response:\n{:?}",
-                err, response,
-            ))
-        })?;
-        Decode!(&response, Req::Response).map_err(|err| {
+        let response = self
+            .agent
+            .update(self.canister_id, Req::METHOD_NAME, request)
+            .map_err(CanisterCallError::CallFail)?;
+
+        // Step 3: Decode and return response (finally!).
+        let response = match response {
+            Some(response) => response,
+            None => return Ok(None),
+        };
+        Decode!(&response, Req::Response).map(Some).map_err(|err| {
             CanisterCallError::ResponseDecodeFail(format!(
-                "Candid deserialization of response failed. reason: {}. response:\n{:?}",
-                err, response,
+                "Candid deserialization of response failed. reason: {}. response:\n{}",
+                err,
+                format_candid(&response),
             ))
         })
     }
 }
 
-struct NnsGovernanceCanister {
-    canister: Canister,
+struct NnsGovernanceCanister<'a> {
+    canister: Canister<'a>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -682,20 +540,20 @@ enum MakeProposalError {
     InvalidResponse(ManageNeuronResponse),
 }
 
-impl NnsGovernanceCanister {
-    pub fn new(network: &str) -> Self {
-        let canister = Canister::new(network, &GOVERNANCE_CANISTER_ID.to_string());
+impl<'a> NnsGovernanceCanister<'a> {
+    pub fn new(agent: &'a CliAgent) -> Self {
+        let canister = Canister::new(agent, GOVERNANCE_CANISTER_ID);
 
         Self { canister }
     }
 
-    /// Neuron used is the one whose subaccount is associated with the current dfx principal with nonce = 0.
+    /// Makes `proposal` with the `proposer` neuron. Returns `None` in a dry run.
     #[allow(clippy::result_large_err)]
     pub fn make_proposal(
         &self,
         proposer: &NeuronIdOrSubaccount,
         proposal: &Proposal,
-    ) -> Result<MakeProposalResponse, MakeProposalError> {
+    ) -> Result<Option<MakeProposalResponse>, MakeProposalError> {
         impl Request for ManageNeuron {
             type Response = ManageNeuronResponse;
             const METHOD_NAME: &'static str = "manage_neuron";
@@ -718,208 +576,28 @@ impl NnsGovernanceCanister {
             .map_err(MakeProposalError::CanisterCallError)?;
 
         // Step 3: Unwrap the response.
+        let manage_neuron_response = match manage_neuron_response {
+            Some(manage_neuron_response) => manage_neuron_response,
+            None => return Ok(None),
+        };
         match manage_neuron_response.command {
-            Some(manage_neuron_response::Command::MakeProposal(response)) => Ok(response),
+            Some(manage_neuron_response::Command::MakeProposal(response)) => Ok(Some(response)),
             _ => Err(MakeProposalError::InvalidResponse(manage_neuron_response)),
         }
     }
 }
 
-fn fetch_canister_controllers_or_exit(network: &str, canister_id: PrincipalId) -> Vec<PrincipalId> {
-    let command = [
-        "dfx",
-        "canister",
-        "--network",
-        network,
-        "info",
-        &canister_id.to_string(),
-    ];
-    let (stdout, _stderr) = run_command(&command).unwrap_or_else(|err| {
-        eprintln!("{}", err);
-        std::process::exit(1);
-    });
-
-    // Parse dfx output. More precisely, look for a line that begins with
-    // "Controllers:".
-    for line in stdout.lines() {
-        let tail = match line.strip_prefix("Controllers:") {
-            None => continue,
-            Some(tail) => tail,
-        };
-
-        return tail
-            .trim()
-            .split(' ')
-            .map(|controller_principal_id| {
-                PrincipalId::from_str(controller_principal_id.trim()).unwrap_or_else(|err| {
-                    eprintln!(
-                        "stdout:\n\
-                         {}\n\
-                         Unable to parse {:?} as a principal ID from the `Controllers:` \
-                         output line of dfx canister info. err = {:?}",
-                        stdout, controller_principal_id, err,
-                    );
-                    std::process::exit(1);
-                })
-            })
-            .collect();
-    }
-
-    // No lines in stdout matched -> fail :(
-    eprintln!(
-        "Unable to determine controllers of {} based on output of dfx:\n{}",
-        canister_id, stdout,
-    );
-    std::process::exit(1);
-}
-
-enum RunCommandError<'a> {
-    UnableToRunCommand {
-        command: &'a [&'a str],
-        error: std::io::Error,
-    },
-
-    UnsuccessfulExit {
-        command: &'a [&'a str],
-        output: std::process::Output,
-    },
-}
-
-impl<'a> Display for RunCommandError<'a> {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(formatter, "{}", self.new_report())
-    }
-}
-
-impl<'a> RunCommandError<'a> {
-    fn new_report(&self) -> String {
-        match self {
-            RunCommandError::UnableToRunCommand { command, error } => {
-                RunCommandError::new_unable_to_run_command_report(command, error)
-            }
-            RunCommandError::UnsuccessfulExit { command, output } => {
-                RunCommandError::new_unsuccessful_exit_report(command, output)
-            }
-        }
-    }
-
-    fn new_unable_to_run_command_report(command: &[&str], error: &std::io::Error) -> String {
-        format!(
-            "command:\n\
-             {}\n\
-             \n\
-             error:\n\
-             {}\n\
-             \n\
-             `{}` command did not run at all.",
-            command.join(" \\n  "),
-            error,
-            command[0],
-        )
-    }
-
-    fn new_unsuccessful_exit_report(command: &[&str], output: &std::process::Output) -> String {
-        let std::process::Output {
-            status,
-            stdout,
-            stderr,
-        } = output;
-
-        let stdout = String::from_utf8_lossy(stdout);
-        let stderr = String::from_utf8_lossy(stderr);
-
-        format!(
-            "command:\n\
-             {}\n\
-             \n\
-             stdout:\n\
-             {}\n\
-             \n\
-             stderr:\n\
-             {}\n\
-             \n\
-             status: {}",
-            command.join(" \\n  "),
-            stdout,
-            stderr,
-            status,
-        )
-    }
-}
-
-fn run_command<'a>(command: &'a [&'a str]) -> Result<(String, String), RunCommandError<'a>> {
-    let output = std::process::Command::new(command[0])
-        .args(&command[1..command.len()])
-        .output()
-        .map_err(|error| RunCommandError::UnableToRunCommand { command, error })?;
-
-    let std::process::Output {
-        status,
-        stdout,
-        stderr,
-    } = &output;
-
-    if !status.success() {
-        return Err(RunCommandError::UnsuccessfulExit { command, output });
-    }
-
-    let stdout = String::from_utf8_lossy(stdout).to_string();
-    let stderr = String::from_utf8_lossy(stderr).to_string();
-    Ok((stdout, stderr))
-}
-
-static DFX_INIT: Once = Once::new();
-
-/// Calls `dfx` with the given args
-#[must_use]
-fn call_dfx(args: &[&str]) -> Output {
-    let dfx_cmd = if Path::new("./dfx").exists() {
-        "./dfx"
-    } else {
-        "dfx"
-    };
-    DFX_INIT.call_once(|| {
-        let version_output = Command::new(dfx_cmd)
-            .args(["--version"])
-            .output()
-            .expect("Failed to execute dfx command");
-        println!(
-            "Invoking dfx with `{dfx_cmd}`. dfx version: {}",
-            String::from_utf8_lossy(&version_output.stdout).trim()
-        );
-    });
-
-    let output = Command::new(dfx_cmd)
-        .args(args)
-        .output()
-        .unwrap_or_else(|e| panic!("dfx failed when called with args: {:?}: {}", args, e));
-
-    // Some dfx commands output stderr instead of stdout, so we assign it for use in both
-    // success and error cases below.
-    let std_err = String::from_utf8_lossy(&output.stderr);
-
-    if output.status.success() {
-        print!("{}", String::from_utf8_lossy(&output.stdout));
-        print!("{}", std_err);
-    } else {
-        println!(
-            "dfx failed when called with args: {:?}, error: {}",
-            args, std_err
-        );
-    }
-
-    output
-}
-
-fn call_dfx_or_panic(args: &[&str]) {
-    let output = call_dfx(args);
-
-    // No need to include diagnostics, because call_dfx already prints stderr.
-    assert!(output.status.success());
-}
-
-/// Given a `CandidType`, return the hex encoding of this object.
-pub fn hex_encode_candid(candid: impl CandidType) -> String {
-    let bytes = Encode!(&candid).unwrap();
-    hex::encode(bytes)
+fn fetch_canister_controllers_or_exit(
+    agent: &CliAgent,
+    canister_id: CanisterId,
+) -> Vec<PrincipalId> {
+    agent
+        .canister_controllers(canister_id)
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "{}\n\nUnable to determine the controllers of {}.",
+                err, canister_id,
+            );
+            exit(1);
+        })
 }
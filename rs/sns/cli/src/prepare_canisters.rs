@@ -1,13 +1,12 @@
-use crate::call_dfx_or_panic;
+use crate::{agent::AgentArgs, new_agent_or_exit};
 use clap::Parser;
-use ic_base_types::PrincipalId;
+use ic_base_types::{CanisterId, PrincipalId};
 use ic_nns_constants::ROOT_CANISTER_ID;
 
 #[derive(Debug, Parser)]
 pub struct PrepareCanistersArgs {
-    /// The network to deploy to. This can be "local", "ic", or the URL of an IC network.
-    #[structopt(default_value = "local", long)]
-    network: String,
+    #[clap(flatten)]
+    pub agent: AgentArgs,
 
     #[clap(subcommand)]
     sub_command: SubCommand,
@@ -29,20 +28,28 @@ enum SubCommand {
 }
 
 pub fn exec(args: PrepareCanistersArgs) {
-    let (operation, sub_args) = match &args.sub_command {
-        SubCommand::AddNnsRoot(sub_args) => ("--add-controller", sub_args),
-        SubCommand::RemoveNnsRoot(sub_args) => ("--remove-controller", sub_args),
-    };
+    let agent = new_agent_or_exit(&args.agent);
 
-    for canister in &sub_args.canisters {
-        call_dfx_or_panic(&[
-            "canister",
-            "--network",
-            &args.network,
-            "update-settings",
-            operation,
-            &ROOT_CANISTER_ID.to_string(),
-            &canister.to_string(),
-        ]);
+    for canister in args.sub_command.canisters() {
+        let canister_id = CanisterId::unchecked_from_principal(*canister);
+        let result = match &args.sub_command {
+            SubCommand::AddNnsRoot(_) => agent.add_controller(canister_id, ROOT_CANISTER_ID.get()),
+            SubCommand::RemoveNnsRoot(_) => {
+                agent.remove_controller(canister_id, ROOT_CANISTER_ID.get())
+            }
+        };
+        result.unwrap_or_else(|err| {
+            panic!("Failed to update the controllers of {}: {}", canister, err)
+        });
+    }
+}
+
+impl SubCommand {
+    fn canisters(&self) -> &[PrincipalId] {
+        match self {
+            SubCommand::AddNnsRoot(sub_args) | SubCommand::RemoveNnsRoot(sub_args) => {
+                &sub_args.canisters
+            }
+        }
     }
 }
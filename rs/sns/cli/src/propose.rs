@@ -1,10 +1,12 @@
 use crate::{
-    fetch_canister_controllers_or_exit, get_identity, use_test_neuron_1_owner_identity,
-    MakeProposalResponse, NnsGovernanceCanister, SaveOriginalDfxIdentityAndRestoreOnExit,
+    agent::{AgentArgs, CliAgent},
+    fetch_canister_controllers_or_exit, MakeProposalResponse, NnsGovernanceCanister,
 };
 use clap::{ArgGroup, Parser};
 use ic_base_types::{CanisterId, PrincipalId};
+use ic_canister_client::Sender;
 use ic_nervous_system_common::ledger::compute_neuron_staking_subaccount_bytes;
+use ic_nervous_system_common_test_keys::TEST_NEURON_1_OWNER_KEYPAIR;
 use ic_nns_common::pb::v1::{NeuronId, ProposalId};
 use ic_nns_constants::ROOT_CANISTER_ID;
 use ic_nns_governance::pb::v1::{manage_neuron::NeuronIdOrSubaccount, proposal::Action, Proposal};
@@ -22,19 +24,17 @@ mod propose_tests;
 #[derive(Debug, Parser)]
 #[clap(group(ArgGroup::new("neuron-selection").multiple(false).required(true)))]
 pub struct ProposeArgs {
-    /// The network to deploy to. This can be "local", "ic", or the URL of an IC
-    /// network.
-    #[structopt(default_value = "local", long)]
-    network: String,
+    #[clap(flatten)]
+    pub agent: AgentArgs,
 
     /// Path to a configuration file specifying the SNS to be created.
     #[clap(parse(from_os_str), default_value = "sns_init.yaml")]
     pub init_config_file: PathBuf,
 
-    /// The neuron with which to make the proposal. The current dfx identity
-    /// must be able to operate this neuron. If not specified, it will be
-    /// assumed that the current dfx identity has a neuron with memo == 0.
-    /// --neuron_memo is an alternative to this.
+    /// The neuron with which to make the proposal. The identity given by
+    /// --pem-file or --use-hsm must be able to operate this neuron. If not
+    /// specified, it will be assumed that this identity has a neuron with
+    /// memo == 0. --neuron_memo is an alternative to this.
     #[clap(long, group = "neuron-selection")]
     pub neuron_id: Option<u64>,
 
@@ -50,7 +50,7 @@ pub struct ProposeArgs {
     /// useful when running against a local instance of NNS (when deployed as
     /// described in the sns-testing Github repo). In addition to specifying
     /// which neuron to propose with, this also controls the principal that
-    /// sends the request.
+    /// sends the request (overriding --pem-file and --use-hsm).
     #[clap(long, group = "neuron-selection")]
     pub test_neuron_proposer: bool,
 
@@ -68,7 +68,7 @@ pub struct ProposeArgs {
 
 pub fn exec(args: ProposeArgs) {
     let ProposeArgs {
-        network,
+        agent,
         init_config_file,
         neuron_id,
         neuron_memo,
@@ -76,8 +76,18 @@ pub fn exec(args: ProposeArgs) {
         test_neuron_proposer,
     } = args;
 
+    let agent = if test_neuron_proposer {
+        agent.new_agent_with_sender(Sender::from_keypair(&TEST_NEURON_1_OWNER_KEYPAIR))
+    } else {
+        agent.new_agent()
+    }
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
     // Step 0: Load configuration
-    let proposal = load_configuration_and_validate_or_exit(&network, &init_config_file);
+    let proposal = load_configuration_and_validate_or_exit(&agent, &init_config_file);
 
     // Step 1: Ensure the save-to file exists and is writeable if specified.
     // We do this check without writing the file to ensure the best chance of successfully
@@ -94,45 +104,38 @@ pub fn exec(args: ProposeArgs) {
     eprintln!(
         "Sending proposal with title {:?} to NNS (--network={})...",
         proposal.title.as_ref().unwrap_or(&"".to_string()),
-        network,
+        agent.network(),
     );
-    let checkpoint = SaveOriginalDfxIdentityAndRestoreOnExit::new_or_panic();
     let proposer = if let Some(id) = neuron_id {
         NeuronIdOrSubaccount::NeuronId(NeuronId { id })
     } else if test_neuron_proposer {
-        if let Err(err) = use_test_neuron_1_owner_identity(&checkpoint) {
-            eprintln!(
-                "{}\n\
-                 \n\
-                 Failed to (import and) use test-neuron-1-owner dfx identity.",
-                err,
-            );
-            std::process::exit(1);
-        }
         NeuronIdOrSubaccount::NeuronId(NeuronId {
             id: TEST_NEURON_1_ID,
         })
     } else {
         let subaccount = compute_neuron_staking_subaccount_bytes(
-            get_identity("get-principal", &network),
+            agent.principal(),
             neuron_memo.unwrap_or_default(),
         );
         NeuronIdOrSubaccount::Subaccount(subaccount.to_vec())
     };
-    let result = NnsGovernanceCanister::new(&network).make_proposal(&proposer, &proposal);
+    let result = NnsGovernanceCanister::new(&agent).make_proposal(&proposer, &proposal);
 
     // Step 3: Report result.
     println!();
     match result {
-        Ok(MakeProposalResponse {
+        Ok(None) => {
+            println!("Dry run: the proposal was not submitted.");
+        }
+        Ok(Some(MakeProposalResponse {
             proposal_id: Some(proposal_id),
             message,
-        }) => {
+        })) => {
             println!("🚀 Success!");
             if let Some(message) = message {
                 println!("Message from NNS governance: {:?}", message);
             }
-            if network == "ic" {
+            if agent.network() == "ic" {
                 println!("View the proposal here:");
                 println!(
                     "https://dashboard.internetcomputer.org/proposal/{}",
@@ -162,7 +165,7 @@ pub fn exec(args: ProposeArgs) {
 }
 
 fn load_configuration_and_validate_or_exit(
-    network: &str,
+    agent: &CliAgent,
     configuration_file_path: &PathBuf,
 ) -> Proposal {
     // Read the file.
@@ -243,7 +246,7 @@ fn load_configuration_and_validate_or_exit(
         }
     };
 
-    all_canisters_have_all_required_controllers(network, &canister_ids, &[ROOT_CANISTER_ID.get()])
+    all_canisters_have_all_required_controllers(agent, &canister_ids, &[ROOT_CANISTER_ID.get()])
         .unwrap_or_else(|err| {
             eprintln!("{}", err);
             std::process::exit(1);
@@ -283,7 +286,7 @@ impl Display for CanistersWithMissingControllers {
 }
 
 fn all_canisters_have_all_required_controllers(
-    network: &str,
+    agent: &CliAgent,
     canister_ids: &[CanisterId],
     required_controllers: &[PrincipalId],
 ) -> Result<(), CanistersWithMissingControllers> {
@@ -294,9 +297,8 @@ fn all_canisters_have_all_required_controllers(
     let defective_canister_ids = canister_ids
         .iter()
         .filter(|canister_id| {
-            let canister_id = PrincipalId::from(**canister_id);
             let controllers =
-                HashSet::from_iter(fetch_canister_controllers_or_exit(network, canister_id));
+                HashSet::from_iter(fetch_canister_controllers_or_exit(agent, **canister_id));
             let ok = controllers.is_superset(&required_controllers);
             !ok
         })
@@ -86,6 +86,9 @@ pub struct Neuron {
     pub aging_since_timestamp_seconds: u64,
     /// The neuron's followees, specified as a map of proposal functions IDs to followees neuron IDs.
     /// The map's keys are represented by integers as Protobuf does not support enum keys in maps.
+    ///
+    /// Following on a specific function takes precedence over following on the function's topic
+    /// (see `topic_followees`), which in turn takes precedence over the catch-all following.
     #[prost(btree_map = "uint64, message", tag = "11")]
     pub followees: ::prost::alloc::collections::BTreeMap<u64, neuron::Followees>,
     /// The accumulated unstaked maturity of the neuron, measured in "e8s equivalent", i.e., in equivalent of
@@ -137,6 +140,15 @@ pub struct Neuron {
     /// entry\[i\].timestamp_of_disbursement_seconds <= entry\[i+1\].timestamp_of_disbursement_seconds
     #[prost(message, repeated, tag = "18")]
     pub disburse_maturity_in_progress: ::prost::alloc::vec::Vec<DisburseMaturityInProgress>,
+    /// The neuron's followees, specified per topic (see `Topic`). When a proposal
+    /// is voted on and the neuron has no function-level `followees` for the
+    /// proposal's function, the function is resolved to a topic and the followees
+    /// for that topic are used.
+    ///
+    /// This field is unset for neurons whose function-level followees have not
+    /// been migrated to topics yet.
+    #[prost(message, optional, tag = "19")]
+    pub topic_followees: ::core::option::Option<TopicFollowees>,
     /// The neuron's dissolve state, specifying whether the neuron is dissolving,
     /// non-dissolving, or dissolved.
     ///
@@ -204,6 +216,28 @@ pub mod neuron {
         DissolveDelaySeconds(u64),
     }
 }
+/// A list of followees for a given topic.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FolloweesForTopic {
+    /// The followee neurons, specified by their neuron ID.
+    #[prost(message, repeated, tag = "1")]
+    pub followees: ::prost::alloc::vec::Vec<NeuronId>,
+    /// The topic for which the followees apply.
+    #[prost(enumeration = "Topic", optional, tag = "2")]
+    pub topic: ::core::option::Option<i32>,
+}
+/// The followees of a neuron per topic.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TopicFollowees {
+    /// Map from a topic (represented by its integer value, as Protobuf does not
+    /// support enum keys in maps) to the followees for that topic.
+    #[prost(btree_map = "int32, message", tag = "1")]
+    pub topic_id_to_followees: ::prost::alloc::collections::BTreeMap<i32, FolloweesForTopic>,
+}
 /// A NervousSystem function that can be executed by governance as a result of an adopted proposal.
 /// Each NervousSystem function has an id and a target canister and target method, that define
 /// the method that will be called if the proposal is adopted.
@@ -259,6 +293,10 @@ pub mod nervous_system_function {
         /// <method_name>(proposal_data: ProposalData) -> Result<String, String>
         #[prost(string, optional, tag = "5")]
         pub validator_method_name: ::core::option::Option<::prost::alloc::string::String>,
+        /// The topic under which proposals executing this function are followed.
+        /// Defaults to TOPIC_APPLICATION_BUSINESS_LOGIC if unset.
+        #[prost(enumeration = "super::Topic", optional, tag = "6")]
+        pub topic: ::core::option::Option<i32>,
     }
     #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1891,6 +1929,18 @@ pub mod manage_neuron {
         #[prost(message, repeated, tag = "2")]
         pub followees: ::prost::alloc::vec::Vec<super::NeuronId>,
     }
+    /// The operation that sets the followees of a neuron per topic.
+    ///
+    /// For each topic in `topic_following`, the neuron's followees for the topic
+    /// are replaced by the given list. If the list is empty, the neuron stops
+    /// following on the topic. Topics that are not mentioned are left unchanged.
+    #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SetFollowing {
+        #[prost(message, repeated, tag = "1")]
+        pub topic_following: ::prost::alloc::vec::Vec<super::FolloweesForTopic>,
+    }
     /// The operation that registers a given vote from the neuron for a given
     /// proposal (a directly cast vote as opposed to a vote that is cast as
     /// a result of a follow relation).
@@ -2011,6 +2061,8 @@ pub mod manage_neuron {
         RemoveNeuronPermissions(RemoveNeuronPermissions),
         #[prost(message, tag = "13")]
         StakeMaturity(StakeMaturity),
+        #[prost(message, tag = "14")]
+        SetFollowing(SetFollowing),
     }
 }
 /// The response of a ManageNeuron command.
@@ -2083,6 +2135,11 @@ pub mod manage_neuron_response {
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct FollowResponse {}
+    /// The response to the ManageNeuron command 'set_following'.
+    #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SetFollowingResponse {}
     /// The response to the ManageNeuron command 'make_proposal'.
     #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2156,6 +2213,8 @@ pub mod manage_neuron_response {
         RemoveNeuronPermission(RemoveNeuronPermissionsResponse),
         #[prost(message, tag = "13")]
         StakeMaturity(StakeMaturityResponse),
+        #[prost(message, tag = "14")]
+        SetFollowing(SetFollowingResponse),
     }
 }
 /// An operation that attempts to get a neuron by a given neuron ID.
@@ -2676,6 +2735,80 @@ impl Vote {
         }
     }
 }
+/// Topics group proposal functions, both native and generic, such that neurons
+/// can follow other neurons on a whole group of functions at once. In
+/// particular, following on a topic also covers generic functions that are
+/// added to the topic in the future.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum Topic {
+    /// Not a valid topic. Used as the default value and for the "catch-all"
+    /// pseudo function (i.e., Action::Unspecified).
+    Unspecified = 0,
+    /// Proposals that change the DAO's settings, e.g., the nervous system
+    /// parameters, the SNS metadata or the ledger parameters.
+    DaoCommunitySettings = 1,
+    /// Proposals that upgrade the SNS framework canisters.
+    SnsFrameworkManagement = 2,
+    /// Proposals that manage the dapp canisters controlled by the SNS, e.g.,
+    /// upgrading or registering them and changing their settings.
+    DappCanisterManagement = 3,
+    /// Proposals that execute generic nervous system functions implementing
+    /// the dapp's business logic. This is the default topic of generic functions.
+    ApplicationBusinessLogic = 4,
+    /// Motion proposals.
+    Governance = 5,
+    /// Proposals that move the SNS's assets, i.e., treasury transfers and minting.
+    TreasuryAssetManagement = 6,
+    /// Proposals that are critical for the dapp's operation, e.g., deregistering
+    /// dapp canisters and adding or removing generic nervous system functions.
+    CriticalDappOperations = 7,
+}
+impl Topic {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Topic::Unspecified => "TOPIC_UNSPECIFIED",
+            Topic::DaoCommunitySettings => "TOPIC_DAO_COMMUNITY_SETTINGS",
+            Topic::SnsFrameworkManagement => "TOPIC_SNS_FRAMEWORK_MANAGEMENT",
+            Topic::DappCanisterManagement => "TOPIC_DAPP_CANISTER_MANAGEMENT",
+            Topic::ApplicationBusinessLogic => "TOPIC_APPLICATION_BUSINESS_LOGIC",
+            Topic::Governance => "TOPIC_GOVERNANCE",
+            Topic::TreasuryAssetManagement => "TOPIC_TREASURY_ASSET_MANAGEMENT",
+            Topic::CriticalDappOperations => "TOPIC_CRITICAL_DAPP_OPERATIONS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TOPIC_UNSPECIFIED" => Some(Self::Unspecified),
+            "TOPIC_DAO_COMMUNITY_SETTINGS" => Some(Self::DaoCommunitySettings),
+            "TOPIC_SNS_FRAMEWORK_MANAGEMENT" => Some(Self::SnsFrameworkManagement),
+            "TOPIC_DAPP_CANISTER_MANAGEMENT" => Some(Self::DappCanisterManagement),
+            "TOPIC_APPLICATION_BUSINESS_LOGIC" => Some(Self::ApplicationBusinessLogic),
+            "TOPIC_GOVERNANCE" => Some(Self::Governance),
+            "TOPIC_TREASURY_ASSET_MANAGEMENT" => Some(Self::TreasuryAssetManagement),
+            "TOPIC_CRITICAL_DAPP_OPERATIONS" => Some(Self::CriticalDappOperations),
+            _ => None,
+        }
+    }
+}
 #[derive(
     candid::CandidType,
     candid::Deserialize,
@@ -32,7 +32,7 @@ use crate::{
                 self,
                 claim_or_refresh::{By, MemoAndController},
                 AddNeuronPermissions, ClaimOrRefresh, DisburseMaturity, FinalizeDisburseMaturity,
-                RemoveNeuronPermissions,
+                RemoveNeuronPermissions, SetFollowing,
             },
             manage_neuron_response::{
                 DisburseMaturityResponse, MergeMaturityResponse, StakeMaturityResponse,
@@ -44,7 +44,7 @@ use crate::{
             ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus, DefaultFollowees,
             DeregisterDappCanisters, DisburseMaturityInProgress, Empty,
             ExecuteGenericNervousSystemFunction, FailStuckUpgradeInProgressRequest,
-            FailStuckUpgradeInProgressResponse, GetMaturityModulationRequest,
+            FailStuckUpgradeInProgressResponse, FolloweesForTopic, GetMaturityModulationRequest,
             GetMaturityModulationResponse, GetMetadataRequest, GetMetadataResponse, GetMode,
             GetModeResponse, GetNeuron, GetNeuronResponse, GetProposal, GetProposalResponse,
             GetSnsInitializationParametersRequest, GetSnsInitializationParametersResponse,
@@ -54,9 +54,9 @@ use crate::{
             ManageSnsMetadata, MintSnsTokens, NervousSystemFunction, NervousSystemParameters,
             Neuron, NeuronId, NeuronPermission, NeuronPermissionList, NeuronPermissionType,
             Proposal, ProposalData, ProposalDecisionStatus, ProposalId, ProposalRewardStatus,
-            RegisterDappCanisters, RewardEvent, Tally, TransferSnsTreasuryFunds,
-            UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote, VotingRewardsParameters,
-            WaitForQuietState,
+            RegisterDappCanisters, RewardEvent, Tally, Topic, TopicFollowees,
+            TransferSnsTreasuryFunds, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
+            VotingRewardsParameters, WaitForQuietState,
         },
     },
     proposal::{
@@ -68,8 +68,8 @@ use crate::{
         UpgradeSnsParams,
     },
     types::{
-        function_id_to_proposal_criticality, is_registered_function_id, Environment,
-        HeapGrowthPotential, LedgerUpdateLock,
+        function_id_to_proposal_criticality, function_id_to_topic, is_registered_function_id,
+        Environment, HeapGrowthPotential, LedgerUpdateLock,
     },
 };
 use candid::{Decode, Encode};
@@ -285,6 +285,151 @@ impl GovernanceProto {
         }
     }
 
+    /// Builds an index that maps topics to (followee) neuron IDs to these neuron's
+    /// followers. The resulting index is a map
+    /// Topic -> (followee's neuron ID) -> set of followers' neuron IDs.
+    ///
+    /// The index is built from the `neurons` in the `Governance` struct, which map followers
+    /// (the neuron ID) to a set of followees per topic.
+    pub fn build_topic_followee_index(
+        neurons: &BTreeMap<String, Neuron>,
+    ) -> BTreeMap<Topic, BTreeMap<String, BTreeSet<NeuronId>>> {
+        let mut topic_followee_index = BTreeMap::new();
+        for neuron in neurons.values() {
+            GovernanceProto::add_neuron_to_topic_followee_index(&mut topic_followee_index, neuron);
+        }
+        topic_followee_index
+    }
+
+    /// Adds a neuron to the topic_followee_index.
+    pub fn add_neuron_to_topic_followee_index(
+        index: &mut BTreeMap<Topic, BTreeMap<String, BTreeSet<NeuronId>>>,
+        neuron: &Neuron,
+    ) {
+        let Some(topic_followees) = neuron.topic_followees.as_ref() else {
+            return;
+        };
+
+        for (topic, followees) in topic_followees.topic_id_to_followees.iter() {
+            let topic = match Topic::try_from(*topic) {
+                Ok(Topic::Unspecified) | Err(_) => continue,
+                Ok(topic) => topic,
+            };
+
+            let followee_index = index.entry(topic).or_default();
+            for followee in followees.followees.iter() {
+                followee_index
+                    .entry(followee.to_string())
+                    .or_default()
+                    .insert(
+                        neuron
+                            .id
+                            .as_ref()
+                            .expect("Neuron must have a NeuronId")
+                            .clone(),
+                    );
+            }
+        }
+    }
+
+    /// Removes a neuron from the topic_followee_index.
+    pub fn remove_neuron_from_topic_followee_index(
+        index: &mut BTreeMap<Topic, BTreeMap<String, BTreeSet<NeuronId>>>,
+        neuron: &Neuron,
+    ) {
+        let Some(topic_followees) = neuron.topic_followees.as_ref() else {
+            return;
+        };
+
+        for (topic, followees) in topic_followees.topic_id_to_followees.iter() {
+            let Ok(topic) = Topic::try_from(*topic) else {
+                continue;
+            };
+
+            if let Some(followee_index) = index.get_mut(&topic) {
+                for followee in followees.followees.iter() {
+                    let nid = followee.to_string();
+                    if let Some(followee_set) = followee_index.get_mut(&nid) {
+                        followee_set
+                            .remove(neuron.id.as_ref().expect("Neuron must have a NeuronId"));
+                        if followee_set.is_empty() {
+                            followee_index.remove(&nid);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Migrates the function-level followees of neurons that have not been
+    /// migrated yet (i.e., whose `topic_followees` is unset) to topic-level
+    /// followees.
+    ///
+    /// A topic is only migrated if the neuron follows the very same neurons on
+    /// every function that currently belongs to the topic. Otherwise, following
+    /// on the topic would change how the neuron votes on some of the topic's
+    /// functions, so the function-level followees are left to apply instead.
+    /// The function-level followees of migrated topics are removed, so that a
+    /// later `Follow` on a single function of such a topic takes effect. Either
+    /// way, the neuron's voting behavior is not changed by the migration.
+    pub fn migrate_function_followees_to_topics(&mut self) {
+        let mut topic_to_function_ids: BTreeMap<Topic, Vec<u64>> = BTreeMap::new();
+        let function_ids = Action::native_function_ids().into_iter().chain(
+            self.id_to_nervous_system_functions
+                .keys()
+                .copied()
+                .filter(|id| is_registered_function_id(*id, &self.id_to_nervous_system_functions)),
+        );
+        for function_id in function_ids {
+            let topic = function_id_to_topic(function_id, &self.id_to_nervous_system_functions);
+            if topic != Topic::Unspecified {
+                topic_to_function_ids
+                    .entry(topic)
+                    .or_default()
+                    .push(function_id);
+            }
+        }
+
+        for neuron in self.neurons.values_mut() {
+            if neuron.topic_followees.is_some() {
+                continue;
+            }
+
+            let mut topic_id_to_followees = BTreeMap::new();
+            for (topic, function_ids) in topic_to_function_ids.iter() {
+                let mut followees = function_ids.iter().map(|function_id| {
+                    neuron
+                        .followees
+                        .get(function_id)
+                        .map(|followees| &followees.followees)
+                });
+
+                let Some(Some(first)) = followees.next() else {
+                    continue;
+                };
+                if first.is_empty() || !followees.all(|other| other == Some(first)) {
+                    continue;
+                }
+
+                let followees = first.clone();
+                for function_id in function_ids {
+                    neuron.followees.remove(function_id);
+                }
+                topic_id_to_followees.insert(
+                    *topic as i32,
+                    FolloweesForTopic {
+                        followees,
+                        topic: Some(*topic as i32),
+                    },
+                );
+            }
+
+            neuron.topic_followees = Some(TopicFollowees {
+                topic_id_to_followees,
+            });
+        }
+    }
+
     /// Iterate through one neuron and add all the principals that have some permission on this
     /// neuron to the index that maps principalIDs to a set of neurons for which the principal
     /// has some permissions.
@@ -667,6 +812,16 @@ pub struct Governance {
     /// Function ID -> (followee's neuron ID) -> set of followers' neuron IDs.
     pub function_followee_index: BTreeMap<u64, BTreeMap<String, BTreeSet<NeuronId>>>,
 
+    /// Cached data structure that (for each topic) maps a followee to the set
+    /// of its followers. It is the inverse of the mapping from follower to
+    /// topic followees that is stored in each (follower) neuron.
+    ///
+    /// This is a cached index and will be removed and recreated when the state
+    /// is saved and restored.
+    ///
+    /// Topic -> (followee's neuron ID) -> set of followers' neuron IDs.
+    pub topic_followee_index: BTreeMap<Topic, BTreeMap<String, BTreeSet<NeuronId>>>,
+
     /// Maps Principals to the Neuron IDs of all Neurons for which this principal
     /// has some permissions, i.e., all neurons that have this principal associated
     /// with a NeuronPermissionType for the Neuron.
@@ -724,6 +879,8 @@ impl Governance {
             })
         }
 
+        proto.migrate_function_followees_to_topics();
+
         thread_local! {
             static PROFILING_INFORMATION: RefCell<SpanStats> = RefCell::default();
         }
@@ -736,6 +893,7 @@ impl Governance {
             nns_ledger,
             cmc,
             function_followee_index: BTreeMap::new(),
+            topic_followee_index: BTreeMap::new(),
             principal_to_neuron_ids_index: BTreeMap::new(),
             closest_proposal_deadline_timestamp_seconds: 0,
             latest_gc_timestamp_seconds: 0,
@@ -794,6 +952,8 @@ impl Governance {
         self.function_followee_index = self
             .proto
             .build_function_followee_index(&self.proto.neurons);
+        self.topic_followee_index =
+            GovernanceProto::build_topic_followee_index(&self.proto.neurons);
         self.principal_to_neuron_ids_index = self
             .proto
             .build_principal_to_neuron_ids_index(&self.proto.neurons);
@@ -904,7 +1064,8 @@ impl Governance {
     }
 
     /// Adds a neuron to the list of neurons and updates the indices
-    /// `principal_to_neuron_ids_index` and `function_followee_index`.
+    /// `principal_to_neuron_ids_index`, `function_followee_index` and
+    /// `topic_followee_index`.
     ///
     /// Preconditions:
     /// - the heap can still grow
@@ -944,13 +1105,19 @@ impl Governance {
             &neuron,
         );
 
+        GovernanceProto::add_neuron_to_topic_followee_index(
+            &mut self.topic_followee_index,
+            &neuron,
+        );
+
         self.proto.neurons.insert(neuron_id.to_string(), neuron);
 
         Ok(())
     }
 
     /// Removes a neuron from the list of neurons and updates the indices
-    /// `principal_to_neuron_ids_index` and `function_followee_index`.
+    /// `principal_to_neuron_ids_index`, `function_followee_index` and
+    /// `topic_followee_index`.
     ///
     /// Preconditions:
     /// - the given `neuron_id` exists in `self.proto.neurons`
@@ -979,6 +1146,11 @@ impl Governance {
             &neuron,
         );
 
+        GovernanceProto::remove_neuron_from_topic_followee_index(
+            &mut self.topic_followee_index,
+            &neuron,
+        );
+
         self.proto.neurons.remove(&neuron_id.to_string());
 
         Ok(())
@@ -1305,6 +1477,7 @@ impl Governance {
             created_timestamp_seconds: creation_timestamp_seconds,
             aging_since_timestamp_seconds: parent_neuron.aging_since_timestamp_seconds,
             followees: parent_neuron.followees.clone(),
+            topic_followees: parent_neuron.topic_followees.clone(),
             maturity_e8s_equivalent: 0,
             dissolve_state: parent_neuron.dissolve_state.clone(),
             voting_power_percentage_multiplier: parent_neuron.voting_power_percentage_multiplier,
@@ -3191,13 +3364,16 @@ impl Governance {
             .neuron_fees_e8s += proposal_data.reject_cost_e8s;
 
         let function_id = u64::from(action);
+        let topic = function_id_to_topic(function_id, &self.proto.id_to_nervous_system_functions);
         // Cast a 'yes'-vote for the proposer, including following.
         Governance::cast_vote_and_cascade_follow(
             &proposal_id,
             proposer_id,
             Vote::Yes,
             function_id,
+            topic,
             &self.function_followee_index,
+            &self.topic_followee_index,
             &self.proto.neurons,
             now_seconds,
             &mut proposal_data.ballots,
@@ -3210,21 +3386,25 @@ impl Governance {
     }
 
     /// Registers the vote `vote_of_neuron` for the neuron `voting_neuron_id`
-    /// and cascades voting according to the following relationship given in
-    /// function_followee_index that (for each action) maps a followee to
-    /// the set of followers.
+    /// and cascades voting according to the following relationships given in
+    /// function_followee_index and topic_followee_index that (for each action,
+    /// respectively each topic) map a followee to the set of followers.
     ///
     /// This method should only be called with `vote_of_neuron` being `yes`
     /// or `no`.
     ///
     /// `function_id` must be a real function ID, not the "catch-all" (pseudo)
-    /// function ID, which is used for following.
+    /// function ID, which is used for following. `topic` is the topic that
+    /// `function_id` belongs to.
+    #[allow(clippy::too_many_arguments)]
     fn cast_vote_and_cascade_follow(
         proposal_id: &ProposalId, // As of Nov, 2023 (a2095be), this is only used for logging.
         voting_neuron_id: &NeuronId,
         vote_of_neuron: Vote,
         function_id: u64,
+        topic: Topic,
         function_followee_index: &BTreeMap<u64, BTreeMap<String, BTreeSet<NeuronId>>>,
+        topic_followee_index: &BTreeMap<Topic, BTreeMap<String, BTreeSet<NeuronId>>>,
         neurons: &BTreeMap<String, Neuron>,
         // As of Dec, 2023 (52eec5c), the next parameter is only used to populate Ballots. In
         // particular, this has no impact on how the implications of following are deduced.
@@ -3237,10 +3417,10 @@ impl Governance {
         // This identifies which other neurons might get "triggered" to vote by
         // filling in the current neuron's ballot.
         //
-        // By default, followers on the specific function_id are reconsidered,
-        // as well as followers have have general "catch-all" following. As an
-        // optimization, catch-all followers are not considered when the
-        // proposal is not Critical.
+        // By default, followers on the function's topic and on the specific
+        // function_id are reconsidered, as well as followers have have general
+        // "catch-all" following. As an optimization, catch-all followers are
+        // not considered when the proposal is not Critical.
         //
         // E.g. if Alice follows Bob on "catch-all", and Bob votes on a
         // TransferSnsTreasuryFunds proposal, then Alice will not be considered
@@ -3253,6 +3433,10 @@ impl Governance {
                 }
             };
 
+            if let Some(member) = topic_followee_index.get(&topic) {
+                members.push(member);
+            }
+
             push_member(function_id);
 
             let proposal_criticality = function_id_to_proposal_criticality(function_id);
@@ -3343,7 +3527,8 @@ impl Governance {
                     }
                 };
 
-                let follower_vote = follower_neuron.would_follow_ballots(function_id, ballots);
+                let follower_vote =
+                    follower_neuron.would_follow_ballots(function_id, topic, ballots);
                 if follower_vote != Vote::Unspecified {
                     // follower_neuron would be swayed by its followees!
                     //
@@ -3439,12 +3624,15 @@ impl Governance {
 
         // Update ballots.
         let function_id = u64::from(action);
+        let topic = function_id_to_topic(function_id, &self.proto.id_to_nervous_system_functions);
         Governance::cast_vote_and_cascade_follow(
             proposal_id,
             neuron_id,
             vote,
             function_id,
+            topic,
             &self.function_followee_index,
+            &self.topic_followee_index,
             &self.proto.neurons,
             now_seconds,
             &mut proposal.ballots,
@@ -3562,6 +3750,127 @@ impl Governance {
         }
     }
 
+    /// Sets the followees of a given neuron per topic.
+    ///
+    /// For each topic in `set_following.topic_following`, the current list of
+    /// followees of the neuron for that topic is replaced with the provided
+    /// list. If the provided list is empty, the neuron stops following on the
+    /// topic. Topics that are not part of the request are left unchanged.
+    ///
+    /// Preconditions:
+    /// - the follower neuron exists
+    /// - the caller has the permission to change followers (same authorization
+    ///   as voting required, i.e., permission `Vote`)
+    /// - each topic is valid and appears at most once in the request
+    /// - no list of followees is too long (does not exceed max_followees_per_function
+    ///   as defined in the nervous system parameters)
+    fn set_following(
+        &mut self,
+        id: &NeuronId,
+        caller: &PrincipalId,
+        set_following: &SetFollowing,
+    ) -> Result<(), GovernanceError> {
+        let neuron = self.proto.neurons.get_mut(&id.to_string()).ok_or_else(||
+            // The specified neuron is not present.
+            GovernanceError::new_with_message(ErrorType::NotFound, format!("Follower neuron not found: {}", id)))?;
+
+        // Check that the caller is authorized to change followers (same authorization
+        // as voting required).
+        neuron.check_authorized(caller, NeuronPermissionType::Vote)?;
+
+        let max_followees_per_function = self
+            .proto
+            .parameters
+            .as_ref()
+            .expect("NervousSystemParameters not present")
+            .max_followees_per_function
+            .expect("NervousSystemParameters must have max_followees_per_function");
+
+        // Validate the whole request before changing anything.
+        let mut topics = BTreeSet::new();
+        let mut topic_following = vec![];
+        for followees_for_topic in &set_following.topic_following {
+            let topic = followees_for_topic
+                .topic
+                .and_then(|topic| Topic::try_from(topic).ok())
+                .filter(|topic| *topic != Topic::Unspecified)
+                .ok_or_else(|| {
+                    GovernanceError::new_with_message(
+                        ErrorType::InvalidCommand,
+                        format!("Invalid topic: {:?}", followees_for_topic.topic),
+                    )
+                })?;
+
+            if !topics.insert(topic) {
+                return Err(GovernanceError::new_with_message(
+                    ErrorType::InvalidCommand,
+                    format!("Topic {:?} is specified more than once.", topic),
+                ));
+            }
+
+            // Check that the list of followees is not too
+            // long. Allowing neurons to follow too many neurons
+            // allows a memory exhaustion attack on the neurons
+            // canister.
+            if followees_for_topic.followees.len() > max_followees_per_function as usize {
+                return Err(GovernanceError::new_with_message(
+                    ErrorType::InvalidCommand,
+                    format!("Too many followees for topic {:?}.", topic),
+                ));
+            }
+
+            topic_following.push((topic, &followees_for_topic.followees));
+        }
+
+        // Neurons created before topics existed are migrated when governance
+        // is (re)initialized, so this only guards against an unset field.
+        let topic_id_to_followees = &mut neuron
+            .topic_followees
+            .get_or_insert_with(TopicFollowees::default)
+            .topic_id_to_followees;
+
+        for (topic, followees) in topic_following {
+            // First, remove the current followees for this neuron and
+            // this topic from the followee index.
+            if let Some(old_followees) = topic_id_to_followees.get(&(topic as i32)) {
+                if let Some(followee_index) = self.topic_followee_index.get_mut(&topic) {
+                    for followee in &old_followees.followees {
+                        let nid = followee.to_string();
+                        if let Some(all_followers) = followee_index.get_mut(&nid) {
+                            all_followers.remove(id);
+                            if all_followers.is_empty() {
+                                followee_index.remove(&nid);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if followees.is_empty() {
+                // This clears the neuron's followees for the given topic.
+                topic_id_to_followees.remove(&(topic as i32));
+                continue;
+            }
+
+            topic_id_to_followees.insert(
+                topic as i32,
+                FolloweesForTopic {
+                    followees: followees.clone(),
+                    topic: Some(topic as i32),
+                },
+            );
+            let followee_index = self.topic_followee_index.entry(topic).or_default();
+            for followee in followees {
+                followee_index
+                    .entry(followee.to_string())
+                    .or_default()
+                    .insert(id.clone());
+            }
+        }
+
+        Ok(())
+    }
+
     /// Configures a given neuron (specified by the given neuron id).
     /// Specifically, this allows to stop and start dissolving a neuron
     /// as well as to increase a neuron's dissolve delay.
@@ -3745,6 +4054,7 @@ impl Governance {
             created_timestamp_seconds: now,
             aging_since_timestamp_seconds: now,
             followees: self.default_followees_or_panic().followees,
+            topic_followees: Some(TopicFollowees::default()),
             maturity_e8s_equivalent: 0,
             dissolve_state: Some(DissolveState::DissolveDelaySeconds(0)),
             // A neuron created through the `claim_or_refresh` ManageNeuron command will
@@ -3897,6 +4207,7 @@ impl Governance {
                 created_timestamp_seconds: now,
                 aging_since_timestamp_seconds: now,
                 followees: neuron_parameter.construct_followees(),
+                topic_followees: Some(TopicFollowees::default()),
                 maturity_e8s_equivalent: 0,
                 dissolve_state: Some(DissolveState::DissolveDelaySeconds(
                     neuron_parameter.get_dissolve_delay_seconds_or_panic(),
@@ -4200,6 +4511,9 @@ impl Governance {
             C::Follow(f) => self
                 .follow(&neuron_id, caller, f)
                 .map(|_| ManageNeuronResponse::follow_response()),
+            C::SetFollowing(f) => self
+                .set_following(&neuron_id, caller, f)
+                .map(|_| ManageNeuronResponse::set_following_response()),
             C::MakeProposal(p) => self
                 .make_proposal(&neuron_id, caller, p)
                 .await
@@ -4261,6 +4575,7 @@ impl Governance {
             Disburse(_) => err("Disburse"),
             Split(_) => err("Split"),
             Follow(_)
+            | SetFollowing(_)
             | MakeProposal(_)
             | RegisterVote(_)
             | ClaimOrRefresh(_)
@@ -5359,7 +5674,7 @@ impl Governance {
     ///   `principal_to_neuron_ids_index`)
     /// - the followees are not changed (it's easy to update followees
     ///   via `manage_neuron` and doing it here would require updating
-    ///   `function_followee_index`)
+    ///   `function_followee_index` and `topic_followee_index`)
     #[cfg(feature = "test")]
     pub fn update_neuron(&mut self, neuron: Neuron) -> Result<(), GovernanceError> {
         let neuron_id = &neuron.id.as_ref().expect("Neuron must have a NeuronId");
@@ -5387,7 +5702,9 @@ impl Governance {
         }
 
         // Must NOT clobber followees.
-        if old_neuron.followees != neuron.followees {
+        if old_neuron.followees != neuron.followees
+            || old_neuron.topic_followees != neuron.topic_followees
+        {
             return Err(GovernanceError::new_with_message(
                 ErrorType::PreconditionFailed,
                 "Cannot update neuron's followees via update_neuron.".to_string(),
@@ -5990,6 +6307,7 @@ mod tests {
                         target_method_name: Some("test_method".to_string()),
                         validator_canister_id: Some(CanisterId::from_u64(1).get()),
                         validator_method_name: Some("test_validator_method".to_string()),
+                        topic: None,
                     },
                 )),
             },
@@ -8654,6 +8972,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(100).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -9637,6 +9956,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -9659,6 +9979,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(invalid_canister_target.get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -9882,7 +10203,9 @@ mod tests {
                     &voting_neuron_id,
                     vote_of_neuron,
                     function_id,
+                    function_id_to_topic(function_id, &BTreeMap::new()),
                     &function_followee_index,
+                    &BTreeMap::new(),
                     &neurons,
                     now_seconds,
                     &mut ballots,
@@ -2,7 +2,7 @@ use crate::{
     pb::v1::{
         governance_error::ErrorType, manage_neuron, neuron::DissolveState, proposal::Action,
         Ballot, Empty, GovernanceError, Neuron, NeuronId, NeuronPermission, NeuronPermissionList,
-        NeuronPermissionType, Vote,
+        NeuronPermissionType, Topic, Vote,
     },
     types::function_id_to_proposal_criticality,
 };
@@ -235,11 +235,13 @@ impl Neuron {
 
     /// Given the specified `ballots`, determine how the neuron would
     /// vote on a proposal of `action` based on which neurons this
-    /// neuron follows on this action (or on the default action if this
-    /// neuron doesn't specify any followees for `action`).
+    /// neuron follows on this action, or, if this neuron doesn't specify
+    /// any followees for `action`, on the action's `topic` (or on the default
+    /// action if this neuron doesn't specify any followees for `topic` either).
     pub(crate) fn would_follow_ballots(
         &self,
         function_id: u64,
+        topic: Topic,
         ballots: &BTreeMap<String, Ballot>,
     ) -> Vote {
         // Step 1: Who are the relevant followees?
@@ -256,8 +258,16 @@ impl Neuron {
 
         let mut followee_neuron_ids = get_followee_neuron_ids(function_id);
 
+        // Following on a specific function takes precedence over following on its topic.
+        if followee_neuron_ids.is_empty() {
+            let topic_followee_neuron_ids = self.followees_for_topic(topic);
+            if !topic_followee_neuron_ids.is_empty() {
+                return Self::count_followee_votes(topic_followee_neuron_ids, ballots);
+            }
+        }
+
         // If the function is not critical, and this Neuron does not have followees specifically for
-        // the function, then fall back to the "catch-all" following.
+        // the function or its topic, then fall back to the "catch-all" following.
         if followee_neuron_ids.is_empty() {
             use ProposalCriticality::{Critical, Normal};
             match function_id_to_proposal_criticality(function_id) {
@@ -274,6 +284,25 @@ impl Neuron {
             return Vote::Unspecified;
         }
 
+        Self::count_followee_votes(followee_neuron_ids, ballots)
+    }
+
+    /// Returns the followees of this neuron for `topic`, or an empty slice if
+    /// the neuron doesn't follow anyone on `topic`.
+    pub(crate) fn followees_for_topic(&self, topic: Topic) -> &[NeuronId] {
+        self.topic_followees
+            .as_ref()
+            .and_then(|topic_followees| topic_followees.topic_id_to_followees.get(&(topic as i32)))
+            .map(|followees_for_topic| followees_for_topic.followees.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Determines how a neuron following `followee_neuron_ids` would vote
+    /// given the specified `ballots`.
+    fn count_followee_votes(
+        followee_neuron_ids: &[NeuronId],
+        ballots: &BTreeMap<String, Ballot>,
+    ) -> Vote {
         // Step 2: Count followee votes.
         let mut yes: usize = 0;
         let mut no: usize = 0;
@@ -11,7 +11,7 @@ use crate::{
         DeregisterDappCanisters, ExecuteGenericNervousSystemFunction, Governance, LogVisibility,
         ManageDappCanisterSettings, ManageLedgerParameters, ManageSnsMetadata, MintSnsTokens,
         Motion, NervousSystemFunction, NervousSystemParameters, Proposal, ProposalData,
-        ProposalDecisionStatus, ProposalRewardStatus, RegisterDappCanisters, Tally,
+        ProposalDecisionStatus, ProposalRewardStatus, RegisterDappCanisters, Tally, Topic,
         TransferSnsTreasuryFunds, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
     },
     sns_upgrade::{get_upgrade_params, UpgradeSnsParams},
@@ -647,6 +647,7 @@ impl TryFrom<&NervousSystemFunction> for ValidGenericNervousSystemFunction {
                 target_method_name,
                 validator_canister_id,
                 validator_method_name,
+                topic,
             })) => {
                 // Validate the target_canister_id field.
                 let target_canister_id =
@@ -670,6 +671,17 @@ impl TryFrom<&NervousSystemFunction> for ValidGenericNervousSystemFunction {
                     defects.push("validator_method_name was empty.".to_string());
                 }
 
+                // Validate the topic field. If unset, the function falls under
+                // Topic::ApplicationBusinessLogic.
+                if let Some(topic) = topic {
+                    match Topic::try_from(*topic) {
+                        Ok(Topic::Unspecified) | Err(_) => {
+                            defects.push(format!("topic {} is not a valid topic.", topic));
+                        }
+                        Ok(_) => (),
+                    }
+                }
+
                 if !defects.is_empty() {
                     return Err(format!(
                         "ExecuteNervousSystemFunction was invalid for the following reason(s):\n{}",
@@ -1930,6 +1942,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from_u64(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2127,6 +2140,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from_u64(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2173,6 +2187,7 @@ mod tests {
                         target_method_name: Some("test_method".to_string()),
                         validator_canister_id: Some(CanisterId::from_u64(i as u64).get()),
                         validator_method_name: Some("test_validator_method".to_string()),
+                        topic: None,
                     },
                 )),
             };
@@ -2189,6 +2204,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from_u64(u64::MAX).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2588,6 +2604,7 @@ Version {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2609,6 +2626,7 @@ Version {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2628,6 +2646,7 @@ Version {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::ic_00().get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
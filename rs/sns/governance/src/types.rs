@@ -26,7 +26,7 @@ use crate::{
             GovernanceError, ManageDappCanisterSettings, ManageNeuronResponse, MintSnsTokens,
             Motion, NervousSystemFunction, NervousSystemParameters, Neuron, NeuronId,
             NeuronPermission, NeuronPermissionList, NeuronPermissionType, ProposalId,
-            RegisterDappCanisters, RewardEvent, TransferSnsTreasuryFunds,
+            RegisterDappCanisters, RewardEvent, Topic, TransferSnsTreasuryFunds,
             UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote, VotingRewardsParameters,
         },
     },
@@ -141,6 +141,7 @@ impl governance::Mode {
         use manage_neuron::Command as C;
         let ok = match command {
             C::Follow(_)
+            | C::SetFollowing(_)
             | C::MakeProposal(_)
             | C::RegisterVote(_)
             | C::AddNeuronPermissions(_)
@@ -302,6 +303,7 @@ impl From<&manage_neuron::Command> for neuron_in_flight_command::Command {
             S::AddNeuronPermissions   (x) => D::AddNeuronPermissions   (x),
             S::RemoveNeuronPermissions(x) => D::RemoveNeuronPermissions(x),
             S::StakeMaturity          (_) => D::SyncCommand(SyncCommand{}),
+            S::SetFollowing           (_) => D::SyncCommand(SyncCommand{}),
         }
     }
 }
@@ -1176,6 +1178,7 @@ impl manage_neuron::Command {
             manage_neuron::Command::AddNeuronPermissions(_) => "AddNeuronPermissions",
             manage_neuron::Command::RemoveNeuronPermissions(_) => "RemoveNeuronPermissions",
             manage_neuron::Command::StakeMaturity(_) => "StakeMaturity",
+            manage_neuron::Command::SetFollowing(_) => "SetFollowing",
         }
         .to_string()
     }
@@ -1264,6 +1267,14 @@ impl ManageNeuronResponse {
         }
     }
 
+    pub fn set_following_response() -> Self {
+        ManageNeuronResponse {
+            command: Some(manage_neuron_response::Command::SetFollowing(
+                manage_neuron_response::SetFollowingResponse {},
+            )),
+        }
+    }
+
     pub fn make_proposal_response(proposal_id: ProposalId) -> Self {
         let proposal_id = Some(proposal_id);
         ManageNeuronResponse {
@@ -1550,6 +1561,82 @@ impl Action {
             | ManageDappCanisterSettings(_) => ProposalCriticality::Normal,
         }
     }
+
+    /// Returns the topic of the action. Generic functions can be assigned to a
+    /// topic when they are added, so for those this returns the default topic,
+    /// see `function_id_to_topic`.
+    pub(crate) fn topic(&self) -> Topic {
+        use Action::*;
+        match self {
+            Unspecified(_) => Topic::Unspecified,
+
+            ManageNervousSystemParameters(_) | ManageSnsMetadata(_) | ManageLedgerParameters(_) => {
+                Topic::DaoCommunitySettings
+            }
+
+            UpgradeSnsToNextVersion(_) => Topic::SnsFrameworkManagement,
+
+            UpgradeSnsControlledCanister(_)
+            | RegisterDappCanisters(_)
+            | ManageDappCanisterSettings(_) => Topic::DappCanisterManagement,
+
+            ExecuteGenericNervousSystemFunction(_) => Topic::ApplicationBusinessLogic,
+
+            Motion(_) => Topic::Governance,
+
+            TransferSnsTreasuryFunds(_) | MintSnsTokens(_) => Topic::TreasuryAssetManagement,
+
+            DeregisterDappCanisters(_)
+            | AddGenericNervousSystemFunction(_)
+            | RemoveGenericNervousSystemFunction(_) => Topic::CriticalDappOperations,
+        }
+    }
+}
+
+/// Resolves a function ID to the topic under which proposals of that function
+/// are followed.
+///
+/// Native functions have a fixed topic. Generic functions use the topic they
+/// were registered with, or Topic::ApplicationBusinessLogic if none was given.
+/// Returns Topic::Unspecified for the "catch-all" pseudo function and for
+/// functions that are not registered.
+pub(crate) fn function_id_to_topic(
+    function_id: u64,
+    registered_functions: &BTreeMap<u64, NervousSystemFunction>,
+) -> Topic {
+    lazy_static! {
+        static ref NATIVE_FUNCTION_ID_TO_TOPIC: HashMap</* function_id */ u64, Topic> = {
+            let mut result = HashMap::new();
+
+            for action in Action::iter() {
+                // Skip non-native, aka generic functions.
+                if let Action::ExecuteGenericNervousSystemFunction(_) = action {
+                    continue;
+                }
+
+                let function_id = u64::from(&action);
+                let previous_value = result.insert(function_id, action.topic());
+                debug_assert!(previous_value.is_none(), "{:#?}", previous_value);
+            }
+
+            result
+        };
+    }
+
+    if let Some(topic) = NATIVE_FUNCTION_ID_TO_TOPIC.get(&function_id) {
+        return *topic;
+    }
+
+    match registered_functions
+        .get(&function_id)
+        .and_then(|function| function.function_type.as_ref())
+    {
+        Some(FunctionType::GenericNervousSystemFunction(generic)) => generic
+            .topic
+            .and_then(|topic| Topic::try_from(topic).ok())
+            .unwrap_or(Topic::ApplicationBusinessLogic),
+        _ => Topic::Unspecified,
+    }
 }
 
 pub(crate) fn function_id_to_proposal_criticality(function_id: u64) -> ProposalCriticality {
@@ -2681,6 +2768,7 @@ pub(crate) mod tests {
             #[rustfmt::skip]
             let allowed_in_pre_initialization_swap = vec! [
                 Command::Follow                  (Default::default()),
+                Command::SetFollowing            (Default::default()),
                 Command::MakeProposal            (Default::default()),
                 Command::RegisterVote            (Default::default()),
                 Command::AddNeuronPermissions    (Default::default()),
@@ -2831,6 +2919,7 @@ pub(crate) mod tests {
                         target_method_name: Some("Foo".to_string()),
                         validator_canister_id: Some(*target_canister_id),
                         validator_method_name: Some("Bar".to_string()),
+                        topic: None,
                     })),
                 }
             }
@@ -3425,4 +3514,58 @@ pub(crate) mod tests {
             execute_generic_nervous_system_function_proposal,
         );
     }
+
+    #[test]
+    fn test_function_id_to_topic() {
+        let generic_function = |id, topic: Option<Topic>| NervousSystemFunction {
+            id,
+            name: "Amaze".to_string(),
+            description: None,
+            function_type: Some(FunctionType::GenericNervousSystemFunction(
+                GenericNervousSystemFunction {
+                    topic: topic.map(|topic| topic as i32),
+                    ..Default::default()
+                },
+            )),
+        };
+        let registered_functions = btreemap! {
+            1000 => generic_function(1000, None),
+            1001 => generic_function(1001, Some(Topic::CriticalDappOperations)),
+            1002 => NERVOUS_SYSTEM_FUNCTION_DELETION_MARKER.clone(),
+        };
+
+        for (function_id, expected_topic) in [
+            (native_action_ids::UNSPECIFIED, Topic::Unspecified),
+            (native_action_ids::MOTION, Topic::Governance),
+            (
+                native_action_ids::MANAGE_NERVOUS_SYSTEM_PARAMETERS,
+                Topic::DaoCommunitySettings,
+            ),
+            (
+                native_action_ids::UPGRADE_SNS_TO_NEXT_VERSION,
+                Topic::SnsFrameworkManagement,
+            ),
+            (
+                native_action_ids::TRANSFER_SNS_TREASURY_FUNDS,
+                Topic::TreasuryAssetManagement,
+            ),
+            (
+                native_action_ids::DEREGISTER_DAPP_CANISTERS,
+                Topic::CriticalDappOperations,
+            ),
+            // Generic functions default to ApplicationBusinessLogic.
+            (1000, Topic::ApplicationBusinessLogic),
+            (1001, Topic::CriticalDappOperations),
+            // Removed and unknown functions have no topic.
+            (1002, Topic::Unspecified),
+            (1003, Topic::Unspecified),
+        ] {
+            assert_eq!(
+                function_id_to_topic(function_id, &registered_functions),
+                expected_topic,
+                "function_id: {}",
+                function_id
+            );
+        }
+    }
 }
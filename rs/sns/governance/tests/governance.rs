@@ -36,11 +36,11 @@ use ic_sns_governance::{
             transfer_sns_treasury_funds::TransferFrom,
             Account as AccountProto, AddMaturityRequest, Ballot, ClaimSwapNeuronsError,
             ClaimSwapNeuronsRequest, ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus,
-            DeregisterDappCanisters, Empty, GovernanceError, ManageNeuronResponse, MintSnsTokens,
-            MintTokensRequest, MintTokensResponse, Motion, NervousSystemParameters, Neuron,
-            NeuronId, NeuronPermission, NeuronPermissionList, NeuronPermissionType, Proposal,
-            ProposalData, ProposalId, RegisterDappCanisters, TransferSnsTreasuryFunds, Vote,
-            WaitForQuietState,
+            DeregisterDappCanisters, Empty, FolloweesForTopic, GovernanceError,
+            ManageNeuronResponse, MintSnsTokens, MintTokensRequest, MintTokensResponse, Motion,
+            NervousSystemParameters, Neuron, NeuronId, NeuronPermission, NeuronPermissionList,
+            NeuronPermissionType, Proposal, ProposalData, ProposalId, RegisterDappCanisters, Topic,
+            TopicFollowees, TransferSnsTreasuryFunds, Vote, WaitForQuietState,
         },
     },
     types::{native_action_ids, ONE_DAY_SECONDS, ONE_MONTH_SECONDS},
@@ -2689,6 +2689,428 @@ fn test_empty_followees_are_filtered() {
     assert_eq!(follower_ballot.vote, Vote::Yes as i32);
 }
 
+/// Tests that following on a specific function takes precedence over following on the topic that
+/// the function belongs to.
+#[test]
+fn test_function_following_takes_precedence_over_topic_following() {
+    // Create the various neurons needed for this test
+    let function_followee_principal_id = PrincipalId::new_user_test_id(1000);
+    let function_followee_neuron_id = neuron_id(function_followee_principal_id, /*memo*/ 0);
+
+    let topic_followee_principal_id = PrincipalId::new_user_test_id(1001);
+    let topic_followee_neuron_id = neuron_id(topic_followee_principal_id, /*memo*/ 0);
+
+    let follower_principal_id = PrincipalId::new_user_test_id(1002);
+    let follower_neuron_id = neuron_id(follower_principal_id, /*memo*/ 0);
+
+    let proposer_principal_id = PrincipalId::new_user_test_id(1003);
+    let proposer_neuron_id = neuron_id(proposer_principal_id, /*memo*/ 0);
+
+    // Set up the test environment with neurons that can vote
+    let mut canister_fixture = GovernanceCanisterFixtureBuilder::new()
+        .add_neuron(
+            NeuronBuilder::new(
+                function_followee_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&function_followee_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                topic_followee_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&topic_followee_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                follower_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&follower_principal_id),
+            )
+            .add_followees(
+                native_action_ids::MOTION,
+                Followees {
+                    followees: vec![function_followee_neuron_id.clone()],
+                },
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                proposer_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&proposer_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .create();
+
+    // The follower neuron follows another neuron on the topic of Motion proposals.
+    canister_fixture
+        .set_following(
+            &follower_neuron_id,
+            vec![FolloweesForTopic {
+                followees: vec![topic_followee_neuron_id.clone()],
+                topic: Some(Topic::Governance as i32),
+            }],
+            follower_principal_id,
+        )
+        .unwrap();
+
+    // Submit a motion proposal
+    let (proposal_id, _) = canister_fixture
+        .make_default_proposal(
+            &proposer_neuron_id,
+            Motion {
+                motion_text: "Test function following".to_string(),
+            },
+            proposer_principal_id,
+        )
+        .unwrap();
+
+    // Vote with the topic followee, this should not result in the follower neuron voting
+    canister_fixture
+        .vote(
+            &topic_followee_neuron_id,
+            proposal_id,
+            Vote::No,
+            topic_followee_principal_id,
+        )
+        .unwrap();
+
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::Unspecified as i32);
+
+    // Vote with the function followee, this should result in the follower neuron voting
+    canister_fixture
+        .vote(
+            &function_followee_neuron_id,
+            proposal_id,
+            Vote::Yes,
+            function_followee_principal_id,
+        )
+        .unwrap();
+
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::Yes as i32);
+
+    // Clearing the function following makes the topic following apply again.
+    canister_fixture
+        .follow(
+            &follower_neuron_id,
+            native_action_ids::MOTION,
+            vec![],
+            follower_principal_id,
+        )
+        .unwrap();
+
+    let (proposal_id, _) = canister_fixture
+        .make_default_proposal(
+            &proposer_neuron_id,
+            Motion {
+                motion_text: "Test topic following".to_string(),
+            },
+            proposer_principal_id,
+        )
+        .unwrap();
+    canister_fixture
+        .vote(
+            &topic_followee_neuron_id,
+            proposal_id,
+            Vote::No,
+            topic_followee_principal_id,
+        )
+        .unwrap();
+
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::No as i32);
+}
+
+/// Tests that invalid SetFollowing commands are rejected without changing the neuron.
+#[test]
+fn test_set_following_rejects_invalid_requests() {
+    let (mut canister_fixture, user_principal, neuron_id) =
+        GovernanceCanisterFixtureBuilder::new().create_with_test_neuron();
+
+    let followees_for_topic = |topic: Option<i32>| FolloweesForTopic {
+        followees: vec![neuron_id.clone()],
+        topic,
+    };
+
+    // Unset topic
+    let error = canister_fixture
+        .set_following(&neuron_id, vec![followees_for_topic(None)], user_principal)
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidCommand as i32);
+
+    // Unspecified topic
+    let error = canister_fixture
+        .set_following(
+            &neuron_id,
+            vec![followees_for_topic(Some(Topic::Unspecified as i32))],
+            user_principal,
+        )
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidCommand as i32);
+
+    // Unknown topic
+    let error = canister_fixture
+        .set_following(
+            &neuron_id,
+            vec![followees_for_topic(Some(1000))],
+            user_principal,
+        )
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidCommand as i32);
+
+    // The same topic twice, even though the first entry on its own would be valid.
+    let error = canister_fixture
+        .set_following(
+            &neuron_id,
+            vec![
+                followees_for_topic(Some(Topic::Governance as i32)),
+                followees_for_topic(Some(Topic::Governance as i32)),
+            ],
+            user_principal,
+        )
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidCommand as i32);
+
+    // Not authorized
+    let error = canister_fixture
+        .set_following(
+            &neuron_id,
+            vec![followees_for_topic(Some(Topic::Governance as i32))],
+            PrincipalId::new_user_test_id(1001),
+        )
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::NotAuthorized as i32);
+
+    assert_eq!(
+        canister_fixture.get_neuron(&neuron_id).topic_followees,
+        Some(TopicFollowees::default())
+    );
+}
+
+/// Tests that function-level followees are migrated to topic-level followees when governance is
+/// initialized, but only for topics where this doesn't change how the neuron votes.
+#[test]
+fn test_function_followees_are_migrated_to_topics() {
+    let followee_principal_id = PrincipalId::new_user_test_id(1000);
+    let followee_neuron_id = neuron_id(followee_principal_id, /*memo*/ 0);
+
+    let follower_principal_id = PrincipalId::new_user_test_id(1001);
+    let follower_neuron_id = neuron_id(follower_principal_id, /*memo*/ 0);
+
+    let followees = Followees {
+        followees: vec![followee_neuron_id.clone()],
+    };
+
+    let canister_fixture = GovernanceCanisterFixtureBuilder::new()
+        .add_neuron(
+            NeuronBuilder::new(
+                followee_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&followee_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                follower_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&follower_principal_id),
+            )
+            // Motion is the only function of its topic, so this is migrated.
+            .add_followees(native_action_ids::MOTION, followees.clone())
+            // Both treasury functions follow the same neuron, so this is migrated.
+            .add_followees(
+                native_action_ids::TRANSFER_SNS_TREASURY_FUNDS,
+                followees.clone(),
+            )
+            .add_followees(native_action_ids::MINT_SNS_TOKENS, followees.clone())
+            // Other functions of this topic are not followed, so this is not migrated.
+            .add_followees(
+                native_action_ids::MANAGE_NERVOUS_SYSTEM_PARAMETERS,
+                followees.clone(),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .create();
+
+    let follower_neuron = canister_fixture.get_neuron(&follower_neuron_id);
+    assert_eq!(
+        follower_neuron.topic_followees,
+        Some(TopicFollowees {
+            topic_id_to_followees: btreemap! {
+                Topic::Governance as i32 => FolloweesForTopic {
+                    followees: vec![followee_neuron_id.clone()],
+                    topic: Some(Topic::Governance as i32),
+                },
+                Topic::TreasuryAssetManagement as i32 => FolloweesForTopic {
+                    followees: vec![followee_neuron_id.clone()],
+                    topic: Some(Topic::TreasuryAssetManagement as i32),
+                },
+            },
+        })
+    );
+    // Only the function-level followees of topics that were not migrated are kept.
+    assert_eq!(
+        follower_neuron.followees,
+        btreemap! {
+            native_action_ids::MANAGE_NERVOUS_SYSTEM_PARAMETERS => followees,
+        }
+    );
+
+    // A neuron that doesn't follow anyone is migrated too.
+    assert_eq!(
+        canister_fixture
+            .get_neuron(&followee_neuron_id)
+            .topic_followees,
+        Some(TopicFollowees::default())
+    );
+}
+
+/// Tests that a `Follow` on a single function issued after the function's topic was migrated
+/// determines how the neuron votes on that function.
+#[test]
+fn test_follow_after_migration_to_topics_is_honored() {
+    let topic_followee_principal_id = PrincipalId::new_user_test_id(1000);
+    let topic_followee_neuron_id = neuron_id(topic_followee_principal_id, /*memo*/ 0);
+
+    let function_followee_principal_id = PrincipalId::new_user_test_id(1001);
+    let function_followee_neuron_id = neuron_id(function_followee_principal_id, /*memo*/ 0);
+
+    let follower_principal_id = PrincipalId::new_user_test_id(1002);
+    let follower_neuron_id = neuron_id(follower_principal_id, /*memo*/ 0);
+
+    let proposer_principal_id = PrincipalId::new_user_test_id(1003);
+    let proposer_neuron_id = neuron_id(proposer_principal_id, /*memo*/ 0);
+
+    let mut canister_fixture = GovernanceCanisterFixtureBuilder::new()
+        .add_neuron(
+            NeuronBuilder::new(
+                topic_followee_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&topic_followee_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                function_followee_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&function_followee_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                follower_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&follower_principal_id),
+            )
+            // Motion is the only function of its topic, so this is migrated.
+            .add_followees(
+                native_action_ids::MOTION,
+                Followees {
+                    followees: vec![topic_followee_neuron_id.clone()],
+                },
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                proposer_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&proposer_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .create();
+
+    let follower_neuron = canister_fixture.get_neuron(&follower_neuron_id);
+    assert_eq!(
+        follower_neuron
+            .topic_followees
+            .unwrap()
+            .topic_id_to_followees
+            .keys()
+            .collect::<Vec<_>>(),
+        vec![&(Topic::Governance as i32)]
+    );
+    assert!(follower_neuron.followees.is_empty());
+
+    // After the migration, the follower follows a different neuron on Motion proposals.
+    canister_fixture
+        .follow(
+            &follower_neuron_id,
+            native_action_ids::MOTION,
+            vec![function_followee_neuron_id.clone()],
+            follower_principal_id,
+        )
+        .unwrap();
+
+    let (proposal_id, _) = canister_fixture
+        .make_default_proposal(
+            &proposer_neuron_id,
+            Motion {
+                motion_text: "Test following after migration".to_string(),
+            },
+            proposer_principal_id,
+        )
+        .unwrap();
+
+    // Vote with the migrated topic followee, this should not result in the follower neuron voting
+    canister_fixture
+        .vote(
+            &topic_followee_neuron_id,
+            proposal_id,
+            Vote::No,
+            topic_followee_principal_id,
+        )
+        .unwrap();
+
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::Unspecified as i32);
+
+    // Vote with the newly followed neuron, this should result in the follower neuron voting
+    canister_fixture
+        .vote(
+            &function_followee_neuron_id,
+            proposal_id,
+            Vote::Yes,
+            function_followee_principal_id,
+        )
+        .unwrap();
+
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::Yes as i32);
+}
+
 /// Tests that `ManageNeuron::DisburseMaturity` disburses the correct given different maturity
 /// modulation values
 #[tokio::test]
@@ -105,7 +105,8 @@ fn test_manage_dapp_canister_settings_successful() {
             Some(1 << 30),
             100_000,
             Some(1_000_000_000_000),
-            ic_management_canister_types::LogVisibility::Public
+            ic_management_canister_types::LogVisibility::Public,
+            None,
         ),
     );
 
@@ -156,7 +157,8 @@ fn test_manage_dapp_canister_settings_successful() {
             Some(0),
             0,
             Some(0),
-            ic_management_canister_types::LogVisibility::Controllers
+            ic_management_canister_types::LogVisibility::Controllers,
+            None,
         ),
     );
 }
@@ -231,7 +233,8 @@ fn test_manage_dapp_canister_settings_failure() {
             Some(1 << 30),
             100_000,
             Some(1_000_000_000_000),
-            ic_management_canister_types::LogVisibility::Public
+            ic_management_canister_types::LogVisibility::Public,
+            None,
         ),
     );
 
@@ -303,7 +306,8 @@ fn test_manage_dapp_canister_settings_failure() {
             Some(1 << 30),
             100_000,
             Some(1_000_000_000_000),
-            ic_management_canister_types::LogVisibility::Public
+            ic_management_canister_types::LogVisibility::Public,
+            None,
         ),
     );
 
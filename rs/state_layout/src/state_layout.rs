@@ -165,6 +165,7 @@ pub struct CanisterStateBits {
     pub total_query_stats: TotalQueryStats,
     pub log_visibility: LogVisibility,
     pub canister_log_records: Vec<CanisterLogRecord>,
+    pub wasm_memory_limit: Option<NumBytes>,
 }
 
 /// This struct contains bits of the `CanisterSnapshot` that are not already
@@ -1790,12 +1791,14 @@ impl From<CanisterStateBits> for pb_canister_state_bits::CanisterStateBits {
             canister_history: Some((&item.canister_history).into()),
             wasm_chunk_store_metadata: Some((&item.wasm_chunk_store_metadata).into()),
             total_query_stats: Some((&item.total_query_stats).into()),
-            log_visibility: item.log_visibility.into(),
+            log_visibility: (&item.log_visibility).into(),
+            log_visibility_v2: Some((&item.log_visibility).into()),
             canister_log_records: item
                 .canister_log_records
                 .into_iter()
                 .map(|record| record.into())
                 .collect(),
+            wasm_memory_limit: item.wasm_memory_limit.map(|v| v.get()),
         }
     }
 }
@@ -1915,12 +1918,18 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
                 "CanisterStateBits::total_query_stats",
             )
             .unwrap_or_default(),
-            log_visibility: value.log_visibility.try_into()?,
+            // Fall back to the legacy field for states written before
+            // `log_visibility_v2` was introduced.
+            log_visibility: match value.log_visibility_v2 {
+                Some(log_visibility_v2) => log_visibility_v2.try_into()?,
+                None => value.log_visibility.try_into()?,
+            },
             canister_log_records: value
                 .canister_log_records
                 .into_iter()
                 .map(|record| record.into())
                 .collect(),
+            wasm_memory_limit: value.wasm_memory_limit.map(NumBytes::from),
         })
     }
 }
@@ -54,6 +54,7 @@ fn default_canister_state_bits() -> CanisterStateBits {
         total_query_stats: TotalQueryStats::default(),
         log_visibility: LogVisibility::default(),
         canister_log_records: Vec::new(),
+        wasm_memory_limit: None,
     }
 }
 
@@ -114,6 +115,40 @@ fn test_encode_decode_non_empty_controllers() {
     assert_eq!(canister_state_bits.controllers, expected_controllers);
 }
 
+#[test]
+fn test_encode_decode_log_visibility_allowed_viewers() {
+    let log_visibility =
+        LogVisibility::AllowedViewers(vec![user_test_id(1).get(), canister_test_id(2).get()]);
+    let canister_state_bits = CanisterStateBits {
+        log_visibility: log_visibility.clone(),
+        ..default_canister_state_bits()
+    };
+
+    let pb_bits = pb_canister_state_bits::CanisterStateBits::from(canister_state_bits);
+    // The legacy field falls back to the controllers-only visibility.
+    assert_eq!(
+        pb_bits.log_visibility,
+        pb_canister_state_bits::LogVisibility::Controllers as i32
+    );
+    let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+
+    assert_eq!(canister_state_bits.log_visibility, log_visibility);
+}
+
+#[test]
+fn test_decode_legacy_log_visibility() {
+    let mut pb_bits = pb_canister_state_bits::CanisterStateBits::from(CanisterStateBits {
+        log_visibility: LogVisibility::Public,
+        ..default_canister_state_bits()
+    });
+    // States written before `log_visibility_v2` only have the legacy field.
+    pb_bits.log_visibility_v2 = None;
+
+    let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+
+    assert_eq!(canister_state_bits.log_visibility, LogVisibility::Public);
+}
+
 #[test]
 fn test_encode_decode_empty_history() {
     let canister_history = CanisterHistory::default();
@@ -1,11 +1,16 @@
 use candid::Decode;
 use core::sync::atomic::Ordering;
+pub use ic_btc_types_internal::{
+    BitcoinAdapterRequestWrapper, BitcoinAdapterResponse, BitcoinAdapterResponseWrapper,
+};
 use ic_config::flag_status::FlagStatus;
 use ic_config::{execution_environment::Config as HypervisorConfig, subnet_config::SubnetConfig};
 use ic_consensus::consensus::payload_builder::PayloadBuilderImpl;
 use ic_constants::{MAX_INGRESS_TTL, PERMITTED_DRIFT, SMALL_APP_SUBNET_MAX_SIZE};
 use ic_crypto_ecdsa_secp256k1::{PrivateKey, PublicKey};
 use ic_crypto_extended_bip32::{DerivationIndex, DerivationPath};
+use ic_crypto_sha2::{Sha256, Sha512};
+use ic_crypto_tecdsa::derive_ed25519_secret_scalar;
 use ic_crypto_test_utils_ni_dkg::{
     dummy_initial_dkg_transcript_with_master_key, sign_message, SecretKeyBytes,
 };
@@ -14,9 +19,11 @@ use ic_crypto_tree_hash::{
     Path as LabeledTreePath,
 };
 use ic_crypto_utils_threshold_sig_der::threshold_sig_public_key_to_der;
+use ic_crypto_vetkd::{derive_vetkd_encrypted_key, vetkd_master_public_key};
 use ic_cycles_account_manager::CyclesAccountManager;
 pub use ic_error_types::{ErrorCode, UserError};
 use ic_execution_environment::{ExecutionServices, IngressHistoryReaderImpl};
+use ic_http_endpoints_public::verify_paths;
 use ic_ingress_manager::{CustomRandomState, IngressManager};
 use ic_interfaces::ingress_pool::{
     IngressPool, PoolSection, UnvalidatedIngressArtifact, ValidatedIngressArtifact,
@@ -35,19 +42,21 @@ use ic_interfaces_state_manager::{
 };
 use ic_logger::ReplicaLogger;
 use ic_management_canister_types::{
-    self as ic00, CanisterIdRecord, InstallCodeArgs, Method, Payload,
+    self as ic00, CanisterIdRecord, InstallCodeArgs, MasterPublicKeyId, Method, Payload,
 };
 pub use ic_management_canister_types::{
     CanisterHttpResponsePayload, CanisterInstallMode, CanisterSettingsArgs, CanisterStatusResultV2,
-    ECDSAPublicKeyResponse, EcdsaCurve, EcdsaKeyId, HttpHeader, HttpMethod, SignWithECDSAReply,
-    UpdateSettingsArgs,
+    ECDSAPublicKeyResponse, EcdsaCurve, EcdsaKeyId, HttpHeader, HttpMethod, SchnorrAlgorithm,
+    SchnorrKeyId, SchnorrPublicKeyResponse, SignWithECDSAReply, SignWithSchnorrReply,
+    UpdateSettingsArgs, VetKdCurve, VetKdDeriveEncryptedKeyResult, VetKdKeyId,
+    VetKdPublicKeyResult,
 };
 use ic_messaging::SyncMessageRouting;
 use ic_metrics::MetricsRegistry;
 use ic_protobuf::registry::crypto::v1::PublicKey as PublicKeyProto;
 use ic_protobuf::registry::subnet::v1::CatchUpPackageContents;
 use ic_protobuf::registry::{
-    crypto::v1::EcdsaSigningSubnetList,
+    crypto::v1::{EcdsaSigningSubnetList, SchnorrSigningSubnetList, VetKdEnabledSubnetList},
     node::v1::{ConnectionEndpoint, NodeRecord},
     provisional_whitelist::v1::ProvisionalWhitelist as PbProvisionalWhitelist,
     routing_table::v1::CanisterMigrations as PbCanisterMigrations,
@@ -61,17 +70,23 @@ use ic_registry_client_helpers::subnet::{SubnetListRegistry, SubnetRegistry};
 use ic_registry_keys::{
     make_canister_migrations_record_key, make_catch_up_package_contents_key, make_crypto_node_key,
     make_ecdsa_signing_subnet_list_key, make_node_record_key,
-    make_provisional_whitelist_record_key, make_routing_table_record_key, ROOT_SUBNET_ID_KEY,
+    make_provisional_whitelist_record_key, make_routing_table_record_key,
+    make_schnorr_signing_subnet_list_key, make_vetkd_enabled_subnet_list_key, ROOT_SUBNET_ID_KEY,
 };
 use ic_registry_proto_data_provider::{ProtoRegistryDataProvider, INITIAL_REGISTRY_VERSION};
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_routing_table::{
     routing_table_insert_subnet, CanisterIdRange, CanisterIdRanges, RoutingTable,
 };
-use ic_registry_subnet_features::{EcdsaConfig, SubnetFeatures, DEFAULT_ECDSA_MAX_QUEUE_SIZE};
+use ic_registry_subnet_features::{
+    EcdsaConfig, SchnorrConfig, SubnetFeatures, VetKdConfig, DEFAULT_ECDSA_MAX_QUEUE_SIZE,
+    DEFAULT_SCHNORR_MAX_QUEUE_SIZE, DEFAULT_VETKD_MAX_QUEUE_SIZE,
+};
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::canister_state::system_state::CyclesUseCase;
-use ic_replicated_state::metadata_state::subnet_call_context_manager::SignWithEcdsaContext;
+use ic_replicated_state::metadata_state::subnet_call_context_manager::{
+    SignWithEcdsaContext, SignWithSchnorrContext, VetKdDeriveEncryptedKeyContext,
+};
 use ic_replicated_state::page_map::Buffer;
 use ic_replicated_state::{
     canister_state::{NumWasmPages, WASM_PAGE_SIZE_IN_BYTES},
@@ -97,13 +112,13 @@ use ic_types::crypto::threshold_sig::ni_dkg::{
 };
 pub use ic_types::crypto::threshold_sig::ThresholdSigPublicKey;
 use ic_types::crypto::{
-    canister_threshold_sig::MasterEcdsaPublicKey, AlgorithmId, CombinedThresholdSig,
-    CombinedThresholdSigOf, KeyPurpose, Signable, Signed,
+    canister_threshold_sig::{ExtendedDerivationPath, MasterPublicKey},
+    AlgorithmId, CombinedThresholdSig, CombinedThresholdSigOf, KeyPurpose, Signable, Signed,
 };
 use ic_types::malicious_flags::MaliciousFlags;
 use ic_types::messages::{
-    CallbackId, Certificate, CertificateDelegation, RejectContext, Response,
-    EXPECTED_MESSAGE_ID_LENGTH,
+    CallbackId, Certificate, CertificateDelegation, ReadState, RejectContext, Response,
+    EXPECTED_MESSAGE_ID_LENGTH, NO_DEADLINE,
 };
 use ic_types::signature::ThresholdSignature;
 use ic_types::time::GENESIS;
@@ -124,6 +139,7 @@ pub use ic_types::{
     time::Time,
     CanisterId, CryptoHashOfState, Cycles, PrincipalId, SubnetId, UserId,
 };
+use ic_validator::CanisterIdSet;
 use ic_xnet_payload_builder::{
     certified_slice_pool::{certified_slice_count_bytes, CertifiedSliceError},
     ExpectedIndices, RefillTaskHandle, XNetPayloadBuilderImpl, XNetPayloadBuilderMetrics,
@@ -144,7 +160,10 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::RwLock;
 use std::time::{Duration, Instant, SystemTime};
-use std::{collections::BTreeMap, convert::TryFrom};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+};
 use std::{fmt, io};
 use tempfile::TempDir;
 use tokio::runtime::Runtime;
@@ -219,6 +238,8 @@ fn make_nodes_registry(
     subnet_id: SubnetId,
     subnet_type: SubnetType,
     ecdsa_keys: &[EcdsaKeyId],
+    schnorr_keys: &[SchnorrKeyId],
+    vetkd_keys: &[VetKdKeyId],
     features: SubnetFeatures,
     registry_data_provider: Arc<ProtoRegistryDataProvider>,
     nodes: &Vec<StateMachineNode>,
@@ -246,6 +267,30 @@ fn make_nodes_registry(
             )
             .unwrap();
     }
+    for key_id in schnorr_keys {
+        let id = make_schnorr_signing_subnet_list_key(key_id);
+        registry_data_provider
+            .add(
+                &id.clone(),
+                registry_version,
+                Some(SchnorrSigningSubnetList {
+                    subnets: vec![subnet_id_proto.clone()],
+                }),
+            )
+            .unwrap();
+    }
+    for key_id in vetkd_keys {
+        let id = make_vetkd_enabled_subnet_list_key(key_id);
+        registry_data_provider
+            .add(
+                &id.clone(),
+                registry_version,
+                Some(VetKdEnabledSubnetList {
+                    subnets: vec![subnet_id_proto.clone()],
+                }),
+            )
+            .unwrap();
+    }
 
     for node in nodes {
         let node_record = NodeRecord {
@@ -303,6 +348,15 @@ fn make_nodes_registry(
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
         })
+        .with_schnorr_config(SchnorrConfig {
+            key_ids: schnorr_keys.to_vec(),
+            max_queue_size: Some(DEFAULT_SCHNORR_MAX_QUEUE_SIZE),
+            signature_request_timeout_ns: None,
+        })
+        .with_vetkd_config(VetKdConfig {
+            key_ids: vetkd_keys.to_vec(),
+            max_queue_size: Some(DEFAULT_VETKD_MAX_QUEUE_SIZE),
+        })
         .with_features(features)
         .build();
 
@@ -564,6 +618,17 @@ impl From<u64> for StateMachineNode {
     }
 }
 
+/// A stand-in for the bitcoin adapter of a subnet: it answers the requests which
+/// canisters with privileged access to the bitcoin API (e.g., the bitcoin canister)
+/// address to the bitcoin adapter via the management canister.
+pub trait BitcoinAdapter: Send + Sync {
+    /// Returns the response of the bitcoin adapter to the given request.
+    fn handle_request(
+        &self,
+        request: BitcoinAdapterRequestWrapper,
+    ) -> BitcoinAdapterResponseWrapper;
+}
+
 /// Represents a replicated state machine detached from the network layer that
 /// can be used to test this part of the stack in isolation.
 pub struct StateMachine {
@@ -572,6 +637,9 @@ pub struct StateMachine {
     public_key_der: Vec<u8>,
     secret_key: SecretKeyBytes,
     ecdsa_secret_key: PrivateKey,
+    schnorr_bip340_secret_key: PrivateKey,
+    schnorr_ed25519_secret_key: [u8; 32],
+    vetkd_secret_key: [u8; 32],
     registry_data_provider: Arc<ProtoRegistryDataProvider>,
     registry_client: Arc<FakeRegistryClient>,
     pub state_manager: Arc<StateManagerImpl>,
@@ -589,9 +657,10 @@ pub struct StateMachine {
     checkpoints_enabled: std::sync::atomic::AtomicBool,
     nonce: std::sync::atomic::AtomicU64,
     time: std::sync::atomic::AtomicU64,
-    ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+    chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey>,
     replica_logger: ReplicaLogger,
     nodes: Vec<StateMachineNode>,
+    bitcoin_adapter: Option<Arc<dyn BitcoinAdapter>>,
 }
 
 impl Default for StateMachine {
@@ -622,6 +691,8 @@ pub struct StateMachineBuilder {
     routing_table: RoutingTable,
     use_cost_scaling_flag: bool,
     ecdsa_keys: Vec<EcdsaKeyId>,
+    schnorr_keys: Vec<SchnorrKeyId>,
+    vetkd_keys: Vec<VetKdKeyId>,
     features: SubnetFeatures,
     runtime: Option<Arc<Runtime>>,
     registry_data_provider: Arc<ProtoRegistryDataProvider>,
@@ -629,6 +700,7 @@ pub struct StateMachineBuilder {
     is_root_subnet: bool,
     seq_no: u8,
     with_extra_canister_range: Option<std::ops::RangeInclusive<CanisterId>>,
+    bitcoin_adapter: Option<Arc<dyn BitcoinAdapter>>,
 }
 
 impl StateMachineBuilder {
@@ -649,6 +721,8 @@ impl StateMachineBuilder {
                 curve: EcdsaCurve::Secp256k1,
                 name: "master_ecdsa_public_key".to_string(),
             }],
+            schnorr_keys: vec![],
+            vetkd_keys: vec![],
             features: SubnetFeatures {
                 http_requests: true,
                 ..SubnetFeatures::default()
@@ -659,6 +733,7 @@ impl StateMachineBuilder {
             is_root_subnet: false,
             seq_no: 0,
             with_extra_canister_range: None,
+            bitcoin_adapter: None,
         }
     }
 
@@ -766,6 +841,51 @@ impl StateMachineBuilder {
         }
     }
 
+    pub fn with_schnorr_key(self, key: SchnorrKeyId) -> Self {
+        let mut schnorr_keys = self.schnorr_keys;
+        schnorr_keys.push(key);
+        Self {
+            schnorr_keys,
+            ..self
+        }
+    }
+
+    /// Adds a BIP340 and an Ed25519 Schnorr key whose names are unique
+    /// to this subnet.
+    pub fn with_multisubnet_schnorr_keys(self) -> Self {
+        let name = format!("master_schnorr_public_key_{}", self.seq_no);
+        Self {
+            schnorr_keys: vec![
+                SchnorrKeyId {
+                    algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+                    name: name.clone(),
+                },
+                SchnorrKeyId {
+                    algorithm: SchnorrAlgorithm::Ed25519,
+                    name,
+                },
+            ],
+            ..self
+        }
+    }
+
+    pub fn with_vetkd_key(self, key: VetKdKeyId) -> Self {
+        let mut vetkd_keys = self.vetkd_keys;
+        vetkd_keys.push(key);
+        Self { vetkd_keys, ..self }
+    }
+
+    /// Adds a vetKD key whose name is unique to this subnet.
+    pub fn with_multisubnet_vetkd_keys(self) -> Self {
+        Self {
+            vetkd_keys: vec![VetKdKeyId {
+                curve: VetKdCurve::Bls12_381_G2,
+                name: format!("master_vetkd_public_key_{}", self.seq_no),
+            }],
+            ..self
+        }
+    }
+
     pub fn with_features(self, features: SubnetFeatures) -> Self {
         Self { features, ..self }
     }
@@ -798,6 +918,15 @@ impl StateMachineBuilder {
         }
     }
 
+    /// Answers the requests to the bitcoin adapter using the given stand-in.
+    /// Without a bitcoin adapter, such requests are never answered.
+    pub fn with_bitcoin_adapter(self, bitcoin_adapter: Arc<dyn BitcoinAdapter>) -> Self {
+        Self {
+            bitcoin_adapter: Some(bitcoin_adapter),
+            ..self
+        }
+    }
+
     pub fn build_internal(self) -> StateMachine {
         StateMachine::setup_from_dir(
             self.state_dir,
@@ -810,6 +939,8 @@ impl StateMachineBuilder {
             self.subnet_id,
             self.use_cost_scaling_flag,
             self.ecdsa_keys,
+            self.schnorr_keys,
+            self.vetkd_keys,
             self.features,
             self.runtime.unwrap_or_else(|| {
                 tokio::runtime::Builder::new_current_thread()
@@ -821,6 +952,7 @@ impl StateMachineBuilder {
             self.lsmt_override,
             self.is_root_subnet,
             self.seq_no,
+            self.bitcoin_adapter,
         )
     }
 
@@ -1009,9 +1141,25 @@ impl StateMachine {
                 originator_reply_callback: id,
                 refund: Cycles::zero(),
                 response_payload: MsgPayload::Data(reply.encode()),
+                deadline: NO_DEADLINE,
             });
         }
 
+        // Push responses to Schnorr management canister calls into `PayloadBuilder`.
+        payload
+            .consensus_responses
+            .extend(self.sign_with_schnorr_responses(&state));
+
+        // Push responses to vetKD management canister calls into `PayloadBuilder`.
+        payload
+            .consensus_responses
+            .extend(self.vetkd_derive_encrypted_key_responses(&state));
+
+        // Push responses to bitcoin adapter requests into `PayloadBuilder`.
+        payload
+            .bitcoin_adapter_responses
+            .extend(self.bitcoin_adapter_responses(&state));
+
         // Finally execute the payload.
         self.execute_payload(payload);
     }
@@ -1037,12 +1185,15 @@ impl StateMachine {
         subnet_id: Option<SubnetId>,
         use_cost_scaling_flag: bool,
         ecdsa_keys: Vec<EcdsaKeyId>,
+        schnorr_keys: Vec<SchnorrKeyId>,
+        vetkd_keys: Vec<VetKdKeyId>,
         features: SubnetFeatures,
         runtime: Arc<Runtime>,
         registry_data_provider: Arc<ProtoRegistryDataProvider>,
         lsmt_override: Option<FlagStatus>,
         is_root_subnet: bool,
         seq_no: u8,
+        bitcoin_adapter: Option<Arc<dyn BitcoinAdapter>>,
     ) -> Self {
         let replica_logger = replica_logger();
 
@@ -1067,6 +1218,8 @@ impl StateMachine {
             subnet_id,
             subnet_type,
             &ecdsa_keys,
+            &schnorr_keys,
+            &vetkd_keys,
             features,
             registry_data_provider.clone(),
             &nodes,
@@ -1085,6 +1238,18 @@ impl StateMachine {
             hypervisor_config.deterministic_time_slicing = FlagStatus::Disabled;
         }
 
+        // Schnorr signing requests are answered by the state machine itself
+        // (see `sign_with_schnorr_responses`), so the API can be enabled
+        // whenever the subnet holds Schnorr keys.
+        if !schnorr_keys.is_empty() {
+            hypervisor_config.threshold_schnorr = FlagStatus::Enabled;
+        }
+        // The same holds for vetKD requests (see
+        // `vetkd_derive_encrypted_key_responses`).
+        if !vetkd_keys.is_empty() {
+            hypervisor_config.vetkd = FlagStatus::Enabled;
+        }
+
         // We are not interested in ingress signature validation.
         let malicious_flags = MaliciousFlags {
             maliciously_disable_ingress_validation: true,
@@ -1159,29 +1324,81 @@ impl StateMachine {
         let ecdsa_secret_key: PrivateKey =
             PrivateKey::deserialize_sec1(private_key_bytes.as_slice()).unwrap();
 
-        let mut ecdsa_subnet_public_keys = BTreeMap::new();
+        let mut chain_key_subnet_public_keys = BTreeMap::new();
 
         for ecdsa_key in ecdsa_keys {
-            ecdsa_subnet_public_keys.insert(
-                ecdsa_key,
-                MasterEcdsaPublicKey {
+            chain_key_subnet_public_keys.insert(
+                MasterPublicKeyId::Ecdsa(ecdsa_key),
+                MasterPublicKey {
                     algorithm_id: AlgorithmId::EcdsaSecp256k1,
                     public_key: ecdsa_secret_key.public_key().serialize_sec1(true),
                 },
             );
         }
 
-        ecdsa_subnet_public_keys.insert(
-            EcdsaKeyId {
+        chain_key_subnet_public_keys.insert(
+            MasterPublicKeyId::Ecdsa(EcdsaKeyId {
                 curve: EcdsaCurve::Secp256k1,
                 name: "master_ecdsa_public_key".to_string(),
-            },
-            MasterEcdsaPublicKey {
+            }),
+            MasterPublicKey {
                 algorithm_id: AlgorithmId::EcdsaSecp256k1,
                 public_key: ecdsa_secret_key.public_key().serialize_sec1(true),
             },
         );
 
+        // Like the ECDSA key above, the Schnorr keys are fixed to have
+        // deterministic results. Please do not use these keys anywhere.
+        let schnorr_bip340_secret_key = PrivateKey::deserialize_sec1(
+            &hex::decode("5c8a1bd8b1ab2a3b7e4c6f0e9d3a2f17e8c4b6a5d0f1e2c3b4a5968778695a4b")
+                .unwrap(),
+        )
+        .unwrap();
+        // A canonical little-endian Ed25519 scalar (i.e., smaller than the group order).
+        let schnorr_ed25519_secret_key: [u8; 32] =
+            hex::decode("3b7a9c2e5d1f40a8b6c4e2d0f1a3b5c7d9e0f2a4b6c8d0e1f3a5b7c9d1e3f506")
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        for schnorr_key in schnorr_keys {
+            let (algorithm_id, public_key) = match schnorr_key.algorithm {
+                SchnorrAlgorithm::Bip340Secp256k1 => (
+                    AlgorithmId::ThresholdSchnorrBip340,
+                    schnorr_bip340_secret_key.public_key().serialize_sec1(true),
+                ),
+                SchnorrAlgorithm::Ed25519 => (
+                    AlgorithmId::ThresholdEd25519,
+                    ed25519_public_key(&schnorr_ed25519_secret_key).to_vec(),
+                ),
+            };
+            chain_key_subnet_public_keys.insert(
+                MasterPublicKeyId::Schnorr(schnorr_key),
+                MasterPublicKey {
+                    algorithm_id,
+                    public_key,
+                },
+            );
+        }
+
+        // A big-endian BLS12-381 scalar (i.e., smaller than the group order).
+        let vetkd_secret_key: [u8; 32] =
+            hex::decode("2d4f1c8b6e0a93d7f5b2c4e6a8d0f1b3c5e7a9d1f2b4c6e8a0d2f4b6c8e0a1d3")
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        for vetkd_key in vetkd_keys {
+            chain_key_subnet_public_keys.insert(
+                MasterPublicKeyId::VetKd(vetkd_key),
+                MasterPublicKey {
+                    algorithm_id: AlgorithmId::ThresBls12_381,
+                    public_key: vetkd_master_public_key(&vetkd_secret_key)
+                        .expect("invalid vetKD secret key"),
+                },
+            );
+        }
+
         let time_source = FastForwardTimeSource::new();
         time_source.set_time(time).unwrap();
         let consensus_time = Arc::new(PocketConsensusTime::new(time));
@@ -1211,6 +1428,9 @@ impl StateMachine {
             public_key,
             public_key_der,
             ecdsa_secret_key,
+            schnorr_bip340_secret_key,
+            schnorr_ed25519_secret_key,
+            vetkd_secret_key,
             registry_data_provider,
             registry_client: registry_client.clone(),
             state_manager,
@@ -1230,9 +1450,10 @@ impl StateMachine {
             checkpoints_enabled: std::sync::atomic::AtomicBool::new(checkpoints_enabled),
             nonce: std::sync::atomic::AtomicU64::new(nonce),
             time: std::sync::atomic::AtomicU64::new(time.as_nanos_since_unix_epoch()),
-            ecdsa_subnet_public_keys,
+            chain_key_subnet_public_keys,
             replica_logger,
             nodes,
+            bitcoin_adapter,
         }
     }
 
@@ -1360,6 +1581,60 @@ impl StateMachine {
         })
     }
 
+    /// Reads the certified state tree at the paths of the given `read_state` request and
+    /// returns the CBOR-encoded certificate, as in a response to a `read_state` request,
+    /// with an optional subnet delegation from the NNS. The time is always included in the
+    /// certificate.
+    ///
+    /// As on the replica, the sender must be authorized to read the requested paths:
+    /// `targets` are the canister IDs the sender's delegations are restricted to
+    /// (`None` if they are not restricted).
+    pub fn read_state(
+        &self,
+        read_state: &ReadState,
+        effective_principal_id: PrincipalId,
+        targets: Option<&BTreeSet<CanisterId>>,
+        delegation: Option<CertificateDelegation>,
+    ) -> Result<Vec<u8>, String> {
+        self.certify_latest_state();
+        let certified_state_reader = match self.state_manager.get_certified_state_snapshot() {
+            Some(reader) => reader,
+            None => {
+                return Err("No certified state available.".to_string());
+            }
+        };
+        let targets = match targets {
+            Some(targets) => CanisterIdSet::try_from_iter(targets.iter().copied())
+                .map_err(|err| format!("Invalid delegation targets: {:?}.", err))?,
+            None => CanisterIdSet::all(),
+        };
+        verify_paths(
+            certified_state_reader.get_state(),
+            &read_state.source,
+            &read_state.paths,
+            &targets,
+            effective_principal_id,
+        )
+        .map_err(|err| err.message)?;
+        let mut paths = read_state.paths.clone();
+        paths.push(LabeledTreePath::from(Label::from("time")));
+        let labeled_tree = sparse_labeled_tree_from_paths(&paths)
+            .map_err(|_| "Failed to parse paths: path too long.".to_string())?;
+        let (tree, certification) = match certified_state_reader.read_certified_state(&labeled_tree)
+        {
+            Some(r) => r,
+            None => {
+                return Err("Certified state could not be read.".to_string());
+            }
+        };
+        let signature = certification.signed.signature.signature.get().0;
+        Ok(into_cbor(&Certificate {
+            tree,
+            signature: Blob(signature),
+            delegation,
+        }))
+    }
+
     /// If the argument is true, the state machine will create an on-disk
     /// checkpoint for each new state it creates.
     ///
@@ -1371,6 +1646,19 @@ impl StateMachine {
             .store(enabled, core::sync::atomic::Ordering::Relaxed)
     }
 
+    /// Makes the state machine tick once with checkpoints enabled and waits
+    /// until the resulting checkpoint is written to disk.
+    ///
+    /// Afterwards, the state directory can be used to recover the state
+    /// machine via [StateMachineBuilder::with_state_dir].
+    pub fn checkpointed_tick(&self) {
+        let cp_enabled = self.checkpoints_enabled.load(Ordering::Relaxed);
+        self.set_checkpoints_enabled(true);
+        self.tick();
+        self.set_checkpoints_enabled(cp_enabled);
+        self.state_manager.flush_tip_channel();
+    }
+
     /// Returns the latest state.
     pub fn get_latest_state(&self) -> Arc<ReplicatedState> {
         self.state_manager.get_latest_state().take()
@@ -1546,11 +1834,142 @@ impl StateMachine {
                 originator_reply_callback: id,
                 refund: Cycles::zero(),
                 response_payload: MsgPayload::Data(reply.encode()),
+                deadline: NO_DEADLINE,
             });
         }
+        payload
+            .consensus_responses
+            .extend(self.sign_with_schnorr_responses(&state));
+        payload
+            .consensus_responses
+            .extend(self.vetkd_derive_encrypted_key_responses(&state));
+        payload
+            .bitcoin_adapter_responses
+            .extend(self.bitcoin_adapter_responses(&state));
         self.execute_payload(payload);
     }
 
+    /// Answers all pending `sign_with_schnorr` requests by signing with the
+    /// Schnorr keys of this state machine.
+    fn sign_with_schnorr_responses(&self, state: &ReplicatedState) -> Vec<Response> {
+        state
+            .metadata
+            .subnet_call_context_manager
+            .sign_with_schnorr_contexts
+            .iter()
+            .map(|(id, context)| {
+                let signature = match context.key_id.algorithm {
+                    SchnorrAlgorithm::Bip340Secp256k1 => {
+                        let derivation_path = DerivationPath::new(
+                            std::iter::once(context.request.sender.get().as_slice().to_vec())
+                                .chain(context.derivation_path.clone().into_iter())
+                                .map(DerivationIndex)
+                                .collect::<Vec<_>>(),
+                        );
+                        sign_bip340_message_with_derived_key(
+                            &self.schnorr_bip340_secret_key,
+                            &context.message,
+                            derivation_path,
+                        )
+                    }
+                    SchnorrAlgorithm::Ed25519 => sign_ed25519_message_with_derived_key(
+                        &self.schnorr_ed25519_secret_key,
+                        &context.message,
+                        &ExtendedDerivationPath {
+                            caller: context.request.sender.get(),
+                            derivation_path: context.derivation_path.clone(),
+                        },
+                    ),
+                };
+                let reply = SignWithSchnorrReply { signature };
+                Response {
+                    originator: CanisterId::ic_00(),
+                    respondent: CanisterId::ic_00(),
+                    originator_reply_callback: *id,
+                    refund: Cycles::zero(),
+                    response_payload: MsgPayload::Data(reply.encode()),
+                    deadline: NO_DEADLINE,
+                }
+            })
+            .collect()
+    }
+
+    /// Answers all pending `vetkd_derive_encrypted_key` requests by deriving
+    /// the keys from the vetKD master secret key of this state machine.
+    ///
+    /// Requests with an invalid encryption public key are rejected.
+    fn vetkd_derive_encrypted_key_responses(&self, state: &ReplicatedState) -> Vec<Response> {
+        state
+            .metadata
+            .subnet_call_context_manager
+            .vetkd_derive_encrypted_key_contexts
+            .iter()
+            .map(|(id, context)| {
+                let response_payload = match derive_vetkd_encrypted_key(
+                    &self.vetkd_secret_key,
+                    &ExtendedDerivationPath {
+                        caller: context.request.sender.get(),
+                        derivation_path: context.derivation_path.clone(),
+                    },
+                    &context.derivation_id,
+                    &context.encryption_public_key,
+                    &mut StdRng::from_seed(context.pseudo_random_id),
+                ) {
+                    Ok(encrypted_key) => {
+                        MsgPayload::Data(VetKdDeriveEncryptedKeyResult { encrypted_key }.encode())
+                    }
+                    Err(err) => MsgPayload::Reject(RejectContext::new(
+                        RejectCode::CanisterReject,
+                        format!("vetkd_derive_encrypted_key failed: {}", err),
+                    )),
+                };
+                Response {
+                    originator: CanisterId::ic_00(),
+                    respondent: CanisterId::ic_00(),
+                    originator_reply_callback: *id,
+                    refund: Cycles::zero(),
+                    response_payload,
+                    deadline: NO_DEADLINE,
+                }
+            })
+            .collect()
+    }
+
+    /// Answers all pending requests to the bitcoin adapter using the bitcoin adapter
+    /// stand-in of this state machine (if any).
+    fn bitcoin_adapter_responses(&self, state: &ReplicatedState) -> Vec<BitcoinAdapterResponse> {
+        let bitcoin_adapter = match &self.bitcoin_adapter {
+            Some(bitcoin_adapter) => bitcoin_adapter,
+            None => return vec![],
+        };
+        let subnet_call_context_manager = &state.metadata.subnet_call_context_manager;
+        let get_successors_requests = subnet_call_context_manager
+            .bitcoin_get_successors_contexts
+            .iter()
+            .map(|(callback_id, context)| {
+                (
+                    callback_id,
+                    BitcoinAdapterRequestWrapper::GetSuccessorsRequest(context.payload.clone()),
+                )
+            });
+        let send_transaction_requests = subnet_call_context_manager
+            .bitcoin_send_transaction_internal_contexts
+            .iter()
+            .map(|(callback_id, context)| {
+                (
+                    callback_id,
+                    BitcoinAdapterRequestWrapper::SendTransactionRequest(context.payload.clone()),
+                )
+            });
+        get_successors_requests
+            .chain(send_transaction_requests)
+            .map(|(callback_id, request)| BitcoinAdapterResponse {
+                response: bitcoin_adapter.handle_request(request),
+                callback_id: callback_id.get(),
+            })
+            .collect()
+    }
+
     /// Makes the state machine tick until there are no more messages in the system.
     /// This method is useful if you need to wait for asynchronous canister communication to
     /// complete.
@@ -1595,11 +2014,11 @@ impl StateMachine {
             messages: BatchMessages {
                 signed_ingress_msgs: payload.ingress_messages,
                 certified_stream_slices: payload.xnet_payload.stream_slices,
-                bitcoin_adapter_responses: vec![],
+                bitcoin_adapter_responses: payload.bitcoin_adapter_responses,
                 query_stats: payload.query_stats,
             },
             randomness: Randomness::from(seed),
-            ecdsa_subnet_public_keys: self.ecdsa_subnet_public_keys.clone(),
+            chain_key_subnet_public_keys: self.chain_key_subnet_public_keys.clone(),
             ecdsa_quadruple_ids: BTreeMap::new(),
             registry_version: self.registry_client.get_latest_version(),
             time: Time::from_nanos_since_unix_epoch(self.time.load(Ordering::Relaxed)),
@@ -2632,6 +3051,28 @@ impl StateMachine {
             .clone()
     }
 
+    /// Returns sign with Schnorr contexts from internal subnet call context manager.
+    pub fn sign_with_schnorr_contexts(&self) -> BTreeMap<CallbackId, SignWithSchnorrContext> {
+        let state = self.state_manager.get_latest_state().take();
+        state
+            .metadata
+            .subnet_call_context_manager
+            .sign_with_schnorr_contexts
+            .clone()
+    }
+
+    /// Returns vetKD derive encrypted key contexts from internal subnet call context manager.
+    pub fn vetkd_derive_encrypted_key_contexts(
+        &self,
+    ) -> BTreeMap<CallbackId, VetKdDeriveEncryptedKeyContext> {
+        let state = self.state_manager.get_latest_state().take();
+        state
+            .metadata
+            .subnet_call_context_manager
+            .vetkd_derive_encrypted_key_contexts
+            .clone()
+    }
+
     /// Returns canister HTTP request contexts from internal subnet call context manager.
     pub fn canister_http_request_contexts(
         &self,
@@ -2683,6 +3124,134 @@ fn sign_prehashed_message_with_derived_key(
     signature.to_vec()
 }
 
+/// Computes the tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)` of BIP340.
+fn bip340_tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::hash(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.write(&tag_hash);
+    hasher.write(&tag_hash);
+    for chunk in data {
+        hasher.write(chunk);
+    }
+    hasher.finish()
+}
+
+/// Signs an arbitrary-length message according to BIP340 using the key
+/// derived from `secret_key` along `derivation_path` (with an all-zero chain code).
+fn sign_bip340_message_with_derived_key(
+    secret_key: &PrivateKey,
+    message: &[u8],
+    derivation_path: DerivationPath,
+) -> Vec<u8> {
+    use k256::elliptic_curve::{ops::Reduce, point::AffineCoordinates, Field, PrimeField};
+    use k256::{FieldBytes, ProjectivePoint, Scalar, U256};
+
+    const CHAIN_CODE: &[u8] = &[0; 32];
+
+    let derived_private_key_bytes = derivation_path
+        .private_key_derivation(&secret_key.serialize_sec1(), CHAIN_CODE)
+        .expect("couldn't derive schnorr private key");
+    let mut d = Option::<Scalar>::from(Scalar::from_repr(FieldBytes::clone_from_slice(
+        &derived_private_key_bytes.derived_private_key,
+    )))
+    .expect("derived private key is not a valid scalar");
+
+    // BIP340 uses x-only public keys, i.e., the secret key is negated
+    // if the corresponding point has an odd y coordinate.
+    let public_key = (ProjectivePoint::GENERATOR * d).to_affine();
+    if bool::from(public_key.y_is_odd()) {
+        d = -d;
+    }
+    let public_key_x = public_key.x();
+
+    // Deterministic nonce derivation with all-zero auxiliary randomness.
+    let aux_hash = bip340_tagged_hash("BIP0340/aux", &[&[0u8; 32]]);
+    let masked_key: Vec<u8> = d
+        .to_bytes()
+        .iter()
+        .zip(aux_hash.iter())
+        .map(|(a, b)| a ^ b)
+        .collect();
+    let nonce_hash = bip340_tagged_hash(
+        "BIP0340/nonce",
+        &[masked_key.as_slice(), public_key_x.as_slice(), message],
+    );
+    let mut k = <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(nonce_hash));
+    assert!(!bool::from(k.is_zero()), "BIP340 nonce must not be zero");
+
+    let nonce_point = (ProjectivePoint::GENERATOR * k).to_affine();
+    if bool::from(nonce_point.y_is_odd()) {
+        k = -k;
+    }
+    let nonce_point_x = nonce_point.x();
+
+    let challenge_hash = bip340_tagged_hash(
+        "BIP0340/challenge",
+        &[nonce_point_x.as_slice(), public_key_x.as_slice(), message],
+    );
+    let e = <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(challenge_hash));
+    let s = k + e * d;
+
+    let mut signature = nonce_point_x.to_vec();
+    signature.extend_from_slice(&s.to_bytes());
+    signature
+}
+
+/// Returns the encoded Ed25519 public key for the given secret scalar.
+fn ed25519_public_key(secret_scalar: &[u8; 32]) -> [u8; 32] {
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
+
+    let scalar =
+        Scalar::from_canonical_bytes(*secret_scalar).expect("Ed25519 scalar is not canonical");
+    (&scalar * &ED25519_BASEPOINT_TABLE).compress().to_bytes()
+}
+
+/// Signs a message according to Ed25519 using the key derived from the
+/// `secret_scalar` along the `derivation_path`.
+///
+/// Since the derived key is a scalar rather than an Ed25519 seed, the nonce is
+/// derived from the scalar and the message instead of the seed's hash prefix.
+fn sign_ed25519_message_with_derived_key(
+    secret_scalar: &[u8; 32],
+    message: &[u8],
+    derivation_path: &ExtendedDerivationPath,
+) -> Vec<u8> {
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
+
+    let derived_scalar = derive_ed25519_secret_scalar(secret_scalar, derivation_path)
+        .expect("couldn't derive Ed25519 secret key");
+    let a = Scalar::from_canonical_bytes(derived_scalar)
+        .expect("derived Ed25519 scalar is not canonical");
+    let public_key = (&a * &ED25519_BASEPOINT_TABLE).compress();
+
+    let mut nonce_hasher = Sha512::new();
+    nonce_hasher.write(b"ic-state-machine-tests-ed25519-nonce");
+    nonce_hasher.write(a.as_bytes());
+    nonce_hasher.write(message);
+    let r = Scalar::from_bytes_mod_order_wide(&nonce_hasher.finish());
+    let nonce_point = (&r * &ED25519_BASEPOINT_TABLE).compress();
+
+    let mut challenge_hasher = Sha512::new();
+    challenge_hasher.write(nonce_point.as_bytes());
+    challenge_hasher.write(public_key.as_bytes());
+    challenge_hasher.write(message);
+    let k = Scalar::from_bytes_mod_order_wide(&challenge_hasher.finish());
+
+    let s = r + k * a;
+
+    let mut signature = nonce_point.as_bytes().to_vec();
+    signature.extend_from_slice(s.as_bytes());
+
+    let verification_key = ed25519_consensus::VerificationKey::try_from(public_key.to_bytes())
+        .expect("invalid derived Ed25519 public key");
+    let sig_bytes: [u8; 64] = signature.clone().try_into().unwrap();
+    verification_key
+        .verify(&ed25519_consensus::Signature::from(sig_bytes), message)
+        .expect("failed to verify Ed25519 signature");
+
+    signature
+}
+
 #[derive(Clone)]
 pub struct PayloadBuilder {
     expiry_time: Time,
@@ -2690,6 +3259,7 @@ pub struct PayloadBuilder {
     ingress_messages: Vec<SignedIngress>,
     xnet_payload: XNetPayload,
     consensus_responses: Vec<Response>,
+    bitcoin_adapter_responses: Vec<BitcoinAdapterResponse>,
     query_stats: Option<QueryStatsPayload>,
 }
 
@@ -2701,6 +3271,7 @@ impl Default for PayloadBuilder {
             ingress_messages: Default::default(),
             xnet_payload: Default::default(),
             consensus_responses: Default::default(),
+            bitcoin_adapter_responses: Default::default(),
             query_stats: Default::default(),
         }
         .with_max_expiry_time_from_now(GENESIS.into())
@@ -2792,6 +3363,7 @@ impl PayloadBuilder {
             originator_reply_callback: id,
             refund: Cycles::zero(),
             response_payload: MsgPayload::Data(payload.encode()),
+            deadline: NO_DEADLINE,
         });
         self
     }
@@ -2808,10 +3380,16 @@ impl PayloadBuilder {
             originator_reply_callback: id,
             refund: Cycles::zero(),
             response_payload: MsgPayload::Reject(RejectContext::new(code, message)),
+            deadline: NO_DEADLINE,
         });
         self
     }
 
+    pub fn bitcoin_adapter_response(mut self, response: BitcoinAdapterResponse) -> Self {
+        self.bitcoin_adapter_responses.push(response);
+        self
+    }
+
     pub fn ingress_ids(&self) -> Vec<MessageId> {
         self.ingress_messages.iter().map(|i| i.id()).collect()
     }
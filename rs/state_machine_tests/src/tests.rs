@@ -122,3 +122,57 @@ fn public_derivation_path() {
         derived_public_key_bytes.derived_public_key
     );
 }
+
+#[test]
+fn check_bip340_signature_with_empty_derivation_path_matches_bip340_test_vector() {
+    // Test vector 0 from the BIP340 specification, which uses all-zero
+    // auxiliary randomness like `sign_bip340_message_with_derived_key`.
+    let secret_key = PrivateKey::deserialize_sec1(
+        &hex::decode("0000000000000000000000000000000000000000000000000000000000000003").unwrap(),
+    )
+    .unwrap();
+    let message = [0u8; 32];
+
+    let signature = crate::sign_bip340_message_with_derived_key(
+        &secret_key,
+        &message,
+        DerivationPath::new(vec![]),
+    );
+
+    assert_eq!(
+        hex::encode_upper(signature),
+        "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+         25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0"
+    );
+}
+
+#[test]
+fn check_derived_bip340_signature() {
+    const DERIVED_PUBLIC_KEY: &str =
+        "037fc92cf6ffabc1f88bfa7c7948c99e3f3cd4a538f234074398b49a43254d5c97";
+    const SIGNATURE: &str = "346ac90a56383eec2b3ff0c9ccc0d616337be35fde165f47944f85f2212867d1\
+                             1c371ca8a9474a9bb93b0edc4d20f6b85acd36fac50eec8fab7c4c1c8258113d";
+    const MESSAGE: &[u8] = b"Hello, BIP340!";
+    const DERIVATION_PATH: [[u8; 10]; 1] = [[0, 0, 0, 0, 0, 0, 0, 0, 1, 1]];
+
+    let derivation_path = DerivationPath::new(
+        DERIVATION_PATH
+            .iter()
+            .map(|index| DerivationIndex(index.to_vec()))
+            .collect(),
+    );
+    let secret_key = PrivateKey::deserialize_sec1(
+        &hex::decode("fb7d1f5b82336bb65b82bf4f27776da4db71c1ef632c6a7c171c0cbfa2ea4920").unwrap(),
+    )
+    .unwrap();
+
+    let derived_public_key = derivation_path
+        .public_key_derivation(&secret_key.public_key().serialize_sec1(true), &[0; 32])
+        .expect("couldn't derive public key")
+        .derived_public_key;
+    assert_eq!(hex::encode(derived_public_key), DERIVED_PUBLIC_KEY);
+
+    let signature =
+        crate::sign_bip340_message_with_derived_key(&secret_key, MESSAGE, derivation_path);
+    assert_eq!(hex::encode(signature), SIGNATURE);
+}
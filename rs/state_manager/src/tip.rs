@@ -997,8 +997,9 @@ fn serialize_canister_to_tip(
                 .metadata()
                 .clone(),
             total_query_stats: canister_state.scheduler_state.total_query_stats.clone(),
-            log_visibility: canister_state.system_state.log_visibility,
+            log_visibility: canister_state.system_state.log_visibility.clone(),
             canister_log_records: canister_state.system_state.canister_log_records.clone(),
+            wasm_memory_limit: canister_state.system_state.wasm_memory_limit,
         }
         .into(),
     )?;
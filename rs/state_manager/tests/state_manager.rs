@@ -3560,6 +3560,8 @@ fn certified_read_can_certify_node_public_keys_since_v12() {
                 subnet_type: SubnetType::System,
                 subnet_features: SubnetFeatures::default(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: Default::default(),
+                vetkd_keys_held: Default::default(),
             },
         );
 
@@ -24,9 +24,11 @@ use ic_replicated_state::{
 use ic_sys::PageBytes;
 use ic_types::{
     ingress::WasmResult,
-    messages::{CallContextId, RejectContext, Request, MAX_INTER_CANISTER_PAYLOAD_IN_BYTES},
+    messages::{
+        CallContextId, RejectContext, Request, MAX_INTER_CANISTER_PAYLOAD_IN_BYTES, NO_DEADLINE,
+    },
     methods::{SystemMethod, WasmClosure},
-    CanisterId, CanisterTimer, ComputeAllocation, Cycles, MemoryAllocation, NumBytes,
+    CanisterId, CanisterTimer, CoarseTime, ComputeAllocation, Cycles, MemoryAllocation, NumBytes,
     NumInstructions, NumPages, PrincipalId, SubnetId, Time, MAX_STABLE_MEMORY_IN_BYTES,
 };
 use ic_utils::deterministic_operations::deterministic_copy_from_slice;
@@ -231,6 +233,9 @@ pub enum ApiType {
         incoming_cycles: Cycles,
         caller: PrincipalId,
         call_context_id: CallContextId,
+        /// The deadline of the incoming call; `NO_DEADLINE` for ingress
+        /// messages and guaranteed response calls.
+        deadline: CoarseTime,
         /// Begins as empty and used to accumulate data for sending replies.
         #[serde(with = "serde_bytes")]
         response_data: Vec<u8>,
@@ -277,6 +282,9 @@ pub enum ApiType {
         incoming_payload: Vec<u8>,
         incoming_cycles: Cycles,
         call_context_id: CallContextId,
+        /// The deadline of the response; `NO_DEADLINE` for guaranteed
+        /// response calls.
+        deadline: CoarseTime,
         // Begins as empty and used to accumulate data for sending replies.
         #[serde(with = "serde_bytes")]
         response_data: Vec<u8>,
@@ -297,6 +305,9 @@ pub enum ApiType {
         reject_context: RejectContext,
         incoming_cycles: Cycles,
         call_context_id: CallContextId,
+        /// The deadline of the response; `NO_DEADLINE` for guaranteed
+        /// response calls.
+        deadline: CoarseTime,
         // Begins as empty and used to accumulate data for sending replies.
         #[serde(with = "serde_bytes")]
         response_data: Vec<u8>,
@@ -389,6 +400,7 @@ impl ApiType {
         incoming_cycles: Cycles,
         caller: PrincipalId,
         call_context_id: CallContextId,
+        deadline: CoarseTime,
     ) -> Self {
         Self::Update {
             time,
@@ -396,6 +408,7 @@ impl ApiType {
             incoming_cycles,
             caller,
             call_context_id,
+            deadline,
             response_data: vec![],
             response_status: ResponseStatus::NotRepliedYet,
             outgoing_request: None,
@@ -449,6 +462,7 @@ impl ApiType {
         incoming_payload: Vec<u8>,
         incoming_cycles: Cycles,
         call_context_id: CallContextId,
+        deadline: CoarseTime,
         replied: bool,
         execution_mode: ExecutionMode,
         call_context_instructions_executed: NumInstructions,
@@ -459,6 +473,7 @@ impl ApiType {
             incoming_payload,
             incoming_cycles,
             call_context_id,
+            deadline,
             response_data: vec![],
             response_status: if replied {
                 ResponseStatus::AlreadyReplied
@@ -479,6 +494,7 @@ impl ApiType {
         reject_context: RejectContext,
         incoming_cycles: Cycles,
         call_context_id: CallContextId,
+        deadline: CoarseTime,
         replied: bool,
         execution_mode: ExecutionMode,
         call_context_instructions_executed: NumInstructions,
@@ -489,6 +505,7 @@ impl ApiType {
             reject_context,
             incoming_cycles,
             call_context_id,
+            deadline,
             response_data: vec![],
             response_status: if replied {
                 ResponseStatus::AlreadyReplied
@@ -1074,6 +1091,26 @@ impl SystemApiImpl {
         }
     }
 
+    /// Returns true if `memory.grow` must respect the Wasm memory limit of the
+    /// canister in the current execution, i.e. in update calls, heartbeats,
+    /// global timers, and replicated response callbacks.
+    fn enforces_wasm_memory_limit(&self) -> bool {
+        match &self.api_type {
+            ApiType::Start { .. }
+            | ApiType::Init { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::Cleanup { .. }
+            | ApiType::ReplicatedQuery { .. }
+            | ApiType::NonReplicatedQuery { .. }
+            | ApiType::InspectMessage { .. } => false,
+            ApiType::Update { .. } | ApiType::SystemTask { .. } => true,
+            ApiType::ReplyCallback { execution_mode, .. }
+            | ApiType::RejectCallback { execution_mode, .. } => {
+                *execution_mode == ExecutionMode::Replicated
+            }
+        }
+    }
+
     fn ic0_call_cycles_add_helper(
         &mut self,
         method_name: &str,
@@ -2008,6 +2045,50 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
+    fn ic0_call_with_best_effort_response(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        let result = match &mut self.api_type {
+            ApiType::Start { .. }
+            | ApiType::Init { .. }
+            | ApiType::ReplicatedQuery { .. }
+            | ApiType::NonReplicatedQuery {
+                query_kind: NonReplicatedQueryKind::Pure,
+                ..
+            }
+            | ApiType::Cleanup { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::InspectMessage { .. } => {
+                Err(self.error_for("ic0_call_with_best_effort_response"))
+            }
+            ApiType::Update {
+                outgoing_request, ..
+            }
+            | ApiType::NonReplicatedQuery {
+                query_kind:
+                    NonReplicatedQueryKind::Stateful {
+                        outgoing_request, ..
+                    },
+                ..
+            }
+            | ApiType::SystemTask {
+                outgoing_request, ..
+            }
+            | ApiType::ReplyCallback {
+                outgoing_request, ..
+            }
+            | ApiType::RejectCallback {
+                outgoing_request, ..
+            } => match outgoing_request {
+                None => Err(HypervisorError::ContractViolation(
+                    "ic0.call_with_best_effort_response called when no call is under construction."
+                        .to_string(),
+                )),
+                Some(request) => request.set_timeout(timeout_seconds),
+            },
+        };
+        trace_syscall!(self, CallWithBestEffortResponse, result, timeout_seconds);
+        result
+    }
+
     fn ic0_call_cycles_add(&mut self, amount: u64) -> HypervisorResult<()> {
         let result = self.ic0_call_cycles_add_helper("ic0_call_cycles_add", Cycles::from(amount));
         trace_syscall!(self, CallCyclesAdd, result, amount);
@@ -2043,26 +2124,31 @@ impl SystemApi for SystemApiImpl {
             | ApiType::PreUpgrade { .. }
             | ApiType::InspectMessage { .. } => Err(self.error_for("ic0_call_perform")),
             ApiType::Update {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::SystemTask {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::ReplyCallback {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::RejectCallback {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::NonReplicatedQuery {
+                time,
                 query_kind:
                     NonReplicatedQueryKind::Stateful {
                         call_context_id,
@@ -2078,6 +2164,7 @@ impl SystemApi for SystemApiImpl {
 
                 let req = into_request(
                     req_in_prep,
+                    *time,
                     *call_context_id,
                     &mut self.sandbox_safe_system_state,
                     &self.log,
@@ -2286,6 +2373,29 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
+    fn ic0_msg_deadline(&self) -> HypervisorResult<u64> {
+        let result = match &self.api_type {
+            ApiType::Start { .. }
+            | ApiType::Init { .. }
+            | ApiType::SystemTask { .. }
+            | ApiType::Cleanup { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::InspectMessage { .. } => Err(self.error_for("ic0_msg_deadline")),
+            ApiType::ReplicatedQuery { .. } | ApiType::NonReplicatedQuery { .. } => Ok(0),
+            ApiType::Update { deadline, .. }
+            | ApiType::ReplyCallback { deadline, .. }
+            | ApiType::RejectCallback { deadline, .. } => {
+                if *deadline == NO_DEADLINE {
+                    Ok(0)
+                } else {
+                    Ok(Time::from(*deadline).as_nanos_since_unix_epoch())
+                }
+            }
+        };
+        trace_syscall!(self, MsgDeadline, result);
+        result
+    }
+
     fn ic0_global_timer_set(&mut self, time: Time) -> HypervisorResult<Time> {
         let result = match &self.api_type {
             ApiType::Start { .. }
@@ -2414,6 +2524,25 @@ impl SystemApi for SystemApiImpl {
                 .map(NumBytes::new)
                 .ok_or(HypervisorError::OutOfMemory)?;
 
+            // The Wasm memory limit is enforced only on `memory.grow` (not on
+            // `table.grow`) and only in executions that may persist state
+            // changes in response to regular messages and system tasks.
+            if element_size == WASM_PAGE_SIZE_IN_BYTES as u64 {
+                if let Some(limit) = self.sandbox_safe_system_state.wasm_memory_limit() {
+                    if self.enforces_wasm_memory_limit() {
+                        let new_size = (native_memory_grow_res as u64)
+                            .saturating_add(additional_elements)
+                            .saturating_mul(element_size);
+                        if new_size > limit.get() {
+                            return Err(HypervisorError::WasmMemoryLimitExceeded {
+                                bytes: NumBytes::new(new_size),
+                                limit,
+                            });
+                        }
+                    }
+                }
+            }
+
             match self.memory_usage.allocate_execution_memory(
                 bytes,
                 &self.api_type,
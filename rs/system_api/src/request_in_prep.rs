@@ -2,9 +2,9 @@ use crate::{sandbox_safe_system_state::SandboxSafeSystemState, valid_subslice};
 use ic_interfaces::execution_environment::{HypervisorError, HypervisorResult};
 use ic_logger::ReplicaLogger;
 use ic_types::{
-    messages::{CallContextId, Request},
+    messages::{CallContextId, Request, MAX_CALL_TIMEOUT_SECONDS, NO_DEADLINE},
     methods::{Callback, WasmClosure},
-    CanisterId, Cycles, NumBytes, PrincipalId,
+    CanisterId, CoarseTime, Cycles, NumBytes, PrincipalId, Time,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -35,6 +35,10 @@ pub struct RequestInPrep {
     on_reject: WasmClosure,
     on_cleanup: Option<WasmClosure>,
     cycles: Cycles,
+    /// The timeout (in seconds) of a best-effort call, if set via
+    /// `ic0.call_with_best_effort_response`. Guaranteed response calls have
+    /// no timeout.
+    timeout_seconds: Option<u32>,
     method_name: String,
     method_payload: Vec<u8>,
     /// The maximum size of a message that will go to a canister on another
@@ -106,6 +110,7 @@ impl RequestInPrep {
             on_reject,
             on_cleanup: None,
             cycles: Cycles::zero(),
+            timeout_seconds: None,
             method_name,
             method_payload: Vec::new(),
             max_size_remote_subnet,
@@ -125,6 +130,20 @@ impl RequestInPrep {
         }
     }
 
+    /// Turns the call into a best-effort call with the given timeout, capped
+    /// at `MAX_CALL_TIMEOUT_SECONDS`.
+    pub(crate) fn set_timeout(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        if self.timeout_seconds.is_some() {
+            Err(HypervisorError::ContractViolation(
+                "ic0.call_with_best_effort_response can be called at most once between `ic0.call_new` and `ic0.call_perform`"
+                    .to_string(),
+            ))
+        } else {
+            self.timeout_seconds = Some(timeout_seconds.min(MAX_CALL_TIMEOUT_SECONDS));
+            Ok(())
+        }
+    }
+
     pub(crate) fn take_cycles(self) -> Cycles {
         self.cycles
     }
@@ -174,11 +193,13 @@ pub(crate) fn into_request(
         on_reject,
         on_cleanup,
         cycles,
+        timeout_seconds,
         method_name,
         method_payload,
         max_size_remote_subnet,
         multiplier_max_size_local_subnet,
     }: RequestInPrep,
+    time: Time,
     call_context_id: CallContextId,
     sandbox_safe_system_state: &mut SandboxSafeSystemState,
     _logger: &ReplicaLogger,
@@ -203,6 +224,12 @@ pub(crate) fn into_request(
     let prepayment_for_response_transmission =
         sandbox_safe_system_state.prepayment_for_response_transmission();
 
+    // Best-effort calls expire `timeout_seconds` after the current time,
+    // rounded down to whole seconds.
+    let deadline = timeout_seconds.map_or(NO_DEADLINE, |timeout_seconds| {
+        CoarseTime::floor(time).saturating_add_secs(timeout_seconds)
+    });
+
     let callback_id = sandbox_safe_system_state.register_callback(Callback::new(
         call_context_id,
         sender,
@@ -213,6 +240,7 @@ pub(crate) fn into_request(
         on_reply,
         on_reject,
         on_cleanup,
+        deadline,
     ))?;
 
     let req = Request {
@@ -223,6 +251,7 @@ pub(crate) fn into_request(
         sender_reply_callback: callback_id,
         payment: cycles,
         metadata: Some(sandbox_safe_system_state.request_metadata.clone()),
+        deadline,
     };
     // We cannot call `Request::payload_size_bytes()` before constructing the
     // request, so ensure our separate calculation matches the actual size.
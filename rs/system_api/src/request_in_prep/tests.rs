@@ -110,3 +110,47 @@ fn payloads_larger_than_intra_limit_rejected() {
         .extend_method_payload(0, 100, &heap)
         .unwrap_err();
 }
+
+#[test]
+fn timeout_can_be_set_at_most_once() {
+    let heap = vec![0; 1024];
+    let callback = WasmClosure::new(0, 0);
+    let mut req_in_prep = RequestInPrep::new(
+        CanisterId::from(1),
+        0,
+        1,
+        0,
+        1,
+        &heap,
+        callback.clone(),
+        callback,
+        NumBytes::from(10),
+        1,
+        1000,
+    )
+    .unwrap();
+    req_in_prep.set_timeout(10).unwrap();
+    req_in_prep.set_timeout(10).unwrap_err();
+}
+
+#[test]
+fn timeout_is_capped_at_max_call_timeout() {
+    let heap = vec![0; 1024];
+    let callback = WasmClosure::new(0, 0);
+    let mut req_in_prep = RequestInPrep::new(
+        CanisterId::from(1),
+        0,
+        1,
+        0,
+        1,
+        &heap,
+        callback.clone(),
+        callback,
+        NumBytes::from(10),
+        1,
+        1000,
+    )
+    .unwrap();
+    req_in_prep.set_timeout(u32::MAX).unwrap();
+    assert_eq!(Some(MAX_CALL_TIMEOUT_SECONDS), req_in_prep.timeout_seconds);
+}
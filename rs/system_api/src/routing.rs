@@ -9,8 +9,11 @@ use ic_management_canister_types::{
     BitcoinSendTransactionArgs, CanisterIdRecord, CanisterInfoRequest, ClearChunkStoreArgs,
     ComputeInitialEcdsaDealingsArgs, ECDSAPublicKeyArgs, EcdsaKeyId, InstallChunkedCodeArgs,
     InstallCodeArgsV2, Method as Ic00Method, NodeMetricsHistoryArgs, Payload,
-    ProvisionalTopUpCanisterArgs, SignWithECDSAArgs, StoredChunksArgs, UninstallCodeArgs,
-    UpdateSettingsArgs, UploadChunkArgs,
+    ProvisionalTopUpCanisterArgs, ReadCanisterSnapshotDataArgs, ReadCanisterSnapshotMetadataArgs,
+    SchnorrKeyId, SchnorrPublicKeyArgs, SignWithECDSAArgs, SignWithSchnorrArgs, StoredChunksArgs,
+    UninstallCodeArgs, UpdateSettingsArgs, UploadCanisterSnapshotDataArgs,
+    UploadCanisterSnapshotMetadataArgs, UploadChunkArgs, VetKdDeriveEncryptedKeyArgs, VetKdKeyId,
+    VetKdPublicKeyArgs,
 };
 use ic_replicated_state::NetworkTopology;
 
@@ -21,6 +24,8 @@ pub(super) enum ResolveDestinationError {
     SubnetNotFound(CanisterId, Ic00Method),
     AlreadyResolved(PrincipalId),
     EcdsaKeyError(String),
+    SchnorrKeyError(String),
+    VetKdKeyError(String),
 }
 
 impl From<UserError> for ResolveDestinationError {
@@ -193,7 +198,7 @@ pub(super) fn resolve_destination(
                 &key_id,
                 network_topology,
                 &None,
-                EcdsaSubnetKind::OnlyHoldsKey,
+                IDkgSubnetKind::OnlyHoldsKey,
             )
         }
         Ok(Ic00Method::SignWithECDSA) => {
@@ -202,7 +207,7 @@ pub(super) fn resolve_destination(
                 &key_id,
                 network_topology,
                 &None,
-                EcdsaSubnetKind::HoldsAndSignWithKey,
+                IDkgSubnetKind::HoldsAndSignWithKey,
             )
         }
         Ok(Ic00Method::ComputeInitialEcdsaDealings) => {
@@ -211,7 +216,31 @@ pub(super) fn resolve_destination(
                 &args.key_id,
                 network_topology,
                 &Some(args.subnet_id),
-                EcdsaSubnetKind::OnlyHoldsKey,
+                IDkgSubnetKind::OnlyHoldsKey,
+            )
+        }
+        Ok(Ic00Method::SchnorrPublicKey) => {
+            let key_id = SchnorrPublicKeyArgs::decode(payload)?.key_id;
+            route_schnorr_message(&key_id, network_topology, IDkgSubnetKind::OnlyHoldsKey)
+        }
+        Ok(Ic00Method::SignWithSchnorr) => {
+            let key_id = SignWithSchnorrArgs::decode(payload)?.key_id;
+            route_schnorr_message(
+                &key_id,
+                network_topology,
+                IDkgSubnetKind::HoldsAndSignWithKey,
+            )
+        }
+        Ok(Ic00Method::VetKdPublicKey) => {
+            let key_id = VetKdPublicKeyArgs::decode(payload)?.key_id;
+            route_vetkd_message(&key_id, network_topology, IDkgSubnetKind::OnlyHoldsKey)
+        }
+        Ok(Ic00Method::VetKdDeriveEncryptedKey) => {
+            let key_id = VetKdDeriveEncryptedKeyArgs::decode(payload)?.key_id;
+            route_vetkd_message(
+                &key_id,
+                network_topology,
+                IDkgSubnetKind::HoldsAndSignWithKey,
             )
         }
         Ok(Ic00Method::UploadChunk) => {
@@ -263,13 +292,69 @@ pub(super) fn resolve_destination(
                 "Snapshotting API is not yet implemented",
             )))
         }
+        Ok(Ic00Method::ReadCanisterSnapshotMetadata) => {
+            let args = ReadCanisterSnapshotMetadataArgs::decode(payload)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or({
+                    ResolveDestinationError::SubnetNotFound(
+                        canister_id,
+                        Ic00Method::ReadCanisterSnapshotMetadata,
+                    )
+                })
+        }
+        Ok(Ic00Method::ReadCanisterSnapshotData) => {
+            let args = ReadCanisterSnapshotDataArgs::decode(payload)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or({
+                    ResolveDestinationError::SubnetNotFound(
+                        canister_id,
+                        Ic00Method::ReadCanisterSnapshotData,
+                    )
+                })
+        }
+        Ok(Ic00Method::UploadCanisterSnapshotMetadata) => {
+            let args = UploadCanisterSnapshotMetadataArgs::decode(payload)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or({
+                    ResolveDestinationError::SubnetNotFound(
+                        canister_id,
+                        Ic00Method::UploadCanisterSnapshotMetadata,
+                    )
+                })
+        }
+        Ok(Ic00Method::UploadCanisterSnapshotData) => {
+            let args = UploadCanisterSnapshotDataArgs::decode(payload)?;
+            let canister_id = args.get_canister_id();
+            network_topology
+                .routing_table
+                .route(canister_id.get())
+                .map(|subnet_id| subnet_id.get())
+                .ok_or({
+                    ResolveDestinationError::SubnetNotFound(
+                        canister_id,
+                        Ic00Method::UploadCanisterSnapshotData,
+                    )
+                })
+        }
         Err(_) => Err(ResolveDestinationError::MethodNotFound(
             method_name.to_string(),
         )),
     }
 }
 
-enum EcdsaSubnetKind {
+enum IDkgSubnetKind {
     OnlyHoldsKey,
     HoldsAndSignWithKey,
 }
@@ -281,7 +366,7 @@ fn route_ecdsa_message(
     key_id: &EcdsaKeyId,
     network_topology: &NetworkTopology,
     requested_subnet: &Option<SubnetId>,
-    signing_must_be_enabled: EcdsaSubnetKind,
+    signing_must_be_enabled: IDkgSubnetKind,
 ) -> Result<PrincipalId, ResolveDestinationError> {
     fn format_keys<'a>(mut found_keys: impl Iterator<Item = &'a EcdsaKeyId>) -> String {
         let mut keys = "[".to_string();
@@ -304,7 +389,7 @@ fn route_ecdsa_message(
             Some(subnet_topology) => {
                 if subnet_topology.ecdsa_keys_held.contains(key_id) {
                     match signing_must_be_enabled {
-                        EcdsaSubnetKind::HoldsAndSignWithKey => {
+                        IDkgSubnetKind::HoldsAndSignWithKey => {
                             if network_topology
                                 .ecdsa_signing_subnets(key_id)
                                 .contains(subnet_id)
@@ -317,7 +402,7 @@ fn route_ecdsa_message(
                                 )))
                             }
                         }
-                        EcdsaSubnetKind::OnlyHoldsKey => Ok((*subnet_id).get()),
+                        IDkgSubnetKind::OnlyHoldsKey => Ok((*subnet_id).get()),
                     }
                 } else {
                     Err(ResolveDestinationError::EcdsaKeyError(format!(
@@ -337,14 +422,14 @@ fn route_ecdsa_message(
             // Otherwise either return an error, or look through all subnets to
             // find one with the key if signing isn't required.
             match signing_must_be_enabled {
-                EcdsaSubnetKind::HoldsAndSignWithKey => {
+                IDkgSubnetKind::HoldsAndSignWithKey => {
                     let keys = format_keys(network_topology.ecdsa_signing_subnets.keys());
                     Err(ResolveDestinationError::EcdsaKeyError(format!(
                         "Requested ECDSA key: {}, existing keys with signing enabled: {}",
                         key_id, keys
                     )))
                 }
-                EcdsaSubnetKind::OnlyHoldsKey => {
+                IDkgSubnetKind::OnlyHoldsKey => {
                     let mut keys = BTreeSet::new();
                     for (subnet_id, topology) in &network_topology.subnets {
                         if topology.ecdsa_keys_held.contains(key_id) {
@@ -363,6 +448,87 @@ fn route_ecdsa_message(
     }
 }
 
+/// Routes to the first subnet enabled to sign with the given Schnorr key, or,
+/// if signing doesn't need to be enabled, to the first subnet holding the key.
+fn route_schnorr_message(
+    key_id: &SchnorrKeyId,
+    network_topology: &NetworkTopology,
+    signing_must_be_enabled: IDkgSubnetKind,
+) -> Result<PrincipalId, ResolveDestinationError> {
+    fn format_keys<'a>(found_keys: impl Iterator<Item = &'a SchnorrKeyId>) -> String {
+        let keys: Vec<_> = found_keys.map(|key| key.to_string()).collect();
+        format!("[{}]", keys.join(", "))
+    }
+
+    if let Some(subnet_id) = network_topology.schnorr_signing_subnets(key_id).first() {
+        return Ok((*subnet_id).get());
+    }
+    match signing_must_be_enabled {
+        IDkgSubnetKind::HoldsAndSignWithKey => {
+            let keys = format_keys(network_topology.schnorr_signing_subnets.keys());
+            Err(ResolveDestinationError::SchnorrKeyError(format!(
+                "Requested Schnorr key: {}, existing keys with signing enabled: {}",
+                key_id, keys
+            )))
+        }
+        IDkgSubnetKind::OnlyHoldsKey => {
+            let mut keys = BTreeSet::new();
+            for (subnet_id, topology) in &network_topology.subnets {
+                if topology.schnorr_keys_held.contains(key_id) {
+                    return Ok((*subnet_id).get());
+                }
+                keys.extend(topology.schnorr_keys_held.iter().cloned());
+            }
+            let keys = format_keys(keys.iter());
+            Err(ResolveDestinationError::SchnorrKeyError(format!(
+                "Requested Schnorr key: {}, existing keys: {}",
+                key_id, keys
+            )))
+        }
+    }
+}
+
+/// Routes to the first subnet enabled to derive keys with the given vetKD key,
+/// or, if key derivation doesn't need to be enabled, to the first subnet
+/// holding the key.
+fn route_vetkd_message(
+    key_id: &VetKdKeyId,
+    network_topology: &NetworkTopology,
+    derivation_must_be_enabled: IDkgSubnetKind,
+) -> Result<PrincipalId, ResolveDestinationError> {
+    fn format_keys<'a>(found_keys: impl Iterator<Item = &'a VetKdKeyId>) -> String {
+        let keys: Vec<_> = found_keys.map(|key| key.to_string()).collect();
+        format!("[{}]", keys.join(", "))
+    }
+
+    if let Some(subnet_id) = network_topology.vetkd_enabled_subnets(key_id).first() {
+        return Ok((*subnet_id).get());
+    }
+    match derivation_must_be_enabled {
+        IDkgSubnetKind::HoldsAndSignWithKey => {
+            let keys = format_keys(network_topology.vetkd_enabled_subnets.keys());
+            Err(ResolveDestinationError::VetKdKeyError(format!(
+                "Requested vetKD key: {}, existing keys with key derivation enabled: {}",
+                key_id, keys
+            )))
+        }
+        IDkgSubnetKind::OnlyHoldsKey => {
+            let mut keys = BTreeSet::new();
+            for (subnet_id, topology) in &network_topology.subnets {
+                if topology.vetkd_keys_held.contains(key_id) {
+                    return Ok((*subnet_id).get());
+                }
+                keys.extend(topology.vetkd_keys_held.iter().cloned());
+            }
+            let keys = format_keys(keys.iter());
+            Err(ResolveDestinationError::VetKdKeyError(format!(
+                "Requested vetKD key: {}, existing keys: {}",
+                key_id, keys
+            )))
+        }
+    }
+}
+
 fn route_bitcoin_message(
     network: BitcoinNetwork,
     network_topology: &NetworkTopology,
@@ -392,7 +558,8 @@ mod tests {
     use candid::Encode;
     use ic_base_types::RegistryVersion;
     use ic_management_canister_types::{
-        ComputeInitialEcdsaDealingsArgs, DerivationPath, EcdsaCurve, EcdsaKeyId, SignWithECDSAArgs,
+        ComputeInitialEcdsaDealingsArgs, DerivationPath, EcdsaCurve, EcdsaKeyId, SchnorrAlgorithm,
+        SignWithECDSAArgs, VetKdCurve,
     };
     use ic_replicated_state::SubnetTopology;
     use ic_test_utilities::types::ids::{canister_test_id, node_test_id, subnet_test_id};
@@ -630,7 +797,7 @@ mod tests {
                 &key_id1(),
                 &network_with_ecdsa_subnets(),
                 &Some(subnet_test_id(0)),
-                EcdsaSubnetKind::HoldsAndSignWithKey
+                IDkgSubnetKind::HoldsAndSignWithKey
             )
             .unwrap(),
             subnet_test_id(0).get()
@@ -646,7 +813,7 @@ mod tests {
             &key_id,
             &network_with_ecdsa_subnets(),
             &Some(subnet_id),
-            EcdsaSubnetKind::HoldsAndSignWithKey,
+            IDkgSubnetKind::HoldsAndSignWithKey,
         ) {
             Err(ResolveDestinationError::EcdsaKeyError(msg)) => assert_eq!(
                 msg,
@@ -658,4 +825,197 @@ mod tests {
             _ => panic!("Unexpected result."),
         };
     }
+
+    fn schnorr_key_id(algorithm: SchnorrAlgorithm) -> SchnorrKeyId {
+        SchnorrKeyId {
+            algorithm,
+            name: "some_key".to_string(),
+        }
+    }
+
+    /// Subnet 0 holds and signs with the Ed25519 key, subnet 1 holds the
+    /// BIP340 key without being enabled to sign with it.
+    fn network_with_schnorr_subnets() -> NetworkTopology {
+        let ed25519_key = schnorr_key_id(SchnorrAlgorithm::Ed25519);
+        let bip340_key = schnorr_key_id(SchnorrAlgorithm::Bip340Secp256k1);
+        NetworkTopology {
+            schnorr_signing_subnets: btreemap! {
+                ed25519_key.clone() => vec![subnet_test_id(0)],
+            },
+            subnets: btreemap! {
+                subnet_test_id(0) => SubnetTopology {
+                    schnorr_keys_held: vec![ed25519_key].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+                subnet_test_id(1) => SubnetTopology {
+                    schnorr_keys_held: vec![bip340_key].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+            },
+            ..NetworkTopology::default()
+        }
+    }
+
+    fn schnorr_sign_req(key_id: SchnorrKeyId) -> Vec<u8> {
+        let args = SignWithSchnorrArgs {
+            message: vec![1; 64],
+            derivation_path: DerivationPath::new(vec![ByteBuf::from(vec![0; 10])]),
+            key_id,
+        };
+        Encode!(&args).unwrap()
+    }
+
+    fn schnorr_public_key_req(key_id: SchnorrKeyId) -> Vec<u8> {
+        let args = SchnorrPublicKeyArgs {
+            canister_id: Some(canister_test_id(1)),
+            derivation_path: DerivationPath::new(vec![ByteBuf::from(vec![0; 10])]),
+            key_id,
+        };
+        Encode!(&args).unwrap()
+    }
+
+    #[test]
+    fn resolve_schnorr_sign() {
+        assert_eq!(
+            resolve_destination(
+                &network_with_schnorr_subnets(),
+                &Ic00Method::SignWithSchnorr.to_string(),
+                &schnorr_sign_req(schnorr_key_id(SchnorrAlgorithm::Ed25519)),
+                subnet_test_id(2),
+            )
+            .unwrap(),
+            PrincipalId::new_subnet_test_id(0)
+        )
+    }
+
+    #[test]
+    fn resolve_schnorr_sign_error() {
+        let key_id = schnorr_key_id(SchnorrAlgorithm::Bip340Secp256k1);
+        assert_matches!(resolve_destination(
+            &network_with_schnorr_subnets(),
+            &Ic00Method::SignWithSchnorr.to_string(),
+            &schnorr_sign_req(key_id.clone()),
+            subnet_test_id(2),
+        )
+        .unwrap_err(),
+        ResolveDestinationError::SchnorrKeyError(err) => assert_eq!(
+                err,
+                format!(
+                    "Requested Schnorr key: {}, existing keys with signing enabled: [{}]",
+                    key_id,
+                    schnorr_key_id(SchnorrAlgorithm::Ed25519)
+                )
+            )
+        )
+    }
+
+    #[test]
+    fn resolve_schnorr_public_key_works_without_signing_enabled() {
+        assert_eq!(
+            resolve_destination(
+                &network_with_schnorr_subnets(),
+                &Ic00Method::SchnorrPublicKey.to_string(),
+                &schnorr_public_key_req(schnorr_key_id(SchnorrAlgorithm::Bip340Secp256k1)),
+                subnet_test_id(2),
+            )
+            .unwrap(),
+            PrincipalId::new_subnet_test_id(1)
+        )
+    }
+
+    fn vetkd_key_id(name: &str) -> VetKdKeyId {
+        VetKdKeyId {
+            curve: VetKdCurve::Bls12_381_G2,
+            name: name.to_string(),
+        }
+    }
+
+    /// Subnet 0 holds and derives keys with `key_1`, subnet 1 holds `key_2`
+    /// without being enabled to derive keys with it.
+    fn network_with_vetkd_subnets() -> NetworkTopology {
+        NetworkTopology {
+            vetkd_enabled_subnets: btreemap! {
+                vetkd_key_id("key_1") => vec![subnet_test_id(0)],
+            },
+            subnets: btreemap! {
+                subnet_test_id(0) => SubnetTopology {
+                    vetkd_keys_held: vec![vetkd_key_id("key_1")].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+                subnet_test_id(1) => SubnetTopology {
+                    vetkd_keys_held: vec![vetkd_key_id("key_2")].into_iter().collect(),
+                    ..SubnetTopology::default()
+                },
+            },
+            ..NetworkTopology::default()
+        }
+    }
+
+    fn vetkd_derive_encrypted_key_req(key_id: VetKdKeyId) -> Vec<u8> {
+        let args = VetKdDeriveEncryptedKeyArgs {
+            derivation_id: vec![1; 32],
+            derivation_path: DerivationPath::new(vec![ByteBuf::from(vec![0; 10])]),
+            key_id,
+            encryption_public_key: vec![2; 48],
+        };
+        Encode!(&args).unwrap()
+    }
+
+    fn vetkd_public_key_req(key_id: VetKdKeyId) -> Vec<u8> {
+        let args = VetKdPublicKeyArgs {
+            canister_id: Some(canister_test_id(1)),
+            derivation_path: DerivationPath::new(vec![ByteBuf::from(vec![0; 10])]),
+            key_id,
+        };
+        Encode!(&args).unwrap()
+    }
+
+    #[test]
+    fn resolve_vetkd_derive_encrypted_key() {
+        assert_eq!(
+            resolve_destination(
+                &network_with_vetkd_subnets(),
+                &Ic00Method::VetKdDeriveEncryptedKey.to_string(),
+                &vetkd_derive_encrypted_key_req(vetkd_key_id("key_1")),
+                subnet_test_id(2),
+            )
+            .unwrap(),
+            PrincipalId::new_subnet_test_id(0)
+        )
+    }
+
+    #[test]
+    fn resolve_vetkd_derive_encrypted_key_error() {
+        let key_id = vetkd_key_id("key_2");
+        assert_matches!(resolve_destination(
+            &network_with_vetkd_subnets(),
+            &Ic00Method::VetKdDeriveEncryptedKey.to_string(),
+            &vetkd_derive_encrypted_key_req(key_id.clone()),
+            subnet_test_id(2),
+        )
+        .unwrap_err(),
+        ResolveDestinationError::VetKdKeyError(err) => assert_eq!(
+                err,
+                format!(
+                    "Requested vetKD key: {}, existing keys with key derivation enabled: [{}]",
+                    key_id,
+                    vetkd_key_id("key_1")
+                )
+            )
+        )
+    }
+
+    #[test]
+    fn resolve_vetkd_public_key_works_without_key_derivation_enabled() {
+        assert_eq!(
+            resolve_destination(
+                &network_with_vetkd_subnets(),
+                &Ic00Method::VetKdPublicKey.to_string(),
+                &vetkd_public_key_req(vetkd_key_id("key_2")),
+                subnet_test_id(2),
+            )
+            .unwrap(),
+            PrincipalId::new_subnet_test_id(1)
+        )
+    }
 }
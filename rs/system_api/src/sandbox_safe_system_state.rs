@@ -235,6 +235,10 @@ impl SystemStateChanges {
             | Ok(Ic00Method::SetupInitialDKG)
             | Ok(Ic00Method::ECDSAPublicKey)
             | Ok(Ic00Method::ComputeInitialEcdsaDealings)
+            | Ok(Ic00Method::SchnorrPublicKey)
+            | Ok(Ic00Method::SignWithSchnorr)
+            | Ok(Ic00Method::VetKdPublicKey)
+            | Ok(Ic00Method::VetKdDeriveEncryptedKey)
             | Ok(Ic00Method::ProvisionalTopUpCanister)
             | Ok(Ic00Method::BitcoinSendTransactionInternal)
             | Ok(Ic00Method::BitcoinGetSuccessors)
@@ -251,7 +255,11 @@ impl SystemStateChanges {
             | Ok(Ic00Method::TakeCanisterSnapshot)
             | Ok(Ic00Method::ListCanisterSnapshots)
             | Ok(Ic00Method::DeleteCanisterSnapshot)
-            | Ok(Ic00Method::LoadCanisterSnapshot) => Ok(None),
+            | Ok(Ic00Method::LoadCanisterSnapshot)
+            | Ok(Ic00Method::ReadCanisterSnapshotMetadata)
+            | Ok(Ic00Method::ReadCanisterSnapshotData)
+            | Ok(Ic00Method::UploadCanisterSnapshotMetadata)
+            | Ok(Ic00Method::UploadCanisterSnapshotData) => Ok(None),
             Err(_) => Err(UserError::new(
                 ErrorCode::CanisterMethodNotFound,
                 format!("Management canister has no method '{}'", msg.method_name),
@@ -310,7 +318,7 @@ impl SystemStateChanges {
                 })?;
                 if (*amount_taken).get() > LOG_CANISTER_OPERATION_CYCLES_THRESHOLD {
                     match call_context.call_origin() {
-                        CallOrigin::CanisterUpdate(origin_canister_id, _)
+                        CallOrigin::CanisterUpdate(origin_canister_id, _, _)
                         | CallOrigin::CanisterQuery(origin_canister_id, _) => info!(
                             logger,
                             "Canister {} accepted {} cycles from canister {}.",
@@ -545,6 +553,7 @@ pub struct SandboxSafeSystemState {
     initial_cycles_balance: Cycles,
     initial_reserved_balance: Cycles,
     reserved_balance_limit: Option<Cycles>,
+    wasm_memory_limit: Option<NumBytes>,
     call_context_balances: BTreeMap<CallContextId, Cycles>,
     cycles_account_manager: CyclesAccountManager,
     // None indicates that we are in a context where the canister cannot
@@ -573,6 +582,7 @@ impl SandboxSafeSystemState {
         initial_cycles_balance: Cycles,
         initial_reserved_balance: Cycles,
         reserved_balance_limit: Option<Cycles>,
+        wasm_memory_limit: Option<NumBytes>,
         call_context_balances: BTreeMap<CallContextId, Cycles>,
         cycles_account_manager: CyclesAccountManager,
         next_callback_id: Option<u64>,
@@ -599,6 +609,7 @@ impl SandboxSafeSystemState {
             initial_cycles_balance,
             initial_reserved_balance,
             reserved_balance_limit,
+            wasm_memory_limit,
             call_context_balances,
             cycles_account_manager,
             next_callback_id,
@@ -668,6 +679,7 @@ impl SandboxSafeSystemState {
             system_state.balance(),
             system_state.reserved_balance(),
             system_state.reserved_balance_limit(),
+            system_state.wasm_memory_limit,
             call_context_balances,
             cycles_account_manager,
             system_state
@@ -697,6 +709,11 @@ impl SandboxSafeSystemState {
         self.canister_version
     }
 
+    /// Returns the user-specified upper limit on the Wasm memory size, if any.
+    pub fn wasm_memory_limit(&self) -> Option<NumBytes> {
+        self.wasm_memory_limit
+    }
+
     pub fn set_global_timer(&mut self, timer: CanisterTimer) {
         // Update both sandbox global timer and the changes.
         self.system_state_changes.new_global_timer = Some(timer);
@@ -22,7 +22,7 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallContextId, CallbackId, RejectContext, RequestMetadata},
+    messages::{CallContextId, CallbackId, RejectContext, RequestMetadata, NO_DEADLINE},
     methods::SystemMethod,
     ComputeAllocation, Cycles, MemoryAllocation, NumInstructions, PrincipalId, Time,
 };
@@ -84,6 +84,7 @@ impl ApiTypeBuilder {
             Cycles::zero(),
             user_test_id(1).get(),
             CallContextId::from(1),
+            NO_DEADLINE,
         )
     }
 
@@ -103,6 +104,7 @@ impl ApiTypeBuilder {
             vec![],
             incoming_cycles,
             CallContextId::new(1),
+            NO_DEADLINE,
             false,
             ExecutionMode::Replicated,
             0.into(),
@@ -116,6 +118,7 @@ impl ApiTypeBuilder {
             reject_context,
             Cycles::zero(),
             call_context_test_id(1),
+            NO_DEADLINE,
             false,
             ExecutionMode::Replicated,
             0.into(),
@@ -163,7 +166,7 @@ pub fn get_system_state() -> SystemState {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
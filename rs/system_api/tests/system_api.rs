@@ -29,6 +29,7 @@ use ic_test_utilities_time::mock_time;
 use ic_types::{
     messages::{
         CallContextId, CallbackId, RejectContext, RequestMetadata, MAX_RESPONSE_COUNT_BYTES,
+        NO_DEADLINE,
     },
     methods::{Callback, WasmClosure},
     time, CanisterTimer, CountBytes, Cycles, NumInstructions, PrincipalId, Time,
@@ -158,10 +159,12 @@ fn test_canister_init_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -216,10 +219,12 @@ fn test_canister_update_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -274,10 +279,12 @@ fn test_canister_replicated_query_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -332,10 +339,12 @@ fn test_canister_pure_query_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -400,10 +409,12 @@ fn test_canister_stateful_query_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -458,10 +469,12 @@ fn test_reply_api_support_on_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -516,10 +529,12 @@ fn test_reply_api_support_non_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -575,10 +590,12 @@ fn test_reject_api_support_on_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -634,10 +651,12 @@ fn test_reject_api_support_non_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -692,10 +711,12 @@ fn test_pre_upgrade_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -750,10 +771,12 @@ fn test_start_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_not_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_not_supported(api.ic0_canister_version());
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -812,10 +835,12 @@ fn test_cleanup_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -875,10 +900,12 @@ fn test_inspect_message_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_not_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -934,10 +961,12 @@ fn test_canister_system_task_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -992,10 +1021,12 @@ fn test_canister_system_task_support_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(10));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
     assert_api_supported(api.ic0_time());
+    assert_api_not_supported(api.ic0_msg_deadline());
     assert_api_supported(api.ic0_canister_version());
     assert_api_supported(api.ic0_global_timer_set(time::UNIX_EPOCH));
     assert_api_supported(
@@ -1166,7 +1197,7 @@ fn test_canister_balance() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1195,7 +1226,7 @@ fn test_canister_cycle_balance() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1230,7 +1261,7 @@ fn test_msg_cycles_available_traps() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             available_cycles,
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1392,7 +1423,7 @@ fn msg_cycles_accept_all_cycles_in_call_context() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::from(amount),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1416,7 +1447,7 @@ fn msg_cycles_accept_all_cycles_in_call_context_when_more_asked() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(40),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1449,7 +1480,7 @@ fn call_perform_not_enough_cycles_does_not_trap() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(40),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1572,6 +1603,7 @@ fn push_output_request_respects_memory_limits() {
             WasmClosure::new(0, 0),
             WasmClosure::new(0, 0),
             None,
+            NO_DEADLINE,
         ))
         .unwrap();
     let mut api = SystemApiImpl::new(
@@ -1678,6 +1710,7 @@ fn push_output_request_oversized_request_memory_limits() {
             WasmClosure::new(0, 0),
             WasmClosure::new(0, 0),
             None,
+            NO_DEADLINE,
         ))
         .unwrap();
     let mut api = SystemApiImpl::new(
@@ -28,8 +28,9 @@ use ic_logger::{replica_logger::no_op_logger, ReplicaLogger};
 use ic_management_canister_types::{
     CanisterIdRecord, CanisterInstallMode, CanisterInstallModeV2, CanisterSettingsArgs,
     CanisterSettingsArgsBuilder, CanisterStatusType, EcdsaKeyId, EmptyBlob, InstallCodeArgs,
-    InstallCodeArgsV2, LogVisibility, Method, Payload, ProvisionalCreateCanisterWithCyclesArgs,
-    SkipPreUpgrade, UpdateSettingsArgs,
+    InstallCodeArgsV2, LogVisibility, MasterPublicKeyId, Method, Payload,
+    ProvisionalCreateCanisterWithCyclesArgs, SchnorrAlgorithm, SchnorrKeyId, SkipPreUpgrade,
+    UpdateSettingsArgs, VetKdKeyId,
 };
 use ic_metrics::MetricsRegistry;
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
@@ -49,7 +50,7 @@ use ic_replicated_state::{page_map::TestPageAllocatorFileDescriptorImpl, PageMap
 use ic_system_api::InstructionLimits;
 use ic_types::{
     batch::QueryStats,
-    crypto::{canister_threshold_sig::MasterEcdsaPublicKey, AlgorithmId},
+    crypto::{canister_threshold_sig::MasterPublicKey, AlgorithmId},
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{
         AnonymousQuery, CallbackId, CanisterCall, CanisterMessage, CanisterTask, MessageId,
@@ -74,6 +75,9 @@ use tempfile::NamedTempFile;
 
 const INITIAL_CANISTER_CYCLES: Cycles = Cycles::new(1_000_000_000_000);
 
+/// The compressed encoding of the generator of the G2 group of BLS12-381.
+const BLS12_381_G2_GENERATOR: &str = "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8";
+
 /// A helper to create subnets.
 pub fn generate_subnets(
     subnet_ids: Vec<SubnetId>,
@@ -100,6 +104,8 @@ pub fn generate_subnets(
                 subnet_type,
                 subnet_features: SubnetFeatures::default(),
                 ecdsa_keys_held: BTreeSet::new(),
+                schnorr_keys_held: Default::default(),
+                vetkd_keys_held: Default::default(),
             },
         );
     }
@@ -134,6 +140,8 @@ pub fn test_registry_settings() -> RegistryExecutionSettings {
         max_number_of_canisters: 0x2000,
         provisional_whitelist: ProvisionalWhitelist::Set(BTreeSet::new()),
         max_ecdsa_queue_size: 20,
+        max_schnorr_queue_size: 20,
+        max_vetkd_queue_size: 20,
         quadruples_to_create_in_advance: 5,
         subnet_size: SMALL_APP_SUBNET_MAX_SIZE,
     }
@@ -195,7 +203,7 @@ pub struct ExecutionTest {
     registry_settings: RegistryExecutionSettings,
     manual_execution: bool,
     caller_canister_id: Option<CanisterId>,
-    ecdsa_subnet_public_keys: BTreeMap<EcdsaKeyId, MasterEcdsaPublicKey>,
+    chain_key_subnet_public_keys: BTreeMap<MasterPublicKeyId, MasterPublicKey>,
 
     // The actual implementation.
     exec_env: ExecutionEnvironment,
@@ -572,6 +580,22 @@ impl ExecutionTest {
         self.subnet_message(Method::UpdateSettings, payload)
     }
 
+    pub fn canister_update_wasm_memory_limit(
+        &mut self,
+        canister_id: CanisterId,
+        wasm_memory_limit: NumBytes,
+    ) -> Result<WasmResult, UserError> {
+        let payload = UpdateSettingsArgs {
+            canister_id: canister_id.into(),
+            settings: CanisterSettingsArgsBuilder::new()
+                .with_wasm_memory_limit(wasm_memory_limit.get())
+                .build(),
+            sender_canister_version: None,
+        }
+        .encode();
+        self.subnet_message(Method::UpdateSettings, payload)
+    }
+
     /// Sends an `install_code` message to the IC management canister.
     /// Consider using higher-level helpers like `canister_from_wat()`.
     pub fn install_code(&mut self, args: InstallCodeArgs) -> Result<WasmResult, UserError> {
@@ -1172,7 +1196,7 @@ impl ExecutionTest {
             state,
             self.install_code_instruction_limits.clone(),
             &mut mock_random_number_generator(),
-            &self.ecdsa_subnet_public_keys,
+            &self.chain_key_subnet_public_keys,
             &self.registry_settings,
             &mut round_limits,
         );
@@ -1579,7 +1603,11 @@ pub struct ExecutionTestBuilder {
     log: ReplicaLogger,
     caller_canister_id: Option<CanisterId>,
     ecdsa_signature_fee: Option<Cycles>,
+    schnorr_signature_fee: Option<Cycles>,
+    vetkd_fee: Option<Cycles>,
     ecdsa_key: Option<EcdsaKeyId>,
+    schnorr_key: Option<SchnorrKeyId>,
+    vetkd_key: Option<VetKdKeyId>,
     instruction_limit: NumInstructions,
     slice_instruction_limit: NumInstructions,
     install_code_instruction_limit: NumInstructions,
@@ -1617,7 +1645,11 @@ impl Default for ExecutionTestBuilder {
             log: no_op_logger(),
             caller_canister_id: None,
             ecdsa_signature_fee: None,
+            schnorr_signature_fee: None,
+            vetkd_fee: None,
             ecdsa_key: None,
+            schnorr_key: None,
+            vetkd_key: None,
             instruction_limit: scheduler_config.max_instructions_per_message,
             slice_instruction_limit: scheduler_config.max_instructions_per_slice,
             install_code_instruction_limit: scheduler_config.max_instructions_per_install_code,
@@ -1693,6 +1725,20 @@ impl ExecutionTestBuilder {
         }
     }
 
+    pub fn with_schnorr_signature_fee(self, schnorr_signing_fee: u128) -> Self {
+        Self {
+            schnorr_signature_fee: Some(Cycles::new(schnorr_signing_fee)),
+            ..self
+        }
+    }
+
+    pub fn with_vetkd_fee(self, vetkd_fee: u128) -> Self {
+        Self {
+            vetkd_fee: Some(Cycles::new(vetkd_fee)),
+            ..self
+        }
+    }
+
     pub fn with_ecdsa_key(self, ecdsa_key: EcdsaKeyId) -> Self {
         Self {
             ecdsa_key: Some(ecdsa_key),
@@ -1700,6 +1746,20 @@ impl ExecutionTestBuilder {
         }
     }
 
+    pub fn with_schnorr_key(self, schnorr_key: SchnorrKeyId) -> Self {
+        Self {
+            schnorr_key: Some(schnorr_key),
+            ..self
+        }
+    }
+
+    pub fn with_vetkd_key(self, vetkd_key: VetKdKeyId) -> Self {
+        Self {
+            vetkd_key: Some(vetkd_key),
+            ..self
+        }
+    }
+
     pub fn with_instruction_limit(self, limit: u64) -> Self {
         Self {
             instruction_limit: NumInstructions::from(limit),
@@ -1939,6 +1999,16 @@ impl ExecutionTestBuilder {
         self
     }
 
+    pub fn with_threshold_schnorr(mut self, status: FlagStatus) -> Self {
+        self.execution_config.threshold_schnorr = status;
+        self
+    }
+
+    pub fn with_vetkd(mut self, status: FlagStatus) -> Self {
+        self.execution_config.vetkd = status;
+        self
+    }
+
     pub fn with_time(mut self, time: Time) -> Self {
         self.time = time;
         self
@@ -2010,6 +2080,12 @@ impl ExecutionTestBuilder {
         if let Some(ecdsa_signature_fee) = self.ecdsa_signature_fee {
             config.ecdsa_signature_fee = ecdsa_signature_fee;
         }
+        if let Some(schnorr_signature_fee) = self.schnorr_signature_fee {
+            config.schnorr_signature_fee = schnorr_signature_fee;
+        }
+        if let Some(vetkd_fee) = self.vetkd_fee {
+            config.vetkd_fee = vetkd_fee;
+        }
         if let Some(ecdsa_key) = &self.ecdsa_key {
             state
                 .metadata
@@ -2025,6 +2101,36 @@ impl ExecutionTestBuilder {
                 .ecdsa_keys_held
                 .insert(ecdsa_key.clone());
         }
+        if let Some(schnorr_key) = &self.schnorr_key {
+            state
+                .metadata
+                .network_topology
+                .schnorr_signing_subnets
+                .insert(schnorr_key.clone(), vec![self.own_subnet_id]);
+            state
+                .metadata
+                .network_topology
+                .subnets
+                .get_mut(&self.own_subnet_id)
+                .unwrap()
+                .schnorr_keys_held
+                .insert(schnorr_key.clone());
+        }
+        if let Some(vetkd_key) = &self.vetkd_key {
+            state
+                .metadata
+                .network_topology
+                .vetkd_enabled_subnets
+                .insert(vetkd_key.clone(), vec![self.own_subnet_id]);
+            state
+                .metadata
+                .network_topology
+                .subnets
+                .get_mut(&self.own_subnet_id)
+                .unwrap()
+                .vetkd_keys_held
+                .insert(vetkd_key.clone());
+        }
 
         state.metadata.network_topology.bitcoin_mainnet_canister_id =
             self.execution_config.bitcoin.mainnet_canister_id;
@@ -2032,19 +2138,41 @@ impl ExecutionTestBuilder {
         state.metadata.network_topology.bitcoin_testnet_canister_id =
             self.execution_config.bitcoin.testnet_canister_id;
 
-        let ecdsa_subnet_public_keys = self
-            .ecdsa_key
-            .into_iter()
-            .map(|key| {
-                (
-                    key,
-                    MasterEcdsaPublicKey {
-                        algorithm_id: AlgorithmId::Secp256k1,
-                        public_key: b"abababab".to_vec(),
-                    },
-                )
-            })
-            .collect();
+        let ecdsa_keys = self.ecdsa_key.into_iter().map(|key| {
+            (
+                MasterPublicKeyId::Ecdsa(key),
+                MasterPublicKey {
+                    algorithm_id: AlgorithmId::Secp256k1,
+                    public_key: b"abababab".to_vec(),
+                },
+            )
+        });
+        let schnorr_keys = self.schnorr_key.into_iter().map(|key| {
+            let algorithm_id = match key.algorithm {
+                SchnorrAlgorithm::Bip340Secp256k1 => AlgorithmId::ThresholdSchnorrBip340,
+                SchnorrAlgorithm::Ed25519 => AlgorithmId::ThresholdEd25519,
+            };
+            (
+                MasterPublicKeyId::Schnorr(key),
+                MasterPublicKey {
+                    algorithm_id,
+                    public_key: b"abababab".to_vec(),
+                },
+            )
+        });
+        let vetkd_keys = self.vetkd_key.into_iter().map(|key| {
+            (
+                MasterPublicKeyId::VetKd(key),
+                MasterPublicKey {
+                    algorithm_id: AlgorithmId::ThresBls12_381,
+                    // The generator of the G2 group of BLS12-381, so that
+                    // public key derivation works on a valid point.
+                    public_key: hex::decode(BLS12_381_G2_GENERATOR).unwrap(),
+                },
+            )
+        });
+        let chain_key_subnet_public_keys =
+            ecdsa_keys.chain(schnorr_keys).chain(vetkd_keys).collect();
         let cycles_account_manager = Arc::new(CyclesAccountManager::new(
             self.instruction_limit,
             self.subnet_type,
@@ -2150,7 +2278,7 @@ impl ExecutionTestBuilder {
             metrics_registry,
             ingress_history_writer,
             manual_execution: self.manual_execution,
-            ecdsa_subnet_public_keys,
+            chain_key_subnet_public_keys,
             log: self.log,
             checkpoint_files: vec![],
         }
@@ -29,7 +29,10 @@ use ic_replicated_state::{
 use ic_test_utilities_time::mock_time;
 use ic_types::methods::{Callback, WasmClosure};
 use ic_types::time::UNIX_EPOCH;
-use ic_types::{batch::RawQueryStats, messages::CallbackId};
+use ic_types::{
+    batch::RawQueryStats,
+    messages::{CallbackId, NO_DEADLINE},
+};
 use ic_types::{
     messages::{Ingress, Request, RequestMetadata, RequestOrResponse},
     nominal_cycles::NominalCycles,
@@ -745,7 +748,7 @@ pub fn register_callback(
         .call_context_manager_mut()
         .unwrap();
     let call_context_id = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(originator, callback_id),
+        CallOrigin::CanisterUpdate(originator, callback_id, NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -761,6 +764,7 @@ pub fn register_callback(
         WasmClosure::new(0, 2),
         WasmClosure::new(0, 2),
         None,
+        NO_DEADLINE,
     ));
 }
 
@@ -896,6 +900,8 @@ pub(crate) fn arb_cycles_use_case() -> impl Strategy<Value = CyclesUseCase> {
         Just(CyclesUseCase::Uninstall),
         Just(CyclesUseCase::CanisterCreation),
         Just(CyclesUseCase::ECDSAOutcalls),
+        Just(CyclesUseCase::SchnorrOutcalls),
+        Just(CyclesUseCase::VetKd),
         Just(CyclesUseCase::HTTPOutcalls),
         Just(CyclesUseCase::DeletedCanisters),
         Just(CyclesUseCase::NonConsumed),
@@ -909,6 +915,8 @@ prop_compose! {
         consumed_cycles_by_deleted_canisters in arb_nominal_cycles(),
         consumed_cycles_http_outcalls in arb_nominal_cycles(),
         consumed_cycles_ecdsa_outcalls in arb_nominal_cycles(),
+        consumed_cycles_schnorr_outcalls in arb_nominal_cycles(),
+        consumed_cycles_vetkd in arb_nominal_cycles(),
         num_canisters in any::<u64>(),
         canister_state_bytes in arb_num_bytes(),
         update_transactions_total in any::<u64>(),
@@ -919,6 +927,8 @@ prop_compose! {
         metrics.consumed_cycles_by_deleted_canisters = consumed_cycles_by_deleted_canisters;
         metrics.consumed_cycles_http_outcalls = consumed_cycles_http_outcalls;
         metrics.consumed_cycles_ecdsa_outcalls = consumed_cycles_ecdsa_outcalls;
+        metrics.consumed_cycles_schnorr_outcalls = consumed_cycles_schnorr_outcalls;
+        metrics.consumed_cycles_vetkd = consumed_cycles_vetkd;
         metrics.num_canisters = num_canisters;
         metrics.canister_state_bytes = canister_state_bytes;
         metrics.update_transactions_total = update_transactions_total;
@@ -19,7 +19,7 @@ impl Default for BatchBuilder {
                 requires_full_state_hash: false,
                 messages: BatchMessages::default(),
                 randomness: Randomness::from([0; 32]),
-                ecdsa_subnet_public_keys: BTreeMap::new(),
+                chain_key_subnet_public_keys: BTreeMap::new(),
                 ecdsa_quadruple_ids: BTreeMap::new(),
                 registry_version: RegistryVersion::from(1),
                 time: mock_time(),
@@ -300,6 +300,7 @@ pub(crate) async fn install_minter(
         mode: Mode::GeneralAvailability,
         kyt_fee: Some(KYT_FEE),
         kyt_principal: Some(kyt_canister_id),
+        utxo_consolidation_fee_threshold: None,
     };
 
     let minter_arg = MinterArg::Init(args);
@@ -295,6 +295,7 @@ pub fn test(env: TestEnv) {
         use LookupStatus::Found;
         let hash_tree: MixedHashTree = serde_cbor::from_slice(&data_certificate.hash_tree).unwrap();
 
+        // The index of the last block keeps its legacy big-endian encoding.
         assert_eq!(
             hash_tree.lookup(&[b"last_block_index"]),
             Found(&mleaf((1_u64).to_be_bytes()))
@@ -23,6 +23,7 @@ pub enum RejectCode {
     DestinationInvalid = 3,
     CanisterReject = 4,
     CanisterError = 5,
+    SysUnknown = 6,
 }
 
 impl ToString for RejectCode {
@@ -39,6 +40,7 @@ impl RejectCode {
             RejectCode::DestinationInvalid => "DESTINATION_INVALID",
             RejectCode::CanisterReject => "CANISTER_REJECT",
             RejectCode::CanisterError => "CANISTER_ERROR",
+            RejectCode::SysUnknown => "SYS_UNKNOWN",
         }
     }
 }
@@ -52,6 +54,7 @@ impl TryFrom<u64> for RejectCode {
             3 => Ok(RejectCode::DestinationInvalid),
             4 => Ok(RejectCode::CanisterReject),
             5 => Ok(RejectCode::CanisterError),
+            6 => Ok(RejectCode::SysUnknown),
             _ => Err(TryFromError::ValueOutOfRange(code)),
         }
     }
@@ -113,6 +116,7 @@ impl From<ErrorCode> for RejectCode {
             ReservedCyclesLimitExceededInMemoryAllocation => CanisterError,
             ReservedCyclesLimitExceededInMemoryGrow => CanisterError,
             InsufficientCyclesInMessageMemoryGrow => CanisterError,
+            CanisterWasmMemoryLimitExceeded => CanisterError,
         }
     }
 }
@@ -178,6 +182,7 @@ pub enum ErrorCode {
     ReservedCyclesLimitExceededInMemoryAllocation = 533,
     ReservedCyclesLimitExceededInMemoryGrow = 534,
     InsufficientCyclesInMessageMemoryGrow = 535,
+    CanisterWasmMemoryLimitExceeded = 536,
 }
 
 impl TryFrom<u64> for ErrorCode {
@@ -235,6 +240,7 @@ impl TryFrom<u64> for ErrorCode {
             533 => Ok(ErrorCode::ReservedCyclesLimitExceededInMemoryAllocation),
             534 => Ok(ErrorCode::ReservedCyclesLimitExceededInMemoryGrow),
             535 => Ok(ErrorCode::InsufficientCyclesInMessageMemoryGrow),
+            536 => Ok(ErrorCode::CanisterWasmMemoryLimitExceeded),
             _ => Err(TryFromError::ValueOutOfRange(err)),
         }
     }
@@ -352,7 +358,8 @@ impl UserError {
             | ErrorCode::InsufficientCyclesInMemoryGrow
             | ErrorCode::ReservedCyclesLimitExceededInMemoryAllocation
             | ErrorCode::ReservedCyclesLimitExceededInMemoryGrow
-            | ErrorCode::InsufficientCyclesInMessageMemoryGrow => false,
+            | ErrorCode::InsufficientCyclesInMessageMemoryGrow
+            | ErrorCode::CanisterWasmMemoryLimitExceeded => false,
         }
     }
 
@@ -65,6 +65,12 @@ pub enum Method {
     UninstallCode,
     UpdateSettings,
     ComputeInitialEcdsaDealings,
+    SchnorrPublicKey,
+    SignWithSchnorr,
+    #[strum(serialize = "vetkd_public_key")]
+    VetKdPublicKey,
+    #[strum(serialize = "vetkd_derive_encrypted_key")]
+    VetKdDeriveEncryptedKey,
 
     // Bitcoin Interface.
     BitcoinGetBalance,
@@ -95,6 +101,10 @@ pub enum Method {
     LoadCanisterSnapshot,
     ListCanisterSnapshots,
     DeleteCanisterSnapshot,
+    ReadCanisterSnapshotMetadata,
+    ReadCanisterSnapshotData,
+    UploadCanisterSnapshotMetadata,
+    UploadCanisterSnapshotData,
 }
 
 fn candid_error_to_user_error(err: candid::Error) -> UserError {
@@ -668,26 +678,41 @@ impl Payload<'_> for UninstallCodeArgs {}
 /// variant {
 ///    controllers;
 ///    public;
+///    allowed_viewers: vec principal;
 /// }
 /// ```
-#[derive(Default, Clone, Copy, CandidType, Deserialize, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq, Eq, EnumIter)]
 pub enum LogVisibility {
     #[default]
     #[serde(rename = "controllers")]
     Controllers,
     #[serde(rename = "public")]
     Public,
+    #[serde(rename = "allowed_viewers")]
+    AllowedViewers(Vec<PrincipalId>),
 }
 
-impl From<LogVisibility> for i32 {
-    fn from(item: LogVisibility) -> Self {
-        match item {
-            LogVisibility::Controllers => 1,
-            LogVisibility::Public => 2,
+impl LogVisibility {
+    /// Returns true if the given principal may fetch the logs of a canister
+    /// with the given controllers.
+    pub fn allows(&self, principal: &PrincipalId, controllers: &BTreeSet<PrincipalId>) -> bool {
+        match self {
+            LogVisibility::Public => true,
+            LogVisibility::Controllers => controllers.contains(principal),
+            // Controllers can always read the logs of their canister.
+            LogVisibility::AllowedViewers(viewers) => {
+                controllers.contains(principal) || viewers.contains(principal)
+            }
         }
     }
 }
 
+impl From<&LogVisibility> for i32 {
+    fn from(item: &LogVisibility) -> Self {
+        pb_canister_state_bits::LogVisibility::from(item) as i32
+    }
+}
+
 impl TryFrom<i32> for LogVisibility {
     type Error = ProxyDecodeError;
 
@@ -704,11 +729,14 @@ impl TryFrom<i32> for LogVisibility {
     }
 }
 
-impl From<LogVisibility> for pb_canister_state_bits::LogVisibility {
-    fn from(item: LogVisibility) -> Self {
+impl From<&LogVisibility> for pb_canister_state_bits::LogVisibility {
+    fn from(item: &LogVisibility) -> Self {
         match item {
             LogVisibility::Controllers => pb_canister_state_bits::LogVisibility::Controllers,
             LogVisibility::Public => pb_canister_state_bits::LogVisibility::Public,
+            // The legacy encoding cannot represent the allowed viewers, so it
+            // falls back to the more restrictive controllers-only visibility.
+            LogVisibility::AllowedViewers(_) => pb_canister_state_bits::LogVisibility::Controllers,
         }
     }
 }
@@ -723,6 +751,54 @@ impl From<pb_canister_state_bits::LogVisibility> for LogVisibility {
     }
 }
 
+impl From<&LogVisibility> for pb_canister_state_bits::LogVisibilityV2 {
+    fn from(item: &LogVisibility) -> Self {
+        use pb_canister_state_bits::log_visibility_v2::LogVisibilityV2;
+        let log_visibility_v2 = match item {
+            LogVisibility::Controllers => {
+                LogVisibilityV2::Controllers(pb_canister_state_bits::LogVisibilityControllers {})
+            }
+            LogVisibility::Public => {
+                LogVisibilityV2::Public(pb_canister_state_bits::LogVisibilityPublic {})
+            }
+            LogVisibility::AllowedViewers(principals) => LogVisibilityV2::AllowedViewers(
+                pb_canister_state_bits::LogVisibilityAllowedViewers {
+                    principals: principals
+                        .iter()
+                        .map(|principal| (*principal).into())
+                        .collect(),
+                },
+            ),
+        };
+        Self {
+            log_visibility_v2: Some(log_visibility_v2),
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::LogVisibilityV2> for LogVisibility {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_canister_state_bits::LogVisibilityV2) -> Result<Self, Self::Error> {
+        use pb_canister_state_bits::log_visibility_v2::LogVisibilityV2;
+        match item
+            .log_visibility_v2
+            .ok_or(ProxyDecodeError::MissingField(
+                "LogVisibilityV2::log_visibility_v2",
+            ))? {
+            LogVisibilityV2::Controllers(_) => Ok(Self::Controllers),
+            LogVisibilityV2::Public(_) => Ok(Self::Public),
+            LogVisibilityV2::AllowedViewers(allowed_viewers) => Ok(Self::AllowedViewers(
+                allowed_viewers
+                    .principals
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<Vec<PrincipalId>, _>>()?,
+            )),
+        }
+    }
+}
+
 /// Struct used for encoding/decoding
 /// `(record {
 ///     controller : principal;
@@ -731,6 +807,7 @@ impl From<pb_canister_state_bits::LogVisibility> for LogVisibility {
 ///     freezing_threshold: nat;
 ///     reserved_cycles_limit: nat;
 ///     log_visibility: log_visibility;
+///     wasm_memory_limit: nat;
 /// })`
 #[derive(CandidType, Clone, Deserialize, Debug, Eq, PartialEq)]
 pub struct DefiniteCanisterSettingsArgs {
@@ -741,9 +818,11 @@ pub struct DefiniteCanisterSettingsArgs {
     freezing_threshold: candid::Nat,
     reserved_cycles_limit: candid::Nat,
     log_visibility: LogVisibility,
+    wasm_memory_limit: candid::Nat,
 }
 
 impl DefiniteCanisterSettingsArgs {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         controller: PrincipalId,
         controllers: Vec<PrincipalId>,
@@ -752,9 +831,11 @@ impl DefiniteCanisterSettingsArgs {
         freezing_threshold: u64,
         reserved_cycles_limit: Option<u128>,
         log_visibility: LogVisibility,
+        wasm_memory_limit: Option<u64>,
     ) -> Self {
         let memory_allocation = candid::Nat::from(memory_allocation.unwrap_or(0));
         let reserved_cycles_limit = candid::Nat::from(reserved_cycles_limit.unwrap_or(0));
+        let wasm_memory_limit = candid::Nat::from(wasm_memory_limit.unwrap_or(0));
         Self {
             controller,
             controllers,
@@ -763,6 +844,7 @@ impl DefiniteCanisterSettingsArgs {
             freezing_threshold: candid::Nat::from(freezing_threshold),
             reserved_cycles_limit,
             log_visibility,
+            wasm_memory_limit,
         }
     }
 
@@ -775,7 +857,11 @@ impl DefiniteCanisterSettingsArgs {
     }
 
     pub fn log_visibility(&self) -> LogVisibility {
-        self.log_visibility
+        self.log_visibility.clone()
+    }
+
+    pub fn wasm_memory_limit(&self) -> candid::Nat {
+        self.wasm_memory_limit.clone()
     }
 }
 
@@ -845,6 +931,94 @@ pub struct QueryStats {
     response_payload_bytes_total: candid::Nat,
 }
 
+/// Struct used for encoding/decoding
+/// `(record {
+///     wasm_memory_size: nat;
+///     stable_memory_size: nat;
+///     global_memory_size: nat;
+///     wasm_binary_size: nat;
+///     custom_sections_size: nat;
+///     canister_history_size: nat;
+///     wasm_chunk_store_size: nat;
+///     snapshots_size: nat;
+///     queued_messages_size: nat;
+/// })`
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct MemoryMetrics {
+    wasm_memory_size: candid::Nat,
+    stable_memory_size: candid::Nat,
+    global_memory_size: candid::Nat,
+    wasm_binary_size: candid::Nat,
+    custom_sections_size: candid::Nat,
+    canister_history_size: candid::Nat,
+    wasm_chunk_store_size: candid::Nat,
+    snapshots_size: candid::Nat,
+    queued_messages_size: candid::Nat,
+}
+
+impl MemoryMetrics {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        wasm_memory_size: NumBytes,
+        stable_memory_size: NumBytes,
+        global_memory_size: NumBytes,
+        wasm_binary_size: NumBytes,
+        custom_sections_size: NumBytes,
+        canister_history_size: NumBytes,
+        wasm_chunk_store_size: NumBytes,
+        snapshots_size: NumBytes,
+        queued_messages_size: NumBytes,
+    ) -> Self {
+        Self {
+            wasm_memory_size: candid::Nat::from(wasm_memory_size.get()),
+            stable_memory_size: candid::Nat::from(stable_memory_size.get()),
+            global_memory_size: candid::Nat::from(global_memory_size.get()),
+            wasm_binary_size: candid::Nat::from(wasm_binary_size.get()),
+            custom_sections_size: candid::Nat::from(custom_sections_size.get()),
+            canister_history_size: candid::Nat::from(canister_history_size.get()),
+            wasm_chunk_store_size: candid::Nat::from(wasm_chunk_store_size.get()),
+            snapshots_size: candid::Nat::from(snapshots_size.get()),
+            queued_messages_size: candid::Nat::from(queued_messages_size.get()),
+        }
+    }
+
+    pub fn wasm_memory_size(&self) -> NumBytes {
+        NumBytes::from(self.wasm_memory_size.0.to_u64().unwrap())
+    }
+
+    pub fn stable_memory_size(&self) -> NumBytes {
+        NumBytes::from(self.stable_memory_size.0.to_u64().unwrap())
+    }
+
+    pub fn global_memory_size(&self) -> NumBytes {
+        NumBytes::from(self.global_memory_size.0.to_u64().unwrap())
+    }
+
+    pub fn wasm_binary_size(&self) -> NumBytes {
+        NumBytes::from(self.wasm_binary_size.0.to_u64().unwrap())
+    }
+
+    pub fn custom_sections_size(&self) -> NumBytes {
+        NumBytes::from(self.custom_sections_size.0.to_u64().unwrap())
+    }
+
+    pub fn canister_history_size(&self) -> NumBytes {
+        NumBytes::from(self.canister_history_size.0.to_u64().unwrap())
+    }
+
+    pub fn wasm_chunk_store_size(&self) -> NumBytes {
+        NumBytes::from(self.wasm_chunk_store_size.0.to_u64().unwrap())
+    }
+
+    pub fn snapshots_size(&self) -> NumBytes {
+        NumBytes::from(self.snapshots_size.0.to_u64().unwrap())
+    }
+
+    pub fn queued_messages_size(&self) -> NumBytes {
+        NumBytes::from(self.queued_messages_size.0.to_u64().unwrap())
+    }
+}
+
 /// Struct used for encoding/decoding
 /// `(record {
 ///     status : variant { running; stopping; stopped };
@@ -852,6 +1026,7 @@ pub struct QueryStats {
 ///     module_hash: opt blob;
 ///     controller: principal;
 ///     memory_size: nat;
+///     memory_metrics: opt memory_metrics;
 ///     cycles: nat;
 ///     freezing_threshold: nat,
 ///     idle_cycles_burned_per_day: nat;
@@ -870,6 +1045,7 @@ pub struct CanisterStatusResultV2 {
     controller: candid::Principal,
     settings: DefiniteCanisterSettingsArgs,
     memory_size: candid::Nat,
+    memory_metrics: Option<MemoryMetrics>,
     cycles: candid::Nat,
     // this is for compat with Spec 0.12/0.13
     balance: Vec<(Vec<u8>, candid::Nat)>,
@@ -887,12 +1063,14 @@ impl CanisterStatusResultV2 {
         controller: PrincipalId,
         controllers: Vec<PrincipalId>,
         memory_size: NumBytes,
+        memory_metrics: MemoryMetrics,
         cycles: u128,
         compute_allocation: u64,
         memory_allocation: Option<u64>,
         freezing_threshold: u64,
         reserved_cycles_limit: Option<u128>,
         log_visibility: LogVisibility,
+        wasm_memory_limit: Option<u64>,
         idle_cycles_burned_per_day: u128,
         reserved_cycles: u128,
         query_num_calls: u128,
@@ -905,6 +1083,7 @@ impl CanisterStatusResultV2 {
             module_hash,
             controller: candid::Principal::from_text(controller.to_string()).unwrap(),
             memory_size: candid::Nat::from(memory_size.get()),
+            memory_metrics: Some(memory_metrics),
             cycles: candid::Nat::from(cycles),
             // the following is spec 0.12/0.13 compat;
             // "\x00" denotes cycles
@@ -917,6 +1096,7 @@ impl CanisterStatusResultV2 {
                 freezing_threshold,
                 reserved_cycles_limit,
                 log_visibility,
+                wasm_memory_limit,
             ),
             freezing_threshold: candid::Nat::from(freezing_threshold),
             idle_cycles_burned_per_day: candid::Nat::from(idle_cycles_burned_per_day),
@@ -977,6 +1157,10 @@ impl CanisterStatusResultV2 {
     pub fn settings(&self) -> DefiniteCanisterSettingsArgs {
         self.settings.clone()
     }
+
+    pub fn memory_metrics(&self) -> Option<MemoryMetrics> {
+        self.memory_metrics.clone()
+    }
 }
 
 /// Indicates whether the canister is running, stopping, or stopped.
@@ -1524,6 +1708,7 @@ fn verify_max_bounded_controllers_length() {
 ///     freezing_threshold: opt nat;
 ///     reserved_cycles_limit: opt nat;
 ///     log_visibility : opt log_visibility;
+///     wasm_memory_limit: opt nat;
 /// })`
 #[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
 pub struct CanisterSettingsArgs {
@@ -1535,6 +1720,7 @@ pub struct CanisterSettingsArgs {
     pub freezing_threshold: Option<candid::Nat>,
     pub reserved_cycles_limit: Option<candid::Nat>,
     pub log_visibility: Option<LogVisibility>,
+    pub wasm_memory_limit: Option<candid::Nat>,
 }
 
 impl Payload<'_> for CanisterSettingsArgs {}
@@ -1551,6 +1737,7 @@ impl CanisterSettingsArgs {
             freezing_threshold: None,
             reserved_cycles_limit: None,
             log_visibility: None,
+            wasm_memory_limit: None,
         }
     }
 
@@ -1568,6 +1755,7 @@ pub struct CanisterSettingsArgsBuilder {
     freezing_threshold: Option<candid::Nat>,
     reserved_cycles_limit: Option<candid::Nat>,
     log_visibility: Option<LogVisibility>,
+    wasm_memory_limit: Option<candid::Nat>,
 }
 
 #[allow(dead_code)]
@@ -1585,6 +1773,7 @@ impl CanisterSettingsArgsBuilder {
             freezing_threshold: self.freezing_threshold,
             reserved_cycles_limit: self.reserved_cycles_limit,
             log_visibility: self.log_visibility,
+            wasm_memory_limit: self.wasm_memory_limit,
         }
     }
 
@@ -1660,6 +1849,14 @@ impl CanisterSettingsArgsBuilder {
             ..self
         }
     }
+
+    /// Sets the Wasm memory limit in bytes.
+    pub fn with_wasm_memory_limit(self, wasm_memory_limit: u64) -> Self {
+        Self {
+            wasm_memory_limit: Some(candid::Nat::from(wasm_memory_limit)),
+            ..self
+        }
+    }
 }
 
 /// Struct used for encoding/decoding
@@ -1990,6 +2187,403 @@ fn ecdsa_key_id_round_trip() {
     }
 }
 
+/// Types of algorithms that can be used for Schnorr signing.
+/// ```text
+/// (variant { bip340secp256k1; ed25519 })
+/// ```
+#[derive(
+    CandidType,
+    Clone,
+    Copy,
+    Debug,
+    PartialOrd,
+    Ord,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Hash,
+    EnumIter,
+)]
+pub enum SchnorrAlgorithm {
+    #[serde(rename = "bip340secp256k1")]
+    Bip340Secp256k1,
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+impl TryFrom<pb_registry_crypto::SchnorrAlgorithm> for SchnorrAlgorithm {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_registry_crypto::SchnorrAlgorithm) -> Result<Self, Self::Error> {
+        match item {
+            pb_registry_crypto::SchnorrAlgorithm::Bip340secp256k1 => {
+                Ok(SchnorrAlgorithm::Bip340Secp256k1)
+            }
+            pb_registry_crypto::SchnorrAlgorithm::Ed25519 => Ok(SchnorrAlgorithm::Ed25519),
+            pb_registry_crypto::SchnorrAlgorithm::Unspecified => {
+                Err(ProxyDecodeError::ValueOutOfRange {
+                    typ: "SchnorrAlgorithm",
+                    err: format!("Unable to convert {:?} to a SchnorrAlgorithm", item),
+                })
+            }
+        }
+    }
+}
+
+impl From<SchnorrAlgorithm> for pb_registry_crypto::SchnorrAlgorithm {
+    fn from(item: SchnorrAlgorithm) -> Self {
+        match item {
+            SchnorrAlgorithm::Bip340Secp256k1 => {
+                pb_registry_crypto::SchnorrAlgorithm::Bip340secp256k1
+            }
+            SchnorrAlgorithm::Ed25519 => pb_registry_crypto::SchnorrAlgorithm::Ed25519,
+        }
+    }
+}
+
+impl std::fmt::Display for SchnorrAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for SchnorrAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Bip340Secp256k1" => Ok(Self::Bip340Secp256k1),
+            "Ed25519" => Ok(Self::Ed25519),
+            _ => Err(format!("{} is not a recognized Schnorr algorithm", s)),
+        }
+    }
+}
+
+#[test]
+fn schnorr_algorithm_round_trip() {
+    use strum::IntoEnumIterator;
+
+    for algorithm in SchnorrAlgorithm::iter() {
+        assert_eq!(
+            format!("{}", algorithm)
+                .parse::<SchnorrAlgorithm>()
+                .unwrap(),
+            algorithm
+        );
+    }
+}
+
+/// Unique identifier for a key that can be used for threshold Schnorr
+/// signatures. The name is just an identifier, but it may be used to convey
+/// some information about the key (e.g. that the key is meant to be used for
+/// testing purposes).
+/// ```text
+/// (record { algorithm: schnorr_algorithm; name: text})
+/// ```
+#[derive(
+    CandidType, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
+)]
+pub struct SchnorrKeyId {
+    pub algorithm: SchnorrAlgorithm,
+    pub name: String,
+}
+
+impl TryFrom<pb_registry_crypto::SchnorrKeyId> for SchnorrKeyId {
+    type Error = ProxyDecodeError;
+    fn try_from(item: pb_registry_crypto::SchnorrKeyId) -> Result<Self, Self::Error> {
+        Ok(Self {
+            algorithm: SchnorrAlgorithm::try_from(
+                pb_registry_crypto::SchnorrAlgorithm::try_from(item.algorithm).map_err(|_| {
+                    ProxyDecodeError::ValueOutOfRange {
+                        typ: "SchnorrKeyId",
+                        err: format!("Unable to convert {} to a SchnorrAlgorithm", item.algorithm),
+                    }
+                })?,
+            )?,
+            name: item.name,
+        })
+    }
+}
+
+impl From<&SchnorrKeyId> for pb_registry_crypto::SchnorrKeyId {
+    fn from(item: &SchnorrKeyId) -> Self {
+        Self {
+            algorithm: pb_registry_crypto::SchnorrAlgorithm::from(item.algorithm) as i32,
+            name: item.name.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for SchnorrKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.name)
+    }
+}
+
+impl FromStr for SchnorrKeyId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (algorithm, name) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Schnorr key id {} does not contain a ':'", s))?;
+        Ok(SchnorrKeyId {
+            algorithm: algorithm.parse::<SchnorrAlgorithm>()?,
+            name: name.to_string(),
+        })
+    }
+}
+
+#[test]
+fn schnorr_key_id_round_trip() {
+    use strum::IntoEnumIterator;
+
+    for algorithm in SchnorrAlgorithm::iter() {
+        for name in ["Ed25519", "", "other_key", "other key", "other:key"] {
+            let key = SchnorrKeyId {
+                algorithm,
+                name: name.to_string(),
+            };
+            assert_eq!(format!("{}", key).parse::<SchnorrKeyId>().unwrap(), key);
+        }
+    }
+}
+
+/// Types of curves that can be used for vetKD key derivation.
+/// ```text
+/// (variant { bls12_381_g2 })
+/// ```
+#[derive(
+    CandidType,
+    Clone,
+    Copy,
+    Debug,
+    PartialOrd,
+    Ord,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Hash,
+    EnumIter,
+)]
+pub enum VetKdCurve {
+    #[serde(rename = "bls12_381_g2")]
+    #[allow(non_camel_case_types)]
+    Bls12_381_G2,
+}
+
+impl TryFrom<pb_registry_crypto::VetKdCurve> for VetKdCurve {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_registry_crypto::VetKdCurve) -> Result<Self, Self::Error> {
+        match item {
+            pb_registry_crypto::VetKdCurve::Bls12381G2 => Ok(VetKdCurve::Bls12_381_G2),
+            pb_registry_crypto::VetKdCurve::Unspecified => Err(ProxyDecodeError::ValueOutOfRange {
+                typ: "VetKdCurve",
+                err: format!("Unable to convert {:?} to a VetKdCurve", item),
+            }),
+        }
+    }
+}
+
+impl From<VetKdCurve> for pb_registry_crypto::VetKdCurve {
+    fn from(item: VetKdCurve) -> Self {
+        match item {
+            VetKdCurve::Bls12_381_G2 => pb_registry_crypto::VetKdCurve::Bls12381G2,
+        }
+    }
+}
+
+impl std::fmt::Display for VetKdCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for VetKdCurve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Bls12_381_G2" => Ok(Self::Bls12_381_G2),
+            _ => Err(format!("{} is not a recognized vetKD curve", s)),
+        }
+    }
+}
+
+#[test]
+fn vetkd_curve_round_trip() {
+    use strum::IntoEnumIterator;
+
+    for curve in VetKdCurve::iter() {
+        assert_eq!(format!("{}", curve).parse::<VetKdCurve>().unwrap(), curve);
+    }
+}
+
+/// Unique identifier for a key that can be used for vetKD key derivation.
+/// The name is just an identifier, but it may be used to convey some
+/// information about the key (e.g. that the key is meant to be used for
+/// testing purposes).
+/// ```text
+/// (record { curve: vetkd_curve; name: text})
+/// ```
+#[derive(
+    CandidType, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash,
+)]
+pub struct VetKdKeyId {
+    pub curve: VetKdCurve,
+    pub name: String,
+}
+
+impl TryFrom<pb_registry_crypto::VetKdKeyId> for VetKdKeyId {
+    type Error = ProxyDecodeError;
+    fn try_from(item: pb_registry_crypto::VetKdKeyId) -> Result<Self, Self::Error> {
+        Ok(Self {
+            curve: VetKdCurve::try_from(
+                pb_registry_crypto::VetKdCurve::try_from(item.curve).map_err(|_| {
+                    ProxyDecodeError::ValueOutOfRange {
+                        typ: "VetKdKeyId",
+                        err: format!("Unable to convert {} to a VetKdCurve", item.curve),
+                    }
+                })?,
+            )?,
+            name: item.name,
+        })
+    }
+}
+
+impl From<&VetKdKeyId> for pb_registry_crypto::VetKdKeyId {
+    fn from(item: &VetKdKeyId) -> Self {
+        Self {
+            curve: pb_registry_crypto::VetKdCurve::from(item.curve) as i32,
+            name: item.name.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for VetKdKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.curve, self.name)
+    }
+}
+
+impl FromStr for VetKdKeyId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (curve, name) = s
+            .split_once(':')
+            .ok_or_else(|| format!("vetKD key id {} does not contain a ':'", s))?;
+        Ok(VetKdKeyId {
+            curve: curve.parse::<VetKdCurve>()?,
+            name: name.to_string(),
+        })
+    }
+}
+
+#[test]
+fn vetkd_key_id_round_trip() {
+    use strum::IntoEnumIterator;
+
+    for curve in VetKdCurve::iter() {
+        for name in ["bls12_381_g2", "", "other_key", "other key", "other:key"] {
+            let key = VetKdKeyId {
+                curve,
+                name: name.to_string(),
+            };
+            assert_eq!(format!("{}", key).parse::<VetKdKeyId>().unwrap(), key);
+        }
+    }
+}
+
+/// Unique identifier for a master public key held by a subnet by means of
+/// threshold cryptography, i.e. a threshold ECDSA, a threshold Schnorr, or
+/// a vetKD key.
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum MasterPublicKeyId {
+    Ecdsa(EcdsaKeyId),
+    Schnorr(SchnorrKeyId),
+    VetKd(VetKdKeyId),
+}
+
+impl From<EcdsaKeyId> for MasterPublicKeyId {
+    fn from(key_id: EcdsaKeyId) -> Self {
+        MasterPublicKeyId::Ecdsa(key_id)
+    }
+}
+
+impl From<SchnorrKeyId> for MasterPublicKeyId {
+    fn from(key_id: SchnorrKeyId) -> Self {
+        MasterPublicKeyId::Schnorr(key_id)
+    }
+}
+
+impl From<VetKdKeyId> for MasterPublicKeyId {
+    fn from(key_id: VetKdKeyId) -> Self {
+        MasterPublicKeyId::VetKd(key_id)
+    }
+}
+
+impl std::fmt::Display for MasterPublicKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ecdsa(key_id) => write!(f, "ecdsa:{}", key_id),
+            Self::Schnorr(key_id) => write!(f, "schnorr:{}", key_id),
+            Self::VetKd(key_id) => write!(f, "vetkd:{}", key_id),
+        }
+    }
+}
+
+impl FromStr for MasterPublicKeyId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scheme, key_id) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Master public key id {} does not contain a ':'", s))?;
+        match scheme {
+            "ecdsa" => Ok(Self::Ecdsa(EcdsaKeyId::from_str(key_id)?)),
+            "schnorr" => Ok(Self::Schnorr(SchnorrKeyId::from_str(key_id)?)),
+            "vetkd" => Ok(Self::VetKd(VetKdKeyId::from_str(key_id)?)),
+            _ => Err(format!(
+                "Scheme {} in master public key id {} is not supported",
+                scheme, s
+            )),
+        }
+    }
+}
+
+#[test]
+fn master_public_key_id_round_trip() {
+    let key_ids: Vec<MasterPublicKeyId> = vec![
+        EcdsaKeyId {
+            curve: EcdsaCurve::Secp256k1,
+            name: "other:key".to_string(),
+        }
+        .into(),
+        SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Bip340Secp256k1,
+            name: "other:key".to_string(),
+        }
+        .into(),
+        SchnorrKeyId {
+            algorithm: SchnorrAlgorithm::Ed25519,
+            name: "".to_string(),
+        }
+        .into(),
+        VetKdKeyId {
+            curve: VetKdCurve::Bls12_381_G2,
+            name: "other:key".to_string(),
+        }
+        .into(),
+    ];
+    for key in key_ids {
+        assert_eq!(
+            format!("{}", key).parse::<MasterPublicKeyId>().unwrap(),
+            key
+        );
+    }
+}
+
 pub type DerivationPath = BoundedVec<MAXIMUM_DERIVATION_PATH_LENGTH, UNBOUNDED, UNBOUNDED, ByteBuf>;
 
 impl Payload<'_> for DerivationPath {}
@@ -2156,6 +2750,133 @@ pub struct ECDSAPublicKeyResponse {
 
 impl Payload<'_> for ECDSAPublicKeyResponse {}
 
+/// Represents the argument of the sign_with_schnorr API.
+/// ```text
+/// (record {
+///   message : blob;
+///   derivation_path : vec blob;
+///   key_id : schnorr_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct SignWithSchnorrArgs {
+    #[serde(with = "serde_bytes")]
+    pub message: Vec<u8>,
+    pub derivation_path: DerivationPath,
+    pub key_id: SchnorrKeyId,
+}
+
+impl Payload<'_> for SignWithSchnorrArgs {}
+
+/// Struct used to return a threshold Schnorr signature.
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SignWithSchnorrReply {
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl Payload<'_> for SignWithSchnorrReply {}
+
+/// Represents the argument of the schnorr_public_key API.
+/// ```text
+/// (record {
+///   canister_id : opt canister_id;
+///   derivation_path : vec blob;
+///   key_id : schnorr_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct SchnorrPublicKeyArgs {
+    pub canister_id: Option<CanisterId>,
+    pub derivation_path: DerivationPath,
+    pub key_id: SchnorrKeyId,
+}
+
+impl Payload<'_> for SchnorrPublicKeyArgs {}
+
+/// Represents the response of the schnorr_public_key API.
+/// ```text
+/// (record {
+///   public_key : blob;
+///   chain_code : blob;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct SchnorrPublicKeyResponse {
+    #[serde(with = "serde_bytes")]
+    pub public_key: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub chain_code: Vec<u8>,
+}
+
+impl Payload<'_> for SchnorrPublicKeyResponse {}
+
+/// Represents the argument of the vetkd_public_key API.
+/// ```text
+/// (record {
+///   canister_id : opt canister_id;
+///   derivation_path : vec blob;
+///   key_id : vetkd_key_id;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct VetKdPublicKeyArgs {
+    pub canister_id: Option<CanisterId>,
+    pub derivation_path: DerivationPath,
+    pub key_id: VetKdKeyId,
+}
+
+impl Payload<'_> for VetKdPublicKeyArgs {}
+
+/// Represents the response of the vetkd_public_key API.
+/// ```text
+/// (record {
+///   public_key : blob;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct VetKdPublicKeyResult {
+    #[serde(with = "serde_bytes")]
+    pub public_key: Vec<u8>,
+}
+
+impl Payload<'_> for VetKdPublicKeyResult {}
+
+/// Represents the argument of the vetkd_derive_encrypted_key API.
+/// ```text
+/// (record {
+///   derivation_id : blob;
+///   derivation_path : vec blob;
+///   key_id : vetkd_key_id;
+///   encryption_public_key : blob;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct VetKdDeriveEncryptedKeyArgs {
+    #[serde(with = "serde_bytes")]
+    pub derivation_id: Vec<u8>,
+    pub derivation_path: DerivationPath,
+    pub key_id: VetKdKeyId,
+    #[serde(with = "serde_bytes")]
+    pub encryption_public_key: Vec<u8>,
+}
+
+impl Payload<'_> for VetKdDeriveEncryptedKeyArgs {}
+
+/// Represents the response of the vetkd_derive_encrypted_key API.
+/// ```text
+/// (record {
+///   encrypted_key : blob;
+/// })
+/// ```
+#[derive(CandidType, Deserialize, Debug)]
+pub struct VetKdDeriveEncryptedKeyResult {
+    #[serde(with = "serde_bytes")]
+    pub encrypted_key: Vec<u8>,
+}
+
+impl Payload<'_> for VetKdDeriveEncryptedKeyResult {}
+
 /// Argument of the compute_initial_ecdsa_dealings API.
 /// `(record {
 ///     key_id: ecdsa_key_id;
@@ -2320,20 +3041,86 @@ pub struct NodeMetricsHistoryResponse {
 
 impl Payload<'_> for NodeMetricsHistoryResponse {}
 
+/// `CandidType` for `FetchCanisterLogsRange`
+/// ```text
+/// record {
+///     start: nat64;
+///     end: nat64;
+/// }
+/// ```
+///
+/// The range includes `start` and excludes `end`.
+#[derive(Default, Clone, Copy, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct FetchCanisterLogsRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl FetchCanisterLogsRange {
+    pub fn new(start: u64, end: u64) -> Self {
+        Self { start, end }
+    }
+
+    fn contains(&self, value: u64) -> bool {
+        self.start <= value && value < self.end
+    }
+}
+
+/// `CandidType` for `FetchCanisterLogsFilter`
+/// ```text
+/// variant {
+///     by_idx: FetchCanisterLogsRange;
+///     by_timestamp_nanos: FetchCanisterLogsRange;
+/// }
+/// ```
+#[derive(Clone, Copy, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum FetchCanisterLogsFilter {
+    #[serde(rename = "by_idx")]
+    ByIdx(FetchCanisterLogsRange),
+    #[serde(rename = "by_timestamp_nanos")]
+    ByTimestampNanos(FetchCanisterLogsRange),
+}
+
+impl FetchCanisterLogsFilter {
+    /// Returns true if the given log record falls into the filter's range.
+    pub fn matches(&self, record: &CanisterLogRecord) -> bool {
+        match self {
+            FetchCanisterLogsFilter::ByIdx(range) => range.contains(record.idx),
+            FetchCanisterLogsFilter::ByTimestampNanos(range) => {
+                range.contains(record.timestamp_nanos)
+            }
+        }
+    }
+}
+
 /// `CandidType` for `FetchCanisterLogsRequest`
 /// ```text
 /// record {
 ///     canister_id: principal;
+///     filter: opt FetchCanisterLogsFilter;
 /// }
 /// ```
 #[derive(Default, Clone, CandidType, Deserialize, Debug)]
 pub struct FetchCanisterLogsRequest {
     pub canister_id: PrincipalId,
+    pub filter: Option<FetchCanisterLogsFilter>,
 }
 
 impl Payload<'_> for FetchCanisterLogsRequest {}
 
 impl FetchCanisterLogsRequest {
+    pub fn new(canister_id: CanisterId) -> Self {
+        Self {
+            canister_id: canister_id.into(),
+            filter: None,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: FetchCanisterLogsFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     pub fn get_canister_id(&self) -> CanisterId {
         CanisterId::unchecked_from_principal(self.canister_id)
     }
@@ -2547,3 +3334,197 @@ impl StoredChunksArgs {
 pub struct StoredChunksReply(pub Vec<ChunkHash>);
 
 impl Payload<'_> for StoredChunksReply {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct ReadCanisterSnapshotMetadataArgs {
+    pub canister_id: PrincipalId,
+    #[serde(with = "serde_bytes")]
+    pub snapshot_id: Vec<u8>,
+}
+
+impl Payload<'_> for ReadCanisterSnapshotMetadataArgs {}
+
+impl ReadCanisterSnapshotMetadataArgs {
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+}
+
+/// Struct to be returned when reading the metadata of a canister snapshot.
+/// `(record {
+///     taken_at_timestamp: nat64;
+///     canister_version: nat64;
+///     certified_data: blob;
+///     wasm_module_size: nat64;
+///     wasm_memory_size: nat64;
+///     stable_memory_size: nat64;
+///     wasm_chunk_store: vec record { hash: blob };
+/// })`
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct ReadCanisterSnapshotMetadataResponse {
+    pub taken_at_timestamp: u64,
+    pub canister_version: u64,
+    #[serde(with = "serde_bytes")]
+    pub certified_data: Vec<u8>,
+    pub wasm_module_size: u64,
+    pub wasm_memory_size: u64,
+    pub stable_memory_size: u64,
+    pub wasm_chunk_store: Vec<ChunkHash>,
+}
+
+impl Payload<'_> for ReadCanisterSnapshotMetadataResponse {}
+
+/// The part of a canister snapshot to read.
+/// `(variant {
+///     wasm_module: record { offset: nat64; size: nat64 };
+///     wasm_memory: record { offset: nat64; size: nat64 };
+///     stable_memory: record { offset: nat64; size: nat64 };
+///     wasm_chunk: record { hash: blob };
+/// })`
+#[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum CanisterSnapshotDataKind {
+    #[serde(rename = "wasm_module")]
+    WasmModule { offset: u64, size: u64 },
+    #[serde(rename = "wasm_memory")]
+    WasmMemory { offset: u64, size: u64 },
+    #[serde(rename = "stable_memory")]
+    StableMemory { offset: u64, size: u64 },
+    #[serde(rename = "wasm_chunk")]
+    WasmChunk {
+        #[serde(with = "serde_bytes")]
+        hash: Vec<u8>,
+    },
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+///     kind: variant {
+///         wasm_module: record { offset: nat64; size: nat64 };
+///         wasm_memory: record { offset: nat64; size: nat64 };
+///         stable_memory: record { offset: nat64; size: nat64 };
+///         wasm_chunk: record { hash: blob };
+///     };
+/// })`
+#[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct ReadCanisterSnapshotDataArgs {
+    pub canister_id: PrincipalId,
+    #[serde(with = "serde_bytes")]
+    pub snapshot_id: Vec<u8>,
+    pub kind: CanisterSnapshotDataKind,
+}
+
+impl Payload<'_> for ReadCanisterSnapshotDataArgs {}
+
+impl ReadCanisterSnapshotDataArgs {
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+}
+
+/// Struct to be returned when reading data of a canister snapshot.
+/// `(record {
+///     chunk: blob;
+/// })`
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct ReadCanisterSnapshotDataResponse {
+    #[serde(with = "serde_bytes")]
+    pub chunk: Vec<u8>,
+}
+
+impl Payload<'_> for ReadCanisterSnapshotDataResponse {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     replace_snapshot: opt blob;
+///     wasm_module_size: nat64;
+///     wasm_memory_size: nat64;
+///     stable_memory_size: nat64;
+///     certified_data: blob;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct UploadCanisterSnapshotMetadataArgs {
+    pub canister_id: PrincipalId,
+    pub replace_snapshot: Option<serde_bytes::ByteBuf>,
+    pub wasm_module_size: u64,
+    pub wasm_memory_size: u64,
+    pub stable_memory_size: u64,
+    #[serde(with = "serde_bytes")]
+    pub certified_data: Vec<u8>,
+}
+
+impl Payload<'_> for UploadCanisterSnapshotMetadataArgs {}
+
+impl UploadCanisterSnapshotMetadataArgs {
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+}
+
+/// Struct to be returned when uploading the metadata of a canister snapshot.
+/// `(record {
+///     snapshot_id: blob;
+/// })`
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct UploadCanisterSnapshotMetadataResponse {
+    #[serde(with = "serde_bytes")]
+    pub snapshot_id: Vec<u8>,
+}
+
+impl Payload<'_> for UploadCanisterSnapshotMetadataResponse {}
+
+/// The part of a canister snapshot to write an uploaded chunk to.
+/// `(variant {
+///     wasm_module: record { offset: nat64 };
+///     wasm_memory: record { offset: nat64 };
+///     stable_memory: record { offset: nat64 };
+///     wasm_chunk;
+/// })`
+#[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum CanisterSnapshotDataOffset {
+    #[serde(rename = "wasm_module")]
+    WasmModule { offset: u64 },
+    #[serde(rename = "wasm_memory")]
+    WasmMemory { offset: u64 },
+    #[serde(rename = "stable_memory")]
+    StableMemory { offset: u64 },
+    #[serde(rename = "wasm_chunk")]
+    WasmChunk,
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+///     kind: variant {
+///         wasm_module: record { offset: nat64 };
+///         wasm_memory: record { offset: nat64 };
+///         stable_memory: record { offset: nat64 };
+///         wasm_chunk;
+///     };
+///     chunk: blob;
+/// })`
+#[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct UploadCanisterSnapshotDataArgs {
+    pub canister_id: PrincipalId,
+    #[serde(with = "serde_bytes")]
+    pub snapshot_id: Vec<u8>,
+    pub kind: CanisterSnapshotDataOffset,
+    #[serde(with = "serde_bytes")]
+    pub chunk: Vec<u8>,
+}
+
+impl Payload<'_> for UploadCanisterSnapshotDataArgs {}
+
+impl UploadCanisterSnapshotDataArgs {
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+}
@@ -630,6 +630,7 @@ mod tests {
     use crate::{time::UNIX_EPOCH, Cycles};
 
     use super::*;
+    use crate::messages::NO_DEADLINE;
 
     #[test]
     fn test_request_arg_variable_size() {
@@ -654,6 +655,7 @@ mod tests {
                 method_name: "tansform".to_string(),
                 method_payload: Vec::new(),
                 metadata: None,
+                deadline: NO_DEADLINE,
             },
             time: UNIX_EPOCH,
         };
@@ -695,6 +697,7 @@ mod tests {
                 method_name: "tansform".to_string(),
                 method_payload: Vec::new(),
                 metadata: None,
+                deadline: NO_DEADLINE,
             },
             time: UNIX_EPOCH,
         };
@@ -19,13 +19,13 @@ pub mod idkg;
 #[cfg(test)]
 mod tests;
 
-/// A threshold ECDSA public key.
+/// A public key derived from a threshold (ECDSA or Schnorr) master public key.
 ///
 /// The public key itself is stored as raw bytes.
 ///
 /// The chain key is included for BIP32-style key derivation
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct EcdsaPublicKey {
+pub struct PublicKey {
     pub algorithm_id: AlgorithmId,
     #[serde(with = "serde_bytes")]
     pub public_key: Vec<u8>,
@@ -34,15 +34,23 @@ pub struct EcdsaPublicKey {
 }
 
 /// A threshold ECDSA public key.
+pub type EcdsaPublicKey = PublicKey;
+
+/// A master public key of a subnet held by means of canister threshold
+/// signatures (i.e. threshold ECDSA or threshold Schnorr).
 ///
-/// The public key itself is stored as raw bytes.
+/// The public key itself is stored as raw bytes; `algorithm_id` determines
+/// how the bytes are interpreted.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct MasterEcdsaPublicKey {
+pub struct MasterPublicKey {
     pub algorithm_id: AlgorithmId,
     #[serde(with = "serde_bytes")]
     pub public_key: Vec<u8>,
 }
 
+/// A threshold ECDSA master public key.
+pub type MasterEcdsaPublicKey = MasterPublicKey;
+
 /// A combined threshold ECDSA signature.
 ///
 /// The signature itself is stored as raw bytes.
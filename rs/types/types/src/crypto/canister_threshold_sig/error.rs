@@ -86,6 +86,14 @@ pub enum ThresholdEcdsaGetPublicKeyError {
 }
 impl_display_using_debug!(ThresholdEcdsaGetPublicKeyError);
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdSchnorrGetPublicKeyError {
+    InvalidArgument(String),
+    UnsupportedAlgorithm(AlgorithmId),
+    InternalError(String),
+}
+impl_display_using_debug!(ThresholdSchnorrGetPublicKeyError);
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IDkgCreateTranscriptError {
     SerializationError {
@@ -14,10 +14,12 @@ pub use self::http::{
     HttpReadStateContent, HttpReadStateResponse, HttpReply, HttpRequest, HttpRequestContent,
     HttpRequestEnvelope, HttpRequestError, HttpSignedQueryResponse, HttpStatusResponse,
     HttpUserQuery, NodeSignature, QueryResponseHash, RawHttpRequestVal, ReplicaHealthStatus,
-    SignedDelegation,
+    SignedDelegation, IC_API_VERSION,
 };
 pub use crate::methods::SystemMethod;
-use crate::{user_id_into_protobuf, user_id_try_from_protobuf, Cycles, Funds, NumBytes, UserId};
+use crate::{
+    user_id_into_protobuf, user_id_try_from_protobuf, CoarseTime, Cycles, Funds, NumBytes, UserId,
+};
 pub use blob::Blob;
 use ic_base_types::{CanisterId, PrincipalId};
 use ic_management_canister_types::CanisterChangeOrigin;
@@ -30,7 +32,7 @@ pub use ingress_messages::{
 };
 pub use inter_canister::{
     CallContextId, CallbackId, Payload, RejectContext, Request, RequestMetadata, RequestOrResponse,
-    Response, MAX_REJECT_MESSAGE_LEN_BYTES,
+    Response, MAX_CALL_TIMEOUT_SECONDS, MAX_REJECT_MESSAGE_LEN_BYTES, NO_DEADLINE,
 };
 pub use message_id::{MessageId, MessageIdError, EXPECTED_MESSAGE_ID_LENGTH};
 use phantom_newtype::Id;
@@ -112,6 +114,9 @@ pub enum StopCanisterContext {
         /// here so that they can be returned to the caller in the eventual
         /// reply.
         cycles: Cycles,
+        /// The deadline of the request to stop the canister, to be carried by
+        /// the eventual reply.
+        deadline: CoarseTime,
     },
 }
 
@@ -148,6 +153,7 @@ impl From<(CanisterCall, StopCanisterCallId)> for StopCanisterContext {
                 reply_callback: req.sender_reply_callback,
                 call_id: Some(call_id),
                 cycles: Arc::make_mut(&mut req).payment.take(),
+                deadline: req.deadline,
             },
             CanisterCall::Ingress(ingress) => StopCanisterContext::Ingress {
                 sender: ingress.source,
@@ -179,6 +185,7 @@ impl From<&StopCanisterContext> for pb::StopCanisterContext {
                 reply_callback,
                 call_id,
                 cycles,
+                deadline,
             } => Self {
                 context: Some(pb::stop_canister_context::Context::Canister(
                     pb::stop_canister_context::Canister {
@@ -187,6 +194,7 @@ impl From<&StopCanisterContext> for pb::StopCanisterContext {
                         call_id: call_id.map(|id| id.get()),
                         funds: Some((&Funds::new(*cycles)).into()),
                         cycles: Some((*cycles).into()),
+                        deadline_seconds: deadline.as_secs_since_unix_epoch(),
                     },
                 )),
             },
@@ -220,6 +228,7 @@ impl TryFrom<pb::StopCanisterContext> for StopCanisterContext {
                         call_id,
                         funds,
                         cycles,
+                        deadline_seconds,
                     },
                 ) => {
                     // To maintain backwards compatibility we fall back to reading from `funds` if
@@ -246,6 +255,7 @@ impl TryFrom<pb::StopCanisterContext> for StopCanisterContext {
                         reply_callback: CallbackId::from(reply_callback),
                         call_id: call_id.map(StopCanisterCallId::from),
                         cycles,
+                        deadline: CoarseTime::from_secs_since_unix_epoch(deadline_seconds),
                     }
                 }
             };
@@ -390,6 +400,15 @@ impl CanisterCall {
         }
     }
 
+    /// Returns the deadline of this message; `NO_DEADLINE` for ingress
+    /// messages and guaranteed response calls.
+    pub fn deadline(&self) -> CoarseTime {
+        match self {
+            CanisterCall::Request(request) => request.deadline,
+            CanisterCall::Ingress(_) => NO_DEADLINE,
+        }
+    }
+
     pub fn canister_change_origin(&self, canister_version: Option<u64>) -> CanisterChangeOrigin {
         match self {
             CanisterCall::Ingress(msg) => CanisterChangeOrigin::from_user(msg.source.get()),
@@ -704,6 +723,7 @@ mod tests {
                 method_name: "method".into(),
                 method_payload: vec![0_u8, 1_u8, 2_u8, 3_u8, 4_u8, 5_u8],
                 metadata,
+                deadline: NO_DEADLINE,
             };
             let bytes = bincode::serialize(&request).unwrap();
             let request1 = bincode::deserialize::<Request>(&bytes);
@@ -719,6 +739,7 @@ mod tests {
             originator_reply_callback: CallbackId::from(100),
             refund: Cycles::from(100_000_000_u128),
             response_payload: Payload::Data(vec![0_u8, 1_u8, 2_u8, 3_u8, 4_u8, 5_u8]),
+            deadline: NO_DEADLINE,
         };
         let bytes = bincode::serialize(&response).unwrap();
         let response1 = bincode::deserialize::<Response>(&bytes);
@@ -12,8 +12,9 @@ use crate::{
 use ic_error_types::{ErrorCode, UserError};
 use ic_management_canister_types::{
     CanisterIdRecord, CanisterInfoRequest, ClearChunkStoreArgs, FetchCanisterLogsRequest,
-    InstallChunkedCodeArgs, InstallCodeArgsV2, Method, Payload, StoredChunksArgs,
-    UpdateSettingsArgs, UploadChunkArgs, IC_00,
+    InstallChunkedCodeArgs, InstallCodeArgsV2, Method, Payload, ReadCanisterSnapshotDataArgs,
+    ReadCanisterSnapshotMetadataArgs, StoredChunksArgs, UpdateSettingsArgs,
+    UploadCanisterSnapshotDataArgs, UploadCanisterSnapshotMetadataArgs, UploadChunkArgs, IC_00,
 };
 use ic_protobuf::{
     log::ingress_message_log_entry::v1::IngressMessageLogEntry,
@@ -515,6 +516,30 @@ pub fn extract_effective_canister_id(
             Ok(record) => Ok(Some(record.get_canister_id())),
             Err(err) => Err(ParseIngressError::InvalidSubnetPayload(err.to_string())),
         },
+        Ok(Method::ReadCanisterSnapshotMetadata) => {
+            match ReadCanisterSnapshotMetadataArgs::decode(ingress.arg()) {
+                Ok(record) => Ok(Some(record.get_canister_id())),
+                Err(err) => Err(ParseIngressError::InvalidSubnetPayload(err.to_string())),
+            }
+        }
+        Ok(Method::ReadCanisterSnapshotData) => {
+            match ReadCanisterSnapshotDataArgs::decode(ingress.arg()) {
+                Ok(record) => Ok(Some(record.get_canister_id())),
+                Err(err) => Err(ParseIngressError::InvalidSubnetPayload(err.to_string())),
+            }
+        }
+        Ok(Method::UploadCanisterSnapshotMetadata) => {
+            match UploadCanisterSnapshotMetadataArgs::decode(ingress.arg()) {
+                Ok(record) => Ok(Some(record.get_canister_id())),
+                Err(err) => Err(ParseIngressError::InvalidSubnetPayload(err.to_string())),
+            }
+        }
+        Ok(Method::UploadCanisterSnapshotData) => {
+            match UploadCanisterSnapshotDataArgs::decode(ingress.arg()) {
+                Ok(record) => Ok(Some(record.get_canister_id())),
+                Err(err) => Err(ParseIngressError::InvalidSubnetPayload(err.to_string())),
+            }
+        }
         Ok(Method::DeleteChunks)
         | Ok(Method::TakeCanisterSnapshot)
         | Ok(Method::LoadCanisterSnapshot)
@@ -529,6 +554,10 @@ pub fn extract_effective_canister_id(
         | Ok(Method::ECDSAPublicKey)
         | Ok(Method::SignWithECDSA)
         | Ok(Method::ComputeInitialEcdsaDealings)
+        | Ok(Method::SchnorrPublicKey)
+        | Ok(Method::SignWithSchnorr)
+        | Ok(Method::VetKdPublicKey)
+        | Ok(Method::VetKdDeriveEncryptedKey)
         | Ok(Method::BitcoinGetBalance)
         | Ok(Method::BitcoinGetUtxos)
         | Ok(Method::BitcoinSendTransaction)
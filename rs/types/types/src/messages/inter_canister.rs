@@ -1,11 +1,15 @@
-use crate::{ingress::WasmResult, CanisterId, CountBytes, Cycles, Funds, NumBytes, Time};
+use crate::{
+    ingress::WasmResult, time::CoarseTime, CanisterId, CountBytes, Cycles, Funds, NumBytes, Time,
+};
 use ic_error_types::{RejectCode, TryFromError, UserError};
 #[cfg(test)]
 use ic_exhaustive_derive::ExhaustiveSet;
 use ic_management_canister_types::{
     CanisterIdRecord, CanisterInfoRequest, ClearChunkStoreArgs, InstallChunkedCodeArgs,
-    InstallCodeArgsV2, Method, Payload as _, ProvisionalTopUpCanisterArgs, StoredChunksArgs,
-    UpdateSettingsArgs, UploadChunkArgs,
+    InstallCodeArgsV2, Method, Payload as _, ProvisionalTopUpCanisterArgs,
+    ReadCanisterSnapshotDataArgs, ReadCanisterSnapshotMetadataArgs, StoredChunksArgs,
+    UpdateSettingsArgs, UploadCanisterSnapshotDataArgs, UploadCanisterSnapshotMetadataArgs,
+    UploadChunkArgs,
 };
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
@@ -37,6 +41,14 @@ pub enum CallContextIdTag {}
 /// Identifies an incoming call.
 pub type CallContextId = Id<CallContextIdTag, u64>;
 
+/// The deadline of guaranteed response calls, i.e. of messages without a
+/// deadline.
+pub const NO_DEADLINE: CoarseTime = CoarseTime::from_secs_since_unix_epoch(0);
+
+/// The maximum timeout of a best-effort call, in seconds. Longer timeouts
+/// requested by canisters are silently reduced to this value.
+pub const MAX_CALL_TIMEOUT_SECONDS: u32 = 300;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct RequestMetadata {
     /// Indicates how many steps down the call tree a request is, starting at 0.
@@ -85,6 +97,9 @@ pub struct Request {
     #[serde(with = "serde_bytes")]
     pub method_payload: Vec<u8>,
     pub metadata: Option<RequestMetadata>,
+    /// The deadline of a best-effort call; `NO_DEADLINE` for guaranteed
+    /// response calls.
+    pub deadline: CoarseTime,
 }
 
 impl Request {
@@ -103,6 +118,11 @@ impl Request {
         &self.method_payload
     }
 
+    /// Returns `true` if this is the request of a best-effort call.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline != NO_DEADLINE
+    }
+
     /// Returns the size of the user-controlled part of this `Request`,
     /// in bytes.
     pub fn payload_size_bytes(&self) -> NumBytes {
@@ -162,6 +182,30 @@ impl Request {
                 Ok(record) => Some(record.get_canister_id()),
                 Err(_) => None,
             },
+            Ok(Method::ReadCanisterSnapshotMetadata) => {
+                match ReadCanisterSnapshotMetadataArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::ReadCanisterSnapshotData) => {
+                match ReadCanisterSnapshotDataArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::UploadCanisterSnapshotMetadata) => {
+                match UploadCanisterSnapshotMetadataArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::UploadCanisterSnapshotData) => {
+                match UploadCanisterSnapshotDataArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
             Ok(Method::DeleteChunks)
             | Ok(Method::TakeCanisterSnapshot)
             | Ok(Method::LoadCanisterSnapshot)
@@ -174,6 +218,10 @@ impl Request {
             | Ok(Method::ECDSAPublicKey)
             | Ok(Method::SignWithECDSA)
             | Ok(Method::ComputeInitialEcdsaDealings)
+            | Ok(Method::SchnorrPublicKey)
+            | Ok(Method::SignWithSchnorr)
+            | Ok(Method::VetKdPublicKey)
+            | Ok(Method::VetKdDeriveEncryptedKey)
             | Ok(Method::BitcoinGetBalance)
             | Ok(Method::BitcoinGetUtxos)
             | Ok(Method::BitcoinSendTransaction)
@@ -213,7 +261,8 @@ impl std::fmt::Debug for Request {
             "method_payload: [{}], ",
             truncate_and_format(&self.method_payload, 1024)
         )?;
-        write!(f, "metadata: {:?} }}", self.metadata)?;
+        write!(f, "metadata: {:?}, ", self.metadata)?;
+        write!(f, "deadline: {:?} }}", self.deadline)?;
         Ok(())
     }
 }
@@ -363,6 +412,9 @@ pub struct Response {
     pub originator_reply_callback: CallbackId,
     pub refund: Cycles,
     pub response_payload: Payload,
+    /// The deadline of the best-effort call this is a response to;
+    /// `NO_DEADLINE` for guaranteed responses.
+    pub deadline: CoarseTime,
 }
 
 impl Response {
@@ -370,6 +422,11 @@ impl Response {
     pub fn payload_size_bytes(&self) -> NumBytes {
         self.response_payload.size_bytes()
     }
+
+    /// Returns `true` if this is a best-effort response.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline != NO_DEADLINE
+    }
 }
 
 /// Canister-to-canister message.
@@ -487,6 +544,7 @@ impl From<&Request> for pb_queues::Request {
             method_payload: req.method_payload.clone(),
             cycles_payment: Some((req.payment).into()),
             metadata: req.metadata.as_ref().map(From::from),
+            deadline_seconds: req.deadline.as_secs_since_unix_epoch(),
         }
     }
 }
@@ -522,6 +580,7 @@ impl TryFrom<pb_queues::Request> for Request {
             method_name: req.method_name,
             method_payload: req.method_payload,
             metadata: req.metadata.map(From::from),
+            deadline: CoarseTime::from_secs_since_unix_epoch(req.deadline_seconds),
         })
     }
 }
@@ -564,6 +623,7 @@ impl From<&Response> for pb_queues::Response {
             refund: Some((&Funds::new(rep.refund)).into()),
             response_payload: Some(p),
             cycles_refund: Some((rep.refund).into()),
+            deadline_seconds: rep.deadline.as_secs_since_unix_epoch(),
         }
     }
 }
@@ -594,6 +654,7 @@ impl TryFrom<pb_queues::Response> for Response {
             originator_reply_callback: rep.originator_reply_callback.into(),
             refund,
             response_payload,
+            deadline: CoarseTime::from_secs_since_unix_epoch(rep.deadline_seconds),
         })
     }
 }
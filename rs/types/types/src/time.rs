@@ -269,6 +269,48 @@ impl fmt::Display for Time {
     }
 }
 
+/// Time since UNIX_EPOCH, in seconds. Used where second granularity is
+/// sufficient and a compact representation is desirable, e.g. for the
+/// deadlines of best-effort inter-canister calls.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Arbitrary, ExhaustiveSet))]
+pub struct CoarseTime(u32);
+
+impl CoarseTime {
+    pub const fn from_secs_since_unix_epoch(secs: u32) -> Self {
+        CoarseTime(secs)
+    }
+
+    /// Number of seconds since UNIX EPOCH
+    pub const fn as_secs_since_unix_epoch(self) -> u32 {
+        self.0
+    }
+
+    /// Rounds the given `Time` down to the previous second, saturating at the
+    /// maximum `CoarseTime`.
+    pub fn floor(time: Time) -> Self {
+        CoarseTime(u32::try_from(time.as_secs_since_unix_epoch()).unwrap_or(u32::MAX))
+    }
+
+    /// Rounds the given `Time` up to the next second, saturating at the
+    /// maximum `CoarseTime`.
+    pub fn ceil(time: Time) -> Self {
+        let secs = time.as_nanos_since_unix_epoch().div_ceil(NANOS_PER_SEC);
+        CoarseTime(u32::try_from(secs).unwrap_or(u32::MAX))
+    }
+
+    /// Saturating `CoarseTime` addition of a number of seconds.
+    pub fn saturating_add_secs(self, secs: u32) -> Self {
+        CoarseTime(self.0.saturating_add(secs))
+    }
+}
+
+impl From<CoarseTime> for Time {
+    fn from(coarse_time: CoarseTime) -> Self {
+        Time(coarse_time.0 as u64 * NANOS_PER_SEC)
+    }
+}
+
 /// Returns the current time.
 ///
 /// WARNING: this function should not be used in any deterministic part of the
@@ -1,4 +1,4 @@
-use crate::time::{TimeInstantiationError, NANOS_PER_MILLI, NANOS_PER_SEC};
+use crate::time::{CoarseTime, TimeInstantiationError, NANOS_PER_MILLI, NANOS_PER_SEC};
 use crate::Time;
 use assert_matches::assert_matches;
 use std::time::SystemTime;
@@ -122,3 +122,26 @@ fn should_convert_from_system_time_and_back() {
     let back: SystemTime = time.into();
     assert_eq!(system_time, back);
 }
+
+#[test]
+fn should_round_time_to_coarse_time() {
+    let time = Time::from_nanos_since_unix_epoch(42 * NANOS_PER_SEC + 1);
+    assert_eq!(CoarseTime::floor(time).as_secs_since_unix_epoch(), 42);
+    assert_eq!(CoarseTime::ceil(time).as_secs_since_unix_epoch(), 43);
+
+    let time = Time::from_nanos_since_unix_epoch(42 * NANOS_PER_SEC);
+    assert_eq!(CoarseTime::floor(time), CoarseTime::ceil(time));
+    assert_eq!(Time::from(CoarseTime::floor(time)), time);
+}
+
+#[test]
+fn should_saturate_coarse_time() {
+    let time = Time::from_nanos_since_unix_epoch(u64::MAX);
+    assert_eq!(CoarseTime::floor(time).as_secs_since_unix_epoch(), u32::MAX);
+    assert_eq!(
+        CoarseTime::from_secs_since_unix_epoch(u32::MAX - 1)
+            .saturating_add_secs(2)
+            .as_secs_since_unix_epoch(),
+        u32::MAX
+    );
+}
@@ -1,7 +1,11 @@
 use crate::ids::{canister_test_id, node_test_id, subnet_test_id, user_test_id};
 use ic_canonical_state::encoding::{
-    old_types::{RequestV13 as CanonicalRequestV13, RequestV3 as CanonicalRequestV3},
-    types::Request as CanonicalRequestV14,
+    old_types::{
+        RequestV13 as CanonicalRequestV13, RequestV16 as CanonicalRequestV16,
+        RequestV3 as CanonicalRequestV3, ResponseV16 as CanonicalResponseV16,
+        ResponseV3 as CanonicalResponseV3,
+    },
+    types::{Request as CanonicalRequestV17, Response as CanonicalResponseV17},
 };
 use ic_certification_version::{CertificationVersion, CURRENT_CERTIFICATION_VERSION};
 use ic_types::{
@@ -11,7 +15,7 @@ use ic_types::{
     },
     time::UNIX_EPOCH,
     xnet::StreamIndex,
-    CanisterId, Cycles, Height, NodeId, RegistryVersion, SubnetId, Time, UserId,
+    CanisterId, CoarseTime, Cycles, Height, NodeId, RegistryVersion, SubnetId, Time, UserId,
 };
 use proptest::prelude::*;
 use std::{convert::TryInto, time::Duration};
@@ -126,6 +130,7 @@ prop_compose! {
         callback in any::<u64>(),
         method_payload in prop::collection::vec(any::<u8>(), 0..16),
         metadata in proptest::option::of(request_metadata()),
+        deadline in any::<u32>(),
     ) -> Request {
         Request {
             receiver,
@@ -135,6 +140,7 @@ prop_compose! {
             method_name,
             method_payload,
             metadata,
+            deadline: CoarseTime::from_secs_since_unix_epoch(deadline),
         }
     }
 }
@@ -159,7 +165,11 @@ prop_compose! {
                 req.try_into().unwrap()
             }
             V14 | V15 | V16 => {
-                let req: CanonicalRequestV14 = (&request, certification_version).into();
+                let req: CanonicalRequestV16 = (&request, certification_version).into();
+                req.try_into().unwrap()
+            }
+            V17 => {
+                let req: CanonicalRequestV17 = (&request, certification_version).into();
                 req.try_into().unwrap()
             }
         }
@@ -193,23 +203,64 @@ pub fn response_payload() -> impl Strategy<Value = Payload> {
 
 prop_compose! {
     /// Returns an arbitrary [`Response`].
-    pub fn response()(
+    ///
+    /// All fields should be populated here, including those not yet supported by the current
+    /// certification version; this way `response()` below will automatically start producing
+    /// responses including such fields once the current certification is bumped.
+    fn response_impl()(
         originator in canister_id(),
         respondent in canister_id(),
         callback in any::<u64>(),
         cycles_refund in any::<u64>(),
         response_payload in response_payload(),
+        deadline in any::<u32>(),
     ) -> Response {
         Response {
             originator,
             respondent,
             originator_reply_callback: CallbackId::from(callback),
             refund: Cycles::from(cycles_refund),
-            response_payload
+            response_payload,
+            deadline: CoarseTime::from_secs_since_unix_epoch(deadline),
         }
     }
 }
 
+prop_compose! {
+    /// Returns an arbitrary [`Response`] valid for a given certification version.
+    ///
+    /// A roundtrip to the canonical version and back ensures compatibility for a given
+    /// certification version; e.g. by stripping off the `deadline` for version 16 and below.
+    pub fn valid_response_for_certification_version(certification_version: CertificationVersion)(
+        response in response_impl(),
+    ) -> Response {
+        use CertificationVersion::*;
+        match certification_version {
+            V0 | V1 | V2 | V3 => {
+                let rep: CanonicalResponseV3 = (&response, certification_version).into();
+                rep.try_into().unwrap()
+            }
+            V4 | V5 | V6 | V7 | V8 | V9 | V10 | V11 | V12 | V13 | V14 | V15 | V16 => {
+                let rep: CanonicalResponseV16 = (&response, certification_version).into();
+                rep.try_into().unwrap()
+            }
+            V17 => {
+                let rep: CanonicalResponseV17 = (&response, certification_version).into();
+                rep.try_into().unwrap()
+            }
+        }
+    }
+}
+
+prop_compose! {
+    /// Returns an arbitrary [`Response`] valid for the current certification version.
+    pub fn response()(
+        response in valid_response_for_certification_version(CURRENT_CERTIFICATION_VERSION),
+    ) -> Response {
+        response
+    }
+}
+
 /// Produces an arbitrary [`RequestOrResponse`].
 pub fn request_or_response() -> impl Strategy<Value = RequestOrResponse> {
     prop_oneof![
@@ -225,7 +276,8 @@ pub fn valid_request_or_response_for_certification_version(
     prop_oneof![
         valid_request_for_certification_version(certification_version)
             .prop_flat_map(|req| Just(req.into())),
-        response().prop_flat_map(|rep| Just(rep.into())),
+        valid_response_for_certification_version(certification_version)
+            .prop_flat_map(|rep| Just(rep.into())),
     ]
 }
 